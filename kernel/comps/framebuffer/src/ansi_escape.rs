@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! A parser of VT100/ANSI escape sequences.
+//!
+//! The parser turns a byte stream into [`Action`]s. It recognizes the C0 control characters, the
+//! escape sequences (`ESC ...`), the control sequences (`CSI ...`), and the UTF-8 encoded
+//! characters. The operating system commands (`OSC ...`) are recognized but ignored.
+//!
+//! Like the Linux console, the C0 control characters are executed even in the middle of an escape
+//! sequence, `ESC` restarts a new escape sequence, and `CAN` or `SUB` aborts the escape sequence.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man4/console_codes.4.html>.
+
+/// The maximum number of parameters in a control sequence.
+///
+/// The extra parameters are merged into the last one.
+const MAX_PARAMS: usize = 16;
+
+/// An action that is parsed by [`EscapeParser`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Action {
+    /// Prints a character.
+    Print(char),
+    /// Executes a C0 control character (e.g., `\n` or `\b`).
+    Control(u8),
+    /// Executes an escape sequence, i.e., `ESC [intermediate] final`.
+    Escape {
+        intermediate: Option<u8>,
+        final_byte: u8,
+    },
+    /// Executes a control sequence, i.e., `CSI [?] params final`.
+    Csi(CsiSequence),
+}
+
+/// A control sequence.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CsiSequence {
+    params: [u16; MAX_PARAMS],
+    num_params: usize,
+    is_private: bool,
+    final_byte: u8,
+}
+
+impl CsiSequence {
+    const fn new() -> Self {
+        Self {
+            params: [0; MAX_PARAMS],
+            num_params: 0,
+            is_private: false,
+            final_byte: 0,
+        }
+    }
+
+    /// Returns the parameters.
+    pub(crate) fn params(&self) -> &[u16] {
+        &self.params[..self.num_params]
+    }
+
+    /// Returns the `index`-th parameter, or `default` if it is missing or zero.
+    pub(crate) fn param_or(&self, index: usize, default: u16) -> u16 {
+        match self.params().get(index) {
+            Some(&param) if param != 0 => param,
+            _ => default,
+        }
+    }
+
+    /// Returns whether the parameters start with `?` (i.e., a DEC private mode).
+    pub(crate) fn is_private(&self) -> bool {
+        self.is_private
+    }
+
+    /// Returns the final byte, which determines the function of the control sequence.
+    pub(crate) fn final_byte(&self) -> u8 {
+        self.final_byte
+    }
+
+    fn push_digit(&mut self, digit: u8) {
+        if self.num_params == 0 {
+            self.num_params = 1;
+        }
+        let param = &mut self.params[self.num_params - 1];
+        *param = param
+            .saturating_mul(10)
+            .saturating_add((digit - b'0') as u16);
+    }
+
+    fn next_param(&mut self) {
+        if self.num_params == 0 {
+            self.num_params = 1;
+        }
+        if self.num_params < MAX_PARAMS {
+            self.num_params += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParserState {
+    Ground,
+    Escape,
+    EscapeIntermediate(u8),
+    Csi,
+    Osc,
+}
+
+/// A parser of VT100/ANSI escape sequences.
+#[derive(Debug)]
+pub(crate) struct EscapeParser {
+    state: ParserState,
+    csi: CsiSequence,
+    /// The code point of the UTF-8 character being decoded.
+    utf8_char: u32,
+    /// The number of the remaining continuation bytes of the UTF-8 character being decoded.
+    utf8_remaining: u8,
+}
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1a;
+const DEL: u8 = 0x7f;
+
+impl EscapeParser {
+    /// Creates a new parser in the ground state.
+    pub(crate) const fn new() -> Self {
+        Self {
+            state: ParserState::Ground,
+            csi: CsiSequence::new(),
+            utf8_char: 0,
+            utf8_remaining: 0,
+        }
+    }
+
+    /// Feeds a byte into the parser.
+    ///
+    /// This method returns the action if the byte completes one.
+    pub(crate) fn feed(&mut self, byte: u8) -> Option<Action> {
+        if self.state == ParserState::Osc {
+            match byte {
+                BEL | CAN | SUB => self.state = ParserState::Ground,
+                ESC => self.state = ParserState::Escape,
+                _ => (),
+            }
+            return None;
+        }
+
+        match byte {
+            ESC => {
+                self.utf8_remaining = 0;
+                self.state = ParserState::Escape;
+                return None;
+            }
+            CAN | SUB => {
+                self.utf8_remaining = 0;
+                self.state = ParserState::Ground;
+                return None;
+            }
+            0x00..0x20 => return Some(Action::Control(byte)),
+            DEL => return None,
+            _ => (),
+        }
+
+        match self.state {
+            ParserState::Ground => self.feed_utf8(byte),
+            ParserState::Escape => match byte {
+                b'[' => {
+                    self.csi = CsiSequence::new();
+                    self.state = ParserState::Csi;
+                    None
+                }
+                b']' => {
+                    self.state = ParserState::Osc;
+                    None
+                }
+                0x20..0x30 => {
+                    self.state = ParserState::EscapeIntermediate(byte);
+                    None
+                }
+                _ => {
+                    self.state = ParserState::Ground;
+                    Some(Action::Escape {
+                        intermediate: None,
+                        final_byte: byte,
+                    })
+                }
+            },
+            ParserState::EscapeIntermediate(intermediate) => {
+                if (0x20..0x30).contains(&byte) {
+                    self.state = ParserState::EscapeIntermediate(byte);
+                    return None;
+                }
+                self.state = ParserState::Ground;
+                Some(Action::Escape {
+                    intermediate: Some(intermediate),
+                    final_byte: byte,
+                })
+            }
+            ParserState::Csi => match byte {
+                b'0'..=b'9' => {
+                    self.csi.push_digit(byte);
+                    None
+                }
+                b';' => {
+                    self.csi.next_param();
+                    None
+                }
+                b'?' | b'<' | b'=' | b'>' => {
+                    self.csi.is_private = true;
+                    None
+                }
+                // Intermediate bytes are not used by the supported sequences.
+                0x20..0x40 => None,
+                _ => {
+                    self.state = ParserState::Ground;
+                    self.csi.final_byte = byte;
+                    Some(Action::Csi(self.csi))
+                }
+            },
+            ParserState::Osc => unreachable!(),
+        }
+    }
+
+    fn feed_utf8(&mut self, byte: u8) -> Option<Action> {
+        if self.utf8_remaining > 0 {
+            if byte & 0xc0 == 0x80 {
+                self.utf8_char = (self.utf8_char << 6) | (byte & 0x3f) as u32;
+                self.utf8_remaining -= 1;
+                if self.utf8_remaining > 0 {
+                    return None;
+                }
+                let ch = char::from_u32(self.utf8_char).unwrap_or(char::REPLACEMENT_CHARACTER);
+                return Some(Action::Print(ch));
+            }
+
+            // The character is truncated. Start over with the new byte.
+            self.utf8_remaining = 0;
+        }
+
+        let (utf8_char, utf8_remaining) = match byte {
+            0x00..0x80 => return Some(Action::Print(byte as char)),
+            0xc0..0xe0 => (byte & 0x1f, 1),
+            0xe0..0xf0 => (byte & 0x0f, 2),
+            0xf0..0xf8 => (byte & 0x07, 3),
+            _ => return Some(Action::Print(char::REPLACEMENT_CHARACTER)),
+        };
+        self.utf8_char = utf8_char as u32;
+        self.utf8_remaining = utf8_remaining;
+        None
+    }
+}
@@ -1,16 +1,19 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use alloc::{sync::Arc, vec::Vec};
+use core::ops::Range;
 
-use aster_console::{AnyConsoleDevice, ConsoleCallback};
-use font8x8::UnicodeFonts;
+use font8x8::{UnicodeFonts, BASIC_FONTS, BLOCK_FONTS, BOX_FONTS, LATIN_FONTS};
 use ostd::{
     sync::{LocalIrqDisabled, SpinLock},
     Error, Result,
 };
 use spin::Once;
 
-use crate::{FrameBuffer, Pixel, FRAMEBUFFER};
+use crate::{
+    screen::{Cell, TextScreen},
+    FrameBuffer, FRAMEBUFFER,
+};
 
 /// The font width in pixels when using `font8x8`.
 const FONT_WIDTH: usize = 8;
@@ -18,14 +21,27 @@ const FONT_WIDTH: usize = 8;
 /// The font height in pixels when using `font8x8`.
 const FONT_HEIGHT: usize = 8;
 
-/// A text console rendered onto the framebuffer.
+/// The number of virtual consoles.
+pub const NR_CONSOLES: usize = 6;
+
+/// The display mode of a virtual console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleMode {
+    /// The text screen is rendered onto the framebuffer.
+    Text,
+    /// The framebuffer is left to graphical applications.
+    Graphics,
+}
+
+/// Text consoles rendered onto the framebuffer.
+///
+/// There are [`NR_CONSOLES`] virtual consoles multiplexed over the framebuffer. Each virtual
+/// console has its own text screen, but only the active one is rendered.
 #[derive(Debug)]
 pub struct FramebufferConsole {
     state: SpinLock<ConsoleState, LocalIrqDisabled>,
 }
 
-pub const CONSOLE_NAME: &str = "Framebuffer-Console";
-
 pub static FRAMEBUFFER_CONSOLE: Once<Arc<FramebufferConsole>> = Once::new();
 
 pub(crate) fn init() {
@@ -37,27 +53,24 @@ pub(crate) fn init() {
     FRAMEBUFFER_CONSOLE.call_once(|| Arc::new(FramebufferConsole::new(fb.clone())));
 }
 
-impl AnyConsoleDevice for FramebufferConsole {
-    fn send(&self, buf: &[u8]) {
-        self.state.lock().send_buf(buf);
-    }
-
-    fn register_callback(&self, _: &'static ConsoleCallback) {
-        // Unsupported, do nothing.
-    }
-}
-
 impl FramebufferConsole {
     /// Creates a new framebuffer console.
     pub fn new(framebuffer: Arc<FrameBuffer>) -> Self {
+        let cols = framebuffer.width() / FONT_WIDTH;
+        let rows = framebuffer.height() / FONT_HEIGHT;
+        let consoles = (0..NR_CONSOLES)
+            .map(|_| VirtualConsole {
+                screen: TextScreen::new(cols, rows),
+                mode: ConsoleMode::Text,
+            })
+            .collect();
+
         let bytes = alloc::vec![0u8; framebuffer.size()];
         Self {
             state: SpinLock::new(ConsoleState {
                 enabled: true,
-                x_pos: 0,
-                y_pos: 0,
-                fg_color: Pixel::WHITE,
-                bg_color: Pixel::BLACK,
+                active: 0,
+                consoles,
                 bytes,
                 backend: framebuffer,
             }),
@@ -70,51 +83,89 @@ impl FramebufferConsole {
     }
 
     /// Enables the console.
+    ///
+    /// The active virtual console will be redrawn.
     pub fn enable(&self) {
-        self.state.lock().enabled = true;
+        let mut state = self.state.lock();
+        if !state.enabled {
+            state.enabled = true;
+            state.redraw();
+        }
     }
 
     /// Disables the console.
+    ///
+    /// The virtual consoles still receive characters while the console is disabled, but nothing
+    /// is rendered onto the framebuffer.
     pub fn disable(&self) {
         self.state.lock().enabled = false;
     }
 
-    /// Returns the current cursor position.
-    pub fn cursor(&self) -> (usize, usize) {
+    /// Returns the size of the text screens as `(columns, rows)`.
+    pub fn size(&self) -> (usize, usize) {
         let state = self.state.lock();
-        (state.x_pos, state.y_pos)
+        let screen = &state.consoles[0].screen;
+        (screen.cols(), screen.rows())
     }
 
-    /// Sets the cursor position.
-    pub fn set_cursor(&self, x: usize, y: usize) -> Result<()> {
-        let mut state = self.state.lock();
-        if x > state.backend.width() - FONT_WIDTH || y > state.backend.height() - FONT_HEIGHT {
-            log::warn!("Invalid framebuffer cursor position: ({}, {})", x, y);
+    /// Returns the index of the active virtual console.
+    pub fn active_console(&self) -> usize {
+        self.state.lock().active
+    }
+
+    /// Switches to the virtual console at `index`.
+    pub fn activate(&self, index: usize) -> Result<()> {
+        if index >= NR_CONSOLES {
             return Err(Error::InvalidArgs);
         }
-        state.x_pos = x;
-        state.y_pos = y;
+
+        let mut state = self.state.lock();
+        if state.active != index {
+            state.active = index;
+            state.redraw();
+        }
         Ok(())
     }
 
-    /// Returns the foreground color.
-    pub fn fg_color(&self) -> Pixel {
-        self.state.lock().fg_color
+    /// Returns the display mode of the virtual console at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `index` is not less than [`NR_CONSOLES`].
+    pub fn mode(&self, index: usize) -> ConsoleMode {
+        self.state.lock().consoles[index].mode
     }
 
-    /// Sets the foreground color.
-    pub fn set_fg_color(&self, val: Pixel) {
-        self.state.lock().fg_color = val;
-    }
+    /// Sets the display mode of the virtual console at `index`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `index` is not less than [`NR_CONSOLES`].
+    pub fn set_mode(&self, index: usize, mode: ConsoleMode) {
+        let mut state = self.state.lock();
+        if state.consoles[index].mode == mode {
+            return;
+        }
 
-    /// Returns the background color.
-    pub fn bg_color(&self) -> Pixel {
-        self.state.lock().bg_color
+        state.consoles[index].mode = mode;
+        if mode == ConsoleMode::Text && index == state.active {
+            state.redraw();
+        }
     }
 
-    /// Sets the background color.
-    pub fn set_bg_color(&self, val: Pixel) {
-        self.state.lock().bg_color = val;
+    /// Sends a buffer of bytes to the virtual console at `index`.
+    ///
+    /// The bytes are interpreted as UTF-8 characters and VT100/ANSI escape sequences.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `index` is not less than [`NR_CONSOLES`].
+    pub fn send(&self, index: usize, buf: &[u8]) {
+        let mut state = self.state.lock();
+        state.consoles[index].screen.write(buf);
+        if state.is_visible(index) {
+            state.render_dirty_rows();
+        }
     }
 }
 
@@ -122,91 +173,121 @@ impl FramebufferConsole {
 struct ConsoleState {
     // FIXME: maybe we should drop the whole `ConsoleState` when it's disabled.
     enabled: bool,
-    x_pos: usize,
-    y_pos: usize,
-    fg_color: Pixel,
-    bg_color: Pixel,
+    active: usize,
+    consoles: Vec<VirtualConsole>,
+    /// The cached pixels of the framebuffer.
     bytes: Vec<u8>,
     backend: Arc<FrameBuffer>,
 }
 
+#[derive(Debug)]
+struct VirtualConsole {
+    screen: TextScreen,
+    mode: ConsoleMode,
+}
+
 impl ConsoleState {
-    fn carriage_return(&mut self) {
-        self.x_pos = 0;
+    fn is_visible(&self, index: usize) -> bool {
+        self.enabled && index == self.active && self.consoles[index].mode == ConsoleMode::Text
     }
 
-    fn newline(&mut self) {
-        if self.y_pos >= self.backend.height() - FONT_HEIGHT {
-            self.shift_lines_up();
+    /// Redraws the whole text screen of the active virtual console.
+    fn redraw(&mut self) {
+        let active = self.active;
+        if !self.is_visible(active) {
+            return;
         }
-        self.y_pos += FONT_HEIGHT;
-        self.x_pos = 0;
-    }
 
-    fn shift_lines_up(&mut self) {
-        let offset = self.backend.calc_offset(0, FONT_HEIGHT).as_usize();
-        self.bytes.copy_within(offset.., 0);
-        self.bytes[self.backend.size() - offset..].fill(0);
-        self.backend.write_bytes_at(0, &self.bytes).unwrap();
-        self.y_pos -= FONT_HEIGHT;
+        let screen = &mut self.consoles[active].screen;
+        screen.take_dirty_rows();
+        let rows = 0..screen.rows();
+        self.render_rows(rows);
     }
 
-    /// Sends a single character to be drawn on the framebuffer.
-    fn send_char(&mut self, c: char) {
-        if c == '\n' {
-            self.newline();
-            return;
-        } else if c == '\r' {
-            self.carriage_return();
-            return;
+    /// Renders the changed rows of the active virtual console.
+    fn render_dirty_rows(&mut self) {
+        let active = self.active;
+        if let Some(rows) = self.consoles[active].screen.take_dirty_rows() {
+            self.render_rows(rows);
         }
+    }
 
-        if self.x_pos + FONT_WIDTH > self.backend.width() {
-            self.newline();
-        }
+    /// Renders the rows of the active virtual console onto the framebuffer.
+    fn render_rows(&mut self, rows: Range<usize>) {
+        let screen = &self.consoles[self.active].screen;
+        let cursor = screen.cursor();
 
-        let rendered = font8x8::BASIC_FONTS
-            .get(c)
-            .expect("character not found in basic font");
-        let fg_pixel = self.backend.render_pixel(self.fg_color);
-        let bg_pixel = self.backend.render_pixel(self.bg_color);
-        let mut offset = self.backend.calc_offset(self.x_pos, self.y_pos);
-        for byte in rendered.iter() {
-            for bit in 0..8 {
-                let on = *byte & (1 << bit) != 0;
-                let pixel = if on { fg_pixel } else { bg_pixel };
-
-                // Cache the rendered pixel
-                self.bytes[offset.as_usize()..offset.as_usize() + pixel.nbytes()]
-                    .copy_from_slice(pixel.as_slice());
-                // Write the pixel to the framebuffer
-                self.backend.write_pixel_at(offset, pixel).unwrap();
-
-                offset.x_add(1);
+        for y in rows.clone() {
+            for x in 0..screen.cols() {
+                let cell = screen.cell(x, y);
+                let is_cursor = cursor == Some((x, y));
+                render_cell(&self.backend, &mut self.bytes, x, y, cell, is_cursor);
             }
-            offset.x_add(-(FONT_WIDTH as isize));
-            offset.y_add(1);
         }
-        self.x_pos += FONT_WIDTH;
+
+        let start = self
+            .backend
+            .calc_offset(0, rows.start * FONT_HEIGHT)
+            .as_usize();
+        let end = self
+            .backend
+            .calc_offset(0, rows.end * FONT_HEIGHT)
+            .as_usize();
+        self.backend
+            .write_bytes_at(start, &self.bytes[start..end])
+            .unwrap();
     }
+}
 
-    /// Sends a buffer of bytes to be drawn on the framebuffer.
-    ///
-    /// # Panics
-    ///
-    /// This method will panic if the buffer contains any characters
-    /// other than Basic Latin characters (`U+0000` - `U+007F`).
-    fn send_buf(&mut self, buf: &[u8]) {
-        if !self.enabled {
-            return;
-        }
+/// Renders a character cell into the cached pixels.
+///
+/// The cell at the cursor is rendered with the foreground and background colors swapped.
+fn render_cell(
+    backend: &FrameBuffer,
+    bytes: &mut [u8],
+    x: usize,
+    y: usize,
+    cell: &Cell,
+    is_cursor: bool,
+) {
+    let (fg, bg) = if is_cursor {
+        (cell.bg, cell.fg)
+    } else {
+        (cell.fg, cell.bg)
+    };
+    let fg_pixel = backend.render_pixel(fg);
+    let bg_pixel = backend.render_pixel(bg);
 
-        // TODO: handle ANSI escape sequences.
-        for &byte in buf.iter() {
-            if byte != 0 {
-                let char = char::from_u32(byte as u32).unwrap();
-                self.send_char(char);
-            }
+    let mut glyph = find_glyph(cell.ch);
+    if cell.is_underlined {
+        glyph[FONT_HEIGHT - 1] = 0xff;
+    }
+
+    let mut offset = backend.calc_offset(x * FONT_WIDTH, y * FONT_HEIGHT);
+    for byte in glyph.iter() {
+        for bit in 0..8 {
+            let on = *byte & (1 << bit) != 0;
+            let pixel = if on { fg_pixel } else { bg_pixel };
+
+            bytes[offset.as_usize()..offset.as_usize() + pixel.nbytes()]
+                .copy_from_slice(pixel.as_slice());
+
+            offset.x_add(1);
         }
+        offset.x_add(-(FONT_WIDTH as isize));
+        offset.y_add(1);
     }
 }
+
+/// Finds the glyph of the character in the fonts.
+///
+/// Characters that are not in the fonts are shown as `?`.
+fn find_glyph(ch: char) -> [u8; FONT_HEIGHT] {
+    BASIC_FONTS
+        .get(ch)
+        .or_else(|| LATIN_FONTS.get(ch))
+        .or_else(|| BOX_FONTS.get(ch))
+        .or_else(|| BLOCK_FONTS.get(ch))
+        .or_else(|| BASIC_FONTS.get('?'))
+        .unwrap()
+}
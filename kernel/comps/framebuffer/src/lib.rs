@@ -6,12 +6,14 @@
 
 extern crate alloc;
 
+mod ansi_escape;
 mod console;
 mod framebuffer;
 mod pixel;
+mod screen;
 
 use component::{init_component, ComponentInitError};
-pub use console::{FramebufferConsole, CONSOLE_NAME, FRAMEBUFFER_CONSOLE};
+pub use console::{ConsoleMode, FramebufferConsole, FRAMEBUFFER_CONSOLE, NR_CONSOLES};
 pub use framebuffer::{FrameBuffer, FRAMEBUFFER};
 pub use pixel::{Pixel, PixelFormat, RenderedPixel};
 
@@ -0,0 +1,613 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{
+    ansi_escape::{Action, CsiSequence, EscapeParser},
+    Pixel,
+};
+
+/// A character cell on a text screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cell {
+    pub(crate) ch: char,
+    pub(crate) fg: Pixel,
+    pub(crate) bg: Pixel,
+    pub(crate) is_underlined: bool,
+}
+
+/// A text screen that interprets VT100/ANSI escape sequences.
+///
+/// The screen only maintains the character cells. It is up to the caller to render the changed
+/// rows (see [`Self::take_dirty_rows`]) onto the framebuffer.
+#[derive(Debug)]
+pub(crate) struct TextScreen {
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+    x: usize,
+    y: usize,
+    /// Whether the cursor is beyond the last column, so the next character goes to a new line.
+    need_wrap: bool,
+    attrs: Attributes,
+    /// The scrolling region, i.e., the rows from `scroll_top` to `scroll_bottom` (exclusive).
+    scroll_top: usize,
+    scroll_bottom: usize,
+    /// The `G0` and `G1` character sets, and the index of the active one.
+    charsets: [Charset; 2],
+    charset_index: usize,
+    saved_cursor: SavedCursor,
+    is_cursor_visible: bool,
+    is_autowrap: bool,
+    is_insert_mode: bool,
+    parser: EscapeParser,
+    dirty_rows: Option<Range<usize>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SavedCursor {
+    x: usize,
+    y: usize,
+    attrs: Attributes,
+    charsets: [Charset; 2],
+    charset_index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Charset {
+    Ascii,
+    /// The DEC special graphics character set, which contains line-drawing characters.
+    DecGraphics,
+}
+
+/// A color specified by the SGR sequences.
+#[derive(Debug, Clone, Copy)]
+enum Color {
+    /// A color in the 256-color palette.
+    Indexed(u8),
+    Rgb(Pixel),
+}
+
+/// The graphic rendition attributes.
+#[derive(Debug, Clone, Copy)]
+struct Attributes {
+    fg: Color,
+    bg: Color,
+    is_bold: bool,
+    is_underlined: bool,
+    is_reversed: bool,
+}
+
+impl Attributes {
+    const DEFAULT: Self = Self {
+        fg: Color::Indexed(7),
+        bg: Color::Indexed(0),
+        is_bold: false,
+        is_underlined: false,
+        is_reversed: false,
+    };
+
+    /// Creates a cell that contains the character with the attributes.
+    fn cell(&self, ch: char) -> Cell {
+        let fg = match self.fg {
+            // Like the Linux console, bold characters are shown in bright colors.
+            Color::Indexed(index @ 0..8) if self.is_bold => Color::Indexed(index + 8),
+            fg => fg,
+        };
+        let (fg, bg) = if self.is_reversed {
+            (self.bg, fg)
+        } else {
+            (fg, self.bg)
+        };
+
+        Cell {
+            ch,
+            fg: fg.to_pixel(),
+            bg: bg.to_pixel(),
+            is_underlined: self.is_underlined,
+        }
+    }
+
+    /// Creates a blank cell used to erase characters.
+    fn blank_cell(&self) -> Cell {
+        Cell {
+            is_underlined: false,
+            ..self.cell(' ')
+        }
+    }
+}
+
+/// The 16 basic colors, which are the same as the default colors of the Linux console.
+const PALETTE: [Pixel; 16] = [
+    rgb(0x00, 0x00, 0x00),
+    rgb(0xaa, 0x00, 0x00),
+    rgb(0x00, 0xaa, 0x00),
+    rgb(0xaa, 0x55, 0x00),
+    rgb(0x00, 0x00, 0xaa),
+    rgb(0xaa, 0x00, 0xaa),
+    rgb(0x00, 0xaa, 0xaa),
+    rgb(0xaa, 0xaa, 0xaa),
+    rgb(0x55, 0x55, 0x55),
+    rgb(0xff, 0x55, 0x55),
+    rgb(0x55, 0xff, 0x55),
+    rgb(0xff, 0xff, 0x55),
+    rgb(0x55, 0x55, 0xff),
+    rgb(0xff, 0x55, 0xff),
+    rgb(0x55, 0xff, 0xff),
+    rgb(0xff, 0xff, 0xff),
+];
+
+const fn rgb(red: u8, green: u8, blue: u8) -> Pixel {
+    Pixel { red, green, blue }
+}
+
+impl Color {
+    fn to_pixel(self) -> Pixel {
+        let index = match self {
+            Color::Rgb(pixel) => return pixel,
+            Color::Indexed(index) => index as usize,
+        };
+
+        match index {
+            0..16 => PALETTE[index],
+            // A 6x6x6 color cube.
+            16..232 => {
+                const LEVELS: [u8; 6] = [0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff];
+                let index = index - 16;
+                rgb(LEVELS[index / 36], LEVELS[index / 6 % 6], LEVELS[index % 6])
+            }
+            // A grayscale ramp.
+            _ => {
+                let level = (8 + (index - 232) * 10) as u8;
+                rgb(level, level, level)
+            }
+        }
+    }
+}
+
+/// The width of a tab stop.
+const TAB_WIDTH: usize = 8;
+
+impl TextScreen {
+    /// Creates a blank screen with the given number of columns and rows.
+    pub(crate) fn new(cols: usize, rows: usize) -> Self {
+        let attrs = Attributes::DEFAULT;
+        Self {
+            cols,
+            rows,
+            cells: alloc::vec![attrs.blank_cell(); cols * rows],
+            x: 0,
+            y: 0,
+            need_wrap: false,
+            attrs,
+            scroll_top: 0,
+            scroll_bottom: rows,
+            charsets: [Charset::Ascii; 2],
+            charset_index: 0,
+            saved_cursor: SavedCursor {
+                x: 0,
+                y: 0,
+                attrs,
+                charsets: [Charset::Ascii; 2],
+                charset_index: 0,
+            },
+            is_cursor_visible: true,
+            is_autowrap: true,
+            is_insert_mode: false,
+            parser: EscapeParser::new(),
+            dirty_rows: Some(0..rows),
+        }
+    }
+
+    /// Returns the number of columns.
+    pub(crate) fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the number of rows.
+    pub(crate) fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the cell at the given position.
+    pub(crate) fn cell(&self, x: usize, y: usize) -> &Cell {
+        &self.cells[y * self.cols + x]
+    }
+
+    /// Returns the cursor position if the cursor is visible.
+    pub(crate) fn cursor(&self) -> Option<(usize, usize)> {
+        self.is_cursor_visible.then_some((self.x, self.y))
+    }
+
+    /// Takes the rows that have changed since the last call.
+    pub(crate) fn take_dirty_rows(&mut self) -> Option<Range<usize>> {
+        self.dirty_rows.take()
+    }
+
+    /// Writes bytes, which may contain escape sequences, to the screen.
+    pub(crate) fn write(&mut self, buf: &[u8]) {
+        // The cursor may move away from its current row.
+        self.mark_dirty(self.y..self.y + 1);
+
+        for &byte in buf {
+            match self.parser.feed(byte) {
+                None => (),
+                Some(Action::Print(ch)) => self.print(ch),
+                Some(Action::Control(byte)) => self.control(byte),
+                Some(Action::Escape {
+                    intermediate,
+                    final_byte,
+                }) => self.escape(intermediate, final_byte),
+                Some(Action::Csi(csi)) => self.csi(&csi),
+            }
+        }
+
+        self.mark_dirty(self.y..self.y + 1);
+    }
+
+    fn mark_dirty(&mut self, rows: Range<usize>) {
+        self.dirty_rows = Some(match self.dirty_rows.take() {
+            Some(dirty) => dirty.start.min(rows.start)..dirty.end.max(rows.end),
+            None => rows,
+        });
+    }
+
+    fn print(&mut self, ch: char) {
+        let ch = match self.charsets[self.charset_index] {
+            Charset::Ascii => ch,
+            Charset::DecGraphics => dec_graphics_char(ch),
+        };
+
+        if self.need_wrap {
+            self.x = 0;
+            self.linefeed();
+        }
+
+        let row_start = self.y * self.cols;
+        if self.is_insert_mode {
+            self.cells.copy_within(
+                row_start + self.x..row_start + self.cols - 1,
+                row_start + self.x + 1,
+            );
+        }
+        self.cells[row_start + self.x] = self.attrs.cell(ch);
+        self.mark_dirty(self.y..self.y + 1);
+
+        if self.x + 1 < self.cols {
+            self.x += 1;
+        } else {
+            self.need_wrap = self.is_autowrap;
+        }
+    }
+
+    fn control(&mut self, byte: u8) {
+        match byte {
+            // BS
+            0x08 => {
+                self.x = self.x.saturating_sub(1);
+                self.need_wrap = false;
+            }
+            // HT
+            0x09 => {
+                self.x = ((self.x / TAB_WIDTH + 1) * TAB_WIDTH).min(self.cols - 1);
+                self.need_wrap = false;
+            }
+            // LF, VT, and FF
+            0x0a..=0x0c => self.linefeed(),
+            // CR
+            0x0d => {
+                self.x = 0;
+                self.need_wrap = false;
+            }
+            // SO and SI
+            0x0e => self.charset_index = 1,
+            0x0f => self.charset_index = 0,
+            // BEL and others
+            _ => (),
+        }
+    }
+
+    fn escape(&mut self, intermediate: Option<u8>, final_byte: u8) {
+        match (intermediate, final_byte) {
+            (None, b'7') => self.save_cursor(),
+            (None, b'8') => self.restore_cursor(),
+            (None, b'D') => self.linefeed(),
+            (None, b'E') => {
+                self.x = 0;
+                self.linefeed();
+            }
+            (None, b'M') => self.reverse_index(),
+            (None, b'c') => self.reset(),
+            (Some(designator @ (b'(' | b')')), charset) => {
+                let index = (designator - b'(') as usize;
+                self.charsets[index] = match charset {
+                    b'0' => Charset::DecGraphics,
+                    _ => Charset::Ascii,
+                };
+            }
+            _ => (),
+        }
+    }
+
+    fn csi(&mut self, csi: &CsiSequence) {
+        let count = csi.param_or(0, 1) as usize;
+
+        if csi.is_private() {
+            let is_set = match csi.final_byte() {
+                b'h' => true,
+                b'l' => false,
+                _ => return,
+            };
+            for &mode in csi.params() {
+                match mode {
+                    7 => self.is_autowrap = is_set,
+                    25 => self.is_cursor_visible = is_set,
+                    _ => (),
+                }
+            }
+            return;
+        }
+
+        match csi.final_byte() {
+            // ICH
+            b'@' => self.insert_chars(count),
+            // CUU
+            b'A' => self.goto(self.x, self.y.saturating_sub(count)),
+            // CUD
+            b'B' | b'e' => self.goto(self.x, self.y.saturating_add(count)),
+            // CUF
+            b'C' | b'a' => self.goto(self.x.saturating_add(count), self.y),
+            // CUB
+            b'D' => self.goto(self.x.saturating_sub(count), self.y),
+            // CNL
+            b'E' => self.goto(0, self.y.saturating_add(count)),
+            // CPL
+            b'F' => self.goto(0, self.y.saturating_sub(count)),
+            // CHA
+            b'G' | b'`' => self.goto(count - 1, self.y),
+            // CUP
+            b'H' | b'f' => self.goto(csi.param_or(1, 1) as usize - 1, count - 1),
+            // ED
+            b'J' => {
+                let pos = self.y * self.cols + self.x;
+                match csi.param_or(0, 0) {
+                    0 => self.erase(pos..self.cells.len()),
+                    1 => self.erase(0..pos + 1),
+                    2 | 3 => self.erase(0..self.cells.len()),
+                    _ => (),
+                }
+            }
+            // EL
+            b'K' => {
+                let row_start = self.y * self.cols;
+                let pos = row_start + self.x;
+                match csi.param_or(0, 0) {
+                    0 => self.erase(pos..row_start + self.cols),
+                    1 => self.erase(row_start..pos + 1),
+                    2 => self.erase(row_start..row_start + self.cols),
+                    _ => (),
+                }
+            }
+            // IL
+            b'L' => {
+                if (self.scroll_top..self.scroll_bottom).contains(&self.y) {
+                    self.scroll_down(self.y..self.scroll_bottom, count);
+                }
+            }
+            // DL
+            b'M' => {
+                if (self.scroll_top..self.scroll_bottom).contains(&self.y) {
+                    self.scroll_up(self.y..self.scroll_bottom, count);
+                }
+            }
+            // DCH
+            b'P' => self.delete_chars(count),
+            // ECH
+            b'X' => {
+                let pos = self.y * self.cols + self.x;
+                let end = (self.y + 1) * self.cols;
+                self.erase(pos..pos.saturating_add(count).min(end));
+            }
+            // VPA
+            b'd' => self.goto(self.x, count - 1),
+            // SGR
+            b'm' => self.set_graphic_rendition(csi.params()),
+            // DECSTBM
+            b'r' => {
+                let top = csi.param_or(0, 1) as usize;
+                let bottom = (csi.param_or(1, self.rows as u16) as usize).min(self.rows);
+                if top < bottom {
+                    self.scroll_top = top - 1;
+                    self.scroll_bottom = bottom;
+                    self.goto(0, 0);
+                }
+            }
+            b's' => self.save_cursor(),
+            b'u' => self.restore_cursor(),
+            // IRM
+            b'h' | b'l' if csi.params() == [4] => {
+                self.is_insert_mode = csi.final_byte() == b'h';
+            }
+            _ => (),
+        }
+    }
+
+    fn set_graphic_rendition(&mut self, params: &[u16]) {
+        if params.is_empty() {
+            self.attrs = Attributes::DEFAULT;
+            return;
+        }
+
+        let mut params = params.iter().copied();
+        while let Some(param) = params.next() {
+            match param {
+                0 => self.attrs = Attributes::DEFAULT,
+                1 => self.attrs.is_bold = true,
+                4 => self.attrs.is_underlined = true,
+                7 => self.attrs.is_reversed = true,
+                21 | 22 => self.attrs.is_bold = false,
+                24 => self.attrs.is_underlined = false,
+                27 => self.attrs.is_reversed = false,
+                30..=37 => self.attrs.fg = Color::Indexed((param - 30) as u8),
+                38 => {
+                    if let Some(color) = parse_extended_color(&mut params) {
+                        self.attrs.fg = color;
+                    }
+                }
+                39 => self.attrs.fg = Attributes::DEFAULT.fg,
+                40..=47 => self.attrs.bg = Color::Indexed((param - 40) as u8),
+                48 => {
+                    if let Some(color) = parse_extended_color(&mut params) {
+                        self.attrs.bg = color;
+                    }
+                }
+                49 => self.attrs.bg = Attributes::DEFAULT.bg,
+                90..=97 => self.attrs.fg = Color::Indexed((param - 90 + 8) as u8),
+                100..=107 => self.attrs.bg = Color::Indexed((param - 100 + 8) as u8),
+                _ => (),
+            }
+        }
+    }
+
+    /// Moves the cursor to the given position, which is clamped to the screen.
+    fn goto(&mut self, x: usize, y: usize) {
+        self.x = x.min(self.cols - 1);
+        self.y = y.min(self.rows - 1);
+        self.need_wrap = false;
+    }
+
+    fn linefeed(&mut self) {
+        if self.y + 1 == self.scroll_bottom {
+            self.scroll_up(self.scroll_top..self.scroll_bottom, 1);
+        } else if self.y + 1 < self.rows {
+            self.y += 1;
+        }
+        self.need_wrap = false;
+    }
+
+    fn reverse_index(&mut self) {
+        if self.y == self.scroll_top {
+            self.scroll_down(self.scroll_top..self.scroll_bottom, 1);
+        } else if self.y > 0 {
+            self.y -= 1;
+        }
+        self.need_wrap = false;
+    }
+
+    /// Scrolls the rows up by `count` lines, filling the bottom rows with blanks.
+    fn scroll_up(&mut self, rows: Range<usize>, count: usize) {
+        let count = count.min(rows.len());
+        let start = rows.start * self.cols;
+        let end = rows.end * self.cols;
+        let offset = count * self.cols;
+
+        self.cells.copy_within(start + offset..end, start);
+        self.erase(end - offset..end);
+        self.mark_dirty(rows);
+    }
+
+    /// Scrolls the rows down by `count` lines, filling the top rows with blanks.
+    fn scroll_down(&mut self, rows: Range<usize>, count: usize) {
+        let count = count.min(rows.len());
+        let start = rows.start * self.cols;
+        let end = rows.end * self.cols;
+        let offset = count * self.cols;
+
+        self.cells.copy_within(start..end - offset, start + offset);
+        self.erase(start..start + offset);
+        self.mark_dirty(rows);
+    }
+
+    fn insert_chars(&mut self, count: usize) {
+        let pos = self.y * self.cols + self.x;
+        let end = (self.y + 1) * self.cols;
+        let count = count.min(end - pos);
+
+        self.cells.copy_within(pos..end - count, pos + count);
+        self.erase(pos..pos + count);
+        self.need_wrap = false;
+    }
+
+    fn delete_chars(&mut self, count: usize) {
+        let pos = self.y * self.cols + self.x;
+        let end = (self.y + 1) * self.cols;
+        let count = count.min(end - pos);
+
+        self.cells.copy_within(pos + count..end, pos);
+        self.erase(end - count..end);
+        self.need_wrap = false;
+    }
+
+    /// Erases the cells in the range, where the range consists of the cell indexes.
+    fn erase(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let blank = self.attrs.blank_cell();
+        self.mark_dirty(range.start / self.cols..(range.end - 1) / self.cols + 1);
+        self.cells[range].fill(blank);
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved_cursor = SavedCursor {
+            x: self.x,
+            y: self.y,
+            attrs: self.attrs,
+            charsets: self.charsets,
+            charset_index: self.charset_index,
+        };
+    }
+
+    fn restore_cursor(&mut self) {
+        let saved = self.saved_cursor;
+        self.goto(saved.x, saved.y);
+        self.attrs = saved.attrs;
+        self.charsets = saved.charsets;
+        self.charset_index = saved.charset_index;
+    }
+
+    /// Resets the screen to its initial state.
+    fn reset(&mut self) {
+        *self = Self::new(self.cols, self.rows);
+    }
+}
+
+/// Parses the color after the SGR parameter 38 or 48, i.e., `5;n` or `2;r;g;b`.
+fn parse_extended_color(params: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match params.next()? {
+        5 => Some(Color::Indexed(params.next()?.min(255) as u8)),
+        2 => {
+            let red = params.next()?.min(255) as u8;
+            let green = params.next()?.min(255) as u8;
+            let blue = params.next()?.min(255) as u8;
+            Some(Color::Rgb(rgb(red, green, blue)))
+        }
+        _ => None,
+    }
+}
+
+/// Maps a character in the DEC special graphics character set to Unicode.
+fn dec_graphics_char(ch: char) -> char {
+    match ch {
+        '`' => '\u{25c6}',
+        'a' => '\u{2592}',
+        'f' => '\u{00b0}',
+        'g' => '\u{00b1}',
+        'j' => '\u{2518}',
+        'k' => '\u{2510}',
+        'l' => '\u{250c}',
+        'm' => '\u{2514}',
+        'n' => '\u{253c}',
+        'q' => '\u{2500}',
+        't' => '\u{251c}',
+        'u' => '\u{2524}',
+        'v' => '\u{2534}',
+        'w' => '\u{252c}',
+        'x' => '\u{2502}',
+        '~' => '\u{00b7}',
+        '0' => '\u{2588}',
+        _ => ch,
+    }
+}
@@ -1,8 +1,13 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use log::{Metadata, Record};
+use log::{Level, LevelFilter, Metadata, Record};
 use ostd::timer::Jiffies;
 
+use crate::{
+    console_level::console_log_levels,
+    log_buffer::{self, KERN_FACILITY},
+};
+
 /// The logger used for Asterinas.
 struct AsterLogger;
 
@@ -14,6 +19,13 @@ impl log::Log for AsterLogger {
     }
 
     fn log(&self, record: &Record) {
+        let level = syslog_level(record.level());
+        log_buffer::append_fmt(KERN_FACILITY, level, *record.args());
+
+        if level >= console_log_levels().console_loglevel() {
+            return;
+        }
+
         let timestamp = Jiffies::elapsed().as_duration().as_secs_f64();
         print_logs(record, timestamp);
     }
@@ -53,6 +65,21 @@ fn print_logs(record: &Record, timestamp: f64) {
     ));
 }
 
+/// Converts the level of a log record to the syslog level.
+fn syslog_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
 pub(super) fn init() {
+    let filter = log::max_level();
+    console_log_levels().init(filter);
+    // Keep warnings and errors in the log buffer even if they are not printed to the console.
+    log::set_max_level(filter.max(LevelFilter::Warn));
+
     ostd::logger::inject_logger(&LOGGER);
 }
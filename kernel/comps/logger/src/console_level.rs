@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The log levels that control which records are printed to the console.
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+use log::LevelFilter;
+
+/// The console log levels, which are exposed as `/proc/sys/kernel/printk`.
+///
+/// All the levels are syslog levels, i.e., from `0` for `KERN_EMERG` to `7` for `KERN_DEBUG`.
+pub struct ConsoleLogLevels {
+    /// The records whose levels are less than this level are printed to the console.
+    console_loglevel: AtomicU8,
+    /// The level of the records that are written to `/dev/kmsg` without a level.
+    default_message_loglevel: AtomicU8,
+    /// The minimum level to which `console_loglevel` can be set by `syslog`.
+    minimum_console_loglevel: AtomicU8,
+    /// The initial value of `console_loglevel`.
+    default_console_loglevel: AtomicU8,
+}
+
+/// Returns the console log levels.
+pub fn console_log_levels() -> &'static ConsoleLogLevels {
+    &CONSOLE_LOG_LEVELS
+}
+
+static CONSOLE_LOG_LEVELS: ConsoleLogLevels = ConsoleLogLevels {
+    console_loglevel: AtomicU8::new(DEFAULT_CONSOLE_LOGLEVEL),
+    default_message_loglevel: AtomicU8::new(4),
+    minimum_console_loglevel: AtomicU8::new(1),
+    default_console_loglevel: AtomicU8::new(DEFAULT_CONSOLE_LOGLEVEL),
+};
+
+/// The default console log level, with which all the records are printed.
+const DEFAULT_CONSOLE_LOGLEVEL: u8 = 8;
+
+impl ConsoleLogLevels {
+    /// Returns the console log level.
+    pub fn console_loglevel(&self) -> u8 {
+        self.console_loglevel.load(Ordering::Relaxed)
+    }
+
+    /// Sets the console log level.
+    pub fn set_console_loglevel(&self, level: u8) {
+        self.console_loglevel.store(level, Ordering::Relaxed);
+    }
+
+    /// Returns the level of the records that are written without a level.
+    pub fn default_message_loglevel(&self) -> u8 {
+        self.default_message_loglevel.load(Ordering::Relaxed)
+    }
+
+    /// Returns the minimum level to which the console log level can be set.
+    pub fn minimum_console_loglevel(&self) -> u8 {
+        self.minimum_console_loglevel.load(Ordering::Relaxed)
+    }
+
+    /// Returns all four levels in the order of `/proc/sys/kernel/printk`.
+    pub fn levels(&self) -> [u8; 4] {
+        [
+            self.console_loglevel(),
+            self.default_message_loglevel(),
+            self.minimum_console_loglevel(),
+            self.default_console_loglevel.load(Ordering::Relaxed),
+        ]
+    }
+
+    /// Sets the levels in the order of `/proc/sys/kernel/printk`.
+    ///
+    /// If fewer than four levels are given, the remaining levels are left unchanged.
+    pub fn set_levels(&self, levels: &[u8]) {
+        let fields = [
+            &self.console_loglevel,
+            &self.default_message_loglevel,
+            &self.minimum_console_loglevel,
+            &self.default_console_loglevel,
+        ];
+        for (field, level) in fields.into_iter().zip(levels) {
+            field.store(*level, Ordering::Relaxed);
+        }
+    }
+
+    /// Initializes the console log level from the log level specified in the kernel command line.
+    ///
+    /// With the derived console log level, only the records at or above the specified log level
+    /// are printed to the console, although more records may be kept in the log buffer.
+    pub(crate) fn init(&self, filter: LevelFilter) {
+        let level = match filter {
+            LevelFilter::Off => self.minimum_console_loglevel(),
+            LevelFilter::Error => 4,
+            LevelFilter::Warn => 5,
+            LevelFilter::Info => 7,
+            LevelFilter::Debug | LevelFilter::Trace => 8,
+        };
+        self.console_loglevel.store(level, Ordering::Relaxed);
+        self.default_console_loglevel
+            .store(level, Ordering::Relaxed);
+    }
+}
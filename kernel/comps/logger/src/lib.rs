@@ -11,6 +11,10 @@
 //! concurrently on other cores.
 //!
 //! IRQs are disabled while printing. So do not print long log messages.
+//!
+//! The log records are also kept in a lock-free ring buffer (see [`log_buffer`]), which
+//! allows the user space to read them later (e.g., via `/dev/kmsg` or `syslog`). Whether a record
+//! is printed to the console is controlled by the [`ConsoleLogLevels`].
 #![no_std]
 #![deny(unsafe_code)]
 
@@ -20,8 +24,11 @@ use component::{init_component, ComponentInitError};
 
 mod aster_logger;
 mod console;
+mod console_level;
+pub mod log_buffer;
 
 pub use console::_print;
+pub use console_level::{console_log_levels, ConsoleLogLevels};
 
 #[init_component]
 fn init() -> Result<(), ComponentInitError> {
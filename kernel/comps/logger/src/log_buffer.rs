@@ -0,0 +1,327 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The kernel log buffer.
+//!
+//! The log buffer keeps the most recent log records in a ring of fixed-size slots. Each slot is
+//! guarded by a sequence lock made of atomic integers, so appending a record is lock-free and can
+//! be done in any context, including interrupt handlers. A reader detects records that are
+//! overwritten while it is reading them and reports them as unavailable.
+//!
+//! Records are identified by sequence numbers, which start from zero and increase by one for each
+//! appended record. Once the ring is full, appending a record overwrites the oldest one.
+
+use alloc::string::String;
+use core::{
+    fmt::{self, Write},
+    sync::atomic::{fence, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use ostd::{
+    cpu::PinCurrentCpu,
+    timer::Jiffies,
+    trap::{in_interrupt_context, irq::disable_local},
+};
+use spin::Once;
+
+/// The number of records that the log buffer can hold.
+pub const NR_RECORDS: usize = 512;
+
+/// The maximum length of the text of a record, in bytes.
+///
+/// Longer texts are truncated.
+pub const MAX_TEXT_LEN: usize = 480;
+
+/// The facility of the records generated by the kernel (i.e., `LOG_KERN`).
+pub const KERN_FACILITY: u8 = 0;
+
+/// The facility of the records generated by the user space (i.e., `LOG_USER`).
+pub const USER_FACILITY: u8 = 1;
+
+/// A record in the kernel log buffer.
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    seq: u64,
+    facility: u8,
+    level: u8,
+    timestamp: Duration,
+    cpu: u32,
+    task: Option<u32>,
+    text: String,
+}
+
+impl LogRecord {
+    /// Returns the sequence number of the record.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Returns the syslog facility (e.g., [`KERN_FACILITY`]) of the record.
+    pub fn facility(&self) -> u8 {
+        self.facility
+    }
+
+    /// Returns the syslog level (i.e., from `0` for `KERN_EMERG` to `7` for `KERN_DEBUG`) of
+    /// the record.
+    pub fn level(&self) -> u8 {
+        self.level
+    }
+
+    /// Returns the syslog priority, which combines the facility and the level.
+    pub fn priority(&self) -> u32 {
+        ((self.facility as u32) << 3) | self.level as u32
+    }
+
+    /// Returns the time elapsed since boot when the record was appended.
+    pub fn timestamp(&self) -> Duration {
+        self.timestamp
+    }
+
+    /// Returns the ID of the CPU that appended the record.
+    pub fn cpu(&self) -> u32 {
+        self.cpu
+    }
+
+    /// Returns the ID of the task that appended the record.
+    ///
+    /// This is `None` if the record was appended in the interrupt context or before the task
+    /// ID getter was injected with [`inject_task_id_getter`].
+    pub fn task(&self) -> Option<u32> {
+        self.task
+    }
+
+    /// Returns the text of the record.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Appends a record with the given text to the log buffer.
+///
+/// The text will be truncated to [`MAX_TEXT_LEN`] bytes. The `level` will be clamped to the
+/// range of the syslog levels.
+pub fn append(facility: u8, level: u8, text: &str) {
+    let mut buf = TextBuf::new();
+    let _ = buf.write_str(text);
+    commit(facility, level.min(7), &buf);
+}
+
+/// Appends a record with the text formatted from `args` to the log buffer.
+pub(crate) fn append_fmt(facility: u8, level: u8, args: fmt::Arguments) {
+    let mut buf = TextBuf::new();
+    let _ = buf.write_fmt(args);
+    commit(facility, level.min(7), &buf);
+}
+
+/// Returns the sequence number of the oldest record that may still be in the log buffer.
+pub fn first_seq() -> u64 {
+    next_seq().saturating_sub(NR_RECORDS as u64)
+}
+
+/// Returns the sequence number that the next appended record will have.
+pub fn next_seq() -> u64 {
+    NEXT_SEQ.load(Ordering::Acquire)
+}
+
+/// Reads the record with the given sequence number.
+///
+/// This method returns `None` if the record has not been fully appended yet or has been
+/// overwritten. The two cases can be distinguished by comparing `seq` with [`first_seq`].
+pub fn read_record(seq: u64) -> Option<LogRecord> {
+    SLOTS[seq as usize % NR_RECORDS].read(seq)
+}
+
+/// Injects a function that returns the ID of the current task.
+///
+/// The ID is recorded in the records appended in the task context. The function may be called
+/// with local IRQs disabled, so it must not sleep.
+pub fn inject_task_id_getter(getter: fn() -> Option<u32>) {
+    TASK_ID_GETTER.call_once(|| getter);
+}
+
+/// Injects a function that will be called after a record is appended.
+///
+/// The function may be called in any context (e.g., in interrupt handlers or while holding
+/// arbitrary spin locks), so it must not sleep or acquire locks.
+pub fn inject_append_handler(handler: fn()) {
+    APPEND_HANDLER.call_once(|| handler);
+}
+
+static SLOTS: [Slot; NR_RECORDS] = [const { Slot::new() }; NR_RECORDS];
+
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+static TASK_ID_GETTER: Once<fn() -> Option<u32>> = Once::new();
+
+static APPEND_HANDLER: Once<fn()> = Once::new();
+
+fn commit(facility: u8, level: u8, text: &TextBuf) {
+    let task = if in_interrupt_context() {
+        None
+    } else {
+        TASK_ID_GETTER.get().and_then(|getter| getter())
+    };
+
+    {
+        // Disable IRQs so that the slot is written in a short and bounded time. Otherwise, the
+        // readers may see the record as unavailable for a long time.
+        let irq_guard = disable_local();
+        let meta = RecordMeta {
+            facility,
+            level,
+            len: text.len as u16,
+            cpu: irq_guard.current_cpu().as_usize() as u32,
+            timestamp: Jiffies::elapsed().as_duration(),
+            task,
+        };
+
+        let seq = NEXT_SEQ.fetch_add(1, Ordering::AcqRel);
+        SLOTS[seq as usize % NR_RECORDS].write(seq, &meta, text.as_bytes());
+    }
+
+    if let Some(handler) = APPEND_HANDLER.get() {
+        handler();
+    }
+}
+
+const TEXT_WORDS: usize = MAX_TEXT_LEN / size_of::<u64>();
+
+/// A slot in the log buffer.
+///
+/// The `state` of the slot is zero if the slot is empty. Otherwise, it is `(seq + 1) << 1` if the
+/// record with the sequence number `seq` is stored in the slot, with the lowest bit set if the
+/// record is being written.
+///
+/// Two writers can write to the same slot concurrently only if [`NR_RECORDS`] records are being
+/// appended at the same time, which is not expected to happen.
+struct Slot {
+    state: AtomicU64,
+    timestamp: AtomicU64,
+    meta: AtomicU64,
+    task: AtomicU64,
+    text: [AtomicU64; TEXT_WORDS],
+}
+
+struct RecordMeta {
+    facility: u8,
+    level: u8,
+    len: u16,
+    cpu: u32,
+    timestamp: Duration,
+    task: Option<u32>,
+}
+
+const STATE_BUSY: u64 = 1;
+
+const fn committed_state(seq: u64) -> u64 {
+    (seq + 1) << 1
+}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            state: AtomicU64::new(0),
+            timestamp: AtomicU64::new(0),
+            meta: AtomicU64::new(0),
+            task: AtomicU64::new(0),
+            text: [const { AtomicU64::new(0) }; TEXT_WORDS],
+        }
+    }
+
+    fn write(&self, seq: u64, meta: &RecordMeta, text: &[u8]) {
+        self.state
+            .store(committed_state(seq) | STATE_BUSY, Ordering::Relaxed);
+        // Order the above store before the following stores. Paired with the fence in
+        // `Self::read`.
+        fence(Ordering::Release);
+
+        self.timestamp
+            .store(meta.timestamp.as_nanos() as u64, Ordering::Relaxed);
+        self.meta.store(
+            meta.facility as u64
+                | ((meta.level as u64) << 8)
+                | ((meta.len as u64) << 16)
+                | ((meta.cpu as u64) << 32),
+            Ordering::Relaxed,
+        );
+        self.task.store(
+            meta.task.map_or(0, |task| (1 << 32) | task as u64),
+            Ordering::Relaxed,
+        );
+        for (word, chunk) in self.text.iter().zip(text.chunks(size_of::<u64>())) {
+            let mut bytes = [0u8; size_of::<u64>()];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            word.store(u64::from_le_bytes(bytes), Ordering::Relaxed);
+        }
+
+        self.state.store(committed_state(seq), Ordering::Release);
+    }
+
+    fn read(&self, seq: u64) -> Option<LogRecord> {
+        let state = self.state.load(Ordering::Acquire);
+        if state != committed_state(seq) {
+            return None;
+        }
+
+        let timestamp = self.timestamp.load(Ordering::Relaxed);
+        let meta = self.meta.load(Ordering::Relaxed);
+        let task = self.task.load(Ordering::Relaxed);
+        let mut text = [0u8; MAX_TEXT_LEN];
+        for (word, chunk) in self.text.iter().zip(text.chunks_mut(size_of::<u64>())) {
+            chunk.copy_from_slice(&word.load(Ordering::Relaxed).to_le_bytes());
+        }
+
+        // Order the above loads before the following load. Paired with the fence in
+        // `Self::write`.
+        fence(Ordering::Acquire);
+        if self.state.load(Ordering::Relaxed) != state {
+            return None;
+        }
+
+        let len = ((meta >> 16) as u16 as usize).min(MAX_TEXT_LEN);
+        Some(LogRecord {
+            seq,
+            facility: meta as u8,
+            level: (meta >> 8) as u8,
+            timestamp: Duration::from_nanos(timestamp),
+            cpu: (meta >> 32) as u32,
+            task: (task >> 32 != 0).then_some(task as u32),
+            text: String::from_utf8_lossy(&text[..len]).into_owned(),
+        })
+    }
+}
+
+/// A buffer on the stack to format the text of a record without allocating memory.
+struct TextBuf {
+    buf: [u8; MAX_TEXT_LEN],
+    len: usize,
+}
+
+impl TextBuf {
+    fn new() -> Self {
+        Self {
+            buf: [0; MAX_TEXT_LEN],
+            len: 0,
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl Write for TextBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let avail = MAX_TEXT_LEN - self.len;
+        let len = if s.len() <= avail {
+            s.len()
+        } else {
+            // Truncate the text at a character boundary.
+            (0..=avail).rev().find(|&i| s.is_char_boundary(i)).unwrap()
+        };
+
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
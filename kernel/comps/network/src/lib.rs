@@ -12,7 +12,7 @@ mod driver;
 
 extern crate alloc;
 
-use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use alloc::{collections::BTreeMap, format, string::String, sync::Arc, vec::Vec};
 use core::{any::Any, fmt::Debug};
 
 use aster_bigtcp::device::DeviceCapabilities;
@@ -69,6 +69,12 @@ pub trait AnyNetworkDevice: Send + Sync + Any + Debug {
 
 pub trait NetDeviceCallback = Fn() + Send + Sync + 'static;
 
+pub trait NetDeviceObserver = Fn(&str) + Send + Sync + 'static;
+
+/// Registers a network device.
+///
+/// If a device with the same name already exists, it will be replaced. Drivers that may manage
+/// more than one device should use [`alloc_device_name`] to obtain a unique name.
 pub fn register_device(
     name: String,
     device: Arc<SpinLock<dyn AnyNetworkDevice, BottomHalfDisabled>>,
@@ -78,7 +84,44 @@ pub fn register_device(
         .unwrap()
         .network_device_table
         .lock()
-        .insert(name, NetworkDeviceIrqCallbackSet::new(device));
+        .insert(name.clone(), NetworkDeviceIrqCallbackSet::new(device));
+
+    notify_observers(&name);
+}
+
+/// Allocates a unique device name that starts with `prefix`.
+///
+/// The name is `prefix` followed by the smallest number that is not in use.
+pub fn alloc_device_name(prefix: &str) -> String {
+    let device_table = COMPONENT.get().unwrap().network_device_table.lock();
+    (0..)
+        .map(|index| format!("{}{}", prefix, index))
+        .find(|name| !device_table.contains_key(name))
+        .unwrap()
+}
+
+/// Registers an observer that will be called with the name of each device that is added later.
+///
+/// The observer is not called for the devices that exist before the registration.
+/// Use [`all_devices`] to enumerate them.
+///
+/// The observer is called in the context of the driver that adds the device,
+/// which must be able to sleep.
+pub fn register_device_observer(observer: impl NetDeviceObserver) {
+    COMPONENT
+        .get()
+        .unwrap()
+        .device_observers
+        .lock()
+        .push(Arc::new(observer));
+}
+
+fn notify_observers(name: &str) {
+    // Clone the observers so that they can register callbacks without deadlocks.
+    let observers = COMPONENT.get().unwrap().device_observers.lock().clone();
+    for observer in observers.iter() {
+        observer(name);
+    }
 }
 
 pub fn get_device(str: &str) -> Option<Arc<SpinLock<dyn AnyNetworkDevice, BottomHalfDisabled>>> {
@@ -188,6 +231,8 @@ struct Component {
     /// Device list, the key is device name, value is (callbacks, device);
     network_device_table:
         SpinLock<BTreeMap<String, NetworkDeviceIrqCallbackSet>, BottomHalfDisabled>,
+    /// Observers that are notified when devices are added.
+    device_observers: SpinLock<Vec<Arc<dyn NetDeviceObserver>>>,
 }
 
 /// The send callbacks and recv callbacks for a network device
@@ -211,6 +256,7 @@ impl Component {
     pub fn init() -> Result<Self, ComponentInitError> {
         Ok(Self {
             network_device_table: SpinLock::new(BTreeMap::new()),
+            device_observers: SpinLock::new(Vec::new()),
         })
     }
 }
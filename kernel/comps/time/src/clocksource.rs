@@ -53,9 +53,39 @@ use crate::NANOS_PER_SECOND;
 pub struct ClockSource {
     read_cycles: Arc<dyn Fn() -> u64 + Sync + Send>,
     base: ClockSourceBase,
-    coeff: Coeff,
+    /// The coeff that converts cycles to time without any frequency adjustments.
+    raw_coeff: Coeff,
     /// A record to an `Instant` and the corresponding cycles of this `ClockSource`.
-    last_record: RwLock<(Instant, u64), LocalIrqDisabled>,
+    last_record: RwLock<ClockRecord, LocalIrqDisabled>,
+}
+
+/// A reference point of a `ClockSource`.
+///
+/// The coeff in use is recorded together with the instants, so that a frequency adjustment
+/// takes effect only after the time passed with the old coeff is accumulated.
+#[derive(Debug, Copy, Clone)]
+pub struct ClockRecord {
+    instant: Instant,
+    raw_instant: Instant,
+    cycles: u64,
+    coeff: Coeff,
+}
+
+impl ClockRecord {
+    /// Returns the recorded instant.
+    pub fn instant(&self) -> Instant {
+        self.instant
+    }
+
+    /// Returns the cycles of the `ClockSource` at the recorded instant.
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Returns the coeff that is in use since the recorded instant.
+    pub fn coeff(&self) -> Coeff {
+        self.coeff
+    }
 }
 
 impl ClockSource {
@@ -77,8 +107,13 @@ impl ClockSource {
         Self {
             read_cycles,
             base,
-            coeff,
-            last_record: RwLock::new((Instant::zero(), 0)),
+            raw_coeff: coeff,
+            last_record: RwLock::new(ClockRecord {
+                instant: Instant::zero(),
+                raw_instant: Instant::zero(),
+                cycles: 0,
+                coeff,
+            }),
         }
     }
 
@@ -88,26 +123,25 @@ impl ClockSource {
     /// recorded cycles stored in the clocksource. Then `ClockSource` will convert
     /// the passed cycles into passed time and calculate the current instant.
     ///
-    /// Returns the calculated instant and instant cycles.
-    fn calculate_instant(&self) -> (Instant, u64) {
-        let (instant_cycles, last_instant, last_cycles) = {
-            let last_record = self.last_record.read();
-            let (last_instant, last_cycles) = *last_record;
-            (self.read_cycles(), last_instant, last_cycles)
-        };
-
-        let delta_nanos = {
-            let delta_cycles = instant_cycles - last_cycles;
-            self.cycles_to_nanos_lossy(delta_cycles)
-        };
-        let duration = Duration::from_nanos(delta_nanos);
-        (last_instant + duration, instant_cycles)
-    }
-
-    fn cycles_to_nanos_lossy(&self, cycles: u64) -> u64 {
+    /// Returns the calculated record, whose coeff is the same as the last record.
+    fn calculate_record(&self, last_record: &ClockRecord) -> ClockRecord {
+        let instant_cycles = self.read_cycles();
+        let delta_cycles = self.bounded_cycles(instant_cycles - last_record.cycles);
+
+        let delta_nanos = last_record.coeff * delta_cycles;
+        let raw_delta_nanos = self.raw_coeff * delta_cycles;
+        ClockRecord {
+            instant: last_record.instant + Duration::from_nanos(delta_nanos),
+            raw_instant: last_record.raw_instant + Duration::from_nanos(raw_delta_nanos),
+            cycles: instant_cycles,
+            coeff: last_record.coeff,
+        }
+    }
+
+    fn bounded_cycles(&self, cycles: u64) -> u64 {
         let max_cycles = self.base.max_delay_secs * self.base.freq;
         if cycles <= max_cycles {
-            self.coeff * cycles
+            cycles
         } else {
             log::warn!(
                 "The clock source becomes not reliable since an \
@@ -115,23 +149,21 @@ impl ClockSource {
                 cycles,
                 max_cycles
             );
-            self.coeff * max_cycles
+            max_cycles
         }
     }
 
-    /// Uses an input instant and cycles to update the `last_record` in the `ClockSource`.
-    fn update_last_record(&self, record: (Instant, u64)) {
-        *self.last_record.write() = record;
-    }
-
     /// Reads current cycles of the `ClockSource`.
     pub fn read_cycles(&self) -> u64 {
         (self.read_cycles)()
     }
 
-    /// Returns the last instant and last cycles recorded in the `ClockSource`.
-    pub fn last_record(&self) -> (Instant, u64) {
-        return *self.last_record.read();
+    /// Returns the last record in the `ClockSource`.
+    ///
+    /// The instant, the cycles and the coeff in the record are consistent with each other, even
+    /// if the record is being updated concurrently.
+    pub fn last_record(&self) -> ClockRecord {
+        *self.last_record.read()
     }
 
     /// Returns the maximum delay seconds for updating of the `ClockSource`.
@@ -139,9 +171,11 @@ impl ClockSource {
         self.base.max_delay_secs
     }
 
-    /// Returns the reference to the generated cycles coeff of the `ClockSource`.
-    pub fn coeff(&self) -> &Coeff {
-        &self.coeff
+    /// Returns the cycles coeff of the `ClockSource` that is currently in use.
+    ///
+    /// The coeff reflects the frequency adjustment set by [`Self::set_freq_adjustment`].
+    pub fn coeff(&self) -> Coeff {
+        self.last_record.read().coeff
     }
 
     /// Returns the frequency of the counter used in the `ClockSource`.
@@ -149,20 +183,52 @@ impl ClockSource {
         self.base.freq
     }
 
+    /// Adjusts the rate at which the time of the `ClockSource` advances.
+    ///
+    /// The time will advance by `NANOS_PER_SECOND + ppb` nanoseconds per second of the counter.
+    /// The time that has passed so far is accumulated with the old rate, so the adjustment never
+    /// causes the time to jump. Note that the resolution of the adjustment is limited by the
+    /// precision of the generated `Coeff`.
+    ///
+    /// The raw instants (see [`Self::read_raw_instant`]) are not affected.
+    pub fn set_freq_adjustment(&self, ppb: i64) {
+        let nanos_per_second = (NANOS_PER_SECOND as i64 + ppb) as u64;
+        let coeff = Coeff::new(
+            nanos_per_second,
+            self.base.freq,
+            self.base.max_delay_secs * self.base.freq,
+        );
+
+        let mut last_record = self.last_record.write();
+        let mut record = self.calculate_record(&last_record);
+        record.coeff = coeff;
+        *last_record = record;
+    }
+
     /// Calibrates the recorded `Instant` to zero, and record the instant cycles.
     pub(crate) fn calibrate(&self, instant_cycles: u64) {
-        self.update_last_record((Instant::zero(), instant_cycles));
+        let mut last_record = self.last_record.write();
+        last_record.instant = Instant::zero();
+        last_record.raw_instant = Instant::zero();
+        last_record.cycles = instant_cycles;
     }
 
     /// Gets the instant to update the internal instant in the `ClockSource`.
     pub(crate) fn update(&self) {
-        let (instant, instant_cycles) = self.calculate_instant();
-        self.update_last_record((instant, instant_cycles));
+        let mut last_record = self.last_record.write();
+        *last_record = self.calculate_record(&last_record);
     }
 
     /// Reads the instant corresponding to the current time.
     pub(crate) fn read_instant(&self) -> Instant {
-        self.calculate_instant().0
+        let last_record = *self.last_record.read();
+        self.calculate_record(&last_record).instant
+    }
+
+    /// Reads the instant corresponding to the current time, without any frequency adjustments.
+    pub(crate) fn read_raw_instant(&self) -> Instant {
+        let last_record = *self.last_record.read();
+        self.calculate_record(&last_record).raw_instant
     }
 }
 
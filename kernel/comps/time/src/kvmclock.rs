@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module provides an instance of `ClockSource` based on kvmclock.
+//!
+//! kvmclock is preferred over the raw TSC when running on KVM, since its parameters are
+//! maintained by the hypervisor and do not suffer from the calibration errors in the guest.
+
+use alloc::sync::Arc;
+
+use ostd::arch::kvmclock;
+
+use crate::{clocksource::ClockSource, VdsoClock, NANOS_PER_SECOND};
+
+const MAX_DELAY_SECS: u64 = 100;
+
+/// Creates a kvmclock clocksource, or returns `None` if kvmclock is not available.
+pub(super) fn new_clocksource() -> Option<ClockSource> {
+    if !kvmclock::is_available() {
+        return None;
+    }
+
+    // The "cycles" of kvmclock are nanoseconds.
+    Some(ClockSource::new(
+        NANOS_PER_SECOND as u64,
+        MAX_DELAY_SECS,
+        Arc::new(kvmclock::read),
+    ))
+}
+
+/// Returns how user space reads the kvmclock clocksource, or `None` if kvmclock is not available.
+pub(super) fn vdso_clock() -> Option<VdsoClock> {
+    let frame = kvmclock::vdso_frame()?;
+
+    // User space can only read the `pvclock_vcpu_time_info` structure of one CPU, which is
+    // correct only if the clocks of all the CPUs are synchronized.
+    if kvmclock::is_stable() {
+        Some(VdsoClock::Pvclock(frame))
+    } else {
+        Some(VdsoClock::None)
+    }
+}
@@ -13,11 +13,13 @@ use core::time::Duration;
 use clocksource::ClockSource;
 pub use clocksource::Instant;
 use component::{init_component, ComponentInitError};
-use ostd::sync::Mutex;
+use ostd::{mm::Frame, sync::Mutex};
 use rtc::Driver;
 use spin::Once;
 
 mod clocksource;
+#[cfg(target_arch = "x86_64")]
+mod kvmclock;
 mod rtc;
 mod tsc;
 
@@ -77,6 +79,21 @@ fn update_time() {
     *lock = RTC_DRIVER.get().unwrap().read_rtc();
 }
 
+/// Writes the time to the RTC.
+///
+/// The time must be a valid date and time.
+pub fn write(time: SystemTime) {
+    RTC_DRIVER.get().unwrap().write_rtc(time);
+}
+
+/// Programs the alarm of the RTC, or clears it if `time` is `None`.
+///
+/// The alarm interrupt is not enabled, so the caller is responsible for noticing that the alarm
+/// time has been reached.
+pub fn set_alarm(time: Option<SystemTime>) {
+    RTC_DRIVER.get().unwrap().set_alarm(time);
+}
+
 /// Return the `START_TIME`, which is the actual time when doing calibrate.
 pub fn read_start_time() -> SystemTime {
     *START_TIME.get().unwrap()
@@ -88,7 +105,35 @@ pub fn read_monotonic_time() -> Duration {
     Duration::new(instant.secs(), instant.nanos())
 }
 
+/// Return the monotonic time from the tsc clocksource, which is not affected by
+/// the frequency adjustments.
+pub fn read_monotonic_raw_time() -> Duration {
+    let instant = tsc::read_raw_instant();
+    Duration::new(instant.secs(), instant.nanos())
+}
+
 /// Return the tsc clocksource.
 pub fn default_clocksource() -> Arc<ClockSource> {
     tsc::CLOCK.get().unwrap().clone()
 }
+
+/// The way that user space (i.e., the vDSO) reads the default clocksource.
+#[derive(Debug, Clone)]
+pub enum VdsoClock {
+    /// User space reads the TSC.
+    Tsc,
+    /// User space reads the `pvclock_vcpu_time_info` structure at the beginning of the frame.
+    Pvclock(Frame<()>),
+    /// User space cannot read the default clocksource and must fall back to system calls.
+    None,
+}
+
+/// Returns the way that user space reads the default clocksource.
+pub fn vdso_clock() -> VdsoClock {
+    #[cfg(target_arch = "x86_64")]
+    if let Some(vdso_clock) = kvmclock::vdso_clock() {
+        return vdso_clock;
+    }
+
+    VdsoClock::Tsc
+}
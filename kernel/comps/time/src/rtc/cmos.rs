@@ -2,18 +2,35 @@
 
 use core::sync::atomic::{AtomicU8, Ordering::Relaxed};
 
-use ostd::arch::device::cmos::{century_register, CMOS_ADDRESS, CMOS_DATA};
+use ostd::{
+    arch::device::cmos::{century_register, CMOS_ADDRESS, CMOS_DATA},
+    sync::SpinLock,
+};
 
 use crate::SystemTime;
 use super::Driver;
 
 static CENTURY_REGISTER: AtomicU8 = AtomicU8::new(0);
 
+/// The bit in register B that inhibits the updates of the time registers.
+const REGISTER_B_SET: u8 = 0x80;
+/// The bit in register B that enables the alarm interrupt.
+const REGISTER_B_AIE: u8 = 0x20;
+
 fn get_cmos(reg: u8) -> u8 {
     CMOS_ADDRESS.write(reg);
     CMOS_DATA.read()
 }
 
+fn set_cmos(reg: u8, value: u8) {
+    CMOS_ADDRESS.write(reg);
+    CMOS_DATA.write(value);
+}
+
+fn binary_to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
 fn is_updating() -> bool {
     CMOS_ADDRESS.write(0x0A);
     CMOS_DATA.read() & 0x80 != 0
@@ -79,12 +96,39 @@ impl CmosData {
         }
     }
 
+    /// Converts binary values to BCD.
+    fn convert_binary_to_bcd(&mut self, register_b: u8) {
+        if register_b & 0x04 == 0 {
+            self.second = binary_to_bcd(self.second);
+            self.minute = binary_to_bcd(self.minute);
+            self.hour = binary_to_bcd(self.hour & 0x7F) | (self.hour & 0x80);
+            self.day = binary_to_bcd(self.day);
+            self.month = binary_to_bcd(self.month);
+            self.year = binary_to_bcd(self.year as u8) as u16;
+            self.century = binary_to_bcd(self.century);
+        }
+    }
+
     /// Converts 12 hour clock to 24 hour clock.
     fn convert_12_hour_to_24_hour(&mut self, register_b: u8) {
         // bit1 in register_b is not set if 12 hour format is enable
         // if highest bit in hour is set, then it is pm
-        if ((register_b & 0x02) == 0) && ((self.hour & 0x80) != 0) {
-            self.hour = ((self.hour & 0x7F) + 12) % 24;
+        if (register_b & 0x02) == 0 {
+            let is_pm = (self.hour & 0x80) != 0;
+            // 12 AM is 0 o'clock and 12 PM is 12 o'clock.
+            self.hour = (self.hour & 0x7F) % 12 + if is_pm { 12 } else { 0 };
+        }
+    }
+
+    /// Converts 24 hour clock to 12 hour clock.
+    fn convert_24_hour_to_12_hour(&mut self, register_b: u8) {
+        if (register_b & 0x02) == 0 {
+            let is_pm = self.hour >= 12;
+            let hour = match self.hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            self.hour = hour | if is_pm { 0x80 } else { 0 };
         }
     }
 
@@ -107,6 +151,59 @@ impl CmosData {
 
         now
     }
+
+    /// Converts the values to the format specified by register B.
+    fn into_rtc_format(mut self, register_b: u8) -> Self {
+        self.convert_24_hour_to_12_hour(register_b);
+        self.convert_binary_to_bcd(register_b);
+        self
+    }
+
+    pub fn write_rtc(self, century_register: u8) {
+        let register_b = get_cmos(0x0B);
+        let raw = self.into_rtc_format(register_b);
+
+        // Inhibit the updates so that the registers are not changed halfway.
+        set_cmos(0x0B, register_b | REGISTER_B_SET);
+
+        set_cmos(0x00, raw.second);
+        set_cmos(0x02, raw.minute);
+        set_cmos(0x04, raw.hour);
+        set_cmos(0x07, raw.day);
+        set_cmos(0x08, raw.month);
+        set_cmos(0x09, raw.year as u8);
+        if century_register != 0 {
+            set_cmos(century_register, raw.century);
+        }
+
+        set_cmos(0x0B, register_b & !REGISTER_B_SET);
+    }
+
+    /// Writes the alarm registers.
+    ///
+    /// The alarm registers only contain the hour, the minute, and the second. The date is ignored.
+    pub fn write_alarm(self) {
+        let register_b = get_cmos(0x0B);
+        let raw = self.into_rtc_format(register_b);
+
+        set_cmos(0x01, raw.second);
+        set_cmos(0x03, raw.minute);
+        set_cmos(0x05, raw.hour);
+    }
+}
+
+impl From<SystemTime> for CmosData {
+    fn from(time: SystemTime) -> CmosData {
+        CmosData {
+            century: (time.year / 100) as u8,
+            year: time.year % 100,
+            month: time.month,
+            day: time.day,
+            hour: time.hour,
+            minute: time.minute,
+            second: time.second,
+        }
+    }
 }
 
 impl From<CmosData> for SystemTime {
@@ -125,16 +222,40 @@ impl From<CmosData> for SystemTime {
 
 pub struct RtcCmos {
     century_register: u8,
+    /// The lock that serializes the accesses to the CMOS registers.
+    ///
+    /// Each access consists of a write to the address port followed by an access to the data
+    /// port, which must not be interleaved with other accesses.
+    lock: SpinLock<()>,
 }
 
 impl Driver for RtcCmos {
     fn try_new() -> Option<RtcCmos> {
         Some(RtcCmos {
             century_register: century_register().unwrap_or(0),
+            lock: SpinLock::new(()),
         })
     }
 
     fn read_rtc(&self) -> SystemTime {
+        let _guard = self.lock.lock();
         CmosData::read_rtc(self.century_register).into()
     }
+
+    fn write_rtc(&self, time: SystemTime) {
+        let _guard = self.lock.lock();
+        CmosData::from(time).write_rtc(self.century_register);
+    }
+
+    fn set_alarm(&self, time: Option<SystemTime>) {
+        let _guard = self.lock.lock();
+
+        if let Some(time) = time {
+            CmosData::from(time).write_alarm();
+        }
+
+        // We do not handle the RTC interrupt, so the alarm interrupt is always disabled.
+        let register_b = get_cmos(0x0B);
+        set_cmos(0x0B, register_b & !REGISTER_B_AIE);
+    }
 }
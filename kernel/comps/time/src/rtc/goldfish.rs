@@ -1,14 +1,52 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use ostd::{arch::boot::DEVICE_TREE, io::IoMem, mm::VmIoOnce};
-use chrono::{DateTime, Datelike, Timelike};
+use chrono::{DateTime, Datelike, NaiveDate, Timelike};
 
 use crate::{SystemTime, rtc::Driver};
 
+const TIME_LOW: usize = 0x00;
+const TIME_HIGH: usize = 0x04;
+const ALARM_LOW: usize = 0x08;
+const ALARM_HIGH: usize = 0x0c;
+const IRQ_ENABLED: usize = 0x10;
+const CLEAR_ALARM: usize = 0x14;
+
 pub struct RtcGoldfish {
     io_mem: IoMem,
 }
 
+impl RtcGoldfish {
+    /// Writes a timestamp to a pair of registers.
+    ///
+    /// The high half must be written first, since writing the low half commits the value.
+    fn write_timestamp(&self, low: usize, high: usize, time: SystemTime) {
+        let timestamp = to_timestamp_nanos(time);
+        self.io_mem
+            .write_once(high, &((timestamp >> 32) as u32))
+            .unwrap();
+        self.io_mem.write_once(low, &(timestamp as u32)).unwrap();
+    }
+}
+
+/// Converts the time to the number of nanoseconds since the Unix epoch.
+fn to_timestamp_nanos(time: SystemTime) -> u64 {
+    let timestamp = NaiveDate::from_ymd_opt(time.year as i32, time.month as u32, time.day as u32)
+        .and_then(|date| {
+            date.and_hms_nano_opt(
+                time.hour as u32,
+                time.minute as u32,
+                time.second as u32,
+                time.nanos as u32,
+            )
+        })
+        .and_then(|time| time.and_utc().timestamp_nanos_opt())
+        .expect("the time should be valid and representable");
+
+    // The time before the Unix epoch cannot be represented by the device.
+    timestamp.max(0) as u64
+}
+
 impl Driver for RtcGoldfish {
     fn try_new() -> Option<RtcGoldfish> {
         let chosen = DEVICE_TREE.get().unwrap().find_node("/soc/rtc").unwrap();
@@ -28,9 +66,6 @@ impl Driver for RtcGoldfish {
     }
 
     fn read_rtc(&self) -> SystemTime {
-        const TIME_LOW: usize = 0;
-        const TIME_HIGH: usize = 4;
-
         let mut last_time_high = self.io_mem.read_once(TIME_HIGH).unwrap();
         let timestamp = loop {
             let time_low: u32 = self.io_mem.read_once(TIME_LOW).unwrap();
@@ -55,4 +90,18 @@ impl Driver for RtcGoldfish {
             nanos: time.nanosecond() as u64,
         }
     }
+
+    fn write_rtc(&self, time: SystemTime) {
+        self.write_timestamp(TIME_LOW, TIME_HIGH, time);
+    }
+
+    fn set_alarm(&self, time: Option<SystemTime>) {
+        // We do not handle the RTC interrupt, so the alarm interrupt is always disabled.
+        self.io_mem.write_once(IRQ_ENABLED, &0u32).unwrap();
+
+        match time {
+            Some(time) => self.write_timestamp(ALARM_LOW, ALARM_HIGH, time),
+            None => self.io_mem.write_once(CLEAR_ALARM, &1u32).unwrap(),
+        }
+    }
 }
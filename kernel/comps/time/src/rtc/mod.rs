@@ -14,6 +14,15 @@ pub trait Driver {
 
     /// Reads RTC.
     fn read_rtc(&self) -> SystemTime;
+
+    /// Writes RTC.
+    fn write_rtc(&self, time: SystemTime);
+
+    /// Programs the alarm of the RTC, or clears it if `time` is `None`.
+    ///
+    /// Only the alarm registers are programmed. The alarm interrupt is left disabled, so the
+    /// caller is responsible for noticing that the alarm time has been reached.
+    fn set_alarm(&self, time: Option<SystemTime>);
 }
 
 macro_rules! declare_rtc_drivers {
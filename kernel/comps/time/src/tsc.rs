@@ -2,13 +2,16 @@
 
 //! This module provide a instance of `ClockSource` based on TSC.
 //!
+//! If kvmclock is available, the `ClockSource` is based on kvmclock instead, which is
+//! also derived from the TSC but maintained by the hypervisor.
+//!
 //! Use `init` to initialize this module.
 use alloc::sync::Arc;
 use core::sync::atomic::{AtomicU64, Ordering};
 
 use ostd::{
     arch::{read_tsc, timer::TIMER_FREQ, tsc_freq},
-    timer,
+    timer::{self, Jiffies},
 };
 use spin::Once;
 
@@ -31,6 +34,11 @@ pub(super) fn init() {
 
 fn init_clock() {
     CLOCK.call_once(|| {
+        #[cfg(target_arch = "x86_64")]
+        if let Some(clock) = crate::kvmclock::new_clocksource() {
+            return Arc::new(clock);
+        }
+
         Arc::new(ClockSource::new(
             tsc_freq(),
             MAX_DELAY_SECS,
@@ -53,18 +61,25 @@ pub(super) fn read_instant() -> Instant {
     clock.read_instant()
 }
 
+/// Read an `Instant` of tsc clocksource without the frequency adjustments.
+pub(super) fn read_raw_instant() -> Instant {
+    let clock = CLOCK.get().unwrap();
+    clock.read_raw_instant()
+}
+
 fn update_clocksource() {
     let clock = CLOCK.get().unwrap();
     clock.update();
 
     // Update vdso data.
     if let Some(update_fn) = VDSO_DATA_HIGH_RES_UPDATE_FN.get() {
-        let (last_instant, last_cycles) = clock.last_record();
-        update_fn(last_instant, last_cycles);
+        let last_record = clock.last_record();
+        update_fn(last_record.instant(), last_record.cycles());
     }
 }
 
-static TSC_UPDATE_COUNTER: AtomicU64 = AtomicU64::new(1);
+/// The jiffies at which the clocksource will be updated next time.
+static NEXT_UPDATE_JIFFIES: AtomicU64 = AtomicU64::new(0);
 
 fn init_timer() {
     // The `max_delay_secs` should be set as `clock.max_delay_secs() >> 1` or something much smaller than `max_delay_secs`.
@@ -74,12 +89,28 @@ fn init_timer() {
     // If without KVM, the delayed time will be larger.
     // TODO: This is a temporary solution, and should be modified in the future.
     let max_delay_secs = CLOCK.get().unwrap().max_delay_secs() >> 1;
-    let delay_counts = TIMER_FREQ * max_delay_secs;
-
+    let delay_jiffies = TIMER_FREQ * max_delay_secs;
+    NEXT_UPDATE_JIFFIES.store(
+        Jiffies::elapsed().as_u64() + delay_jiffies,
+        Ordering::Relaxed,
+    );
+
+    // The update is driven by the elapsed jiffies instead of the number of ticks, since ticks
+    // are skipped while the CPU is idle.
     let update = move || {
-        let counter = TSC_UPDATE_COUNTER.fetch_add(1, Ordering::Relaxed);
-
-        if counter % delay_counts == 0 {
+        let now = Jiffies::elapsed().as_u64();
+        let next_update = NEXT_UPDATE_JIFFIES.load(Ordering::Relaxed);
+
+        if now >= next_update
+            && NEXT_UPDATE_JIFFIES
+                .compare_exchange(
+                    next_update,
+                    now + delay_jiffies,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
             update_clocksource();
         }
     };
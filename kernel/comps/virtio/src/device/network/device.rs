@@ -1,8 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{
-    boxed::Box, collections::linked_list::LinkedList, string::ToString, sync::Arc, vec::Vec,
-};
+use alloc::{boxed::Box, collections::linked_list::LinkedList, sync::Arc, vec::Vec};
 use core::{fmt::Debug, mem::size_of};
 
 use aster_bigtcp::device::{Checksum, DeviceCapabilities, Medium};
@@ -145,10 +143,9 @@ impl NetworkDevice {
 
         device.transport.finish_init();
 
-        aster_network::register_device(
-            super::DEVICE_NAME.to_string(),
-            Arc::new(SpinLock::new(device)),
-        );
+        // There may be multiple virtio-net devices, so each of them gets a unique name.
+        let name = aster_network::alloc_device_name(super::DEVICE_NAME);
+        aster_network::register_device(name, Arc::new(SpinLock::new(device)));
         Ok(())
     }
 
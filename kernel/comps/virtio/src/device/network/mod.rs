@@ -4,4 +4,7 @@ pub mod config;
 pub mod device;
 pub mod header;
 
+/// The prefix of the names of virtio-net devices.
+///
+/// The devices are named `Virtio-Net0`, `Virtio-Net1`, and so on.
 pub const DEVICE_NAME: &str = "Virtio-Net";
@@ -9,6 +9,23 @@ pub enum BindError {
     InUse,
 }
 
+/// An error indicating that the route table of an iface is full.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RouteTableFull;
+
+/// An error returned by [`Iface::send_frame`].
+///
+/// [`Iface::send_frame`]: crate::iface::Iface::send_frame
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SendFrameError {
+    /// The iface does not have a link layer.
+    NotSupported,
+    /// The frame is larger than the maximum transmission unit.
+    TooLarge,
+    /// The device cannot queue more frames for now.
+    Exhausted,
+}
+
 pub mod tcp {
     /// An error returned by [`TcpListener::new_listen`].
     ///
@@ -112,3 +129,29 @@ pub mod udp {
         }
     }
 }
+
+pub mod raw {
+    /// An error returned by [`RawIpSocket::send`] and [`RawIpSocket::send_hdrincl`].
+    ///
+    /// [`RawIpSocket::send`]: crate::socket::RawIpSocket::send
+    /// [`RawIpSocket::send_hdrincl`]: crate::socket::RawIpSocket::send_hdrincl
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum SendError {
+        /// The socket has no local address to send from.
+        Unaddressable,
+        BufferFull,
+        /// The packet is too large.
+        TooLarge,
+        /// The packet or its header is malformed.
+        InvalidPacket,
+    }
+
+    /// An error returned by [`RawIpSocket::recv`].
+    ///
+    /// [`RawIpSocket::recv`]: crate::socket::RawIpSocket::recv
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum RecvError {
+        /// The receive buffer is empty.
+        Exhausted,
+    }
+}
@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::{iface::ScheduleNextPoll, socket::SocketEventObserver};
+use crate::{
+    iface::{PacketTap, ScheduleNextPoll},
+    socket::SocketEventObserver,
+};
 
 /// Extension to be implemented by users of this crate.
 ///
@@ -19,4 +22,10 @@ pub trait Ext {
 
     /// The type for UDP sockets to observe events.
     type UdpEventObserver: SocketEventObserver;
+
+    /// The type for raw IP sockets to observe events.
+    type RawEventObserver: SocketEventObserver;
+
+    /// The type for packet sockets to capture link-layer frames.
+    type PacketTap: PacketTap + ?Sized;
 }
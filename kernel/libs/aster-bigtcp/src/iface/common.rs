@@ -6,7 +6,7 @@ use alloc::{
     sync::Arc,
     vec::Vec,
 };
-use core::sync::atomic::{AtomicU32, Ordering};
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
 
 use aster_softirq::BottomHalfDisabled;
 use bitflags::bitflags;
@@ -15,20 +15,24 @@ use ostd::sync::{SpinLock, SpinLockGuard};
 use smoltcp::{
     iface::{packet::Packet, Context},
     phy::Device,
-    wire::{IpAddress, IpEndpoint, Ipv4Address, Ipv4Packet},
+    wire::{
+        EthernetAddress, HardwareAddress, IpAddress, IpEndpoint, Ipv4Address, Ipv4Cidr, Ipv4Packet,
+    },
 };
 
 use super::{
     poll::{FnHelper, PollContext, SocketTableAction},
     poll_iface::PollableIface,
     port::BindPortConfig,
+    stats::{InterfaceCounters, InterfaceStats},
+    tap::PacketType,
     time::get_network_timestamp,
     Iface,
 };
 use crate::{
-    errors::BindError,
+    errors::{BindError, RouteTableFull},
     ext::Ext,
-    socket::{TcpListenerBg, UdpSocketBg},
+    socket::{RawIpSocketBg, TcpListenerBg, TcpSocketInfo, UdpSocketBg, UdpSocketInfo},
     socket_table::SocketTable,
 };
 
@@ -36,11 +40,16 @@ pub struct IfaceCommon<E: Ext> {
     index: u32,
     name: String,
     type_: InterfaceType,
-    flags: InterfaceFlags,
+    flags: AtomicU32,
+    mtu: AtomicUsize,
+    max_mtu: usize,
+    promiscuity: AtomicUsize,
+    counters: InterfaceCounters,
 
     interface: SpinLock<PollableIface<E>, BottomHalfDisabled>,
     used_ports: SpinLock<BTreeMap<u16, usize>, BottomHalfDisabled>,
     sockets: SpinLock<SocketTable<E>, BottomHalfDisabled>,
+    packet_taps: SpinLock<Vec<Arc<E::PacketTap>>, BottomHalfDisabled>,
     sched_poll: E::ScheduleNextPoll,
 }
 
@@ -49,6 +58,7 @@ impl<E: Ext> IfaceCommon<E> {
         name: String,
         type_: InterfaceType,
         flags: InterfaceFlags,
+        max_mtu: usize,
         interface: smoltcp::iface::Interface,
         sched_poll: E::ScheduleNextPoll,
     ) -> Self {
@@ -58,10 +68,15 @@ impl<E: Ext> IfaceCommon<E> {
             index,
             name,
             type_,
-            flags,
+            flags: AtomicU32::new(flags.bits()),
+            mtu: AtomicUsize::new(max_mtu),
+            max_mtu,
+            promiscuity: AtomicUsize::new(0),
+            counters: InterfaceCounters::new(),
             interface: SpinLock::new(PollableIface::new(interface)),
             used_ports: SpinLock::new(BTreeMap::new()),
             sockets: SpinLock::new(SocketTable::new()),
+            packet_taps: SpinLock::new(Vec::new()),
             sched_poll,
         }
     }
@@ -79,7 +94,69 @@ impl<E: Ext> IfaceCommon<E> {
     }
 
     pub(super) fn flags(&self) -> InterfaceFlags {
-        self.flags
+        let mut flags = InterfaceFlags::from_bits_truncate(self.flags.load(Ordering::Relaxed));
+        if self.promiscuity.load(Ordering::Relaxed) > 0 {
+            flags |= InterfaceFlags::PROMISC;
+        }
+        flags
+    }
+
+    pub(super) fn set_flags(&self, flags: InterfaceFlags) {
+        // The `PROMISC` flag is derived from the promiscuity count and is never stored.
+        let flags = flags - InterfaceFlags::PROMISC;
+        self.flags.store(flags.bits(), Ordering::Relaxed);
+    }
+
+    pub(super) fn mtu(&self) -> usize {
+        self.mtu.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn max_mtu(&self) -> usize {
+        self.max_mtu
+    }
+
+    pub(super) fn set_mtu(&self, mtu: usize) {
+        debug_assert!(mtu <= self.max_mtu);
+        self.mtu.store(mtu, Ordering::Relaxed);
+    }
+
+    pub(super) fn is_promiscuous(&self) -> bool {
+        self.flags().contains(InterfaceFlags::PROMISC)
+    }
+
+    pub(super) fn inc_promiscuity(&self) {
+        self.promiscuity.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn dec_promiscuity(&self) {
+        let old_promiscuity = self.promiscuity.fetch_sub(1, Ordering::Relaxed);
+        debug_assert!(old_promiscuity > 0);
+    }
+
+    pub(super) fn stats(&self) -> InterfaceStats {
+        self.counters.snapshot()
+    }
+
+    /// Records a frame (or an IP packet if the iface has no link layer) received by the device.
+    pub(super) fn count_rx(&self, len: usize) {
+        self.counters.on_rx(len);
+    }
+
+    /// Records a multicast frame received by the device.
+    pub(super) fn count_rx_multicast(&self) {
+        self.counters.on_rx_multicast();
+    }
+
+    /// Records a frame (or an IP packet if the iface has no link layer) transmitted by the device.
+    pub(super) fn count_tx(&self, len: usize) {
+        self.counters.on_tx(len);
+    }
+
+    pub(super) fn hw_addr(&self) -> Option<EthernetAddress> {
+        match self.interface.lock().hardware_addr() {
+            HardwareAddress::Ethernet(ether_addr) => Some(ether_addr),
+            _ => None,
+        }
     }
 
     pub(super) fn ipv4_addr(&self) -> Option<Ipv4Address> {
@@ -90,6 +167,22 @@ impl<E: Ext> IfaceCommon<E> {
         self.interface.lock().prefix_len()
     }
 
+    pub(super) fn set_ipv4_cidr(&self, ip_cidr: Option<Ipv4Cidr>) {
+        self.interface.lock().set_ipv4_cidr(ip_cidr);
+    }
+
+    pub(super) fn add_ipv4_route(
+        &self,
+        cidr: Ipv4Cidr,
+        gateway: Ipv4Address,
+    ) -> core::result::Result<(), RouteTableFull> {
+        self.interface.lock().add_ipv4_route(cidr, gateway)
+    }
+
+    pub(super) fn remove_ipv4_route(&self, cidr: Ipv4Cidr, gateway: Ipv4Address) {
+        self.interface.lock().remove_ipv4_route(cidr, gateway);
+    }
+
     pub(super) fn sched_poll(&self) -> &E::ScheduleNextPoll {
         &self.sched_poll
     }
@@ -100,7 +193,7 @@ impl<E: Ext> IfaceCommon<E> {
 // FIXME: This allocator is specific to each network namespace.
 pub static INTERFACE_INDEX_ALLOCATOR: AtomicU32 = AtomicU32::new(1);
 
-// Lock order: `interface` -> `sockets`
+// Lock order: `interface` -> `sockets` -> `packet_taps`
 impl<E: Ext> IfaceCommon<E> {
     /// Acquires the lock to the interface.
     pub(crate) fn interface(&self) -> SpinLockGuard<'_, PollableIface<E>, BottomHalfDisabled> {
@@ -126,6 +219,11 @@ impl<E: Ext> IfaceCommon<E> {
         Ok(BoundPort { iface, port })
     }
 
+    pub(super) fn bind_raw(&self, iface: Arc<dyn Iface<E>>) -> BoundPort<E> {
+        // Port zero is never allocated, so releasing it has no effect.
+        BoundPort { iface, port: 0 }
+    }
+
     /// Allocates an unused ephemeral port.
     ///
     /// We follow the port range that many Linux kernels use by default, which is 32768-60999.
@@ -179,12 +277,42 @@ impl<E: Ext> IfaceCommon<E> {
     }
 }
 
+impl<E: Ext> IfaceCommon<E> {
+    pub(super) fn tcp_socket_infos(&self) -> Vec<TcpSocketInfo> {
+        let sockets = self.sockets.lock();
+
+        let listeners = sockets.listener_iter().map(|listener| listener.info());
+        let connections = sockets
+            .connection_iter()
+            .map(|connection| connection.info());
+        listeners.chain(connections).collect()
+    }
+
+    pub(super) fn udp_socket_infos(&self) -> Vec<UdpSocketInfo> {
+        // Lock order: `interface` -> `sockets`
+        let Some(iface_addr) = self.ipv4_addr() else {
+            return Vec::new();
+        };
+
+        let sockets = self.sockets.lock();
+        sockets
+            .udp_socket_iter()
+            .map(|socket| socket.info(IpAddress::Ipv4(iface_addr)))
+            .collect()
+    }
+}
+
 impl<E: Ext> IfaceCommon<E> {
     pub(crate) fn register_udp_socket(&self, socket: Arc<UdpSocketBg<E>>) {
         let mut sockets = self.sockets.lock();
         sockets.insert_udp_socket(socket);
     }
 
+    pub(crate) fn register_raw_socket(&self, socket: Arc<RawIpSocketBg<E>>) {
+        let mut sockets = self.sockets.lock();
+        sockets.insert_raw_socket(socket);
+    }
+
     pub(crate) fn remove_tcp_listener(&self, socket: &Arc<TcpListenerBg<E>>) {
         let mut sockets = self.sockets.lock();
         let removed = sockets.remove_listener(socket.listener_key());
@@ -196,6 +324,35 @@ impl<E: Ext> IfaceCommon<E> {
         let removed = sockets.remove_udp_socket(socket);
         debug_assert!(removed.is_some());
     }
+
+    pub(crate) fn remove_raw_socket(&self, socket: &Arc<RawIpSocketBg<E>>) {
+        let mut sockets = self.sockets.lock();
+        let removed = sockets.remove_raw_socket(socket);
+        debug_assert!(removed.is_some());
+    }
+}
+
+impl<E: Ext> IfaceCommon<E> {
+    pub(super) fn register_packet_tap(&self, tap: Arc<E::PacketTap>) {
+        self.packet_taps.lock().push(tap);
+    }
+
+    pub(super) fn remove_packet_tap(&self, tap: &Arc<E::PacketTap>) {
+        let mut packet_taps = self.packet_taps.lock();
+        let pos = packet_taps.iter().position(|t| Arc::ptr_eq(t, tap));
+        debug_assert!(pos.is_some());
+        if let Some(pos) = pos {
+            packet_taps.swap_remove(pos);
+        }
+    }
+
+    /// Delivers a link-layer frame to all the registered packet taps.
+    pub(super) fn capture_frame(&self, frame: &[u8], pkt_type: PacketType) {
+        let packet_taps = self.packet_taps.lock();
+        for tap in packet_taps.iter() {
+            tap.capture(self.index, frame, pkt_type);
+        }
+    }
 }
 
 impl<E: Ext> IfaceCommon<E> {
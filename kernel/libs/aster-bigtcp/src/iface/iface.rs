@@ -1,11 +1,15 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec::Vec};
 
-use smoltcp::wire::Ipv4Address;
+use smoltcp::wire::{EthernetAddress, Ipv4Address, Ipv4Cidr};
 
-use super::{port::BindPortConfig, BoundPort, InterfaceFlags, InterfaceType};
-use crate::{errors::BindError, ext::Ext};
+use super::{port::BindPortConfig, BoundPort, InterfaceFlags, InterfaceStats, InterfaceType};
+use crate::{
+    errors::{BindError, RouteTableFull, SendFrameError},
+    ext::Ext,
+    socket::{TcpSocketInfo, UdpSocketInfo},
+};
 
 /// A network interface.
 ///
@@ -17,8 +21,12 @@ pub trait Iface<E>: internal::IfaceInternal<E> + Send + Sync {
     /// Transmits or receives packets queued in the iface, and updates socket status accordingly.
     fn poll(&self);
 
-    /// Returns the maximum transmission unit.
-    fn mtu(&self) -> usize;
+    /// Transmits a link-layer frame through the iface.
+    ///
+    /// The frame must contain the link-layer header. It bypasses the network stack, so it can be
+    /// sent even before the iface has an IP address. The frame is also delivered to the packet
+    /// taps.
+    fn send_frame(&self, frame: &[u8]) -> core::result::Result<(), SendFrameError>;
 }
 
 impl<E: Ext> dyn Iface<E> {
@@ -41,6 +49,15 @@ impl<E: Ext> dyn Iface<E> {
         common.bind(self.clone(), config)
     }
 
+    /// Binds a raw IP socket to the iface.
+    ///
+    /// Raw IP sockets do not have ports, so no ports will be reserved. The port of the returned
+    /// [`BoundPort`] is always zero.
+    pub fn bind_raw(self: &Arc<Self>) -> BoundPort<E> {
+        let common = self.common();
+        common.bind_raw(self.clone())
+    }
+
     /// Returns the interface index.
     pub fn index(&self) -> u32 {
         self.common().index()
@@ -63,6 +80,85 @@ impl<E: Ext> dyn Iface<E> {
         self.common().flags()
     }
 
+    /// Sets the interface flags.
+    pub fn set_flags(&self, flags: InterfaceFlags) {
+        self.common().set_flags(flags);
+    }
+
+    /// Returns the maximum transmission unit.
+    pub fn mtu(&self) -> usize {
+        self.common().mtu()
+    }
+
+    /// Returns the largest maximum transmission unit supported by the underlying device.
+    pub fn max_mtu(&self) -> usize {
+        self.common().max_mtu()
+    }
+
+    /// Sets the maximum transmission unit.
+    ///
+    /// The MTU must not exceed [`Self::max_mtu`].
+    ///
+    /// FIXME: The MTU is only recorded and reported. Outgoing packets are still limited by the
+    /// MTU of the underlying device.
+    pub fn set_mtu(&self, mtu: usize) {
+        self.common().set_mtu(mtu);
+    }
+
+    /// Returns whether the iface is in promiscuous mode.
+    ///
+    /// In promiscuous mode, the frames destined to other hosts are also delivered to the packet
+    /// taps. They are still ignored by the network stack.
+    pub fn is_promiscuous(&self) -> bool {
+        self.common().is_promiscuous()
+    }
+
+    /// Increments the promiscuity counter of the iface.
+    ///
+    /// The iface is in promiscuous mode as long as the counter is positive. Each call to this
+    /// method should be paired with a call to [`Self::dec_promiscuity`].
+    pub fn inc_promiscuity(&self) {
+        self.common().inc_promiscuity();
+    }
+
+    /// Decrements the promiscuity counter of the iface.
+    pub fn dec_promiscuity(&self) {
+        self.common().dec_promiscuity();
+    }
+
+    /// Returns the statistics of the iface.
+    pub fn stats(&self) -> InterfaceStats {
+        self.common().stats()
+    }
+
+    /// Returns snapshots of the TCP sockets (both listeners and connections) bound to the iface.
+    pub fn tcp_socket_infos(&self) -> Vec<TcpSocketInfo> {
+        self.common().tcp_socket_infos()
+    }
+
+    /// Returns snapshots of the UDP sockets bound to the iface.
+    pub fn udp_socket_infos(&self) -> Vec<UdpSocketInfo> {
+        self.common().udp_socket_infos()
+    }
+
+    /// Gets the hardware address of the iface, if it is an Ethernet iface.
+    pub fn hw_addr(&self) -> Option<EthernetAddress> {
+        self.common().hw_addr()
+    }
+
+    /// Registers a packet tap to capture link-layer frames received or transmitted by the iface.
+    ///
+    /// FIXME: Ifaces that do not have a link layer (e.g., the loopback iface) never deliver frames
+    /// to packet taps.
+    pub fn register_packet_tap(&self, tap: Arc<E::PacketTap>) {
+        self.common().register_packet_tap(tap);
+    }
+
+    /// Removes a packet tap registered by [`Self::register_packet_tap`].
+    pub fn remove_packet_tap(&self, tap: &Arc<E::PacketTap>) {
+        self.common().remove_packet_tap(tap);
+    }
+
     /// Gets the IPv4 address of the iface, if any.
     ///
     /// FIXME: One iface may have multiple IPv4 addresses.
@@ -78,6 +174,30 @@ impl<E: Ext> dyn Iface<E> {
         self.common().prefix_len()
     }
 
+    /// Sets the IPv4 address and the prefix length of the iface.
+    ///
+    /// If `ip_cidr` is `None`, the IPv4 address of the iface will be removed.
+    pub fn set_ipv4_cidr(&self, ip_cidr: Option<Ipv4Cidr>) {
+        self.common().set_ipv4_cidr(ip_cidr);
+    }
+
+    /// Adds a route that forwards packets destined to `cidr` through `gateway`.
+    ///
+    /// Packets destined to the subnet of the iface are always delivered directly, so the routes
+    /// only matter for packets destined to other networks.
+    pub fn add_ipv4_route(
+        &self,
+        cidr: Ipv4Cidr,
+        gateway: Ipv4Address,
+    ) -> core::result::Result<(), RouteTableFull> {
+        self.common().add_ipv4_route(cidr, gateway)
+    }
+
+    /// Removes a route added by [`Self::add_ipv4_route`].
+    pub fn remove_ipv4_route(&self, cidr: Ipv4Cidr, gateway: Ipv4Address) {
+        self.common().remove_ipv4_route(cidr, gateway);
+    }
+
     /// Returns a reference to the associated [`ScheduleNextPoll`].
     pub fn sched_poll(&self) -> &E::ScheduleNextPoll {
         self.common().sched_poll()
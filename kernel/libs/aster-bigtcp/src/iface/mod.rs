@@ -8,6 +8,8 @@ mod poll;
 mod poll_iface;
 mod port;
 mod sched;
+mod stats;
+mod tap;
 mod time;
 
 pub use common::{BoundPort, InterfaceFlags, InterfaceType};
@@ -16,3 +18,5 @@ pub use phy::{EtherIface, IpIface};
 pub(crate) use poll_iface::{PollKey, PollableIfaceMut};
 pub use port::BindPortConfig;
 pub use sched::ScheduleNextPoll;
+pub use stats::InterfaceStats;
+pub use tap::{PacketTap, PacketType};
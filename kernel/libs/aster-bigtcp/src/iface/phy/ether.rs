@@ -15,13 +15,15 @@ use smoltcp::{
 
 use crate::{
     device::{NotifyDevice, WithDevice},
+    errors::SendFrameError,
     ext::Ext,
     iface::{
         common::{IfaceCommon, InterfaceType},
         iface::internal::IfaceInternal,
         time::get_network_timestamp,
-        Iface, InterfaceFlags, ScheduleNextPoll,
+        Iface, InterfaceFlags, PacketType, ScheduleNextPoll,
     },
+    stats::IP_COUNTERS,
 };
 
 pub struct EtherIface<D, E: Ext> {
@@ -35,29 +37,33 @@ impl<D: WithDevice, E: Ext> EtherIface<D, E> {
     pub fn new(
         driver: D,
         ether_addr: EthernetAddress,
-        ip_cidr: Ipv4Cidr,
-        gateway: Ipv4Address,
+        ip_cidr: Option<Ipv4Cidr>,
         name: String,
         sched_poll: E::ScheduleNextPoll,
         flags: InterfaceFlags,
     ) -> Arc<Self> {
-        let interface = driver.with(|device| {
+        let (interface, mtu) = driver.with(|device| {
             let config = Config::new(wire::HardwareAddress::Ethernet(ether_addr));
             let now = get_network_timestamp();
 
             let mut interface = smoltcp::iface::Interface::new(config, device, now);
-            interface.update_ip_addrs(|ip_addrs| {
-                debug_assert!(ip_addrs.is_empty());
-                ip_addrs.push(wire::IpCidr::Ipv4(ip_cidr)).unwrap();
-            });
-            interface
-                .routes_mut()
-                .add_default_ipv4_route(gateway)
-                .unwrap();
-            interface
+            if let Some(ip_cidr) = ip_cidr {
+                interface.update_ip_addrs(|ip_addrs| {
+                    debug_assert!(ip_addrs.is_empty());
+                    ip_addrs.push(wire::IpCidr::Ipv4(ip_cidr)).unwrap();
+                });
+            }
+            (interface, device.capabilities().max_transmission_unit)
         });
 
-        let common = IfaceCommon::new(name, InterfaceType::ETHER, flags, interface, sched_poll);
+        let common = IfaceCommon::new(
+            name,
+            InterfaceType::ETHER,
+            flags,
+            mtu,
+            interface,
+            sched_poll,
+        );
 
         Arc::new(Self {
             driver,
@@ -90,9 +96,24 @@ where
         });
     }
 
-    fn mtu(&self) -> usize {
-        self.driver
-            .with(|device| device.capabilities().max_transmission_unit)
+    fn send_frame(&self, frame: &[u8]) -> core::result::Result<(), SendFrameError> {
+        // The MTU of an Ethernet device includes the Ethernet header.
+        if frame.len() > self.common.max_mtu() {
+            return Err(SendFrameError::TooLarge);
+        }
+
+        self.driver.with(|device| {
+            let tx_token = device
+                .transmit(get_network_timestamp())
+                .ok_or(SendFrameError::Exhausted)?;
+            tx_token.consume(frame.len(), |buffer| {
+                buffer.copy_from_slice(frame);
+                self.common.count_tx(buffer.len());
+                self.common.capture_frame(buffer, PacketType::Outgoing);
+            });
+            device.notify_poll_end();
+            Ok(())
+        })
     }
 }
 
@@ -103,10 +124,12 @@ impl<D, E: Ext> EtherIface<D, E> {
         iface_cx: &mut Context,
         tx_token: T,
     ) -> Option<(Ipv4Packet<&'pkt [u8]>, T)> {
+        self.common.count_rx(data.len());
+
         match self.parse_ip_or_process_arp(data, iface_cx) {
             Ok(pkt) => Some((pkt, tx_token)),
             Err(Some(arp)) => {
-                Self::emit_arp(&arp, tx_token);
+                self.emit_arp(&arp, tx_token);
                 None
             }
             Err(None) => None,
@@ -122,8 +145,28 @@ impl<D, E: Ext> EtherIface<D, E> {
         let frame = EthernetFrame::new_checked(data).map_err(|_| None)?;
         let repr = EthernetRepr::parse(&frame).map_err(|_| None)?;
 
+        let pkt_type = if repr.dst_addr == self.ether_addr {
+            PacketType::Host
+        } else if repr.dst_addr.is_broadcast() {
+            PacketType::Broadcast
+        } else if repr.dst_addr.is_multicast() {
+            PacketType::Multicast
+        } else {
+            PacketType::OtherHost
+        };
+
+        if pkt_type == PacketType::Multicast {
+            self.common.count_rx_multicast();
+        }
+
+        // Let packet taps see the Ethernet frame. Frames sent to other hosts are only visible in
+        // promiscuous mode.
+        if pkt_type != PacketType::OtherHost || self.common.is_promiscuous() {
+            self.common.capture_frame(data, pkt_type);
+        }
+
         // Ignore the Ethernet frame if it is not sent to us.
-        if !repr.dst_addr.is_broadcast() && repr.dst_addr != self.ether_addr {
+        if !matches!(pkt_type, PacketType::Host | PacketType::Broadcast) {
             return Err(None);
         }
 
@@ -198,9 +241,11 @@ impl<D, E: Ext> EtherIface<D, E> {
     }
 
     fn dispatch<T: TxToken>(&self, pkt: &Packet, iface_cx: &mut Context, tx_token: T) {
+        IP_COUNTERS.out_requests.inc();
+
         match self.resolve_ether_or_generate_arp(pkt, iface_cx) {
-            Ok(ether) => Self::emit_ip(&ether, pkt, &iface_cx.caps, tx_token),
-            Err(Some(arp)) => Self::emit_arp(&arp, tx_token),
+            Ok(ether) => self.emit_ip(&ether, pkt, &iface_cx.caps, tx_token),
+            Err(Some(arp)) => self.emit_arp(&arp, tx_token),
             Err(None) => (),
         }
     }
@@ -243,6 +288,7 @@ impl<D, E: Ext> EtherIface<D, E> {
 
     /// Consumes the token and emits an IP packet.
     fn emit_ip<T: TxToken>(
+        &self,
         ether_repr: &EthernetRepr,
         ip_pkt: &Packet,
         caps: &DeviceCapabilities,
@@ -251,7 +297,7 @@ impl<D, E: Ext> EtherIface<D, E> {
         tx_token.consume(
             ether_repr.buffer_len() + ip_pkt.ip_repr().buffer_len(),
             |buffer| {
-                let mut frame = EthernetFrame::new_unchecked(buffer);
+                let mut frame = EthernetFrame::new_unchecked(&mut *buffer);
                 ether_repr.emit(&mut frame);
 
                 let ip_repr = ip_pkt.ip_repr();
@@ -261,12 +307,15 @@ impl<D, E: Ext> EtherIface<D, E> {
                     &mut frame.payload_mut()[ip_repr.header_len()..],
                     caps,
                 );
+
+                self.common.count_tx(buffer.len());
+                self.common.capture_frame(buffer, PacketType::Outgoing);
             },
         );
     }
 
     /// Consumes the token and emits an ARP packet.
-    fn emit_arp<T: TxToken>(arp_repr: &ArpRepr, tx_token: T) {
+    fn emit_arp<T: TxToken>(&self, arp_repr: &ArpRepr, tx_token: T) {
         let ether_repr = match arp_repr {
             ArpRepr::EthernetIpv4 {
                 source_hardware_addr,
@@ -281,11 +330,14 @@ impl<D, E: Ext> EtherIface<D, E> {
         };
 
         tx_token.consume(ether_repr.buffer_len() + arp_repr.buffer_len(), |buffer| {
-            let mut frame = EthernetFrame::new_unchecked(buffer);
+            let mut frame = EthernetFrame::new_unchecked(&mut *buffer);
             ether_repr.emit(&mut frame);
 
             let mut pkt = ArpPacket::new_unchecked(frame.payload_mut());
             arp_repr.emit(&mut pkt);
+
+            self.common.count_tx(buffer.len());
+            self.common.capture_frame(buffer, PacketType::Outgoing);
         });
     }
 }
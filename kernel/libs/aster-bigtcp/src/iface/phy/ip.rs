@@ -10,6 +10,7 @@ use smoltcp::{
 
 use crate::{
     device::WithDevice,
+    errors::SendFrameError,
     ext::Ext,
     iface::{
         common::{IfaceCommon, InterfaceFlags, InterfaceType},
@@ -17,6 +18,7 @@ use crate::{
         time::get_network_timestamp,
         Iface, ScheduleNextPoll,
     },
+    stats::IP_COUNTERS,
 };
 
 pub struct IpIface<D, E: Ext> {
@@ -33,7 +35,7 @@ impl<D: WithDevice, E: Ext> IpIface<D, E> {
         type_: InterfaceType,
         flags: InterfaceFlags,
     ) -> Arc<Self> {
-        let interface = driver.with(|device| {
+        let (interface, mtu) = driver.with(|device| {
             let config = Config::new(smoltcp::wire::HardwareAddress::Ip);
             let now = get_network_timestamp();
 
@@ -42,10 +44,10 @@ impl<D: WithDevice, E: Ext> IpIface<D, E> {
                 debug_assert!(ip_addrs.is_empty());
                 ip_addrs.push(wire::IpCidr::Ipv4(ip_cidr)).unwrap();
             });
-            interface
+            (interface, device.capabilities().max_transmission_unit)
         });
 
-        let common = IfaceCommon::new(name, type_, flags, interface, sched_poll);
+        let common = IfaceCommon::new(name, type_, flags, mtu, interface, sched_poll);
 
         Arc::new(Self { driver, common })
     }
@@ -62,9 +64,15 @@ impl<D: WithDevice + 'static, E: Ext> Iface<E> for IpIface<D, E> {
         self.driver.with(|device| {
             let next_poll = self.common.poll(
                 device,
-                |data, _iface_cx, tx_token| Some((Ipv4Packet::new_checked(data).ok()?, tx_token)),
+                |data, _iface_cx, tx_token| {
+                    self.common.count_rx(data.len());
+                    Some((Ipv4Packet::new_checked(data).ok()?, tx_token))
+                },
                 |pkt, iface_cx, tx_token| {
+                    IP_COUNTERS.out_requests.inc();
+
                     let ip_repr = pkt.ip_repr();
+                    self.common.count_tx(ip_repr.buffer_len());
                     tx_token.consume(ip_repr.buffer_len(), |buffer| {
                         ip_repr.emit(&mut buffer[..], &iface_cx.checksum_caps());
                         pkt.emit_payload(
@@ -79,8 +87,7 @@ impl<D: WithDevice + 'static, E: Ext> Iface<E> for IpIface<D, E> {
         });
     }
 
-    fn mtu(&self) -> usize {
-        self.driver
-            .with(|device| device.capabilities().max_transmission_unit)
+    fn send_frame(&self, _frame: &[u8]) -> core::result::Result<(), SendFrameError> {
+        Err(SendFrameError::NotSupported)
     }
 }
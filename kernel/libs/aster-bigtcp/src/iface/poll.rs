@@ -9,8 +9,8 @@ use smoltcp::{
     },
     phy::{ChecksumCapabilities, Device, RxToken, TxToken},
     wire::{
-        Icmpv4DstUnreachable, Icmpv4Repr, IpAddress, IpProtocol, IpRepr, Ipv4Address, Ipv4Packet,
-        Ipv4Repr, TcpControl, TcpPacket, TcpRepr, UdpPacket, UdpRepr, IPV4_HEADER_LEN,
+        Icmpv4DstUnreachable, Icmpv4Packet, Icmpv4Repr, IpAddress, IpProtocol, IpRepr, Ipv4Address,
+        Ipv4Packet, Ipv4Repr, TcpControl, TcpPacket, TcpRepr, UdpPacket, UdpRepr, IPV4_HEADER_LEN,
         IPV4_MIN_MTU,
     },
 };
@@ -20,6 +20,7 @@ use crate::{
     ext::Ext,
     socket::{TcpConnectionBg, TcpProcessResult},
     socket_table::{ConnectionKey, ListenerKey, SocketTable},
+    stats::{ICMP_COUNTERS, IP_COUNTERS, TCP_COUNTERS, UDP_COUNTERS},
 };
 
 pub(super) struct PollContext<'a, E: Ext> {
@@ -92,10 +93,16 @@ impl<E: Ext> PollContext<'_, E> {
         &mut self,
         pkt: Ipv4Packet<&'pkt [u8]>,
     ) -> Option<Packet<'pkt>> {
+        IP_COUNTERS.in_receives.inc();
+
         // Parse the IP header. Ignore the packet if the header is ill-formed.
-        let repr = Ipv4Repr::parse(&pkt, &self.iface.context().checksum_caps()).ok()?;
+        let Ok(repr) = Ipv4Repr::parse(&pkt, &self.iface.context().checksum_caps()) else {
+            IP_COUNTERS.in_hdr_errors.inc();
+            return None;
+        };
 
         if !repr.dst_addr.is_broadcast() && !self.is_unicast_local(IpAddress::Ipv4(repr.dst_addr)) {
+            IP_COUNTERS.in_addr_errors.inc();
             return self.generate_icmp_unreachable(
                 &IpRepr::Ipv4(repr),
                 pkt.payload(),
@@ -103,14 +110,100 @@ impl<E: Ext> PollContext<'_, E> {
             );
         }
 
+        // Raw IP sockets receive a copy of the packet before the packet is handled by the
+        // protocol.
+        self.process_raw(&repr, &pkt.as_ref()[..pkt.total_len() as usize]);
+
         let checksum_caps = self.iface.context().checksum_caps();
         match repr.next_header {
             IpProtocol::Tcp => {
+                IP_COUNTERS.in_delivers.inc();
                 self.parse_and_process_tcp(&IpRepr::Ipv4(repr), pkt.payload(), &checksum_caps)
             }
             IpProtocol::Udp => {
+                IP_COUNTERS.in_delivers.inc();
                 self.parse_and_process_udp(&IpRepr::Ipv4(repr), pkt.payload(), &checksum_caps)
             }
+            IpProtocol::Icmp => {
+                IP_COUNTERS.in_delivers.inc();
+                self.parse_and_process_icmp(&repr, pkt.payload(), &checksum_caps)
+            }
+            _ => {
+                IP_COUNTERS.in_unknown_protos.inc();
+                None
+            }
+        }
+    }
+
+    fn process_raw(&self, ip_repr: &Ipv4Repr, ip_packet: &[u8]) {
+        for socket in self.sockets.raw_socket_iter() {
+            socket.process(ip_repr, ip_packet);
+        }
+    }
+
+    fn parse_and_process_icmp<'pkt>(
+        &mut self,
+        ip_repr: &Ipv4Repr,
+        ip_payload: &'pkt [u8],
+        checksum_caps: &ChecksumCapabilities,
+    ) -> Option<Packet<'pkt>> {
+        // Parse the ICMP header. Ignore the packet if the header is ill-formed.
+        let Some(icmp_repr) = Icmpv4Packet::new_checked(ip_payload)
+            .and_then(|icmp_pkt| Icmpv4Repr::parse(&icmp_pkt, checksum_caps))
+            .ok()
+        else {
+            ICMP_COUNTERS.in_errors.inc();
+            return None;
+        };
+        ICMP_COUNTERS.in_msgs.inc();
+
+        match icmp_repr {
+            Icmpv4Repr::EchoRequest {
+                ident,
+                seq_no,
+                data,
+            } => {
+                ICMP_COUNTERS.in_echos.inc();
+
+                // Echo requests to broadcast addresses may be silently ignored. See
+                // <https://datatracker.ietf.org/doc/html/rfc1122#section-3.2.2.6>.
+                if ip_repr.dst_addr.is_broadcast()
+                    || !IpAddress::Ipv4(ip_repr.src_addr).is_unicast()
+                {
+                    return None;
+                }
+
+                let icmp_reply_repr = Icmpv4Repr::EchoReply {
+                    ident,
+                    seq_no,
+                    data,
+                };
+                ICMP_COUNTERS.out_msgs.inc();
+                ICMP_COUNTERS.out_echo_reps.inc();
+                Some(Packet::new_ipv4(
+                    Ipv4Repr {
+                        src_addr: ip_repr.dst_addr,
+                        dst_addr: ip_repr.src_addr,
+                        next_header: IpProtocol::Icmp,
+                        payload_len: icmp_reply_repr.buffer_len(),
+                        hop_limit: 64,
+                    },
+                    IpPayload::Icmpv4(icmp_reply_repr),
+                ))
+            }
+            Icmpv4Repr::EchoReply { ident, .. } => {
+                ICMP_COUNTERS.in_echo_reps.inc();
+                for socket in self.sockets.raw_socket_iter() {
+                    if socket.can_process(ident) && socket.process_echo_reply(ip_repr, ip_payload) {
+                        break;
+                    }
+                }
+                None
+            }
+            Icmpv4Repr::DstUnreachable { .. } => {
+                ICMP_COUNTERS.in_dest_unreachs.inc();
+                None
+            }
             _ => None,
         }
     }
@@ -129,14 +222,20 @@ impl<E: Ext> PollContext<'_, E> {
         }
 
         // Parse the TCP header. Ignore the packet if the header is ill-formed.
-        let tcp_pkt = TcpPacket::new_checked(ip_payload).ok()?;
-        let tcp_repr = TcpRepr::parse(
-            &tcp_pkt,
-            &ip_repr.src_addr(),
-            &ip_repr.dst_addr(),
-            checksum_caps,
-        )
-        .ok()?;
+        let Some(tcp_repr) = TcpPacket::new_checked(ip_payload)
+            .and_then(|tcp_pkt| {
+                TcpRepr::parse(
+                    &tcp_pkt,
+                    &ip_repr.src_addr(),
+                    &ip_repr.dst_addr(),
+                    checksum_caps,
+                )
+            })
+            .ok()
+        else {
+            TCP_COUNTERS.in_errs.inc();
+            return None;
+        };
 
         self.process_tcp_until_outgoing(ip_repr, &tcp_repr)
             .map(|(ip_repr, tcp_repr)| Packet::new(ip_repr, IpPayload::Tcp(tcp_repr)))
@@ -164,6 +263,21 @@ impl<E: Ext> PollContext<'_, E> {
         &mut self,
         ip_repr: &IpRepr,
         tcp_repr: &TcpRepr,
+    ) -> Option<(IpRepr, TcpRepr<'static>)> {
+        TCP_COUNTERS.in_segs.inc();
+
+        let reply = self.process_tcp_segment(ip_repr, tcp_repr);
+        if let Some((_, reply_repr)) = reply.as_ref() {
+            count_tcp_out_segment(reply_repr);
+        }
+
+        reply
+    }
+
+    fn process_tcp_segment(
+        &mut self,
+        ip_repr: &IpRepr,
+        tcp_repr: &TcpRepr,
     ) -> Option<(IpRepr, TcpRepr<'static>)> {
         // Process packets belonging to existing connections first.
         // Note that we must do this first because SYN packets may match existing TIME-WAIT
@@ -226,6 +340,7 @@ impl<E: Ext> PollContext<'_, E> {
                     listener.process(&mut self.iface, ip_repr, tcp_repr);
 
                 if let Some(tcp_conn) = new_tcp_conn {
+                    TCP_COUNTERS.passive_opens.inc();
                     self.actions.push(SocketTableAction::AddTcpConn(tcp_conn));
                 }
 
@@ -255,16 +370,26 @@ impl<E: Ext> PollContext<'_, E> {
         checksum_caps: &ChecksumCapabilities,
     ) -> Option<Packet<'pkt>> {
         // Parse the UDP header. Ignore the packet if the header is ill-formed.
-        let udp_pkt = UdpPacket::new_checked(ip_payload).ok()?;
-        let udp_repr = UdpRepr::parse(
-            &udp_pkt,
-            &ip_repr.src_addr(),
-            &ip_repr.dst_addr(),
-            checksum_caps,
-        )
-        .ok()?;
-
-        if !self.process_udp(ip_repr, &udp_repr, udp_pkt.payload()) {
+        let Some((udp_pkt, udp_repr)) = UdpPacket::new_checked(ip_payload)
+            .and_then(|udp_pkt| {
+                let udp_repr = UdpRepr::parse(
+                    &udp_pkt,
+                    &ip_repr.src_addr(),
+                    &ip_repr.dst_addr(),
+                    checksum_caps,
+                )?;
+                Ok((udp_pkt, udp_repr))
+            })
+            .ok()
+        else {
+            UDP_COUNTERS.in_errors.inc();
+            return None;
+        };
+
+        if self.process_udp(ip_repr, &udp_repr, udp_pkt.payload()) {
+            UDP_COUNTERS.in_datagrams.inc();
+        } else {
+            UDP_COUNTERS.no_ports.inc();
             return self.generate_icmp_unreachable(
                 ip_repr,
                 ip_payload,
@@ -314,6 +439,9 @@ impl<E: Ext> PollContext<'_, E> {
 
         let IpRepr::Ipv4(ipv4_repr) = ip_repr;
 
+        ICMP_COUNTERS.out_msgs.inc();
+        ICMP_COUNTERS.out_dest_unreachs.inc();
+
         let reply_len = icmp_reply_payload_len(ip_payload.len(), IPV4_MIN_MTU, IPV4_HEADER_LEN);
         let icmp_repr = Icmpv4Repr::DstUnreachable {
             reason,
@@ -376,9 +504,15 @@ impl<E: Ext> PollContext<'_, E> {
             return did_something_tcp;
         };
 
-        let (did_something_udp, _tx_token) = self.dispatch_udp(tx_token, dispatch_phy);
+        let (did_something_udp, tx_token) = self.dispatch_udp(tx_token, dispatch_phy);
+
+        let Some(tx_token) = tx_token else {
+            return did_something_tcp || did_something_udp;
+        };
+
+        let (did_something_raw, _tx_token) = self.dispatch_raw(tx_token, dispatch_phy);
 
-        did_something_tcp || did_something_udp
+        did_something_tcp || did_something_udp || did_something_raw
     }
 
     fn dispatch_tcp<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> (bool, Option<T>)
@@ -402,6 +536,8 @@ impl<E: Ext> PollContext<'_, E> {
 
             let (reply, became_dead) =
                 TcpConnectionBg::dispatch(&socket, &mut self.iface, |iface, ip_repr, tcp_repr| {
+                    count_tcp_out_segment(tcp_repr);
+
                     let mut this = PollContext::new(iface, self.sockets, self.actions);
 
                     if !this.is_unicast_local(ip_repr.dst_addr()) {
@@ -501,6 +637,8 @@ impl<E: Ext> PollContext<'_, E> {
 
             let (cx, pending) = self.iface.inner_mut();
             socket.dispatch(cx, |cx, ip_repr, udp_repr, udp_payload| {
+                UDP_COUNTERS.out_datagrams.inc();
+
                 let iface = PollableIfaceMut::new(cx, pending);
                 let mut this = PollContext::new(iface, self.sockets, &mut actions);
 
@@ -560,4 +698,98 @@ impl<E: Ext> PollContext<'_, E> {
 
         (did_something, tx_token)
     }
+
+    fn dispatch_raw<T, Q>(&mut self, tx_token: T, dispatch_phy: &mut Q) -> (bool, Option<T>)
+    where
+        T: TxToken,
+        Q: FnMut(&Packet, &mut Context, T),
+    {
+        let mut tx_token = Some(tx_token);
+        let mut did_something = false;
+
+        let sockets = self.sockets;
+        for socket in sockets.raw_socket_iter() {
+            if !socket.need_dispatch() {
+                continue;
+            }
+
+            // The socket lock is released after the packet is dequeued, so the packet can be
+            // processed locally without causing deadlocks.
+            let Some((ip_repr, ip_payload)) = socket.dispatch() else {
+                continue;
+            };
+            did_something = true;
+
+            let packet = Packet::new_ipv4(ip_repr, IpPayload::Raw(&ip_payload));
+            if ip_repr.dst_addr.is_broadcast()
+                || !self.is_unicast_local(IpAddress::Ipv4(ip_repr.dst_addr))
+            {
+                dispatch_phy(&packet, self.iface.context_mut(), tx_token.take().unwrap());
+            } else {
+                self.process_local_ipv4(&packet, &mut tx_token, dispatch_phy);
+            }
+
+            if tx_token.is_none() {
+                break;
+            }
+        }
+
+        (did_something, tx_token)
+    }
+
+    /// Processes an IP packet destined to the local iface.
+    ///
+    /// If the processing generates a reply that is also destined to the local iface, the reply
+    /// will be processed as well. Otherwise, the reply will be dispatched via `dispatch_phy`.
+    fn process_local_ipv4<T, Q>(
+        &mut self,
+        packet: &Packet,
+        tx_token: &mut Option<T>,
+        dispatch_phy: &mut Q,
+    ) where
+        T: TxToken,
+        Q: FnMut(&Packet, &mut Context, T),
+    {
+        let mut buffer = self.emit_local_ipv4(packet);
+
+        loop {
+            let Ok(ip_packet) = Ipv4Packet::new_checked(buffer.as_slice()) else {
+                return;
+            };
+            let Some(reply) = self.parse_and_process_ipv4(ip_packet) else {
+                return;
+            };
+
+            if !self.is_unicast_local(reply.ip_repr().dst_addr()) {
+                dispatch_phy(&reply, self.iface.context_mut(), tx_token.take().unwrap());
+                return;
+            }
+
+            let new_buffer = self.emit_local_ipv4(&reply);
+            buffer = new_buffer;
+        }
+    }
+
+    /// Emits an IP packet destined to the local iface into a buffer.
+    ///
+    /// The checksums are always generated, since the packet may be delivered to raw IP sockets
+    /// and read by user programs.
+    fn emit_local_ipv4(&self, packet: &Packet) -> Vec<u8> {
+        let mut caps = self.iface.context().caps.clone();
+        caps.checksum = ChecksumCapabilities::default();
+
+        let ip_repr = packet.ip_repr();
+        let mut buffer = vec![0; ip_repr.buffer_len()];
+        ip_repr.emit(&mut buffer[..], &caps.checksum);
+        packet.emit_payload(&ip_repr, &mut buffer[ip_repr.header_len()..], &caps);
+
+        buffer
+    }
+}
+
+fn count_tcp_out_segment(tcp_repr: &TcpRepr) {
+    TCP_COUNTERS.out_segs.inc();
+    if tcp_repr.control == TcpControl::Rst {
+        TCP_COUNTERS.out_rsts.inc();
+    }
 }
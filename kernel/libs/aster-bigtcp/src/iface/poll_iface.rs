@@ -6,7 +6,13 @@ use core::{
     sync::atomic::{AtomicU64, Ordering},
 };
 
+use smoltcp::{
+    iface::Route,
+    wire::{IpAddress, IpCidr, Ipv4Address, Ipv4Cidr},
+};
+
 use crate::{
+    errors::RouteTableFull,
     ext::Ext,
     socket::{NeedIfacePoll, TcpConnectionBg},
 };
@@ -35,6 +41,10 @@ impl<E: Ext> PollableIface<E> {
         }
     }
 
+    pub(super) fn hardware_addr(&self) -> smoltcp::wire::HardwareAddress {
+        self.interface.hardware_addr()
+    }
+
     pub(super) fn ipv4_addr(&self) -> Option<smoltcp::wire::Ipv4Address> {
         self.interface.ipv4_addr()
     }
@@ -46,6 +56,49 @@ impl<E: Ext> PollableIface<E> {
             .map(|ip_addr| ip_addr.prefix_len())
     }
 
+    /// Replaces the IPv4 address of the interface.
+    ///
+    /// FIXME: One iface may have multiple IPv4 addresses.
+    pub(super) fn set_ipv4_cidr(&mut self, ip_cidr: Option<Ipv4Cidr>) {
+        self.interface.update_ip_addrs(|ip_addrs| {
+            ip_addrs.clear();
+            if let Some(ip_cidr) = ip_cidr {
+                ip_addrs.push(IpCidr::Ipv4(ip_cidr)).unwrap();
+            }
+        });
+    }
+
+    /// Adds a route that forwards packets destined to `cidr` through `gateway`.
+    pub(super) fn add_ipv4_route(
+        &mut self,
+        cidr: Ipv4Cidr,
+        gateway: Ipv4Address,
+    ) -> Result<(), RouteTableFull> {
+        let route = Route {
+            cidr: IpCidr::Ipv4(cidr),
+            via_router: IpAddress::Ipv4(gateway),
+            preferred_until: None,
+            expires_at: None,
+        };
+
+        let mut result = Ok(());
+        self.interface.routes_mut().update(|routes| {
+            if routes.push(route).is_err() {
+                result = Err(RouteTableFull);
+            }
+        });
+        result
+    }
+
+    /// Removes a route added by [`Self::add_ipv4_route`].
+    pub(super) fn remove_ipv4_route(&mut self, cidr: Ipv4Cidr, gateway: Ipv4Address) {
+        self.interface.routes_mut().update(|routes| {
+            routes.retain(|route| {
+                route.cidr != IpCidr::Ipv4(cidr) || route.via_router != IpAddress::Ipv4(gateway)
+            });
+        });
+    }
+
     /// Returns the next poll time.
     pub(super) fn next_poll_at_ms(&self) -> Option<u64> {
         self.pending_conns.next_poll_at_ms()
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// The statistics of an iface.
+///
+/// Only the packets that pass through the underlying device are counted. Packets that are
+/// delivered locally (e.g., packets sent to the iface's own address) are not counted.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_link.h#L44>.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InterfaceStats {
+    /// The number of packets received.
+    pub rx_packets: u64,
+    /// The number of bytes received.
+    pub rx_bytes: u64,
+    /// The number of multicast packets received.
+    pub rx_multicast: u64,
+    /// The number of packets transmitted.
+    pub tx_packets: u64,
+    /// The number of bytes transmitted.
+    pub tx_bytes: u64,
+}
+
+/// The counters behind [`InterfaceStats`].
+pub(super) struct InterfaceCounters {
+    rx_packets: AtomicU64,
+    rx_bytes: AtomicU64,
+    rx_multicast: AtomicU64,
+    tx_packets: AtomicU64,
+    tx_bytes: AtomicU64,
+}
+
+impl InterfaceCounters {
+    pub(super) const fn new() -> Self {
+        Self {
+            rx_packets: AtomicU64::new(0),
+            rx_bytes: AtomicU64::new(0),
+            rx_multicast: AtomicU64::new(0),
+            tx_packets: AtomicU64::new(0),
+            tx_bytes: AtomicU64::new(0),
+        }
+    }
+
+    pub(super) fn on_rx(&self, len: usize) {
+        self.rx_packets.fetch_add(1, Ordering::Relaxed);
+        self.rx_bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn on_rx_multicast(&self) {
+        self.rx_multicast.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn on_tx(&self, len: usize) {
+        self.tx_packets.fetch_add(1, Ordering::Relaxed);
+        self.tx_bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn snapshot(&self) -> InterfaceStats {
+        InterfaceStats {
+            rx_packets: self.rx_packets.load(Ordering::Relaxed),
+            rx_bytes: self.rx_bytes.load(Ordering::Relaxed),
+            rx_multicast: self.rx_multicast.load(Ordering::Relaxed),
+            tx_packets: self.tx_packets.load(Ordering::Relaxed),
+            tx_bytes: self.tx_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
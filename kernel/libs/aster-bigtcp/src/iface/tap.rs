@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MPL-2.0
+
+/// The type of a link-layer frame captured by a [`PacketTap`].
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L28>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketType {
+    /// The frame is destined to us.
+    Host = 0,
+    /// The frame is destined to the broadcast address.
+    Broadcast = 1,
+    /// The frame is destined to a multicast address.
+    Multicast = 2,
+    /// The frame is destined to someone else.
+    ///
+    /// Such frames are only captured if the iface is in promiscuous mode.
+    OtherHost = 3,
+    /// The frame is sent by us.
+    Outgoing = 4,
+}
+
+/// A tap that captures link-layer frames received or transmitted by ifaces.
+///
+/// Taps are registered with [`Iface::register_packet_tap`].
+///
+/// [`Iface::register_packet_tap`]: super::Iface::register_packet_tap
+pub trait PacketTap: Send + Sync {
+    /// Captures a frame that passes through the iface whose index is `iface_index`.
+    ///
+    /// This method is called with the iface locked, so it must not poll the iface or register
+    /// (or remove) taps.
+    fn capture(&self, iface_index: u32, frame: &[u8], pkt_type: PacketType);
+}
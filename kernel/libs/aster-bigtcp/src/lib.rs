@@ -21,6 +21,7 @@ pub mod ext;
 pub mod iface;
 pub mod socket;
 pub mod socket_table;
+pub mod stats;
 pub mod time;
 pub mod wire;
 
@@ -15,11 +15,12 @@ use crate::{
 
 pub struct Socket<T: Inner<E>, E: Ext>(pub(super) Takeable<Arc<SocketBg<T, E>>>);
 
-/// [`TcpConnectionInner`], [`TcpListenerInner`], or [`UdpSocketInner`].
+/// [`TcpConnectionInner`], [`TcpListenerInner`], [`UdpSocketInner`], or [`RawIpSocketInner`].
 ///
 /// [`TcpConnectionInner`]: super::tcp_conn::TcpConnectionInner
 /// [`TcpListenerInner`]: super::tcp_listen::TcpListenerInner
 /// [`UdpSocketInner`]: super::udp::UdpSocketInner
+/// [`RawIpSocketInner`]: super::raw::RawIpSocketInner
 pub trait Inner<E: Ext> {
     type Observer: SocketEventObserver;
 
@@ -30,7 +31,8 @@ pub trait Inner<E: Ext> {
         Self: Sized;
 }
 
-/// Common states shared by [`TcpConnectionBg`], [`TcpListenerBg`], and [`UdpSocketBg`].
+/// Common states shared by [`TcpConnectionBg`], [`TcpListenerBg`], [`UdpSocketBg`], and
+/// [`RawIpSocketBg`].
 ///
 /// In the type name, `Bg` means "background". Its meaning is described below:
 /// - A foreground socket (e.g., [`TcpConnection`]) handles system calls from the user program.
@@ -39,6 +41,7 @@ pub trait Inner<E: Ext> {
 /// [`TcpConnectionBg`]: super::tcp_conn::TcpConnectionBg
 /// [`TcpListenerBg`]: super::tcp_listen::TcpListenerBg
 /// [`UdpSocketBg`]: super::udp::UdpSocketBg
+/// [`RawIpSocketBg`]: super::raw::RawIpSocketBg
 /// [`TcpConnection`]: super::tcp_conn::TcpConnection
 pub struct SocketBg<T: Inner<E>, E: Ext> {
     pub(super) bound: BoundPort<E>,
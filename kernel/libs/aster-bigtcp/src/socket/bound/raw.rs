@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::{boxed::Box, collections::vec_deque::VecDeque, sync::Arc};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use aster_softirq::BottomHalfDisabled;
+use ostd::sync::SpinLock;
+use smoltcp::wire::{
+    Icmpv4Message, Icmpv4Packet, IpProtocol, Ipv4Address, Ipv4Packet, Ipv4Repr, IPV4_HEADER_LEN,
+};
+
+use super::common::{Inner, Socket, SocketBg};
+use crate::{
+    errors::raw::{RecvError, SendError},
+    ext::Ext,
+    iface::BoundPort,
+    socket::{event::SocketEvents, RAW_RECV_BUF_LEN, RAW_SEND_BUF_LEN},
+};
+
+pub type RawIpSocket<E> = Socket<RawIpSocketInner, E>;
+
+/// The kind of a [`RawIpSocket`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawIpSocketKind {
+    /// A raw socket that receives all IP packets of the specified protocol.
+    ///
+    /// Received packets are delivered with their IP headers.
+    Raw(IpProtocol),
+    /// An ICMP ping socket that only exchanges ICMP echo messages.
+    ///
+    /// The identifier of the echo messages is the bound port. Received packets are delivered
+    /// without their IP headers.
+    Ping,
+}
+
+/// States needed by [`RawIpSocketBg`].
+pub struct RawIpSocketInner {
+    kind: RawIpSocketKind,
+    icmp_filter: AtomicU32,
+    recv_queue: SpinLock<PacketQueue<Ipv4Address>, BottomHalfDisabled>,
+    send_queue: SpinLock<PacketQueue<Ipv4Repr>, BottomHalfDisabled>,
+    need_dispatch: AtomicBool,
+}
+
+impl<E: Ext> Inner<E> for RawIpSocketInner {
+    type Observer = E::RawEventObserver;
+
+    fn on_drop(this: &Arc<SocketBg<Self, E>>) {
+        // A raw IP socket can be removed immediately.
+        this.bound.iface().common().remove_raw_socket(this);
+    }
+}
+
+pub(crate) type RawIpSocketBg<E> = SocketBg<RawIpSocketInner, E>;
+
+impl<E: Ext> RawIpSocketBg<E> {
+    /// Tries to process an incoming IP packet and returns whether the packet is processed.
+    ///
+    /// This method is only useful for raw sockets. Ping sockets will ignore the packet.
+    pub(crate) fn process(&self, ip_repr: &Ipv4Repr, ip_packet: &[u8]) -> bool {
+        let RawIpSocketKind::Raw(protocol) = self.inner.kind else {
+            return false;
+        };
+        if ip_repr.next_header != protocol {
+            return false;
+        }
+
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/raw.c#L134>.
+        if protocol == IpProtocol::Icmp {
+            let icmp_type =
+                ip_packet.get(Ipv4Packet::new_unchecked(ip_packet).header_len() as usize);
+            if icmp_type.is_some_and(|ty| {
+                *ty < 32 && self.inner.icmp_filter.load(Ordering::Relaxed) & (1 << *ty) != 0
+            }) {
+                return false;
+            }
+        }
+
+        self.enqueue_recv(ip_repr.src_addr, ip_packet)
+    }
+
+    /// Tries to process an incoming ICMP echo reply and returns whether the packet is processed.
+    ///
+    /// This method is only useful for ping sockets. Raw sockets will ignore the packet.
+    pub(crate) fn process_echo_reply(&self, ip_repr: &Ipv4Repr, icmp_packet: &[u8]) -> bool {
+        if self.inner.kind != RawIpSocketKind::Ping {
+            return false;
+        }
+
+        self.enqueue_recv(ip_repr.src_addr, icmp_packet)
+    }
+
+    fn enqueue_recv(&self, src_addr: Ipv4Address, data: &[u8]) -> bool {
+        let mut recv_queue = self.inner.recv_queue.lock();
+
+        // The packet is dropped silently if the receive buffer is full. This is how Linux behaves.
+        if !recv_queue.push(src_addr, data.into()) {
+            return false;
+        }
+        drop(recv_queue);
+
+        self.notify_events(SocketEvents::CAN_RECV);
+
+        true
+    }
+
+    /// Tries to generate an outgoing packet.
+    ///
+    /// The returned packet consists of the IP header and the IP payload.
+    pub(crate) fn dispatch(&self) -> Option<(Ipv4Repr, Box<[u8]>)> {
+        let mut send_queue = self.inner.send_queue.lock();
+
+        let packet = send_queue.pop();
+        self.inner
+            .need_dispatch
+            .store(!send_queue.is_empty(), Ordering::Relaxed);
+        drop(send_queue);
+
+        // For raw IP sockets, dequeuing a packet means that we can queue more packets.
+        if packet.is_some() {
+            self.notify_events(SocketEvents::CAN_SEND);
+        }
+
+        packet
+    }
+
+    /// Returns whether the socket _may_ generate an outgoing packet.
+    ///
+    /// The check is intended to be lock-free and fast, but may have false positives.
+    pub(crate) fn need_dispatch(&self) -> bool {
+        self.inner.need_dispatch.load(Ordering::Relaxed)
+    }
+}
+
+impl<E: Ext> RawIpSocket<E> {
+    /// Binds to the iface specified by `bound`.
+    ///
+    /// For ping sockets, the bound port is used as the identifier of the echo messages. For raw
+    /// sockets, the bound port is ignored.
+    ///
+    /// Polling the iface is _not_ required after this method succeeds.
+    pub fn new_bind(
+        bound: BoundPort<E>,
+        kind: RawIpSocketKind,
+        observer: E::RawEventObserver,
+    ) -> Self {
+        let inner = RawIpSocketInner {
+            kind,
+            icmp_filter: AtomicU32::new(0),
+            recv_queue: SpinLock::new(PacketQueue::new(RAW_RECV_BUF_LEN)),
+            send_queue: SpinLock::new(PacketQueue::new(RAW_SEND_BUF_LEN)),
+            need_dispatch: AtomicBool::new(false),
+        };
+
+        let socket = Self::new(bound, inner);
+        socket.init_observer(observer);
+        socket
+            .iface()
+            .common()
+            .register_raw_socket(socket.inner().clone());
+
+        socket
+    }
+
+    /// Sends an IP packet whose payload is `payload`.
+    ///
+    /// The IP header is generated by the socket. For ping sockets, `payload` must be an ICMP echo
+    /// request, whose identifier and checksum will be overwritten.
+    ///
+    /// Polling the iface is _always_ required after this method succeeds.
+    pub fn send(
+        &self,
+        dst_addr: Ipv4Address,
+        hop_limit: u8,
+        payload: &[u8],
+    ) -> Result<(), SendError> {
+        let Some(src_addr) = self.iface().ipv4_addr() else {
+            return Err(SendError::Unaddressable);
+        };
+
+        let (next_header, payload) = match self.0.inner.kind {
+            RawIpSocketKind::Raw(protocol) => (protocol, Box::from(payload)),
+            RawIpSocketKind::Ping => (IpProtocol::Icmp, self.make_echo_request(payload)?),
+        };
+
+        let ip_repr = Ipv4Repr {
+            src_addr,
+            dst_addr,
+            next_header,
+            payload_len: payload.len(),
+            hop_limit,
+        };
+
+        self.enqueue_send(ip_repr, payload)
+    }
+
+    fn make_echo_request(&self, payload: &[u8]) -> Result<Box<[u8]>, SendError> {
+        let mut payload = Box::<[u8]>::from(payload);
+
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/ping.c#L699>.
+        let Ok(mut icmp_packet) = Icmpv4Packet::new_checked(&mut payload[..]) else {
+            return Err(SendError::InvalidPacket);
+        };
+        if icmp_packet.msg_type() != Icmpv4Message::EchoRequest || icmp_packet.msg_code() != 0 {
+            return Err(SendError::InvalidPacket);
+        }
+
+        icmp_packet.set_echo_ident(self.0.bound.port());
+        icmp_packet.fill_checksum();
+
+        Ok(payload)
+    }
+
+    /// Sends an IP packet whose IP header is supplied in `packet`.
+    ///
+    /// If the source address in the header is unspecified, the address of the iface will be
+    /// used. The total length and the checksum in the header will always be regenerated.
+    ///
+    /// FIXME: Other fields in the IP header (e.g., the TOS, the identification, and the IP
+    /// options) are not preserved for now.
+    ///
+    /// Polling the iface is _always_ required after this method succeeds.
+    pub fn send_hdrincl(&self, packet: &[u8]) -> Result<(), SendError> {
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/raw.c#L384>.
+        if packet.len() < IPV4_HEADER_LEN {
+            return Err(SendError::InvalidPacket);
+        }
+        let ip_packet = Ipv4Packet::new_unchecked(packet);
+        let header_len = ip_packet.header_len() as usize;
+        if header_len < IPV4_HEADER_LEN || header_len > packet.len() {
+            return Err(SendError::InvalidPacket);
+        }
+
+        let src_addr = if ip_packet.src_addr().is_unspecified() {
+            let Some(src_addr) = self.iface().ipv4_addr() else {
+                return Err(SendError::Unaddressable);
+            };
+            src_addr
+        } else {
+            ip_packet.src_addr()
+        };
+
+        let ip_repr = Ipv4Repr {
+            src_addr,
+            dst_addr: ip_packet.dst_addr(),
+            next_header: ip_packet.next_header(),
+            payload_len: packet.len() - header_len,
+            hop_limit: ip_packet.hop_limit(),
+        };
+
+        self.enqueue_send(ip_repr, Box::from(&packet[header_len..]))
+    }
+
+    fn enqueue_send(&self, ip_repr: Ipv4Repr, payload: Box<[u8]>) -> Result<(), SendError> {
+        if ip_repr.buffer_len() + payload.len() > self.iface().mtu() {
+            return Err(SendError::TooLarge);
+        }
+
+        let mut send_queue = self.0.inner.send_queue.lock();
+
+        if !send_queue.push(ip_repr, payload) {
+            return Err(SendError::BufferFull);
+        }
+
+        self.0.inner.need_dispatch.store(true, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Receives a packet.
+    ///
+    /// For raw sockets, the packet contains the IP header. For ping sockets, the packet is the
+    /// ICMP message.
+    ///
+    /// Polling the iface is _not_ required after this method succeeds.
+    pub fn recv<F, R>(&self, f: F) -> Result<R, RecvError>
+    where
+        F: FnOnce(&[u8], Ipv4Address) -> R,
+    {
+        let mut recv_queue = self.0.inner.recv_queue.lock();
+
+        let Some((src_addr, packet)) = recv_queue.pop() else {
+            return Err(RecvError::Exhausted);
+        };
+        drop(recv_queue);
+
+        Ok(f(&packet, src_addr))
+    }
+
+    /// Returns whether there are packets that can be received.
+    pub fn can_recv(&self) -> bool {
+        !self.0.inner.recv_queue.lock().is_empty()
+    }
+
+    /// Returns whether there is space to send more packets.
+    pub fn can_send(&self) -> bool {
+        !self.0.inner.send_queue.lock().is_full()
+    }
+
+    /// Sets the ICMP filter.
+    ///
+    /// ICMP messages whose type is `ty` will be dropped if the `ty`-th bit in `filter` is set.
+    /// This only affects raw sockets with the ICMP protocol.
+    pub fn set_icmp_filter(&self, filter: u32) {
+        self.0.inner.icmp_filter.store(filter, Ordering::Relaxed);
+    }
+}
+
+/// A queue of packets whose total size is limited.
+struct PacketQueue<M> {
+    packets: VecDeque<(M, Box<[u8]>)>,
+    size: usize,
+    capacity: usize,
+}
+
+impl<M> PacketQueue<M> {
+    const fn new(capacity: usize) -> Self {
+        Self {
+            packets: VecDeque::new(),
+            size: 0,
+            capacity,
+        }
+    }
+
+    /// Pushes a packet and returns whether there is enough space for it.
+    fn push(&mut self, meta: M, data: Box<[u8]>) -> bool {
+        if self.capacity - self.size < data.len() {
+            return false;
+        }
+
+        self.size += data.len();
+        self.packets.push_back((meta, data));
+
+        true
+    }
+
+    fn pop(&mut self) -> Option<(M, Box<[u8]>)> {
+        let (meta, data) = self.packets.pop_front()?;
+        self.size -= data.len();
+
+        Some((meta, data))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.size >= self.capacity
+    }
+}
@@ -9,6 +9,7 @@ use core::ops::{Deref, DerefMut};
 use aster_softirq::BottomHalfDisabled;
 use ostd::sync::{SpinLock, SpinLockGuard};
 use smoltcp::{
+    iface::Context,
     socket::{tcp::State, PollAt},
     time::Duration,
     wire::{IpEndpoint, IpRepr, TcpControl, TcpRepr},
@@ -24,11 +25,14 @@ use crate::{
     ext::Ext,
     iface::{BoundPort, PollKey, PollableIfaceMut},
     socket::{
+        congestion::{CongestionControl, CongestionInfo, TcpCongestion},
         event::SocketEvents,
         option::{RawTcpOption, RawTcpSetOption},
         unbound::{new_tcp_socket, RawTcpSocket},
+        TcpSocketInfo,
     },
     socket_table::ConnectionKey,
+    stats::TCP_COUNTERS,
 };
 
 pub type TcpConnection<E> = Socket<TcpConnectionInner<E>, E>;
@@ -48,6 +52,7 @@ pub struct RawTcpSocketExt<E: Ext> {
     is_recv_shut: bool,
     /// Indicates if the socket is closed by a RST packet.
     is_rst_closed: bool,
+    congestion: TcpCongestion,
 }
 
 impl<E: Ext> Deref for RawTcpSocketExt<E> {
@@ -106,6 +111,31 @@ impl<E: Ext> RawTcpSocketExt<E> {
     pub fn is_rst_closed(&self) -> bool {
         self.is_rst_closed
     }
+
+    /// Returns the congestion control algorithm.
+    pub fn congestion_control(&self) -> CongestionControl {
+        self.congestion.control()
+    }
+
+    /// Returns the congestion control information.
+    pub fn congestion_info(&self) -> CongestionInfo {
+        self.congestion.info()
+    }
+
+    /// Returns when the socket should be polled next.
+    ///
+    /// This is similar to [`RawTcpSocket::poll_at`]. However, this method takes the congestion
+    /// window into account. If new segments are held back by the congestion window, there is no
+    /// need to poll the socket until an ACK arrives or the retransmission timer may expire.
+    pub fn poll_at(&self, cx: &mut Context) -> PollAt {
+        let poll_at = self.socket.poll_at(cx);
+
+        if poll_at == PollAt::Now && self.congestion.is_cwnd_limited() {
+            PollAt::Time(cx.now() + self.congestion.rto())
+        } else {
+            poll_at
+        }
+    }
 }
 
 define_boolean_value!(
@@ -114,6 +144,56 @@ define_boolean_value!(
 );
 
 impl<E: Ext> RawTcpSocketExt<E> {
+    /// Processes an incoming packet and feeds the ACK to the congestion control.
+    fn process_with_congestion(
+        &mut self,
+        cx: &mut Context,
+        ip_repr: &IpRepr,
+        tcp_repr: &TcpRepr,
+    ) -> Option<(IpRepr, TcpRepr<'static>)> {
+        let reply = self.socket.process(cx, ip_repr, tcp_repr);
+        self.congestion.on_recv(cx.now(), tcp_repr);
+        reply
+    }
+
+    /// Generates an outgoing packet, if the congestion window allows, and dispatches it.
+    fn dispatch_with_congestion<D>(&mut self, cx: &mut Context, dispatch: D)
+    where
+        D: FnOnce(&mut Context, &IpRepr, &TcpRepr),
+    {
+        let Self {
+            socket, congestion, ..
+        } = self;
+
+        // If smoltcp fails to emit a packet, its state is kept as if the packet had never been
+        // generated, so the held-back segment will be generated again later.
+        let _ = socket.dispatch(cx, |cx, (ip_repr, tcp_repr)| {
+            let now = cx.now();
+
+            if congestion.can_send(&tcp_repr) {
+                dispatch(cx, &ip_repr, &tcp_repr);
+                congestion.on_sent(now, &tcp_repr);
+                return Ok(());
+            }
+
+            // The segment is held back, but the ACK it carries should not be delayed.
+            if congestion.carries_new_ack(&tcp_repr) {
+                let ack_repr = TcpRepr {
+                    control: TcpControl::None,
+                    payload: &[],
+                    ..tcp_repr
+                };
+                let IpRepr::Ipv4(mut ipv4_repr) = ip_repr;
+                ipv4_repr.payload_len = ack_repr.buffer_len();
+
+                dispatch(cx, &IpRepr::Ipv4(ipv4_repr), &ack_repr);
+                congestion.on_sent(now, &ack_repr);
+            }
+
+            Err(())
+        });
+    }
+
     /// Checks the TCP state for additional events and whether the connection is dead.
     fn check_state(
         &mut self,
@@ -218,6 +298,7 @@ impl<E: Ext> TcpConnectionInner<E> {
     pub(super) fn new(
         socket: Box<RawTcpSocket>,
         listener: Option<Arc<TcpListenerBg<E>>>,
+        congestion_control: CongestionControl,
         weak_self: &Weak<TcpConnectionBg<E>>,
     ) -> Self {
         let connection_key = {
@@ -235,6 +316,7 @@ impl<E: Ext> TcpConnectionInner<E> {
             has_connected: false,
             is_recv_shut: false,
             is_rst_closed: false,
+            congestion: TcpCongestion::new(congestion_control),
         };
 
         TcpConnectionInner {
@@ -323,14 +405,17 @@ impl<E: Ext> TcpConnection<E> {
             socket
         };
 
-        let connection =
-            Self::new_cyclic(bound, |weak| TcpConnectionInner::new(socket, None, weak));
+        let connection = Self::new_cyclic(bound, |weak| {
+            TcpConnectionInner::new(socket, None, option.congestion_control, weak)
+        });
         interface.update_next_poll_at_ms(&connection.0, PollAt::Now);
         connection.init_observer(observer);
 
         let res = sockets.insert_connection(connection.inner().clone());
         debug_assert!(res.is_ok());
 
+        TCP_COUNTERS.active_opens.inc();
+
         Ok(connection)
     }
 
@@ -537,6 +622,11 @@ impl<E: Ext> RawTcpSetOption for TcpConnection<E> {
         let mut socket = self.0.inner.lock();
         socket.set_nagle_enabled(enabled);
     }
+
+    fn set_congestion_control(&self, control: CongestionControl) {
+        let mut socket = self.0.inner.lock();
+        socket.congestion.set_control(control);
+    }
 }
 
 impl<E: Ext> TcpConnectionBg<E> {
@@ -547,6 +637,19 @@ impl<E: Ext> TcpConnectionBg<E> {
     pub(crate) const fn connection_key(&self) -> &ConnectionKey {
         &self.inner.connection_key
     }
+
+    /// Returns a snapshot of the connection.
+    pub(crate) fn info(&self) -> TcpSocketInfo {
+        let socket = self.inner.lock();
+
+        TcpSocketInfo {
+            local_endpoint: self.inner.connection_key.local_endpoint(),
+            remote_endpoint: Some(self.inner.connection_key.remote_endpoint()),
+            state: socket.state(),
+            recv_queue: socket.recv_queue(),
+            send_queue: socket.send_queue(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -613,7 +716,7 @@ impl<E: Ext> TcpConnectionBg<E> {
         // to be queued.
         let mut events = SocketEvents::CAN_RECV | SocketEvents::CAN_SEND;
 
-        let result = match socket.process(iface.context_mut(), ip_repr, tcp_repr) {
+        let result = match socket.process_with_congestion(iface.context_mut(), ip_repr, tcp_repr) {
             None => TcpProcessResult::Processed,
             Some((ip_repr, tcp_repr)) => TcpProcessResult::ProcessedWithReply(ip_repr, tcp_repr),
         };
@@ -648,12 +751,9 @@ impl<E: Ext> TcpConnectionBg<E> {
 
         let mut reply = None;
         let (cx, pending) = iface.inner_mut();
-        socket
-            .dispatch(cx, |cx, (ip_repr, tcp_repr)| {
-                reply = dispatch(PollableIfaceMut::new(cx, pending), &ip_repr, &tcp_repr);
-                Ok::<(), ()>(())
-            })
-            .unwrap();
+        socket.dispatch_with_congestion(cx, |cx, ip_repr, tcp_repr| {
+            reply = dispatch(PollableIfaceMut::new(cx, pending), ip_repr, tcp_repr);
+        });
 
         // `dispatch` can return a packet in response to the generated packet. If the socket
         // accepts the packet, we can process it directly.
@@ -663,7 +763,7 @@ impl<E: Ext> TcpConnectionBg<E> {
             }
             is_rst |= tcp_repr.control == TcpControl::Rst;
             events |= SocketEvents::CAN_RECV | SocketEvents::CAN_SEND;
-            reply = socket.process(iface.context_mut(), ip_repr, tcp_repr);
+            reply = socket.process_with_congestion(iface.context_mut(), ip_repr, tcp_repr);
         }
 
         let (state_events, became_dead) =
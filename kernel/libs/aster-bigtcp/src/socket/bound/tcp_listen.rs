@@ -19,8 +19,10 @@ use crate::{
     ext::Ext,
     iface::{BindPortConfig, BoundPort, PollableIfaceMut},
     socket::{
+        congestion::CongestionControl,
         option::{RawTcpOption, RawTcpSetOption},
         unbound::{new_tcp_socket, RawTcpSocket},
+        TcpSocketInfo, TcpState,
     },
     socket_table::{ConnectionKey, ListenerKey},
 };
@@ -29,6 +31,8 @@ pub type TcpListener<E> = Socket<TcpListenerInner<E>, E>;
 
 pub struct TcpBacklog<E: Ext> {
     socket: Box<RawTcpSocket>,
+    /// The congestion control algorithm of new connections.
+    congestion_control: CongestionControl,
     max_conn: usize,
     pub(super) connecting: BTreeMap<ConnectionKey, TcpConnection<E>>,
     pub(super) connected: Vec<TcpConnection<E>>,
@@ -63,6 +67,21 @@ impl<E: Ext> Inner<E> for TcpListenerInner<E> {
 
 pub(crate) type TcpListenerBg<E> = SocketBg<TcpListenerInner<E>, E>;
 
+impl<E: Ext> TcpListenerBg<E> {
+    /// Returns a snapshot of the listener.
+    pub(crate) fn info(&self) -> TcpSocketInfo {
+        let backlog = self.inner.backlog.lock();
+
+        TcpSocketInfo {
+            local_endpoint: self.inner.listener_key.endpoint(),
+            remote_endpoint: None,
+            state: TcpState::Listen,
+            recv_queue: backlog.connected.len(),
+            send_queue: backlog.max_conn,
+        }
+    }
+}
+
 impl<E: Ext> TcpListener<E> {
     /// Listens at a specified endpoint.
     ///
@@ -101,6 +120,7 @@ impl<E: Ext> TcpListener<E> {
         let inner = {
             let backlog = TcpBacklog {
                 socket,
+                congestion_control: option.congestion_control,
                 max_conn,
                 connecting: BTreeMap::new(),
                 connected: Vec::new(),
@@ -182,6 +202,11 @@ impl<E: Ext> RawTcpSetOption for TcpListener<E> {
         let mut backlog = self.0.inner.backlog.lock();
         backlog.socket.set_nagle_enabled(enabled);
     }
+
+    fn set_congestion_control(&self, control: CongestionControl) {
+        let mut backlog = self.0.inner.backlog.lock();
+        backlog.congestion_control = control;
+    }
 }
 
 impl<E: Ext> TcpListenerBg<E> {
@@ -242,6 +267,7 @@ impl<E: Ext> TcpListenerBg<E> {
                 TcpConnectionInner::new(
                     core::mem::replace(&mut backlog.socket, new_socket),
                     Some(self.clone()),
+                    backlog.congestion_control,
                     weak,
                 )
             },
@@ -8,7 +8,7 @@ use ostd::sync::SpinLock;
 use smoltcp::{
     iface::Context,
     socket::udp::UdpMetadata,
-    wire::{IpRepr, UdpRepr},
+    wire::{IpAddress, IpEndpoint, IpRepr, UdpRepr},
 };
 
 use super::common::{Inner, Socket, SocketBg};
@@ -16,7 +16,7 @@ use crate::{
     errors::udp::SendError,
     ext::Ext,
     iface::BoundPort,
-    socket::{event::SocketEvents, unbound::new_udp_socket, RawUdpSocket},
+    socket::{event::SocketEvents, unbound::new_udp_socket, RawUdpSocket, UdpSocketInfo},
 };
 
 pub type UdpSocket<E> = Socket<UdpSocketInner, E>;
@@ -90,6 +90,20 @@ impl<E: Ext> UdpSocketBg<E> {
             .store(socket.send_queue() > 0, Ordering::Relaxed);
     }
 
+    /// Returns a snapshot of the socket.
+    ///
+    /// The caller should provide the address of the iface, since the iface cannot be locked while
+    /// the socket table is locked.
+    pub(crate) fn info(&self, iface_addr: IpAddress) -> UdpSocketInfo {
+        let socket = self.inner.socket.lock();
+
+        UdpSocketInfo {
+            local_endpoint: IpEndpoint::new(iface_addr, self.bound.port()),
+            recv_queue: socket.recv_queue(),
+            send_queue: socket.send_queue(),
+        }
+    }
+
     /// Returns whether the socket _may_ generate an outgoing packet.
     ///
     /// The check is intended to be lock-free and fast, but may have false positives.
@@ -182,6 +196,13 @@ impl<E: Ext> UdpSocket<E> {
         Ok(result)
     }
 
+    /// Returns the length of the next datagram to be received, if any.
+    pub fn peek_len(&self) -> Option<usize> {
+        let mut socket = self.0.inner.socket.lock();
+
+        socket.peek().ok().map(|(data, _)| data.len())
+    }
+
     /// Calls `f` with an immutable reference to the associated [`RawUdpSocket`].
     //
     // NOTE: If a mutable reference is required, add a method above that correctly updates the next
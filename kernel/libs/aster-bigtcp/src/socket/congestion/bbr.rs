@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! BBR (Bottleneck Bandwidth and Round-trip propagation time).
+//!
+//! BBR estimates the bottleneck bandwidth and the minimum RTT of the path, and sizes the
+//! congestion window to a multiple of their product. Unlike loss-based algorithms, it does not
+//! treat packet loss as a congestion signal.
+//!
+//! bigtcp does not pace outgoing segments, so the pacing gains of the original algorithm are
+//! applied to the congestion window instead.
+
+use smoltcp::time::{Duration, Instant};
+
+use super::{AckSample, CongestionOps, CongestionWindow, Loss, INITIAL_CWND};
+
+/// The unit of gains.
+const GAIN_UNIT: u64 = 1000;
+/// The gain to double the sending rate every round in the startup phase (`2/ln(2)`).
+const HIGH_GAIN: u64 = 2885;
+/// The gains to probe for more bandwidth and then drain the queue in the steady phase.
+const PROBE_BW_GAINS: [u64; 8] = [1250, 750, 1000, 1000, 1000, 1000, 1000, 1000];
+/// The number of rounds in which the maximum bandwidth is kept.
+const BW_FILTER_ROUNDS: usize = 10;
+/// The time after which the minimum RTT needs to be probed again.
+const MIN_RTT_WINDOW: Duration = Duration::from_secs(10);
+/// The time to keep the window small when probing the minimum RTT.
+const PROBE_RTT_DURATION: Duration = Duration::from_millis(200);
+/// The number of rounds without significant bandwidth growth that end the startup phase.
+const FULL_BW_ROUNDS: u32 = 3;
+/// The minimum window, which keeps enough segments in flight for delayed ACKs.
+const MIN_CWND: u32 = 4;
+/// The extra segments allowed in flight to absorb delayed and stretched ACKs.
+const CWND_HEADROOM: u32 = 3;
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    /// Grows the window exponentially to find the bottleneck bandwidth.
+    Startup,
+    /// Drains the queue built in the startup phase.
+    Drain,
+    /// Cycles the gains to probe for more bandwidth.
+    ProbeBw {
+        cycle_index: usize,
+        cycle_start: Instant,
+    },
+    /// Shrinks the window to measure the minimum RTT.
+    ProbeRtt { done_at: Instant },
+}
+
+pub(super) struct Bbr {
+    mode: Mode,
+    /// The maximum delivery rate of the recent rounds, in bytes per second.
+    bw_filter: [u64; BW_FILTER_ROUNDS],
+    round_count: usize,
+    /// The time and the delivered bytes when the current round started.
+    round_start: Option<(Instant, u64)>,
+    min_rtt: Option<Duration>,
+    min_rtt_stamp: Option<Instant>,
+    full_bw: u64,
+    full_bw_count: u32,
+    is_full_bw_reached: bool,
+    /// The window before entering the `ProbeRtt` mode.
+    prior_cwnd: u32,
+}
+
+impl Bbr {
+    pub(super) fn new() -> Self {
+        Self {
+            mode: Mode::Startup,
+            bw_filter: [0; BW_FILTER_ROUNDS],
+            round_count: 0,
+            round_start: None,
+            min_rtt: None,
+            min_rtt_stamp: None,
+            full_bw: 0,
+            full_bw_count: 0,
+            is_full_bw_reached: false,
+            prior_cwnd: 0,
+        }
+    }
+
+    fn max_bw(&self) -> u64 {
+        self.bw_filter.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the bandwidth-delay product scaled by `gain`, in segments.
+    fn target_cwnd(&self, gain: u64, mss: u32) -> u32 {
+        let (Some(min_rtt), bw) = (self.min_rtt, self.max_bw()) else {
+            return INITIAL_CWND;
+        };
+        if bw == 0 {
+            return INITIAL_CWND;
+        }
+
+        let bdp = bw as u128 * min_rtt.total_micros() as u128 / 1_000_000 / mss as u128;
+        let target = bdp * gain as u128 / GAIN_UNIT as u128;
+        u32::try_from(target)
+            .unwrap_or(u32::MAX)
+            .saturating_add(CWND_HEADROOM)
+    }
+
+    fn cwnd_gain(&self) -> u64 {
+        match self.mode {
+            Mode::Startup => HIGH_GAIN,
+            Mode::Drain => GAIN_UNIT,
+            Mode::ProbeBw { cycle_index, .. } => PROBE_BW_GAINS[cycle_index],
+            Mode::ProbeRtt { .. } => GAIN_UNIT,
+        }
+    }
+
+    /// Updates the round counter and samples the delivery rate at the end of each round.
+    ///
+    /// A round lasts about one minimum RTT. Returns whether a round has ended.
+    fn update_round(&mut self, sample: &AckSample) -> bool {
+        let Some((start, start_delivered)) = self.round_start else {
+            self.round_start = Some((sample.now, sample.delivered_bytes));
+            return false;
+        };
+        let Some(min_rtt) = self.min_rtt else {
+            return false;
+        };
+
+        let elapsed = sample.now - start;
+        if elapsed < min_rtt || elapsed.total_micros() == 0 {
+            return false;
+        }
+
+        let delivered = sample.delivered_bytes - start_delivered;
+        let bw = delivered * 1_000_000 / elapsed.total_micros();
+
+        self.round_count += 1;
+        self.bw_filter[self.round_count % BW_FILTER_ROUNDS] = bw;
+        self.round_start = Some((sample.now, sample.delivered_bytes));
+
+        true
+    }
+
+    /// Checks whether the bandwidth has stopped growing in the startup phase.
+    fn check_full_bw_reached(&mut self) {
+        let bw = self.max_bw();
+
+        // The bandwidth is still growing if it increases by at least 25% in a round.
+        if bw >= self.full_bw * 5 / 4 {
+            self.full_bw = bw;
+            self.full_bw_count = 0;
+            return;
+        }
+
+        self.full_bw_count += 1;
+        if self.full_bw_count >= FULL_BW_ROUNDS {
+            self.is_full_bw_reached = true;
+        }
+    }
+
+    /// Updates the minimum RTT and returns whether it has expired.
+    fn update_min_rtt(&mut self, sample: &AckSample) -> bool {
+        let is_expired = self
+            .min_rtt_stamp
+            .is_some_and(|stamp| sample.now - stamp > MIN_RTT_WINDOW);
+
+        if let Some(rtt) = sample.rtt {
+            if is_expired || self.min_rtt.is_none_or(|min_rtt| rtt < min_rtt) {
+                self.min_rtt = Some(rtt);
+                self.min_rtt_stamp = Some(sample.now);
+            }
+        }
+
+        is_expired
+    }
+
+    fn enter_probe_bw(&mut self, now: Instant) {
+        // Start with a phase that neither probes nor drains.
+        self.mode = Mode::ProbeBw {
+            cycle_index: 2,
+            cycle_start: now,
+        };
+    }
+
+    fn update_mode(&mut self, window: &mut CongestionWindow, sample: &AckSample) {
+        let is_round_end = self.update_round(sample);
+        let is_min_rtt_expired = self.update_min_rtt(sample);
+
+        match self.mode {
+            Mode::Startup => {
+                if is_round_end {
+                    self.check_full_bw_reached();
+                }
+                if self.is_full_bw_reached {
+                    self.mode = Mode::Drain;
+                }
+            }
+            Mode::Drain => {
+                if sample.in_flight <= self.target_cwnd(GAIN_UNIT, sample.mss) {
+                    self.enter_probe_bw(sample.now);
+                }
+            }
+            Mode::ProbeBw {
+                cycle_index,
+                cycle_start,
+            } => {
+                if self
+                    .min_rtt
+                    .is_some_and(|min_rtt| sample.now - cycle_start > min_rtt)
+                {
+                    self.mode = Mode::ProbeBw {
+                        cycle_index: (cycle_index + 1) % PROBE_BW_GAINS.len(),
+                        cycle_start: sample.now,
+                    };
+                }
+            }
+            Mode::ProbeRtt { done_at } => {
+                if sample.now >= done_at {
+                    self.min_rtt_stamp = Some(sample.now);
+                    window.cwnd = window.cwnd.max(self.prior_cwnd);
+                    if self.is_full_bw_reached {
+                        self.enter_probe_bw(sample.now);
+                    } else {
+                        self.mode = Mode::Startup;
+                    }
+                }
+                return;
+            }
+        }
+
+        if is_min_rtt_expired {
+            self.prior_cwnd = window.cwnd;
+            self.mode = Mode::ProbeRtt {
+                done_at: sample.now + PROBE_RTT_DURATION,
+            };
+        }
+    }
+}
+
+impl CongestionOps for Bbr {
+    fn on_ack(&mut self, window: &mut CongestionWindow, sample: &AckSample) {
+        self.update_mode(window, sample);
+
+        if let Mode::ProbeRtt { .. } = self.mode {
+            window.cwnd = MIN_CWND;
+            return;
+        }
+
+        let target = self.target_cwnd(self.cwnd_gain(), sample.mss);
+        let cwnd = window.cwnd.saturating_add(sample.acked);
+        window.cwnd = if self.is_full_bw_reached {
+            cwnd.min(target)
+        } else if window.cwnd < target {
+            cwnd
+        } else {
+            window.cwnd
+        };
+        window.cwnd = window.cwnd.max(MIN_CWND);
+    }
+
+    fn on_loss(&mut self, window: &mut CongestionWindow, loss: Loss, _now: Instant) {
+        // The model is kept. Only the expiration of the retransmission timer collapses the
+        // window, after which it grows back quickly towards the target.
+        if loss == Loss::Timeout {
+            window.cwnd = 1;
+        }
+    }
+}
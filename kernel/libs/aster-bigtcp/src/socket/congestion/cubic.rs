@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! CUBIC, as specified in RFC 9438.
+//!
+//! The implementation follows Linux's `tcp_cubic.c` and uses fixed-point arithmetic. HyStart is
+//! not implemented.
+
+use smoltcp::time::{Duration, Instant};
+
+use super::{AckSample, CongestionOps, CongestionWindow, Loss};
+
+const BETA_SCALE: u32 = 1024;
+/// The multiplicative decrease factor (0.7), scaled by [`BETA_SCALE`].
+const BETA: u32 = 717;
+/// Time is measured in units of `2^-TIME_SHIFT` seconds.
+const TIME_SHIFT: u32 = 10;
+/// The scaling constant `C` (0.4), scaled by 1024.
+const CUBE_RTT_SCALE: u64 = 410;
+/// The factor to compute `K = cbrt(CUBE_FACTOR * (W_max - cwnd))` in scaled time units.
+const CUBE_FACTOR: u64 = (1 << (10 + 3 * TIME_SHIFT)) / CUBE_RTT_SCALE;
+/// The factor to estimate the window of Reno, scaled by 8.
+const RENO_BETA_SCALE: u32 = 8 * (BETA_SCALE + BETA) / 3 / (BETA_SCALE - BETA);
+
+pub(super) struct Cubic {
+    /// The window size just before the last reduction.
+    last_max_cwnd: u32,
+    /// The start time of the current congestion avoidance epoch.
+    epoch_start: Option<Instant>,
+    /// The window size at the plateau of the cubic function.
+    origin_point: u32,
+    /// The time to reach the plateau, in scaled time units.
+    k: u64,
+    /// The number of ACKed segments in the current epoch, used to estimate the window of Reno.
+    ack_cnt: u32,
+    /// The estimated window of Reno.
+    tcp_cwnd: u32,
+    /// The number of ACKed segments needed to grow the window by one segment.
+    cnt: u32,
+}
+
+impl Cubic {
+    pub(super) fn new() -> Self {
+        Self {
+            last_max_cwnd: 0,
+            epoch_start: None,
+            origin_point: 0,
+            k: 0,
+            ack_cnt: 0,
+            tcp_cwnd: 0,
+            cnt: 0,
+        }
+    }
+
+    /// Computes how fast the window should grow.
+    ///
+    /// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/tcp_cubic.c#L214>.
+    fn update(&mut self, cwnd: u32, acked: u32, now: Instant, min_rtt: Option<Duration>) {
+        self.ack_cnt += acked;
+
+        let epoch_start = match self.epoch_start {
+            Some(epoch_start) => epoch_start,
+            None => {
+                self.epoch_start = Some(now);
+                self.ack_cnt = acked;
+                self.tcp_cwnd = cwnd;
+
+                if self.last_max_cwnd <= cwnd {
+                    self.k = 0;
+                    self.origin_point = cwnd;
+                } else {
+                    self.k = cube_root(CUBE_FACTOR * (self.last_max_cwnd - cwnd) as u64);
+                    self.origin_point = self.last_max_cwnd;
+                }
+
+                now
+            }
+        };
+
+        // Compute the target window one RTT ahead.
+        let elapsed = now - epoch_start + min_rtt.unwrap_or(Duration::from_micros(0));
+        let t = (elapsed.total_micros() << TIME_SHIFT) / 1_000_000;
+
+        let offs = t.abs_diff(self.k) as u128;
+        let delta = (CUBE_RTT_SCALE as u128 * offs * offs * offs) >> (10 + 3 * TIME_SHIFT);
+        let delta = u32::try_from(delta).unwrap_or(u32::MAX);
+        let target = if t < self.k {
+            self.origin_point.saturating_sub(delta)
+        } else {
+            self.origin_point.saturating_add(delta)
+        };
+
+        self.cnt = if target > cwnd {
+            cwnd / (target - cwnd)
+        } else {
+            // Grow very slowly near the plateau.
+            cwnd.saturating_mul(100)
+        };
+
+        // Grow moderately at the beginning of the connection.
+        if self.last_max_cwnd == 0 {
+            self.cnt = self.cnt.min(20);
+        }
+
+        // The window should grow at least as fast as Reno does.
+        let delta = (cwnd.saturating_mul(RENO_BETA_SCALE) >> 3).max(1);
+        while self.ack_cnt > delta {
+            self.ack_cnt -= delta;
+            self.tcp_cwnd += 1;
+        }
+        if self.tcp_cwnd > cwnd {
+            let max_cnt = cwnd / (self.tcp_cwnd - cwnd);
+            self.cnt = self.cnt.min(max_cnt);
+        }
+
+        self.cnt = self.cnt.max(2);
+    }
+}
+
+impl CongestionOps for Cubic {
+    fn on_ack(&mut self, window: &mut CongestionWindow, sample: &AckSample) {
+        if !sample.is_cwnd_limited {
+            return;
+        }
+
+        let mut acked = sample.acked;
+
+        if window.in_slow_start() {
+            acked = window.slow_start(acked);
+            if acked == 0 {
+                return;
+            }
+        }
+
+        self.update(window.cwnd, acked, sample.now, sample.min_rtt);
+        window.cong_avoid_ai(self.cnt, acked);
+    }
+
+    fn on_loss(&mut self, window: &mut CongestionWindow, loss: Loss, _now: Instant) {
+        let cwnd = window.cwnd;
+
+        self.epoch_start = None;
+
+        // Fast convergence: release bandwidth to new flows if the window keeps shrinking.
+        self.last_max_cwnd = if cwnd < self.last_max_cwnd {
+            (cwnd as u64 * (BETA_SCALE + BETA) as u64 / (2 * BETA_SCALE) as u64) as u32
+        } else {
+            cwnd
+        };
+
+        window.ssthresh = ((cwnd as u64 * BETA as u64 / BETA_SCALE as u64) as u32).max(2);
+        window.cwnd = match loss {
+            Loss::FastRetransmit => window.ssthresh,
+            Loss::Timeout => {
+                // Like Linux, start over after the retransmission timer expires.
+                *self = Self::new();
+                1
+            }
+        };
+    }
+}
+
+/// Computes the integer cube root.
+fn cube_root(a: u64) -> u64 {
+    let (mut low, mut high) = (0u64, 1u64 << 22);
+
+    while low < high {
+        let mid = (low + high + 1) / 2;
+        if (mid as u128).pow(3) <= a as u128 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    low
+}
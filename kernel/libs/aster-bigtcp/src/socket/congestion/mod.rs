@@ -0,0 +1,541 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! TCP congestion control.
+//!
+//! smoltcp only limits the data in flight by the receive window advertised by the peer. To avoid
+//! flooding the network, bigtcp observes the segments sent and the ACKs received by each TCP
+//! connection, maintains a congestion window with a pluggable algorithm, and holds back segments
+//! carrying new data when the congestion window is full.
+
+use alloc::boxed::Box;
+
+use smoltcp::{
+    time::{Duration, Instant},
+    wire::{TcpControl, TcpRepr, TcpSeqNumber},
+};
+
+mod bbr;
+mod cubic;
+mod reno;
+
+/// A TCP congestion control algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CongestionControl {
+    Reno,
+    Cubic,
+    Bbr,
+}
+
+impl CongestionControl {
+    /// All the available algorithms.
+    pub const ALL: [Self; 3] = [Self::Reno, Self::Cubic, Self::Bbr];
+
+    /// Returns the name of the algorithm, as used by `TCP_CONGESTION`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Reno => "reno",
+            Self::Cubic => "cubic",
+            Self::Bbr => "bbr",
+        }
+    }
+
+    /// Looks up an algorithm by its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|control| control.name() == name)
+    }
+
+    fn new_ops(self) -> Box<dyn CongestionOps> {
+        match self {
+            Self::Reno => Box::new(reno::Reno::new()),
+            Self::Cubic => Box::new(cubic::Cubic::new()),
+            Self::Bbr => Box::new(bbr::Bbr::new()),
+        }
+    }
+}
+
+/// The congestion avoidance state of a TCP connection.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/tcp.h#L190>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaState {
+    /// Nothing bad has been observed.
+    Open = 0,
+    /// Duplicate ACKs have been received.
+    Disorder = 1,
+    /// Fast retransmission is in progress.
+    Recovery = 3,
+    /// The retransmission timer has expired.
+    Loss = 4,
+}
+
+/// The congestion control information of a TCP connection.
+///
+/// All the window sizes are counted in segments.
+#[derive(Debug, Clone, Copy)]
+pub struct CongestionInfo {
+    pub control: CongestionControl,
+    pub ca_state: CaState,
+    pub cwnd: u32,
+    pub ssthresh: u32,
+    pub mss: u32,
+    pub srtt: Option<Duration>,
+    pub rttvar: Option<Duration>,
+    pub min_rtt: Option<Duration>,
+    pub rto: Duration,
+    pub unacked: u32,
+    pub total_retrans: u32,
+    pub bytes_acked: u64,
+    pub delivered: u32,
+}
+
+impl CongestionInfo {
+    /// Creates the information of a TCP connection that has not sent anything yet.
+    pub fn new(control: CongestionControl) -> Self {
+        TcpCongestion::new(control).info()
+    }
+}
+
+/// The initial congestion window, as specified in RFC 6928.
+const INITIAL_CWND: u32 = 10;
+/// The slow start threshold before any loss is detected.
+const INFINITE_SSTHRESH: u32 = 0x7fff_ffff;
+/// The MSS assumed before any data is sent.
+const DEFAULT_MSS: u32 = 536;
+/// The number of duplicate ACKs that trigger fast retransmission.
+const DUP_ACK_THRESHOLD: u32 = 3;
+
+const RTO_INIT: Duration = Duration::from_secs(1);
+const RTO_MIN: Duration = Duration::from_millis(200);
+const RTO_MAX: Duration = Duration::from_secs(120);
+
+/// The congestion window and the slow start threshold, in segments.
+#[derive(Debug)]
+struct CongestionWindow {
+    cwnd: u32,
+    ssthresh: u32,
+    /// The number of ACKed segments not yet used to grow the window in congestion avoidance.
+    cwnd_cnt: u32,
+}
+
+impl CongestionWindow {
+    fn in_slow_start(&self) -> bool {
+        self.cwnd < self.ssthresh
+    }
+
+    /// Grows the window by one segment per ACKed segment, up to the slow start threshold.
+    ///
+    /// Returns the number of ACKed segments left for congestion avoidance.
+    ///
+    /// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/tcp_cong.c#L455>.
+    fn slow_start(&mut self, acked: u32) -> u32 {
+        let cwnd = self.cwnd.saturating_add(acked).min(self.ssthresh);
+        let used = cwnd - self.cwnd;
+        self.cwnd = cwnd;
+        acked - used
+    }
+
+    /// Grows the window by one segment per `w` ACKed segments.
+    ///
+    /// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/tcp_cong.c#L469>.
+    fn cong_avoid_ai(&mut self, w: u32, acked: u32) {
+        let w = w.max(1);
+
+        // If credits accumulated at a higher `w`, apply them gently now.
+        if self.cwnd_cnt >= w {
+            self.cwnd_cnt = 0;
+            self.cwnd += 1;
+        }
+
+        self.cwnd_cnt += acked;
+        if self.cwnd_cnt >= w {
+            let delta = self.cwnd_cnt / w;
+            self.cwnd_cnt -= delta * w;
+            self.cwnd += delta;
+        }
+    }
+}
+
+/// The information about an ACK that acknowledges new data.
+#[derive(Debug)]
+struct AckSample {
+    now: Instant,
+    /// The number of newly ACKed segments.
+    acked: u32,
+    /// The number of segments still in flight.
+    in_flight: u32,
+    /// Whether the congestion window limited the transmission before the ACK arrived.
+    ///
+    /// Loss-based algorithms should not grow the window if it is not fully used.
+    is_cwnd_limited: bool,
+    /// The RTT sampled from this ACK, if any.
+    rtt: Option<Duration>,
+    /// The minimum RTT observed so far.
+    min_rtt: Option<Duration>,
+    /// The total number of bytes ACKed so far.
+    delivered_bytes: u64,
+    mss: u32,
+}
+
+/// How a segment loss is detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Loss {
+    /// The loss is detected by duplicate ACKs.
+    FastRetransmit,
+    /// The loss is detected by the expiration of the retransmission timer.
+    Timeout,
+}
+
+/// The hooks of a congestion control algorithm.
+///
+/// This is similar to Linux's `struct tcp_congestion_ops`.
+trait CongestionOps: Send + Sync {
+    /// Updates the window after new data is ACKed.
+    ///
+    /// This is not called during fast recovery.
+    fn on_ack(&mut self, window: &mut CongestionWindow, sample: &AckSample);
+
+    /// Updates the window after a segment loss is detected.
+    fn on_loss(&mut self, window: &mut CongestionWindow, loss: Loss, now: Instant);
+}
+
+/// The RTT estimator specified in RFC 6298.
+#[derive(Debug, Default)]
+struct RttEstimator {
+    srtt: Option<Duration>,
+    rttvar: Duration,
+    min_rtt: Option<Duration>,
+}
+
+impl RttEstimator {
+    fn on_sample(&mut self, rtt: Duration) {
+        match self.srtt {
+            None => {
+                self.srtt = Some(rtt);
+                self.rttvar = rtt / 2;
+            }
+            Some(srtt) => {
+                let diff = if srtt > rtt { srtt - rtt } else { rtt - srtt };
+                self.rttvar = (self.rttvar * 3 + diff) / 4;
+                self.srtt = Some((srtt * 7 + rtt) / 8);
+            }
+        }
+
+        if self.min_rtt.is_none_or(|min_rtt| rtt < min_rtt) {
+            self.min_rtt = Some(rtt);
+        }
+    }
+
+    fn rto(&self) -> Duration {
+        let Some(srtt) = self.srtt else {
+            return RTO_INIT;
+        };
+
+        (srtt + self.rttvar * 4).clamp(RTO_MIN, RTO_MAX)
+    }
+}
+
+/// The congestion control state of a TCP connection.
+pub(crate) struct TcpCongestion {
+    control: CongestionControl,
+    ops: Box<dyn CongestionOps>,
+    window: CongestionWindow,
+    ca_state: CaState,
+    mss: u32,
+    /// The oldest unacknowledged sequence number.
+    snd_una: Option<TcpSeqNumber>,
+    /// The sequence number next to the highest sequence number sent.
+    snd_nxt: Option<TcpSeqNumber>,
+    /// The value of `snd_nxt` when the loss recovery started.
+    high_seq: TcpSeqNumber,
+    dup_acks: u32,
+    /// Whether smoltcp is about to retransmit the first unacknowledged segment because of
+    /// duplicate ACKs.
+    is_fast_retransmit_pending: bool,
+    /// Whether the last segment with new data was held back by the congestion window.
+    is_cwnd_limited: bool,
+    last_ack_sent: Option<TcpSeqNumber>,
+    rtt: RttEstimator,
+    /// The segment being timed for an RTT sample, as its end sequence number and sending time.
+    rtt_probe: Option<(TcpSeqNumber, Instant)>,
+    total_retrans: u32,
+    bytes_acked: u64,
+    delivered: u32,
+}
+
+impl TcpCongestion {
+    pub(crate) fn new(control: CongestionControl) -> Self {
+        Self {
+            control,
+            ops: control.new_ops(),
+            window: CongestionWindow {
+                cwnd: INITIAL_CWND,
+                ssthresh: INFINITE_SSTHRESH,
+                cwnd_cnt: 0,
+            },
+            ca_state: CaState::Open,
+            mss: DEFAULT_MSS,
+            snd_una: None,
+            snd_nxt: None,
+            high_seq: TcpSeqNumber(0),
+            dup_acks: 0,
+            is_fast_retransmit_pending: false,
+            is_cwnd_limited: false,
+            last_ack_sent: None,
+            rtt: RttEstimator::default(),
+            rtt_probe: None,
+            total_retrans: 0,
+            bytes_acked: 0,
+            delivered: 0,
+        }
+    }
+
+    /// Returns the congestion control algorithm.
+    pub(crate) fn control(&self) -> CongestionControl {
+        self.control
+    }
+
+    /// Switches to another congestion control algorithm.
+    ///
+    /// The current window is kept, but the algorithm-specific state starts afresh.
+    pub(crate) fn set_control(&mut self, control: CongestionControl) {
+        if control == self.control {
+            return;
+        }
+
+        self.control = control;
+        self.ops = control.new_ops();
+        self.window.cwnd_cnt = 0;
+    }
+
+    /// Returns whether the last segment with new data was held back by the congestion window.
+    pub(crate) fn is_cwnd_limited(&self) -> bool {
+        self.is_cwnd_limited
+    }
+
+    /// Returns the retransmission timeout.
+    pub(crate) fn rto(&self) -> Duration {
+        self.rtt.rto()
+    }
+
+    pub(crate) fn info(&self) -> CongestionInfo {
+        CongestionInfo {
+            control: self.control,
+            ca_state: self.ca_state,
+            cwnd: self.window.cwnd,
+            ssthresh: self.window.ssthresh,
+            mss: self.mss,
+            srtt: self.rtt.srtt,
+            rttvar: self.rtt.srtt.map(|_| self.rtt.rttvar),
+            min_rtt: self.rtt.min_rtt,
+            rto: self.rtt.rto(),
+            unacked: self.in_flight_segments(),
+            total_retrans: self.total_retrans,
+            bytes_acked: self.bytes_acked,
+            delivered: self.delivered,
+        }
+    }
+
+    fn in_flight_bytes(&self) -> usize {
+        match (self.snd_una, self.snd_nxt) {
+            (Some(una), Some(nxt)) if nxt > una => nxt - una,
+            _ => 0,
+        }
+    }
+
+    fn in_flight_segments(&self) -> u32 {
+        self.in_flight_bytes().div_ceil(self.mss as usize) as u32
+    }
+
+    /// Checks whether an outgoing segment fits in the congestion window.
+    ///
+    /// The first unacknowledged segment can always be sent, so that retransmissions cannot be
+    /// blocked.
+    pub(crate) fn can_send(&mut self, repr: &TcpRepr) -> bool {
+        if repr.payload.is_empty() {
+            return true;
+        }
+        let Some(una) = self.snd_una else {
+            return true;
+        };
+        if repr.seq_number <= una {
+            return true;
+        }
+
+        let seq_end = repr.seq_number + repr.payload.len();
+        let cwnd_bytes = self.window.cwnd as usize * self.mss as usize;
+        if seq_end - una <= cwnd_bytes {
+            return true;
+        }
+
+        self.is_cwnd_limited = true;
+        false
+    }
+
+    /// Checks whether an outgoing segment acknowledges data that has not been acknowledged yet.
+    pub(crate) fn carries_new_ack(&self, repr: &TcpRepr) -> bool {
+        match (repr.ack_number, self.last_ack_sent) {
+            (Some(ack), Some(last_ack)) => ack > last_ack,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// Updates the state after a segment is sent.
+    pub(crate) fn on_sent(&mut self, now: Instant, repr: &TcpRepr) {
+        if repr.ack_number.is_some() {
+            self.last_ack_sent = repr.ack_number;
+        }
+
+        let seg_len = repr.segment_len();
+        if seg_len == 0 {
+            return;
+        }
+        let seq_end = repr.seq_number + seg_len;
+
+        // The SYN does not count as data, so start tracking after it.
+        if repr.control == TcpControl::Syn {
+            self.snd_una = Some(seq_end);
+            self.snd_nxt = Some(seq_end);
+            return;
+        }
+
+        self.is_cwnd_limited = false;
+
+        if self.snd_nxt.is_some_and(|nxt| seq_end <= nxt) {
+            self.on_retransmit(now, repr.seq_number);
+            return;
+        }
+
+        if self.snd_una.is_none() {
+            self.snd_una = Some(repr.seq_number);
+        }
+        self.snd_nxt = Some(seq_end);
+        self.mss = self.mss.max(repr.payload.len() as u32);
+
+        if self.rtt_probe.is_none() {
+            self.rtt_probe = Some((seq_end, now));
+        }
+    }
+
+    fn on_retransmit(&mut self, now: Instant, seq: TcpSeqNumber) {
+        self.total_retrans += 1;
+        // Karn's algorithm: RTT samples must not be taken from retransmitted segments.
+        self.rtt_probe = None;
+
+        // After a loss is detected, smoltcp restarts the transmission from the first
+        // unacknowledged segment. The other retransmitted segments are not new loss events.
+        if Some(seq) != self.snd_una {
+            return;
+        }
+
+        if self.is_fast_retransmit_pending {
+            self.is_fast_retransmit_pending = false;
+            return;
+        }
+
+        if self.ca_state == CaState::Loss {
+            // The retransmission timer expires again. Linux does not reduce the slow start
+            // threshold further in this case.
+            self.window.cwnd = 1;
+        } else {
+            self.ops.on_loss(&mut self.window, Loss::Timeout, now);
+        }
+        self.enter_recovery(CaState::Loss);
+    }
+
+    fn enter_recovery(&mut self, ca_state: CaState) {
+        self.ca_state = ca_state;
+        self.high_seq = self.snd_nxt.unwrap_or(TcpSeqNumber(0));
+        self.dup_acks = 0;
+        self.window.cwnd_cnt = 0;
+    }
+
+    /// Updates the state after a segment is received.
+    pub(crate) fn on_recv(&mut self, now: Instant, repr: &TcpRepr) {
+        let (Some(ack), Some(una), Some(nxt)) = (repr.ack_number, self.snd_una, self.snd_nxt)
+        else {
+            return;
+        };
+
+        if ack > una && ack <= nxt {
+            self.on_new_ack(now, una, ack);
+        } else if ack == una && repr.payload.is_empty() && una < nxt {
+            self.on_dup_ack(now);
+        }
+    }
+
+    fn on_new_ack(&mut self, now: Instant, una: TcpSeqNumber, ack: TcpSeqNumber) {
+        // Like Linux, consider the window as fully used in slow start if at least half of it
+        // is in flight.
+        //
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/net/tcp.h#L1356>.
+        let is_cwnd_limited = if self.window.in_slow_start() {
+            self.in_flight_segments() * 2 >= self.window.cwnd
+        } else {
+            self.is_cwnd_limited
+        };
+
+        let acked_bytes = ack - una;
+        self.snd_una = Some(ack);
+        self.dup_acks = 0;
+        self.is_fast_retransmit_pending = false;
+
+        let rtt = match self.rtt_probe {
+            Some((probe_end, sent_at)) if ack >= probe_end => {
+                self.rtt_probe = None;
+                Some(now - sent_at)
+            }
+            _ => None,
+        };
+        if let Some(rtt) = rtt {
+            self.rtt.on_sample(rtt);
+        }
+
+        let acked = acked_bytes.div_ceil(self.mss as usize) as u32;
+        self.bytes_acked += acked_bytes as u64;
+        self.delivered += acked;
+
+        match self.ca_state {
+            CaState::Open => (),
+            CaState::Disorder => self.ca_state = CaState::Open,
+            // During fast recovery, the window is kept until all the data outstanding at the time
+            // the loss was detected are ACKed.
+            CaState::Recovery if ack < self.high_seq => return,
+            // After the retransmission timer expires, the window grows again from one segment.
+            CaState::Loss if ack < self.high_seq => (),
+            CaState::Recovery | CaState::Loss => self.ca_state = CaState::Open,
+        }
+
+        let sample = AckSample {
+            now,
+            acked,
+            in_flight: self.in_flight_segments(),
+            is_cwnd_limited,
+            rtt,
+            min_rtt: self.rtt.min_rtt,
+            delivered_bytes: self.bytes_acked,
+            mss: self.mss,
+        };
+        self.ops.on_ack(&mut self.window, &sample);
+    }
+
+    fn on_dup_ack(&mut self, now: Instant) {
+        self.dup_acks += 1;
+
+        if !matches!(self.ca_state, CaState::Open | CaState::Disorder) {
+            return;
+        }
+
+        if self.dup_acks < DUP_ACK_THRESHOLD {
+            self.ca_state = CaState::Disorder;
+            return;
+        }
+
+        // smoltcp will retransmit the first unacknowledged segment.
+        self.ops
+            .on_loss(&mut self.window, Loss::FastRetransmit, now);
+        self.enter_recovery(CaState::Recovery);
+        self.is_fast_retransmit_pending = true;
+    }
+}
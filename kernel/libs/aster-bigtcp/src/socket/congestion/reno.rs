@@ -0,0 +1,44 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! TCP Reno, as specified in RFC 5681.
+
+use smoltcp::time::Instant;
+
+use super::{AckSample, CongestionOps, CongestionWindow, Loss};
+
+pub(super) struct Reno;
+
+impl Reno {
+    pub(super) fn new() -> Self {
+        Self
+    }
+}
+
+impl CongestionOps for Reno {
+    fn on_ack(&mut self, window: &mut CongestionWindow, sample: &AckSample) {
+        if !sample.is_cwnd_limited {
+            return;
+        }
+
+        let mut acked = sample.acked;
+
+        if window.in_slow_start() {
+            acked = window.slow_start(acked);
+            if acked == 0 {
+                return;
+            }
+        }
+
+        // In congestion avoidance, the window grows by one segment per RTT.
+        let cwnd = window.cwnd;
+        window.cong_avoid_ai(cwnd, acked);
+    }
+
+    fn on_loss(&mut self, window: &mut CongestionWindow, loss: Loss, _now: Instant) {
+        window.ssthresh = (window.cwnd / 2).max(2);
+        window.cwnd = match loss {
+            Loss::FastRetransmit => window.ssthresh,
+            Loss::Timeout => 1,
+        };
+    }
+}
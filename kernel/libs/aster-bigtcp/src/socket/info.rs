@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use smoltcp::wire::IpEndpoint;
+
+use super::TcpState;
+
+/// A snapshot of a TCP socket, which is used to report the socket state to the user.
+#[derive(Debug, Clone, Copy)]
+pub struct TcpSocketInfo {
+    pub local_endpoint: IpEndpoint,
+    /// The remote endpoint, which is `None` if the socket is listening.
+    pub remote_endpoint: Option<IpEndpoint>,
+    pub state: TcpState,
+    /// The number of bytes waiting to be received.
+    ///
+    /// For a listening socket, this is the number of connections waiting to be accepted.
+    pub recv_queue: usize,
+    /// The number of bytes waiting to be sent or acknowledged.
+    ///
+    /// For a listening socket, this is the maximum number of pending connections.
+    pub send_queue: usize,
+}
+
+/// A snapshot of a UDP socket, which is used to report the socket state to the user.
+#[derive(Debug, Clone, Copy)]
+pub struct UdpSocketInfo {
+    pub local_endpoint: IpEndpoint,
+    /// The number of bytes waiting to be received.
+    pub recv_queue: usize,
+    /// The number of bytes waiting to be sent.
+    pub send_queue: usize,
+}
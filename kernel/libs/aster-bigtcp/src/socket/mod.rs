@@ -1,16 +1,25 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod bound;
+mod congestion;
 mod event;
+mod info;
 mod option;
 mod unbound;
 
 pub use bound::{
-    ConnectState, NeedIfacePoll, RawTcpSocketExt, TcpConnection, TcpListener, UdpSocket,
+    ConnectState, NeedIfacePoll, RawIpSocket, RawIpSocketKind, RawTcpSocketExt, TcpConnection,
+    TcpListener, UdpSocket,
 };
-pub(crate) use bound::{TcpConnectionBg, TcpListenerBg, TcpProcessResult, UdpSocketBg};
+pub(crate) use bound::{
+    RawIpSocketBg, TcpConnectionBg, TcpListenerBg, TcpProcessResult, UdpSocketBg,
+};
+pub use congestion::{CaState, CongestionControl, CongestionInfo};
 pub use event::{SocketEventObserver, SocketEvents};
+pub use info::{TcpSocketInfo, UdpSocketInfo};
 pub use option::{RawTcpOption, RawTcpSetOption};
+pub use smoltcp::socket::tcp::State as TcpState;
 pub use unbound::{
-    RawUdpSocket, TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN, UDP_RECV_PAYLOAD_LEN, UDP_SEND_PAYLOAD_LEN,
+    RawUdpSocket, RAW_RECV_BUF_LEN, RAW_SEND_BUF_LEN, TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN,
+    UDP_RECV_PAYLOAD_LEN, UDP_SEND_PAYLOAD_LEN,
 };
@@ -2,7 +2,7 @@
 
 use smoltcp::time::Duration;
 
-use super::{unbound::RawTcpSocket, NeedIfacePoll};
+use super::{congestion::CongestionControl, unbound::RawTcpSocket, NeedIfacePoll};
 
 /// A trait defines setting socket options on a raw socket.
 pub trait RawTcpSetOption {
@@ -15,6 +15,11 @@ pub trait RawTcpSetOption {
     ///
     /// Polling the iface is _not_ required after this method succeeds.
     fn set_nagle_enabled(&self, enabled: bool);
+
+    /// Sets the congestion control algorithm.
+    ///
+    /// Polling the iface is _not_ required after this method succeeds.
+    fn set_congestion_control(&self, control: CongestionControl);
 }
 
 /// Socket options on a raw socket.
@@ -23,6 +28,8 @@ pub struct RawTcpOption {
     pub keep_alive: Option<Duration>,
     /// Whether Nagle's algorithm is enabled.
     pub is_nagle_enabled: bool,
+    /// The congestion control algorithm.
+    pub congestion_control: CongestionControl,
 }
 
 impl RawTcpOption {
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Protocol statistics.
+//!
+//! The counters follow the MIB-II definitions, which are reported in `/proc/net/snmp` on Linux.
+//!
+//! Reference: <https://www.rfc-editor.org/rfc/rfc1213#section-6>.
+//
+// FIXME: The statistics should be specific to each network namespace.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+macro_rules! define_stats {
+    (
+        $(#[$stats_attr:meta])*
+        $stats:ident, $counters:ident {
+            $( $(#[$field_attr:meta])* $field:ident, )*
+        }
+    ) => {
+        $(#[$stats_attr])*
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct $stats {
+            $( $(#[$field_attr])* pub $field: u64, )*
+        }
+
+        pub(crate) struct $counters {
+            $( pub(crate) $field: Counter, )*
+        }
+
+        impl $counters {
+            const fn new() -> Self {
+                Self {
+                    $( $field: Counter::new(), )*
+                }
+            }
+
+            fn snapshot(&self) -> $stats {
+                $stats {
+                    $( $field: self.$field.get(), )*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) struct Counter(AtomicU64);
+
+impl Counter {
+    const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    pub(crate) fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+define_stats!(
+    /// IP statistics.
+    IpStats, IpCounters {
+        /// The number of received packets.
+        in_receives,
+        /// The number of received packets discarded due to errors in their IP headers.
+        in_hdr_errors,
+        /// The number of received packets discarded because they are not destined to us.
+        in_addr_errors,
+        /// The number of received packets discarded because of an unsupported protocol.
+        in_unknown_protos,
+        /// The number of received packets delivered to the upper-layer protocols.
+        in_delivers,
+        /// The number of packets supplied by the upper-layer protocols for transmission.
+        out_requests,
+    }
+);
+
+define_stats!(
+    /// ICMP statistics.
+    IcmpStats, IcmpCounters {
+        /// The number of received messages.
+        in_msgs,
+        /// The number of received messages that have errors.
+        in_errors,
+        /// The number of received "Destination Unreachable" messages.
+        in_dest_unreachs,
+        /// The number of received "Echo Request" messages.
+        in_echos,
+        /// The number of received "Echo Reply" messages.
+        in_echo_reps,
+        /// The number of sent messages.
+        out_msgs,
+        /// The number of sent "Destination Unreachable" messages.
+        out_dest_unreachs,
+        /// The number of sent "Echo Reply" messages.
+        out_echo_reps,
+    }
+);
+
+define_stats!(
+    /// TCP statistics.
+    ///
+    /// The gauges (e.g., the number of established connections) are not included, since they are
+    /// derived from the socket table.
+    TcpStats, TcpCounters {
+        /// The number of connections that are actively opened (i.e., by `connect`).
+        active_opens,
+        /// The number of connections that are passively opened (i.e., by `listen`).
+        passive_opens,
+        /// The number of received segments.
+        in_segs,
+        /// The number of sent segments.
+        out_segs,
+        /// The number of received segments that have errors.
+        in_errs,
+        /// The number of sent segments that contain the RST flag.
+        out_rsts,
+    }
+);
+
+define_stats!(
+    /// UDP statistics.
+    UdpStats, UdpCounters {
+        /// The number of datagrams delivered to the sockets.
+        in_datagrams,
+        /// The number of received datagrams that have no sockets at the destination port.
+        no_ports,
+        /// The number of received datagrams that have errors.
+        in_errors,
+        /// The number of sent datagrams.
+        out_datagrams,
+    }
+);
+
+pub(crate) static IP_COUNTERS: IpCounters = IpCounters::new();
+pub(crate) static ICMP_COUNTERS: IcmpCounters = IcmpCounters::new();
+pub(crate) static TCP_COUNTERS: TcpCounters = TcpCounters::new();
+pub(crate) static UDP_COUNTERS: UdpCounters = UdpCounters::new();
+
+/// Returns the IP statistics.
+pub fn ip_stats() -> IpStats {
+    IP_COUNTERS.snapshot()
+}
+
+/// Returns the ICMP statistics.
+pub fn icmp_stats() -> IcmpStats {
+    ICMP_COUNTERS.snapshot()
+}
+
+/// Returns the TCP statistics.
+pub fn tcp_stats() -> TcpStats {
+    TCP_COUNTERS.snapshot()
+}
+
+/// Returns the UDP statistics.
+pub fn udp_stats() -> UdpStats {
+    UDP_COUNTERS.snapshot()
+}
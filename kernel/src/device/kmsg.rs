@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The kernel log device (i.e., `/dev/kmsg`).
+//!
+//! Each opened file reads the records in the kernel log buffer one record per `read`, in the
+//! format of `<priority>,<sequence>,<timestamp>,<flags>[,caller=<caller>];<text>\n`. The user
+//! space can also append records to the log buffer by writing to the device.
+//!
+//! Reference: <https://www.kernel.org/doc/Documentation/ABI/testing/dev-kmsg>.
+
+use alloc::format;
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use aster_logger::{
+    console_log_levels,
+    log_buffer::{self, LogRecord, USER_FACILITY},
+};
+use aster_softirq::Taskless;
+use ostd::sync::WaitQueue;
+use spin::Once;
+
+use crate::{
+    events::IoEvents,
+    fs::{
+        device::{Device, DeviceId, DeviceType},
+        inode_handle::FileIo,
+        utils::{SeekFrom, StatusFlags},
+    },
+    prelude::*,
+    process::{
+        posix_thread::AsPosixThread,
+        signal::{PollHandle, Pollable, Pollee},
+    },
+    thread::Thread,
+};
+
+pub struct Kmsg;
+
+impl Device for Kmsg {
+    fn type_(&self) -> DeviceType {
+        DeviceType::CharDevice
+    }
+
+    fn id(&self) -> DeviceId {
+        // Same value with Linux
+        DeviceId::new(1, 11)
+    }
+
+    fn open(&self) -> Result<Option<Arc<dyn FileIo>>> {
+        Ok(Some(KmsgFile::open()))
+    }
+}
+
+impl Pollable for Kmsg {
+    fn poll(&self, _mask: IoEvents, _poller: Option<&mut PollHandle>) -> IoEvents {
+        IoEvents::empty()
+    }
+}
+
+impl FileIo for Kmsg {
+    fn read(&self, _writer: &mut VmWriter, _status_flags: StatusFlags) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "cannot read kmsg device");
+    }
+
+    fn write(&self, _reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "cannot write kmsg device");
+    }
+}
+
+/// An opened kernel log device.
+struct KmsgFile {
+    /// The sequence number of the next record to read.
+    seq: Mutex<u64>,
+    pollee: Pollee,
+}
+
+impl KmsgFile {
+    fn open() -> Arc<Self> {
+        let file = Arc::new(Self {
+            seq: Mutex::new(log_buffer::first_seq()),
+            pollee: Pollee::new(),
+        });
+
+        let mut readers = READERS.disable_irq().lock();
+        readers.retain(|reader| reader.strong_count() > 0);
+        readers.push(Arc::downgrade(&file));
+
+        file
+    }
+
+    fn try_read(&self, writer: &mut VmWriter) -> Result<usize> {
+        let mut seq = self.seq.lock();
+
+        let first_seq = log_buffer::first_seq();
+        if *seq < first_seq {
+            // Like Linux, report that some records are lost once, and then continue with the
+            // oldest record available.
+            *seq = first_seq;
+            return_errno_with_message!(Errno::EPIPE, "the records have been overwritten");
+        }
+
+        let Some(record) = log_buffer::read_record(*seq) else {
+            return_errno_with_message!(Errno::EAGAIN, "no records are available");
+        };
+
+        let text = format_record(&record);
+        if writer.avail() < text.len() {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small for the record");
+        }
+        writer.write_fallible(&mut VmReader::from(text.as_bytes()).to_fallible())?;
+
+        *seq += 1;
+        self.pollee.invalidate();
+
+        Ok(text.len())
+    }
+
+    fn check_io_events(&self) -> IoEvents {
+        if *self.seq.lock() < log_buffer::next_seq() {
+            IoEvents::IN
+        } else {
+            IoEvents::empty()
+        }
+    }
+}
+
+impl Pollable for KmsgFile {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut PollHandle>) -> IoEvents {
+        self.pollee
+            .poll_with(mask, poller, || self.check_io_events())
+    }
+}
+
+impl FileIo for KmsgFile {
+    fn read(&self, writer: &mut VmWriter, status_flags: StatusFlags) -> Result<usize> {
+        if status_flags.contains(StatusFlags::O_NONBLOCK) {
+            self.try_read(writer)
+        } else {
+            self.wait_events(IoEvents::IN, None, || self.try_read(writer))
+        }
+    }
+
+    fn write(&self, reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/kernel/printk/printk.c#L749>.
+        const MAX_RECORD_LEN: usize = 1024;
+
+        let len = reader.remain();
+        if len > MAX_RECORD_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the record is too long");
+        }
+
+        let mut buffer = vec![0u8; len];
+        reader.read_fallible(&mut VmWriter::from(buffer.as_mut_slice()))?;
+
+        let (facility, level, text) = parse_user_record(&buffer);
+        log_buffer::append(facility, level, &String::from_utf8_lossy(text));
+
+        Ok(len)
+    }
+
+    fn seek(&self, pos: SeekFrom) -> Option<Result<usize>> {
+        let (offset, seq) = match pos {
+            SeekFrom::Start(offset) => (offset as isize, log_buffer::first_seq()),
+            SeekFrom::End(offset) => (offset, log_buffer::next_seq()),
+            // Seek to the first record after the last clear (e.g., by `dmesg -c`).
+            SeekFrom::Data(offset) => (offset as isize, clear_seq()),
+            SeekFrom::Current(_) | SeekFrom::Hole(_) => {
+                return Some(Err(Error::with_message(
+                    Errno::EINVAL,
+                    "the seek operation is not supported",
+                )))
+            }
+        };
+        if offset != 0 {
+            return Some(Err(Error::with_message(
+                Errno::ESPIPE,
+                "the offset must be zero",
+            )));
+        }
+
+        *self.seq.lock() = seq;
+        self.pollee.invalidate();
+
+        Some(Ok(0))
+    }
+}
+
+/// Formats a record in the format of `/dev/kmsg`.
+fn format_record(record: &LogRecord) -> String {
+    let caller = match record.task() {
+        Some(tid) => format!("T{}", tid),
+        None => format!("C{}", record.cpu()),
+    };
+    let mut text = format!(
+        "{},{},{},-,caller={};",
+        record.priority(),
+        record.seq(),
+        record.timestamp().as_micros(),
+        caller
+    );
+
+    // Like Linux, escape the non-printable characters and the backslashes in the text.
+    for byte in record.text().bytes() {
+        if byte < b' ' || byte >= 0x7f || byte == b'\\' {
+            let _ = write!(text, "\\x{:02x}", byte);
+        } else {
+            text.push(byte as char);
+        }
+    }
+    text.push('\n');
+
+    text
+}
+
+/// Parses a record written by the user space.
+///
+/// The record may start with a `<priority>` prefix. Otherwise, the record will have the
+/// `LOG_USER` facility and the default message log level.
+fn parse_user_record(record: &[u8]) -> (u8, u8, &[u8]) {
+    let record = record.strip_suffix(b"\n").unwrap_or(record);
+
+    let mut facility = USER_FACILITY;
+    let mut level = console_log_levels().default_message_loglevel();
+    let mut text = record;
+
+    if let Some(rest) = record.strip_prefix(b"<") {
+        let digits = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+        let priority = core::str::from_utf8(&rest[..digits])
+            .ok()
+            .and_then(|digits| digits.parse::<u32>().ok());
+        if let Some(priority) = priority
+            && rest.get(digits) == Some(&b'>')
+        {
+            level = (priority & 7) as u8;
+            if (priority & 0x3f8) != 0 {
+                facility = ((priority & 0x3f8) >> 3) as u8;
+            }
+            text = &rest[digits + 1..];
+        }
+    }
+
+    (facility, level, text)
+}
+
+/// Returns the sequence number of the first record after the log buffer is last cleared.
+pub fn clear_seq() -> u64 {
+    CLEAR_SEQ.load(Ordering::Relaxed)
+}
+
+/// Clears the records before `seq` for the subsequent `SEEK_DATA` operations and the `syslog`
+/// reads.
+///
+/// The records are not actually removed from the log buffer.
+pub fn set_clear_seq(seq: u64) {
+    CLEAR_SEQ.store(seq, Ordering::Relaxed);
+}
+
+/// Returns the wait queue that is woken up when new records are appended.
+pub fn record_wait_queue() -> &'static WaitQueue {
+    &RECORD_WAIT_QUEUE
+}
+
+static CLEAR_SEQ: AtomicU64 = AtomicU64::new(0);
+
+static RECORD_WAIT_QUEUE: WaitQueue = WaitQueue::new();
+
+static READERS: SpinLock<Vec<Weak<KmsgFile>>> = SpinLock::new(Vec::new());
+
+static NOTIFIER: Once<Arc<Taskless>> = Once::new();
+
+pub(super) fn init() {
+    aster_logger::log_buffer::inject_task_id_getter(|| {
+        let thread = Thread::current()?;
+        Some(thread.as_posix_thread()?.tid())
+    });
+
+    // The handler can be called in any context, so the readers are notified later in the
+    // softirq context.
+    NOTIFIER.call_once(|| {
+        Taskless::new(|| {
+            let readers = READERS.disable_irq().lock();
+            for reader in readers.iter().filter_map(Weak::upgrade) {
+                reader.pollee.notify(IoEvents::IN);
+            }
+            drop(readers);
+
+            RECORD_WAIT_QUEUE.wake_all();
+        })
+    });
+    aster_logger::log_buffer::inject_append_handler(|| {
+        if let Some(notifier) = NOTIFIER.get() {
+            notifier.schedule();
+        }
+    });
+}
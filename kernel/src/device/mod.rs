@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
+pub mod kmsg;
 mod null;
 mod pty;
 mod random;
+mod rtc;
 mod shm;
 pub mod tty;
 mod urandom;
@@ -42,6 +44,15 @@ pub fn init() -> Result<()> {
         add_node(tty.clone(), &format!("tty{}", index))?;
     }
 
+    for tty in tty::iter_vt_tty() {
+        add_node(tty.clone(), &format!("tty{}", tty.index()))?;
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    for tty in tty::iter_serial_tty() {
+        add_node(tty.clone(), &format!("ttyS{}", tty.index()))?;
+    }
+
     #[cfg(target_arch = "x86_64")]
     ostd::if_tdx_enabled!({
         add_node(Arc::new(tdxguest::TdxGuest), "tdx_guest")?;
@@ -50,9 +61,14 @@ pub fn init() -> Result<()> {
     let random = Arc::new(random::Random);
     add_node(random, "random")?;
 
+    kmsg::init();
+    add_node(Arc::new(kmsg::Kmsg), "kmsg")?;
+
     let urandom = Arc::new(urandom::Urandom);
     add_node(urandom, "urandom")?;
 
+    add_node(Arc::new(rtc::Rtc), "rtc0")?;
+
     pty::init()?;
 
     shm::init()?;
@@ -79,6 +95,7 @@ pub fn get_device(dev: usize) -> Result<Arc<dyn Device>> {
         (5, 0) => Ok(Arc::new(tty::TtyDevice)),
         (1, 8) => Ok(Arc::new(random::Random)),
         (1, 9) => Ok(Arc::new(urandom::Urandom)),
+        (1, 11) => Ok(Arc::new(kmsg::Kmsg)),
         _ => return_errno_with_message!(Errno::EINVAL, "unsupported device"),
     }
 }
@@ -5,7 +5,7 @@
 use super::*;
 use crate::{
     events::IoEvents,
-    fs::inode_handle::FileIo,
+    fs::{inode_handle::FileIo, utils::StatusFlags},
     prelude::*,
     process::signal::{PollHandle, Pollable},
 };
@@ -35,11 +35,11 @@ impl Pollable for Null {
 }
 
 impl FileIo for Null {
-    fn read(&self, _writer: &mut VmWriter) -> Result<usize> {
+    fn read(&self, _writer: &mut VmWriter, _status_flags: StatusFlags) -> Result<usize> {
         Ok(0)
     }
 
-    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+    fn write(&self, reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
         Ok(reader.remain())
     }
 }
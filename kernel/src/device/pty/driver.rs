@@ -3,8 +3,9 @@
 use ostd::sync::SpinLock;
 
 use crate::{
-    device::tty::{PushCharError, Tty, TtyDriver},
+    device::tty::{termio::CTermios, PushCharError, Tty, TtyDriver},
     events::IoEvents,
+    fs::utils::IoctlCmd,
     prelude::{return_errno_with_message, Errno, Result},
     process::signal::Pollee,
     util::ring_buffer::RingBuffer,
@@ -62,23 +63,13 @@ impl TtyDriver for PtyDriver {
     fn push_output(&self, chs: &[u8]) -> core::result::Result<usize, PushCharError> {
         let mut output = self.output.lock();
 
-        let mut len = 0;
-        for ch in chs {
-            // TODO: This is termios-specific behavior and should be part of the TTY implementation
-            // instead of the TTY driver implementation. See the ONLCR flag for more details.
-            if *ch == b'\n' && output.capacity() - output.len() >= 2 {
-                output.push(b'\r').unwrap();
-                output.push(b'\n').unwrap();
-            } else if *ch != b'\n' && !output.is_full() {
-                output.push(*ch).unwrap();
-            } else if len == 0 {
-                return Err(PushCharError);
-            } else {
-                break;
-            }
-            len += 1;
+        if output.is_full() {
+            return Err(PushCharError);
         }
 
+        let len = output.free_len().min(chs.len());
+        output.push_slice(&chs[..len]).unwrap();
+
         self.pollee.notify(IoEvents::IN);
         Ok(len)
     }
@@ -104,12 +95,27 @@ impl TtyDriver for PtyDriver {
         }
     }
 
-    fn can_push(&self) -> bool {
-        let output = self.output.lock();
-        output.capacity() - output.len() >= 2
+    fn write_room(&self) -> usize {
+        self.output.lock().free_len()
+    }
+
+    fn chars_in_buffer(&self) -> usize {
+        // Following Linux, the characters are considered to be sent once they are in the buffer,
+        // so waiting for the output to be sent never blocks.
+        0
     }
 
     fn notify_input(&self) {
         self.pollee.notify(IoEvents::OUT);
     }
+
+    fn on_termios_change(&self, _old_termios: &CTermios, _new_termios: &CTermios) {}
+
+    fn set_break(&self, _is_on: bool) -> bool {
+        false
+    }
+
+    fn ioctl(&self, _cmd: IoctlCmd, _arg: usize) -> Result<Option<i32>> {
+        Ok(None)
+    }
 }
@@ -0,0 +1,598 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The RTC device (i.e., `/dev/rtc0`).
+//!
+//! The time is read from and written to the RTC hardware via the RTC driver. However, we do not
+//! handle the RTC interrupt. The interrupts that can be observed by the user space (i.e., the
+//! alarm interrupt, the update interrupt, and the periodic interrupt) are emulated with kernel
+//! timers, which is similar to what Linux does with `CONFIG_RTC_INTF_DEV_UIE_EMUL`.
+//!
+//! Reference: <https://docs.kernel.org/admin-guide/rtc.html>.
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use spin::Once;
+use time::{Date, Month, PrimitiveDateTime, Time};
+
+use crate::{
+    current_userspace,
+    events::IoEvents,
+    fs::{
+        device::{Device, DeviceId, DeviceType},
+        inode_handle::FileIo,
+        utils::{IoctlCmd, StatusFlags},
+    },
+    prelude::*,
+    process::{
+        credentials::capabilities::CapSet,
+        posix_thread::AsPosixThread,
+        signal::{PollHandle, Pollable, Pollee},
+    },
+    time::{clocks::MonotonicClock, timer::Timeout, Timer},
+};
+
+pub struct Rtc;
+
+impl Device for Rtc {
+    fn type_(&self) -> DeviceType {
+        DeviceType::CharDevice
+    }
+
+    fn id(&self) -> DeviceId {
+        // Linux allocates the major number dynamically. This is the number that is usually
+        // allocated on x86-64 machines.
+        DeviceId::new(253, 0)
+    }
+
+    fn open(&self) -> Result<Option<Arc<dyn FileIo>>> {
+        Ok(Some(Arc::new(RtcFile::open()?)))
+    }
+}
+
+impl Pollable for Rtc {
+    fn poll(&self, _mask: IoEvents, _poller: Option<&mut PollHandle>) -> IoEvents {
+        IoEvents::empty()
+    }
+}
+
+impl FileIo for Rtc {
+    fn read(&self, _writer: &mut VmWriter, _status_flags: StatusFlags) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "cannot read RTC device");
+    }
+
+    fn write(&self, _reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "cannot write RTC device");
+    }
+}
+
+/// An opened RTC device.
+///
+/// The RTC device can be opened only once at a time.
+struct RtcFile {
+    state: &'static RtcState,
+}
+
+impl RtcFile {
+    fn open() -> Result<Self> {
+        let state = RTC_STATE.call_once(RtcState::new);
+
+        if state.is_opened.swap(true, Ordering::Acquire) {
+            return_errno_with_message!(Errno::EBUSY, "the RTC device is already opened");
+        }
+
+        state.irq.disable_irq().lock().clear_data();
+
+        Ok(Self { state })
+    }
+
+    fn try_read(&self, writer: &mut VmWriter) -> Result<usize> {
+        let data = self.state.irq.disable_irq().lock().take_data();
+        if data == 0 {
+            return_errno_with_message!(Errno::EAGAIN, "no interrupts have occurred");
+        }
+
+        // The data is an `unsigned long`, but `unsigned int` is also accepted.
+        if writer.avail() == size_of::<u32>() {
+            writer.write_val(&(data as u32))?;
+            Ok(size_of::<u32>())
+        } else {
+            writer.write_val(&data)?;
+            Ok(size_of::<u64>())
+        }
+    }
+}
+
+impl Drop for RtcFile {
+    fn drop(&mut self) {
+        // Like Linux, the update interrupt and the periodic interrupt are disabled when the
+        // device is closed, but the alarm interrupt is kept.
+        let mut irq = self.state.irq.disable_irq().lock();
+        irq.is_uie_enabled = false;
+        self.state.update_timer.cancel();
+        irq.is_pie_enabled = false;
+        self.state.periodic_timer.cancel();
+        drop(irq);
+
+        self.state.is_opened.store(false, Ordering::Release);
+    }
+}
+
+impl Pollable for RtcFile {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut PollHandle>) -> IoEvents {
+        self.state
+            .pollee
+            .poll_with(mask, poller, || self.state.check_io_events())
+    }
+}
+
+impl FileIo for RtcFile {
+    fn read(&self, writer: &mut VmWriter, _status_flags: StatusFlags) -> Result<usize> {
+        let avail = writer.avail();
+        if avail != size_of::<u32>() && avail < size_of::<u64>() {
+            return_errno_with_message!(Errno::EINVAL, "the buffer is too small");
+        }
+
+        self.wait_events(IoEvents::IN, None, || self.try_read(writer))
+    }
+
+    fn write(&self, _reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
+        return_errno_with_message!(Errno::EINVAL, "the RTC device cannot be written");
+    }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
+        match cmd {
+            IoctlCmd::RTC_RD_TIME => {
+                let now = read_rtc()?;
+                current_userspace!().write_val(arg, &RtcTime::from(now))?;
+            }
+            IoctlCmd::RTC_SET_TIME => {
+                check_sys_time()?;
+                let rtc_time: RtcTime = current_userspace!().read_val(arg)?;
+                write_rtc(rtc_time.try_into()?);
+                // The alarm must be rescheduled since the time has changed.
+                self.state.rearm_alarm()?;
+            }
+            IoctlCmd::RTC_ALM_SET => {
+                let rtc_time: RtcTime = current_userspace!().read_val(arg)?;
+                let Some(time_of_day) = rtc_time.time_of_day() else {
+                    return_errno_with_message!(Errno::EINVAL, "the alarm time is invalid");
+                };
+
+                // Only the hour, the minute, and the second are used. The alarm goes off at the
+                // next occurrence of the time.
+                let now = read_rtc()?;
+                let mut alarm = now.replace_time(time_of_day);
+                if alarm < now {
+                    alarm += time::Duration::DAY;
+                }
+
+                self.state.set_alarm(alarm, false)?;
+            }
+            IoctlCmd::RTC_ALM_READ => {
+                let alarm = self.state.irq.disable_irq().lock().alarm;
+                let Some(time) = alarm.time else {
+                    return_errno_with_message!(Errno::EINVAL, "the alarm is not set");
+                };
+                current_userspace!().write_val(arg, &RtcTime::from(time))?;
+            }
+            IoctlCmd::RTC_WKALM_SET => {
+                let wkalrm: RtcWkalrm = current_userspace!().read_val(arg)?;
+                self.state
+                    .set_alarm(wkalrm.time.try_into()?, wkalrm.enabled != 0)?;
+            }
+            IoctlCmd::RTC_WKALM_RD => {
+                let alarm = self.state.irq.disable_irq().lock().alarm;
+                let wkalrm = RtcWkalrm {
+                    enabled: alarm.is_enabled as u8,
+                    pending: alarm.is_pending as u8,
+                    _padding: [0; 2],
+                    time: alarm
+                        .time
+                        .map(RtcTime::from)
+                        .unwrap_or_else(RtcTime::new_zeroed),
+                };
+                current_userspace!().write_val(arg, &wkalrm)?;
+            }
+            IoctlCmd::RTC_AIE_ON => self.state.enable_alarm(true)?,
+            IoctlCmd::RTC_AIE_OFF => self.state.enable_alarm(false)?,
+            IoctlCmd::RTC_UIE_ON => self.state.enable_update_irq(true)?,
+            IoctlCmd::RTC_UIE_OFF => self.state.enable_update_irq(false)?,
+            IoctlCmd::RTC_PIE_ON => self.state.enable_periodic_irq(true),
+            IoctlCmd::RTC_PIE_OFF => self.state.enable_periodic_irq(false),
+            IoctlCmd::RTC_IRQP_READ => {
+                let freq = self.state.irq.disable_irq().lock().periodic_freq;
+                current_userspace!().write_val(arg, &freq)?;
+            }
+            IoctlCmd::RTC_IRQP_SET => self.state.set_periodic_freq(arg as u64)?,
+            _ => return_errno_with_message!(Errno::EINVAL, "the ioctl command is unknown"),
+        }
+
+        Ok(0)
+    }
+}
+
+static RTC_STATE: Once<RtcState> = Once::new();
+
+/// The flag indicating that interrupts have occurred.
+const RTC_IRQF: u64 = 0x80;
+/// The flag indicating that periodic interrupts have occurred.
+const RTC_PF: u64 = 0x40;
+/// The flag indicating that alarm interrupts have occurred.
+const RTC_AF: u64 = 0x20;
+/// The flag indicating that update interrupts have occurred.
+const RTC_UF: u64 = 0x10;
+
+/// The maximum frequency of the periodic interrupt.
+const RTC_MAX_FREQ: u64 = 8192;
+/// The maximum frequency of the periodic interrupt that can be set without `CAP_SYS_RESOURCE`.
+///
+/// This is the default value of `/sys/class/rtc/rtc0/max_user_freq` in Linux.
+const MAX_USER_FREQ: u64 = 64;
+
+/// The state of the RTC device, which outlives the opened files.
+struct RtcState {
+    is_opened: AtomicBool,
+    irq: SpinLock<IrqState>,
+    pollee: Pollee,
+    alarm_timer: Arc<Timer>,
+    update_timer: Arc<Timer>,
+    periodic_timer: Arc<Timer>,
+}
+
+struct IrqState {
+    /// The number of interrupts that have occurred since the last read.
+    count: u64,
+    /// The flags (e.g., [`RTC_AF`]) of the interrupts that have occurred since the last read.
+    flags: u64,
+    alarm: Alarm,
+    is_uie_enabled: bool,
+    is_pie_enabled: bool,
+    periodic_freq: u64,
+}
+
+#[derive(Clone, Copy)]
+struct Alarm {
+    time: Option<PrimitiveDateTime>,
+    is_enabled: bool,
+    /// Whether the alarm has gone off since it was last set.
+    is_pending: bool,
+}
+
+impl RtcState {
+    fn new() -> Self {
+        let timer_manager = MonotonicClock::timer_manager();
+
+        Self {
+            is_opened: AtomicBool::new(false),
+            irq: SpinLock::new(IrqState {
+                count: 0,
+                flags: 0,
+                alarm: Alarm {
+                    time: None,
+                    is_enabled: false,
+                    is_pending: false,
+                },
+                is_uie_enabled: false,
+                is_pie_enabled: false,
+                periodic_freq: 1,
+            }),
+            pollee: Pollee::new(),
+            alarm_timer: timer_manager.create_timer(|| RTC_STATE.get().unwrap().on_alarm()),
+            update_timer: timer_manager.create_timer(|| {
+                RTC_STATE
+                    .get()
+                    .unwrap()
+                    .on_irq(|irq| irq.is_uie_enabled, RTC_UF)
+            }),
+            periodic_timer: timer_manager.create_timer(|| {
+                RTC_STATE
+                    .get()
+                    .unwrap()
+                    .on_irq(|irq| irq.is_pie_enabled, RTC_PF)
+            }),
+        }
+    }
+
+    fn check_io_events(&self) -> IoEvents {
+        if self.irq.disable_irq().lock().count != 0 {
+            IoEvents::IN
+        } else {
+            IoEvents::empty()
+        }
+    }
+
+    fn on_irq(&self, is_enabled: impl FnOnce(&IrqState) -> bool, flag: u64) {
+        let mut irq = self.irq.disable_irq().lock();
+        // The timer may go off after the interrupt is disabled.
+        if !is_enabled(&irq) {
+            return;
+        }
+        irq.add_data(flag);
+        drop(irq);
+
+        self.pollee.notify(IoEvents::IN);
+    }
+
+    fn on_alarm(&self) {
+        let mut irq = self.irq.disable_irq().lock();
+        if !irq.alarm.is_enabled {
+            return;
+        }
+        irq.alarm.is_enabled = false;
+        irq.alarm.is_pending = true;
+        irq.add_data(RTC_AF);
+        drop(irq);
+
+        self.pollee.notify(IoEvents::IN);
+    }
+
+    fn set_alarm(&self, time: PrimitiveDateTime, is_enabled: bool) -> Result<()> {
+        let now = read_rtc()?;
+
+        let mut irq = self.irq.disable_irq().lock();
+        irq.alarm = Alarm {
+            time: Some(time),
+            is_enabled,
+            is_pending: false,
+        };
+        self.arm_alarm_timer(&irq.alarm, now);
+        drop(irq);
+
+        aster_time::set_alarm(Some(to_aster_time(time)));
+
+        Ok(())
+    }
+
+    fn enable_alarm(&self, is_enabled: bool) -> Result<()> {
+        let now = read_rtc()?;
+
+        let mut irq = self.irq.disable_irq().lock();
+        if is_enabled && irq.alarm.time.is_none() {
+            return_errno_with_message!(Errno::EINVAL, "the alarm is not set");
+        }
+        irq.alarm.is_enabled = is_enabled;
+        self.arm_alarm_timer(&irq.alarm, now);
+
+        Ok(())
+    }
+
+    /// Reschedules the alarm after the time of the RTC is changed.
+    fn rearm_alarm(&self) -> Result<()> {
+        let now = read_rtc()?;
+
+        let irq = self.irq.disable_irq().lock();
+        self.arm_alarm_timer(&irq.alarm, now);
+
+        Ok(())
+    }
+
+    fn arm_alarm_timer(&self, alarm: &Alarm, now: PrimitiveDateTime) {
+        let Some(time) = alarm.time.filter(|_| alarm.is_enabled) else {
+            self.alarm_timer.cancel();
+            return;
+        };
+
+        // If the alarm time has passed, the alarm goes off immediately.
+        let delay = Duration::try_from(time - now).unwrap_or(Duration::ZERO);
+        self.alarm_timer.set_timeout(Timeout::After(delay));
+    }
+
+    fn enable_update_irq(&self, is_enabled: bool) -> Result<()> {
+        let now = read_rtc()?;
+
+        let mut irq = self.irq.disable_irq().lock();
+        if irq.is_uie_enabled == is_enabled {
+            return Ok(());
+        }
+        irq.is_uie_enabled = is_enabled;
+
+        if is_enabled {
+            // Align the update interrupts with the second boundaries of the RTC.
+            let delay = Duration::from_secs(1) - Duration::from_nanos(now.nanosecond() as u64);
+            self.update_timer.set_interval(Duration::from_secs(1));
+            self.update_timer.set_timeout(Timeout::After(delay));
+        } else {
+            self.update_timer.cancel();
+        }
+
+        Ok(())
+    }
+
+    fn enable_periodic_irq(&self, is_enabled: bool) {
+        let mut irq = self.irq.disable_irq().lock();
+        if irq.is_pie_enabled == is_enabled {
+            return;
+        }
+        irq.is_pie_enabled = is_enabled;
+
+        if is_enabled {
+            self.arm_periodic_timer(irq.periodic_freq);
+        } else {
+            self.periodic_timer.cancel();
+        }
+    }
+
+    fn set_periodic_freq(&self, freq: u64) -> Result<()> {
+        if freq == 0 || freq > RTC_MAX_FREQ {
+            return_errno_with_message!(Errno::EINVAL, "the frequency is out of range");
+        }
+        if freq > MAX_USER_FREQ {
+            let current_thread = current_thread!();
+            let posix_thread = current_thread.as_posix_thread().unwrap();
+            if !posix_thread
+                .credentials()
+                .effective_capset()
+                .contains(CapSet::SYS_RESOURCE)
+            {
+                return_errno_with_message!(
+                    Errno::EACCES,
+                    "setting high frequencies requires `CAP_SYS_RESOURCE`"
+                );
+            }
+        }
+
+        let mut irq = self.irq.disable_irq().lock();
+        irq.periodic_freq = freq;
+        if irq.is_pie_enabled {
+            self.arm_periodic_timer(freq);
+        }
+
+        Ok(())
+    }
+
+    fn arm_periodic_timer(&self, freq: u64) {
+        let period = Duration::from_secs(1) / freq as u32;
+        self.periodic_timer.set_interval(period);
+        self.periodic_timer.set_timeout(Timeout::After(period));
+    }
+}
+
+impl IrqState {
+    fn add_data(&mut self, flag: u64) {
+        self.count += 1;
+        self.flags |= RTC_IRQF | flag;
+    }
+
+    /// Takes the data that is returned by `read`.
+    ///
+    /// The data contains the number of interrupts in the high bytes and the flags in the lowest
+    /// byte. It is zero if no interrupts have occurred.
+    fn take_data(&mut self) -> u64 {
+        let data = (self.count << 8) | self.flags;
+        self.clear_data();
+        data
+    }
+
+    fn clear_data(&mut self) {
+        self.count = 0;
+        self.flags = 0;
+    }
+}
+
+fn check_sys_time() -> Result<()> {
+    let current_thread = current_thread!();
+    let posix_thread = current_thread.as_posix_thread().unwrap();
+    if !posix_thread
+        .credentials()
+        .effective_capset()
+        .contains(CapSet::SYS_TIME)
+    {
+        return_errno_with_message!(Errno::EPERM, "setting the RTC requires `CAP_SYS_TIME`");
+    }
+
+    Ok(())
+}
+
+fn read_rtc() -> Result<PrimitiveDateTime> {
+    let time = aster_time::read();
+
+    let date = Month::try_from(time.month)
+        .ok()
+        .and_then(|month| Date::from_calendar_date(time.year as i32, month, time.day).ok());
+    let time_ = Time::from_hms_nano(time.hour, time.minute, time.second, time.nanos as u32).ok();
+
+    match (date, time_) {
+        (Some(date), Some(time_)) => Ok(PrimitiveDateTime::new(date, time_)),
+        _ => return_errno_with_message!(Errno::EIO, "the RTC contains an invalid time"),
+    }
+}
+
+fn write_rtc(time: PrimitiveDateTime) {
+    aster_time::write(to_aster_time(time));
+}
+
+fn to_aster_time(time: PrimitiveDateTime) -> aster_time::SystemTime {
+    aster_time::SystemTime {
+        year: time.year() as u16,
+        month: time.month() as u8,
+        day: time.day(),
+        hour: time.hour(),
+        minute: time.minute(),
+        second: time.second(),
+        nanos: 0,
+    }
+}
+
+/// The time of the RTC, i.e., `struct rtc_time` in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/rtc.h#L21>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct RtcTime {
+    tm_sec: i32,
+    tm_min: i32,
+    tm_hour: i32,
+    tm_mday: i32,
+    /// The month in the range of 0 to 11.
+    tm_mon: i32,
+    /// The number of years since 1900.
+    tm_year: i32,
+    tm_wday: i32,
+    tm_yday: i32,
+    tm_isdst: i32,
+}
+
+/// The wakeup alarm of the RTC, i.e., `struct rtc_wkalrm` in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/rtc.h#L37>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct RtcWkalrm {
+    enabled: u8,
+    pending: u8,
+    _padding: [u8; 2],
+    time: RtcTime,
+}
+
+impl RtcTime {
+    /// Returns the time of the day, ignoring the date.
+    fn time_of_day(&self) -> Option<Time> {
+        let hour = u8::try_from(self.tm_hour).ok()?;
+        let minute = u8::try_from(self.tm_min).ok()?;
+        let second = u8::try_from(self.tm_sec).ok()?;
+        Time::from_hms(hour, minute, second).ok()
+    }
+}
+
+impl From<PrimitiveDateTime> for RtcTime {
+    fn from(time: PrimitiveDateTime) -> Self {
+        Self {
+            tm_sec: time.second() as i32,
+            tm_min: time.minute() as i32,
+            tm_hour: time.hour() as i32,
+            tm_mday: time.day() as i32,
+            tm_mon: time.month() as i32 - 1,
+            tm_year: time.year() - 1900,
+            tm_wday: time.weekday().number_days_from_sunday() as i32,
+            tm_yday: time.ordinal() as i32 - 1,
+            tm_isdst: 0,
+        }
+    }
+}
+
+impl TryFrom<RtcTime> for PrimitiveDateTime {
+    type Error = Error;
+
+    fn try_from(time: RtcTime) -> Result<Self> {
+        // The RTC cannot hold the time before the Unix epoch.
+        if !(70..=9999 - 1900).contains(&time.tm_year) {
+            return_errno_with_message!(Errno::EINVAL, "the year is out of range");
+        }
+
+        let date = u8::try_from(time.tm_mon + 1)
+            .ok()
+            .and_then(|month| Month::try_from(month).ok())
+            .and_then(|month| {
+                let day = u8::try_from(time.tm_mday).ok()?;
+                Date::from_calendar_date(time.tm_year + 1900, month, day).ok()
+            });
+
+        match (date, time.time_of_day()) {
+            (Some(date), Some(time_)) => Ok(PrimitiveDateTime::new(date, time_)),
+            _ => return_errno_with_message!(Errno::EINVAL, "the time is invalid"),
+        }
+    }
+}
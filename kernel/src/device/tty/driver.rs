@@ -1,6 +1,10 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::prelude::{Errno, Error};
+use super::termio::CTermios;
+use crate::{
+    fs::{device::DeviceId, utils::IoctlCmd},
+    prelude::{Errno, Error, Result},
+};
 
 /// An error indicating that no characters can be pushed because the buffer is full.
 #[derive(Debug, Clone, Copy)]
@@ -36,10 +40,16 @@ pub trait TtyDriver: Send + Sync + 'static {
     /// During this time, calls to other methods such as [`Self::push_output`] may cause deadlocks.
     fn echo_callback(&self) -> impl FnMut(&[u8]) + '_;
 
-    /// Returns whether new characters can be pushed into the output buffer.
+    /// Returns the number of bytes that can be pushed into the output buffer.
     ///
-    /// This method should return `false` if the output buffer is full.
-    fn can_push(&self) -> bool;
+    /// A subsequent call to [`Self::push_output`] should accept at least this number of bytes.
+    fn write_room(&self) -> usize;
+
+    /// Returns the number of bytes in the output buffer that have not been sent.
+    ///
+    /// If the driver sends the characters synchronously or cannot tell how many characters are
+    /// pending, this method should return zero.
+    fn chars_in_buffer(&self) -> usize;
 
     /// Notifies that the input buffer now has room for new characters.
     ///
@@ -48,4 +58,30 @@ pub trait TtyDriver: Send + Sync + 'static {
     ///
     /// [`Tty::can_push`]: super::Tty::can_push
     fn notify_input(&self);
+
+    /// Notifies that the termios has been changed from `old_termios` to `new_termios`.
+    ///
+    /// Drivers of hardware devices can use this to program the line settings (e.g., the baud
+    /// rate, the parity, and the stop bits) described by the control flags.
+    fn on_termios_change(&self, old_termios: &CTermios, new_termios: &CTermios);
+
+    /// Starts sending a break if `is_on` is true, or stops sending a break otherwise.
+    ///
+    /// This method returns `false` if the driver does not support sending breaks. In this case,
+    /// the break requests are silently ignored, as what Linux does.
+    fn set_break(&self, is_on: bool) -> bool;
+
+    /// Handles a driver-specific ioctl command.
+    ///
+    /// This method returns `Ok(None)` if the driver does not know the command. In this case, the
+    /// command will be handled as a job control command.
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<Option<i32>>;
+
+    /// Returns the device ID of the TTY at `index`.
+    ///
+    /// Drivers of the TTYs that have well-known device numbers in Linux should override this
+    /// method, so that the user space can identify the TTYs by their device numbers.
+    fn device_id(&self, index: u32) -> DeviceId {
+        DeviceId::new(88, index)
+    }
 }
@@ -0,0 +1,225 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Line disciplines.
+//!
+//! A line discipline sits between a TTY driver and the user space. It processes the characters
+//! received from the driver before the user space can read them, and it processes the characters
+//! written by the user space before they are sent to the driver.
+//!
+//! The line discipline of a TTY can be changed by the `TIOCSETD` ioctl. Currently, the following
+//! line disciplines are supported:
+//!  - [`LdiscNum::N_TTY`]: The default line discipline, which implements the terminal semantics
+//!    described by the termios (e.g., line editing, echoing, and signals).
+//!  - [`LdiscNum::N_NULL`]: A line discipline that discards all input and rejects all I/O. It is
+//!    used when the TTY is taken over by other kernel components (e.g., serial protocols).
+
+use self::{n_null::NNull, n_tty::NTty};
+use super::{termio::CTermios, PushCharError};
+use crate::{prelude::*, process::signal::sig_num::SigNum};
+
+mod n_null;
+mod n_tty;
+
+/// The number of a line discipline; `N_*` in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/tty.h#L10>.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+#[expect(non_camel_case_types)]
+pub(super) enum LdiscNum {
+    N_TTY = 0,
+    N_NULL = 27,
+}
+
+impl LdiscNum {
+    /// Creates a new line discipline of this type.
+    pub(super) fn new_ldisc(self) -> Box<dyn LineDiscipline> {
+        match self {
+            Self::N_TTY => Box::new(NTty::new()),
+            Self::N_NULL => Box::new(NNull),
+        }
+    }
+}
+
+/// A line discipline.
+///
+/// This references the interface of Linux:
+/// <https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/tty_ldisc.h>.
+pub(super) trait LineDiscipline: Send + Sync {
+    /// Returns the number of the line discipline.
+    fn num(&self) -> LdiscNum;
+
+    /// Receives a character from the driver.
+    ///
+    /// If the input buffer is full, this method fails with [`PushCharError`]. The caller can
+    /// silently ignore the error (if the input comes from the keyboard) or block the user space
+    /// (if the input comes from the pseudoterminal master).
+    fn receive_char(
+        &mut self,
+        ch: u8,
+        ctx: &mut LdiscContext,
+    ) -> core::result::Result<(), PushCharError>;
+
+    /// Receives a break condition from the driver.
+    ///
+    /// Depending on the termios, the break is ignored, turned into a `SIGINT` signal, or
+    /// received as a NUL character. In the last case, this method fails with [`PushCharError`]
+    /// if the input buffer is full.
+    fn receive_break(&mut self, ctx: &mut LdiscContext) -> core::result::Result<(), PushCharError>;
+
+    /// Reads bytes to `dst`, returning the actual bytes read.
+    ///
+    /// In the non-canonical mode, this method fails with [`Errno::EAGAIN`] if the available
+    /// bytes are fewer than `min(dst.len(), min_len)`. In the canonical mode, `min_len` is
+    /// ignored and this method fails with [`Errno::EAGAIN`] if no lines are available.
+    fn try_read(&mut self, dst: &mut [u8], min_len: usize, ctx: &mut LdiscContext)
+        -> Result<usize>;
+
+    /// Processes the bytes in `src` written by the user space.
+    ///
+    /// The processed bytes are appended to `output`, which will grow by at most `room` bytes.
+    /// This method returns the number of bytes consumed from `src`.
+    fn process_output(
+        &mut self,
+        src: &[u8],
+        room: usize,
+        termios: &CTermios,
+        output: &mut Vec<u8>,
+    ) -> Result<usize>;
+
+    /// Returns whether a read will not block.
+    fn is_readable(&self) -> bool;
+
+    /// Returns the number of bytes that are available to read.
+    fn readable_len(&self) -> usize;
+
+    /// Returns whether the input buffer is full.
+    fn is_full(&self) -> bool;
+
+    /// Discards all the pending input.
+    fn flush_input(&mut self);
+
+    /// Notifies that the termios has been changed from `old` to `new`.
+    fn set_termios(&mut self, old: &CTermios, new: &CTermios);
+}
+
+/// The states of the output flow control.
+///
+/// The output can be stopped by the `VSTOP` character (if `IXON` is set) or by the `TCXONC` ioctl
+/// with `TCOOFF`. In Linux, the two are tracked separately, so the `VSTART` character cannot
+/// restart the output stopped by the ioctl.
+#[derive(Debug, Default)]
+pub(super) struct FlowControl {
+    is_stopped: bool,
+    is_tco_stopped: bool,
+}
+
+impl FlowControl {
+    /// Returns whether the output is stopped.
+    pub(super) fn is_stopped(&self) -> bool {
+        self.is_stopped
+    }
+
+    /// Stops the output by the `VSTOP` character.
+    fn stop(&mut self) {
+        self.is_stopped = true;
+    }
+
+    /// Restarts the output by the `VSTART` character.
+    ///
+    /// This method returns whether the output is restarted.
+    pub(super) fn start(&mut self) -> bool {
+        if !self.is_stopped || self.is_tco_stopped {
+            return false;
+        }
+
+        self.is_stopped = false;
+        true
+    }
+
+    /// Stops the output by the `TCOOFF` ioctl.
+    pub(super) fn tco_stop(&mut self) {
+        self.is_tco_stopped = true;
+        self.is_stopped = true;
+    }
+
+    /// Restarts the output by the `TCOON` ioctl.
+    ///
+    /// This method returns whether the output is restarted.
+    pub(super) fn tco_start(&mut self) -> bool {
+        if !self.is_tco_stopped {
+            return false;
+        }
+
+        self.is_tco_stopped = false;
+        self.is_stopped = false;
+        true
+    }
+}
+
+/// The context in which a line discipline operates.
+///
+/// The effects on the driver (e.g., echoing and flushing the output) are recorded in the context,
+/// and will be applied by the [`Tty`] after the line discipline returns.
+///
+/// [`Tty`]: super::Tty
+pub(super) struct LdiscContext<'a> {
+    termios: &'a mut CTermios,
+    flow: &'a mut FlowControl,
+    signal_callback: &'a dyn Fn(SigNum),
+    echo: Vec<u8>,
+    should_flush_output: bool,
+    is_output_restarted: bool,
+}
+
+impl<'a> LdiscContext<'a> {
+    pub(super) fn new(
+        termios: &'a mut CTermios,
+        flow: &'a mut FlowControl,
+        signal_callback: &'a dyn Fn(SigNum),
+    ) -> Self {
+        Self {
+            termios,
+            flow,
+            signal_callback,
+            echo: Vec::new(),
+            should_flush_output: false,
+            is_output_restarted: false,
+        }
+    }
+
+    /// Returns the characters that should be echoed.
+    pub(super) fn echo(&self) -> &[u8] {
+        &self.echo
+    }
+
+    /// Returns whether the output buffer should be flushed before echoing.
+    pub(super) fn should_flush_output(&self) -> bool {
+        self.should_flush_output
+    }
+
+    /// Returns whether the output has been restarted.
+    pub(super) fn is_output_restarted(&self) -> bool {
+        self.is_output_restarted
+    }
+
+    fn send_signal(&self, signum: SigNum) {
+        (self.signal_callback)(signum);
+    }
+
+    fn flush_output(&mut self) {
+        // The echoed characters so far belong to the output that is flushed.
+        self.echo.clear();
+        self.should_flush_output = true;
+    }
+
+    fn stop_output(&mut self) {
+        self.flow.stop();
+    }
+
+    fn start_output(&mut self) {
+        if self.flow.start() {
+            self.is_output_restarted = true;
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{LdiscContext, LdiscNum, LineDiscipline};
+use crate::{
+    device::tty::{termio::CTermios, PushCharError},
+    prelude::*,
+};
+
+/// The null line discipline; `N_NULL` in Linux.
+///
+/// All the input characters are discarded, and all the reads and writes from the user space fail
+/// with [`Errno::EOPNOTSUPP`].
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/drivers/tty/n_null.c>.
+pub(super) struct NNull;
+
+impl LineDiscipline for NNull {
+    fn num(&self) -> LdiscNum {
+        LdiscNum::N_NULL
+    }
+
+    fn receive_char(
+        &mut self,
+        _ch: u8,
+        _ctx: &mut LdiscContext,
+    ) -> core::result::Result<(), PushCharError> {
+        Ok(())
+    }
+
+    fn receive_break(
+        &mut self,
+        _ctx: &mut LdiscContext,
+    ) -> core::result::Result<(), PushCharError> {
+        Ok(())
+    }
+
+    fn try_read(
+        &mut self,
+        _dst: &mut [u8],
+        _min_len: usize,
+        _ctx: &mut LdiscContext,
+    ) -> Result<usize> {
+        return_errno_with_message!(
+            Errno::EOPNOTSUPP,
+            "the null line discipline does not support reading"
+        );
+    }
+
+    fn process_output(
+        &mut self,
+        _src: &[u8],
+        _room: usize,
+        _termios: &CTermios,
+        _output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        return_errno_with_message!(
+            Errno::EOPNOTSUPP,
+            "the null line discipline does not support writing"
+        );
+    }
+
+    fn is_readable(&self) -> bool {
+        // Reads never block because they always fail.
+        true
+    }
+
+    fn readable_len(&self) -> usize {
+        0
+    }
+
+    fn is_full(&self) -> bool {
+        false
+    }
+
+    fn flush_input(&mut self) {}
+
+    fn set_termios(&mut self, _old: &CTermios, _new: &CTermios) {}
+}
@@ -0,0 +1,811 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use ostd::const_assert;
+
+use super::{LdiscContext, LdiscNum, LineDiscipline};
+use crate::{
+    device::tty::{
+        termio::{CCtrlCharId, CInputFlags, CLocalFlags, COutputFlags, CTermios},
+        PushCharError,
+    },
+    prelude::*,
+    process::signal::{
+        constants::{SIGINT, SIGQUIT, SIGTSTP},
+        sig_num::SigNum,
+    },
+    util::ring_buffer::RingBuffer,
+};
+
+// This implementation references the implementation of Linux:
+// <https://elixir.bootlin.com/linux/v6.0.9/source/drivers/tty/n_tty.c>
+
+const LINE_CAPACITY: usize = 4095;
+const BUFFER_CAPACITY: usize = 8192;
+
+// `LINE_CAPACITY` must be less than `BUFFER_CAPACITY`. Otherwise, `write()` can be blocked
+// indefinitely if both the current line and the buffer are full, so even the line terminator won't
+// be accepted.
+const_assert!(LINE_CAPACITY < BUFFER_CAPACITY);
+
+/// The number of buffered characters above which the input is throttled if `IXOFF` is set.
+const THROTTLE_THRESHOLD: usize = BUFFER_CAPACITY - 128;
+/// The number of buffered characters below which the throttled input is restarted.
+const UNTHROTTLE_THRESHOLD: usize = 128;
+
+const TAB_WIDTH: usize = 8;
+
+/// The default line discipline; `N_TTY` in Linux.
+pub(super) struct NTty {
+    /// The line being edited in the canonical mode.
+    current_line: CurrentLine,
+    /// The characters that can be read.
+    read_buffer: RingBuffer<u8>,
+    /// The lengths of the lines in `read_buffer` in the canonical mode.
+    ///
+    /// A line that is terminated by `VEOF` does not contain the terminator, so it can be empty.
+    line_lens: VecDeque<usize>,
+    /// The column of the cursor.
+    column: usize,
+    /// The column where the current line starts.
+    canon_column: usize,
+    /// Whether the next character should be taken literally (see `VLNEXT`).
+    is_lnext: bool,
+    /// Whether erased characters are being printed (see `ECHOPRT`).
+    is_erasing: bool,
+    /// Whether `VSTOP` has been sent to throttle the input (see `IXOFF`).
+    is_throttled: bool,
+}
+
+struct CurrentLine {
+    buffer: Box<[u8]>,
+    len: usize,
+}
+
+impl Default for CurrentLine {
+    fn default() -> Self {
+        Self {
+            buffer: vec![0; LINE_CAPACITY].into_boxed_slice(),
+            len: 0,
+        }
+    }
+}
+
+impl CurrentLine {
+    /// Pushes a character to the current line.
+    ///
+    /// This method returns `false` if the line is full.
+    fn push_char(&mut self, ch: u8) -> bool {
+        // If the line is full, the character will be ignored, but other actions such as echoing
+        // and signaling will work as normal. This will never block the caller, even if the input
+        // comes from the pseduoterminal master.
+        if self.len == self.buffer.len() {
+            return false;
+        }
+
+        self.buffer[self.len] = ch;
+        self.len += 1;
+        true
+    }
+
+    /// Clears the current line and returns the bytes in it.
+    fn drain(&mut self) -> &[u8] {
+        let chs = &self.buffer[..self.len];
+        self.len = 0;
+        chs
+    }
+
+    /// Removes the characters after the first `len` characters.
+    fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Returns the number of characters in the current line.
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// The kind of erasing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EraseKind {
+    /// Erases a character (`VERASE`).
+    Char,
+    /// Erases a word (`VWERASE`).
+    Word,
+    /// Erases the whole line (`VKILL`).
+    Line,
+}
+
+impl NTty {
+    pub(super) fn new() -> Self {
+        Self {
+            current_line: CurrentLine::default(),
+            read_buffer: RingBuffer::new(BUFFER_CAPACITY),
+            line_lens: VecDeque::new(),
+            column: 0,
+            canon_column: 0,
+            is_lnext: false,
+            is_erasing: false,
+            is_throttled: false,
+        }
+    }
+
+    fn receive_signal_char(
+        &mut self,
+        ch: u8,
+        signum: SigNum,
+        termios: &CTermios,
+        ctx: &mut LdiscContext,
+    ) {
+        if !termios.local_flags().contains(CLocalFlags::NOFLSH) {
+            self.flush_input();
+            ctx.flush_output();
+        }
+
+        if termios.input_flags().contains(CInputFlags::IXON) {
+            ctx.start_output();
+        }
+
+        if termios.local_flags().contains(CLocalFlags::ECHO) {
+            self.finish_erasing(termios, ctx);
+            self.echo_char(ch, termios, ctx);
+        }
+
+        ctx.send_signal(signum);
+    }
+
+    fn receive_canonical_char(&mut self, ch: u8, termios: &CTermios, ctx: &mut LdiscContext) {
+        let lflags = termios.local_flags();
+        let is_iexten = lflags.contains(CLocalFlags::IEXTEN);
+        let is_echo = lflags.contains(CLocalFlags::ECHO);
+
+        if termios.is_special_char(ch, CCtrlCharId::VERASE) {
+            self.erase(EraseKind::Char, ch, termios, ctx);
+            return;
+        }
+        if termios.is_special_char(ch, CCtrlCharId::VKILL) {
+            self.erase(EraseKind::Line, ch, termios, ctx);
+            return;
+        }
+        if is_iexten && termios.is_special_char(ch, CCtrlCharId::VWERASE) {
+            self.erase(EraseKind::Word, ch, termios, ctx);
+            return;
+        }
+
+        if is_iexten && termios.is_special_char(ch, CCtrlCharId::VLNEXT) {
+            self.is_lnext = true;
+            if is_echo {
+                self.finish_erasing(termios, ctx);
+                if lflags.contains(CLocalFlags::ECHOCTL) {
+                    // Show a caret that will be overwritten by the next character.
+                    self.echo_output(b'^', termios, ctx);
+                    self.echo_output(b'\x08', termios, ctx);
+                }
+            }
+            return;
+        }
+
+        if is_iexten && is_echo && termios.is_special_char(ch, CCtrlCharId::VREPRINT) {
+            self.finish_erasing(termios, ctx);
+            self.echo_char(ch, termios, ctx);
+            self.reprint_line(termios, ctx);
+            return;
+        }
+
+        if ch == b'\n' {
+            if is_echo || lflags.contains(CLocalFlags::ECHONL) {
+                self.finish_erasing(termios, ctx);
+                self.echo_output(b'\n', termios, ctx);
+            }
+            self.commit_line(Some(ch), termios, ctx);
+            return;
+        }
+
+        if termios.is_special_char(ch, CCtrlCharId::VEOF) {
+            // The EOF character is neither echoed nor returned to the user space.
+            self.commit_line(None, termios, ctx);
+            return;
+        }
+
+        if termios.is_special_char(ch, CCtrlCharId::VEOL)
+            || (is_iexten && termios.is_special_char(ch, CCtrlCharId::VEOL2))
+        {
+            if is_echo {
+                self.finish_erasing(termios, ctx);
+                if self.current_line.is_empty() {
+                    self.canon_column = self.column;
+                }
+                self.echo_char(ch, termios, ctx);
+            }
+            self.commit_line(Some(ch), termios, ctx);
+            return;
+        }
+
+        self.receive_normal_char(ch, termios, ctx);
+    }
+
+    /// Receives a character that has no special meaning.
+    fn receive_normal_char(&mut self, ch: u8, termios: &CTermios, ctx: &mut LdiscContext) {
+        let lflags = termios.local_flags();
+
+        let was_line_empty = self.current_line.is_empty();
+        if !termios.is_canonical_mode() {
+            // Note that `unwrap()` below won't fail because the caller has checked `is_full()`.
+            self.read_buffer.push(ch).unwrap();
+            self.throttle(termios, ctx);
+        } else if !self.current_line.push_char(ch)
+            && termios.input_flags().contains(CInputFlags::IMAXBEL)
+        {
+            // Ring the bell instead of echoing the character that is dropped.
+            ctx.echo.push(b'\x07');
+            return;
+        }
+
+        if !lflags.contains(CLocalFlags::ECHO) {
+            return;
+        }
+
+        self.finish_erasing(termios, ctx);
+        if ch == b'\n' {
+            self.echo_output(b'\n', termios, ctx);
+        } else {
+            if was_line_empty {
+                self.canon_column = self.column;
+            }
+            self.echo_char(ch, termios, ctx);
+        }
+    }
+
+    /// Moves the current line to the read buffer, followed by `terminator`.
+    fn commit_line(&mut self, terminator: Option<u8>, termios: &CTermios, ctx: &mut LdiscContext) {
+        // Note that `unwrap()` below won't fail because the caller has checked `is_full()`.
+        let line = self.current_line.drain();
+        self.read_buffer.push_slice(line).unwrap();
+        let mut line_len = line.len();
+
+        if let Some(terminator) = terminator {
+            self.read_buffer.push(terminator).unwrap();
+            line_len += 1;
+        }
+
+        self.line_lens.push_back(line_len);
+        self.throttle(termios, ctx);
+    }
+
+    fn erase(&mut self, kind: EraseKind, ch: u8, termios: &CTermios, ctx: &mut LdiscContext) {
+        if self.current_line.is_empty() {
+            return;
+        }
+
+        let lflags = termios.local_flags();
+        let is_echo = lflags.contains(CLocalFlags::ECHO);
+
+        if kind == EraseKind::Line {
+            if !is_echo {
+                self.current_line.truncate(0);
+                return;
+            }
+
+            if !lflags.contains(CLocalFlags::ECHOK | CLocalFlags::ECHOKE | CLocalFlags::ECHOE) {
+                // Echo the kill character instead of erasing the characters visually.
+                self.current_line.truncate(0);
+                self.finish_erasing(termios, ctx);
+                self.echo_char(ch, termios, ctx);
+                if lflags.contains(CLocalFlags::ECHOK) {
+                    self.echo_output(b'\n', termios, ctx);
+                }
+                return;
+            }
+        }
+
+        let is_utf8 = termios.input_flags().contains(CInputFlags::IUTF8);
+        let mut seen_alnums = 0;
+
+        while !self.current_line.is_empty() {
+            // Find the first byte of the last character.
+            let line = self.current_line.as_slice();
+            let mut start = line.len() - 1;
+            while is_utf8 && start > 0 && is_continuation(line[start]) {
+                start -= 1;
+            }
+            let erased_ch = line[start];
+
+            if kind == EraseKind::Word {
+                if erased_ch.is_ascii_alphanumeric() || erased_ch == b'_' {
+                    seen_alnums += 1;
+                } else if seen_alnums > 0 {
+                    break;
+                }
+            }
+
+            let continuations = line[start + 1..].to_vec();
+            self.current_line.truncate(start);
+
+            if is_echo {
+                self.echo_erased_char(erased_ch, &continuations, kind, termios, ctx);
+            }
+
+            if kind == EraseKind::Char {
+                break;
+            }
+        }
+
+        if self.current_line.is_empty() && is_echo {
+            self.finish_erasing(termios, ctx);
+        }
+    }
+
+    /// Echoes a character that is just erased.
+    fn echo_erased_char(
+        &mut self,
+        erased_ch: u8,
+        continuations: &[u8],
+        kind: EraseKind,
+        termios: &CTermios,
+        ctx: &mut LdiscContext,
+    ) {
+        let lflags = termios.local_flags();
+        let is_echo_ctl = lflags.contains(CLocalFlags::ECHOCTL);
+
+        if lflags.contains(CLocalFlags::ECHOPRT) {
+            // Print the erased characters between `\` and `/`.
+            if !self.is_erasing {
+                self.echo_output(b'\\', termios, ctx);
+                self.is_erasing = true;
+            }
+            self.echo_char(erased_ch, termios, ctx);
+            for ch in continuations {
+                self.echo_output(*ch, termios, ctx);
+            }
+        } else if kind == EraseKind::Char && !lflags.contains(CLocalFlags::ECHOE) {
+            self.echo_char(termios.special_char(CCtrlCharId::VERASE), termios, ctx);
+        } else if erased_ch == b'\t' {
+            // Move the cursor back to where the tab starts.
+            let tab_column = self.line_width(termios);
+            while self.column > tab_column {
+                self.echo_output(b'\x08', termios, ctx);
+            }
+        } else {
+            // Overwrite the character (and the caret for control characters) with spaces.
+            if erased_ch.is_ascii_control() && is_echo_ctl {
+                self.echo_str(b"\x08 \x08", termios, ctx);
+            }
+            if !erased_ch.is_ascii_control() || is_echo_ctl {
+                self.echo_str(b"\x08 \x08", termios, ctx);
+            }
+        }
+    }
+
+    /// Returns the column after echoing the current line.
+    fn line_width(&self, termios: &CTermios) -> usize {
+        let is_echo_ctl = termios.local_flags().contains(CLocalFlags::ECHOCTL);
+        let is_utf8 = termios.input_flags().contains(CInputFlags::IUTF8);
+
+        self.current_line
+            .as_slice()
+            .iter()
+            .fold(self.canon_column, |column, ch| match *ch {
+                b'\t' => column + TAB_WIDTH - column % TAB_WIDTH,
+                ch if ch.is_ascii_control() && is_echo_ctl => column + 2,
+                ch if ch.is_ascii_control() => column,
+                ch if is_utf8 && is_continuation(ch) => column,
+                _ => column + 1,
+            })
+    }
+
+    /// Echoes a new line followed by the current line.
+    fn reprint_line(&mut self, termios: &CTermios, ctx: &mut LdiscContext) {
+        self.echo_output(b'\n', termios, ctx);
+
+        let line = self.current_line.as_slice().to_vec();
+        for ch in line {
+            self.echo_char(ch, termios, ctx);
+        }
+    }
+
+    /// Finishes printing the erased characters (see `ECHOPRT`).
+    fn finish_erasing(&mut self, termios: &CTermios, ctx: &mut LdiscContext) {
+        if self.is_erasing {
+            self.echo_output(b'/', termios, ctx);
+            self.is_erasing = false;
+        }
+    }
+
+    /// Echoes a character, showing control characters as `^X` if `ECHOCTL` is set.
+    fn echo_char(&mut self, ch: u8, termios: &CTermios, ctx: &mut LdiscContext) {
+        if ch.is_ascii_control()
+            && ch != b'\t'
+            && termios.local_flags().contains(CLocalFlags::ECHOCTL)
+        {
+            ctx.echo.extend_from_slice(&[b'^', ch ^ 0x40]);
+            self.column += 2;
+        } else {
+            self.echo_output(ch, termios, ctx);
+        }
+    }
+
+    fn echo_str(&mut self, chs: &[u8], termios: &CTermios, ctx: &mut LdiscContext) {
+        for ch in chs {
+            self.echo_output(*ch, termios, ctx);
+        }
+    }
+
+    /// Echoes a character with the output processing.
+    fn echo_output(&mut self, ch: u8, termios: &CTermios, ctx: &mut LdiscContext) {
+        // The echo buffer is unbounded, so this won't fail.
+        self.output_char(ch, termios, usize::MAX, &mut ctx.echo);
+    }
+
+    /// Processes an output character and appends the result to `output`.
+    ///
+    /// This method returns `false` if the result does not fit in `room` bytes, in which case
+    /// nothing is appended.
+    fn output_char(
+        &mut self,
+        ch: u8,
+        termios: &CTermios,
+        room: usize,
+        output: &mut Vec<u8>,
+    ) -> bool {
+        if room == 0 {
+            return false;
+        }
+
+        let oflags = termios.output_flags();
+        if !oflags.contains(COutputFlags::OPOST) {
+            output.push(ch);
+            return true;
+        }
+
+        let ch = match ch {
+            b'\n' => {
+                if oflags.contains(COutputFlags::ONLRET) {
+                    self.column = 0;
+                }
+                if oflags.contains(COutputFlags::ONLCR) {
+                    if room < 2 {
+                        return false;
+                    }
+                    self.column = 0;
+                    self.canon_column = 0;
+                    output.extend_from_slice(b"\r\n");
+                    return true;
+                }
+                self.canon_column = self.column;
+                b'\n'
+            }
+            b'\r' => {
+                if oflags.contains(COutputFlags::ONOCR) && self.column == 0 {
+                    return true;
+                }
+                if oflags.contains(COutputFlags::OCRNL) {
+                    if oflags.contains(COutputFlags::ONLRET) {
+                        self.column = 0;
+                        self.canon_column = 0;
+                    }
+                    b'\n'
+                } else {
+                    self.column = 0;
+                    self.canon_column = 0;
+                    b'\r'
+                }
+            }
+            b'\t' => {
+                let spaces = TAB_WIDTH - self.column % TAB_WIDTH;
+                if oflags.contains(COutputFlags::XTABS) {
+                    if room < spaces {
+                        return false;
+                    }
+                    self.column += spaces;
+                    output.resize(output.len() + spaces, b' ');
+                    return true;
+                }
+                self.column += spaces;
+                b'\t'
+            }
+            b'\x08' => {
+                self.column = self.column.saturating_sub(1);
+                b'\x08'
+            }
+            ch if !ch.is_ascii_control() => {
+                if !(termios.input_flags().contains(CInputFlags::IUTF8) && is_continuation(ch)) {
+                    self.column += 1;
+                }
+                if oflags.contains(COutputFlags::OLCUC) {
+                    ch.to_ascii_uppercase()
+                } else {
+                    ch
+                }
+            }
+            ch => ch,
+        };
+
+        output.push(ch);
+        true
+    }
+
+    /// Sends `VSTOP` if the input buffer is almost full and `IXOFF` is set.
+    fn throttle(&mut self, termios: &CTermios, ctx: &mut LdiscContext) {
+        if self.is_throttled
+            || !termios.input_flags().contains(CInputFlags::IXOFF)
+            || self.read_buffer.len() + self.current_line.len() < THROTTLE_THRESHOLD
+        {
+            return;
+        }
+
+        self.is_throttled = true;
+        ctx.echo.push(termios.special_char(CCtrlCharId::VSTOP));
+    }
+
+    /// Sends `VSTART` if the input is throttled and the input buffer is almost empty.
+    fn unthrottle(&mut self, termios: &CTermios, ctx: &mut LdiscContext) {
+        if !self.is_throttled
+            || self.read_buffer.len() + self.current_line.len() > UNTHROTTLE_THRESHOLD
+        {
+            return;
+        }
+
+        self.is_throttled = false;
+        ctx.echo.push(termios.special_char(CCtrlCharId::VSTART));
+    }
+
+    fn read_line(&mut self, dst: &mut [u8]) -> Result<usize> {
+        let Some(line_len) = self.line_lens.front_mut() else {
+            return_errno_with_message!(Errno::EAGAIN, "no lines are available");
+        };
+
+        // If `dst` is too small, the rest of the line will be returned by the next read.
+        let read_len = dst.len().min(*line_len);
+        self.read_buffer.pop_slice(&mut dst[..read_len]).unwrap();
+
+        *line_len -= read_len;
+        if *line_len == 0 {
+            self.line_lens.pop_front();
+        }
+
+        Ok(read_len)
+    }
+}
+
+impl LineDiscipline for NTty {
+    fn num(&self) -> LdiscNum {
+        LdiscNum::N_TTY
+    }
+
+    fn receive_char(
+        &mut self,
+        ch: u8,
+        ctx: &mut LdiscContext,
+    ) -> core::result::Result<(), PushCharError> {
+        if self.is_full() {
+            // If the buffer is full, we should not push the character into the buffer.
+            return Err(PushCharError);
+        }
+
+        let termios = *ctx.termios;
+        let iflags = termios.input_flags();
+        let lflags = termios.local_flags();
+
+        if lflags.contains(CLocalFlags::PENDIN) {
+            // Reprint the pending input, which is typically set after the mode is switched.
+            ctx.termios.local_flags_mut().remove(CLocalFlags::PENDIN);
+            if lflags.contains(CLocalFlags::ECHO) {
+                self.finish_erasing(&termios, ctx);
+                self.reprint_line(&termios, ctx);
+            }
+        }
+
+        let mut ch = ch;
+        if iflags.contains(CInputFlags::ISTRIP) {
+            ch &= 0x7f;
+        }
+        if iflags.contains(CInputFlags::IUCLC) && lflags.contains(CLocalFlags::IEXTEN) {
+            ch = ch.to_ascii_lowercase();
+        }
+
+        if self.is_lnext {
+            self.is_lnext = false;
+            self.receive_normal_char(ch, &termios, ctx);
+            return Ok(());
+        }
+
+        if iflags.contains(CInputFlags::IXON) {
+            if termios.is_special_char(ch, CCtrlCharId::VSTART) {
+                ctx.start_output();
+                return Ok(());
+            }
+            if termios.is_special_char(ch, CCtrlCharId::VSTOP) {
+                ctx.stop_output();
+                return Ok(());
+            }
+        }
+
+        if lflags.contains(CLocalFlags::ISIG) {
+            if let Some(signum) = char_to_signal(ch, &termios) {
+                self.receive_signal_char(ch, signum, &termios, ctx);
+                return Ok(());
+            }
+        }
+
+        if iflags.contains(CInputFlags::IXON | CInputFlags::IXANY) {
+            ctx.start_output();
+        }
+
+        let ch = match ch {
+            b'\r' if iflags.contains(CInputFlags::IGNCR) => return Ok(()),
+            b'\r' if iflags.contains(CInputFlags::ICRNL) => b'\n',
+            b'\n' if iflags.contains(CInputFlags::INLCR) => b'\r',
+            ch => ch,
+        };
+
+        if termios.is_canonical_mode() {
+            self.receive_canonical_char(ch, &termios, ctx);
+        } else {
+            self.receive_normal_char(ch, &termios, ctx);
+        }
+
+        Ok(())
+    }
+
+    fn receive_break(&mut self, ctx: &mut LdiscContext) -> core::result::Result<(), PushCharError> {
+        let termios = *ctx.termios;
+        let iflags = termios.input_flags();
+
+        if iflags.contains(CInputFlags::IGNBRK) {
+            return Ok(());
+        }
+
+        if iflags.contains(CInputFlags::BRKINT) {
+            if !termios.local_flags().contains(CLocalFlags::NOFLSH) {
+                self.flush_input();
+                ctx.flush_output();
+            }
+            ctx.send_signal(SIGINT);
+            return Ok(());
+        }
+
+        // The break is read as a NUL character, which is marked by `\377 \0` if `PARMRK` is set.
+        let chs: &[u8] = if iflags.contains(CInputFlags::PARMRK) {
+            b"\xff\0\0"
+        } else {
+            b"\0"
+        };
+        if self.read_buffer.len() + self.current_line.len() + chs.len()
+            > self.read_buffer.capacity()
+        {
+            return Err(PushCharError);
+        }
+
+        for ch in chs {
+            self.receive_normal_char(*ch, &termios, ctx);
+        }
+
+        Ok(())
+    }
+
+    fn try_read(
+        &mut self,
+        dst: &mut [u8],
+        min_len: usize,
+        ctx: &mut LdiscContext,
+    ) -> Result<usize> {
+        if dst.is_empty() {
+            return Ok(0);
+        }
+
+        let termios = *ctx.termios;
+
+        let read_len = if termios.is_canonical_mode() {
+            self.read_line(dst)?
+        } else {
+            // If `min_len` is zero, the following condition will always be false. This is
+            // correct, as the expected behavior is to never block or return `EAGAIN`.
+            if self.read_buffer.len() < dst.len().min(min_len) {
+                return_errno_with_message!(
+                    Errno::EAGAIN,
+                    "the characters in the buffer are not enough"
+                );
+            }
+
+            let read_len = dst.len().min(self.read_buffer.len());
+            self.read_buffer.pop_slice(&mut dst[..read_len]).unwrap();
+            read_len
+        };
+
+        self.unthrottle(&termios, ctx);
+        Ok(read_len)
+    }
+
+    fn process_output(
+        &mut self,
+        src: &[u8],
+        mut room: usize,
+        termios: &CTermios,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        if termios.local_flags().contains(CLocalFlags::FLUSHO) {
+            // The output is discarded.
+            return Ok(src.len());
+        }
+
+        for (i, ch) in src.iter().enumerate() {
+            let old_len = output.len();
+            if !self.output_char(*ch, termios, room, output) {
+                return Ok(i);
+            }
+            room -= output.len() - old_len;
+        }
+
+        Ok(src.len())
+    }
+
+    fn is_readable(&self) -> bool {
+        // In the canonical mode, `line_lens` may contain empty lines terminated by `VEOF`.
+        !self.read_buffer.is_empty() || !self.line_lens.is_empty()
+    }
+
+    fn readable_len(&self) -> usize {
+        self.read_buffer.len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.read_buffer.len() + self.current_line.len() >= self.read_buffer.capacity()
+    }
+
+    fn flush_input(&mut self) {
+        self.current_line.truncate(0);
+        self.read_buffer.clear();
+        self.line_lens.clear();
+        self.is_lnext = false;
+        self.is_erasing = false;
+    }
+
+    fn set_termios(&mut self, old: &CTermios, new: &CTermios) {
+        if old.is_canonical_mode() == new.is_canonical_mode() {
+            return;
+        }
+
+        if new.is_canonical_mode() {
+            // The pending input becomes a line that can be read without a line terminator.
+            self.line_lens.clear();
+            if !self.read_buffer.is_empty() {
+                self.line_lens.push_back(self.read_buffer.len());
+            }
+        } else {
+            // The line being edited becomes available to read.
+            // Note that `unwrap()` below won't fail because of the `is_full()` checks.
+            self.read_buffer
+                .push_slice(self.current_line.drain())
+                .unwrap();
+            self.line_lens.clear();
+        }
+
+        self.is_lnext = false;
+        self.is_erasing = false;
+    }
+}
+
+/// Returns whether `ch` is a UTF-8 continuation byte.
+fn is_continuation(ch: u8) -> bool {
+    (ch & 0xc0) == 0x80
+}
+
+fn char_to_signal(ch: u8, termios: &CTermios) -> Option<SigNum> {
+    if termios.is_special_char(ch, CCtrlCharId::VINTR) {
+        Some(SIGINT)
+    } else if termios.is_special_char(ch, CCtrlCharId::VQUIT) {
+        Some(SIGQUIT)
+    } else if termios.is_special_char(ch, CCtrlCharId::VSUSP) {
+        Some(SIGTSTP)
+    } else {
+        None
+    }
+}
@@ -1,19 +1,26 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use ostd::sync::LocalIrqDisabled;
+use core::time::Duration;
 
-use self::line_discipline::LineDiscipline;
+use ostd::sync::{LocalIrqDisabled, Waiter};
+
+use self::{
+    line_discipline::{FlowControl, LdiscContext, LdiscNum, LineDiscipline},
+    termio::{CCtrlCharId, CInputFlags, CTermios, CWinSize},
+};
 use crate::{
     current_userspace,
     events::IoEvents,
     fs::{
         device::{Device, DeviceId, DeviceType},
         inode_handle::FileIo,
-        utils::IoctlCmd,
+        utils::{IoctlCmd, StatusFlags},
     },
     prelude::*,
     process::{
         broadcast_signal_async,
+        credentials::capabilities::CapSet,
+        posix_thread::AsPosixThread,
         signal::{PollHandle, Pollable, Pollee},
         JobControl, Terminal,
     },
@@ -23,15 +30,37 @@ mod device;
 mod driver;
 mod line_discipline;
 mod n_tty;
-mod termio;
+#[cfg(target_arch = "x86_64")]
+mod serial;
+pub mod termio;
+mod vt;
 
 pub use device::TtyDevice;
 pub use driver::{PushCharError, TtyDriver};
-pub(super) use n_tty::init;
 pub use n_tty::{iter_n_tty, system_console};
+#[cfg(target_arch = "x86_64")]
+pub use serial::{iter_serial_tty, SerialDriver};
+pub use vt::{iter_vt_tty, VtDriver};
+
+pub(super) fn init() {
+    n_tty::init();
+    #[cfg(target_arch = "x86_64")]
+    serial::init();
+    vt::init();
+}
 
 const IO_CAPACITY: usize = 4096;
 
+/// The major device number of the virtual terminals and the serial ports.
+///
+/// Reference: <https://www.kernel.org/doc/Documentation/admin-guide/devices.txt>.
+const TTY_MAJOR: u32 = 4;
+
+/// The duration of a break sent by `TCSBRK`.
+///
+/// POSIX requires that the duration is between 0.25 and 0.5 seconds.
+const BREAK_DURATION: Duration = Duration::from_millis(250);
+
 /// A teletyper (TTY).
 ///
 /// This abstracts the general functionality of a TTY in a way that
@@ -52,21 +81,39 @@ const IO_CAPACITY: usize = 4096;
 ///             +-------+
 /// Tty::push_input   D::push_output
 /// ```
+///
+/// Both the input and the output characters are processed by the line discipline of the TTY,
+/// which can be changed by the `TIOCSETD` ioctl.
 pub struct Tty<D> {
     index: u32,
     driver: D,
-    ldisc: SpinLock<LineDiscipline, LocalIrqDisabled>,
+    state: SpinLock<TtyState, LocalIrqDisabled>,
     job_control: JobControl,
     pollee: Pollee,
     weak_self: Weak<Self>,
 }
 
+/// The states of a TTY that are processed by the line discipline.
+struct TtyState {
+    ldisc: Box<dyn LineDiscipline>,
+    termios: CTermios,
+    winsize: CWinSize,
+    flow: FlowControl,
+}
+
 impl<D> Tty<D> {
     pub fn new(index: u32, driver: D) -> Arc<Self> {
+        let state = TtyState {
+            ldisc: LdiscNum::N_TTY.new_ldisc(),
+            termios: CTermios::default(),
+            winsize: CWinSize::default(),
+            flow: FlowControl::default(),
+        };
+
         Arc::new_cyclic(move |weak_ref| Tty {
             index,
             driver,
-            ldisc: SpinLock::new(LineDiscipline::new()),
+            state: SpinLock::new(state),
             job_control: JobControl::new(),
             pollee: Pollee::new(),
             weak_self: weak_ref.clone(),
@@ -81,17 +128,24 @@ impl<D> Tty<D> {
         &self.driver
     }
 
+    /// Sets the window size.
+    ///
+    /// This is used by the drivers that know the size of the underlying screen.
+    fn set_window_size(&self, winsize: CWinSize) {
+        self.state.lock().winsize = winsize;
+    }
+
     /// Returns whether new characters can be pushed into the input buffer.
     ///
     /// This method should return `false` if the input buffer is full.
     pub fn can_push(&self) -> bool {
-        !self.ldisc.lock().is_full()
+        !self.state.lock().ldisc.is_full()
     }
 
     /// Notifies that the output buffer now has room for new characters.
     ///
-    /// This method should be called when the state of [`TtyDriver::can_push`] changes from `false`
-    /// to `true`.
+    /// This method should be called when the value of [`TtyDriver::write_room`] increases from
+    /// zero, or when the value of [`TtyDriver::chars_in_buffer`] decreases to zero.
     pub fn notify_output(&self) {
         self.pollee.notify(IoEvents::OUT);
     }
@@ -103,41 +157,250 @@ impl<D: TtyDriver> Tty<D> {
     /// This method returns the number of bytes pushed or fails with an error if no bytes can be
     /// pushed because the buffer is full.
     pub fn push_input(&self, chs: &[u8]) -> core::result::Result<usize, PushCharError> {
-        let mut ldisc = self.ldisc.lock();
-        let mut echo = self.driver.echo_callback();
-
-        let mut len = 0;
-        for ch in chs {
-            let res = ldisc.push_char(
-                *ch,
-                |signum| {
-                    if let Some(foreground) = self.job_control.foreground() {
-                        broadcast_signal_async(Arc::downgrade(&foreground), signum);
-                    }
-                },
-                &mut echo,
-            );
-            if res.is_err() && len == 0 {
-                return Err(PushCharError);
-            } else if res.is_err() {
-                break;
-            } else {
-                len += 1;
-            }
+        let len = self.with_ldisc(|ldisc, ctx| {
+            chs.iter()
+                .take_while(|ch| ldisc.receive_char(**ch, ctx).is_ok())
+                .count()
+        });
+        if len == 0 && !chs.is_empty() {
+            return Err(PushCharError);
         }
 
         self.pollee.notify(IoEvents::IN);
         Ok(len)
     }
 
+    /// Pushes a break condition into the input buffer.
+    ///
+    /// This method fails with an error if the break cannot be received because the buffer is
+    /// full.
+    pub fn push_break(&self) -> core::result::Result<(), PushCharError> {
+        self.with_ldisc(|ldisc, ctx| ldisc.receive_break(ctx))?;
+
+        self.pollee.notify(IoEvents::IN);
+        Ok(())
+    }
+
+    /// Performs an operation on the line discipline and applies its effects on the driver.
+    fn with_ldisc<R>(&self, op: impl FnOnce(&mut dyn LineDiscipline, &mut LdiscContext) -> R) -> R {
+        let mut state = self.state.lock();
+        let TtyState {
+            ldisc,
+            termios,
+            flow,
+            ..
+        } = &mut *state;
+
+        let signal_callback = |signum| {
+            if let Some(foreground) = self.job_control.foreground() {
+                broadcast_signal_async(Arc::downgrade(&foreground), signum);
+            }
+        };
+        let mut ctx = LdiscContext::new(termios, flow, &signal_callback);
+
+        let res = op(ldisc.as_mut(), &mut ctx);
+
+        if ctx.should_flush_output() {
+            self.driver.drain_output();
+        }
+        if !ctx.echo().is_empty() {
+            (self.driver.echo_callback())(ctx.echo());
+        }
+        if ctx.is_output_restarted() {
+            self.pollee.notify(IoEvents::OUT);
+        }
+
+        res
+    }
+
+    fn try_read(&self, buf: &mut [u8], min_len: usize) -> Result<usize> {
+        self.with_ldisc(|ldisc, ctx| ldisc.try_read(buf, min_len, ctx))
+    }
+
+    /// Returns the number of bytes available to read if it is greater than `len`.
+    fn try_wait_for_more(&self, len: usize) -> Result<usize> {
+        let readable_len = self.state.lock().ldisc.readable_len();
+        if readable_len <= len {
+            return_errno_with_message!(Errno::EAGAIN, "no more characters are available");
+        }
+
+        Ok(readable_len)
+    }
+
+    /// Reads the input to `buf`, blocking as specified by `VMIN` and `VTIME`.
+    ///
+    /// Reference: <https://man7.org/linux/man-pages/man3/termios.3.html> (see "Noncanonical mode").
+    fn wait_for_input(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let (is_canonical, vmin, vtime) = {
+            let state = self.state.lock();
+            let termios = &state.termios;
+            (
+                termios.is_canonical_mode(),
+                termios.special_char(CCtrlCharId::VMIN) as usize,
+                termios.special_char(CCtrlCharId::VTIME),
+            )
+        };
+
+        if is_canonical {
+            return self.wait_events(IoEvents::IN, None, || self.try_read(buf, 1));
+        }
+
+        if vtime == 0 {
+            // If `vmin` is also zero, this is a polling read that never blocks.
+            return self.wait_events(IoEvents::IN, None, || self.try_read(buf, vmin));
+        }
+
+        // `VTIME` is in tenths of a second.
+        let timeout = Duration::from_millis(vtime as u64 * 100);
+
+        if vmin == 0 {
+            // The read returns as soon as a character is available or the timer expires.
+            return match self.wait_events(IoEvents::IN, Some(&timeout), || self.try_read(buf, 1)) {
+                Err(err) if err.error() == Errno::ETIME => Ok(0),
+                res => res,
+            };
+        }
+
+        // The timer is an inter-byte timer, which is started after the first character is
+        // received and restarted after each further character is received.
+        let mut len = self.wait_events(IoEvents::IN, None, || self.try_wait_for_more(0))?;
+        while len < vmin.min(buf.len()) {
+            match self.wait_events(IoEvents::IN, Some(&timeout), || self.try_wait_for_more(len)) {
+                Ok(new_len) => len = new_len,
+                Err(err) if err.error() == Errno::ETIME => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.try_read(buf, 0)
+    }
+
+    fn try_write(&self, chs: &[u8]) -> Result<usize> {
+        let mut state = self.state.lock();
+        if state.flow.is_stopped() {
+            return_errno_with_message!(Errno::EAGAIN, "the output is stopped");
+        }
+
+        let TtyState { ldisc, termios, .. } = &mut *state;
+
+        let mut output = Vec::new();
+        let len = ldisc.process_output(chs, self.driver.write_room(), termios, &mut output)?;
+        if len == 0 && !chs.is_empty() {
+            return Err(PushCharError.into());
+        }
+
+        if !output.is_empty() {
+            // This won't fail because the output fits in the room of the driver.
+            self.driver.push_output(&output)?;
+        }
+
+        Ok(len)
+    }
+
+    /// Waits until all the characters in the output buffer are sent.
+    fn wait_until_sent(&self) -> Result<()> {
+        self.wait_events(IoEvents::OUT, None, || {
+            if self.driver.chars_in_buffer() > 0 {
+                return_errno_with_message!(Errno::EAGAIN, "the output buffer is not empty");
+            }
+            Ok(())
+        })
+    }
+
+    fn set_termios(&self, mut termios: CTermios) {
+        let mut state = self.state.lock();
+
+        let old_termios = state.termios;
+        // The line discipline can only be changed by `TIOCSETD`.
+        termios.set_line(old_termios.line());
+        state.termios = termios;
+        state.ldisc.set_termios(&old_termios, &termios);
+        self.driver.on_termios_change(&old_termios, &termios);
+
+        // Disabling `IXON` restarts the output stopped by `VSTOP`.
+        if !termios.input_flags().contains(CInputFlags::IXON) {
+            state.flow.start();
+        }
+
+        // Switching the mode may change whether the characters can be read.
+        self.pollee.notify(IoEvents::IN | IoEvents::OUT);
+    }
+
+    /// Sends a break for [`BREAK_DURATION`].
+    fn send_break(&self) -> Result<()> {
+        if !self.driver.set_break(true) {
+            return Ok(());
+        }
+
+        let (waiter, _waker) = Waiter::new_pair();
+        let res = waiter.pause_until_or_timeout(|| None::<()>, &BREAK_DURATION);
+        self.driver.set_break(false);
+
+        match res {
+            Err(err) if err.error() == Errno::ETIME => Ok(()),
+            res => res,
+        }
+    }
+
+    fn flush_input(&self) {
+        self.state.lock().ldisc.flush_input();
+        self.pollee.invalidate();
+        self.driver.notify_input();
+    }
+
+    fn set_ldisc(&self, num: LdiscNum) {
+        let mut state = self.state.lock();
+        if state.ldisc.num() == num {
+            return;
+        }
+
+        // The pending input in the old line discipline is discarded.
+        state.ldisc = num.new_ldisc();
+        state.termios.set_line(num as u8);
+
+        self.pollee.notify(IoEvents::IN | IoEvents::OUT);
+        self.driver.notify_input();
+    }
+
+    /// Checks whether the current process can insert input characters by `TIOCSTI`.
+    fn check_sti_permission(&self) -> Result<()> {
+        let this = self.weak_self.upgrade().unwrap() as Arc<dyn Terminal>;
+        if current!()
+            .terminal()
+            .is_some_and(|terminal| Arc::ptr_eq(&terminal, &this))
+        {
+            return Ok(());
+        }
+
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        if posix_thread
+            .credentials()
+            .effective_capset()
+            .contains(CapSet::SYS_ADMIN)
+        {
+            return Ok(());
+        }
+
+        return_errno_with_message!(
+            Errno::EPERM,
+            "inserting characters requires the controlling terminal or `CAP_SYS_ADMIN`"
+        );
+    }
+
     fn check_io_events(&self) -> IoEvents {
         let mut events = IoEvents::empty();
+        let state = self.state.lock();
 
-        if self.ldisc.lock().buffer_len() > 0 {
+        if state.ldisc.is_readable() {
             events |= IoEvents::IN;
         }
 
-        if self.driver.can_push() {
+        if !state.flow.is_stopped() && self.driver.write_room() > 0 {
             events |= IoEvents::OUT;
         }
 
@@ -153,13 +416,12 @@ impl<D: TtyDriver> Pollable for Tty<D> {
 }
 
 impl<D: TtyDriver> FileIo for Tty<D> {
-    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+    fn read(&self, writer: &mut VmWriter, _status_flags: StatusFlags) -> Result<usize> {
         self.job_control.wait_until_in_foreground()?;
 
-        // TODO: Add support for non-blocking mode and timeout
+        // TODO: Add support for non-blocking mode
         let mut buf = vec![0u8; writer.avail().min(IO_CAPACITY)];
-        let read_len =
-            self.wait_events(IoEvents::IN, None, || self.ldisc.lock().try_read(&mut buf))?;
+        let read_len = self.wait_for_input(&mut buf)?;
         self.pollee.invalidate();
         self.driver.notify_input();
 
@@ -168,14 +430,12 @@ impl<D: TtyDriver> FileIo for Tty<D> {
         Ok(read_len)
     }
 
-    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+    fn write(&self, reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
         let mut buf = vec![0u8; reader.remain().min(IO_CAPACITY)];
         let write_len = reader.read_fallible(&mut buf.as_mut_slice().into())?;
 
         // TODO: Add support for non-blocking mode and timeout
-        let len = self.wait_events(IoEvents::OUT, None, || {
-            Ok(self.driver.push_output(&buf[..write_len])?)
-        })?;
+        let len = self.wait_events(IoEvents::OUT, None, || self.try_write(&buf[..write_len]))?;
         self.pollee.invalidate();
         Ok(len)
     }
@@ -183,41 +443,86 @@ impl<D: TtyDriver> FileIo for Tty<D> {
     fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
         match cmd {
             IoctlCmd::TCGETS => {
-                let termios = *self.ldisc.lock().termios();
+                let termios = self.state.lock().termios;
 
                 current_userspace!().write_val(arg, &termios)?;
             }
             IoctlCmd::TCSETS => {
                 let termios = current_userspace!().read_val(arg)?;
 
-                self.ldisc.lock().set_termios(termios);
+                self.set_termios(termios);
             }
             IoctlCmd::TCSETSW => {
                 let termios = current_userspace!().read_val(arg)?;
 
-                let mut ldisc = self.ldisc.lock();
-                ldisc.set_termios(termios);
-                self.driver.drain_output();
+                self.wait_until_sent()?;
+                self.set_termios(termios);
             }
             IoctlCmd::TCSETSF => {
                 let termios = current_userspace!().read_val(arg)?;
 
-                let mut ldisc = self.ldisc.lock();
-                ldisc.set_termios(termios);
-                ldisc.drain_input();
-                self.driver.drain_output();
-
-                self.pollee.invalidate();
+                self.wait_until_sent()?;
+                self.set_termios(termios);
+                self.flush_input();
+            }
+            IoctlCmd::TCSBRK => {
+                self.wait_until_sent()?;
+                if arg == 0 {
+                    self.send_break()?;
+                }
+            }
+            IoctlCmd::TIOCSBRK => {
+                self.driver.set_break(true);
+            }
+            IoctlCmd::TIOCCBRK => {
+                self.driver.set_break(false);
+            }
+            IoctlCmd::TCXONC => match arg as u32 {
+                TCOOFF => self.state.lock().flow.tco_stop(),
+                TCOON => {
+                    if self.state.lock().flow.tco_start() {
+                        self.pollee.notify(IoEvents::OUT);
+                    }
+                }
+                TCIOFF | TCION => {
+                    let id = if arg as u32 == TCIOFF {
+                        CCtrlCharId::VSTOP
+                    } else {
+                        CCtrlCharId::VSTART
+                    };
+                    let state = self.state.lock();
+                    let ch = state.termios.special_char(id);
+                    if state.termios.is_special_char(ch, id) {
+                        (self.driver.echo_callback())(&[ch]);
+                    }
+                }
+                _ => return_errno_with_message!(Errno::EINVAL, "the flow action is invalid"),
+            },
+            IoctlCmd::TCFLSH => match arg as u32 {
+                TCIFLUSH => self.flush_input(),
+                TCOFLUSH => self.driver.drain_output(),
+                TCIOFLUSH => {
+                    self.flush_input();
+                    self.driver.drain_output();
+                }
+                _ => return_errno_with_message!(Errno::EINVAL, "the flush queue is invalid"),
+            },
+            IoctlCmd::TIOCSTI => {
+                self.check_sti_permission()?;
+                let ch = current_userspace!().read_val::<u8>(arg)?;
+
+                // Like the input from the driver, the character is dropped if the buffer is full.
+                let _ = self.push_input(&[ch]);
             }
             IoctlCmd::TIOCGWINSZ => {
-                let winsize = self.ldisc.lock().window_size();
+                let winsize = self.state.lock().winsize;
 
                 current_userspace!().write_val(arg, &winsize)?;
             }
             IoctlCmd::TIOCSWINSZ => {
                 let winsize = current_userspace!().read_val(arg)?;
 
-                self.ldisc.lock().set_window_size(winsize);
+                self.state.lock().winsize = winsize;
             }
             IoctlCmd::TIOCGPTN => {
                 let idx = self.index;
@@ -225,18 +530,55 @@ impl<D: TtyDriver> FileIo for Tty<D> {
                 current_userspace!().write_val(arg, &idx)?;
             }
             IoctlCmd::FIONREAD => {
-                let buffer_len = self.ldisc.lock().buffer_len() as u32;
+                let buffer_len = self.state.lock().ldisc.readable_len() as u32;
 
                 current_userspace!().write_val(arg, &buffer_len)?;
             }
-            _ => (self.weak_self.upgrade().unwrap() as Arc<dyn Terminal>)
-                .job_ioctl(cmd, arg, false)?,
+            IoctlCmd::TIOCOUTQ => {
+                let buffer_len = self.driver.chars_in_buffer() as u32;
+
+                current_userspace!().write_val(arg, &buffer_len)?;
+            }
+            IoctlCmd::TIOCGETD => {
+                let num = self.state.lock().ldisc.num() as u32;
+
+                current_userspace!().write_val(arg, &num)?;
+            }
+            IoctlCmd::TIOCSETD => {
+                let num = current_userspace!().read_val::<u32>(arg)?;
+                let num = LdiscNum::try_from(num).map_err(|_| {
+                    Error::with_message(Errno::EINVAL, "the line discipline is not supported")
+                })?;
+
+                self.set_ldisc(num);
+            }
+            _ => {
+                if let Some(res) = self.driver.ioctl(cmd, arg)? {
+                    return Ok(res);
+                }
+
+                (self.weak_self.upgrade().unwrap() as Arc<dyn Terminal>)
+                    .job_ioctl(cmd, arg, false)?
+            }
         }
 
         Ok(0)
     }
 }
 
+// Arguments of `TCXONC`.
+// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits-common.h#L53>
+const TCOOFF: u32 = 0;
+const TCOON: u32 = 1;
+const TCIOFF: u32 = 2;
+const TCION: u32 = 3;
+
+// Arguments of `TCFLSH`.
+// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits-common.h#L59>
+const TCIFLUSH: u32 = 0;
+const TCOFLUSH: u32 = 1;
+const TCIOFLUSH: u32 = 2;
+
 impl<D: TtyDriver> Terminal for Tty<D> {
     fn job_control(&self) -> &JobControl {
         &self.job_control
@@ -249,6 +591,6 @@ impl<D: TtyDriver> Device for Tty<D> {
     }
 
     fn id(&self) -> DeviceId {
-        DeviceId::new(88, self.index)
+        self.driver.device_id(self.index)
     }
 }
@@ -6,7 +6,8 @@ use aster_console::AnyConsoleDevice;
 use ostd::mm::{Infallible, VmReader, VmWriter};
 use spin::Once;
 
-use super::{PushCharError, Tty, TtyDriver};
+use super::{termio::CTermios, PushCharError, Tty, TtyDriver};
+use crate::{fs::utils::IoctlCmd, prelude::Result};
 
 pub struct ConsoleDriver {
     console: Arc<dyn AnyConsoleDevice>,
@@ -24,11 +25,25 @@ impl TtyDriver for ConsoleDriver {
         |chs| self.console.send(chs)
     }
 
-    fn can_push(&self) -> bool {
-        true
+    fn write_room(&self) -> usize {
+        usize::MAX
+    }
+
+    fn chars_in_buffer(&self) -> usize {
+        0
     }
 
     fn notify_input(&self) {}
+
+    fn on_termios_change(&self, _old_termios: &CTermios, _new_termios: &CTermios) {}
+
+    fn set_break(&self, _is_on: bool) -> bool {
+        false
+    }
+
+    fn ioctl(&self, _cmd: IoctlCmd, _arg: usize) -> Result<Option<i32>> {
+        Ok(None)
+    }
 }
 
 static N_TTY: Once<Box<[Arc<Tty<ConsoleDriver>>]>> = Once::new();
@@ -40,8 +55,7 @@ pub(in crate::device) fn init() {
         // there a better way than hardcoding this?
         devices.sort_by_key(|(name, _)| match name.as_str() {
             aster_virtio::device::console::DEVICE_NAME => 0,
-            aster_framebuffer::CONSOLE_NAME => 1,
-            _ => 2,
+            _ => 1,
         });
         devices
     };
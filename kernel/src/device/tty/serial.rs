@@ -0,0 +1,738 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The TTYs of the 16550 UARTs (i.e., `/dev/ttyS*`).
+//!
+//! The UARTs at the standard I/O ports of the PC are probed, and each of them is exposed as
+//! `/dev/ttySN`, where `N` follows the numbering in Linux (e.g., COM2 is `/dev/ttyS1`). COM1 (i.e.,
+//! `/dev/ttyS0`) is not exposed, because it is owned by OSTD to print the kernel messages.
+//!
+//! Both the input and the output are interrupt-driven. The received characters are pushed to the
+//! line discipline in the interrupt handler, and the characters written by the user space are
+//! buffered in a ring buffer that is drained whenever the transmitter becomes empty.
+//!
+//! References:
+//!  - <https://wiki.osdev.org/Serial_Ports>;
+//!  - <https://elixir.bootlin.com/linux/v6.0.9/source/drivers/tty/serial/8250/8250_port.c>.
+
+use ostd::{
+    arch::{
+        device::io_port::ReadWriteAccess,
+        kernel::{MappedIrqLine, IRQ_CHIP},
+    },
+    io::IoPort,
+    sync::{LocalIrqDisabled, WaitQueue},
+    trap::irq::IrqLine,
+};
+use spin::Once;
+
+use super::{
+    termio::{CCtrlSize, CTermios},
+    PushCharError, Tty, TtyDriver, TTY_MAJOR,
+};
+use crate::{
+    current_userspace,
+    fs::{device::DeviceId, utils::IoctlCmd},
+    prelude::*,
+    util::ring_buffer::RingBuffer,
+};
+
+/// The standard UARTs that can be exposed, i.e., `(index, I/O port base, ISA IRQ number)`.
+const UARTS: [(u32, u16, u8); 3] = [(1, 0x2F8, 3), (2, 0x3E8, 4), (3, 0x2E8, 3)];
+
+/// The minor device number of `/dev/ttyS0`.
+const SERIAL_MINOR_BASE: u32 = 64;
+
+/// The frequency of the baud rate generator divided by 16, i.e., the baud rate of divisor 1.
+const BASE_BAUD: u32 = 115200;
+
+/// The size of the receiver FIFO and the transmitter FIFO.
+const FIFO_SIZE: usize = 16;
+
+const TX_BUFFER_CAPACITY: usize = 4096;
+
+/// The maximum number of times the interrupt handler services a UART before it gives up.
+///
+/// This prevents a misbehaving UART from locking up the CPU.
+const IRQ_PASS_LIMIT: usize = 256;
+
+/// A serial TTY, i.e., `/dev/ttySN`.
+pub type SerialTty = Tty<SerialDriver>;
+
+static SERIAL_TTY: Once<Box<[Arc<SerialTty>]>> = Once::new();
+
+/// The IRQ lines of the UARTs, which must be kept alive so that the interrupts stay enabled.
+static IRQ_LINES: Once<Vec<MappedIrqLine>> = Once::new();
+
+pub(super) fn init() {
+    let uarts: Vec<_> = UARTS
+        .iter()
+        .filter_map(|&(index, base, isa_irq)| {
+            let uart = Uart::acquire(base)?;
+            uart.probe().then_some((index, uart, isa_irq))
+        })
+        .collect();
+
+    let mut irq_lines = Vec::new();
+    let mut mapped_isa_irqs = Vec::new();
+    for isa_irq in [3, 4] {
+        if !uarts.iter().any(|(_, _, irq)| *irq == isa_irq) {
+            continue;
+        }
+
+        let Ok(mut irq_line) = IrqLine::alloc()
+            .and_then(|irq_line| IRQ_CHIP.get().unwrap().map_isa_pin_to(irq_line, isa_irq))
+        else {
+            warn!("[Serial]: Failed to map the ISA IRQ {}", isa_irq);
+            continue;
+        };
+        irq_line.on_active(move |_| handle_irq(isa_irq));
+        irq_lines.push(irq_line);
+        mapped_isa_irqs.push(isa_irq);
+    }
+
+    // A UART without interrupts would never send anything, so it is not exposed.
+    let ttys = uarts
+        .into_iter()
+        .filter(|(_, _, isa_irq)| mapped_isa_irqs.contains(isa_irq))
+        .map(|(index, uart, isa_irq)| Tty::new(index, SerialDriver::new(uart, isa_irq)))
+        .collect();
+    SERIAL_TTY.call_once(|| ttys);
+    IRQ_LINES.call_once(|| irq_lines);
+
+    for tty in iter_serial_tty() {
+        tty.driver().startup();
+    }
+}
+
+/// Iterates all serial TTY devices, i.e., `/dev/ttyS1`, `/dev/ttyS2`, e.t.c.
+pub fn iter_serial_tty() -> impl Iterator<Item = &'static Arc<SerialTty>> {
+    SERIAL_TTY.get().unwrap().iter()
+}
+
+fn handle_irq(isa_irq: u8) {
+    let Some(ttys) = SERIAL_TTY.get() else {
+        return;
+    };
+
+    // Multiple UARTs can share the same IRQ line.
+    for tty in ttys.iter().filter(|tty| tty.driver().isa_irq == isa_irq) {
+        for _ in 0..IRQ_PASS_LIMIT {
+            // The TTY lock must be taken before the driver lock, so `can_push` and `push_input`
+            // are called without holding the driver lock.
+            let can_receive = tty.can_push();
+
+            let mut events = IrqEvents::default();
+            tty.driver().service(can_receive, &mut events);
+
+            if events.rx_len > 0 {
+                // The characters are dropped if the input buffer is full, as if the hardware FIFO
+                // were overrun.
+                let _ = tty.push_input(&events.rx_buffer[..events.rx_len]);
+            }
+            if events.has_break {
+                let _ = tty.push_break();
+            }
+            if events.has_sent {
+                tty.notify_output();
+            }
+
+            if !events.is_pending {
+                break;
+            }
+        }
+    }
+}
+
+/// A 16550 UART driver.
+pub struct SerialDriver {
+    uart: Uart,
+    isa_irq: u8,
+    state: SpinLock<SerialState, LocalIrqDisabled>,
+    /// The wait queue for changes of the modem status lines (see `TIOCMIWAIT`).
+    modem_wait_queue: WaitQueue,
+}
+
+struct SerialState {
+    tx_buffer: RingBuffer<u8>,
+    /// The value written to the interrupt enable register.
+    int_en: IntEnable,
+    /// The value written to the line control register.
+    line_ctrl: LineCtrl,
+    /// The value written to the modem control register.
+    modem_ctrl: ModemCtrl,
+    /// Whether the output is stopped until CTS is asserted (see `CRTSCTS`).
+    is_rtscts: bool,
+    /// The numbers of changes of the modem status lines.
+    modem_counts: ModemCounts,
+}
+
+/// The numbers of changes of the modem status lines; `struct serial_icounter_struct` in Linux.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ModemCounts {
+    cts: u32,
+    dsr: u32,
+    rng: u32,
+    dcd: u32,
+}
+
+impl ModemCounts {
+    /// Returns whether any of the lines in `lines` have changed since `old`.
+    fn has_changed(&self, old: &Self, lines: ModemLines) -> bool {
+        (lines.contains(ModemLines::CTS) && self.cts != old.cts)
+            || (lines.contains(ModemLines::DSR) && self.dsr != old.dsr)
+            || (lines.contains(ModemLines::RNG) && self.rng != old.rng)
+            || (lines.contains(ModemLines::CAR) && self.dcd != old.dcd)
+    }
+}
+
+/// The events collected by [`SerialDriver::service`] in the interrupt handler.
+#[derive(Default)]
+struct IrqEvents {
+    rx_buffer: [u8; FIFO_SIZE],
+    rx_len: usize,
+    has_break: bool,
+    has_sent: bool,
+    is_pending: bool,
+}
+
+impl SerialDriver {
+    fn new(uart: Uart, isa_irq: u8) -> Self {
+        let state = SerialState {
+            tx_buffer: RingBuffer::new(TX_BUFFER_CAPACITY),
+            int_en: IntEnable::empty(),
+            line_ctrl: LineCtrl::empty(),
+            // OUT2 gates the interrupt line of the UART on the PC, so it is always set.
+            modem_ctrl: ModemCtrl::DTR | ModemCtrl::RTS | ModemCtrl::OUT2,
+            is_rtscts: false,
+            modem_counts: ModemCounts::default(),
+        };
+
+        Self {
+            uart,
+            isa_irq,
+            state: SpinLock::new(state),
+            modem_wait_queue: WaitQueue::new(),
+        }
+    }
+
+    /// Programs the default line settings and enables the interrupts.
+    fn startup(&self) {
+        let mut state = self.state.lock();
+
+        self.uart.int_en.write(0);
+        // This matches the default termios, i.e., `B38400 | CS8`.
+        state.line_ctrl = LineCtrl::WORD_LEN_8;
+        self.set_divisor(&state, (BASE_BAUD / 38400) as u16);
+        self.uart.int_id_fifo_ctrl.write(FIFO_ENABLE_AND_CLEAR);
+        self.uart.modem_ctrl.write(state.modem_ctrl.bits());
+
+        // Clear the pending conditions.
+        self.uart.line_status.read();
+        self.uart.modem_status.read();
+        while LineStatus::from_bits_truncate(self.uart.line_status.read())
+            .contains(LineStatus::DATA_READY)
+        {
+            self.uart.data.read();
+        }
+
+        state.int_en = IntEnable::RX_DATA | IntEnable::LINE_STATUS | IntEnable::MODEM_STATUS;
+        self.uart.int_en.write(state.int_en.bits());
+    }
+
+    fn set_divisor(&self, state: &SerialState, divisor: u16) {
+        let [low, high] = divisor.to_le_bytes();
+        self.uart
+            .line_ctrl
+            .write((state.line_ctrl | LineCtrl::DLAB).bits());
+        self.uart.data.write(low);
+        self.uart.int_en.write(high);
+        self.uart.line_ctrl.write(state.line_ctrl.bits());
+    }
+
+    fn set_int_en(&self, state: &mut SerialState, int_en: IntEnable) {
+        if state.int_en != int_en {
+            state.int_en = int_en;
+            self.uart.int_en.write(int_en.bits());
+        }
+    }
+
+    /// Enables the transmitter interrupt if there are characters that can be sent.
+    ///
+    /// The UART raises the interrupt immediately if the transmitter is already empty, so the
+    /// characters will be sent by the interrupt handler.
+    fn start_tx(&self, state: &mut SerialState) {
+        if state.tx_buffer.is_empty() || (state.is_rtscts && !self.is_cts(state)) {
+            return;
+        }
+
+        let int_en = state.int_en | IntEnable::TX_EMPTY;
+        self.set_int_en(state, int_en);
+    }
+
+    fn is_cts(&self, state: &mut SerialState) -> bool {
+        self.read_modem_status(state).contains(ModemStatus::CTS)
+    }
+
+    /// Reads the modem status register and records the changes of the modem status lines.
+    ///
+    /// Reading the register clears the delta bits, so the register should only be read by this
+    /// method.
+    fn read_modem_status(&self, state: &mut SerialState) -> ModemStatus {
+        let status = ModemStatus::from_bits_truncate(self.uart.modem_status.read());
+
+        let counts = &mut state.modem_counts;
+        if status.contains(ModemStatus::DELTA_CTS) {
+            counts.cts += 1;
+        }
+        if status.contains(ModemStatus::DELTA_DSR) {
+            counts.dsr += 1;
+        }
+        if status.contains(ModemStatus::TRAILING_EDGE_RI) {
+            counts.rng += 1;
+        }
+        if status.contains(ModemStatus::DELTA_DCD) {
+            counts.dcd += 1;
+        }
+
+        if status.intersects(ModemStatus::DELTAS) {
+            self.modem_wait_queue.wake_all();
+        }
+
+        status
+    }
+
+    /// Services the UART in the interrupt handler.
+    fn service(&self, can_receive: bool, events: &mut IrqEvents) {
+        let mut state = self.state.lock();
+
+        if self.uart.int_id_fifo_ctrl.read() & INT_ID_NO_INT != 0 {
+            return;
+        }
+        events.is_pending = true;
+
+        // Receive the characters.
+        if can_receive {
+            while events.rx_len < events.rx_buffer.len() {
+                let status = LineStatus::from_bits_truncate(self.uart.line_status.read());
+                if !status.contains(LineStatus::DATA_READY) {
+                    break;
+                }
+
+                let ch = self.uart.data.read();
+                if status.contains(LineStatus::BREAK) {
+                    // The NUL character that comes with the break is discarded. The break is
+                    // pushed after the characters received before it.
+                    events.has_break = true;
+                    break;
+                }
+                events.rx_buffer[events.rx_len] = ch;
+                events.rx_len += 1;
+            }
+        } else {
+            // Stop receiving until the line discipline has room (see `notify_input`). The
+            // characters are kept in the hardware FIFO.
+            let int_en = state.int_en - IntEnable::RX_DATA - IntEnable::LINE_STATUS;
+            self.set_int_en(&mut state, int_en);
+        }
+
+        // Check the modem status lines.
+        let modem_status = self.read_modem_status(&mut state);
+
+        // Send the characters.
+        let status = LineStatus::from_bits_truncate(self.uart.line_status.read());
+        if !status.contains(LineStatus::THR_EMPTY) {
+            return;
+        }
+        if state.tx_buffer.is_empty()
+            || (state.is_rtscts && !modem_status.contains(ModemStatus::CTS))
+        {
+            let int_en = state.int_en - IntEnable::TX_EMPTY;
+            self.set_int_en(&mut state, int_en);
+            return;
+        }
+        // The transmitter interrupt may have been disabled when CTS was deasserted.
+        let int_en = state.int_en | IntEnable::TX_EMPTY;
+        self.set_int_en(&mut state, int_en);
+        for _ in 0..FIFO_SIZE {
+            let Some(ch) = state.tx_buffer.pop() else {
+                break;
+            };
+            self.uart.data.write(ch);
+        }
+        events.has_sent = true;
+    }
+
+    fn modem_lines(&self) -> ModemLines {
+        let mut state = self.state.lock();
+
+        let modem_ctrl = state.modem_ctrl;
+        let modem_status = self.read_modem_status(&mut state);
+
+        ModemLines::from_ctrl(modem_ctrl) | ModemLines::from_status(modem_status)
+    }
+
+    fn update_modem_lines(&self, set: ModemLines, clear: ModemLines) {
+        let mut state = self.state.lock();
+
+        let modem_ctrl = (state.modem_ctrl - clear.to_ctrl()) | set.to_ctrl() | ModemCtrl::OUT2;
+        self.set_modem_ctrl(&mut state, modem_ctrl);
+    }
+
+    fn set_modem_ctrl(&self, state: &mut SerialState, modem_ctrl: ModemCtrl) {
+        state.modem_ctrl = modem_ctrl;
+        self.uart.modem_ctrl.write(modem_ctrl.bits());
+
+        // In the loopback mode, the modem status lines follow the modem control lines.
+        let modem_status = self.read_modem_status(state);
+        if state.is_rtscts && modem_status.contains(ModemStatus::CTS) {
+            self.start_tx(state);
+        }
+    }
+
+    /// Waits until any of the modem status lines in `lines` changes.
+    fn wait_modem_change(&self, lines: ModemLines) -> Result<()> {
+        let old_counts = {
+            let mut state = self.state.lock();
+            self.read_modem_status(&mut state);
+            state.modem_counts
+        };
+
+        self.modem_wait_queue.pause_until(|| {
+            let state = self.state.lock();
+            state
+                .modem_counts
+                .has_changed(&old_counts, lines)
+                .then_some(())
+        })
+    }
+}
+
+impl TtyDriver for SerialDriver {
+    fn push_output(&self, chs: &[u8]) -> core::result::Result<usize, PushCharError> {
+        let mut state = self.state.lock();
+
+        if state.tx_buffer.is_full() {
+            return Err(PushCharError);
+        }
+
+        let len = state.tx_buffer.free_len().min(chs.len());
+        state.tx_buffer.push_slice(&chs[..len]).unwrap();
+        self.start_tx(&mut state);
+
+        Ok(len)
+    }
+
+    fn drain_output(&self) {
+        self.state.lock().tx_buffer.clear();
+    }
+
+    fn echo_callback(&self) -> impl FnMut(&[u8]) + '_ {
+        |chs| {
+            let mut state = self.state.lock();
+            for ch in chs {
+                let _ = state.tx_buffer.push(*ch);
+            }
+            self.start_tx(&mut state);
+        }
+    }
+
+    fn write_room(&self) -> usize {
+        self.state.lock().tx_buffer.free_len()
+    }
+
+    fn chars_in_buffer(&self) -> usize {
+        self.state.lock().tx_buffer.len()
+    }
+
+    fn notify_input(&self) {
+        let mut state = self.state.lock();
+
+        // If there are characters in the hardware FIFO, the UART will raise the interrupt
+        // immediately.
+        let int_en = state.int_en | IntEnable::RX_DATA | IntEnable::LINE_STATUS;
+        self.set_int_en(&mut state, int_en);
+    }
+
+    fn on_termios_change(&self, old_termios: &CTermios, new_termios: &CTermios) {
+        let old_cflags = old_termios.ctrl_flags();
+        let cflags = new_termios.ctrl_flags();
+
+        let mut line_ctrl = match cflags.size() {
+            Ok(CCtrlSize::CS5) => LineCtrl::empty(),
+            Ok(CCtrlSize::CS6) => LineCtrl::WORD_LEN_6,
+            Ok(CCtrlSize::CS7) => LineCtrl::WORD_LEN_7,
+            Ok(CCtrlSize::CS8) | Err(_) => LineCtrl::WORD_LEN_8,
+        };
+        if cflags.has_two_stop_bits() {
+            line_ctrl |= LineCtrl::TWO_STOP_BITS;
+        }
+        if cflags.is_parity_enabled() {
+            line_ctrl |= LineCtrl::PARITY;
+            if !cflags.is_odd_parity() {
+                line_ctrl |= LineCtrl::EVEN_PARITY;
+            }
+            if cflags.is_mark_space_parity() {
+                line_ctrl |= LineCtrl::STICK_PARITY;
+            }
+        }
+
+        let mut state = self.state.lock();
+
+        // The break is controlled separately (see `set_break`).
+        state.line_ctrl = line_ctrl | (state.line_ctrl & LineCtrl::BREAK);
+
+        let old_rate = old_cflags.baud().map_or(0, |baud| baud.rate());
+        match cflags.baud().map(|baud| baud.rate()) {
+            // `B0` hangs up the line by dropping DTR and RTS.
+            Ok(0) => {
+                let modem_ctrl = state.modem_ctrl - ModemCtrl::DTR - ModemCtrl::RTS;
+                self.set_modem_ctrl(&mut state, modem_ctrl);
+                self.uart.line_ctrl.write(state.line_ctrl.bits());
+            }
+            Ok(rate) => {
+                // Rates that are too high for the UART are rounded down to the highest rate.
+                let divisor = (BASE_BAUD / rate).clamp(1, u16::MAX as u32);
+                self.set_divisor(&state, divisor as u16);
+                if old_rate == 0 {
+                    let modem_ctrl = state.modem_ctrl | ModemCtrl::DTR | ModemCtrl::RTS;
+                    self.set_modem_ctrl(&mut state, modem_ctrl);
+                }
+            }
+            // The baud rate is unknown, so only the other settings are changed.
+            Err(_) => self.uart.line_ctrl.write(state.line_ctrl.bits()),
+        }
+
+        let was_rtscts = state.is_rtscts;
+        state.is_rtscts = cflags.is_rtscts();
+        if was_rtscts && !state.is_rtscts {
+            self.start_tx(&mut state);
+        }
+    }
+
+    fn set_break(&self, is_on: bool) -> bool {
+        let mut state = self.state.lock();
+
+        if is_on {
+            state.line_ctrl |= LineCtrl::BREAK;
+        } else {
+            state.line_ctrl -= LineCtrl::BREAK;
+        }
+        self.uart.line_ctrl.write(state.line_ctrl.bits());
+
+        true
+    }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<Option<i32>> {
+        match cmd {
+            IoctlCmd::TIOCMGET => {
+                let lines = self.modem_lines().bits();
+
+                current_userspace!().write_val(arg, &lines)?;
+            }
+            IoctlCmd::TIOCMSET | IoctlCmd::TIOCMBIS | IoctlCmd::TIOCMBIC => {
+                let lines = ModemLines::from_bits_truncate(current_userspace!().read_val(arg)?);
+
+                let (set, clear) = match cmd {
+                    IoctlCmd::TIOCMSET => (lines, ModemLines::all() - lines),
+                    IoctlCmd::TIOCMBIS => (lines, ModemLines::empty()),
+                    _ => (ModemLines::empty(), lines),
+                };
+                self.update_modem_lines(set, clear);
+            }
+            IoctlCmd::TIOCMIWAIT => {
+                let lines = ModemLines::from_bits_truncate(arg as u32);
+
+                self.wait_modem_change(lines)?;
+            }
+            _ => return Ok(None),
+        }
+
+        Ok(Some(0))
+    }
+
+    fn device_id(&self, index: u32) -> DeviceId {
+        DeviceId::new(TTY_MAJOR, SERIAL_MINOR_BASE + index)
+    }
+}
+
+/// The registers of a 16550 UART.
+struct Uart {
+    /// Receiver buffer (read) or transmitter holding (write) register; or the low byte of the
+    /// divisor latch if DLAB is set.
+    data: IoPort<u8, ReadWriteAccess>,
+    /// Interrupt enable register; or the high byte of the divisor latch if DLAB is set.
+    int_en: IoPort<u8, ReadWriteAccess>,
+    /// Interrupt identification register (read) or FIFO control register (write).
+    int_id_fifo_ctrl: IoPort<u8, ReadWriteAccess>,
+    /// Line control register.
+    line_ctrl: IoPort<u8, ReadWriteAccess>,
+    /// Modem control register.
+    modem_ctrl: IoPort<u8, ReadWriteAccess>,
+    /// Line status register.
+    line_status: IoPort<u8, ReadWriteAccess>,
+    /// Modem status register.
+    modem_status: IoPort<u8, ReadWriteAccess>,
+    /// Scratch register.
+    scratch: IoPort<u8, ReadWriteAccess>,
+}
+
+impl Uart {
+    /// Acquires the I/O ports of the UART at `base`.
+    fn acquire(base: u16) -> Option<Self> {
+        let port = |offset| IoPort::acquire(base + offset).ok();
+
+        Some(Self {
+            data: port(0)?,
+            int_en: port(1)?,
+            int_id_fifo_ctrl: port(2)?,
+            line_ctrl: port(3)?,
+            modem_ctrl: port(4)?,
+            line_status: port(5)?,
+            modem_status: port(6)?,
+            scratch: port(7)?,
+        })
+    }
+
+    /// Checks whether the UART is present.
+    fn probe(&self) -> bool {
+        // If nothing is connected to the ports, the reads will return `0xFF`.
+        [0x55, 0xAA].into_iter().all(|value| {
+            self.scratch.write(value);
+            self.scratch.read() == value
+        })
+    }
+}
+
+/// The value of the FIFO control register that enables the FIFOs, clears them, and sets the
+/// receiver trigger level to 14 bytes.
+const FIFO_ENABLE_AND_CLEAR: u8 = 0xC7;
+
+/// The bit in the interrupt identification register that indicates no pending interrupts.
+const INT_ID_NO_INT: u8 = 0x01;
+
+bitflags! {
+    /// The interrupt enable register.
+    struct IntEnable: u8 {
+        const RX_DATA       = 1 << 0;
+        const TX_EMPTY      = 1 << 1;
+        const LINE_STATUS   = 1 << 2;
+        const MODEM_STATUS  = 1 << 3;
+    }
+}
+
+bitflags! {
+    /// The line control register.
+    struct LineCtrl: u8 {
+        const WORD_LEN_6    = 0b01;
+        const WORD_LEN_7    = 0b10;
+        const WORD_LEN_8    = 0b11;
+        const TWO_STOP_BITS = 1 << 2;
+        const PARITY        = 1 << 3;
+        const EVEN_PARITY   = 1 << 4;
+        const STICK_PARITY  = 1 << 5;
+        const BREAK         = 1 << 6;
+        /// Divisor latch access bit.
+        const DLAB          = 1 << 7;
+    }
+}
+
+bitflags! {
+    /// The modem control register.
+    struct ModemCtrl: u8 {
+        const DTR           = 1 << 0;
+        const RTS           = 1 << 1;
+        const OUT1          = 1 << 2;
+        const OUT2          = 1 << 3;
+        const LOOP          = 1 << 4;
+    }
+}
+
+bitflags! {
+    /// The line status register.
+    struct LineStatus: u8 {
+        const DATA_READY    = 1 << 0;
+        const BREAK         = 1 << 4;
+        const THR_EMPTY     = 1 << 5;
+    }
+}
+
+bitflags! {
+    /// The modem status register.
+    struct ModemStatus: u8 {
+        const DELTA_CTS         = 1 << 0;
+        const DELTA_DSR         = 1 << 1;
+        const TRAILING_EDGE_RI  = 1 << 2;
+        const DELTA_DCD         = 1 << 3;
+        const CTS               = 1 << 4;
+        const DSR               = 1 << 5;
+        const RI                = 1 << 6;
+        const DCD               = 1 << 7;
+
+        const DELTAS = Self::DELTA_CTS.bits
+            | Self::DELTA_DSR.bits
+            | Self::TRAILING_EDGE_RI.bits
+            | Self::DELTA_DCD.bits;
+    }
+}
+
+bitflags! {
+    /// The modem lines; `TIOCM_*` in Linux.
+    ///
+    /// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termios.h#L22>.
+    struct ModemLines: u32 {
+        const DTR   = 0x002;
+        const RTS   = 0x004;
+        const CTS   = 0x020;
+        const CAR   = 0x040;
+        const RNG   = 0x080;
+        const DSR   = 0x100;
+        const OUT1  = 0x2000;
+        const OUT2  = 0x4000;
+        const LOOP  = 0x8000;
+    }
+}
+
+impl ModemLines {
+    fn from_ctrl(ctrl: ModemCtrl) -> Self {
+        let mut lines = Self::empty();
+        for (ctrl_bit, line) in Self::CTRL_MAP {
+            if ctrl.contains(ctrl_bit) {
+                lines |= line;
+            }
+        }
+        lines
+    }
+
+    fn to_ctrl(self) -> ModemCtrl {
+        let mut ctrl = ModemCtrl::empty();
+        for (ctrl_bit, line) in Self::CTRL_MAP {
+            if self.contains(line) {
+                ctrl |= ctrl_bit;
+            }
+        }
+        ctrl
+    }
+
+    fn from_status(status: ModemStatus) -> Self {
+        let mut lines = Self::empty();
+        for (status_bit, line) in [
+            (ModemStatus::CTS, Self::CTS),
+            (ModemStatus::DSR, Self::DSR),
+            (ModemStatus::RI, Self::RNG),
+            (ModemStatus::DCD, Self::CAR),
+        ] {
+            if status.contains(status_bit) {
+                lines |= line;
+            }
+        }
+        lines
+    }
+
+    const CTRL_MAP: [(ModemCtrl, Self); 5] = [
+        (ModemCtrl::DTR, Self::DTR),
+        (ModemCtrl::RTS, Self::RTS),
+        (ModemCtrl::OUT1, Self::OUT1),
+        (ModemCtrl::OUT2, Self::OUT2),
+        (ModemCtrl::LOOP, Self::LOOP),
+    ];
+}
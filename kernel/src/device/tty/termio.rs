@@ -7,6 +7,11 @@ use crate::prelude::*;
 /// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits-common.h#L5>.
 type CCtrlChar = u8;
 
+/// The value that disables a special character; `__DISABLED_CHAR` in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/tty.h#L15>.
+const DISABLED_CHAR: CCtrlChar = b'\0';
+
 bitflags! {
     /// The input flags; `c_iflags` bits in Linux.
     #[derive(Pod)]
@@ -52,6 +57,8 @@ bitflags! {
         const ONLRET = 1 << 5;
         const OFILL  = 1 << 6;
         const OFDEL  = 1 << 7;
+        // https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits.h#L84
+        const XTABS  = 0x1800;			/* Expand tabs to spaces */
     }
 }
 
@@ -76,26 +83,58 @@ impl Default for CCtrlFlags {
 }
 
 impl CCtrlFlags {
+    // https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits.h#L96
     const BAUD_MASK: u32 = 0x0000100f;
     const SIZE_MASK: u32 = 0x00000030;
+    const STOP_BIT: u32 = 0x00000040;
     const READ_BIT: u32 = 0x00000080;
+    const PARITY_BIT: u32 = 0x00000100;
+    const ODD_PARITY_BIT: u32 = 0x00000200;
+    const MARK_SPACE_PARITY_BIT: u32 = 0x40000000;
+    const RTSCTS_BIT: u32 = 0x80000000;
 
-    #[expect(dead_code)]
     pub(super) fn baud(&self) -> Result<CCtrlBaud> {
         let baud = self.0 & Self::BAUD_MASK;
         Ok(CCtrlBaud::try_from(baud)?)
     }
 
-    #[expect(dead_code)]
     pub(super) fn size(&self) -> Result<CCtrlSize> {
         let size = self.0 & Self::SIZE_MASK;
         Ok(CCtrlSize::try_from(size)?)
     }
 
+    /// Returns whether two stop bits are used instead of one (`CSTOPB`).
+    pub(super) fn has_two_stop_bits(&self) -> bool {
+        self.0 & Self::STOP_BIT != 0
+    }
+
     #[expect(dead_code)]
     pub(super) fn is_read(&self) -> bool {
         self.0 & Self::READ_BIT != 0
     }
+
+    /// Returns whether the parity bit is generated and checked (`PARENB`).
+    pub(super) fn is_parity_enabled(&self) -> bool {
+        self.0 & Self::PARITY_BIT != 0
+    }
+
+    /// Returns whether the parity is odd instead of even (`PARODD`).
+    pub(super) fn is_odd_parity(&self) -> bool {
+        self.0 & Self::ODD_PARITY_BIT != 0
+    }
+
+    /// Returns whether the parity is mark or space (`CMSPAR`).
+    ///
+    /// If this is set, the parity bit is always one for the odd parity (i.e., mark) and always
+    /// zero for the even parity (i.e., space).
+    pub(super) fn is_mark_space_parity(&self) -> bool {
+        self.0 & Self::MARK_SPACE_PARITY_BIT != 0
+    }
+
+    /// Returns whether the RTS/CTS hardware flow control is enabled (`CRTSCTS`).
+    pub(super) fn is_rtscts(&self) -> bool {
+        self.0 & Self::RTSCTS_BIT != 0
+    }
 }
 
 /// The size part of the control flags ([`CCtrlFlags`]).
@@ -130,6 +169,61 @@ pub(super) enum CCtrlBaud {
     B9600 = 0x0000000d,
     B19200 = 0x0000000e,
     B38400 = 0x0000000f,
+    // https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits.h#L109
+    B57600 = 0x00001001,
+    B115200 = 0x00001002,
+    B230400 = 0x00001003,
+    B460800 = 0x00001004,
+    B500000 = 0x00001005,
+    B576000 = 0x00001006,
+    B921600 = 0x00001007,
+    B1000000 = 0x00001008,
+    B1152000 = 0x00001009,
+    B1500000 = 0x0000100a,
+    B2000000 = 0x0000100b,
+    B2500000 = 0x0000100c,
+    B3000000 = 0x0000100d,
+    B3500000 = 0x0000100e,
+    B4000000 = 0x0000100f,
+}
+
+impl CCtrlBaud {
+    /// Returns the baud rate in bits per second.
+    pub(super) fn rate(self) -> u32 {
+        match self {
+            Self::B0 => 0,
+            Self::B50 => 50,
+            Self::B75 => 75,
+            Self::B110 => 110,
+            Self::B134 => 134,
+            Self::B150 => 150,
+            Self::B200 => 200,
+            Self::B300 => 300,
+            Self::B600 => 600,
+            Self::B1200 => 1200,
+            Self::B1800 => 1800,
+            Self::B2400 => 2400,
+            Self::B4800 => 4800,
+            Self::B9600 => 9600,
+            Self::B19200 => 19200,
+            Self::B38400 => 38400,
+            Self::B57600 => 57600,
+            Self::B115200 => 115200,
+            Self::B230400 => 230400,
+            Self::B460800 => 460800,
+            Self::B500000 => 500000,
+            Self::B576000 => 576000,
+            Self::B921600 => 921600,
+            Self::B1000000 => 1000000,
+            Self::B1152000 => 1152000,
+            Self::B1500000 => 1500000,
+            Self::B2000000 => 2000000,
+            Self::B2500000 => 2500000,
+            Self::B3000000 => 3000000,
+            Self::B3500000 => 3500000,
+            Self::B4000000 => 4000000,
+        }
+    }
 }
 
 bitflags! {
@@ -230,7 +324,7 @@ impl CCtrlCharId {
 /// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/asm-generic/termbits.h#L30>.
 #[derive(Debug, Clone, Copy, Pod)]
 #[repr(C)]
-pub(super) struct CTermios {
+pub struct CTermios {
     c_iflags: CInputFlags,
     c_oflags: COutputFlags,
     c_cflags: CCtrlFlags,
@@ -284,6 +378,15 @@ impl CTermios {
         &mut self.c_cc[id as usize]
     }
 
+    /// Returns whether `ch` is the special character identified by `id`.
+    ///
+    /// A special character is disabled if it is set to [`DISABLED_CHAR`]. A disabled
+    /// special character never matches any characters.
+    pub(super) fn is_special_char(&self, ch: u8, id: CCtrlCharId) -> bool {
+        let special_char = self.special_char(id);
+        special_char != DISABLED_CHAR && ch == special_char
+    }
+
     /// Returns whether the terminal is in the canonical mode.
     ///
     /// The canonical mode means that the input characters will be handled by lines, not by single
@@ -292,27 +395,33 @@ impl CTermios {
         self.c_lflags.contains(CLocalFlags::ICANON)
     }
 
-    /// Returns whether the input flags contain `ICRNL`.
-    ///
-    /// The `ICRNL` flag means the `\r` characters in the input should be mapped to `\n`.
-    pub(super) fn contains_icrnl(&self) -> bool {
-        self.c_iflags.contains(CInputFlags::ICRNL)
+    pub(super) fn input_flags(&self) -> CInputFlags {
+        self.c_iflags
     }
 
-    pub(super) fn contains_isig(&self) -> bool {
-        self.c_lflags.contains(CLocalFlags::ISIG)
+    pub(super) fn output_flags(&self) -> COutputFlags {
+        self.c_oflags
     }
 
-    pub(super) fn contain_echo(&self) -> bool {
-        self.c_lflags.contains(CLocalFlags::ECHO)
+    pub(super) fn ctrl_flags(&self) -> CCtrlFlags {
+        self.c_cflags
     }
 
-    pub(super) fn contains_echo_ctl(&self) -> bool {
-        self.c_lflags.contains(CLocalFlags::ECHOCTL)
+    pub(super) fn local_flags(&self) -> CLocalFlags {
+        self.c_lflags
     }
 
-    pub(super) fn contains_iexten(&self) -> bool {
-        self.c_lflags.contains(CLocalFlags::IEXTEN)
+    pub(super) fn local_flags_mut(&mut self) -> &mut CLocalFlags {
+        &mut self.c_lflags
+    }
+
+    /// Returns the number of the line discipline.
+    pub(super) fn line(&self) -> u8 {
+        self.c_line
+    }
+
+    pub(super) fn set_line(&mut self, line: u8) {
+        self.c_line = line;
     }
 }
 
@@ -327,3 +436,15 @@ pub(super) struct CWinSize {
     ws_xpixel: u16,
     ws_ypixel: u16,
 }
+
+impl CWinSize {
+    /// Creates a window size with the given number of rows and columns.
+    pub(super) fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
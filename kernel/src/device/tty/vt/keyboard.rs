@@ -0,0 +1,290 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The keyboard input of the virtual terminals.
+//!
+//! The key events from the input devices are translated into characters with the US keyboard
+//! layout. Like the Linux console, the cursor keys and the function keys are translated into
+//! escape sequences, and the `Alt` modifier prefixes the characters with `ESC`.
+//!
+//! Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/drivers/tty/vt/defkeymap.map>.
+
+use aster_framebuffer::{FRAMEBUFFER_CONSOLE, NR_CONSOLES};
+use aster_input::{
+    key::{Key, KeyStatus},
+    InputEvent,
+};
+use ostd::sync::LocalIrqDisabled;
+
+use crate::prelude::*;
+
+bitflags! {
+    /// The modifier keys that are held down.
+    struct Modifiers: u8 {
+        const LEFT_SHIFT  = 1 << 0;
+        const RIGHT_SHIFT = 1 << 1;
+        const LEFT_CTRL   = 1 << 2;
+        const RIGHT_CTRL  = 1 << 3;
+        const LEFT_ALT    = 1 << 4;
+        const RIGHT_ALT   = 1 << 5;
+    }
+}
+
+impl Modifiers {
+    fn from_key(key: Key) -> Option<Self> {
+        let modifier = match key {
+            Key::LeftShift => Self::LEFT_SHIFT,
+            Key::RightShift => Self::RIGHT_SHIFT,
+            Key::LeftCtrl => Self::LEFT_CTRL,
+            Key::RightCtrl => Self::RIGHT_CTRL,
+            Key::LeftAlt => Self::LEFT_ALT,
+            Key::RightAlt => Self::RIGHT_ALT,
+            _ => return None,
+        };
+        Some(modifier)
+    }
+
+    fn is_shift(&self) -> bool {
+        self.intersects(Self::LEFT_SHIFT | Self::RIGHT_SHIFT)
+    }
+
+    fn is_ctrl(&self) -> bool {
+        self.intersects(Self::LEFT_CTRL | Self::RIGHT_CTRL)
+    }
+
+    fn is_alt(&self) -> bool {
+        self.intersects(Self::LEFT_ALT | Self::RIGHT_ALT)
+    }
+}
+
+struct KeyboardState {
+    modifiers: Modifiers,
+    is_capslock_on: bool,
+}
+
+static KEYBOARD: SpinLock<KeyboardState, LocalIrqDisabled> = SpinLock::new(KeyboardState {
+    modifiers: Modifiers::empty(),
+    is_capslock_on: false,
+});
+
+pub(super) fn init() {
+    for (_, device) in aster_input::all_devices() {
+        device.register_callbacks(&handle_event);
+    }
+}
+
+fn handle_event(event: InputEvent) {
+    let InputEvent::KeyBoard(key, status) = event;
+    let is_pressed = status == KeyStatus::Pressed;
+
+    let mut keyboard = KEYBOARD.lock();
+
+    if let Some(modifier) = Modifiers::from_key(key) {
+        keyboard.modifiers.set(modifier, is_pressed);
+        return;
+    }
+    if !is_pressed {
+        return;
+    }
+    if key == Key::Capslock {
+        keyboard.is_capslock_on = !keyboard.is_capslock_on;
+        return;
+    }
+
+    let modifiers = keyboard.modifiers;
+    let is_capslock_on = keyboard.is_capslock_on;
+    drop(keyboard);
+
+    if modifiers.is_alt() {
+        if let Some(index) = console_to_switch(key) {
+            super::activate(index);
+            return;
+        }
+    }
+
+    let mut buf = [0u8; 2];
+    let input = match translate_key(key, modifiers, is_capslock_on) {
+        Some(KeyInput::Char(ch)) if modifiers.is_alt() => {
+            buf = [0x1b, ch];
+            &buf[..]
+        }
+        Some(KeyInput::Char(ch)) => {
+            buf[0] = ch;
+            &buf[..1]
+        }
+        Some(KeyInput::Sequence(seq)) => seq,
+        None => return,
+    };
+
+    // Like the input from other consoles, the characters are dropped if the buffer is full.
+    let _ = super::active_tty().push_input(input);
+}
+
+/// Returns the index of the virtual console that `Alt` plus the key switches to.
+fn console_to_switch(key: Key) -> Option<usize> {
+    let active = FRAMEBUFFER_CONSOLE.get().unwrap().active_console();
+
+    let index = match key {
+        Key::Left => (active + NR_CONSOLES - 1) % NR_CONSOLES,
+        Key::Right => (active + 1) % NR_CONSOLES,
+        _ => function_key_num(key)? - 1,
+    };
+    (index < NR_CONSOLES).then_some(index)
+}
+
+/// Returns `N` if the key is `FN`.
+fn function_key_num(key: Key) -> Option<usize> {
+    let num = match key {
+        Key::F1 => 1,
+        Key::F2 => 2,
+        Key::F3 => 3,
+        Key::F4 => 4,
+        Key::F5 => 5,
+        Key::F6 => 6,
+        Key::F7 => 7,
+        Key::F8 => 8,
+        Key::F9 => 9,
+        Key::F10 => 10,
+        Key::F11 => 11,
+        Key::F12 => 12,
+        _ => return None,
+    };
+    Some(num)
+}
+
+enum KeyInput {
+    Char(u8),
+    Sequence(&'static [u8]),
+}
+
+fn translate_key(key: Key, modifiers: Modifiers, is_capslock_on: bool) -> Option<KeyInput> {
+    if let Some(seq) = key_to_sequence(key) {
+        return Some(KeyInput::Sequence(seq));
+    }
+
+    let (normal, shifted) = key_to_chars(key)?;
+    let is_shifted = if normal.is_ascii_lowercase() {
+        modifiers.is_shift() != is_capslock_on
+    } else {
+        modifiers.is_shift()
+    };
+    let ch = if is_shifted { shifted } else { normal };
+
+    if !modifiers.is_ctrl() {
+        return Some(KeyInput::Char(ch));
+    }
+
+    let ch = match ch {
+        b'a'..=b'z' | b'@'..=b'_' => ch & 0x1f,
+        b' ' => 0x00,
+        b'?' => 0x7f,
+        _ => ch,
+    };
+    Some(KeyInput::Char(ch))
+}
+
+/// Returns the characters of the key without and with `Shift`.
+fn key_to_chars(key: Key) -> Option<(u8, u8)> {
+    let chars = match key {
+        Key::ESC => (0x1b, 0x1b),
+        Key::One => (b'1', b'!'),
+        Key::Two => (b'2', b'@'),
+        Key::Three => (b'3', b'#'),
+        Key::Four => (b'4', b'$'),
+        Key::Five => (b'5', b'%'),
+        Key::Six => (b'6', b'^'),
+        Key::Seven => (b'7', b'&'),
+        Key::Eight => (b'8', b'*'),
+        Key::Nine => (b'9', b'('),
+        Key::Zero => (b'0', b')'),
+        Key::Minus => (b'-', b'_'),
+        Key::Equal => (b'=', b'+'),
+        Key::BackSpace => (0x7f, 0x7f),
+        Key::Tab => (b'\t', b'\t'),
+        Key::Q => (b'q', b'Q'),
+        Key::W => (b'w', b'W'),
+        Key::E => (b'e', b'E'),
+        Key::R => (b'r', b'R'),
+        Key::T => (b't', b'T'),
+        Key::Y => (b'y', b'Y'),
+        Key::U => (b'u', b'U'),
+        Key::I => (b'i', b'I'),
+        Key::O => (b'o', b'O'),
+        Key::P => (b'p', b'P'),
+        Key::LeftBrace => (b'[', b'{'),
+        Key::RightBrace => (b']', b'}'),
+        Key::Enter | Key::KpEnter => (b'\r', b'\r'),
+        Key::A => (b'a', b'A'),
+        Key::S => (b's', b'S'),
+        Key::D => (b'd', b'D'),
+        Key::F => (b'f', b'F'),
+        Key::G => (b'g', b'G'),
+        Key::H => (b'h', b'H'),
+        Key::J => (b'j', b'J'),
+        Key::K => (b'k', b'K'),
+        Key::L => (b'l', b'L'),
+        Key::SemiColon => (b';', b':'),
+        Key::Apostrophe => (b'\'', b'"'),
+        Key::Grave => (b'`', b'~'),
+        Key::BackSlash => (b'\\', b'|'),
+        Key::Z => (b'z', b'Z'),
+        Key::X => (b'x', b'X'),
+        Key::C => (b'c', b'C'),
+        Key::V => (b'v', b'V'),
+        Key::B => (b'b', b'B'),
+        Key::N => (b'n', b'N'),
+        Key::M => (b'm', b'M'),
+        Key::Comma => (b',', b'<'),
+        Key::Dot => (b'.', b'>'),
+        Key::Slash => (b'/', b'?'),
+        Key::Space => (b' ', b' '),
+        Key::LineFeed => (b'\n', b'\n'),
+        // The keypad always works as if `NumLock` is on.
+        Key::KpAsterisk => (b'*', b'*'),
+        Key::KpMinus => (b'-', b'-'),
+        Key::KpPlus => (b'+', b'+'),
+        Key::KpSlash => (b'/', b'/'),
+        Key::KpDot => (b'.', b'.'),
+        Key::Kp0 => (b'0', b'0'),
+        Key::Kp1 => (b'1', b'1'),
+        Key::Kp2 => (b'2', b'2'),
+        Key::Kp3 => (b'3', b'3'),
+        Key::Kp4 => (b'4', b'4'),
+        Key::Kp5 => (b'5', b'5'),
+        Key::Kp6 => (b'6', b'6'),
+        Key::Kp7 => (b'7', b'7'),
+        Key::Kp8 => (b'8', b'8'),
+        Key::Kp9 => (b'9', b'9'),
+        _ => return None,
+    };
+    Some(chars)
+}
+
+/// Returns the escape sequence of the key, which is the same as that of the Linux console.
+fn key_to_sequence(key: Key) -> Option<&'static [u8]> {
+    let seq: &[u8] = match key {
+        Key::Up => b"\x1b[A",
+        Key::Down => b"\x1b[B",
+        Key::Right => b"\x1b[C",
+        Key::Left => b"\x1b[D",
+        Key::Home => b"\x1b[1~",
+        Key::Insert => b"\x1b[2~",
+        Key::Delete => b"\x1b[3~",
+        Key::End => b"\x1b[4~",
+        Key::PageUp => b"\x1b[5~",
+        Key::PageDown => b"\x1b[6~",
+        Key::F1 => b"\x1b[[A",
+        Key::F2 => b"\x1b[[B",
+        Key::F3 => b"\x1b[[C",
+        Key::F4 => b"\x1b[[D",
+        Key::F5 => b"\x1b[[E",
+        Key::F6 => b"\x1b[17~",
+        Key::F7 => b"\x1b[18~",
+        Key::F8 => b"\x1b[19~",
+        Key::F9 => b"\x1b[20~",
+        Key::F10 => b"\x1b[21~",
+        Key::F11 => b"\x1b[23~",
+        Key::F12 => b"\x1b[24~",
+        _ => return None,
+    };
+    Some(seq)
+}
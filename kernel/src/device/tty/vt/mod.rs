@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The virtual terminals (i.e., `/dev/tty1` to `/dev/tty6`).
+//!
+//! The virtual terminals are multiplexed over the framebuffer console. Only the active one is
+//! displayed and receives the keyboard input. The active virtual terminal can be switched by
+//! pressing `Alt+Fn` or by the `VT_ACTIVATE` ioctl.
+//!
+//! Note that the virtual terminals are numbered from one, while the virtual consoles of
+//! [`FramebufferConsole`] are indexed from zero.
+//!
+//! Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/drivers/tty/vt/vt_ioctl.c>.
+
+use aster_framebuffer::{ConsoleMode, FramebufferConsole, FRAMEBUFFER_CONSOLE, NR_CONSOLES};
+use ostd::sync::WaitQueue;
+use spin::Once;
+
+use super::{
+    termio::{CTermios, CWinSize},
+    PushCharError, Tty, TtyDriver, TTY_MAJOR,
+};
+use crate::{
+    current_userspace,
+    fs::{device::DeviceId, utils::IoctlCmd},
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::AsPosixThread, Terminal},
+};
+
+mod keyboard;
+
+/// A virtual terminal, i.e., `/dev/ttyN`.
+pub type VtTty = Tty<VtDriver>;
+
+static VT_TTY: Once<Box<[Arc<VtTty>]>> = Once::new();
+
+/// The wait queue for the processes waiting for a virtual terminal to become active.
+static VT_ACTIVATE_WAIT_QUEUE: WaitQueue = WaitQueue::new();
+
+pub(super) fn init() {
+    let Some(console) = FRAMEBUFFER_CONSOLE.get() else {
+        VT_TTY.call_once(Box::default);
+        return;
+    };
+
+    let (cols, rows) = console.size();
+    let winsize = CWinSize::new(rows as u16, cols as u16);
+
+    let ttys = (0..NR_CONSOLES)
+        .map(|index| {
+            let driver = VtDriver {
+                console: console.clone(),
+                index,
+            };
+            let tty = Tty::new(index as u32 + 1, driver);
+            tty.set_window_size(winsize);
+            tty
+        })
+        .collect();
+    VT_TTY.call_once(|| ttys);
+
+    keyboard::init();
+}
+
+/// Iterates all virtual terminals, i.e., `/dev/tty1`, `/dev/tty2`, e.t.c.
+pub fn iter_vt_tty() -> impl Iterator<Item = &'static Arc<VtTty>> {
+    VT_TTY.get().unwrap().iter()
+}
+
+/// Returns the active virtual terminal.
+fn active_tty() -> &'static Arc<VtTty> {
+    let index = FRAMEBUFFER_CONSOLE.get().unwrap().active_console();
+    &VT_TTY.get().unwrap()[index]
+}
+
+/// Switches to the virtual console at `index`.
+fn activate(index: usize) {
+    let console = FRAMEBUFFER_CONSOLE.get().unwrap();
+
+    // The framebuffer console may have been disabled during the boot. Switching to a virtual
+    // terminal explicitly brings it back.
+    console.enable();
+    console.activate(index).unwrap();
+
+    VT_ACTIVATE_WAIT_QUEUE.wake_all();
+}
+
+/// Converts the argument of `VT_ACTIVATE` or `VT_WAITACTIVE`, which is the number of a virtual
+/// terminal, to the index of the virtual console.
+fn vt_num_to_index(arg: usize) -> Result<usize> {
+    if arg == 0 || arg > NR_CONSOLES {
+        return_errno_with_message!(Errno::ENXIO, "the virtual terminal does not exist");
+    }
+
+    Ok(arg - 1)
+}
+
+/// The TTY driver of a virtual terminal.
+pub struct VtDriver {
+    console: Arc<FramebufferConsole>,
+    index: usize,
+}
+
+impl VtDriver {
+    /// Checks whether the current process can change the states of the virtual terminals.
+    ///
+    /// Like Linux, this requires the virtual terminal to be the controlling terminal or the
+    /// `CAP_SYS_TTY_CONFIG` capability.
+    fn check_permission(&self) -> Result<()> {
+        let this = VT_TTY.get().unwrap()[self.index].clone() as Arc<dyn Terminal>;
+        if current!()
+            .terminal()
+            .is_some_and(|terminal| Arc::ptr_eq(&terminal, &this))
+        {
+            return Ok(());
+        }
+
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        if posix_thread
+            .credentials()
+            .effective_capset()
+            .contains(CapSet::SYS_TTY_CONFIG)
+        {
+            return Ok(());
+        }
+
+        return_errno_with_message!(
+            Errno::EPERM,
+            "configuring virtual terminals requires the controlling terminal or `CAP_SYS_TTY_CONFIG`"
+        );
+    }
+}
+
+impl TtyDriver for VtDriver {
+    fn push_output(&self, chs: &[u8]) -> core::result::Result<usize, PushCharError> {
+        self.console.send(self.index, chs);
+        Ok(chs.len())
+    }
+
+    fn drain_output(&self) {}
+
+    fn echo_callback(&self) -> impl FnMut(&[u8]) + '_ {
+        |chs| self.console.send(self.index, chs)
+    }
+
+    fn write_room(&self) -> usize {
+        usize::MAX
+    }
+
+    fn chars_in_buffer(&self) -> usize {
+        0
+    }
+
+    fn notify_input(&self) {}
+
+    fn on_termios_change(&self, _old_termios: &CTermios, _new_termios: &CTermios) {}
+
+    fn set_break(&self, _is_on: bool) -> bool {
+        false
+    }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<Option<i32>> {
+        match cmd {
+            IoctlCmd::VT_GETSTATE => {
+                // Bit 0 stands for `/dev/tty0`, and all the virtual terminals are always
+                // allocated.
+                let state = CVtStat {
+                    v_active: self.console.active_console() as u16 + 1,
+                    v_signal: 0,
+                    v_state: (1 << (NR_CONSOLES + 1)) - 1,
+                };
+
+                current_userspace!().write_val(arg, &state)?;
+            }
+            IoctlCmd::VT_ACTIVATE => {
+                self.check_permission()?;
+                let index = vt_num_to_index(arg)?;
+
+                activate(index);
+            }
+            IoctlCmd::VT_WAITACTIVE => {
+                let index = vt_num_to_index(arg)?;
+
+                VT_ACTIVATE_WAIT_QUEUE
+                    .pause_until(|| (self.console.active_console() == index).then_some(()))?;
+            }
+            IoctlCmd::KDGETMODE => {
+                let mode = match self.console.mode(self.index) {
+                    ConsoleMode::Text => KD_TEXT,
+                    ConsoleMode::Graphics => KD_GRAPHICS,
+                };
+
+                current_userspace!().write_val(arg, &mode)?;
+            }
+            IoctlCmd::KDSETMODE => {
+                self.check_permission()?;
+                let mode = match arg as u32 {
+                    KD_TEXT | KD_TEXT0 | KD_TEXT1 => ConsoleMode::Text,
+                    KD_GRAPHICS => ConsoleMode::Graphics,
+                    _ => return_errno_with_message!(Errno::EINVAL, "the console mode is invalid"),
+                };
+
+                self.console.set_mode(self.index, mode);
+            }
+            _ => return Ok(None),
+        }
+
+        Ok(Some(0))
+    }
+
+    fn device_id(&self, index: u32) -> DeviceId {
+        // The minor device number is the number of the virtual terminal (e.g., 1 for `/dev/tty1`).
+        DeviceId::new(TTY_MAJOR, index)
+    }
+}
+
+/// The state of the virtual terminals; `vt_stat` in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/vt.h#L33>.
+#[derive(Debug, Clone, Copy, Pod)]
+#[repr(C)]
+struct CVtStat {
+    /// The active virtual terminal.
+    v_active: u16,
+    /// The signal to send (unused).
+    v_signal: u16,
+    /// The bitmask of the allocated virtual terminals.
+    v_state: u16,
+}
+
+// Arguments of `KDSETMODE`.
+// Reference: <https://elixir.bootlin.com/linux/v6.0.9/source/include/uapi/linux/kd.h#L45>
+const KD_TEXT: u32 = 0x00;
+const KD_GRAPHICS: u32 = 0x01;
+const KD_TEXT0: u32 = 0x02;
+const KD_TEXT1: u32 = 0x03;
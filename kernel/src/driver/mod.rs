@@ -1,8 +1,5 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::string::ToString;
-
-use aster_framebuffer::{CONSOLE_NAME, FRAMEBUFFER_CONSOLE};
 use log::info;
 
 pub fn init() {
@@ -10,8 +7,4 @@ pub fn init() {
     for (name, _) in aster_input::all_devices() {
         info!("Found Input device, name:{}", name);
     }
-
-    if let Some(console) = FRAMEBUFFER_CONSOLE.get() {
-        aster_console::register_device(CONSOLE_NAME.to_string(), console.clone());
-    }
 }
@@ -6,7 +6,7 @@
 use super::*;
 use crate::{
     events::IoEvents,
-    fs::inode_handle::FileIo,
+    fs::{inode_handle::FileIo, utils::StatusFlags},
     process::signal::{PollHandle, Pollable},
 };
 
@@ -192,11 +192,11 @@ impl Pollable for Inner {
 }
 
 impl FileIo for Inner {
-    fn read(&self, writer: &mut VmWriter) -> Result<usize> {
+    fn read(&self, writer: &mut VmWriter, _status_flags: StatusFlags) -> Result<usize> {
         return_errno_with_message!(Errno::EINVAL, "cannot read ptmx");
     }
 
-    fn write(&self, reader: &mut VmReader) -> Result<usize> {
+    fn write(&self, reader: &mut VmReader, _status_flags: StatusFlags) -> Result<usize> {
         return_errno_with_message!(Errno::EINVAL, "cannot write ptmx");
     }
 }
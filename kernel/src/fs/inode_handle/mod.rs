@@ -47,7 +47,7 @@ struct InodeHandle_ {
 impl InodeHandle_ {
     pub fn read(&self, writer: &mut VmWriter) -> Result<usize> {
         if let Some(ref file_io) = self.file_io {
-            return file_io.read(writer);
+            return file_io.read(writer, self.status_flags());
         }
 
         if !self.dentry.inode().is_seekable() {
@@ -64,7 +64,7 @@ impl InodeHandle_ {
 
     pub fn write(&self, reader: &mut VmReader) -> Result<usize> {
         if let Some(ref file_io) = self.file_io {
-            return file_io.write(reader);
+            return file_io.write(reader, self.status_flags());
         }
 
         if !self.dentry.inode().is_seekable() {
@@ -114,6 +114,12 @@ impl InodeHandle_ {
     }
 
     pub fn seek(&self, pos: SeekFrom) -> Result<usize> {
+        if let Some(ref file_io) = self.file_io {
+            if let Some(result) = file_io.seek(pos) {
+                return result;
+            }
+        }
+
         let mut offset = self.offset.lock();
         let new_offset: isize = match pos {
             SeekFrom::Start(off /* as usize */) => {
@@ -132,6 +138,19 @@ impl InodeHandle_ {
             SeekFrom::Current(off /* as isize */) => (*offset as isize)
                 .checked_add(off)
                 .ok_or_else(|| Error::with_message(Errno::EOVERFLOW, "file offset overflow"))?,
+            // Like Linux's generic implementation, the whole file is considered as data, with an
+            // implicit hole at the end of the file.
+            SeekFrom::Data(off) | SeekFrom::Hole(off) => {
+                let file_size = self.dentry.size();
+                if off >= file_size {
+                    return_errno_with_message!(Errno::ENXIO, "file offset is beyond the end");
+                }
+                if matches!(pos, SeekFrom::Data(_)) {
+                    off as isize
+                } else {
+                    file_size as isize
+                }
+            }
         };
         if new_offset < 0 {
             return_errno_with_message!(Errno::EINVAL, "file offset must not be negative");
@@ -380,9 +399,16 @@ impl<R> Drop for InodeHandle<R> {
 }
 
 pub trait FileIo: Pollable + Send + Sync + 'static {
-    fn read(&self, writer: &mut VmWriter) -> Result<usize>;
+    fn read(&self, writer: &mut VmWriter, status_flags: StatusFlags) -> Result<usize>;
 
-    fn write(&self, reader: &mut VmReader) -> Result<usize>;
+    fn write(&self, reader: &mut VmReader, status_flags: StatusFlags) -> Result<usize>;
+
+    /// Seeks the file if the file has its own notion of the file position.
+    ///
+    /// Returns `None` if the file offset of the file handle should be used instead.
+    fn seek(&self, pos: SeekFrom) -> Option<Result<usize>> {
+        None
+    }
 
     fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
         return_errno_with_message!(Errno::EINVAL, "ioctl is not supported");
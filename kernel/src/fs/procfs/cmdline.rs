@@ -0,0 +1,34 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/cmdline` file support, which tells the user space
+//! about the command line that the kernel is booted with.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man5/proc_cmdline.5.html>
+
+use alloc::format;
+
+use ostd::boot::boot_info;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/cmdline`.
+pub struct CmdlineFileOps;
+
+impl CmdlineFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for CmdlineFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let output = format!("{}\n", boot_info().kernel_cmdline);
+        Ok(output.into_bytes())
+    }
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/interrupts` file support, which provides the
+//! number of interrupts handled by each CPU.
+//!
+//! Currently, only the local timer interrupts (the `LOC` line) are reported.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man5/proc_interrupts.5.html>
+
+use core::fmt::Write;
+
+use ostd::{cpu::all_cpus, timer};
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/interrupts`.
+pub struct InterruptsFileOps;
+
+impl InterruptsFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for InterruptsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = String::new();
+
+        write!(output, "{:11}", "").unwrap();
+        for cpu in all_cpus() {
+            write!(output, "CPU{:<8}", cpu.as_usize()).unwrap();
+        }
+        writeln!(output).unwrap();
+
+        write!(output, "{:>3}: ", "LOC").unwrap();
+        for cpu in all_cpus() {
+            write!(output, "{:>10} ", timer::nr_interrupts(cpu)).unwrap();
+        }
+        writeln!(output, "  Local timer interrupts").unwrap();
+
+        Ok(output.into_bytes())
+    }
+}
@@ -5,10 +5,14 @@ use core::sync::atomic::{AtomicU64, Ordering};
 use filesystems::{FileSystemType, FILESYSTEM_TYPES};
 
 use self::{
+    cmdline::CmdlineFileOps,
     cpuinfo::CpuInfoFileOps,
+    interrupts::InterruptsFileOps,
     loadavg::LoadAvgFileOps,
     meminfo::MemInfoFileOps,
+    net::NetDirOps,
     pid::PidDirOps,
+    schedstat::SchedStatFileOps,
     self_::SelfSymOps,
     sys::SysDirOps,
     template::{DirOps, ProcDir, ProcDirBuilder, ProcSymBuilder, SymOps},
@@ -27,11 +31,15 @@ use crate::{
     },
 };
 
+mod cmdline;
 mod cpuinfo;
 mod filesystems;
+mod interrupts;
 mod loadavg;
 mod meminfo;
+mod net;
 mod pid;
+mod schedstat;
 mod self_;
 mod sys;
 mod template;
@@ -134,6 +142,14 @@ impl DirOps for RootDirOps {
             LoadAvgFileOps::new_inode(this_ptr.clone())
         } else if name == "cpuinfo" {
             CpuInfoFileOps::new_inode(this_ptr.clone())
+        } else if name == "cmdline" {
+            CmdlineFileOps::new_inode(this_ptr.clone())
+        } else if name == "interrupts" {
+            InterruptsFileOps::new_inode(this_ptr.clone())
+        } else if name == "schedstat" {
+            SchedStatFileOps::new_inode(this_ptr.clone())
+        } else if name == "net" {
+            NetDirOps::new_inode(this_ptr.clone())
         } else if let Ok(pid) = name.parse::<Pid>() {
             let process_ref =
                 process_table::get_process(pid).ok_or_else(|| Error::new(Errno::ENOENT))?;
@@ -164,6 +180,15 @@ impl DirOps for RootDirOps {
             .put_entry_if_not_found("loadavg", || LoadAvgFileOps::new_inode(this_ptr.clone()));
         cached_children
             .put_entry_if_not_found("cpuinfo", || CpuInfoFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("cmdline", || CmdlineFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("interrupts", || {
+            InterruptsFileOps::new_inode(this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("schedstat", || {
+            SchedStatFileOps::new_inode(this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("net", || NetDirOps::new_inode(this_ptr.clone()));
         for process in process_table::process_table_mut().iter() {
             let pid = process.pid().to_string();
             cached_children.put_entry_if_not_found(&pid, || {
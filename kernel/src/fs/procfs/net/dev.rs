@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/dev` file support, which tells the user space about the
+//! statistics of the network interfaces.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man5/proc_net.5.html>
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::iface::all_ifaces,
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/dev`.
+pub struct DevFileOps;
+
+impl DevFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for DevFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = String::from(
+            "Inter-|   Receive                                                |  Transmit\n \
+             face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets \
+             errs drop fifo colls carrier compressed\n",
+        );
+
+        for iface in all_ifaces().iter() {
+            let stats = iface.stats();
+
+            // FIXME: Errors and drops are not counted yet.
+            output.push_str(&format!(
+                "{:>6}: {:7} {:7} {:4} {:4} {:4} {:5} {:10} {:9} {:8} {:7} {:4} {:4} {:4} {:5} \
+                 {:7} {:10}\n",
+                iface.name(),
+                stats.rx_bytes,
+                stats.rx_packets,
+                0,
+                0,
+                0,
+                0,
+                0,
+                stats.rx_multicast,
+                stats.tx_bytes,
+                stats.tx_packets,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+            ));
+        }
+
+        Ok(output.into_bytes())
+    }
+}
@@ -0,0 +1,35 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/if_inet6` file support, which tells the user space about the
+//! IPv6 addresses of the network interfaces.
+//!
+//! Reference: <https://tldp.org/HOWTO/Linux+IPv6-HOWTO/ch11s04.html>
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/net/if_inet6`.
+pub struct IfInet6FileOps;
+
+impl IfInet6FileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for IfInet6FileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        // TODO: List the IPv6 addresses once IPv6 is supported. The file exists so that the user
+        // space can tell that the kernel has no IPv6 addresses (rather than no IPv6 support).
+        Ok(Vec::new())
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_bigtcp::wire::{IpAddress, IpEndpoint};
+
+use self::{
+    dev::DevFileOps, if_inet6::IfInet6FileOps, netstat::NetstatFileOps, pnp::PnpFileOps,
+    route::RouteFileOps, snmp::SnmpFileOps, tcp::TcpFileOps, udp::UdpFileOps, unix::UnixFileOps,
+};
+use crate::{
+    fs::{
+        procfs::template::{DirOps, ProcDir, ProcDirBuilder},
+        utils::{DirEntryVecExt, Inode},
+    },
+    prelude::*,
+};
+
+mod dev;
+mod if_inet6;
+mod netstat;
+mod pnp;
+mod route;
+mod snmp;
+mod tcp;
+mod udp;
+mod unix;
+
+/// Represents the inode at `/proc/net`.
+pub struct NetDirOps;
+
+impl NetDirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for NetDirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let inode = match name {
+            "dev" => DevFileOps::new_inode(this_ptr.clone()),
+            "if_inet6" => IfInet6FileOps::new_inode(this_ptr.clone()),
+            "netstat" => NetstatFileOps::new_inode(this_ptr.clone()),
+            "pnp" => PnpFileOps::new_inode(this_ptr.clone()),
+            "route" => RouteFileOps::new_inode(this_ptr.clone()),
+            "snmp" => SnmpFileOps::new_inode(this_ptr.clone()),
+            "tcp" => TcpFileOps::new_inode(this_ptr.clone()),
+            "udp" => UdpFileOps::new_inode(this_ptr.clone()),
+            "unix" => UnixFileOps::new_inode(this_ptr.clone()),
+            _ => return_errno!(Errno::ENOENT),
+        };
+        Ok(inode)
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<NetDirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        cached_children.put_entry_if_not_found("dev", || DevFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("if_inet6", || IfInet6FileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("netstat", || NetstatFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("pnp", || PnpFileOps::new_inode(this_ptr.clone()));
+        cached_children
+            .put_entry_if_not_found("route", || RouteFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("snmp", || SnmpFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("tcp", || TcpFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("udp", || UdpFileOps::new_inode(this_ptr.clone()));
+        cached_children.put_entry_if_not_found("unix", || UnixFileOps::new_inode(this_ptr.clone()));
+    }
+}
+
+/// Formats the endpoint in the way of `/proc/net/tcp` and `/proc/net/udp`.
+///
+/// The address is printed as the native integer interpreted from the bytes in the network byte
+/// order, and the port is printed in hexadecimal. A missing endpoint is printed as zeros.
+fn format_endpoint(endpoint: Option<&IpEndpoint>) -> String {
+    let Some(endpoint) = endpoint else {
+        return String::from("00000000:0000");
+    };
+
+    let IpAddress::Ipv4(addr) = endpoint.addr;
+    format!(
+        "{:08X}:{:04X}",
+        u32::from_ne_bytes(addr.octets()),
+        endpoint.port
+    )
+}
+
+/// Appends a section of `/proc/net/snmp` or `/proc/net/netstat`.
+///
+/// A section consists of two lines that share the same prefix. The first line lists the names of
+/// the counters and the second line lists the values.
+fn push_section(output: &mut String, prefix: &str, fields: &[(&str, i64)]) {
+    output.push_str(prefix);
+    output.push(':');
+    for (name, _) in fields.iter() {
+        output.push(' ');
+        output.push_str(name);
+    }
+    output.push('\n');
+
+    output.push_str(prefix);
+    output.push(':');
+    for (_, value) in fields.iter() {
+        output.push_str(&format!(" {}", value));
+    }
+    output.push('\n');
+}
@@ -0,0 +1,53 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/pnp` file support, which tells the user space about the network
+//! configuration obtained at boot time. The file uses the format of `/etc/resolv.conf`, so it can
+//! be used by resolvers directly (e.g., by linking `/etc/resolv.conf` to it).
+//!
+//! Reference: <https://www.kernel.org/doc/html/v6.13/admin-guide/nfs/nfsroot.html>
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::iface::current_lease,
+    prelude::*,
+};
+
+/// The maximum number of name servers reported, which is the limit of `/etc/resolv.conf`.
+const MAX_NAME_SERVERS: usize = 3;
+
+/// Represents the inode at `/proc/net/pnp`.
+pub struct PnpFileOps;
+
+impl PnpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for PnpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let Some(lease) = current_lease() else {
+            return Ok(b"#MANUAL\n".to_vec());
+        };
+
+        let mut output = String::from("#PROTO: DHCP\n");
+        if let Some(domain_name) = lease.domain_name.as_ref() {
+            output.push_str(&format!("domain {}\n", domain_name));
+        }
+        for dns_server in lease.dns_servers.iter().take(MAX_NAME_SERVERS) {
+            output.push_str(&format!("nameserver {}\n", dns_server));
+        }
+        output.push_str(&format!("bootserver {}\n", lease.server));
+
+        Ok(output.into_bytes())
+    }
+}
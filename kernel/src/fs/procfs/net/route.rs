@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/route` file support, which tells the user space about the IPv4
+//! routes in the main routing table.
+//!
+//! Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/fib_trie.c#L2983>
+
+use alloc::format;
+
+use aster_bigtcp::wire::Ipv4Address;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::iface::{routing_table, Route, RouteType},
+    prelude::*,
+};
+
+/// The width of each line, excluding the trailing newline.
+const LINE_WIDTH: usize = 127;
+
+/// The route flags (`RTF_*` in Linux).
+const RTF_UP: u16 = 0x0001;
+const RTF_GATEWAY: u16 = 0x0002;
+const RTF_HOST: u16 = 0x0004;
+
+/// Represents the inode at `/proc/net/route`.
+pub struct RouteFileOps;
+
+impl RouteFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for RouteFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = format!(
+            "{:<LINE_WIDTH$}\n",
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT"
+        );
+
+        let table = routing_table().read();
+        let routes = table
+            .iter()
+            .filter(|route| route.table == Route::MAIN_TABLE && route.type_ == RouteType::Unicast);
+        for route in routes {
+            let mut flags = RTF_UP;
+            if route.gateway.is_some() {
+                flags |= RTF_GATEWAY;
+            }
+            if route.dst.prefix_len() == 32 {
+                flags |= RTF_HOST;
+            }
+
+            let line = format!(
+                "{}\t{:08X}\t{:08X}\t{:04X}\t0\t0\t{}\t{:08X}\t0\t0\t0",
+                route.iface.name(),
+                to_linux_u32(route.dst.address()),
+                to_linux_u32(route.gateway.unwrap_or(Ipv4Address::UNSPECIFIED)),
+                flags,
+                route.metric,
+                to_linux_u32(route.dst.netmask()),
+            );
+            output.push_str(&format!("{:<LINE_WIDTH$}\n", line));
+        }
+
+        Ok(output.into_bytes())
+    }
+}
+
+/// Converts the address to the value that Linux prints, which is the address in the network byte
+/// order interpreted as a native integer.
+fn to_linux_u32(addr: Ipv4Address) -> u32 {
+    u32::from_ne_bytes(addr.octets())
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/snmp` file support, which tells the user space about the
+//! statistics of the network protocols.
+//!
+//! Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/proc.c#L397>
+
+use alloc::format;
+
+use aster_bigtcp::{
+    socket::TcpState,
+    stats::{icmp_stats, ip_stats, tcp_stats, udp_stats},
+};
+
+use super::push_section;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::iface::all_ifaces,
+    prelude::*,
+};
+
+/// The value of `Ip: Forwarding` if IP forwarding is disabled.
+const IP_FORWARDING_DISABLED: i64 = 2;
+const DEFAULT_TTL: i64 = 64;
+
+/// The RTO algorithm specified in RFC 6298 (`other(1)` in RFC 1213).
+const TCP_RTO_ALGORITHM: i64 = 1;
+/// The minimum and maximum RTO in milliseconds.
+const TCP_RTO_MIN: i64 = 200;
+const TCP_RTO_MAX: i64 = 120_000;
+/// The maximum number of TCP connections, where -1 means that the limit is dynamic.
+const TCP_MAX_CONN: i64 = -1;
+
+/// The ICMP message types (`ICMP_*` in Linux).
+const ICMP_ECHOREPLY: u8 = 0;
+const ICMP_DEST_UNREACH: u8 = 3;
+const ICMP_ECHO: u8 = 8;
+
+/// Represents the inode at `/proc/net/snmp`.
+pub struct SnmpFileOps;
+
+impl SnmpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for SnmpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = String::new();
+
+        let ip = ip_stats();
+        push_section(
+            &mut output,
+            "Ip",
+            &[
+                ("Forwarding", IP_FORWARDING_DISABLED),
+                ("DefaultTTL", DEFAULT_TTL),
+                ("InReceives", ip.in_receives as i64),
+                ("InHdrErrors", ip.in_hdr_errors as i64),
+                ("InAddrErrors", ip.in_addr_errors as i64),
+                ("ForwDatagrams", 0),
+                ("InUnknownProtos", ip.in_unknown_protos as i64),
+                ("InDiscards", 0),
+                ("InDelivers", ip.in_delivers as i64),
+                ("OutRequests", ip.out_requests as i64),
+                ("OutDiscards", 0),
+                ("OutNoRoutes", 0),
+                ("ReasmTimeout", 0),
+                ("ReasmReqds", 0),
+                ("ReasmOKs", 0),
+                ("ReasmFails", 0),
+                ("FragOKs", 0),
+                ("FragFails", 0),
+                ("FragCreates", 0),
+                ("OutTransmits", ip.out_requests as i64),
+            ],
+        );
+
+        let icmp = icmp_stats();
+        push_section(
+            &mut output,
+            "Icmp",
+            &[
+                ("InMsgs", icmp.in_msgs as i64),
+                ("InErrors", icmp.in_errors as i64),
+                ("InCsumErrors", 0),
+                ("InDestUnreachs", icmp.in_dest_unreachs as i64),
+                ("InTimeExcds", 0),
+                ("InParmProbs", 0),
+                ("InSrcQuenchs", 0),
+                ("InRedirects", 0),
+                ("InEchos", icmp.in_echos as i64),
+                ("InEchoReps", icmp.in_echo_reps as i64),
+                ("InTimestamps", 0),
+                ("InTimestampReps", 0),
+                ("InAddrMasks", 0),
+                ("InAddrMaskReps", 0),
+                ("OutMsgs", icmp.out_msgs as i64),
+                ("OutErrors", 0),
+                ("OutRateLimitGlobal", 0),
+                ("OutRateLimitHost", 0),
+                ("OutDestUnreachs", icmp.out_dest_unreachs as i64),
+                ("OutTimeExcds", 0),
+                ("OutParmProbs", 0),
+                ("OutSrcQuenchs", 0),
+                ("OutRedirects", 0),
+                ("OutEchos", 0),
+                ("OutEchoReps", icmp.out_echo_reps as i64),
+                ("OutTimestamps", 0),
+                ("OutTimestampReps", 0),
+                ("OutAddrMasks", 0),
+                ("OutAddrMaskReps", 0),
+            ],
+        );
+
+        // Like Linux, only the message types that have been seen are reported, and the section is
+        // omitted if there are no such message types.
+        let icmp_msgs = [
+            ("In", ICMP_ECHOREPLY, icmp.in_echo_reps),
+            ("In", ICMP_DEST_UNREACH, icmp.in_dest_unreachs),
+            ("In", ICMP_ECHO, icmp.in_echos),
+            ("Out", ICMP_ECHOREPLY, icmp.out_echo_reps),
+            ("Out", ICMP_DEST_UNREACH, icmp.out_dest_unreachs),
+        ];
+        let icmp_msg_names = icmp_msgs
+            .iter()
+            .filter(|(_, _, count)| *count > 0)
+            .map(|(dir, type_, count)| (format!("{}Type{}", dir, type_), *count as i64))
+            .collect::<Vec<_>>();
+        if !icmp_msg_names.is_empty() {
+            let fields = icmp_msg_names
+                .iter()
+                .map(|(name, count)| (name.as_str(), *count))
+                .collect::<Vec<_>>();
+            push_section(&mut output, "IcmpMsg", &fields);
+        }
+
+        let tcp = tcp_stats();
+        let curr_estab = all_ifaces()
+            .iter()
+            .flat_map(|iface| iface.tcp_socket_infos())
+            .filter(|info| matches!(info.state, TcpState::Established | TcpState::CloseWait))
+            .count();
+        push_section(
+            &mut output,
+            "Tcp",
+            &[
+                ("RtoAlgorithm", TCP_RTO_ALGORITHM),
+                ("RtoMin", TCP_RTO_MIN),
+                ("RtoMax", TCP_RTO_MAX),
+                ("MaxConn", TCP_MAX_CONN),
+                ("ActiveOpens", tcp.active_opens as i64),
+                ("PassiveOpens", tcp.passive_opens as i64),
+                ("AttemptFails", 0),
+                ("EstabResets", 0),
+                ("CurrEstab", curr_estab as i64),
+                ("InSegs", tcp.in_segs as i64),
+                ("OutSegs", tcp.out_segs as i64),
+                ("RetransSegs", 0),
+                ("InErrs", tcp.in_errs as i64),
+                ("OutRsts", tcp.out_rsts as i64),
+                ("InCsumErrors", 0),
+            ],
+        );
+
+        let udp = udp_stats();
+        let udp_fields = [
+            ("InDatagrams", udp.in_datagrams as i64),
+            ("NoPorts", udp.no_ports as i64),
+            ("InErrors", udp.in_errors as i64),
+            ("OutDatagrams", udp.out_datagrams as i64),
+            ("RcvbufErrors", 0),
+            ("SndbufErrors", 0),
+            ("InCsumErrors", 0),
+            ("IgnoredMulti", 0),
+            ("MemErrors", 0),
+        ];
+        push_section(&mut output, "Udp", &udp_fields);
+
+        // UDP-Lite is not supported, so all the counters are zeros.
+        let udplite_fields = udp_fields.map(|(name, _)| (name, 0));
+        push_section(&mut output, "UdpLite", &udplite_fields);
+
+        Ok(output.into_bytes())
+    }
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/tcp` file support, which tells the user space about the TCP
+//! sockets in the system.
+//!
+//! Reference: <https://www.kernel.org/doc/html/v6.13/networking/proc_net_tcp.html>
+
+use alloc::format;
+
+use aster_bigtcp::socket::TcpState;
+
+use super::format_endpoint;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::iface::all_ifaces,
+    prelude::*,
+};
+
+/// The width of each line, excluding the trailing newline.
+const LINE_WIDTH: usize = 149;
+
+/// Represents the inode at `/proc/net/tcp`.
+pub struct TcpFileOps;
+
+impl TcpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for TcpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = format!(
+            "{:<LINE_WIDTH$}\n",
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  \
+             timeout inode"
+        );
+
+        let ifaces = all_ifaces();
+        let infos = ifaces.iter().flat_map(|iface| iface.tcp_socket_infos());
+        for (index, info) in infos.enumerate() {
+            // FIXME: Report the owner and the inode number of the socket once the sockets are
+            // associated with them.
+            let line = format!(
+                "{:4}: {} {} {:02X} {:08X}:{:08X} 00:00000000 00000000 {:5} {:8} {} 1 \
+                 0000000000000000 100 0 0 10 0",
+                index,
+                format_endpoint(Some(&info.local_endpoint)),
+                format_endpoint(info.remote_endpoint.as_ref()),
+                state_to_linux(info.state),
+                info.send_queue,
+                info.recv_queue,
+                0,
+                0,
+                0,
+            );
+            output.push_str(&format!("{:<LINE_WIDTH$}\n", line));
+        }
+
+        Ok(output.into_bytes())
+    }
+}
+
+/// Converts the TCP state to the value of `TCP_*` in Linux.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/net/tcp_states.h#L12>.
+fn state_to_linux(state: TcpState) -> u8 {
+    match state {
+        TcpState::Established => 1,
+        TcpState::SynSent => 2,
+        TcpState::SynReceived => 3,
+        TcpState::FinWait1 => 4,
+        TcpState::FinWait2 => 5,
+        TcpState::TimeWait => 6,
+        TcpState::Closed => 7,
+        TcpState::CloseWait => 8,
+        TcpState::LastAck => 9,
+        TcpState::Listen => 10,
+        TcpState::Closing => 11,
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/udp` file support, which tells the user space about the UDP
+//! sockets in the system.
+//!
+//! Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/udp.c#L3434>
+
+use alloc::format;
+
+use super::format_endpoint;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::iface::all_ifaces,
+    prelude::*,
+};
+
+/// The width of each line, excluding the trailing newline.
+const LINE_WIDTH: usize = 127;
+
+/// The state of unconnected UDP sockets (`TCP_CLOSE` in Linux).
+const STATE_CLOSE: u8 = 7;
+
+/// Represents the inode at `/proc/net/udp`.
+pub struct UdpFileOps;
+
+impl UdpFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for UdpFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = format!(
+            "{:<LINE_WIDTH$}\n",
+            "   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  \
+             timeout inode ref pointer drops"
+        );
+
+        let ifaces = all_ifaces();
+        let infos = ifaces.iter().flat_map(|iface| iface.udp_socket_infos());
+        for (index, info) in infos.enumerate() {
+            // FIXME: Report the remote endpoint of connected sockets, which is only tracked by
+            // the socket layer. Also report the owner and the inode number of the socket once the
+            // sockets are associated with them.
+            let line = format!(
+                "{:5}: {} {} {:02X} {:08X}:{:08X} 00:00000000 00000000 {:5} {:8} {} 2 \
+                 0000000000000000 0",
+                index,
+                format_endpoint(Some(&info.local_endpoint)),
+                format_endpoint(None),
+                STATE_CLOSE,
+                info.send_queue,
+                info.recv_queue,
+                0,
+                0,
+                0,
+            );
+            output.push_str(&format!("{:<LINE_WIDTH$}\n", line));
+        }
+
+        Ok(output.into_bytes())
+    }
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/net/unix` file support, which tells the user space about the UNIX
+//! domain sockets in the system.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man5/proc_net.5.html>
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::socket::unix::{unix_socket_infos, UnixSocketAddr, UnixSocketState},
+    prelude::*,
+};
+
+/// The flag of listening sockets (`__SO_ACCEPTCON` in Linux).
+const SO_ACCEPTCON: u32 = 1 << 16;
+
+/// The socket states (`SS_*` in Linux).
+const SS_UNCONNECTED: u8 = 1;
+const SS_CONNECTED: u8 = 3;
+
+/// Represents the inode at `/proc/net/unix`.
+pub struct UnixFileOps;
+
+impl UnixFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for UnixFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = String::from("Num       RefCount Protocol Flags    Type St Inode Path\n");
+
+        for info in unix_socket_infos() {
+            let (flags, state) = match info.state {
+                UnixSocketState::Unconnected => (0, SS_UNCONNECTED),
+                UnixSocketState::Listening => (SO_ACCEPTCON, SS_UNCONNECTED),
+                UnixSocketState::Connected => (0, SS_CONNECTED),
+            };
+
+            // Like Linux with `kptr_restrict` enabled, the kernel address of the socket is hidden.
+            //
+            // FIXME: Report the reference count and the inode number of the socket once the
+            // sockets are associated with inodes.
+            output.push_str(&format!(
+                "0000000000000000: {:08X} {:08X} {:08X} {:04X} {:02X} {:5}",
+                2, 0, flags, info.type_ as i32, state, 0,
+            ));
+
+            match info.addr {
+                UnixSocketAddr::Unnamed => (),
+                UnixSocketAddr::Path(path) => {
+                    output.push(' ');
+                    output.push_str(&path);
+                }
+                UnixSocketAddr::Abstract(name) => {
+                    // Abstract names start with '@', and the null bytes are also shown as '@'.
+                    output.push_str(" @");
+                    for byte in name.iter() {
+                        output.push(if *byte == 0 { '@' } else { *byte as char });
+                    }
+                }
+            }
+
+            output.push('\n');
+        }
+
+        Ok(output.into_bytes())
+    }
+}
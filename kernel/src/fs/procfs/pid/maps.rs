@@ -0,0 +1,138 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::fmt::Write;
+
+use super::check_mm_access;
+use crate::{
+    fs::{
+        device::DeviceId,
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    prelude::*,
+    vm::{perms::VmPerms, vmar::vm_mapping::VmMapping},
+    Process,
+};
+
+/// Represents the inode at `/proc/[pid]/maps`.
+/// See https://github.com/torvalds/linux/blob/ce1c54fdff7c4556b08f5b875a331d8952e8b6b7/fs/proc/task_mmu.c#L300
+///
+/// Each line describes a mapping, with the following fields:
+/// - address:  The start and end addresses of the mapping.
+/// - perms:    The permissions, and whether the mapping is shared (s) or private (p).
+/// - offset:   The offset in the file for file-backed mappings.
+/// - dev:      The device (major:minor) of the file.
+/// - inode:    The inode number of the file.
+/// - pathname: The path of the file, or the name of a special mapping
+///   (e.g., `[heap]`, `[stack]` and `[vdso]`).
+pub struct MapsFileOps(Arc<Process>);
+
+impl MapsFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for MapsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        check_mm_access(&self.0)?;
+
+        let mut maps_output = String::new();
+
+        let vmar_guard = self.0.lock_root_vmar();
+        let Some(vmar) = vmar_guard.as_ref() else {
+            return Ok(maps_output.into_bytes());
+        };
+
+        let query_guard = vmar.query(vmar.base()..vmar.base() + vmar.size());
+        for vm_mapping in query_guard.iter() {
+            write_mapping_header(&mut maps_output, vm_mapping);
+        }
+
+        Ok(maps_output.into_bytes())
+    }
+}
+
+/// The width to which a line is padded before the path name is written.
+const PATHNAME_PAD_WIDTH: usize = 72;
+
+/// Writes the line that describes `vm_mapping` in `/proc/[pid]/maps`.
+pub(super) fn write_mapping_header(output: &mut String, vm_mapping: &VmMapping) {
+    let perms = vm_mapping.perms();
+    let (offset, dev, ino) = match vm_mapping.inode() {
+        Some(inode) => {
+            let metadata = inode.metadata();
+            (
+                vm_mapping.vmo_offset().unwrap_or(0),
+                DeviceId::from(metadata.dev),
+                metadata.ino,
+            )
+        }
+        None => (0, DeviceId::new(0, 0), 0),
+    };
+
+    let name = if let Some(dentry) = vm_mapping.dentry() {
+        Some(dentry.abs_path())
+    } else {
+        vm_mapping
+            .special()
+            .map(|special| special.name().to_string())
+    };
+
+    write_header_line(
+        output,
+        vm_mapping.map_to_addr(),
+        vm_mapping.map_end(),
+        perms,
+        vm_mapping.is_shared(),
+        offset,
+        dev,
+        ino,
+        name.as_deref(),
+    );
+}
+
+#[expect(clippy::too_many_arguments)]
+pub(super) fn write_header_line(
+    output: &mut String,
+    start: Vaddr,
+    end: Vaddr,
+    perms: VmPerms,
+    is_shared: bool,
+    offset: usize,
+    dev: DeviceId,
+    ino: u64,
+    name: Option<&str>,
+) {
+    let flag_char = |is_set: bool, c: char| if is_set { c } else { '-' };
+
+    let line_start = output.len();
+    write!(
+        output,
+        "{:08x}-{:08x} {}{}{}{} {:08x} {:02x}:{:02x} {} ",
+        start,
+        end,
+        flag_char(perms.contains(VmPerms::READ), 'r'),
+        flag_char(perms.contains(VmPerms::WRITE), 'w'),
+        flag_char(perms.contains(VmPerms::EXEC), 'x'),
+        if is_shared { 's' } else { 'p' },
+        offset,
+        dev.major(),
+        dev.minor(),
+        ino,
+    )
+    .unwrap();
+
+    if let Some(name) = name {
+        let line_len = output.len() - line_start;
+        for _ in line_len..PATHNAME_PAD_WIDTH {
+            output.push(' ');
+        }
+        output.push(' ');
+        output.push_str(name);
+    }
+    output.push('\n');
+}
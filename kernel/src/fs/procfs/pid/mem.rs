@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::check_mm_access;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    prelude::*,
+    Process,
+};
+
+/// Represents the inode at `/proc/[pid]/mem`.
+/// See https://man7.org/linux/man-pages/man5/proc_pid_mem.5.html
+///
+/// The file offset is the virtual address in the process. Accessing the
+/// memory that is not mapped or not accessible fails with `EIO`.
+///
+/// FIXME: On Linux, the memory can be accessed regardless of the permissions
+/// of the mappings (e.g., debuggers can write breakpoints to read-only code).
+/// This is not supported yet.
+pub struct MemFileOps(Arc<Process>);
+
+impl MemFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o600))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for MemFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        // The content covers the whole user address space, which is too large
+        // to be generated at once.
+        return_errno_with_message!(Errno::EINVAL, "mem can only be accessed by ranges");
+    }
+
+    fn read_at(&self, offset: usize, writer: &mut VmWriter) -> Result<usize> {
+        check_mm_access(&self.0)?;
+
+        let vmar_guard = self.0.lock_root_vmar();
+        let Some(vmar) = vmar_guard.as_ref() else {
+            return Ok(0);
+        };
+
+        let read_len = vmar.read_remote(offset, writer)?;
+        if read_len == 0 && writer.has_avail() {
+            return_errno_with_message!(Errno::EIO, "the memory is not readable");
+        }
+
+        Ok(read_len)
+    }
+
+    fn write_at(&self, offset: usize, reader: &mut VmReader) -> Result<usize> {
+        check_mm_access(&self.0)?;
+
+        let vmar_guard = self.0.lock_root_vmar();
+        let Some(vmar) = vmar_guard.as_ref() else {
+            return Ok(0);
+        };
+
+        let write_len = vmar.write_remote(offset, reader)?;
+        if write_len == 0 && reader.has_remain() {
+            return_errno_with_message!(Errno::EIO, "the memory is not writable");
+        }
+
+        Ok(write_len)
+    }
+}
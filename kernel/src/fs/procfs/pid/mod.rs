@@ -1,7 +1,16 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use self::{
-    cmdline::CmdlineFileOps, comm::CommFileOps, exe::ExeSymOps, fd::FdDirOps, task::TaskDirOps,
+    cmdline::CmdlineFileOps,
+    comm::CommFileOps,
+    exe::ExeSymOps,
+    fd::FdDirOps,
+    maps::MapsFileOps,
+    mem::MemFileOps,
+    net::NetSymOps,
+    pagemap::PagemapFileOps,
+    smaps::{SmapsFileOps, SmapsRollupFileOps},
+    task::TaskDirOps,
 };
 use super::template::{DirOps, ProcDir, ProcDirBuilder};
 use crate::{
@@ -11,17 +20,40 @@ use crate::{
         utils::{DirEntryVecExt, Inode},
     },
     prelude::*,
-    process::{posix_thread::AsPosixThread, Process},
+    process::{
+        credentials::{check_ptrace_access, PtraceCreds},
+        posix_thread::AsPosixThread,
+        Process,
+    },
 };
 
 mod cmdline;
 mod comm;
 mod exe;
 mod fd;
+mod maps;
+mod mem;
+mod net;
+mod pagemap;
+mod smaps;
 mod stat;
 mod status;
 mod task;
 
+/// Checks whether the current thread may access the memory of `process` via
+/// the files in `/proc/[pid]` (e.g., `mem` and `maps`).
+///
+/// This corresponds to the `PTRACE_MODE_*_FSCREDS` checks in Linux.
+fn check_mm_access(process: &Process) -> Result<()> {
+    let current_thread = current_thread!();
+    check_ptrace_access(
+        current_thread.as_posix_thread().unwrap(),
+        process,
+        PtraceCreds::Fs,
+    )
+    .map_err(|_| Error::with_message(Errno::EACCES, "the memory of the process is not accessible"))
+}
+
 /// Represents the inode at `/proc/[pid]`.
 pub struct PidDirOps(Arc<Process>);
 
@@ -67,6 +99,12 @@ impl DirOps for PidDirOps {
             "status" => status::StatusFileOps::new_inode(self.0.clone(), this_ptr.clone()),
             "stat" => stat::StatFileOps::new_inode(self.0.clone(), this_ptr.clone()),
             "task" => TaskDirOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "maps" => MapsFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "mem" => MemFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "smaps" => SmapsFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "smaps_rollup" => SmapsRollupFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "pagemap" => PagemapFileOps::new_inode(self.0.clone(), this_ptr.clone()),
+            "net" => NetSymOps::new_inode(this_ptr.clone()),
             _ => return_errno!(Errno::ENOENT),
         };
         Ok(inode)
@@ -99,5 +137,21 @@ impl DirOps for PidDirOps {
         cached_children.put_entry_if_not_found("task", || {
             TaskDirOps::new_inode(self.0.clone(), this_ptr.clone())
         });
+        cached_children.put_entry_if_not_found("maps", || {
+            MapsFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("mem", || {
+            MemFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("smaps", || {
+            SmapsFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("smaps_rollup", || {
+            SmapsRollupFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("pagemap", || {
+            PagemapFileOps::new_inode(self.0.clone(), this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("net", || NetSymOps::new_inode(this_ptr.clone()));
     }
 }
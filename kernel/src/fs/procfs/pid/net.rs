@@ -0,0 +1,31 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    fs::{
+        procfs::{ProcSymBuilder, SymOps},
+        utils::Inode,
+    },
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/[pid]/net`.
+///
+/// On Linux, the directory shows the network status of the network namespace that the process
+/// belongs to. Since there is only one network namespace, the inode simply links to `/proc/net`.
+///
+/// FIXME: Make it a directory of the process's network namespace once network namespaces are
+/// supported.
+pub struct NetSymOps;
+
+impl NetSymOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcSymBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl SymOps for NetSymOps {
+    fn read_link(&self) -> Result<String> {
+        // The link is relative, so it works wherever procfs is mounted.
+        Ok(String::from("../net"))
+    }
+}
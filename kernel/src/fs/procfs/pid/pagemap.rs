@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::mem::size_of;
+
+use super::check_mm_access;
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::AsPosixThread},
+    vm::vmar::vm_mapping::MappedPageInfo,
+    Process,
+};
+
+/// Represents the inode at `/proc/[pid]/pagemap`.
+/// See https://www.kernel.org/doc/Documentation/vm/pagemap.txt
+///
+/// The file contains a 64-bit entry for each virtual page, which can be
+/// located by seeking to `vaddr / PAGE_SIZE * 8`. Each entry has the
+/// following bits:
+/// - Bits 0-54:  The page frame number if the page is present. It is only
+///   shown to users with `CAP_SYS_ADMIN`.
+/// - Bit 56:     The page is exclusively mapped.
+/// - Bit 61:     The page is a file page or a shared anonymous page.
+/// - Bit 62:     The page is swapped.
+/// - Bit 63:     The page is present.
+pub struct PagemapFileOps(Arc<Process>);
+
+impl PagemapFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+const PM_ENTRY_BYTES: usize = size_of::<u64>();
+const PM_PFRAME_MASK: u64 = (1 << 55) - 1;
+const PM_MMAP_EXCLUSIVE: u64 = 1 << 56;
+const PM_FILE: u64 = 1 << 61;
+const PM_PRESENT: u64 = 1 << 63;
+
+/// The maximum number of entries that are generated at a time.
+const PM_WALK_ENTRIES: usize = 512;
+
+impl FileOps for PagemapFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        // The content covers the whole user address space, which is too large
+        // to be generated at once.
+        return_errno_with_message!(Errno::EINVAL, "pagemap can only be read by ranges");
+    }
+
+    fn read_at(&self, offset: usize, writer: &mut VmWriter) -> Result<usize> {
+        check_mm_access(&self.0)?;
+
+        if offset % PM_ENTRY_BYTES != 0 || writer.avail() % PM_ENTRY_BYTES != 0 {
+            return_errno_with_message!(Errno::EINVAL, "the pagemap entries are not aligned");
+        }
+
+        let vmar_guard = self.0.lock_root_vmar();
+        let Some(vmar) = vmar_guard.as_ref() else {
+            return Ok(0);
+        };
+
+        let show_pfn = current_thread!()
+            .as_posix_thread()
+            .unwrap()
+            .credentials()
+            .effective_capset()
+            .contains(CapSet::SYS_ADMIN);
+
+        let end_vaddr = vmar.base() + vmar.size();
+        let Some(mut vaddr) = (offset / PM_ENTRY_BYTES).checked_mul(PAGE_SIZE) else {
+            return Ok(0);
+        };
+
+        let mut read_len = 0;
+        let mut entries = Vec::with_capacity(PM_WALK_ENTRIES * PM_ENTRY_BYTES);
+        while writer.avail() >= PM_ENTRY_BYTES && vaddr < end_vaddr {
+            let nr_entries = (writer.avail() / PM_ENTRY_BYTES)
+                .min((end_vaddr - vaddr) / PAGE_SIZE)
+                .min(PM_WALK_ENTRIES);
+            let range = vaddr..vaddr + nr_entries * PAGE_SIZE;
+
+            entries.clear();
+            entries.resize(nr_entries * PM_ENTRY_BYTES, 0);
+
+            let query_guard = vmar.query(range.clone());
+            for vm_mapping in query_guard.iter() {
+                let start = vm_mapping.map_to_addr().max(range.start);
+                let end = vm_mapping.map_end().min(range.end);
+                vm_mapping.for_each_mapped_page(vmar.vm_space(), start..end, |va, page| {
+                    let index = (va - range.start) / PAGE_SIZE * PM_ENTRY_BYTES;
+                    entries[index..index + PM_ENTRY_BYTES]
+                        .copy_from_slice(&pagemap_entry(&page, show_pfn).to_ne_bytes());
+                });
+            }
+            drop(query_guard);
+
+            writer.write_fallible(&mut entries.as_slice().into())?;
+            read_len += entries.len();
+            vaddr = range.end;
+        }
+
+        Ok(read_len)
+    }
+}
+
+fn pagemap_entry(page: &MappedPageInfo, show_pfn: bool) -> u64 {
+    let mut entry = PM_PRESENT;
+    if show_pfn {
+        entry |= (page.paddr / PAGE_SIZE) as u64 & PM_PFRAME_MASK;
+    }
+    if page.map_count == 1 {
+        entry |= PM_MMAP_EXCLUSIVE;
+    }
+    if !page.is_anon {
+        entry |= PM_FILE;
+    }
+    entry
+}
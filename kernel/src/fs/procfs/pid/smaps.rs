@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::fmt::Write;
+
+use ostd::mm::{PageFlags, VmSpace};
+
+use super::{
+    check_mm_access,
+    maps::{write_header_line, write_mapping_header},
+};
+use crate::{
+    fs::{
+        device::DeviceId,
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    prelude::*,
+    vm::{
+        perms::VmPerms,
+        vmar::vm_mapping::{MappedPageInfo, VmMapping},
+    },
+    Process,
+};
+
+/// Represents the inode at `/proc/[pid]/smaps`.
+/// See https://github.com/torvalds/linux/blob/ce1c54fdff7c4556b08f5b875a331d8952e8b6b7/fs/proc/task_mmu.c#L832
+///
+/// Each mapping is shown with the same line as in `/proc/[pid]/maps`,
+/// followed by its memory usage:
+/// - Size:          The size of the mapping.
+/// - Rss:           The size of the pages that are resident in memory.
+/// - Pss:           The proportional share of the resident pages, i.e., each
+///   page is divided by the number of mappings that map it.
+/// - Shared_*:      The resident pages that are also mapped elsewhere.
+/// - Private_*:     The resident pages that are only mapped here.
+/// - Referenced:    The resident pages that have been accessed.
+/// - Anonymous:     The resident pages that do not belong to files or shared memory.
+/// - Swap:          The pages that are swapped out.
+/// - Locked:        The pages that are locked in memory.
+/// - VmFlags:       The flags of the mapping.
+///
+/// FIXME: Swapping and huge pages are not supported, so the corresponding
+/// fields are always zero.
+pub struct SmapsFileOps(Arc<Process>);
+
+impl SmapsFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for SmapsFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        check_mm_access(&self.0)?;
+
+        let mut smaps_output = String::new();
+
+        let vmar_guard = self.0.lock_root_vmar();
+        let Some(vmar) = vmar_guard.as_ref() else {
+            return Ok(smaps_output.into_bytes());
+        };
+
+        let query_guard = vmar.query(vmar.base()..vmar.base() + vmar.size());
+        for vm_mapping in query_guard.iter() {
+            let mut stats = MemStats::default();
+            stats.account_mapping(vm_mapping, vmar.vm_space());
+
+            write_mapping_header(&mut smaps_output, vm_mapping);
+            write_kb(&mut smaps_output, "Size:", vm_mapping.map_size() as u64);
+            write_kb(&mut smaps_output, "KernelPageSize:", PAGE_SIZE as u64);
+            write_kb(&mut smaps_output, "MMUPageSize:", PAGE_SIZE as u64);
+            stats.write_to(&mut smaps_output, false);
+            writeln!(smaps_output, "THPeligible:    0").unwrap();
+            write_vm_flags(&mut smaps_output, vm_mapping);
+        }
+
+        Ok(smaps_output.into_bytes())
+    }
+}
+
+/// Represents the inode at `/proc/[pid]/smaps_rollup`.
+///
+/// It shows the memory usage of all the mappings in `/proc/[pid]/smaps`
+/// summed up.
+pub struct SmapsRollupFileOps(Arc<Process>);
+
+impl SmapsRollupFileOps {
+    pub fn new_inode(process_ref: Arc<Process>, parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self(process_ref))
+            .parent(parent)
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for SmapsRollupFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        check_mm_access(&self.0)?;
+
+        let mut rollup_output = String::new();
+
+        let vmar_guard = self.0.lock_root_vmar();
+        let Some(vmar) = vmar_guard.as_ref() else {
+            return Ok(rollup_output.into_bytes());
+        };
+
+        let query_guard = vmar.query(vmar.base()..vmar.base() + vmar.size());
+        let mut stats = MemStats::default();
+        let mut range: Option<(Vaddr, Vaddr)> = None;
+        for vm_mapping in query_guard.iter() {
+            stats.account_mapping(vm_mapping, vmar.vm_space());
+            let start = range.map_or(vm_mapping.map_to_addr(), |(start, _)| start);
+            range = Some((start, vm_mapping.map_end()));
+        }
+
+        let Some((start, end)) = range else {
+            return Ok(rollup_output.into_bytes());
+        };
+
+        write_header_line(
+            &mut rollup_output,
+            start,
+            end,
+            VmPerms::empty(),
+            false,
+            0,
+            DeviceId::new(0, 0),
+            0,
+            Some("[rollup]"),
+        );
+        stats.write_to(&mut rollup_output, true);
+
+        Ok(rollup_output.into_bytes())
+    }
+}
+
+/// The number of bits that are used as the fractional part of the PSS.
+const PSS_SHIFT: u32 = 12;
+
+/// The memory usage of mappings, in bytes.
+#[derive(Debug, Default)]
+struct MemStats {
+    rss: u64,
+    /// The PSS values are left-shifted by [`PSS_SHIFT`] to keep the precision.
+    pss: u64,
+    pss_dirty: u64,
+    pss_anon: u64,
+    pss_file: u64,
+    pss_shmem: u64,
+    shared_clean: u64,
+    shared_dirty: u64,
+    private_clean: u64,
+    private_dirty: u64,
+    referenced: u64,
+    anonymous: u64,
+    pss_locked: u64,
+}
+
+impl MemStats {
+    fn account_mapping(&mut self, vm_mapping: &VmMapping, vm_space: &VmSpace) {
+        let is_file = vm_mapping.inode().is_some();
+        let is_locked = vm_mapping.mlock().is_some();
+        let range = vm_mapping.map_to_addr()..vm_mapping.map_end();
+        vm_mapping.for_each_mapped_page(vm_space, range, |_, page| {
+            self.account_page(&page, is_file, is_locked);
+        });
+    }
+
+    fn account_page(&mut self, page: &MappedPageInfo, is_file: bool, is_locked: bool) {
+        let size = PAGE_SIZE as u64;
+        let is_dirty = page.flags.contains(PageFlags::DIRTY);
+        let pss = (size << PSS_SHIFT) / page.map_count as u64;
+
+        self.rss += size;
+        self.pss += pss;
+        if is_dirty {
+            self.pss_dirty += pss;
+        }
+        if is_locked {
+            self.pss_locked += pss;
+        }
+        if page.is_anon {
+            self.pss_anon += pss;
+            self.anonymous += size;
+        } else if is_file {
+            self.pss_file += pss;
+        } else {
+            self.pss_shmem += pss;
+        }
+
+        match (page.map_count > 1, is_dirty) {
+            (true, false) => self.shared_clean += size,
+            (true, true) => self.shared_dirty += size,
+            (false, false) => self.private_clean += size,
+            (false, true) => self.private_dirty += size,
+        }
+
+        if page.flags.contains(PageFlags::ACCESSED) {
+            self.referenced += size;
+        }
+    }
+
+    fn write_to(&self, output: &mut String, is_rollup: bool) {
+        write_kb(output, "Rss:", self.rss);
+        write_kb(output, "Pss:", self.pss >> PSS_SHIFT);
+        write_kb(output, "Pss_Dirty:", self.pss_dirty >> PSS_SHIFT);
+        if is_rollup {
+            write_kb(output, "Pss_Anon:", self.pss_anon >> PSS_SHIFT);
+            write_kb(output, "Pss_File:", self.pss_file >> PSS_SHIFT);
+            write_kb(output, "Pss_Shmem:", self.pss_shmem >> PSS_SHIFT);
+        }
+        write_kb(output, "Shared_Clean:", self.shared_clean);
+        write_kb(output, "Shared_Dirty:", self.shared_dirty);
+        write_kb(output, "Private_Clean:", self.private_clean);
+        write_kb(output, "Private_Dirty:", self.private_dirty);
+        write_kb(output, "Referenced:", self.referenced);
+        write_kb(output, "Anonymous:", self.anonymous);
+        for name in [
+            "KSM:",
+            "LazyFree:",
+            "AnonHugePages:",
+            "ShmemPmdMapped:",
+            "FilePmdMapped:",
+            "Shared_Hugetlb:",
+            "Private_Hugetlb:",
+            "Swap:",
+            "SwapPss:",
+        ] {
+            write_kb(output, name, 0);
+        }
+        write_kb(output, "Locked:", self.pss_locked >> PSS_SHIFT);
+    }
+}
+
+fn write_kb(output: &mut String, name: &str, bytes: u64) {
+    writeln!(output, "{:<16}{:>8} kB", name, bytes >> 10).unwrap();
+}
+
+fn write_vm_flags(output: &mut String, vm_mapping: &VmMapping) {
+    let perms = vm_mapping.perms();
+
+    output.push_str("VmFlags: ");
+    let flags = [
+        (perms.contains(VmPerms::READ), "rd"),
+        (perms.contains(VmPerms::WRITE), "wr"),
+        (perms.contains(VmPerms::EXEC), "ex"),
+        (vm_mapping.is_shared(), "sh"),
+        (vm_mapping.mlock().is_some(), "lo"),
+    ];
+    for (_, mnemonic) in flags.iter().filter(|(is_set, _)| *is_set) {
+        write!(output, "{} ", mnemonic).unwrap();
+    }
+    output.push('\n');
+}
@@ -100,6 +100,12 @@ impl FileOps for StatusFileOps {
         .unwrap();
 
         if let Some(vmar_ref) = process.lock_root_vmar().as_ref() {
+            writeln!(
+                status_output,
+                "VmLck:\t{} kB",
+                vmar_ref.locked_size() / 1024
+            )
+            .unwrap();
             let anon = vmar_ref.get_rss_counter(RssType::RSS_ANONPAGES) * (PAGE_SIZE / 1024);
             let file = vmar_ref.get_rss_counter(RssType::RSS_FILEPAGES) * (PAGE_SIZE / 1024);
             let rss = anon + file;
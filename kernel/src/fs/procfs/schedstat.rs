@@ -0,0 +1,110 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module offers `/proc/schedstat` file support, which provides the
+//! scheduling statistics of each CPU, including the load-balancing migrations.
+//!
+//! Reference: <https://docs.kernel.org/scheduler/sched-stats.html>
+
+use alloc::format;
+use core::fmt::Write;
+
+use ostd::{
+    cpu::{all_cpus, num_cpus},
+    timer::Jiffies,
+};
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::Inode,
+    },
+    prelude::*,
+    sched,
+};
+
+/// The version of the output format.
+const SCHEDSTAT_VERSION: u32 = 15;
+
+/// Represents the inode at `/proc/schedstat`.
+pub struct SchedStatFileOps;
+
+impl SchedStatFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl FileOps for SchedStatFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let mut output = format!(
+            "version {}\ntimestamp {}\n",
+            SCHEDSTAT_VERSION,
+            Jiffies::elapsed().as_u64()
+        );
+
+        // All CPUs are in a single scheduling domain.
+        let domain_mask = cpu_mask_all();
+
+        for cpu in all_cpus() {
+            let stats = sched::cpu_sched_stats(cpu);
+
+            // The `rq_cpu_time`, `run_delay` and `pcount` fields are not tracked.
+            writeln!(
+                output,
+                "cpu{} {} 0 {} {} {} {} 0 0 0",
+                cpu.as_usize(),
+                stats.yld_count,
+                stats.sched_count,
+                stats.sched_goidle,
+                stats.ttwu_count,
+                stats.ttwu_local,
+            )
+            .unwrap();
+
+            write!(output, "domain0 {}", domain_mask).unwrap();
+            for lb in stats.lb.iter() {
+                // The `lb_nobusyg` field is always zero since there are no
+                // scheduling groups.
+                write!(
+                    output,
+                    " {} {} {} {} {} {} {} 0",
+                    lb.lb_count,
+                    lb.lb_balanced,
+                    lb.lb_failed,
+                    lb.lb_imbalance,
+                    lb.lb_gained,
+                    lb.lb_hot_gained,
+                    lb.lb_nobusyq,
+                )
+                .unwrap();
+            }
+            // Active balancing, `SD_BALANCE_EXEC` and `SD_BALANCE_FORK` are not
+            // supported. Among the wakeup statistics, only `ttwu_move_affine`
+            // is tracked.
+            writeln!(output, " 0 0 0 0 0 0 0 0 0 0 {} 0", stats.ttwu_move_affine).unwrap();
+        }
+
+        Ok(output.into_bytes())
+    }
+}
+
+/// Formats the mask of all CPUs in the way Linux prints a `cpumask`, i.e.,
+/// comma-separated 32-bit hexadecimal words with the highest word first.
+fn cpu_mask_all() -> String {
+    let num_cpus = num_cpus();
+    let nr_words = num_cpus.div_ceil(32);
+
+    (0..nr_words)
+        .rev()
+        .map(|word_index| {
+            let nr_bits = (num_cpus - word_index * 32).min(32);
+            let word = if nr_bits == 32 {
+                u32::MAX
+            } else {
+                (1u32 << nr_bits) - 1
+            };
+            format!("{:08x}", word)
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
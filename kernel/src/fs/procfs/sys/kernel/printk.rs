@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_logger::console_log_levels;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/sys/kernel/printk`.
+///
+/// The file contains four log levels: the console log level, the default message log level, the
+/// minimum console log level, and the default console log level.
+pub struct PrintkFileOps;
+
+impl PrintkFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o644))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for PrintkFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let [console, default_message, minimum_console, default_console] =
+            console_log_levels().levels();
+        let output = format!(
+            "{}\t{}\t{}\t{}\n",
+            console, default_message, minimum_console, default_console
+        );
+        Ok(output.into_bytes())
+    }
+
+    fn write_at(&self, _offset: usize, reader: &mut VmReader) -> Result<usize> {
+        const MAX_INPUT_LEN: usize = 64;
+
+        let len = reader.remain();
+        if len > MAX_INPUT_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the input is too long");
+        }
+
+        let mut buffer = vec![0u8; len];
+        reader.read_fallible(&mut VmWriter::from(buffer.as_mut_slice()))?;
+
+        let levels = parse_levels(&buffer)?;
+        console_log_levels().set_levels(&levels);
+
+        Ok(len)
+    }
+}
+
+/// Parses up to four log levels.
+///
+/// Like Linux, the levels that are not specified are left unchanged.
+fn parse_levels(input: &[u8]) -> Result<Vec<u8>> {
+    const NR_LEVELS: usize = 4;
+
+    let input = core::str::from_utf8(input)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "the input is not valid UTF-8"))?;
+
+    let levels = input
+        .split_ascii_whitespace()
+        .map(|s| {
+            s.parse::<u8>()
+                .map_err(|_| Error::with_message(Errno::EINVAL, "the log level is invalid"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if levels.len() > NR_LEVELS {
+        return_errno_with_message!(Errno::EINVAL, "too many log levels are specified");
+    }
+
+    Ok(levels)
+}
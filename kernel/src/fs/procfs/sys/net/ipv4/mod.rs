@@ -0,0 +1,61 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    fs::{
+        procfs::{
+            sys::net::ipv4::{
+                ping_group_range::PingGroupRangeFileOps,
+                tcp_available_congestion_control::TcpAvailableCongestionControlFileOps,
+                tcp_congestion_control::TcpCongestionControlFileOps,
+            },
+            template::{DirOps, ProcDirBuilder},
+            ProcDir,
+        },
+        utils::{DirEntryVecExt, Inode},
+    },
+    prelude::*,
+};
+
+mod ping_group_range;
+mod tcp_available_congestion_control;
+mod tcp_congestion_control;
+
+/// Represents the inode at `/proc/sys/net/ipv4`.
+pub struct Ipv4DirOps;
+
+impl Ipv4DirOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcDirBuilder::new(Self).parent(parent).build().unwrap()
+    }
+}
+
+impl DirOps for Ipv4DirOps {
+    fn lookup_child(&self, this_ptr: Weak<dyn Inode>, name: &str) -> Result<Arc<dyn Inode>> {
+        let inode = match name {
+            "ping_group_range" => PingGroupRangeFileOps::new_inode(this_ptr.clone()),
+            "tcp_available_congestion_control" => {
+                TcpAvailableCongestionControlFileOps::new_inode(this_ptr.clone())
+            }
+            "tcp_congestion_control" => TcpCongestionControlFileOps::new_inode(this_ptr.clone()),
+            _ => return_errno!(Errno::ENOENT),
+        };
+        Ok(inode)
+    }
+
+    fn populate_children(&self, this_ptr: Weak<dyn Inode>) {
+        let this = {
+            let this = this_ptr.upgrade().unwrap();
+            this.downcast_ref::<ProcDir<Ipv4DirOps>>().unwrap().this()
+        };
+        let mut cached_children = this.cached_children().write();
+        cached_children.put_entry_if_not_found("ping_group_range", || {
+            PingGroupRangeFileOps::new_inode(this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("tcp_available_congestion_control", || {
+            TcpAvailableCongestionControlFileOps::new_inode(this_ptr.clone())
+        });
+        cached_children.put_entry_if_not_found("tcp_congestion_control", || {
+            TcpCongestionControlFileOps::new_inode(this_ptr.clone())
+        });
+    }
+}
@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::socket::ip::{ping_group_range, set_ping_group_range},
+    prelude::*,
+    process::Gid,
+};
+
+/// Represents the inode at `/proc/sys/net/ipv4/ping_group_range`.
+///
+/// The file contains two group IDs, which specify the range of groups that are allowed to
+/// create ICMP ping sockets.
+pub struct PingGroupRangeFileOps;
+
+impl PingGroupRangeFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o644))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for PingGroupRangeFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let (low, high) = ping_group_range();
+        let output = format!("{}\t{}\n", u32::from(low), u32::from(high));
+        Ok(output.into_bytes())
+    }
+
+    fn write_at(&self, _offset: usize, reader: &mut VmReader) -> Result<usize> {
+        const MAX_INPUT_LEN: usize = 64;
+
+        let len = reader.remain();
+        if len > MAX_INPUT_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the input is too long");
+        }
+
+        let mut buffer = vec![0u8; len];
+        reader.read_fallible(&mut VmWriter::from(buffer.as_mut_slice()))?;
+
+        let (low, high) = parse_range(&buffer)?;
+        set_ping_group_range(low, high);
+
+        Ok(len)
+    }
+}
+
+fn parse_range(input: &[u8]) -> Result<(Gid, Gid)> {
+    // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/sysctl_net_ipv4.c#L163>.
+    let parse_gid = |s: Option<&str>| -> Result<Gid> {
+        s.and_then(|s| s.parse::<u32>().ok())
+            .filter(|gid| *gid <= i32::MAX as u32)
+            .map(Gid::new)
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the group ID is invalid"))
+    };
+
+    let input = core::str::from_utf8(input)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "the input is not valid UTF-8"))?;
+    let mut fields = input.split_ascii_whitespace();
+
+    let low = parse_gid(fields.next())?;
+    let high = parse_gid(fields.next())?;
+    if fields.next().is_some() {
+        return_errno_with_message!(Errno::EINVAL, "too many group IDs are specified");
+    }
+
+    Ok((low, high))
+}
@@ -0,0 +1,39 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::socket::CongestionControl;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/sys/net/ipv4/tcp_available_congestion_control`.
+///
+/// The file lists the names of all the supported congestion control algorithms.
+pub struct TcpAvailableCongestionControlFileOps;
+
+impl TcpAvailableCongestionControlFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o444))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for TcpAvailableCongestionControlFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let names: Vec<&str> = CongestionControl::ALL
+            .iter()
+            .map(|control| control.name())
+            .collect();
+
+        let mut output = names.join(" ");
+        output.push('\n');
+        Ok(output.into_bytes())
+    }
+}
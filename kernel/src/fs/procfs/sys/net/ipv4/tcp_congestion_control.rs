@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_bigtcp::socket::CongestionControl;
+
+use crate::{
+    fs::{
+        procfs::template::{FileOps, ProcFileBuilder},
+        utils::{Inode, InodeMode},
+    },
+    net::socket::ip::stream_options::{default_congestion_control, set_default_congestion_control},
+    prelude::*,
+};
+
+/// Represents the inode at `/proc/sys/net/ipv4/tcp_congestion_control`.
+///
+/// The file contains the name of the congestion control algorithm used by new TCP sockets.
+pub struct TcpCongestionControlFileOps;
+
+impl TcpCongestionControlFileOps {
+    pub fn new_inode(parent: Weak<dyn Inode>) -> Arc<dyn Inode> {
+        ProcFileBuilder::new(Self)
+            .parent(parent)
+            .mode(InodeMode::from_bits_truncate(0o644))
+            .build()
+            .unwrap()
+    }
+}
+
+impl FileOps for TcpCongestionControlFileOps {
+    fn data(&self) -> Result<Vec<u8>> {
+        let output = format!("{}\n", default_congestion_control().name());
+        Ok(output.into_bytes())
+    }
+
+    fn write_at(&self, _offset: usize, reader: &mut VmReader) -> Result<usize> {
+        const MAX_INPUT_LEN: usize = 64;
+
+        let len = reader.remain();
+        if len > MAX_INPUT_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the input is too long");
+        }
+
+        let mut buffer = vec![0u8; len];
+        reader.read_fallible(&mut VmWriter::from(buffer.as_mut_slice()))?;
+
+        let name = core::str::from_utf8(&buffer)
+            .map_err(|_| Error::with_message(Errno::EINVAL, "the input is not valid UTF-8"))?;
+        let Some(control) = CongestionControl::from_name(name.trim()) else {
+            return_errno_with_message!(Errno::ENOENT, "the congestion control is not available");
+        };
+        set_default_congestion_control(control);
+
+        Ok(len)
+    }
+}
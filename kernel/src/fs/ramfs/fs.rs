@@ -26,7 +26,7 @@ use crate::{
         utils::{
             CStr256, CachePage, DirentVisitor, Extension, FallocMode, FileSystem, FsFlags, Inode,
             InodeMode, InodeType, IoctlCmd, Metadata, MknodType, PageCache, PageCacheBackend,
-            Permission, SuperBlock, XattrName, XattrNamespace, XattrSetFlags,
+            Permission, StatusFlags, SuperBlock, XattrName, XattrNamespace, XattrSetFlags,
         },
     },
     prelude::*,
@@ -535,7 +535,7 @@ impl Inode for RamInode {
                     read_len
                 }
                 Inner::Device(device) => {
-                    device.read(writer)?
+                    device.read(writer, StatusFlags::empty())?
                     // Typically, devices like "/dev/zero" or "/dev/null" do not require modifying
                     // timestamps here. Please adjust this behavior accordingly if there are special devices.
                 }
@@ -581,7 +581,7 @@ impl Inode for RamInode {
             }
             InodeType::CharDevice | InodeType::BlockDevice => {
                 let device = self.inner.as_device().unwrap();
-                device.write(reader)?
+                device.write(reader, StatusFlags::empty())?
                 // Typically, devices like "/dev/zero" or "/dev/null" do not require modifying
                 // timestamps here. Please adjust this behavior accordingly if there are special devices.
             }
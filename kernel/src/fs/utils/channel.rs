@@ -137,6 +137,11 @@ impl<T> Producer<T> {
 }
 
 impl Producer<u8> {
+    /// Returns the number of bytes that can be written to the channel without blocking.
+    pub fn free_len(&self) -> usize {
+        self.this_end().rb().free_len()
+    }
+
     /// Tries to write `buf` to the channel.
     ///
     /// - Returns `Ok(_)` with the number of bytes written if successful.
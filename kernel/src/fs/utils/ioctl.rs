@@ -12,21 +12,47 @@ pub enum IoctlCmd {
     TCSETSW = 0x5403,
     /// Drain the output buffer, and discard pending input, and set attributes
     TCSETSF = 0x5404,
+    /// Wait until the output buffer is sent, and send a break if the argument is zero
+    TCSBRK = 0x5409,
+    /// Suspend or restart the transmission or reception of data
+    TCXONC = 0x540A,
+    /// Discard the data in the input buffer, the output buffer, or both
+    TCFLSH = 0x540B,
     /// Make the given terminal the controlling terminal of the calling process.
     TIOCSCTTY = 0x540e,
     /// Get the process group ID of the foreground process group on this terminal
     TIOCGPGRP = 0x540f,
     /// Set the foreground process group ID of this terminal.
     TIOCSPGRP = 0x5410,
-    /// Get the number of bytes in the input buffer.
+    /// Get the number of bytes in the output buffer (also known as `SIOCOUTQ`).
+    TIOCOUTQ = 0x5411,
+    /// Get the number of bytes in the input buffer (also known as `SIOCINQ`).
     FIONREAD = 0x541B,
+    /// Insert the given byte into the input buffer
+    TIOCSTI = 0x5412,
     /// Set window size
     TIOCGWINSZ = 0x5413,
     TIOCSWINSZ = 0x5414,
+    /// Get the status of the modem control lines
+    TIOCMGET = 0x5415,
+    /// Set the given modem control lines
+    TIOCMBIS = 0x5416,
+    /// Clear the given modem control lines
+    TIOCMBIC = 0x5417,
+    /// Set the status of the modem control lines
+    TIOCMSET = 0x5418,
     /// Enable or disable non-blocking I/O mode.
     FIONBIO = 0x5421,
     /// the calling process gives up this controlling terminal
     TIOCNOTTY = 0x5422,
+    /// Set the line discipline of the terminal
+    TIOCSETD = 0x5423,
+    /// Get the line discipline of the terminal
+    TIOCGETD = 0x5424,
+    /// Start sending a break
+    TIOCSBRK = 0x5427,
+    /// Stop sending a break
+    TIOCCBRK = 0x5428,
     /// Return the session ID of FD
     TIOCGSID = 0x5429,
     /// Clear the close on exec flag on a file descriptor
@@ -35,6 +61,72 @@ pub enum IoctlCmd {
     FIOCLEX = 0x5451,
     /// Enable or disable asynchronous I/O mode.
     FIOASYNC = 0x5452,
+    /// Wait for a change on the given modem status lines
+    TIOCMIWAIT = 0x545C,
+    /// Get the global state of the virtual consoles
+    VT_GETSTATE = 0x5603,
+    /// Switch to the given virtual console
+    VT_ACTIVATE = 0x5606,
+    /// Wait until the given virtual console is active
+    VT_WAITACTIVE = 0x5607,
+    /// Set the text or graphics mode of the virtual console
+    KDSETMODE = 0x4B3A,
+    /// Get the text or graphics mode of the virtual console
+    KDGETMODE = 0x4B3B,
+    /// Get the name of a network interface by its index
+    SIOCGIFNAME = 0x8910,
+    /// Get the list of network interface addresses
+    SIOCGIFCONF = 0x8912,
+    /// Get the flags of a network interface
+    SIOCGIFFLAGS = 0x8913,
+    /// Set the flags of a network interface
+    SIOCSIFFLAGS = 0x8914,
+    /// Get the IPv4 address of a network interface
+    SIOCGIFADDR = 0x8915,
+    /// Set the IPv4 address of a network interface
+    SIOCSIFADDR = 0x8916,
+    /// Get the IPv4 network mask of a network interface
+    SIOCGIFNETMASK = 0x891b,
+    /// Set the IPv4 network mask of a network interface
+    SIOCSIFNETMASK = 0x891c,
+    /// Get the MTU of a network interface
+    SIOCGIFMTU = 0x8921,
+    /// Set the MTU of a network interface
+    SIOCSIFMTU = 0x8922,
+    /// Set the hardware address of a network interface
+    SIOCSIFHWADDR = 0x8924,
+    /// Get the hardware address of a network interface
+    SIOCGIFHWADDR = 0x8927,
+    /// Get the index of a network interface by its name
+    SIOCGIFINDEX = 0x8933,
+    /// Enable the RTC alarm interrupt
+    RTC_AIE_ON = 0x7001,
+    /// Disable the RTC alarm interrupt
+    RTC_AIE_OFF = 0x7002,
+    /// Enable the RTC update interrupt
+    RTC_UIE_ON = 0x7003,
+    /// Disable the RTC update interrupt
+    RTC_UIE_OFF = 0x7004,
+    /// Enable the RTC periodic interrupt
+    RTC_PIE_ON = 0x7005,
+    /// Disable the RTC periodic interrupt
+    RTC_PIE_OFF = 0x7006,
+    /// Set the RTC alarm time
+    RTC_ALM_SET = 0x40247007,
+    /// Read the RTC alarm time
+    RTC_ALM_READ = 0x80247008,
+    /// Read the RTC time
+    RTC_RD_TIME = 0x80247009,
+    /// Set the RTC time
+    RTC_SET_TIME = 0x4024700a,
+    /// Read the frequency of the RTC periodic interrupt
+    RTC_IRQP_READ = 0x8008700b,
+    /// Set the frequency of the RTC periodic interrupt
+    RTC_IRQP_SET = 0x4008700c,
+    /// Set the RTC wakeup alarm
+    RTC_WKALM_SET = 0x4028700f,
+    /// Read the RTC wakeup alarm
+    RTC_WKALM_RD = 0x80287010,
     /// Get Pty Number
     TIOCGPTN = 0x80045430,
     /// Lock/unlock Pty
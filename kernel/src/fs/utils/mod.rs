@@ -53,6 +53,10 @@ pub enum SeekFrom {
     Start(usize),
     End(isize),
     Current(isize),
+    /// Seeks to the next data region at or after the offset (i.e., `SEEK_DATA`).
+    Data(usize),
+    /// Seeks to the next hole at or after the offset (i.e., `SEEK_HOLE`).
+    Hole(usize),
 }
 
 /// Maximum bytes in a path
@@ -39,6 +39,7 @@ pub enum ModuleArg {
 pub struct KCmdlineArg {
     initproc: InitprocArgs,
     module_args: BTreeMap<String, Vec<ModuleArg>>,
+    ip_config: Option<String>,
 }
 
 // Define get APIs.
@@ -59,6 +60,10 @@ impl KCmdlineArg {
     pub fn get_module_args(&self, module: &str) -> Option<&Vec<ModuleArg>> {
         self.module_args.get(module)
     }
+    /// Gets the IP autoconfiguration method (e.g., `dhcp` in `ip=dhcp`).
+    pub fn get_ip_config(&self) -> Option<&str> {
+        self.ip_config.as_deref()
+    }
 }
 
 // Splits the command line string by spaces but preserve
@@ -86,6 +91,7 @@ impl From<&str> for KCmdlineArg {
                 envp: Vec::new(),
             },
             module_args: BTreeMap::new(),
+            ip_config: None,
         };
 
         // Every thing after the "--" mark is the initproc arguments.
@@ -161,6 +167,9 @@ impl From<&str> for KCmdlineArg {
                         }
                         result.initproc.path = Some(value.to_string());
                     }
+                    "ip" => {
+                        result.ip_config = Some(value.to_string());
+                    }
                     _ => {
                         // If the option is not recognized, it is passed to the initproc.
                         // Pattern 'option=value' is treated as the init environment.
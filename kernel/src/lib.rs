@@ -38,7 +38,10 @@ use ostd::{
 use process::{spawn_init_process, Process};
 use sched::SchedPolicy;
 
-use crate::{prelude::*, thread::kernel_thread::ThreadOptions};
+use crate::{
+    prelude::*,
+    thread::{kernel_thread::ThreadOptions, Thread},
+};
 
 extern crate alloc;
 extern crate lru;
@@ -112,9 +115,14 @@ fn ap_init() {
 
         loop {
             ostd::task::halt_cpu();
+            // Threads may have been pulled to this CPU by the load balancer
+            // in the timer interrupt.
+            Thread::yield_now();
         }
     }
 
+    sched::init_on_ap();
+
     ThreadOptions::new(ap_idle_thread)
         // No races because `ap_init` runs on a certain AP.
         .cpu_affinity(CpuId::current_racy().into())
@@ -140,9 +148,10 @@ fn init_thread() {
 
     print_banner();
 
-    // FIXME: CI fails due to suspected performance issues with the framebuffer console.
-    // Additionally, userspace program may render GUIs using the framebuffer,
-    // so we disable the framebuffer console here.
+    // FIXME: CI fails due to suspected performance issues with the framebuffer console,
+    // so we disable the framebuffer console here. It will be enabled again once a virtual
+    // terminal is activated (e.g., by `Alt+Fn` or the `VT_ACTIVATE` ioctl). Userspace
+    // programs that render GUIs using the framebuffer should switch to `KD_GRAPHICS`.
     if let Some(console) = FRAMEBUFFER_CONSOLE.get() {
         console.disable();
     };
@@ -159,6 +168,9 @@ fn init_thread() {
     // Wait till initproc become zombie.
     while !initproc.status().is_zombie() {
         ostd::task::halt_cpu();
+        // Throttled deadline threads may have been replenished when the CPU
+        // is woken up by their timer event.
+        Thread::yield_now();
     }
 
     // TODO: exit via qemu isa debug device should not be the only way.
@@ -0,0 +1,161 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Ethernet frames that carry UDP datagrams.
+//!
+//! The DHCP client runs before the iface has an IPv4 address, so the network stack cannot send or
+//! receive the datagrams on its behalf. Instead, the client builds and parses the frames itself.
+
+use aster_bigtcp::wire::{EthernetAddress, Ipv4Address};
+
+use crate::prelude::*;
+
+const ETHER_HEADER_LEN: usize = 14;
+const IPV4_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const IP_PROTOCOL_UDP: u8 = 17;
+const DEFAULT_TTL: u8 = 64;
+
+/// The addresses of a UDP datagram carried in an Ethernet frame.
+pub(super) struct UdpFrameAddrs {
+    pub(super) src_ether: EthernetAddress,
+    pub(super) dst_ether: EthernetAddress,
+    pub(super) src_addr: Ipv4Address,
+    pub(super) dst_addr: Ipv4Address,
+    pub(super) src_port: u16,
+    pub(super) dst_port: u16,
+}
+
+/// Builds an Ethernet frame that carries `payload` in a UDP datagram.
+pub(super) fn build_udp_frame(addrs: &UdpFrameAddrs, payload: &[u8]) -> Vec<u8> {
+    let udp_len = UDP_HEADER_LEN + payload.len();
+    let ip_len = IPV4_HEADER_LEN + udp_len;
+
+    let mut frame = Vec::with_capacity(ETHER_HEADER_LEN + ip_len);
+
+    frame.extend_from_slice(addrs.dst_ether.as_bytes());
+    frame.extend_from_slice(addrs.src_ether.as_bytes());
+    frame.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+
+    let ip_header = {
+        let mut header = [0u8; IPV4_HEADER_LEN];
+        header[0] = 0x45; // Version 4, 5 words
+        header[2..4].copy_from_slice(&(ip_len as u16).to_be_bytes());
+        header[8] = DEFAULT_TTL;
+        header[9] = IP_PROTOCOL_UDP;
+        header[12..16].copy_from_slice(&addrs.src_addr.octets());
+        header[16..20].copy_from_slice(&addrs.dst_addr.octets());
+        let checksum = checksum(&[&header]);
+        header[10..12].copy_from_slice(&checksum.to_be_bytes());
+        header
+    };
+    frame.extend_from_slice(&ip_header);
+
+    let udp_header = {
+        let mut header = [0u8; UDP_HEADER_LEN];
+        header[0..2].copy_from_slice(&addrs.src_port.to_be_bytes());
+        header[2..4].copy_from_slice(&addrs.dst_port.to_be_bytes());
+        header[4..6].copy_from_slice(&(udp_len as u16).to_be_bytes());
+        let pseudo_header = pseudo_header(&addrs.src_addr, &addrs.dst_addr, udp_len);
+        // A zero checksum means that there is no checksum, so it is transmitted as all ones.
+        let checksum = match checksum(&[&pseudo_header, &header, payload]) {
+            0 => 0xffff,
+            checksum => checksum,
+        };
+        header[6..8].copy_from_slice(&checksum.to_be_bytes());
+        header
+    };
+    frame.extend_from_slice(&udp_header);
+
+    frame.extend_from_slice(payload);
+
+    frame
+}
+
+/// Parses an Ethernet frame that carries a UDP datagram.
+///
+/// This method returns the addresses and the payload of the datagram, or `None` if the frame is
+/// malformed or does not carry a UDP datagram.
+pub(super) fn parse_udp_frame(frame: &[u8]) -> Option<(UdpFrameAddrs, &[u8])> {
+    let (ether_header, packet) = frame.split_at_checked(ETHER_HEADER_LEN)?;
+    if ether_header[12..14] != ETHERTYPE_IPV4.to_be_bytes() {
+        return None;
+    }
+
+    let header_len = ((*packet.first()? & 0x0f) as usize) * 4;
+    if packet[0] >> 4 != 4 || header_len < IPV4_HEADER_LEN || packet.len() < header_len {
+        return None;
+    }
+    let ip_header = &packet[..header_len];
+    let total_len = u16::from_be_bytes([ip_header[2], ip_header[3]]) as usize;
+    // Fragmented packets are not supported.
+    let is_fragmented = u16::from_be_bytes([ip_header[6], ip_header[7]]) & 0x3fff != 0;
+    if ip_header[9] != IP_PROTOCOL_UDP
+        || is_fragmented
+        || total_len < header_len
+        || total_len > packet.len()
+        || checksum(&[ip_header]) != 0
+    {
+        return None;
+    }
+    let src_addr = Ipv4Address::from(<[u8; 4]>::try_from(&ip_header[12..16]).unwrap());
+    let dst_addr = Ipv4Address::from(<[u8; 4]>::try_from(&ip_header[16..20]).unwrap());
+
+    let datagram = &packet[header_len..total_len];
+    let (udp_header, _) = datagram.split_at_checked(UDP_HEADER_LEN)?;
+    let udp_len = u16::from_be_bytes([udp_header[4], udp_header[5]]) as usize;
+    if udp_len < UDP_HEADER_LEN || udp_len > datagram.len() {
+        return None;
+    }
+    let datagram = &datagram[..udp_len];
+    let has_checksum = udp_header[6..8] != [0, 0];
+    if has_checksum && checksum(&[&pseudo_header(&src_addr, &dst_addr, udp_len), datagram]) != 0 {
+        return None;
+    }
+
+    let addrs = UdpFrameAddrs {
+        src_ether: EthernetAddress::from_bytes(&ether_header[6..12]),
+        dst_ether: EthernetAddress::from_bytes(&ether_header[0..6]),
+        src_addr,
+        dst_addr,
+        src_port: u16::from_be_bytes([udp_header[0], udp_header[1]]),
+        dst_port: u16::from_be_bytes([udp_header[2], udp_header[3]]),
+    };
+
+    Some((addrs, &datagram[UDP_HEADER_LEN..]))
+}
+
+fn pseudo_header(src_addr: &Ipv4Address, dst_addr: &Ipv4Address, udp_len: usize) -> [u8; 12] {
+    let mut header = [0u8; 12];
+    header[0..4].copy_from_slice(&src_addr.octets());
+    header[4..8].copy_from_slice(&dst_addr.octets());
+    header[9] = IP_PROTOCOL_UDP;
+    header[10..12].copy_from_slice(&(udp_len as u16).to_be_bytes());
+    header
+}
+
+/// Computes the Internet checksum of the concatenated chunks.
+///
+/// All the chunks except the last one must have an even length.
+///
+/// Reference: <https://www.rfc-editor.org/rfc/rfc1071>.
+fn checksum(chunks: &[&[u8]]) -> u16 {
+    let mut sum = 0u32;
+
+    for chunk in chunks {
+        let mut words = chunk.chunks_exact(2);
+        for word in words.by_ref() {
+            sum += u16::from_be_bytes([word[0], word[1]]) as u32;
+        }
+        if let [last] = words.remainder() {
+            sum += (*last as u32) << 8;
+        }
+    }
+
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+
+    !(sum as u16)
+}
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! DHCP messages.
+//!
+//! Reference: <https://www.rfc-editor.org/rfc/rfc2131#section-2> and
+//! <https://www.rfc-editor.org/rfc/rfc2132>.
+
+use aster_bigtcp::wire::{EthernetAddress, Ipv4Address};
+
+use crate::prelude::*;
+
+pub(super) const SERVER_PORT: u16 = 67;
+pub(super) const CLIENT_PORT: u16 = 68;
+
+const OP_BOOTREQUEST: u8 = 1;
+const OP_BOOTREPLY: u8 = 2;
+const HTYPE_ETHERNET: u8 = 1;
+/// Asks the server to broadcast the replies, since we cannot receive unicast IP packets before
+/// the iface is configured.
+const FLAG_BROADCAST: u16 = 0x8000;
+const MAGIC_COOKIE: [u8; 4] = [99, 130, 83, 99];
+
+/// The length of the fixed part of a message, including the magic cookie.
+const FIXED_LEN: usize = 240;
+const XID_OFFSET: usize = 4;
+const CIADDR_OFFSET: usize = 12;
+const YIADDR_OFFSET: usize = 16;
+const CHADDR_OFFSET: usize = 28;
+const COOKIE_OFFSET: usize = 236;
+
+const OPTION_PAD: u8 = 0;
+const OPTION_SUBNET_MASK: u8 = 1;
+const OPTION_ROUTER: u8 = 3;
+const OPTION_DNS_SERVER: u8 = 6;
+const OPTION_DOMAIN_NAME: u8 = 15;
+const OPTION_REQUESTED_ADDR: u8 = 50;
+const OPTION_LEASE_TIME: u8 = 51;
+const OPTION_MESSAGE_TYPE: u8 = 53;
+const OPTION_SERVER_ID: u8 = 54;
+const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+const OPTION_MAX_MESSAGE_SIZE: u8 = 57;
+const OPTION_RENEWAL_TIME: u8 = 58;
+const OPTION_REBINDING_TIME: u8 = 59;
+const OPTION_CLIENT_ID: u8 = 61;
+const OPTION_END: u8 = 255;
+
+/// The largest message that we can receive in a 1500-byte Ethernet frame.
+const MAX_MESSAGE_SIZE: u16 = 576;
+
+/// The type of a DHCP message (option 53).
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+pub(super) enum MessageType {
+    Discover = 1,
+    Offer = 2,
+    Request = 3,
+    Decline = 4,
+    Ack = 5,
+    Nak = 6,
+    Release = 7,
+    Inform = 8,
+}
+
+/// A message sent from the client to the servers.
+pub(super) struct ClientMessage {
+    pub(super) type_: MessageType,
+    pub(super) xid: u32,
+    pub(super) hw_addr: EthernetAddress,
+    /// The address that the client is using, which is only set when renewing the lease.
+    pub(super) client_addr: Ipv4Address,
+    pub(super) requested_addr: Option<Ipv4Address>,
+    pub(super) server_id: Option<Ipv4Address>,
+}
+
+impl ClientMessage {
+    pub(super) fn emit(&self) -> Vec<u8> {
+        let mut buf = vec![0u8; FIXED_LEN];
+
+        buf[0] = OP_BOOTREQUEST;
+        buf[1] = HTYPE_ETHERNET;
+        buf[2] = self.hw_addr.as_bytes().len() as u8;
+        buf[XID_OFFSET..XID_OFFSET + 4].copy_from_slice(&self.xid.to_be_bytes());
+        if self.client_addr.is_unspecified() {
+            buf[10..12].copy_from_slice(&FLAG_BROADCAST.to_be_bytes());
+        }
+        buf[CIADDR_OFFSET..CIADDR_OFFSET + 4].copy_from_slice(&self.client_addr.octets());
+        buf[CHADDR_OFFSET..CHADDR_OFFSET + 6].copy_from_slice(self.hw_addr.as_bytes());
+        buf[COOKIE_OFFSET..FIXED_LEN].copy_from_slice(&MAGIC_COOKIE);
+
+        buf.extend_from_slice(&[OPTION_MESSAGE_TYPE, 1, self.type_ as u8]);
+
+        // The client identifier consists of the hardware type and the hardware address.
+        buf.extend_from_slice(&[OPTION_CLIENT_ID, 7, HTYPE_ETHERNET]);
+        buf.extend_from_slice(self.hw_addr.as_bytes());
+
+        if let Some(requested_addr) = self.requested_addr {
+            buf.extend_from_slice(&[OPTION_REQUESTED_ADDR, 4]);
+            buf.extend_from_slice(&requested_addr.octets());
+        }
+        if let Some(server_id) = self.server_id {
+            buf.extend_from_slice(&[OPTION_SERVER_ID, 4]);
+            buf.extend_from_slice(&server_id.octets());
+        }
+
+        buf.extend_from_slice(&[OPTION_MAX_MESSAGE_SIZE, 2]);
+        buf.extend_from_slice(&MAX_MESSAGE_SIZE.to_be_bytes());
+
+        let parameters = [
+            OPTION_SUBNET_MASK,
+            OPTION_ROUTER,
+            OPTION_DNS_SERVER,
+            OPTION_DOMAIN_NAME,
+            OPTION_LEASE_TIME,
+            OPTION_RENEWAL_TIME,
+            OPTION_REBINDING_TIME,
+        ];
+        buf.extend_from_slice(&[OPTION_PARAMETER_REQUEST_LIST, parameters.len() as u8]);
+        buf.extend_from_slice(&parameters);
+
+        buf.push(OPTION_END);
+
+        buf
+    }
+}
+
+/// A message sent from a server to the client.
+#[derive(Debug)]
+pub(super) struct ServerMessage {
+    pub(super) type_: MessageType,
+    pub(super) xid: u32,
+    /// The address assigned to the client (`yiaddr`).
+    pub(super) your_addr: Ipv4Address,
+    pub(super) server_id: Option<Ipv4Address>,
+    pub(super) subnet_mask: Option<Ipv4Address>,
+    pub(super) router: Option<Ipv4Address>,
+    pub(super) dns_servers: Vec<Ipv4Address>,
+    pub(super) domain_name: Option<String>,
+    /// The lease time in seconds.
+    pub(super) lease_time: Option<u32>,
+    /// The time in seconds until the client starts renewing the lease (T1).
+    pub(super) renewal_time: Option<u32>,
+    /// The time in seconds until the client starts rebinding the lease (T2).
+    pub(super) rebinding_time: Option<u32>,
+}
+
+impl ServerMessage {
+    /// Parses a message sent to the client whose hardware address is `hw_addr`.
+    ///
+    /// This method returns `None` if the message is malformed or sent to another client.
+    pub(super) fn parse(buf: &[u8], hw_addr: &EthernetAddress) -> Option<Self> {
+        if buf.len() < FIXED_LEN
+            || buf[0] != OP_BOOTREPLY
+            || buf[1] != HTYPE_ETHERNET
+            || buf[CHADDR_OFFSET..CHADDR_OFFSET + 6] != *hw_addr.as_bytes()
+            || buf[COOKIE_OFFSET..FIXED_LEN] != MAGIC_COOKIE
+        {
+            return None;
+        }
+
+        let xid = u32::from_be_bytes(buf[XID_OFFSET..XID_OFFSET + 4].try_into().unwrap());
+        let your_addr = read_addr(&buf[YIADDR_OFFSET..YIADDR_OFFSET + 4])?;
+
+        let mut type_ = None;
+        let mut message = Self {
+            type_: MessageType::Nak,
+            xid,
+            your_addr,
+            server_id: None,
+            subnet_mask: None,
+            router: None,
+            dns_servers: Vec::new(),
+            domain_name: None,
+            lease_time: None,
+            renewal_time: None,
+            rebinding_time: None,
+        };
+
+        // FIXME: Options overloaded into the `sname` and `file` fields (option 52) are ignored.
+        let mut options = &buf[FIXED_LEN..];
+        while let Some((&code, rest)) = options.split_first() {
+            match code {
+                OPTION_PAD => {
+                    options = rest;
+                    continue;
+                }
+                OPTION_END => break,
+                _ => (),
+            }
+
+            let (&len, rest) = rest.split_first()?;
+            let value = rest.get(..len as usize)?;
+            options = &rest[len as usize..];
+
+            match code {
+                OPTION_MESSAGE_TYPE => {
+                    type_ = Some(MessageType::try_from(*value.first()?).ok()?);
+                }
+                OPTION_SERVER_ID => message.server_id = read_addr(value),
+                OPTION_SUBNET_MASK => message.subnet_mask = read_addr(value),
+                // Only the first router is used.
+                OPTION_ROUTER => message.router = value.get(..4).and_then(read_addr),
+                OPTION_DNS_SERVER => {
+                    message.dns_servers = value.chunks_exact(4).filter_map(read_addr).collect();
+                }
+                OPTION_DOMAIN_NAME => {
+                    let name = core::str::from_utf8(value).ok()?;
+                    message.domain_name = Some(name.trim_end_matches('\0').to_string());
+                }
+                OPTION_LEASE_TIME => message.lease_time = read_u32(value),
+                OPTION_RENEWAL_TIME => message.renewal_time = read_u32(value),
+                OPTION_REBINDING_TIME => message.rebinding_time = read_u32(value),
+                _ => (),
+            }
+        }
+
+        message.type_ = type_?;
+        Some(message)
+    }
+}
+
+fn read_addr(value: &[u8]) -> Option<Ipv4Address> {
+    let octets: [u8; 4] = value.try_into().ok()?;
+    Some(Ipv4Address::from(octets))
+}
+
+fn read_u32(value: &[u8]) -> Option<u32> {
+    Some(u32::from_be_bytes(value.try_into().ok()?))
+}
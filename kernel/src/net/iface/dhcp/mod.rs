@@ -0,0 +1,538 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The DHCPv4 client that configures the primary iface at boot time.
+//!
+//! The client is enabled by `ip=dhcp` on the kernel command line. It obtains the address, the
+//! netmask, the default gateway and the DNS servers from a DHCP server, and then keeps renewing
+//! the lease in a kernel thread. The lease is published in `/proc/net/pnp`, so that user-space
+//! resolvers can find the DNS servers.
+//!
+//! The iface has no address before the lease is obtained, so the client sends raw frames with
+//! [`Iface::send_frame`] and receives the replies with a packet tap.
+//!
+//! Reference: <https://www.rfc-editor.org/rfc/rfc2131>.
+
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use aster_bigtcp::{
+    iface::{PacketTap, PacketType},
+    wire::{EthernetAddress, Ipv4Address, Ipv4Cidr},
+};
+use aster_softirq::BottomHalfDisabled;
+use ostd::{boot::boot_info, sync::WaitQueue, timer::Jiffies};
+
+use self::{
+    frame::{build_udp_frame, parse_udp_frame, UdpFrameAddrs},
+    message::{ClientMessage, MessageType, ServerMessage, CLIENT_PORT, SERVER_PORT},
+};
+use super::{
+    init::configure_primary_statically,
+    route::{routing_table, Route, RouteType},
+    Iface,
+};
+use crate::{
+    kcmdline::KCmdlineArg, net::socket::netlink::update_iface_addr, prelude::*,
+    thread::kernel_thread::ThreadOptions, util::random::getrandom,
+};
+
+mod frame;
+mod message;
+
+/// The configuration obtained from a DHCP server.
+#[derive(Debug, Clone)]
+pub struct DhcpLease {
+    /// The address and the netmask of the iface.
+    pub cidr: Ipv4Cidr,
+    /// The default gateway.
+    pub gateway: Option<Ipv4Address>,
+    pub dns_servers: Vec<Ipv4Address>,
+    pub domain_name: Option<String>,
+    /// The address of the DHCP server that grants the lease.
+    pub server: Ipv4Address,
+}
+
+static LEASE: RwLock<Option<DhcpLease>> = RwLock::new(None);
+
+/// The client that is created by [`init`] but not yet spawned.
+static PENDING_CLIENT: SpinLock<Option<Client>> = SpinLock::new(None);
+
+/// Whether the DHCP client is configuring the iface at boot time.
+static IS_BOOT_CONFIG_PENDING: AtomicBool = AtomicBool::new(false);
+static BOOT_CONFIG_WAIT_QUEUE: WaitQueue = WaitQueue::new();
+
+/// Returns the current lease, if any.
+pub fn current_lease() -> Option<DhcpLease> {
+    LEASE.read().clone()
+}
+
+/// Returns whether the DHCP client is enabled on the kernel command line.
+pub(super) fn is_enabled() -> bool {
+    let karg: KCmdlineArg = boot_info().kernel_cmdline.as_str().into();
+    // Like Linux, `ip=on` and `ip=any` enable all the supported protocols, i.e., only DHCP.
+    matches!(karg.get_ip_config(), Some("dhcp" | "on" | "any"))
+}
+
+/// Creates the DHCP client to configure `iface`.
+///
+/// The iface is left unconfigured until [`lazy_init`] starts the client in a kernel thread. If no
+/// lease can be obtained at boot time, the iface will be configured statically as if the DHCP
+/// client were not enabled.
+pub(super) fn init(iface: Arc<Iface>) {
+    let Some(hw_addr) = iface.hw_addr() else {
+        warn!("DHCP: `{}` has no hardware address", iface.name());
+        return;
+    };
+
+    let tap = Arc::new(DhcpTap::new(hw_addr));
+    iface.register_packet_tap(tap.clone());
+
+    *PENDING_CLIENT.lock() = Some(Client {
+        iface,
+        hw_addr,
+        tap,
+    });
+}
+
+/// Spawns the thread of the DHCP client, if any, and waits until the client finishes configuring
+/// the iface at boot time.
+pub(super) fn lazy_init() {
+    let Some(client) = PENDING_CLIENT.lock().take() else {
+        return;
+    };
+
+    IS_BOOT_CONFIG_PENDING.store(true, Ordering::Relaxed);
+    ThreadOptions::new(move || client.run()).spawn();
+
+    BOOT_CONFIG_WAIT_QUEUE
+        .wait_until(|| (!IS_BOOT_CONFIG_PENDING.load(Ordering::Relaxed)).then_some(()));
+}
+
+fn finish_boot_config() {
+    IS_BOOT_CONFIG_PENDING.store(false, Ordering::Relaxed);
+    BOOT_CONFIG_WAIT_QUEUE.wake_all();
+}
+
+/// The timeouts for the attempts to obtain a lease at boot time.
+const BOOT_TIMEOUTS: [Duration; 3] = [
+    Duration::from_secs(2),
+    Duration::from_secs(4),
+    Duration::from_secs(8),
+];
+/// The maximum timeout for the attempts to obtain a lease after the lease is lost.
+const MAX_TIMEOUT: Duration = Duration::from_secs(64);
+/// The minimum interval between retransmissions when renewing or rebinding the lease.
+const MIN_RENEW_INTERVAL: Duration = Duration::from_secs(60);
+/// The lease time that means that the lease never expires.
+const INFINITE_LEASE_TIME: u32 = u32::MAX;
+
+struct Client {
+    iface: Arc<Iface>,
+    hw_addr: EthernetAddress,
+    tap: Arc<DhcpTap>,
+}
+
+/// A lease that the client is bound to.
+struct Binding {
+    lease: DhcpLease,
+    /// The Ethernet address of the DHCP server, to which the renewal requests are sent.
+    server_ether: EthernetAddress,
+    /// The time when the lease is requested.
+    start_time: Duration,
+    /// The lease time in seconds.
+    lease_time: u32,
+    /// The time in seconds until the client starts renewing the lease (T1).
+    renewal_time: u32,
+    /// The time in seconds until the client starts rebinding the lease (T2).
+    rebinding_time: u32,
+}
+
+/// How a request is sent to the servers.
+enum Destination {
+    /// The request is broadcast from the address of the iface, which is unspecified if the iface
+    /// is not configured.
+    Broadcast,
+    /// The request is sent to the server that grants the lease.
+    Server(Ipv4Address, EthernetAddress),
+}
+
+impl Client {
+    fn run(self) {
+        self.maintain();
+        self.iface
+            .remove_packet_tap(&(self.tap.clone() as Arc<dyn PacketTap>));
+    }
+
+    /// Configures the iface and keeps the lease alive until the iface is removed.
+    fn maintain(&self) {
+        let binding = self.acquire(BOOT_TIMEOUTS);
+        match binding.as_ref() {
+            Some(binding) => self.apply(binding),
+            None => {
+                warn!(
+                    "DHCP: no lease is obtained for `{}`, falling back to the static configuration",
+                    self.iface.name()
+                );
+                configure_primary_statically(&self.iface);
+            }
+        }
+        finish_boot_config();
+
+        let Some(mut binding) = binding else {
+            return;
+        };
+
+        // An infinite lease never needs to be renewed.
+        while binding.lease_time != INFINITE_LEASE_TIME {
+            if let Some(new_binding) = self.renew(&binding) {
+                binding = new_binding;
+                self.apply(&binding);
+                continue;
+            }
+            warn!("DHCP: the lease of `{}` is lost", self.iface.name());
+            self.unapply();
+
+            let timeouts = core::iter::successors(Some(BOOT_TIMEOUTS[0]), |timeout| {
+                Some((*timeout * 2).min(MAX_TIMEOUT))
+            });
+            let Some(new_binding) = self.acquire(timeouts) else {
+                return;
+            };
+            binding = new_binding;
+            self.apply(&binding);
+        }
+    }
+
+    /// Obtains a new lease by broadcasting `DHCPDISCOVER` and `DHCPREQUEST`.
+    ///
+    /// One attempt is made for each timeout. This method returns `None` if all the attempts fail.
+    fn acquire(&self, timeouts: impl IntoIterator<Item = Duration>) -> Option<Binding> {
+        for timeout in timeouts {
+            let xid = new_xid();
+            let start_time = now();
+            let deadline = start_time + timeout;
+
+            let discover = self.new_message(MessageType::Discover, xid, None, None);
+            let is_offer = |reply: &ServerMessage| {
+                reply.type_ == MessageType::Offer
+                    && !reply.your_addr.is_unspecified()
+                    && reply.server_id.is_some()
+            };
+            let Some((offer, _)) =
+                self.transact(&discover, &Destination::Broadcast, deadline, is_offer)
+            else {
+                continue;
+            };
+
+            let request = self.new_message(
+                MessageType::Request,
+                xid,
+                Some(offer.your_addr),
+                offer.server_id,
+            );
+            match self.request(&request, &Destination::Broadcast, start_time, deadline) {
+                Some(Some(binding)) => return Some(binding),
+                // The server declines the request or does not respond. Start over.
+                Some(None) | None => continue,
+            }
+        }
+
+        None
+    }
+
+    /// Renews the lease with `DHCPREQUEST`, first from the server and then from any server.
+    ///
+    /// This method returns `None` if the lease expires or the lease is revoked.
+    fn renew(&self, binding: &Binding) -> Option<Binding> {
+        let renewal_time = binding.start_time + secs(binding.renewal_time);
+        let rebinding_time = binding.start_time + secs(binding.rebinding_time);
+        let expiry_time = binding.start_time + secs(binding.lease_time);
+
+        // Sleep until the renewal time.
+        let _ = self
+            .tap
+            .wait_queue
+            .wait_until_or_timeout(|| None::<()>, &until(renewal_time));
+
+        let server = Destination::Server(binding.lease.server, binding.server_ether);
+        for (destination, end_time) in [
+            (&server, rebinding_time),
+            (&Destination::Broadcast, expiry_time),
+        ] {
+            loop {
+                let start_time = now();
+                if start_time >= end_time {
+                    break;
+                }
+
+                // Retransmit at half of the remaining time, but not too frequently.
+                let deadline = (start_time + (end_time - start_time) / 2)
+                    .max(start_time + MIN_RENEW_INTERVAL)
+                    .min(end_time);
+
+                let request = ClientMessage {
+                    client_addr: binding.lease.cidr.address(),
+                    ..self.new_message(MessageType::Request, new_xid(), None, None)
+                };
+                match self.request(&request, destination, start_time, deadline) {
+                    Some(Some(binding)) => return Some(binding),
+                    Some(None) => return None,
+                    None => continue,
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Sends `DHCPREQUEST` and waits for `DHCPACK` or `DHCPNAK`.
+    ///
+    /// This method returns `None` if no reply is received before `deadline`, or `Some(None)` if
+    /// `DHCPNAK` is received.
+    fn request(
+        &self,
+        request: &ClientMessage,
+        destination: &Destination,
+        start_time: Duration,
+        deadline: Duration,
+    ) -> Option<Option<Binding>> {
+        let (reply, server_ether) = self.transact(request, destination, deadline, |reply| {
+            let is_from_server = request
+                .server_id
+                .is_none_or(|server_id| reply.server_id == Some(server_id));
+            is_from_server
+                && (reply.type_ == MessageType::Nak
+                    || (reply.type_ == MessageType::Ack && !reply.your_addr.is_unspecified()))
+        })?;
+
+        if reply.type_ == MessageType::Nak {
+            return Some(None);
+        }
+
+        let lease_time = reply.lease_time.unwrap_or(INFINITE_LEASE_TIME);
+        let renewal_time = reply.renewal_time.unwrap_or(lease_time / 2);
+        let rebinding_time = reply
+            .rebinding_time
+            .unwrap_or((lease_time as u64 * 7 / 8) as u32);
+
+        let prefix_len = match reply.subnet_mask {
+            Some(mask) => u32::from_be_bytes(mask.octets()).leading_ones() as u8,
+            None => default_prefix_len(&reply.your_addr),
+        };
+        let server = match (reply.server_id, destination) {
+            (Some(server_id), _) => server_id,
+            (None, Destination::Server(server, _)) => *server,
+            (None, Destination::Broadcast) => return None,
+        };
+
+        let lease = DhcpLease {
+            cidr: Ipv4Cidr::new(reply.your_addr, prefix_len),
+            gateway: reply.router,
+            dns_servers: reply.dns_servers,
+            domain_name: reply.domain_name,
+            server,
+        };
+
+        Some(Some(Binding {
+            lease,
+            server_ether,
+            start_time,
+            lease_time,
+            renewal_time: renewal_time.min(lease_time),
+            rebinding_time: rebinding_time.clamp(renewal_time.min(lease_time), lease_time),
+        }))
+    }
+
+    /// Sends `request` and waits for a reply that is accepted by `is_wanted`.
+    ///
+    /// This method returns the reply and the Ethernet address of its sender, or `None` if no such
+    /// reply is received before `deadline`.
+    fn transact<F>(
+        &self,
+        request: &ClientMessage,
+        destination: &Destination,
+        deadline: Duration,
+        mut is_wanted: F,
+    ) -> Option<(ServerMessage, EthernetAddress)>
+    where
+        F: FnMut(&ServerMessage) -> bool,
+    {
+        self.tap.replies.lock().clear();
+
+        let (dst_addr, dst_ether) = match destination {
+            Destination::Broadcast => (Ipv4Address::BROADCAST, EthernetAddress::BROADCAST),
+            Destination::Server(addr, ether) => (*addr, *ether),
+        };
+        let addrs = UdpFrameAddrs {
+            src_ether: self.hw_addr,
+            dst_ether,
+            src_addr: request.client_addr,
+            dst_addr,
+            src_port: CLIENT_PORT,
+            dst_port: SERVER_PORT,
+        };
+        let frame = build_udp_frame(&addrs, &request.emit());
+        if let Err(err) = self.iface.send_frame(&frame) {
+            debug!("DHCP: failed to send the request: {:?}", err);
+        }
+
+        loop {
+            let (reply, server_ether) = self
+                .tap
+                .wait_queue
+                .wait_until_or_timeout(|| self.tap.replies.lock().pop_front(), &until(deadline))
+                .ok()?;
+
+            if reply.xid == request.xid && is_wanted(&reply) {
+                return Some((reply, server_ether));
+            }
+        }
+    }
+
+    fn new_message(
+        &self,
+        type_: MessageType,
+        xid: u32,
+        requested_addr: Option<Ipv4Address>,
+        server_id: Option<Ipv4Address>,
+    ) -> ClientMessage {
+        ClientMessage {
+            type_,
+            xid,
+            hw_addr: self.hw_addr,
+            client_addr: Ipv4Address::UNSPECIFIED,
+            requested_addr,
+            server_id,
+        }
+    }
+
+    /// Configures the iface with the lease.
+    fn apply(&self, binding: &Binding) {
+        let lease = &binding.lease;
+        info!(
+            "DHCP: `{}` is configured with {} (gateway {:?}, lease time {}s)",
+            self.iface.name(),
+            lease.cidr,
+            lease.gateway,
+            binding.lease_time
+        );
+
+        update_iface_addr(&self.iface, Some(lease.cidr));
+        self.replace_default_route(lease.gateway);
+
+        *LEASE.write() = Some(lease.clone());
+    }
+
+    /// Removes the configuration of the iface after the lease is lost.
+    fn unapply(&self) {
+        *LEASE.write() = None;
+
+        // This also removes the default route.
+        update_iface_addr(&self.iface, None);
+    }
+
+    fn replace_default_route(&self, gateway: Option<Ipv4Address>) {
+        let mut routing_table = routing_table().write();
+
+        while let Some(index) = routing_table.iter().position(|route| {
+            Arc::ptr_eq(&route.iface, &self.iface)
+                && route.dst.prefix_len() == 0
+                && route.protocol == Route::PROTOCOL_BOOT
+        }) {
+            routing_table.remove(index);
+        }
+
+        let Some(gateway) = gateway else {
+            return;
+        };
+        let default_route = Route {
+            dst: Ipv4Cidr::new(Ipv4Address::UNSPECIFIED, 0),
+            gateway: Some(gateway),
+            iface: self.iface.clone(),
+            pref_src: None,
+            metric: 0,
+            table: Route::MAIN_TABLE,
+            type_: RouteType::Unicast,
+            scope: Route::SCOPE_UNIVERSE,
+            protocol: Route::PROTOCOL_BOOT,
+        };
+        routing_table.insert(default_route, None);
+    }
+}
+
+/// A packet tap that receives the replies from the DHCP servers.
+struct DhcpTap {
+    hw_addr: EthernetAddress,
+    replies: SpinLock<VecDeque<(ServerMessage, EthernetAddress)>, BottomHalfDisabled>,
+    wait_queue: WaitQueue,
+}
+
+/// The maximum number of replies that are not yet handled by the client.
+const MAX_PENDING_REPLIES: usize = 8;
+
+impl DhcpTap {
+    fn new(hw_addr: EthernetAddress) -> Self {
+        Self {
+            hw_addr,
+            replies: SpinLock::new(VecDeque::new()),
+            wait_queue: WaitQueue::new(),
+        }
+    }
+}
+
+impl PacketTap for DhcpTap {
+    fn capture(&self, _iface_index: u32, frame: &[u8], pkt_type: PacketType) {
+        if pkt_type == PacketType::Outgoing {
+            return;
+        }
+
+        let Some((addrs, payload)) = parse_udp_frame(frame) else {
+            return;
+        };
+        if addrs.src_port != SERVER_PORT || addrs.dst_port != CLIENT_PORT {
+            return;
+        }
+        let Some(reply) = ServerMessage::parse(payload, &self.hw_addr) else {
+            return;
+        };
+
+        let mut replies = self.replies.lock();
+        if replies.len() >= MAX_PENDING_REPLIES {
+            return;
+        }
+        replies.push_back((reply, addrs.src_ether));
+        drop(replies);
+
+        self.wait_queue.wake_all();
+    }
+}
+
+fn new_xid() -> u32 {
+    let mut xid = [0u8; 4];
+    getrandom(&mut xid).unwrap();
+    u32::from_ne_bytes(xid)
+}
+
+/// Returns the prefix length deduced from the class of the address.
+fn default_prefix_len(addr: &Ipv4Address) -> u8 {
+    match addr.octets()[0].leading_ones() {
+        0 => 8,
+        1 => 16,
+        _ => 24,
+    }
+}
+
+fn now() -> Duration {
+    Jiffies::elapsed().as_duration()
+}
+
+/// Returns the duration from now until `time`.
+fn until(time: Duration) -> Duration {
+    time.saturating_sub(now())
+}
+
+fn secs(secs: u32) -> Duration {
+    Duration::from_secs(secs as u64)
+}
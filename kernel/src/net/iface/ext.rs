@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use aster_bigtcp::iface::PacketTap;
+
 use super::sched::PollScheduler;
 use crate::net::socket::ip::{DatagramObserver, StreamObserver};
 
@@ -10,4 +12,7 @@ impl aster_bigtcp::ext::Ext for BigtcpExt {
 
     type TcpEventObserver = StreamObserver;
     type UdpEventObserver = DatagramObserver;
+    type RawEventObserver = DatagramObserver;
+
+    type PacketTap = dyn PacketTap;
 }
@@ -1,88 +1,201 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{borrow::ToOwned, sync::Arc};
-use core::slice::Iter;
+use alloc::{borrow::ToOwned, collections::btree_map::BTreeMap, format, sync::Arc};
 
 use aster_bigtcp::{
     device::WithDevice,
     iface::{InterfaceFlags, InterfaceType},
+    wire::{Ipv4Address, Ipv4Cidr},
 };
+use aster_network::AnyNetworkDevice;
 use aster_softirq::BottomHalfDisabled;
+use ostd::sync::{PreemptDisabled, RwLockReadGuard};
 use spin::Once;
 
-use super::{poll::poll_ifaces, Iface};
-use crate::{net::iface::sched::PollScheduler, prelude::*};
+use super::{
+    dhcp,
+    poll::{poll_ifaces, spawn_background_poll_thread},
+    route::{self, routing_table, Route, RouteType},
+    Iface,
+};
+use crate::{
+    net::{
+        iface::sched::PollScheduler,
+        socket::netlink::{notify_link_added, update_iface_addr},
+    },
+    prelude::*,
+};
+
+/// All the ifaces, with the loopback iface at the front.
+static IFACES: RwLock<Vec<Arc<Iface>>> = RwLock::new(Vec::new());
 
-static IFACES: Once<Vec<Arc<Iface>>> = Once::new();
+static LOOPBACK_IFACE: Once<Arc<Iface>> = Once::new();
+
+/// The state of the ifaces created for network devices.
+///
+/// The lock also serializes the addition of ifaces.
+static DEVICE_IFACES: Mutex<DeviceIfaces> = Mutex::new(DeviceIfaces {
+    ifaces: BTreeMap::new(),
+    is_polling_started: false,
+});
+
+struct DeviceIfaces {
+    /// The ifaces, keyed by the names of the network devices.
+    ifaces: BTreeMap<String, Arc<Iface>>,
+    /// Whether the background polling threads have been started.
+    is_polling_started: bool,
+}
 
 pub fn loopback_iface() -> &'static Arc<Iface> {
-    &IFACES.get().unwrap()[0]
+    LOOPBACK_IFACE.get().unwrap()
 }
 
-pub fn virtio_iface() -> Option<&'static Arc<Iface>> {
-    IFACES.get().unwrap().get(1)
+/// Returns all the ifaces, with the loopback iface at the front.
+///
+/// No ifaces can be added while the returned guard is held, so the caller must not sleep (e.g.,
+/// by accessing the user space) before dropping it.
+pub fn all_ifaces() -> RwLockReadGuard<'static, Vec<Arc<Iface>>, PreemptDisabled> {
+    IFACES.read()
 }
 
-pub fn iter_all_ifaces() -> Iter<'static, Arc<Iface>> {
-    IFACES.get().unwrap().iter()
+/// Returns the first iface that satisfies `predicate`.
+pub fn find_iface(mut predicate: impl FnMut(&Arc<Iface>) -> bool) -> Option<Arc<Iface>> {
+    IFACES.read().iter().find(|iface| predicate(iface)).cloned()
 }
 
 pub fn init() {
-    IFACES.call_once(|| {
-        let mut ifaces = Vec::with_capacity(2);
+    let loopback = LOOPBACK_IFACE.call_once(new_loopback);
+    IFACES.write().push(loopback.clone());
+    route::init(core::slice::from_ref(loopback));
 
-        // Initialize loopback before virtio
-        // to ensure the loopback interface index is ahead of virtio.
-        ifaces.push(new_loopback());
-
-        if let Some(iface_virtio) = new_virtio() {
-            ifaces.push(iface_virtio);
-        }
-
-        ifaces
+    aster_network::register_device_observer(|name| {
+        add_device_iface(name, AddrConfig::Unconfigured)
     });
 
-    if let Some(iface_virtio) = virtio_iface() {
-        for (name, _) in aster_network::all_devices() {
-            // TODO: further check that the irq num is the same as iface's irq num
-            let callback = || iface_virtio.poll();
-            aster_network::register_recv_callback(&name, callback);
-            aster_network::register_send_callback(&name, callback);
-        }
+    let primary_config = if dhcp::is_enabled() {
+        AddrConfig::Dhcp
+    } else {
+        AddrConfig::Static
+    };
+
+    let mut devices = aster_network::all_devices();
+    // Sort the devices so that `Virtio-Net2` comes before `Virtio-Net10`.
+    devices.sort_by(|(name1, _), (name2, _)| (name1.len(), name1).cmp(&(name2.len(), name2)));
+    for (index, (name, _)) in devices.iter().enumerate() {
+        // The first device is configured to work with the QEMU user network.
+        let config = if index == 0 {
+            primary_config
+        } else {
+            AddrConfig::Unconfigured
+        };
+        add_device_iface(name, config);
     }
 
     poll_ifaces();
 }
 
-fn new_virtio() -> Option<Arc<Iface>> {
-    use aster_bigtcp::{
-        iface::EtherIface,
-        wire::{EthernetAddress, Ipv4Address, Ipv4Cidr},
+/// Marks that the background polling threads should be started, and returns the ifaces that
+/// need them.
+///
+/// Ifaces added after this call will have their threads started automatically.
+pub(super) fn start_background_polling() -> Vec<Arc<Iface>> {
+    let mut device_ifaces = DEVICE_IFACES.lock();
+    device_ifaces.is_polling_started = true;
+    IFACES.read().clone()
+}
+
+/// How the address of an iface is configured.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum AddrConfig {
+    /// The iface is left unconfigured.
+    Unconfigured,
+    /// The iface is configured statically with the primary address and the default route.
+    Static,
+    /// The iface is configured by the DHCP client.
+    Dhcp,
+}
+
+/// Creates an iface for the network device `name`.
+fn add_device_iface(name: &str, config: AddrConfig) {
+    let mut device_ifaces = DEVICE_IFACES.lock();
+    if device_ifaces.ifaces.contains_key(name) {
+        return;
+    }
+
+    let Some(device) = aster_network::get_device(name) else {
+        return;
     };
-    use aster_network::AnyNetworkDevice;
-    use aster_virtio::device::network::DEVICE_NAME;
 
-    const VIRTIO_ADDRESS: Ipv4Address = Ipv4Address::new(10, 0, 2, 15);
-    const VIRTIO_ADDRESS_PREFIX_LEN: u8 = 24; // mask: 255.255.255.0
-    const VIRTIO_GATEWAY: Ipv4Address = Ipv4Address::new(10, 0, 2, 2);
+    let iface_name = {
+        let ifaces = IFACES.read();
+        (0..)
+            .map(|index| format!("eth{}", index))
+            .find(|iface_name| ifaces.iter().all(|iface| iface.name() != iface_name))
+            .unwrap()
+    };
+    let ip_cidr = (config == AddrConfig::Static)
+        .then(|| Ipv4Cidr::new(PRIMARY_ADDRESS, PRIMARY_ADDRESS_PREFIX_LEN));
+    let iface = new_ether(device, iface_name, ip_cidr);
 
-    let virtio_net = aster_network::get_device(DEVICE_NAME)?;
+    // The callbacks live as long as the device, so they must not keep the iface alive.
+    let weak_iface = Arc::downgrade(&iface);
+    let callback = move || {
+        if let Some(iface) = weak_iface.upgrade() {
+            iface.poll();
+        }
+    };
+    aster_network::register_recv_callback(name, callback.clone());
+    aster_network::register_send_callback(name, callback);
 
-    let ether_addr = virtio_net.lock().mac_addr().0;
+    IFACES.write().push(iface.clone());
 
-    struct Wrapper(Arc<SpinLock<dyn AnyNetworkDevice, BottomHalfDisabled>>);
+    if let Some(route) = Route::new_connected(&iface) {
+        routing_table().write().insert(route, None);
+    }
+    if config == AddrConfig::Static {
+        add_primary_default_route(&iface);
+    }
 
-    impl WithDevice for Wrapper {
-        type Device = dyn AnyNetworkDevice;
+    if device_ifaces.is_polling_started {
+        spawn_background_poll_thread(iface.clone());
+    }
+    device_ifaces.ifaces.insert(name.to_owned(), iface.clone());
+    drop(device_ifaces);
 
-        fn with<F, R>(&self, f: F) -> R
-        where
-            F: FnOnce(&mut Self::Device) -> R,
-        {
-            let mut device = self.0.lock();
-            f(&mut *device)
-        }
+    iface.poll();
+    notify_link_added(&iface);
+
+    if config == AddrConfig::Dhcp {
+        dhcp::init(iface);
     }
+}
+
+const PRIMARY_ADDRESS: Ipv4Address = Ipv4Address::new(10, 0, 2, 15);
+const PRIMARY_ADDRESS_PREFIX_LEN: u8 = 24; // mask: 255.255.255.0
+const PRIMARY_GATEWAY: Ipv4Address = Ipv4Address::new(10, 0, 2, 2);
+
+struct DeviceWrapper(Arc<SpinLock<dyn AnyNetworkDevice, BottomHalfDisabled>>);
+
+impl WithDevice for DeviceWrapper {
+    type Device = dyn AnyNetworkDevice;
+
+    fn with<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Self::Device) -> R,
+    {
+        let mut device = self.0.lock();
+        f(&mut *device)
+    }
+}
+
+fn new_ether(
+    device: Arc<SpinLock<dyn AnyNetworkDevice, BottomHalfDisabled>>,
+    name: String,
+    ip_cidr: Option<Ipv4Cidr>,
+) -> Arc<Iface> {
+    use aster_bigtcp::{iface::EtherIface, wire::EthernetAddress};
+
+    let ether_addr = device.lock().mac_addr().0;
 
     // FIXME: These flags are currently hardcoded.
     // In the future, we should set appropriate values.
@@ -92,22 +205,44 @@ fn new_virtio() -> Option<Arc<Iface>> {
         | InterfaceFlags::MULTICAST
         | InterfaceFlags::LOWER_UP;
 
-    Some(EtherIface::new(
-        Wrapper(virtio_net),
+    EtherIface::new(
+        DeviceWrapper(device),
         EthernetAddress(ether_addr),
-        Ipv4Cidr::new(VIRTIO_ADDRESS, VIRTIO_ADDRESS_PREFIX_LEN),
-        VIRTIO_GATEWAY,
-        "eth0".to_owned(),
+        ip_cidr,
+        name,
         PollScheduler::new(),
         flags,
-    ))
+    )
+}
+
+/// Configures `iface` statically as the primary iface.
+///
+/// This is used if the DHCP client fails to obtain a lease at boot time.
+pub(super) fn configure_primary_statically(iface: &Arc<Iface>) {
+    let ip_cidr = Ipv4Cidr::new(PRIMARY_ADDRESS, PRIMARY_ADDRESS_PREFIX_LEN);
+    update_iface_addr(iface, Some(ip_cidr));
+    add_primary_default_route(iface);
+}
+
+fn add_primary_default_route(iface: &Arc<Iface>) {
+    let default_route = Route {
+        dst: Ipv4Cidr::new(Ipv4Address::UNSPECIFIED, 0),
+        gateway: Some(PRIMARY_GATEWAY),
+        iface: iface.clone(),
+        pref_src: None,
+        metric: 0,
+        table: Route::MAIN_TABLE,
+        type_: RouteType::Unicast,
+        scope: Route::SCOPE_UNIVERSE,
+        protocol: Route::PROTOCOL_BOOT,
+    };
+    routing_table().write().insert(default_route, None);
 }
 
 fn new_loopback() -> Arc<Iface> {
     use aster_bigtcp::{
         device::{Loopback, Medium},
         iface::IpIface,
-        wire::{Ipv4Address, Ipv4Cidr},
     };
 
     const LOOPBACK_ADDRESS: Ipv4Address = Ipv4Address::new(127, 0, 0, 1);
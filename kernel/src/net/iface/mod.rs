@@ -1,12 +1,16 @@
 // SPDX-License-Identifier: MPL-2.0
 
+mod dhcp;
 mod ext;
 mod init;
 mod poll;
+mod route;
 mod sched;
 
-pub use init::{init, iter_all_ifaces, loopback_iface, virtio_iface};
+pub use dhcp::{current_lease, DhcpLease};
+pub use init::{all_ifaces, find_iface, init, loopback_iface};
 pub use poll::lazy_init;
+pub use route::{routing_table, Route, RouteType, RoutingTable};
 
 pub type Iface = dyn aster_bigtcp::iface::Iface<ext::BigtcpExt>;
 pub type BoundPort = aster_bigtcp::iface::BoundPort<ext::BigtcpExt>;
@@ -16,3 +20,4 @@ pub type RawTcpSocketExt = aster_bigtcp::socket::RawTcpSocketExt<ext::BigtcpExt>
 pub type TcpConnection = aster_bigtcp::socket::TcpConnection<ext::BigtcpExt>;
 pub type TcpListener = aster_bigtcp::socket::TcpListener<ext::BigtcpExt>;
 pub type UdpSocket = aster_bigtcp::socket::UdpSocket<ext::BigtcpExt>;
+pub type RawIpSocket = aster_bigtcp::socket::RawIpSocket<ext::BigtcpExt>;
@@ -6,7 +6,7 @@ use core::time::Duration;
 use log::trace;
 use ostd::timer::Jiffies;
 
-use super::{iter_all_ifaces, Iface};
+use super::{all_ifaces, dhcp, init::start_background_polling, Iface};
 use crate::{
     sched::{Nice, SchedPolicy},
     thread::kernel_thread::ThreadOptions,
@@ -14,18 +14,22 @@ use crate::{
 };
 
 pub fn lazy_init() {
-    for iface in iter_all_ifaces() {
-        spawn_background_poll_thread(iface.clone());
+    for iface in start_background_polling() {
+        spawn_background_poll_thread(iface);
     }
+
+    // Make sure that the network is configured before the init process starts.
+    dhcp::lazy_init();
 }
 
 pub(super) fn poll_ifaces() {
-    for iface in iter_all_ifaces() {
+    for iface in all_ifaces().iter() {
         iface.poll();
     }
 }
 
-fn spawn_background_poll_thread(iface: Arc<Iface>) {
+/// Spawns a thread that polls `iface` in the background.
+pub(super) fn spawn_background_poll_thread(iface: Arc<Iface>) {
     let task_fn = move || {
         trace!("spawn background poll thread for {}", iface.name());
 
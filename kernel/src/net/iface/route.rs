@@ -0,0 +1,224 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The IPv4 routing table.
+//!
+//! The routing table decides which iface (and which next hop) should be used to reach a
+//! destination. Routes can be inspected and modified from user space via netlink route sockets.
+
+use aster_bigtcp::{
+    iface::InterfaceType,
+    wire::{Ipv4Address, Ipv4Cidr},
+};
+use spin::Once;
+
+use super::Iface;
+use crate::prelude::*;
+
+/// An IPv4 route.
+#[derive(Clone)]
+pub struct Route {
+    /// The destination network.
+    pub dst: Ipv4Cidr,
+    /// The next hop, or `None` if the destination is directly reachable.
+    pub gateway: Option<Ipv4Address>,
+    /// The output iface.
+    pub iface: Arc<Iface>,
+    /// The preferred source address.
+    pub pref_src: Option<Ipv4Address>,
+    /// The priority of the route. Lower values are preferred.
+    pub metric: u32,
+    /// The table ID (e.g., [`Route::MAIN_TABLE`]).
+    pub table: u32,
+    pub type_: RouteType,
+    /// The scope of the destination (`rt_scope_t` in Linux).
+    pub scope: u8,
+    /// The origin of the route (`RTPROT_*` in Linux).
+    ///
+    /// The kernel does not interpret this value. It is only reported back to user space.
+    pub protocol: u8,
+}
+
+/// The type of a route.
+///
+/// The values are the same as `RTN_*` in Linux.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+pub enum RouteType {
+    /// A gateway or direct route
+    Unicast = 1,
+    /// A local interface route
+    Local = 2,
+    // TODO: Support other route types, such as blackhole and unreachable routes.
+}
+
+impl Route {
+    /// The table for ordinary routes (`RT_TABLE_MAIN` in Linux).
+    pub const MAIN_TABLE: u32 = 254;
+    /// The table for local and broadcast addresses (`RT_TABLE_LOCAL` in Linux).
+    pub const LOCAL_TABLE: u32 = 255;
+
+    /// Route installed by the kernel (`RTPROT_KERNEL` in Linux).
+    pub const PROTOCOL_KERNEL: u8 = 2;
+    /// Route installed during boot (`RTPROT_BOOT` in Linux).
+    pub const PROTOCOL_BOOT: u8 = 3;
+
+    /// The destination is anywhere (`RT_SCOPE_UNIVERSE` in Linux).
+    pub const SCOPE_UNIVERSE: u8 = 0;
+    /// The destination is on the attached link (`RT_SCOPE_LINK` in Linux).
+    pub const SCOPE_LINK: u8 = 253;
+    /// The destination is on the local host (`RT_SCOPE_HOST` in Linux).
+    pub const SCOPE_HOST: u8 = 254;
+
+    /// Creates the route to the subnet that is directly attached to `iface`.
+    ///
+    /// This method returns `None` if `iface` has no IPv4 address.
+    pub fn new_connected(iface: &Arc<Iface>) -> Option<Self> {
+        let addr = iface.ipv4_addr()?;
+        let prefix_len = iface.prefix_len().unwrap();
+        let dst = Ipv4Cidr::new(addr, prefix_len).network();
+
+        let route = if iface.type_() == InterfaceType::LOOPBACK {
+            Self {
+                dst,
+                gateway: None,
+                iface: iface.clone(),
+                pref_src: Some(addr),
+                metric: 0,
+                table: Self::LOCAL_TABLE,
+                type_: RouteType::Local,
+                scope: Self::SCOPE_HOST,
+                protocol: Self::PROTOCOL_KERNEL,
+            }
+        } else {
+            Self {
+                dst,
+                gateway: None,
+                iface: iface.clone(),
+                pref_src: Some(addr),
+                metric: 0,
+                table: Self::MAIN_TABLE,
+                type_: RouteType::Unicast,
+                scope: Self::SCOPE_LINK,
+                protocol: Self::PROTOCOL_KERNEL,
+            }
+        };
+
+        Some(route)
+    }
+
+    /// Returns the source address that should be used to reach the destination.
+    pub fn src_addr(&self) -> Option<Ipv4Address> {
+        self.pref_src.or_else(|| self.iface.ipv4_addr())
+    }
+
+    /// Returns whether this route is the same as `other` in terms of the routing key.
+    ///
+    /// Linux does not allow two routes with the same key to coexist in a table unless
+    /// `NLM_F_APPEND` is specified.
+    pub fn has_same_key(&self, other: &Route) -> bool {
+        self.table == other.table && self.dst == other.dst && self.metric == other.metric
+    }
+}
+
+/// The routing table.
+///
+/// FIXME: The routing table should be a per-network namespace object.
+pub struct RoutingTable {
+    routes: Vec<Route>,
+}
+
+impl RoutingTable {
+    fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Returns an iterator over all the routes.
+    pub fn iter(&self) -> impl Iterator<Item = &Route> {
+        self.routes.iter()
+    }
+
+    /// Finds the best route to `dst`.
+    ///
+    /// Routes in the local table take precedence. Then the longest prefix match wins, and the
+    /// route with the lowest metric is preferred if the prefix lengths are the same.
+    pub fn lookup(&self, dst: Ipv4Address) -> Option<&Route> {
+        self.routes
+            .iter()
+            .filter(|route| route.dst.contains_addr(&dst))
+            .min_by_key(|route| {
+                (
+                    route.table != Route::LOCAL_TABLE,
+                    u8::MAX - route.dst.prefix_len(),
+                    route.metric,
+                )
+            })
+    }
+
+    /// Inserts a new route.
+    ///
+    /// If `index` is `Some(_)`, the route at that position is replaced.
+    pub fn insert(&mut self, route: Route, index: Option<usize>) {
+        if let Some(index) = index {
+            Self::unsync(&self.routes[index]);
+        }
+
+        if let Some(gateway) = route.gateway {
+            if route.iface.add_ipv4_route(route.dst, gateway).is_err() {
+                warn!(
+                    "the route table of `{}` is full, packets may be routed incorrectly",
+                    route.iface.name()
+                );
+            }
+        }
+
+        if let Some(index) = index {
+            self.routes[index] = route;
+        } else {
+            self.routes.push(route);
+        }
+    }
+
+    /// Removes the route at `index`.
+    pub fn remove(&mut self, index: usize) -> Route {
+        let route = self.routes.remove(index);
+        Self::unsync(&route);
+        route
+    }
+
+    /// Removes all the routes whose output iface is `iface`.
+    ///
+    /// This should be called when `iface` is down or loses its address.
+    pub fn remove_iface_routes(&mut self, iface: &Arc<Iface>) {
+        for route in self
+            .routes
+            .extract_if(.., |route| Arc::ptr_eq(&route.iface, iface))
+        {
+            Self::unsync(&route);
+        }
+    }
+
+    fn unsync(route: &Route) {
+        if let Some(gateway) = route.gateway {
+            route.iface.remove_ipv4_route(route.dst, gateway);
+        }
+    }
+}
+
+static ROUTING_TABLE: Once<RwLock<RoutingTable>> = Once::new();
+
+/// Returns the routing table.
+pub fn routing_table() -> &'static RwLock<RoutingTable> {
+    ROUTING_TABLE.get().unwrap()
+}
+
+pub(super) fn init(ifaces: &[Arc<Iface>]) {
+    let mut table = RoutingTable::new();
+
+    for iface in ifaces.iter() {
+        if let Some(route) = Route::new_connected(iface) {
+            table.insert(route, None);
+        }
+    }
+
+    ROUTING_TABLE.call_once(|| RwLock::new(table));
+}
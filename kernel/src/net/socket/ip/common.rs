@@ -3,49 +3,54 @@
 use aster_bigtcp::{
     errors::BindError,
     iface::BindPortConfig,
-    wire::{IpAddress, IpEndpoint},
+    wire::{IpAddress, IpEndpoint, Ipv4Address},
 };
 
 use crate::{
-    net::iface::{iter_all_ifaces, loopback_iface, virtio_iface, BoundPort, Iface},
+    net::iface::{find_iface, routing_table, BoundPort, Iface},
     prelude::*,
 };
 
 pub(super) fn get_iface_to_bind(ip_addr: &IpAddress) -> Option<Arc<Iface>> {
     let IpAddress::Ipv4(ipv4_addr) = ip_addr;
-    iter_all_ifaces()
-        .find(|iface| {
-            if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
-                iface_ipv4_addr == *ipv4_addr
-            } else {
-                false
-            }
-        })
-        .map(Clone::clone)
+    find_iface(|iface| {
+        if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
+            iface_ipv4_addr == *ipv4_addr
+        } else {
+            false
+        }
+    })
 }
 
 /// Get a suitable iface to deal with sendto/connect request if the socket is not bound to an iface.
 /// If the remote address is the same as that of some iface, we will use the iface.
-/// Otherwise, we will use a default interface.
-fn get_ephemeral_iface(remote_ip_addr: &IpAddress) -> Arc<Iface> {
+/// Otherwise, we will choose the iface according to the routing table.
+///
+/// This method returns the iface and the source address that should be used.
+fn get_ephemeral_iface(remote_ip_addr: &IpAddress) -> Result<(Arc<Iface>, Ipv4Address)> {
     let IpAddress::Ipv4(remote_ipv4_addr) = remote_ip_addr;
-    if let Some(iface) = iter_all_ifaces().find(|iface| {
+    if let Some(iface) = find_iface(|iface| {
         if let Some(iface_ipv4_addr) = iface.ipv4_addr() {
             iface_ipv4_addr == *remote_ipv4_addr
         } else {
             false
         }
     }) {
-        return iface.clone();
+        return Ok((iface, *remote_ipv4_addr));
     }
 
-    // FIXME: Instead of hardcoding the rules here, we should choose the
-    // default interface according to the routing table.
-    if let Some(virtio_iface) = virtio_iface() {
-        virtio_iface.clone()
-    } else {
-        loopback_iface().clone()
-    }
+    let routing_table = routing_table().read();
+    let Some(route) = routing_table.lookup(*remote_ipv4_addr) else {
+        return_errno_with_message!(Errno::ENETUNREACH, "no route to the remote address");
+    };
+    let Some(src_addr) = route.src_addr() else {
+        return_errno_with_message!(
+            Errno::EADDRNOTAVAIL,
+            "the iface to the remote address has no address"
+        );
+    };
+
+    Ok((route.iface.clone(), src_addr))
 }
 
 pub(super) fn bind_port(endpoint: &IpEndpoint, can_reuse: bool) -> Result<BoundPort> {
@@ -77,8 +82,7 @@ impl From<BindError> for Error {
     }
 }
 
-pub(super) fn get_ephemeral_endpoint(remote_endpoint: &IpEndpoint) -> IpEndpoint {
-    let iface = get_ephemeral_iface(&remote_endpoint.addr);
-    let ip_addr = iface.ipv4_addr().unwrap();
-    IpEndpoint::new(IpAddress::Ipv4(ip_addr), 0)
+pub(super) fn get_ephemeral_endpoint(remote_endpoint: &IpEndpoint) -> Result<IpEndpoint> {
+    let (_, ip_addr) = get_ephemeral_iface(&remote_endpoint.addr)?;
+    Ok(IpEndpoint::new(IpAddress::Ipv4(ip_addr), 0))
 }
@@ -31,6 +31,16 @@ impl BoundDatagram {
     pub(super) fn iface(&self) -> &Arc<Iface> {
         self.bound_socket.iface()
     }
+
+    /// Returns the length of the next datagram to be received.
+    pub(super) fn next_recv_len(&self) -> usize {
+        self.bound_socket.peek_len().unwrap_or(0)
+    }
+
+    /// Returns the number of bytes that are waiting to be sent.
+    pub(super) fn send_queue_len(&self) -> usize {
+        self.bound_socket.raw_with(|socket| socket.send_queue())
+    }
 }
 
 impl datagram_common::Bound for BoundDatagram {
@@ -8,7 +8,9 @@ use unbound::{BindOptions, UnboundDatagram};
 
 use super::addr::UNSPECIFIED_LOCAL_ENDPOINT;
 use crate::{
+    current_userspace,
     events::IoEvents,
+    fs::utils::IoctlCmd,
     match_sock_option_mut,
     net::socket::{
         options::{Error as SocketError, SocketOption},
@@ -176,7 +178,8 @@ impl Socket for DatagramSocket {
 
         let MessageHeader {
             addr,
-            control_message,
+            control_messages,
+            ..
         } = message_header;
 
         let endpoint = match addr {
@@ -184,7 +187,7 @@ impl Socket for DatagramSocket {
             None => None,
         };
 
-        if control_message.is_some() {
+        if !control_messages.is_empty() {
             // TODO: Support sending control message
             warn!("sending control message is not supported");
         }
@@ -208,7 +211,7 @@ impl Socket for DatagramSocket {
 
         // TODO: Receive control message
 
-        let message_header = MessageHeader::new(Some(peer_addr), None);
+        let message_header = MessageHeader::new(Some(peer_addr), Vec::new());
 
         Ok((received_bytes, message_header))
     }
@@ -251,6 +254,21 @@ impl Socket for DatagramSocket {
             }
         }
     }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: Vaddr) -> Result<i32> {
+        let inner = self.inner.read();
+
+        let len = match (cmd, &*inner) {
+            (IoctlCmd::FIONREAD | IoctlCmd::TIOCOUTQ, Inner::Unbound(_)) => 0,
+            (IoctlCmd::FIONREAD, Inner::Bound(bound_datagram)) => bound_datagram.next_recv_len(),
+            (IoctlCmd::TIOCOUTQ, Inner::Bound(bound_datagram)) => bound_datagram.send_queue_len(),
+            _ => return_errno_with_message!(Errno::EINVAL, "the ioctl command is not supported"),
+        };
+        drop(inner);
+
+        current_userspace!().write_val(arg, &(len as i32))?;
+        Ok(0)
+    }
 }
 
 impl SetSocketLevelOption for Inner<UnboundDatagram, BoundDatagram> {}
@@ -4,9 +4,11 @@ mod addr;
 mod common;
 mod datagram;
 pub mod options;
+mod raw;
 mod stream;
 
 pub(in crate::net) use datagram::observer::DatagramObserver;
 pub use datagram::DatagramSocket;
+pub use raw::{options as raw_options, ping_group_range, set_ping_group_range, RawSocket};
 pub(in crate::net) use stream::observer::StreamObserver;
 pub use stream::{options as stream_options, StreamSocket};
@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::{
+    errors::raw::{RecvError, SendError},
+    wire::{IpAddress, IpEndpoint},
+};
+
+use super::OptionSet;
+use crate::{
+    events::IoEvents,
+    net::{
+        iface::{Iface, RawIpSocket},
+        socket::util::{datagram_common, SendRecvFlags},
+    },
+    prelude::*,
+    util::{MultiRead, MultiWrite},
+};
+
+pub(super) struct BoundRaw {
+    bound_socket: RawIpSocket,
+    remote_endpoint: Option<IpEndpoint>,
+    options: Arc<RwLock<OptionSet>>,
+}
+
+impl BoundRaw {
+    pub(super) fn new(bound_socket: RawIpSocket, options: Arc<RwLock<OptionSet>>) -> Self {
+        Self {
+            bound_socket,
+            remote_endpoint: None,
+            options,
+        }
+    }
+
+    pub(super) fn iface(&self) -> &Arc<Iface> {
+        self.bound_socket.iface()
+    }
+
+    pub(super) fn set_icmp_filter(&self, filter: u32) {
+        self.bound_socket.set_icmp_filter(filter);
+    }
+}
+
+impl datagram_common::Bound for BoundRaw {
+    type Endpoint = IpEndpoint;
+
+    fn local_endpoint(&self) -> Self::Endpoint {
+        // For raw sockets, the port is always zero. For ping sockets, the port is the
+        // identifier of ICMP echo messages.
+        self.bound_socket.local_endpoint().unwrap()
+    }
+
+    fn remote_endpoint(&self) -> Option<&Self::Endpoint> {
+        self.remote_endpoint.as_ref()
+    }
+
+    fn set_remote_endpoint(&mut self, endpoint: &Self::Endpoint) {
+        self.remote_endpoint = Some(*endpoint)
+    }
+
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        _flags: SendRecvFlags,
+    ) -> Result<(usize, Self::Endpoint)> {
+        let result = self.bound_socket.recv(|packet, src_addr| {
+            let copied_res = writer.write(&mut VmReader::from(packet));
+            (copied_res, src_addr)
+        });
+
+        match result {
+            Ok((Ok(res), src_addr)) => Ok((res, IpEndpoint::new(IpAddress::Ipv4(src_addr), 0))),
+            Ok((Err(e), _)) => Err(e),
+            Err(RecvError::Exhausted) => {
+                return_errno_with_message!(Errno::EAGAIN, "the receive buffer is empty")
+            }
+        }
+    }
+
+    fn try_send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote: &Self::Endpoint,
+        _flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let mut buffer = vec![0u8; reader.sum_lens()];
+        let len = reader.read(&mut VmWriter::from(buffer.as_mut_slice()))?;
+        buffer.truncate(len);
+
+        let ip_options = self.options.read().ip;
+
+        let IpAddress::Ipv4(dst_addr) = remote.addr;
+        let result = if ip_options.hdrincl() {
+            self.bound_socket.send_hdrincl(&buffer)
+        } else {
+            self.bound_socket
+                .send(dst_addr, ip_options.ttl().get(), &buffer)
+        };
+
+        match result {
+            Ok(()) => Ok(len),
+            Err(SendError::TooLarge) => {
+                return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+            }
+            Err(SendError::Unaddressable) => {
+                return_errno_with_message!(
+                    Errno::EADDRNOTAVAIL,
+                    "the iface to send the packet has no address"
+                );
+            }
+            Err(SendError::BufferFull) => {
+                return_errno_with_message!(Errno::EAGAIN, "the send buffer is full");
+            }
+            Err(SendError::InvalidPacket) => {
+                return_errno_with_message!(Errno::EINVAL, "the packet is invalid");
+            }
+        }
+    }
+
+    fn check_io_events(&self) -> IoEvents {
+        let mut events = IoEvents::empty();
+
+        if self.bound_socket.can_recv() {
+            events |= IoEvents::IN;
+        }
+
+        if self.bound_socket.can_send() {
+            events |= IoEvents::OUT;
+        }
+
+        events
+    }
+}
@@ -0,0 +1,404 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use aster_bigtcp::{
+    socket::{NeedIfacePoll, RawIpSocketKind},
+    wire::{IpEndpoint, IpProtocol},
+};
+use bound::BoundRaw;
+use options::IcmpFilter;
+use unbound::{BindOptions, UnboundRaw};
+
+use super::{
+    addr::UNSPECIFIED_LOCAL_ENDPOINT,
+    options::{IpOptionSet, SetIpLevelOption},
+};
+use crate::{
+    events::IoEvents,
+    match_sock_option_mut, match_sock_option_ref,
+    net::socket::{
+        options::{Error as SocketError, SocketOption},
+        private::SocketPrivate,
+        util::{
+            datagram_common::{select_remote_and_bind, Inner},
+            options::{SetSocketLevelOption, SocketOptionSet},
+            MessageHeader, SendRecvFlags, SocketAddr,
+        },
+        Socket,
+    },
+    prelude::*,
+    process::{
+        credentials::capabilities::CapSet,
+        posix_thread::AsPosixThread,
+        signal::{PollHandle, Pollable, Pollee},
+    },
+    util::{net::Protocol, MultiRead, MultiWrite},
+};
+
+mod bound;
+pub mod options;
+mod ping;
+mod unbound;
+
+pub use ping::{ping_group_range, set_ping_group_range};
+
+#[derive(Debug, Clone)]
+struct OptionSet {
+    socket: SocketOptionSet,
+    ip: IpOptionSet,
+    icmp_filter: u32,
+}
+
+impl OptionSet {
+    fn new(hdrincl: bool) -> Self {
+        let socket = SocketOptionSet::new_raw();
+        let ip = IpOptionSet::new_raw(hdrincl);
+        OptionSet {
+            socket,
+            ip,
+            icmp_filter: 0,
+        }
+    }
+}
+
+/// A raw IPv4 socket (`SOCK_RAW`) or an ICMP ping socket (`SOCK_DGRAM` with `IPPROTO_ICMP`).
+pub struct RawSocket {
+    kind: RawIpSocketKind,
+
+    // Lock order: `inner` first, `options` second
+    inner: RwMutex<Inner<UnboundRaw, BoundRaw>>,
+    options: Arc<RwLock<OptionSet>>,
+
+    is_nonblocking: AtomicBool,
+    pollee: Pollee,
+}
+
+impl RawSocket {
+    /// Creates a raw socket that sends and receives IP packets of `protocol`.
+    ///
+    /// Creating raw sockets requires `CAP_NET_RAW`.
+    pub fn new_raw(is_nonblocking: bool, protocol: u8) -> Result<Arc<Self>> {
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        if !posix_thread
+            .credentials()
+            .effective_capset()
+            .contains(CapSet::NET_RAW)
+        {
+            return_errno_with_message!(Errno::EPERM, "creating raw sockets requires `CAP_NET_RAW`");
+        }
+
+        // `IPPROTO_RAW` implies `IP_HDRINCL`.
+        // Reference: <https://man7.org/linux/man-pages/man7/raw.7.html>.
+        let hdrincl = protocol == Protocol::IPPROTO_RAW as u8;
+
+        Ok(Self::new(
+            RawIpSocketKind::Raw(IpProtocol::from(protocol)),
+            OptionSet::new(hdrincl),
+            is_nonblocking,
+        ))
+    }
+
+    /// Creates an ICMP ping socket.
+    ///
+    /// Creating ping sockets requires the group of the current thread to be in
+    /// `net.ipv4.ping_group_range`.
+    pub fn new_ping(is_nonblocking: bool) -> Result<Arc<Self>> {
+        ping::check_ping_permission()?;
+
+        Ok(Self::new(
+            RawIpSocketKind::Ping,
+            OptionSet::new(false),
+            is_nonblocking,
+        ))
+    }
+
+    fn new(kind: RawIpSocketKind, options: OptionSet, is_nonblocking: bool) -> Arc<Self> {
+        let options = Arc::new(RwLock::new(options));
+        let unbound_raw = UnboundRaw::new(kind, options.clone());
+        Arc::new(Self {
+            kind,
+            inner: RwMutex::new(Inner::Unbound(unbound_raw)),
+            options,
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+            pollee: Pollee::new(),
+        })
+    }
+
+    fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, SocketAddr)> {
+        let recv_bytes = self
+            .inner
+            .read()
+            .try_recv(writer, flags)
+            .map(|(recv_bytes, remote_endpoint)| (recv_bytes, remote_endpoint.into()))?;
+        self.pollee.invalidate();
+
+        Ok(recv_bytes)
+    }
+
+    fn try_send(
+        &self,
+        reader: &mut dyn MultiRead,
+        remote: Option<&IpEndpoint>,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        let (sent_bytes, iface_to_poll) = select_remote_and_bind(
+            &self.inner,
+            remote,
+            || {
+                let remote_endpoint = remote.ok_or_else(|| {
+                    Error::with_message(
+                        Errno::EDESTADDRREQ,
+                        "the destination address is not specified",
+                    )
+                })?;
+                self.inner
+                    .write()
+                    .bind_ephemeral(remote_endpoint, &self.pollee)
+            },
+            |bound_raw, remote_endpoint| {
+                let sent_bytes = bound_raw.try_send(reader, remote_endpoint, flags)?;
+                let iface_to_poll = bound_raw.iface().clone();
+                Ok((sent_bytes, iface_to_poll))
+            },
+        )?;
+
+        self.pollee.invalidate();
+        iface_to_poll.poll();
+
+        Ok(sent_bytes)
+    }
+
+    fn is_icmp_raw(&self) -> bool {
+        self.kind == RawIpSocketKind::Raw(IpProtocol::Icmp)
+    }
+}
+
+impl Pollable for RawSocket {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut PollHandle>) -> IoEvents {
+        self.pollee
+            .poll_with(mask, poller, || self.inner.read().check_io_events())
+    }
+}
+
+impl SocketPrivate for RawSocket {
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn set_nonblocking(&self, is_nonblocking: bool) {
+        self.is_nonblocking.store(is_nonblocking, Ordering::Relaxed);
+    }
+}
+
+impl Socket for RawSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let endpoint = socket_addr.try_into()?;
+        let can_reuse = self.options.read().socket.reuse_addr();
+
+        self.inner
+            .write()
+            .bind(&endpoint, &self.pollee, BindOptions { can_reuse })
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let endpoint = socket_addr.try_into()?;
+
+        self.inner.write().connect(&endpoint, &self.pollee)
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let endpoint = self
+            .inner
+            .read()
+            .addr()
+            .unwrap_or(UNSPECIFIED_LOCAL_ENDPOINT);
+
+        Ok(endpoint.into())
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        let endpoint =
+            *self.inner.read().peer_addr().ok_or_else(|| {
+                Error::with_message(Errno::ENOTCONN, "the socket is not connected")
+            })?;
+
+        Ok(endpoint.into())
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        // TODO: Deal with flags
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let MessageHeader {
+            addr,
+            control_messages,
+            ..
+        } = message_header;
+
+        let endpoint = match addr {
+            Some(addr) => Some(addr.try_into()?),
+            None => None,
+        };
+
+        if !control_messages.is_empty() {
+            // TODO: Support sending control message
+            warn!("sending control message is not supported");
+        }
+
+        // TODO: Block if the send buffer is full
+        self.try_send(reader, endpoint.as_ref(), flags)
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        // TODO: Deal with flags
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let (received_bytes, peer_addr) =
+            self.block_on(IoEvents::IN, || self.try_recv(writer, flags))?;
+
+        // TODO: Receive control message
+
+        let message_header = MessageHeader::new(Some(peer_addr), Vec::new());
+
+        Ok((received_bytes, message_header))
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_errors: SocketError => {
+                // TODO: Support socket errors for raw IP sockets
+                socket_errors.set(None);
+                return Ok(());
+            },
+            _ => ()
+        });
+
+        let options = self.options.read();
+
+        // Deal with socket-level options
+        match options.socket.get_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        // Deal with IP-level options
+        match options.ip.get_option(option) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => (),
+            res => return res,
+        }
+
+        // Deal with raw-level options
+        match_sock_option_mut!(option, {
+            icmp_filter: IcmpFilter => {
+                if !self.is_icmp_raw() {
+                    return_errno_with_message!(
+                        Errno::EOPNOTSUPP,
+                        "ICMP_FILTER is only supported by raw ICMP sockets"
+                    );
+                }
+                icmp_filter.set(options.icmp_filter);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        let inner = self.inner.read();
+        let mut options = self.options.write();
+
+        // Deal with socket-level options
+        let need_iface_poll = match options.socket.set_option(option, &*inner) {
+            Err(err) if err.error() == Errno::ENOPROTOOPT => {
+                // Deal with IP-level options
+                match options.ip.set_option(option, self) {
+                    Err(err) if err.error() == Errno::ENOPROTOOPT => {
+                        // Deal with raw-level options
+                        do_raw_setsockopt(option, &mut options, &inner, self.is_icmp_raw())?
+                    }
+                    Err(err) => return Err(err),
+                    Ok(need_iface_poll) => need_iface_poll,
+                }
+            }
+            Err(err) => return Err(err),
+            Ok(need_iface_poll) => need_iface_poll,
+        };
+
+        let iface_to_poll = need_iface_poll
+            .then(|| match &*inner {
+                Inner::Unbound(_) => None,
+                Inner::Bound(bound_raw) => Some(bound_raw.iface().clone()),
+            })
+            .flatten();
+
+        drop(inner);
+        drop(options);
+
+        if let Some(iface) = iface_to_poll {
+            iface.poll();
+        }
+
+        Ok(())
+    }
+}
+
+fn do_raw_setsockopt(
+    option: &dyn SocketOption,
+    options: &mut OptionSet,
+    inner: &Inner<UnboundRaw, BoundRaw>,
+    is_icmp_raw: bool,
+) -> Result<NeedIfacePoll> {
+    match_sock_option_ref!(option, {
+        icmp_filter: IcmpFilter => {
+            if !is_icmp_raw {
+                return_errno_with_message!(
+                    Errno::EOPNOTSUPP,
+                    "ICMP_FILTER is only supported by raw ICMP sockets"
+                );
+            }
+            let filter = *icmp_filter.get().unwrap();
+            options.icmp_filter = filter;
+            if let Inner::Bound(bound_raw) = inner {
+                bound_raw.set_icmp_filter(filter);
+            }
+        },
+        _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+    });
+
+    Ok(NeedIfacePoll::FALSE)
+}
+
+impl SetSocketLevelOption for Inner<UnboundRaw, BoundRaw> {}
+
+impl SetIpLevelOption for RawSocket {
+    fn set_hdrincl(&self, _hdrincl: bool) -> Result<()> {
+        if self.kind == RawIpSocketKind::Ping {
+            return_errno_with_message!(
+                Errno::ENOPROTOOPT,
+                "IP_HDRINCL cannot be set on ping sockets"
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    prelude::*,
+    process::{posix_thread::AsPosixThread, Gid},
+};
+
+/// The range of groups that are allowed to create ICMP ping sockets.
+///
+/// This corresponds to `net.ipv4.ping_group_range` in Linux. By default, the range is `1 0`,
+/// which is empty. So no one can create ping sockets, not even root.
+///
+/// Reference: <https://docs.kernel.org/networking/ip-sysctl.html>.
+static PING_GROUP_RANGE: RwLock<(Gid, Gid)> = RwLock::new((Gid::new(1), Gid::new(0)));
+
+/// Returns the range of groups that are allowed to create ICMP ping sockets.
+pub fn ping_group_range() -> (Gid, Gid) {
+    *PING_GROUP_RANGE.read()
+}
+
+/// Sets the range of groups that are allowed to create ICMP ping sockets.
+///
+/// If `high` is less than `low`, the range will be empty.
+pub fn set_ping_group_range(low: Gid, high: Gid) {
+    *PING_GROUP_RANGE.write() = (low, high);
+}
+
+/// Checks whether the current thread is allowed to create ICMP ping sockets.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/ping.c#L254>.
+pub(super) fn check_ping_permission() -> Result<()> {
+    let (low, high) = ping_group_range();
+    let is_in_range = |gid: &Gid| low <= *gid && *gid <= high;
+
+    let current_thread = current_thread!();
+    let posix_thread = current_thread.as_posix_thread().unwrap();
+    let credentials = posix_thread.credentials();
+
+    if !is_in_range(&credentials.egid()) && !credentials.groups().iter().any(is_in_range) {
+        return_errno_with_message!(
+            Errno::EACCES,
+            "the group is not allowed to create ping sockets"
+        );
+    }
+
+    Ok(())
+}
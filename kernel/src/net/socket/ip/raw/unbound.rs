@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::{socket::RawIpSocketKind, wire::IpEndpoint};
+
+use super::{bound::BoundRaw, OptionSet};
+use crate::{
+    events::IoEvents,
+    net::{
+        iface::RawIpSocket,
+        socket::{
+            ip::{
+                common::{bind_port, get_ephemeral_endpoint, get_iface_to_bind},
+                DatagramObserver,
+            },
+            util::datagram_common,
+        },
+    },
+    prelude::*,
+    process::signal::Pollee,
+};
+
+pub(super) struct UnboundRaw {
+    kind: RawIpSocketKind,
+    options: Arc<RwLock<OptionSet>>,
+}
+
+impl UnboundRaw {
+    pub(super) fn new(kind: RawIpSocketKind, options: Arc<RwLock<OptionSet>>) -> Self {
+        Self { kind, options }
+    }
+}
+
+pub(super) struct BindOptions {
+    pub(super) can_reuse: bool,
+}
+
+impl datagram_common::Unbound for UnboundRaw {
+    type Endpoint = IpEndpoint;
+    type BindOptions = BindOptions;
+
+    type Bound = BoundRaw;
+
+    fn bind(
+        &mut self,
+        endpoint: &Self::Endpoint,
+        pollee: &Pollee,
+        options: BindOptions,
+    ) -> Result<Self::Bound> {
+        let bound_port = match self.kind {
+            // Raw sockets have no ports. The port in the endpoint is ignored.
+            RawIpSocketKind::Raw(_) => {
+                let Some(iface) = get_iface_to_bind(&endpoint.addr) else {
+                    return_errno_with_message!(
+                        Errno::EADDRNOTAVAIL,
+                        "the address is not available from the local machine"
+                    );
+                };
+                iface.bind_raw()
+            }
+            // Ping sockets use the port as the identifier of ICMP echo messages.
+            RawIpSocketKind::Ping => bind_port(endpoint, options.can_reuse)?,
+        };
+
+        let bound_socket =
+            RawIpSocket::new_bind(bound_port, self.kind, DatagramObserver::new(pollee.clone()));
+        bound_socket.set_icmp_filter(self.options.read().icmp_filter);
+
+        Ok(BoundRaw::new(bound_socket, self.options.clone()))
+    }
+
+    fn bind_ephemeral(
+        &mut self,
+        remote_endpoint: &Self::Endpoint,
+        pollee: &Pollee,
+    ) -> Result<Self::Bound> {
+        let endpoint = get_ephemeral_endpoint(remote_endpoint)?;
+        self.bind(&endpoint, pollee, BindOptions { can_reuse: false })
+    }
+
+    fn check_io_events(&self) -> IoEvents {
+        IoEvents::OUT
+    }
+}
@@ -9,7 +9,7 @@ use aster_bigtcp::{
 use super::{connected::ConnectedStream, init::InitStream, observer::StreamObserver};
 use crate::{
     events::IoEvents,
-    net::iface::{BoundPort, Iface, TcpConnection},
+    net::iface::{BoundPort, Iface, RawTcpSocketExt, TcpConnection},
     prelude::*,
 };
 
@@ -111,6 +111,10 @@ impl ConnectingStream {
         set_option(&self.tcp_conn)
     }
 
+    pub(super) fn raw_with<R>(&self, f: impl FnOnce(&RawTcpSocketExt) -> R) -> R {
+        self.tcp_conn.raw_with(f)
+    }
+
     pub(super) fn into_connection(self) -> TcpConnection {
         self.tcp_conn
     }
@@ -93,7 +93,10 @@ impl InitStream {
         let bound_port = if let Some(bound_port) = self.bound_port {
             bound_port
         } else {
-            let endpoint = get_ephemeral_endpoint(remote_endpoint);
+            let endpoint = match get_ephemeral_endpoint(remote_endpoint) {
+                Ok(endpoint) => endpoint,
+                Err(err) => return Err((err, self)),
+            };
             match bind_port(&endpoint, false) {
                 Ok(bound_port) => bound_port,
                 Err(err) => return Err((err, self)),
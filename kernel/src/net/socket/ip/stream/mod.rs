@@ -3,7 +3,7 @@
 use core::sync::atomic::{AtomicBool, Ordering};
 
 use aster_bigtcp::{
-    socket::{NeedIfacePoll, RawTcpOption, RawTcpSetOption},
+    socket::{CongestionControl, CongestionInfo, NeedIfacePoll, RawTcpOption, RawTcpSetOption},
     wire::IpEndpoint,
 };
 use connected::{close_and_linger, ConnectedStream};
@@ -12,8 +12,8 @@ use init::InitStream;
 use listen::ListenStream;
 use observer::StreamObserver;
 use options::{
-    Congestion, DeferAccept, Inq, KeepIdle, MaxSegment, NoDelay, SynCnt, UserTimeout, WindowClamp,
-    KEEPALIVE_INTERVAL,
+    CTcpInfo, CTcpState, Congestion, DeferAccept, Info, Inq, KeepIdle, MaxSegment, NoDelay, SynCnt,
+    UserTimeout, WindowClamp, KEEPALIVE_INTERVAL,
 };
 use ostd::sync::{PreemptDisabled, RwLockReadGuard, RwLockWriteGuard};
 use takeable::Takeable;
@@ -24,11 +24,12 @@ use super::{
     options::{IpOptionSet, SetIpLevelOption},
 };
 use crate::{
+    current_userspace,
     events::IoEvents,
-    fs::file_handle::FileLike,
+    fs::{file_handle::FileLike, utils::IoctlCmd},
     match_sock_option_mut, match_sock_option_ref,
     net::{
-        iface::Iface,
+        iface::{Iface, RawTcpSocketExt},
         socket::{
             options::{Error as SocketError, SocketOption},
             private::SocketPrivate,
@@ -91,6 +92,7 @@ impl OptionSet {
         RawTcpOption {
             keep_alive: self.socket.keep_alive().then_some(KEEPALIVE_INTERVAL),
             is_nagle_enabled: !self.tcp.no_delay(),
+            congestion_control: self.tcp.congestion(),
         }
     }
 }
@@ -118,6 +120,10 @@ impl StreamSocket {
                 options.tcp.set_no_delay(true);
             }
 
+            options
+                .tcp
+                .set_congestion(raw_tcp_socket.congestion_control());
+
             // TODO: Update other options for a newly-accepted socket
 
             options
@@ -541,14 +547,14 @@ impl Socket for StreamSocket {
         }
 
         let MessageHeader {
-            control_message, ..
+            control_messages, ..
         } = message_header;
 
         // According to the Linux man pages, `EISCONN` _may_ be returned when the destination
         // address is specified for a connection-mode socket. In practice, the destination address
         // is simply ignored. We follow the same behavior as the Linux implementation to ignore it.
 
-        if control_message.is_some() {
+        if !control_messages.is_empty() {
             // TODO: Support sending control message
             warn!("sending control message is not supported");
         }
@@ -574,7 +580,7 @@ impl Socket for StreamSocket {
 
         // According to <https://elixir.bootlin.com/linux/v6.0.9/source/net/ipv4/tcp.c#L2645>,
         // peer address is ignored for connected socket.
-        let message_header = MessageHeader::new(None, None);
+        let message_header = MessageHeader::new(None, Vec::new());
 
         Ok((received_bytes, message_header))
     }
@@ -585,6 +591,12 @@ impl Socket for StreamSocket {
                 socket_errors.set(self.test_and_clear_error());
                 return Ok(());
             },
+            tcp_info: Info => {
+                let state = self.read_updated_state();
+                let congestion = self.options.read().tcp.congestion();
+                tcp_info.set(state.tcp_info(congestion));
+                return Ok(());
+            },
             _ => ()
         });
 
@@ -692,6 +704,28 @@ impl Socket for StreamSocket {
 
         Ok(())
     }
+
+    fn ioctl(&self, cmd: IoctlCmd, arg: Vaddr) -> Result<i32> {
+        let state = self.read_updated_state();
+
+        let len = match (cmd, state.as_ref()) {
+            (IoctlCmd::FIONREAD | IoctlCmd::TIOCOUTQ, State::Listen(_)) => {
+                return_errno_with_message!(Errno::EINVAL, "the socket is listening");
+            }
+            (IoctlCmd::FIONREAD, State::Connected(connected_stream)) => {
+                connected_stream.raw_with(|socket| socket.recv_queue())
+            }
+            (IoctlCmd::TIOCOUTQ, State::Connected(connected_stream)) => {
+                connected_stream.raw_with(|socket| socket.send_queue())
+            }
+            (IoctlCmd::FIONREAD | IoctlCmd::TIOCOUTQ, State::Init(_) | State::Connecting(_)) => 0,
+            _ => return_errno_with_message!(Errno::EINVAL, "the ioctl command is not supported"),
+        };
+        drop(state);
+
+        current_userspace!().write_val(arg, &(len as i32))?;
+        Ok(0)
+    }
 }
 
 fn do_tcp_setsockopt(
@@ -756,6 +790,7 @@ fn do_tcp_setsockopt(
         tcp_congestion: Congestion => {
             let congestion = tcp_congestion.get().unwrap();
             options.tcp.set_congestion(*congestion);
+            state.set_raw_option(|raw_socket: &dyn RawTcpSetOption| raw_socket.set_congestion_control(*congestion));
         },
         tcp_user_timeout: UserTimeout => {
             let user_timeout = tcp_user_timeout.get().unwrap();
@@ -790,6 +825,23 @@ impl State {
         }
     }
 
+    /// Returns the information reported by `TCP_INFO`.
+    ///
+    /// For sockets without connections, `congestion` is used to report the initial congestion
+    /// control information.
+    fn tcp_info(&self, congestion: CongestionControl) -> CTcpInfo {
+        let raw_info = |raw_socket: &RawTcpSocketExt| {
+            CTcpInfo::new(raw_socket.state().into(), &raw_socket.congestion_info())
+        };
+
+        match self {
+            State::Init(_) => CTcpInfo::new(CTcpState::Close, &CongestionInfo::new(congestion)),
+            State::Connecting(connecting_stream) => connecting_stream.raw_with(raw_info),
+            State::Connected(connected_stream) => connected_stream.raw_with(raw_info),
+            State::Listen(_) => CTcpInfo::new(CTcpState::Listen, &CongestionInfo::new(congestion)),
+        }
+    }
+
     fn iface(&self) -> Option<&Arc<Iface>> {
         match self {
             State::Init(_) => None,
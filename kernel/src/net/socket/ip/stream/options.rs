@@ -1,5 +1,8 @@
 // SPDX-License-Identifier: MPL-2.0
 
+pub use aster_bigtcp::socket::CongestionControl;
+use aster_bigtcp::socket::{CongestionInfo, TcpState};
+
 use crate::{impl_socket_options, prelude::*};
 
 impl_socket_options!(
@@ -9,35 +12,174 @@ impl_socket_options!(
     pub struct SynCnt(u8);
     pub struct DeferAccept(u32);
     pub struct WindowClamp(u32);
+    pub struct Info(CTcpInfo);
     pub struct Congestion(CongestionControl);
     pub struct UserTimeout(u32);
     pub struct Inq(bool);
 );
 
-#[derive(Debug, Clone, Copy)]
-pub enum CongestionControl {
-    Reno,
-    Cubic,
+/// The default congestion control algorithm of new TCP sockets.
+///
+/// This corresponds to `net.ipv4.tcp_congestion_control` in Linux.
+static DEFAULT_CONGESTION_CONTROL: RwLock<CongestionControl> =
+    RwLock::new(CongestionControl::Cubic);
+
+/// Returns the default congestion control algorithm of new TCP sockets.
+pub fn default_congestion_control() -> CongestionControl {
+    *DEFAULT_CONGESTION_CONTROL.read()
 }
 
-impl CongestionControl {
-    const RENO: &'static str = "reno";
-    const CUBIC: &'static str = "cubic";
+/// Sets the default congestion control algorithm of new TCP sockets.
+///
+/// Existing sockets are not affected.
+pub fn set_default_congestion_control(control: CongestionControl) {
+    *DEFAULT_CONGESTION_CONTROL.write() = control;
+}
+
+/// The information about a TCP socket, returned by `TCP_INFO`.
+///
+/// Only the states, the congestion control information, and the RTT estimation are reported.
+/// The other fields are always zero.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/tcp.h#L221>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CTcpInfo {
+    tcpi_state: u8,
+    tcpi_ca_state: u8,
+    tcpi_retransmits: u8,
+    tcpi_probes: u8,
+    tcpi_backoff: u8,
+    tcpi_options: u8,
+    tcpi_wscale: u8,
+    tcpi_flags: u8,
+
+    tcpi_rto: u32,
+    tcpi_ato: u32,
+    tcpi_snd_mss: u32,
+    tcpi_rcv_mss: u32,
+
+    tcpi_unacked: u32,
+    tcpi_sacked: u32,
+    tcpi_lost: u32,
+    tcpi_retrans: u32,
+    tcpi_fackets: u32,
+
+    tcpi_last_data_sent: u32,
+    tcpi_last_ack_sent: u32,
+    tcpi_last_data_recv: u32,
+    tcpi_last_ack_recv: u32,
+
+    tcpi_pmtu: u32,
+    tcpi_rcv_ssthresh: u32,
+    tcpi_rtt: u32,
+    tcpi_rttvar: u32,
+    tcpi_snd_ssthresh: u32,
+    tcpi_snd_cwnd: u32,
+    tcpi_advmss: u32,
+    tcpi_reordering: u32,
+
+    tcpi_rcv_rtt: u32,
+    tcpi_rcv_space: u32,
+
+    tcpi_total_retrans: u32,
 
-    pub fn new(name: &str) -> Result<Self> {
-        let congestion = match name {
-            Self::RENO => Self::Reno,
-            Self::CUBIC => Self::Cubic,
-            _ => return_errno_with_message!(Errno::ENOENT, "unsupported congestion name"),
+    tcpi_pacing_rate: u64,
+    tcpi_max_pacing_rate: u64,
+    tcpi_bytes_acked: u64,
+    tcpi_bytes_received: u64,
+    tcpi_segs_out: u32,
+    tcpi_segs_in: u32,
+
+    tcpi_notsent_bytes: u32,
+    tcpi_min_rtt: u32,
+    tcpi_data_segs_in: u32,
+    tcpi_data_segs_out: u32,
+
+    tcpi_delivery_rate: u64,
+
+    tcpi_busy_time: u64,
+    tcpi_rwnd_limited: u64,
+    tcpi_sndbuf_limited: u64,
+
+    tcpi_delivered: u32,
+    tcpi_delivered_ce: u32,
+
+    tcpi_bytes_sent: u64,
+    tcpi_bytes_retrans: u64,
+    tcpi_dsack_dups: u32,
+    tcpi_reord_seen: u32,
+
+    tcpi_rcv_ooopack: u32,
+
+    tcpi_snd_wnd: u32,
+    tcpi_rcv_wnd: u32,
+    tcpi_rehash: u32,
+    tcpi_total_rto: u16,
+    tcpi_total_rto_recoveries: u16,
+    tcpi_total_rto_time: u32,
+}
+
+impl CTcpInfo {
+    pub(super) fn new(state: CTcpState, info: &CongestionInfo) -> Self {
+        let as_micros = |duration: aster_bigtcp::time::Duration| {
+            u32::try_from(duration.total_micros()).unwrap_or(u32::MAX)
         };
 
-        Ok(congestion)
+        Self {
+            tcpi_state: state as u8,
+            tcpi_ca_state: info.ca_state as u8,
+            tcpi_rto: as_micros(info.rto),
+            tcpi_snd_mss: info.mss,
+            tcpi_rcv_mss: info.mss,
+            tcpi_unacked: info.unacked,
+            tcpi_rtt: info.srtt.map_or(0, as_micros),
+            tcpi_rttvar: info.rttvar.map_or(0, as_micros),
+            tcpi_snd_ssthresh: info.ssthresh,
+            tcpi_snd_cwnd: info.cwnd,
+            tcpi_total_retrans: info.total_retrans,
+            tcpi_bytes_acked: info.bytes_acked,
+            // Like Linux, report `u32::MAX` if the minimum RTT is not known yet.
+            tcpi_min_rtt: info.min_rtt.map_or(u32::MAX, as_micros),
+            tcpi_delivered: info.delivered,
+            ..Self::new_zeroed()
+        }
     }
+}
+
+/// The TCP states reported by `TCP_INFO`.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/net/tcp_states.h#L12>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy)]
+pub(super) enum CTcpState {
+    Established = 1,
+    SynSent = 2,
+    SynRecv = 3,
+    FinWait1 = 4,
+    FinWait2 = 5,
+    TimeWait = 6,
+    Close = 7,
+    CloseWait = 8,
+    LastAck = 9,
+    Listen = 10,
+    Closing = 11,
+}
 
-    pub fn name(&self) -> &'static str {
-        match self {
-            Self::Reno => Self::RENO,
-            Self::Cubic => Self::CUBIC,
+impl From<TcpState> for CTcpState {
+    fn from(state: TcpState) -> Self {
+        match state {
+            TcpState::Closed => Self::Close,
+            TcpState::Listen => Self::Listen,
+            TcpState::SynSent => Self::SynSent,
+            TcpState::SynReceived => Self::SynRecv,
+            TcpState::Established => Self::Established,
+            TcpState::FinWait1 => Self::FinWait1,
+            TcpState::FinWait2 => Self::FinWait2,
+            TcpState::CloseWait => Self::CloseWait,
+            TcpState::Closing => Self::Closing,
+            TcpState::LastAck => Self::LastAck,
+            TcpState::TimeWait => Self::TimeWait,
         }
     }
 }
@@ -2,7 +2,7 @@
 
 use aster_bigtcp::time::Duration;
 
-use super::options::CongestionControl;
+use super::options::{default_congestion_control, CongestionControl};
 use crate::prelude::*;
 
 #[derive(Debug, Clone, Copy, CopyGetters, Setters)]
@@ -34,7 +34,7 @@ impl TcpOptionSet {
             syn_cnt: DEFAULT_SYN_CNT,
             defer_accept: Retrans(0),
             window_clamp: DEFAULT_WINDOW_CLAMP,
-            congestion: CongestionControl::Reno,
+            congestion: default_congestion_control(),
             user_timeout: 0,
             receive_inq: false,
         }
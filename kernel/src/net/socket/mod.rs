@@ -1,20 +1,23 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use aster_rights::Rights;
 use options::SocketOption;
-use util::{MessageHeader, SendRecvFlags, SockShutdownCmd, SocketAddr};
+use util::{handle_iface_ioctl, MessageHeader, SendRecvFlags, SockShutdownCmd, SocketAddr};
 
 use crate::{
     fs::{
         file_handle::FileLike,
-        utils::{InodeMode, Metadata, StatusFlags},
+        utils::{InodeMode, IoctlCmd, Metadata, StatusFlags},
     },
     prelude::*,
     util::{MultiRead, MultiWrite},
+    vm::vmo::Vmo,
 };
 
 pub mod ip;
 pub mod netlink;
 pub mod options;
+pub mod packet;
 pub mod unix;
 pub mod util;
 pub mod vsock;
@@ -121,6 +124,18 @@ pub trait Socket: private::SocketPrivate + Send + Sync {
         writers: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, MessageHeader)>;
+
+    /// Performs a socket-specific `ioctl` command.
+    fn ioctl(&self, _cmd: IoctlCmd, _arg: Vaddr) -> Result<i32> {
+        return_errno_with_message!(Errno::EINVAL, "the ioctl command is not supported");
+    }
+
+    /// Returns the VMO to be mapped into the user space by `mmap`.
+    ///
+    /// The `offset` and `len` arguments describe the range to be mapped.
+    fn mmap_vmo(&self, _offset: usize, _len: usize) -> Result<Vmo<Rights>> {
+        return_errno_with_message!(Errno::ENODEV, "mmap() is not supported");
+    }
 }
 
 impl<T: Socket + 'static> FileLike for T {
@@ -139,14 +154,30 @@ impl<T: Socket + 'static> FileLike for T {
         // TODO: Set correct flags
         self.sendmsg(
             reader,
-            MessageHeader {
-                addr: None,
-                control_message: None,
-            },
+            MessageHeader::new(None, Vec::new()),
             SendRecvFlags::empty(),
         )
     }
 
+    fn ioctl(&self, cmd: IoctlCmd, arg: usize) -> Result<i32> {
+        match cmd {
+            IoctlCmd::SIOCGIFNAME
+            | IoctlCmd::SIOCGIFCONF
+            | IoctlCmd::SIOCGIFFLAGS
+            | IoctlCmd::SIOCSIFFLAGS
+            | IoctlCmd::SIOCGIFADDR
+            | IoctlCmd::SIOCSIFADDR
+            | IoctlCmd::SIOCGIFNETMASK
+            | IoctlCmd::SIOCSIFNETMASK
+            | IoctlCmd::SIOCGIFMTU
+            | IoctlCmd::SIOCSIFMTU
+            | IoctlCmd::SIOCSIFHWADDR
+            | IoctlCmd::SIOCGIFHWADDR
+            | IoctlCmd::SIOCGIFINDEX => handle_iface_ioctl(cmd, arg),
+            _ => Socket::ioctl(self, cmd, arg),
+        }
+    }
+
     fn status_flags(&self) -> StatusFlags {
         // TODO: Support other flags (e.g., `O_ASYNC`)
         if self.is_nonblocking() {
@@ -129,7 +129,8 @@ where
     ) -> Result<usize> {
         let MessageHeader {
             addr,
-            control_message,
+            control_messages,
+            ..
         } = message_header;
 
         let remote = match addr {
@@ -137,7 +138,7 @@ where
             Some(addr) => Some(addr.try_into()?),
         };
 
-        if control_message.is_some() {
+        if !control_messages.is_empty() {
             // TODO: Support sending control message
             warn!("sending control message is not supported");
         }
@@ -160,7 +161,7 @@ where
 
         // TODO: Receive control message
 
-        let message_header = MessageHeader::new(Some(addr), None);
+        let message_header = MessageHeader::new(Some(addr), Vec::new());
 
         Ok((received_len, message_header))
     }
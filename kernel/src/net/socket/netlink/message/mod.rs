@@ -12,7 +12,7 @@ pub(super) use attr::{noattr::NoAttr, Attribute, CAttrHeader};
 pub(super) use segment::{
     ack::{DoneSegment, ErrorSegment},
     common::SegmentCommon,
-    header::{CMsgSegHdr, GetRequestFlags, SegHdrCommonFlags},
+    header::{CMsgSegHdr, DeleteRequestFlags, GetRequestFlags, NewRequestFlags, SegHdrCommonFlags},
     CSegmentType, SegmentBody,
 };
 
@@ -25,7 +25,7 @@ use crate::{
 ///
 /// A netlink message can be transmitted to and from user space using a single send/receive syscall.
 /// It consists of one or more [`ProtocolSegment`]s.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Message<T: ProtocolSegment> {
     segments: Vec<T>,
 }
@@ -50,6 +50,9 @@ pub use addr::{GroupIdSet, NetlinkSocketAddr};
 pub use kobject_uevent::NetlinkUeventSocket;
 pub use options::{AddMembership, DropMembership};
 pub use route::NetlinkRouteSocket;
+pub(in crate::net) use route::{
+    notify_link_added, set_iface_addr, set_link_flags, set_link_mtu, update_iface_addr,
+};
 pub use table::{is_valid_protocol, StandardNetlinkProtocol};
 
 pub(in crate::net) fn init() {
@@ -4,12 +4,19 @@
 
 use core::num::NonZeroU32;
 
-use super::util::finish_response;
+use aster_bigtcp::{
+    iface::InterfaceFlags,
+    wire::{Ipv4Address, Ipv4Cidr},
+};
+
+use super::util::{check_net_admin, find_iface_by_index, finish_response, notify, RtnlGroup};
 use crate::{
     net::{
-        iface::{iter_all_ifaces, Iface},
+        iface::{all_ifaces, routing_table, Iface, Route, RoutingTable},
         socket::netlink::{
-            message::{CMsgSegHdr, CSegmentType, GetRequestFlags, SegHdrCommonFlags},
+            message::{
+                CMsgSegHdr, CSegmentType, GetRequestFlags, NewRequestFlags, SegHdrCommonFlags,
+            },
             route::message::{
                 AddrAttr, AddrMessageFlags, AddrSegment, AddrSegmentBody, RtScope, RtnlSegment,
             },
@@ -28,7 +35,8 @@ pub(super) fn do_get_addr(request_segment: &AddrSegment) -> Result<Vec<RtnlSegme
         return_errno_with_message!(Errno::EOPNOTSUPP, "GETADDR only supports dump requests");
     }
 
-    let mut response_segments: Vec<RtnlSegment> = iter_all_ifaces()
+    let mut response_segments: Vec<RtnlSegment> = all_ifaces()
+        .iter()
         // GETADDR only supports dump mode, so we're going to report all addresses.
         .filter_map(|iface| iface_to_new_addr(request_segment.header(), iface))
         .map(RtnlSegment::NewAddr)
@@ -39,6 +47,179 @@ pub(super) fn do_get_addr(request_segment: &AddrSegment) -> Result<Vec<RtnlSegme
     Ok(response_segments)
 }
 
+pub(super) fn do_new_addr(request_segment: &AddrSegment) -> Result<Vec<RtnlSegment>> {
+    check_net_admin()?;
+
+    let iface = &find_iface(request_segment.body())?;
+
+    let prefix_len = request_segment.body().prefix_len;
+    if prefix_len > 32 {
+        return_errno_with_message!(Errno::EINVAL, "the prefix length is invalid");
+    }
+
+    // `IFA_LOCAL` is the address of the interface, while `IFA_ADDRESS` is the address of the
+    // other end for point-to-point interfaces. They are the same for other interfaces, and
+    // `IFA_ADDRESS` is used if `IFA_LOCAL` is missing.
+    // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/devinet.c#L902>.
+    let local_addr = request_segment
+        .attrs()
+        .iter()
+        .find_map(|attr| match attr {
+            AddrAttr::Local(addr) => Some(*addr),
+            _ => None,
+        })
+        .or_else(|| {
+            request_segment.attrs().iter().find_map(|attr| match attr {
+                AddrAttr::Address(addr) => Some(*addr),
+                _ => None,
+            })
+        })
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "the local address is missing"))?;
+    let new_cidr = Ipv4Cidr::new(Ipv4Address::from(local_addr), prefix_len);
+
+    let flags = NewRequestFlags::from_bits_truncate(request_segment.header().flags);
+    let old_addr = iface.ipv4_addr();
+    if old_addr == Some(new_cidr.address())
+        && (flags.contains(NewRequestFlags::EXCL) || !flags.contains(NewRequestFlags::REPLACE))
+    {
+        return_errno_with_message!(Errno::EEXIST, "the address already exists");
+    }
+
+    apply_new_addr(request_segment.header(), iface, new_cidr);
+
+    Ok(Vec::new())
+}
+
+/// Sets the address of an interface on behalf of the `SIOCSIFADDR` and `SIOCSIFNETMASK` ioctls.
+///
+/// If `new_cidr` is `None`, the address of the interface is removed.
+pub(in crate::net) fn set_iface_addr(iface: &Arc<Iface>, new_cidr: Option<Ipv4Cidr>) -> Result<()> {
+    check_net_admin()?;
+
+    update_iface_addr(iface, new_cidr);
+
+    Ok(())
+}
+
+/// Sets the address of an interface on behalf of the kernel (e.g., the DHCP client).
+///
+/// Unlike [`set_iface_addr`], this function does not check the permission of the current thread.
+pub(in crate::net) fn update_iface_addr(iface: &Arc<Iface>, new_cidr: Option<Ipv4Cidr>) {
+    let old_cidr = iface
+        .ipv4_addr()
+        .map(|addr| Ipv4Cidr::new(addr, iface.prefix_len().unwrap()));
+    if old_cidr == new_cidr {
+        return;
+    }
+
+    match new_cidr {
+        Some(new_cidr) => apply_new_addr(&CMsgSegHdr::new_zeroed(), iface, new_cidr),
+        None => apply_del_addr(&CMsgSegHdr::new_zeroed(), iface),
+    }
+}
+
+/// Replaces the address of an interface with `new_cidr` and notifies the listeners.
+fn apply_new_addr(request_header: &CMsgSegHdr, iface: &Arc<Iface>, new_cidr: Ipv4Cidr) {
+    let old_addr = iface.ipv4_addr();
+
+    // FIXME: An interface can have only one IPv4 address for now. So instead of adding a
+    // secondary address, we replace the old address with the new one.
+    let old_segment = iface_to_new_addr(request_header, iface);
+
+    {
+        let mut routing_table = routing_table().write();
+        if let Some(old_addr) = old_addr {
+            remove_addr_routes(&mut routing_table, iface, old_addr);
+        }
+        iface.set_ipv4_cidr(Some(new_cidr));
+        if iface.flags().contains(InterfaceFlags::UP) {
+            if let Some(route) = Route::new_connected(iface) {
+                routing_table.insert(route, None);
+            }
+        }
+    }
+
+    if let Some(mut old_segment) = old_segment.filter(|_| old_addr != Some(new_cidr.address())) {
+        old_segment.header_mut().type_ = CSegmentType::DELADDR as _;
+        notify(RtnlGroup::IPV4_IFADDR, RtnlSegment::DelAddr(old_segment));
+    }
+    if let Some(new_segment) = iface_to_new_addr(request_header, iface) {
+        notify(RtnlGroup::IPV4_IFADDR, RtnlSegment::NewAddr(new_segment));
+    }
+}
+
+pub(super) fn do_del_addr(request_segment: &AddrSegment) -> Result<Vec<RtnlSegment>> {
+    check_net_admin()?;
+
+    let iface = &find_iface(request_segment.body())?;
+
+    let (Some(addr), Some(prefix_len)) = (iface.ipv4_addr(), iface.prefix_len()) else {
+        return_errno_with_message!(Errno::EADDRNOTAVAIL, "the interface has no address");
+    };
+
+    // Check whether the address matches the one specified in the request.
+    // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/devinet.c#L667>.
+    let is_matched = request_segment.attrs().iter().all(|attr| match attr {
+        AddrAttr::Local(local) => Ipv4Address::from(*local) == addr,
+        AddrAttr::Label(label) => label.to_bytes() == iface.name().as_bytes(),
+        AddrAttr::Address(address) => {
+            request_segment.body().prefix_len == prefix_len
+                && Ipv4Cidr::new(addr, prefix_len).contains_addr(&Ipv4Address::from(*address))
+        }
+    });
+    if !is_matched {
+        return_errno_with_message!(Errno::EADDRNOTAVAIL, "the address does not exist");
+    }
+
+    apply_del_addr(request_segment.header(), iface);
+
+    Ok(Vec::new())
+}
+
+/// Removes the address of an interface and notifies the listeners.
+fn apply_del_addr(request_header: &CMsgSegHdr, iface: &Arc<Iface>) {
+    let mut del_segment = iface_to_new_addr(request_header, iface).unwrap();
+    del_segment.header_mut().type_ = CSegmentType::DELADDR as _;
+
+    {
+        let mut routing_table = routing_table().write();
+        iface.set_ipv4_cidr(None);
+        // The interface has no address now, so no routes can go through it.
+        routing_table.remove_iface_routes(iface);
+    }
+
+    notify(RtnlGroup::IPV4_IFADDR, RtnlSegment::DelAddr(del_segment));
+}
+
+fn find_iface(body: &AddrSegmentBody) -> Result<Arc<Iface>> {
+    if body.family != CSocketAddrFamily::AF_INET as i32 {
+        return_errno_with_message!(
+            Errno::EOPNOTSUPP,
+            "only IPv4 addresses can be added or deleted"
+        );
+    }
+
+    let Some(index) = body.index else {
+        return_errno_with_message!(Errno::ENODEV, "the interface index is not specified");
+    };
+
+    find_iface_by_index(index.get())
+}
+
+/// Removes the routes that depend on `addr`, which will be removed from `iface`.
+fn remove_addr_routes(routing_table: &mut RoutingTable, iface: &Arc<Iface>, addr: Ipv4Address) {
+    let prefix_len = iface.prefix_len().unwrap();
+    let connected_dst = Ipv4Cidr::new(addr, prefix_len).network();
+
+    while let Some(index) = routing_table.iter().position(|route| {
+        Arc::ptr_eq(&route.iface, iface)
+            && (route.pref_src == Some(addr)
+                || (route.dst == connected_dst && route.protocol == Route::PROTOCOL_KERNEL))
+    }) {
+        routing_table.remove(index);
+    }
+}
+
 fn iface_to_new_addr(request_header: &CMsgSegHdr, iface: &Arc<Iface>) -> Option<AddrSegment> {
     let ipv4_addr = iface.ipv4_addr()?;
 
@@ -4,14 +4,16 @@
 
 use core::num::NonZero;
 
-use aster_bigtcp::iface::InterfaceType;
+use aster_bigtcp::iface::{InterfaceFlags, InterfaceType};
 
-use super::util::finish_response;
+use super::util::{check_net_admin, find_iface_by_index, finish_response, notify, RtnlGroup};
 use crate::{
     net::{
-        iface::{iter_all_ifaces, Iface},
+        iface::{all_ifaces, find_iface, routing_table, Iface, Route},
         socket::netlink::{
-            message::{CMsgSegHdr, CSegmentType, GetRequestFlags, SegHdrCommonFlags},
+            message::{
+                CMsgSegHdr, CSegmentType, GetRequestFlags, NewRequestFlags, SegHdrCommonFlags,
+            },
             route::message::{LinkAttr, LinkSegment, LinkSegmentBody, RtnlSegment},
         },
     },
@@ -22,14 +24,15 @@ use crate::{
 pub(super) fn do_get_link(request_segment: &LinkSegment) -> Result<Vec<RtnlSegment>> {
     let filter_by = FilterBy::from_request(request_segment)?;
 
-    let mut response_segments: Vec<RtnlSegment> = iter_all_ifaces()
+    let mut response_segments: Vec<RtnlSegment> = all_ifaces()
+        .iter()
         // Filter to include only requested links.
         .filter(|iface| match &filter_by {
             FilterBy::Index(index) => *index == iface.index(),
             FilterBy::Name(name) => *name == iface.name(),
             FilterBy::Dump => true,
         })
-        .map(|iface| iface_to_new_link(request_segment.header(), iface))
+        .map(|iface| iface_to_new_link(request_segment.header(), iface, InterfaceFlags::empty()))
         .map(RtnlSegment::NewLink)
         .collect();
 
@@ -91,11 +94,10 @@ impl<'a> FilterBy<'a> {
 // Reference: <https://docs.kernel.org/userspace-api/netlink/intro.html#strict-checking>.
 
 fn validate_getlink_request(body: &LinkSegmentBody) -> Result<()> {
-    // FIXME: The Linux implementation also checks the `padding` and `change` fields,
-    // but these fields are lost during the conversion of a `CIfInfoMsg` to `LinkSegmentBody`.
-    // We should consider including the `change` field in `LinkSegmentBody`.
+    // FIXME: The Linux implementation also checks the `padding` field,
+    // but it is lost during the conversion of a `CIfInfoMsg` to `LinkSegmentBody`.
     // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/core/rtnetlink.c#L4043>.
-    if !body.flags.is_empty() || body.type_ != InterfaceType::NETROM {
+    if !body.flags.is_empty() || !body.change.is_empty() || body.type_ != InterfaceType::NETROM {
         return_errno_with_message!(Errno::EINVAL, "the flags or the type is not valid");
     }
 
@@ -103,9 +105,9 @@ fn validate_getlink_request(body: &LinkSegmentBody) -> Result<()> {
 }
 
 fn validate_dumplink_request(body: &LinkSegmentBody) -> Result<()> {
-    // FIXME: The Linux implementation also checks the `padding` and `change` fields.
+    // FIXME: The Linux implementation also checks the `padding` field.
     // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/core/rtnetlink.c#L2378>.
-    if !body.flags.is_empty() || body.type_ != InterfaceType::NETROM {
+    if !body.flags.is_empty() || !body.change.is_empty() || body.type_ != InterfaceType::NETROM {
         return_errno_with_message!(Errno::EINVAL, "the flags or the type is not valid");
     }
 
@@ -120,7 +122,183 @@ fn validate_dumplink_request(body: &LinkSegmentBody) -> Result<()> {
     Ok(())
 }
 
-fn iface_to_new_link(request_header: &CMsgSegHdr, iface: &Arc<Iface>) -> LinkSegment {
+pub(super) fn do_set_link(request_segment: &LinkSegment) -> Result<Vec<RtnlSegment>> {
+    check_net_admin()?;
+
+    let body = request_segment.body();
+    let flags = NewRequestFlags::from_bits_truncate(request_segment.header().flags);
+    let is_new_link = request_segment.header().type_ == CSegmentType::NEWLINK as u16;
+
+    let required_name = request_segment.attrs().iter().find_map(|attr| {
+        if let LinkAttr::Name(name) = attr {
+            Some(name.to_str().unwrap())
+        } else {
+            None
+        }
+    });
+
+    // `index` takes precedence over `required_name`.
+    let iface = if let Some(index) = body.index {
+        find_iface_by_index(index.get())?
+    } else if let Some(iface) =
+        required_name.and_then(|name| find_iface(|iface| iface.name() == name))
+    {
+        iface
+    } else if is_new_link && flags.contains(NewRequestFlags::CREATE) {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "creating links is not supported");
+    } else {
+        return_errno_with_message!(Errno::ENODEV, "no link found");
+    };
+
+    if is_new_link && flags.contains(NewRequestFlags::EXCL) {
+        return_errno_with_message!(Errno::EEXIST, "the link already exists");
+    }
+    if body.index.is_some() && required_name.is_some_and(|name| name != iface.name()) {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "renaming links is not supported");
+    }
+
+    let new_mtu = request_segment.attrs().iter().find_map(|attr| {
+        if let LinkAttr::Mtu(mtu) = attr {
+            Some(*mtu as usize)
+        } else {
+            None
+        }
+    });
+    if let Some(new_mtu) = new_mtu {
+        if !(MIN_MTU..=iface.max_mtu()).contains(&new_mtu) {
+            return_errno_with_message!(Errno::EINVAL, "the MTU is out of range");
+        }
+    }
+
+    let new_flags = new_link_flags(iface.flags(), body.flags, body.change);
+
+    apply_link_changes(request_segment.header(), &iface, new_flags, new_mtu);
+
+    Ok(Vec::new())
+}
+
+/// Sets the flags of a link on behalf of the `SIOCSIFFLAGS` ioctl.
+pub(in crate::net) fn set_link_flags(iface: &Arc<Iface>, flags: InterfaceFlags) -> Result<()> {
+    check_net_admin()?;
+
+    let new_flags = new_link_flags(iface.flags(), flags, CHANGEABLE_FLAGS);
+    apply_link_changes(&CMsgSegHdr::new_zeroed(), iface, new_flags, None);
+
+    Ok(())
+}
+
+/// Sets the MTU of a link on behalf of the `SIOCSIFMTU` ioctl.
+pub(in crate::net) fn set_link_mtu(iface: &Arc<Iface>, mtu: usize) -> Result<()> {
+    check_net_admin()?;
+
+    if !(MIN_MTU..=iface.max_mtu()).contains(&mtu) {
+        return_errno_with_message!(Errno::EINVAL, "the MTU is out of range");
+    }
+    apply_link_changes(&CMsgSegHdr::new_zeroed(), iface, iface.flags(), Some(mtu));
+
+    Ok(())
+}
+
+/// Notifies the listeners that a link has been added.
+pub(in crate::net) fn notify_link_added(iface: &Arc<Iface>) {
+    let segment = iface_to_new_link(&CMsgSegHdr::new_zeroed(), iface, InterfaceFlags::all());
+    notify(RtnlGroup::LINK, RtnlSegment::NewLink(segment));
+}
+
+/// Applies the validated changes to a link and notifies the listeners.
+fn apply_link_changes(
+    request_header: &CMsgSegHdr,
+    iface: &Arc<Iface>,
+    new_flags: InterfaceFlags,
+    new_mtu: Option<usize>,
+) {
+    let old_flags = iface.flags();
+
+    let mtu_changed = new_mtu.is_some_and(|new_mtu| new_mtu != iface.mtu());
+    if let Some(new_mtu) = new_mtu {
+        iface.set_mtu(new_mtu);
+    }
+
+    let changed_flags = old_flags ^ new_flags;
+    if !changed_flags.is_empty() {
+        let mut routing_table = routing_table().write();
+        iface.set_flags(new_flags);
+
+        // Linux removes all routes through an interface when it goes down, and adds back the
+        // route to the attached subnet when it goes up.
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/fib_frontend.c#L1468>.
+        if changed_flags.contains(InterfaceFlags::UP) {
+            if new_flags.contains(InterfaceFlags::UP) {
+                if let Some(route) = Route::new_connected(iface) {
+                    routing_table.insert(route, None);
+                }
+            } else {
+                routing_table.remove_iface_routes(iface);
+            }
+        }
+    }
+
+    if mtu_changed || !changed_flags.is_empty() {
+        let segment = iface_to_new_link(request_header, iface, changed_flags);
+        notify(RtnlGroup::LINK, RtnlSegment::NewLink(segment));
+    }
+}
+
+/// Flags that can be changed directly from user space.
+const CHANGEABLE_FLAGS: InterfaceFlags = InterfaceFlags::from_bits_truncate(
+    InterfaceFlags::UP.bits()
+        | InterfaceFlags::DEBUG.bits()
+        | InterfaceFlags::NOTRAILERS.bits()
+        | InterfaceFlags::NOARP.bits()
+        | InterfaceFlags::DYNAMIC.bits()
+        | InterfaceFlags::MULTICAST.bits()
+        | InterfaceFlags::PORTSEL.bits()
+        | InterfaceFlags::AUTOMEDIA.bits(),
+);
+
+/// Computes the new flags of a link according to the requested `flags` and `change` mask.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/core/dev.c#L9065>.
+fn new_link_flags(
+    old_flags: InterfaceFlags,
+    flags: InterfaceFlags,
+    change: InterfaceFlags,
+) -> InterfaceFlags {
+    // Flags that reflect the operational state when the link is up.
+    const OPER_FLAGS: InterfaceFlags = InterfaceFlags::from_bits_truncate(
+        InterfaceFlags::RUNNING.bits() | InterfaceFlags::LOWER_UP.bits(),
+    );
+
+    if flags.is_empty() && change.is_empty() {
+        return old_flags;
+    }
+
+    // For backward compatibility, a zero `change` field means that all flags should be changed.
+    let change = if change.is_empty() {
+        CHANGEABLE_FLAGS
+    } else {
+        change & CHANGEABLE_FLAGS
+    };
+    let mut new_flags = (old_flags - change) | (flags & change);
+
+    // TODO: Report the actual carrier state instead of assuming that the carrier is always on.
+    if new_flags.contains(InterfaceFlags::UP) {
+        new_flags |= OPER_FLAGS;
+    } else {
+        new_flags -= OPER_FLAGS;
+    }
+
+    new_flags
+}
+
+/// The minimum MTU (`ETH_MIN_MTU` in Linux).
+const MIN_MTU: usize = 68;
+
+fn iface_to_new_link(
+    request_header: &CMsgSegHdr,
+    iface: &Arc<Iface>,
+    change: InterfaceFlags,
+) -> LinkSegment {
     let header = CMsgSegHdr {
         len: 0,
         type_: CSegmentType::NEWLINK as _,
@@ -134,6 +312,7 @@ fn iface_to_new_link(request_header: &CMsgSegHdr, iface: &Arc<Iface>) -> LinkSeg
         type_: iface.type_(),
         index: NonZero::new(iface.index()),
         flags: iface.flags(),
+        change,
     };
 
     let attrs = vec![
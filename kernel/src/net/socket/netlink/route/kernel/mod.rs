@@ -9,7 +9,9 @@ use super::message::{RtnlMessage, RtnlSegment};
 use crate::{
     net::socket::netlink::{
         addr::PortNum,
-        message::{CSegmentType, ErrorSegment, ProtocolSegment},
+        message::{
+            CSegmentType, ErrorSegment, GetRequestFlags, ProtocolSegment, SegHdrCommonFlags,
+        },
         table::{NetlinkRouteProtocol, SupportedNetlinkProtocol},
     },
     prelude::*,
@@ -17,8 +19,12 @@ use crate::{
 
 mod addr;
 mod link;
+mod route;
 mod util;
 
+pub(in crate::net) use addr::{set_iface_addr, update_iface_addr};
+pub(in crate::net) use link::{notify_link_added, set_link_flags, set_link_mtu};
+
 pub(super) struct NetlinkRouteKernelSocket {
     _private: PhantomData<()>,
 }
@@ -40,24 +46,48 @@ impl NetlinkRouteKernelSocket {
 
             let response_segments = match segment {
                 RtnlSegment::GetLink(request_segment) => link::do_get_link(request_segment),
+                RtnlSegment::NewLink(request_segment) | RtnlSegment::SetLink(request_segment) => {
+                    link::do_set_link(request_segment)
+                }
                 RtnlSegment::GetAddr(request_segment) => addr::do_get_addr(request_segment),
+                RtnlSegment::NewAddr(request_segment) => addr::do_new_addr(request_segment),
+                RtnlSegment::DelAddr(request_segment) => addr::do_del_addr(request_segment),
+                RtnlSegment::GetRoute(request_segment) => route::do_get_route(request_segment),
+                RtnlSegment::NewRoute(request_segment) => route::do_new_route(request_segment),
+                RtnlSegment::DelRoute(request_segment) => route::do_del_route(request_segment),
                 _ => {
-                    // FIXME: The error is currently silently ignored.
                     warn!("unsupported request type: {:?}", segment_type);
-                    return;
+                    Err(Error::with_message(
+                        Errno::EOPNOTSUPP,
+                        "the request type is not supported",
+                    ))
                 }
             };
 
-            let response = match response_segments {
-                Ok(segments) => RtnlMessage::new(segments),
+            // Errors are always reported. Successful requests are acknowledged only if the
+            // `ACK` flag is set, and dump requests are terminated by `DONE` rather than an ACK.
+            // Reference: <https://docs.kernel.org/userspace-api/netlink/intro.html#netlink-message-types>.
+            let mut segments = match response_segments {
+                Ok(segments) => segments,
                 Err(error) => {
-                    // TODO: Deal with the `NetlinkMessageCommonFlags::ACK` flag.
-                    // Should we return `ErrorSegment` if ACK flag does not exist?
-                    // Reference: <https://docs.kernel.org/userspace-api/netlink/intro.html#netlink-message-types>.
                     let err_segment = ErrorSegment::new_from_request(request_header, Some(error));
-                    RtnlMessage::new(vec![RtnlSegment::Error(err_segment)])
+                    vec![RtnlSegment::Error(err_segment)]
                 }
             };
+            let is_dump = is_get_request(segment_type)
+                && GetRequestFlags::from_bits_truncate(request_header.flags)
+                    .contains(GetRequestFlags::DUMP);
+            let needs_ack = SegHdrCommonFlags::from_bits_truncate(request_header.flags)
+                .contains(SegHdrCommonFlags::ACK);
+            if needs_ack && !is_dump && !matches!(segments.last(), Some(RtnlSegment::Error(_))) {
+                let ack_segment = ErrorSegment::new_from_request(request_header, None);
+                segments.push(RtnlSegment::Error(ack_segment));
+            }
+
+            if segments.is_empty() {
+                continue;
+            }
+            let response = RtnlMessage::new(segments);
 
             debug!("netlink route response: {:?}", response);
 
@@ -72,3 +102,10 @@ static NETLINK_ROUTE_KERNEL: NetlinkRouteKernelSocket = NetlinkRouteKernelSocket
 pub(super) fn get_netlink_route_kernel() -> &'static NetlinkRouteKernelSocket {
     &NETLINK_ROUTE_KERNEL
 }
+
+fn is_get_request(segment_type: CSegmentType) -> bool {
+    matches!(
+        segment_type,
+        CSegmentType::GETLINK | CSegmentType::GETADDR | CSegmentType::GETROUTE
+    )
+}
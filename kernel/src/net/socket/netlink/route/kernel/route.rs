@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Handle route-related requests.
+
+use aster_bigtcp::{
+    iface::InterfaceFlags,
+    wire::{Ipv4Address, Ipv4Cidr},
+};
+
+use super::util::{check_net_admin, find_iface_by_index, finish_response, notify, RtnlGroup};
+use crate::{
+    net::{
+        iface::{all_ifaces, find_iface, routing_table, Iface, Route, RouteType},
+        socket::netlink::{
+            message::{
+                CMsgSegHdr, CSegmentType, GetRequestFlags, NewRequestFlags, SegHdrCommonFlags,
+            },
+            route::message::{
+                RouteAttr, RouteMessageFlags, RouteSegment, RouteSegmentBody, RtScope, RtType,
+                RtnlSegment,
+            },
+        },
+    },
+    prelude::*,
+    util::net::CSocketAddrFamily,
+};
+
+pub(super) fn do_get_route(request_segment: &RouteSegment) -> Result<Vec<RtnlSegment>> {
+    let dump_all = {
+        let flags = GetRequestFlags::from_bits_truncate(request_segment.header().flags);
+        flags.contains(GetRequestFlags::DUMP)
+    };
+
+    let body = request_segment.body();
+
+    if dump_all {
+        let mut response_segments: Vec<RtnlSegment> = if body.family
+            == CSocketAddrFamily::AF_UNSPEC as i32
+            || body.family == CSocketAddrFamily::AF_INET as i32
+        {
+            routing_table()
+                .read()
+                .iter()
+                .map(|route| {
+                    route_to_segment(request_segment.header(), CSegmentType::NEWROUTE, route)
+                })
+                .map(RtnlSegment::NewRoute)
+                .collect()
+        } else {
+            // Only IPv4 routes are supported, so there is nothing to report for other families.
+            Vec::new()
+        };
+
+        finish_response(request_segment.header(), dump_all, &mut response_segments);
+
+        return Ok(response_segments);
+    }
+
+    check_ipv4_family(body)?;
+
+    let dst = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Dst(dst) => Some(Ipv4Address::from(*dst)),
+        _ => None,
+    })
+    .unwrap_or(Ipv4Address::UNSPECIFIED);
+
+    let routing_table = routing_table().read();
+    let Some(route) = routing_table.lookup(dst) else {
+        return_errno_with_message!(Errno::ENETUNREACH, "no route to the destination");
+    };
+
+    let segment = if body.flags.contains(RouteMessageFlags::FIB_MATCH) {
+        // Report the matched route itself.
+        route_to_segment(request_segment.header(), CSegmentType::NEWROUTE, route)
+    } else {
+        // Report how the packets to the destination will be routed.
+        // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/route.c#L2907>.
+        lookup_result_to_segment(request_segment.header(), dst, route)
+    };
+    let mut response_segments = vec![RtnlSegment::NewRoute(segment)];
+
+    finish_response(request_segment.header(), dump_all, &mut response_segments);
+
+    Ok(response_segments)
+}
+
+pub(super) fn do_new_route(request_segment: &RouteSegment) -> Result<Vec<RtnlSegment>> {
+    check_net_admin()?;
+
+    let new_route = parse_new_route(request_segment)?;
+
+    let flags = NewRequestFlags::from_bits_truncate(request_segment.header().flags);
+
+    let mut routing_table = routing_table().write();
+
+    // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/fib_trie.c#L1210>.
+    let existing = routing_table
+        .iter()
+        .position(|route| route.has_same_key(&new_route));
+    let replaced_index = match existing {
+        Some(_) if flags.contains(NewRequestFlags::EXCL) => {
+            return_errno_with_message!(Errno::EEXIST, "the route already exists");
+        }
+        Some(index) if flags.contains(NewRequestFlags::REPLACE) => Some(index),
+        Some(_)
+            if routing_table.iter().any(|route| {
+                route.has_same_key(&new_route)
+                    && route.gateway == new_route.gateway
+                    && Arc::ptr_eq(&route.iface, &new_route.iface)
+            }) =>
+        {
+            return_errno_with_message!(Errno::EEXIST, "the same route already exists");
+        }
+        None if !flags.contains(NewRequestFlags::CREATE) => {
+            return_errno_with_message!(Errno::ENOENT, "the route does not exist");
+        }
+        _ => None,
+    };
+
+    let segment = route_to_segment(request_segment.header(), CSegmentType::NEWROUTE, &new_route);
+    routing_table.insert(new_route, replaced_index);
+    drop(routing_table);
+
+    notify(RtnlGroup::IPV4_ROUTE, RtnlSegment::NewRoute(segment));
+
+    Ok(Vec::new())
+}
+
+pub(super) fn do_del_route(request_segment: &RouteSegment) -> Result<Vec<RtnlSegment>> {
+    check_net_admin()?;
+
+    let body = request_segment.body();
+    check_ipv4_family(body)?;
+
+    let table = parse_table(request_segment);
+    let dst = parse_dst(request_segment)?;
+    let metric = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Priority(priority) => Some(*priority),
+        _ => None,
+    });
+    let oif = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Oif(oif) => Some(*oif),
+        _ => None,
+    });
+    let gateway = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Gateway(gateway) => Some(Ipv4Address::from(*gateway)),
+        _ => None,
+    });
+    let pref_src = find_attr(request_segment, |attr| match attr {
+        RouteAttr::PrefSrc(pref_src) => Some(Ipv4Address::from(*pref_src)),
+        _ => None,
+    });
+
+    let mut routing_table = routing_table().write();
+
+    // Unspecified fields match any route.
+    // Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/fib_trie.c#L1700>.
+    let Some(index) = routing_table.iter().position(|route| {
+        route.table == table
+            && route.dst == dst
+            && (body.protocol == 0 || route.protocol == body.protocol)
+            && (matches!(body.scope, RtScope::NOWHERE) || route.scope == body.scope as u8)
+            && (body.type_ == RtType::UNSPEC || rt_type_of(route) == body.type_)
+            && metric.is_none_or(|metric| route.metric == metric)
+            && oif.is_none_or(|oif| route.iface.index() == oif)
+            && gateway.is_none_or(|gateway| route.gateway == Some(gateway))
+            && pref_src.is_none_or(|pref_src| route.pref_src == Some(pref_src))
+    }) else {
+        return_errno_with_message!(Errno::ESRCH, "the route does not exist");
+    };
+
+    let route = routing_table.remove(index);
+    drop(routing_table);
+
+    let segment = route_to_segment(request_segment.header(), CSegmentType::DELROUTE, &route);
+    notify(RtnlGroup::IPV4_ROUTE, RtnlSegment::DelRoute(segment));
+
+    Ok(Vec::new())
+}
+
+fn parse_new_route(request_segment: &RouteSegment) -> Result<Route> {
+    let body = request_segment.body();
+    check_ipv4_family(body)?;
+
+    let type_ = match body.type_ {
+        RtType::UNICAST => RouteType::Unicast,
+        RtType::LOCAL => RouteType::Local,
+        RtType::UNSPEC => {
+            return_errno_with_message!(Errno::EINVAL, "the route type is not specified")
+        }
+        _ => return_errno_with_message!(Errno::EOPNOTSUPP, "the route type is not supported"),
+    };
+
+    let dst = parse_dst(request_segment)?;
+    let metric = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Priority(priority) => Some(*priority),
+        _ => None,
+    })
+    .unwrap_or(0);
+    let gateway = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Gateway(gateway) => Some(Ipv4Address::from(*gateway)),
+        _ => None,
+    });
+    let pref_src = find_attr(request_segment, |attr| match attr {
+        RouteAttr::PrefSrc(pref_src) => Some(Ipv4Address::from(*pref_src)),
+        _ => None,
+    });
+    let oif = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Oif(oif) => Some(*oif),
+        _ => None,
+    });
+
+    let iface = match (oif, gateway) {
+        (Some(oif), gateway) => {
+            let iface = find_iface_by_index(oif)?;
+            if gateway.is_some_and(|gateway| !is_on_link(&iface, gateway)) {
+                return_errno_with_message!(Errno::ENETUNREACH, "the gateway is not reachable");
+            }
+            iface
+        }
+        (None, Some(gateway)) => find_iface(|iface| is_on_link(iface, gateway))
+            .ok_or_else(|| Error::with_message(Errno::ENETUNREACH, "the gateway is unreachable"))?,
+        (None, None) => {
+            return_errno_with_message!(Errno::EINVAL, "the output interface is not specified")
+        }
+    };
+    if !iface.flags().contains(InterfaceFlags::UP) {
+        return_errno_with_message!(Errno::ENETDOWN, "the output interface is down");
+    }
+
+    if let Some(pref_src) = pref_src {
+        if !all_ifaces()
+            .iter()
+            .any(|iface| iface.ipv4_addr() == Some(pref_src))
+        {
+            return_errno_with_message!(Errno::EINVAL, "the preferred source address is invalid");
+        }
+    }
+
+    Ok(Route {
+        dst,
+        gateway,
+        iface: iface.clone(),
+        pref_src,
+        metric,
+        table: parse_table(request_segment),
+        type_,
+        scope: body.scope as u8,
+        protocol: body.protocol,
+    })
+}
+
+fn check_ipv4_family(body: &RouteSegmentBody) -> Result<()> {
+    if body.family != CSocketAddrFamily::AF_INET as i32 {
+        return_errno_with_message!(Errno::EOPNOTSUPP, "only IPv4 routes are supported");
+    }
+
+    Ok(())
+}
+
+/// Parses the table ID, where `RTA_TABLE` takes precedence over the one in the body.
+fn parse_table(request_segment: &RouteSegment) -> u32 {
+    let table = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Table(table) => Some(*table),
+        _ => None,
+    })
+    .unwrap_or(request_segment.body().table as u32);
+
+    if table == RT_TABLE_UNSPEC {
+        Route::MAIN_TABLE
+    } else {
+        table
+    }
+}
+
+fn parse_dst(request_segment: &RouteSegment) -> Result<Ipv4Cidr> {
+    let dst_len = request_segment.body().dst_len;
+    if dst_len > 32 {
+        return_errno_with_message!(Errno::EINVAL, "the prefix length is invalid");
+    }
+
+    let dst_addr = find_attr(request_segment, |attr| match attr {
+        RouteAttr::Dst(dst) => Some(Ipv4Address::from(*dst)),
+        _ => None,
+    })
+    .unwrap_or(Ipv4Address::UNSPECIFIED);
+
+    let dst = Ipv4Cidr::new(dst_addr, dst_len);
+    if dst.network() != dst {
+        return_errno_with_message!(Errno::EINVAL, "the prefix is invalid for the prefix length");
+    }
+
+    Ok(dst)
+}
+
+fn find_attr<T>(
+    request_segment: &RouteSegment,
+    f: impl FnMut(&RouteAttr) -> Option<T>,
+) -> Option<T> {
+    request_segment.attrs().iter().find_map(f)
+}
+
+/// Returns whether `addr` is in the subnet that is directly attached to `iface`.
+fn is_on_link(iface: &Arc<Iface>, addr: Ipv4Address) -> bool {
+    let (Some(iface_addr), Some(prefix_len)) = (iface.ipv4_addr(), iface.prefix_len()) else {
+        return false;
+    };
+    Ipv4Cidr::new(iface_addr, prefix_len).contains_addr(&addr)
+}
+
+fn rt_type_of(route: &Route) -> RtType {
+    match route.type_ {
+        RouteType::Unicast => RtType::UNICAST,
+        RouteType::Local => RtType::LOCAL,
+    }
+}
+
+fn route_to_segment(
+    request_header: &CMsgSegHdr,
+    type_: CSegmentType,
+    route: &Route,
+) -> RouteSegment {
+    let header = new_header(request_header, type_);
+
+    let body = RouteSegmentBody {
+        family: CSocketAddrFamily::AF_INET as _,
+        dst_len: route.dst.prefix_len(),
+        src_len: 0,
+        tos: 0,
+        table: table_to_u8(route.table),
+        protocol: route.protocol,
+        scope: RtScope::try_from(route.scope).unwrap(),
+        type_: rt_type_of(route),
+        flags: RouteMessageFlags::empty(),
+    };
+
+    let mut attrs = vec![RouteAttr::Table(route.table)];
+    if route.dst.prefix_len() != 0 {
+        attrs.push(RouteAttr::Dst(route.dst.address().octets()));
+    }
+    if route.metric != 0 {
+        attrs.push(RouteAttr::Priority(route.metric));
+    }
+    if let Some(pref_src) = route.pref_src {
+        attrs.push(RouteAttr::PrefSrc(pref_src.octets()));
+    }
+    if let Some(gateway) = route.gateway {
+        attrs.push(RouteAttr::Gateway(gateway.octets()));
+    }
+    attrs.push(RouteAttr::Oif(route.iface.index()));
+
+    RouteSegment::new(header, body, attrs)
+}
+
+fn lookup_result_to_segment(
+    request_header: &CMsgSegHdr,
+    dst: Ipv4Address,
+    route: &Route,
+) -> RouteSegment {
+    let header = new_header(request_header, CSegmentType::NEWROUTE);
+
+    let body = RouteSegmentBody {
+        family: CSocketAddrFamily::AF_INET as _,
+        dst_len: 32,
+        src_len: 0,
+        tos: 0,
+        table: table_to_u8(route.table),
+        protocol: 0,
+        scope: RtScope::UNIVERSE,
+        type_: rt_type_of(route),
+        flags: RouteMessageFlags::CLONED,
+    };
+
+    let mut attrs = vec![
+        RouteAttr::Table(route.table),
+        RouteAttr::Dst(dst.octets()),
+        RouteAttr::Oif(route.iface.index()),
+    ];
+    if let Some(src_addr) = route.src_addr() {
+        attrs.push(RouteAttr::PrefSrc(src_addr.octets()));
+    }
+    if let Some(gateway) = route.gateway {
+        attrs.push(RouteAttr::Gateway(gateway.octets()));
+    }
+
+    RouteSegment::new(header, body, attrs)
+}
+
+fn new_header(request_header: &CMsgSegHdr, type_: CSegmentType) -> CMsgSegHdr {
+    CMsgSegHdr {
+        len: 0,
+        type_: type_ as _,
+        flags: SegHdrCommonFlags::empty().bits(),
+        seq: request_header.seq,
+        pid: request_header.pid,
+    }
+}
+
+/// Converts the table ID to the one in [`RouteSegmentBody`].
+///
+/// Table IDs that do not fit in a byte are only reported in the `RTA_TABLE` attribute.
+fn table_to_u8(table: u32) -> u8 {
+    u8::try_from(table).unwrap_or(RT_TABLE_COMPAT)
+}
+
+/// The unspecified table ID (`RT_TABLE_UNSPEC` in Linux).
+const RT_TABLE_UNSPEC: u32 = 0;
+/// The table ID used when the real ID does not fit in a byte (`RT_TABLE_COMPAT` in Linux).
+const RT_TABLE_COMPAT: u8 = 252;
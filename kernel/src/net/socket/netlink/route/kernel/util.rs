@@ -1,11 +1,17 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use crate::{
-    net::socket::netlink::{
-        message::{CMsgSegHdr, DoneSegment, ProtocolSegment, SegHdrCommonFlags},
-        route::message::RtnlSegment,
+    net::{
+        iface::{find_iface, Iface},
+        socket::netlink::{
+            addr::GroupIdSet,
+            message::{CMsgSegHdr, DoneSegment, ProtocolSegment, SegHdrCommonFlags},
+            route::message::{RtnlMessage, RtnlSegment},
+            table::{NetlinkRouteProtocol, SupportedNetlinkProtocol},
+        },
     },
     prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::AsPosixThread},
 };
 
 /// Finishes a response message.
@@ -37,3 +43,59 @@ fn add_multi_flag(response_segments: &mut [RtnlSegment]) {
         header.flags = flags.bits();
     }
 }
+
+/// Checks whether the current process is allowed to modify the network configuration.
+pub fn check_net_admin() -> Result<()> {
+    let current_thread = current_thread!();
+    let posix_thread = current_thread.as_posix_thread().unwrap();
+
+    if !posix_thread
+        .credentials()
+        .effective_capset()
+        .contains(CapSet::NET_ADMIN)
+    {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "modifying the network configuration requires `CAP_NET_ADMIN`"
+        );
+    }
+
+    Ok(())
+}
+
+/// Finds the iface with the specified index.
+pub fn find_iface_by_index(index: u32) -> Result<Arc<Iface>> {
+    find_iface(|iface| iface.index() == index)
+        .ok_or_else(|| Error::with_message(Errno::ENODEV, "the interface does not exist"))
+}
+
+/// Multicast groups of the netlink route protocol.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/rtnetlink.h#L700>.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy)]
+#[expect(non_camel_case_types)]
+#[expect(clippy::upper_case_acronyms)]
+pub enum RtnlGroup {
+    LINK = 1,
+    IPV4_IFADDR = 5,
+    IPV4_ROUTE = 7,
+    // TODO: The list is not exhaustive.
+}
+
+/// Notifies the sockets in the multicast `group` of a configuration change.
+pub fn notify(group: RtnlGroup, segment: RtnlSegment) {
+    let groups = GroupIdSet::new(1 << (group as u32 - 1));
+    let message = RtnlMessage::new(vec![segment]);
+
+    debug!("netlink route notification: {:?}", message);
+
+    // The configuration change has already taken effect, so a listener whose receive queue is
+    // full only misses the notification.
+    if let Err(error) = NetlinkRouteProtocol::multicast(groups, message) {
+        debug!(
+            "failed to deliver the netlink route notification: {:?}",
+            error
+        );
+    }
+}
@@ -28,7 +28,7 @@ enum AddrAttrClass {
     TARGET_NETNSID = 10,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AddrAttr {
     Address([u8; 4]),
     Local([u8; 4]),
@@ -1,21 +1,149 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use crate::prelude::*;
+use super::{addr::RtScope, legacy::CRtGenMsg};
+use crate::{
+    net::socket::netlink::{
+        message::{SegmentBody, SegmentCommon},
+        route::message::attr::route::RouteAttr,
+    },
+    prelude::*,
+};
+
+pub type RouteSegment = SegmentCommon<RouteSegmentBody, RouteAttr>;
+
+impl SegmentBody for RouteSegmentBody {
+    type CLegacyType = CRtGenMsg;
+    type CType = CRtMsg;
+}
 
 /// `rtmsg` in Linux.
 ///
 /// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/rtnetlink.h#L237>.
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod)]
-#[expect(unused)]
-pub(super) struct CRtMsg {
-    family: u8,
-    dst_len: u8,
-    src_len: u8,
-    tos: u8,
-    table: u8,
-    protocol: u8,
-    scope: u8,
-    type_: u8,
-    flags: u32,
+pub struct CRtMsg {
+    pub family: u8,
+    /// Length of the destination prefix
+    pub dst_len: u8,
+    /// Length of the source prefix
+    pub src_len: u8,
+    /// TOS filter
+    pub tos: u8,
+    /// Routing table ID
+    pub table: u8,
+    /// Routing protocol
+    pub protocol: u8,
+    /// Distance to the destination
+    pub scope: u8,
+    /// Route type
+    pub type_: u8,
+    /// Flags
+    pub flags: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RouteSegmentBody {
+    pub family: i32,
+    pub dst_len: u8,
+    pub src_len: u8,
+    pub tos: u8,
+    pub table: u8,
+    pub protocol: u8,
+    pub scope: RtScope,
+    pub type_: RtType,
+    pub flags: RouteMessageFlags,
+}
+
+impl TryFrom<CRtMsg> for RouteSegmentBody {
+    type Error = Error;
+
+    fn try_from(value: CRtMsg) -> Result<Self> {
+        let scope = RtScope::try_from(value.scope)?;
+        let type_ = RtType::try_from(value.type_)?;
+        let flags = RouteMessageFlags::from_bits_truncate(value.flags);
+
+        Ok(Self {
+            family: value.family as i32,
+            dst_len: value.dst_len,
+            src_len: value.src_len,
+            tos: value.tos,
+            table: value.table,
+            protocol: value.protocol,
+            scope,
+            type_,
+            flags,
+        })
+    }
+}
+
+impl From<RouteSegmentBody> for CRtMsg {
+    fn from(value: RouteSegmentBody) -> Self {
+        CRtMsg {
+            family: value.family as u8,
+            dst_len: value.dst_len,
+            src_len: value.src_len,
+            tos: value.tos,
+            table: value.table,
+            protocol: value.protocol,
+            scope: value.scope as _,
+            type_: value.type_ as _,
+            flags: value.flags.bits(),
+        }
+    }
+}
+
+/// Route types.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/rtnetlink.h#L260>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+#[expect(clippy::upper_case_acronyms)]
+pub enum RtType {
+    UNSPEC = 0,
+    /// Gateway or direct route
+    UNICAST = 1,
+    /// Accept locally
+    LOCAL = 2,
+    /// Accept locally as broadcast, send as broadcast
+    BROADCAST = 3,
+    /// Accept locally as broadcast, but send as unicast
+    ANYCAST = 4,
+    /// Multicast route
+    MULTICAST = 5,
+    /// Drop
+    BLACKHOLE = 6,
+    /// Destination is unreachable
+    UNREACHABLE = 7,
+    /// Administratively prohibited
+    PROHIBIT = 8,
+    /// Not in this table
+    THROW = 9,
+    /// Translate this address
+    NAT = 10,
+    /// Use external resolver
+    XRESOLVE = 11,
+}
+
+bitflags! {
+    /// Flags in [`CRtMsg`].
+    ///
+    /// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/rtnetlink.h#L325>.
+    pub struct RouteMessageFlags: u32 {
+        /// Notify user of route change
+        const NOTIFY       = 0x100;
+        /// This route is cloned
+        const CLONED       = 0x200;
+        /// Multipath equalizer: NI
+        const EQUALIZE     = 0x400;
+        /// Prefix addresses
+        const PREFIX       = 0x800;
+        /// Set `rtm_table` to FIB lookup result
+        const LOOKUP_TABLE = 0x1000;
+        /// Return full FIB lookup match
+        const FIB_MATCH    = 0x2000;
+        /// Route is offloaded
+        const OFFLOAD      = 0x4000;
+        /// Route is trapping packets
+        const TRAP         = 0x8000;
+    }
 }
@@ -2,6 +2,9 @@
 
 //! Netlink Route Socket.
 
+pub(in crate::net) use kernel::{
+    notify_link_added, set_iface_addr, set_link_flags, set_link_mtu, update_iface_addr,
+};
 pub(super) use message::RtnlMessage;
 
 use crate::net::socket::netlink::{common::NetlinkSocket, table::NetlinkRouteProtocol};
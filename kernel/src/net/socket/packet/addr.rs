@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::{iface::InterfaceType, wire::EthernetAddress};
+
+use super::ETHER_ADDR_LEN;
+use crate::{net::socket::util::SocketAddr, prelude::*};
+
+/// The link-layer socket address of a packet socket.
+///
+/// When binding a packet socket, only `protocol` and `ifindex` are used. When receiving a frame,
+/// the address describes where the frame comes from.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkLayerSocketAddr {
+    /// The link-layer protocol (e.g., `ETH_P_IP`) in host byte order.
+    pub protocol: u16,
+    /// The interface index, or zero for any interfaces.
+    pub ifindex: u32,
+    /// The ARP hardware type (e.g., `ARPHRD_ETHER`).
+    pub hatype: u16,
+    /// The packet type (e.g., `PACKET_HOST`).
+    pub pkttype: u8,
+    /// The length of the valid bytes in `addr`.
+    pub halen: u8,
+    /// The link-layer address.
+    pub addr: [u8; 8],
+}
+
+impl LinkLayerSocketAddr {
+    /// Creates the address that describes an Ethernet frame.
+    pub fn new_ether(
+        protocol: u16,
+        ifindex: u32,
+        pkttype: u8,
+        ether_addr: &EthernetAddress,
+    ) -> Self {
+        let mut addr = [0; 8];
+        addr[..ETHER_ADDR_LEN].copy_from_slice(ether_addr.as_bytes());
+
+        Self {
+            protocol,
+            ifindex,
+            hatype: InterfaceType::ETHER as u16,
+            pkttype,
+            halen: ETHER_ADDR_LEN as u8,
+            addr,
+        }
+    }
+}
+
+impl TryFrom<SocketAddr> for LinkLayerSocketAddr {
+    type Error = Error;
+
+    fn try_from(value: SocketAddr) -> Result<Self> {
+        match value {
+            SocketAddr::LinkLayer(addr) => Ok(addr),
+            _ => return_errno_with_message!(
+                Errno::EINVAL,
+                "the address is in an unsupported address family"
+            ),
+        }
+    }
+}
+
+impl From<LinkLayerSocketAddr> for SocketAddr {
+    fn from(value: LinkLayerSocketAddr) -> Self {
+        SocketAddr::LinkLayer(value)
+    }
+}
@@ -0,0 +1,486 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! This module defines packet sockets.
+//!
+//! Packet sockets (`AF_PACKET`) capture the link-layer frames that pass through the network
+//! interfaces. They are typically used by tools like `tcpdump` for network monitoring.
+//!
+//! A `SOCK_RAW` packet socket receives frames with the link-layer header, whereas a `SOCK_DGRAM`
+//! packet socket receives frames with the link-layer header removed. The frames can be filtered by
+//! a classic BPF program attached with `SO_ATTACH_FILTER`, and they can be received either by
+//! `recvmsg` or via a `TPACKET_V3` ring mapped into the user space. A `SOCK_RAW` packet socket
+//! can also send frames that contain the link-layer header.
+//!
+//! Reference: <https://man7.org/linux/man-pages/man7/packet.7.html>.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+pub use addr::LinkLayerSocketAddr;
+use aster_bigtcp::{errors::SendFrameError, iface::PacketTap};
+use aster_rights::Rights;
+pub use options::CPacketMreq;
+use options::{AddMembership, DropMembership, HdrLen, Reserve, Statistics, Version};
+pub use ring::{CTpacketReq3, TpacketVersion};
+pub use tap::{PacketSocketTap, PacketStats};
+
+use crate::{
+    events::IoEvents,
+    match_sock_option_mut, match_sock_option_ref,
+    net::{
+        iface::{all_ifaces, find_iface, Iface},
+        socket::{
+            options::{AttachFilter, DetachFilter, Error as SocketError, SocketOption},
+            private::SocketPrivate,
+            util::{MessageHeader, SendRecvFlags, SocketAddr},
+            Socket,
+        },
+    },
+    prelude::*,
+    process::{
+        credentials::capabilities::CapSet,
+        posix_thread::AsPosixThread,
+        signal::{PollHandle, Pollable},
+    },
+    util::{MultiRead, MultiWrite},
+    vm::vmo::Vmo,
+};
+
+mod addr;
+pub mod options;
+mod ring;
+mod tap;
+
+/// The kind of a packet socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketSocketKind {
+    /// A `SOCK_RAW` packet socket, which receives frames with the link-layer header.
+    Raw,
+    /// A `SOCK_DGRAM` packet socket, which receives frames without the link-layer header.
+    Dgram,
+}
+
+/// A packet socket.
+pub struct PacketSocket {
+    tap: Arc<PacketSocketTap>,
+    binding: Mutex<Binding>,
+    memberships: Mutex<Vec<Membership>>,
+    is_nonblocking: AtomicBool,
+}
+
+struct Binding {
+    /// The index of the bound iface, or zero for all ifaces.
+    ifindex: u32,
+    /// The ifaces that the tap is registered with.
+    ///
+    /// FIXME: If the socket is bound to all ifaces, the ifaces that are added later should also
+    /// be included.
+    ifaces: Vec<Arc<Iface>>,
+}
+
+struct Membership {
+    iface: Arc<Iface>,
+    type_: MembershipType,
+    alen: u16,
+    address: [u8; 8],
+    count: usize,
+}
+
+/// The types of the packet socket memberships.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L304>.
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[expect(non_camel_case_types)]
+#[expect(clippy::upper_case_acronyms)]
+enum MembershipType {
+    MULTICAST = 0,
+    PROMISC = 1,
+    ALLMULTI = 2,
+    UNICAST = 3,
+}
+
+impl PacketSocket {
+    /// Creates a packet socket that receives frames of `protocol` (in host byte order).
+    ///
+    /// If `protocol` is zero, the socket will not receive any frames until it is bound to a
+    /// non-zero protocol. Creating packet sockets requires `CAP_NET_RAW`.
+    pub fn new(is_nonblocking: bool, kind: PacketSocketKind, protocol: u16) -> Result<Arc<Self>> {
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        if !posix_thread
+            .credentials()
+            .effective_capset()
+            .contains(CapSet::NET_RAW)
+        {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "creating packet sockets requires `CAP_NET_RAW`"
+            );
+        }
+
+        let socket = Arc::new(Self {
+            tap: Arc::new(PacketSocketTap::new(kind, protocol)),
+            binding: Mutex::new(Binding {
+                ifindex: 0,
+                ifaces: Vec::new(),
+            }),
+            memberships: Mutex::new(Vec::new()),
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+        });
+
+        if protocol != 0 {
+            let mut binding = socket.binding.lock();
+            binding.ifaces = all_ifaces().clone();
+            socket.register_tap(&binding);
+        }
+
+        Ok(socket)
+    }
+
+    fn register_tap(&self, binding: &Binding) {
+        for iface in binding.ifaces.iter() {
+            iface.register_packet_tap(self.tap.clone());
+        }
+    }
+
+    fn unregister_tap(&self, binding: &mut Binding) {
+        let tap = self.tap.clone() as Arc<dyn PacketTap>;
+        for iface in binding.ifaces.drain(..) {
+            iface.remove_packet_tap(&tap);
+        }
+    }
+
+    fn add_membership(&self, mreq: &CPacketMreq) -> Result<()> {
+        let (iface, type_) = parse_mreq(mreq)?;
+
+        let mut memberships = self.memberships.lock();
+        if let Some(membership) = memberships
+            .iter_mut()
+            .find(|membership| membership.matches(&iface, type_, mreq))
+        {
+            membership.count += 1;
+            return Ok(());
+        }
+
+        // TODO: Filter multicast frames according to the memberships. Currently, all the multicast
+        // frames are captured, so `PACKET_MR_MULTICAST` and `PACKET_MR_ALLMULTI` take no effect.
+        if type_ == MembershipType::PROMISC {
+            iface.inc_promiscuity();
+        }
+
+        memberships.push(Membership {
+            iface,
+            type_,
+            alen: mreq.mr_alen,
+            address: mreq.mr_address,
+            count: 1,
+        });
+
+        Ok(())
+    }
+
+    fn drop_membership(&self, mreq: &CPacketMreq) -> Result<()> {
+        let (iface, type_) = parse_mreq(mreq)?;
+
+        let mut memberships = self.memberships.lock();
+        let Some(pos) = memberships
+            .iter()
+            .position(|membership| membership.matches(&iface, type_, mreq))
+        else {
+            // Linux silently ignores memberships that do not exist.
+            return Ok(());
+        };
+
+        memberships[pos].count -= 1;
+        if memberships[pos].count == 0 {
+            memberships.swap_remove(pos).release();
+        }
+
+        Ok(())
+    }
+}
+
+impl Membership {
+    fn matches(&self, iface: &Arc<Iface>, type_: MembershipType, mreq: &CPacketMreq) -> bool {
+        let alen = self.alen as usize;
+
+        Arc::ptr_eq(&self.iface, iface)
+            && self.type_ == type_
+            && self.alen == mreq.mr_alen
+            && self.address[..alen] == mreq.mr_address[..alen]
+    }
+
+    fn release(self) {
+        if self.type_ == MembershipType::PROMISC {
+            self.iface.dec_promiscuity();
+        }
+    }
+}
+
+fn parse_mreq(mreq: &CPacketMreq) -> Result<(Arc<Iface>, MembershipType)> {
+    let iface = find_iface_by_index(mreq.mr_ifindex as u32)?;
+
+    let type_ = MembershipType::try_from(mreq.mr_type)
+        .map_err(|_| Error::with_message(Errno::EINVAL, "the membership type is invalid"))?;
+
+    let max_alen = if iface.hw_addr().is_some() {
+        ETHER_ADDR_LEN
+    } else {
+        0
+    };
+    if mreq.mr_alen as usize > max_alen {
+        return_errno_with_message!(Errno::EINVAL, "the address length is invalid");
+    }
+
+    Ok((iface, type_))
+}
+
+fn find_iface_by_index(ifindex: u32) -> Result<Arc<Iface>> {
+    find_iface(|iface| iface.index() == ifindex)
+        .ok_or_else(|| Error::with_message(Errno::ENODEV, "the interface does not exist"))
+}
+
+impl Pollable for PacketSocket {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut PollHandle>) -> IoEvents {
+        self.tap
+            .pollee()
+            .poll_with(mask, poller, || self.tap.check_io_events())
+    }
+}
+
+impl SocketPrivate for PacketSocket {
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn set_nonblocking(&self, is_nonblocking: bool) {
+        self.is_nonblocking.store(is_nonblocking, Ordering::Relaxed);
+    }
+}
+
+impl Socket for PacketSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr = LinkLayerSocketAddr::try_from(socket_addr)?;
+
+        let ifaces = if addr.ifindex == 0 {
+            all_ifaces().clone()
+        } else {
+            vec![find_iface_by_index(addr.ifindex)?]
+        };
+
+        let mut binding = self.binding.lock();
+        self.unregister_tap(&mut binding);
+
+        // Like Linux, a zero protocol means that the protocol is unchanged.
+        if addr.protocol != 0 {
+            self.tap.set_protocol(addr.protocol);
+        }
+        binding.ifindex = addr.ifindex;
+
+        if self.tap.protocol() != 0 {
+            binding.ifaces = ifaces;
+            self.register_tap(&binding);
+        }
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let ifindex = self.binding.lock().ifindex;
+
+        let mut local_addr = LinkLayerSocketAddr {
+            protocol: self.tap.protocol(),
+            ifindex,
+            ..Default::default()
+        };
+        if ifindex != 0 {
+            if let Ok(iface) = find_iface_by_index(ifindex) {
+                local_addr.hatype = iface.type_() as u16;
+                if let Some(hw_addr) = iface.hw_addr() {
+                    local_addr.halen = ETHER_ADDR_LEN as u8;
+                    local_addr.addr[..ETHER_ADDR_LEN].copy_from_slice(hw_addr.as_bytes());
+                }
+            }
+        }
+
+        Ok(local_addr.into())
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_errors: SocketError => {
+                // TODO: Support socket errors for packet sockets
+                socket_errors.set(None);
+            },
+            statistics: Statistics => {
+                statistics.set(self.tap.take_stats());
+            },
+            version: Version => {
+                version.set(self.tap.version() as u32);
+            },
+            hdrlen: HdrLen => {
+                let version = hdrlen.get().copied().unwrap_or_default();
+                let version = TpacketVersion::try_from(version)
+                    .map_err(|_| Error::with_message(Errno::EINVAL, "the version is invalid"))?;
+                hdrlen.set(version.hdrlen() as u32);
+            },
+            reserve: Reserve => {
+                reserve.set(self.tap.reserve());
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            attach_filter: AttachFilter => {
+                let filter = attach_filter.get().unwrap();
+                self.tap.set_filter(Some(filter.clone()));
+            },
+            _detach_filter: DetachFilter => {
+                if self.tap.set_filter(None).is_none() {
+                    return_errno_with_message!(Errno::ENOENT, "no filter is attached");
+                }
+            },
+            add_membership: AddMembership => {
+                self.add_membership(add_membership.get().unwrap())?;
+            },
+            drop_membership: DropMembership => {
+                self.drop_membership(drop_membership.get().unwrap())?;
+            },
+            rx_ring: options::RxRing => {
+                self.tap.set_rx_ring(rx_ring.get().unwrap())?;
+            },
+            version: Version => {
+                let version = TpacketVersion::try_from(*version.get().unwrap())
+                    .map_err(|_| Error::with_message(Errno::EINVAL, "the version is invalid"))?;
+                self.tap.set_version(version)?;
+            },
+            reserve: Reserve => {
+                let reserve = *reserve.get().unwrap();
+                if reserve > i32::MAX as u32 {
+                    return_errno_with_message!(Errno::EINVAL, "the reserved space is too large");
+                }
+                self.tap.set_reserve(reserve)?;
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        // TODO: Deal with flags
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        if self.tap.kind() != PacketSocketKind::Raw {
+            // TODO: Support sending frames via `SOCK_DGRAM` packet sockets, which requires
+            // building the link-layer header from the destination address.
+            return_errno_with_message!(
+                Errno::EOPNOTSUPP,
+                "sending frames via datagram packet sockets is not supported"
+            );
+        }
+
+        // Like Linux, the frame is sent to the iface in the destination address, or to the bound
+        // iface if no address is given.
+        let ifindex = match message_header.addr {
+            Some(addr) => LinkLayerSocketAddr::try_from(addr)?.ifindex,
+            None => self.binding.lock().ifindex,
+        };
+        let iface = find_iface_by_index(ifindex).map_err(|_| {
+            Error::with_message(Errno::ENXIO, "the iface to send the frame is unknown")
+        })?;
+
+        let mut frame = vec![0u8; reader.sum_lens()];
+        let len = reader.read(&mut VmWriter::from(frame.as_mut_slice()))?;
+        frame.truncate(len);
+
+        if iface.hw_addr().is_some() && len < ETHER_HEADER_LEN {
+            return_errno_with_message!(Errno::EINVAL, "the frame is too short");
+        }
+
+        match iface.send_frame(&frame) {
+            Ok(()) => Ok(len),
+            Err(SendFrameError::NotSupported) => {
+                return_errno_with_message!(Errno::EOPNOTSUPP, "the iface has no link layer")
+            }
+            Err(SendFrameError::TooLarge) => {
+                return_errno_with_message!(Errno::EMSGSIZE, "the frame is too large")
+            }
+            Err(SendFrameError::Exhausted) => {
+                return_errno_with_message!(Errno::ENOBUFS, "the device queue is full")
+            }
+        }
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        // TODO: Deal with other flags. Only MSG_TRUNC is handled here.
+        if !flags.sub(SendRecvFlags::MSG_TRUNC).is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let (read_len, frame_len, addr) =
+            self.block_on(IoEvents::IN, || self.tap.try_recv(writer))?;
+
+        let msg_flags = if read_len < frame_len {
+            SendRecvFlags::MSG_TRUNC
+        } else {
+            SendRecvFlags::empty()
+        };
+        let received_len = if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            frame_len
+        } else {
+            read_len
+        };
+
+        let message_header =
+            MessageHeader::new(Some(addr.into()), Vec::new()).with_flags(msg_flags);
+
+        Ok((received_len, message_header))
+    }
+
+    fn mmap_vmo(&self, offset: usize, len: usize) -> Result<Vmo<Rights>> {
+        self.tap.rx_ring_vmo(offset, len)
+    }
+}
+
+impl Drop for PacketSocket {
+    fn drop(&mut self) {
+        let tap = self.tap.clone() as Arc<dyn PacketTap>;
+        let binding = self.binding.get_mut();
+        for iface in binding.ifaces.drain(..) {
+            iface.remove_packet_tap(&tap);
+        }
+
+        for membership in self.memberships.get_mut().drain(..) {
+            membership.release();
+        }
+
+        // Tear down the ring so that its retire timer is cancelled.
+        self.tap.teardown_rx_ring();
+    }
+}
+
+/// The protocol that matches all the link-layer protocols.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_ether.h#L133>.
+const ETH_P_ALL: u16 = 0x0003;
+
+const ETHER_ADDR_LEN: usize = 6;
+
+/// The length of the Ethernet header.
+const ETHER_HEADER_LEN: usize = 14;
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{ring::CTpacketReq3, tap::PacketStats};
+use crate::{impl_socket_options, prelude::*};
+
+impl_socket_options!(
+    pub struct AddMembership(CPacketMreq);
+    pub struct DropMembership(CPacketMreq);
+    pub struct RxRing(CTpacketReq3);
+    pub struct Statistics(PacketStats);
+    pub struct Version(u32);
+    pub struct HdrLen(u32);
+    pub struct Reserve(u32);
+);
+
+/// The membership request for `PACKET_ADD_MEMBERSHIP` and `PACKET_DROP_MEMBERSHIP`.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L297>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CPacketMreq {
+    pub mr_ifindex: i32,
+    pub mr_type: u16,
+    pub mr_alen: u16,
+    pub mr_address: [u8; 8],
+}
@@ -0,0 +1,423 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The `TPACKET_V3` receive ring of packet sockets.
+//!
+//! The ring consists of several blocks, each of which holds a block descriptor followed by
+//! variable-length frames. The kernel fills the blocks one by one and hands a block over to the
+//! user space by setting its status to `TP_STATUS_USER`, either when the block is full or when the
+//! retire timer expires. The user space returns the block by setting its status back to
+//! `TP_STATUS_KERNEL`.
+//!
+//! Reference: <https://www.kernel.org/doc/html/v6.13/networking/packet_mmap.html>.
+
+use core::time::Duration;
+
+use align_ext::AlignExt;
+use aster_rights::Rights;
+use ostd::mm::VmIo;
+
+use super::{addr::LinkLayerSocketAddr, PacketSocketKind};
+use crate::{
+    prelude::*,
+    time::{clocks::RealTimeClock, timer::Timeout, Timer},
+    util::net::CSocketAddrLinkLayer,
+    vm::vmo::{CommitFlags, Vmo, VmoOptions},
+};
+
+/// The ring request for `PACKET_RX_RING` with `TPACKET_V3`.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L281>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CTpacketReq3 {
+    pub tp_block_size: u32,
+    pub tp_block_nr: u32,
+    pub tp_frame_size: u32,
+    pub tp_frame_nr: u32,
+    pub tp_retire_blk_tov: u32,
+    pub tp_sizeof_priv: u32,
+    pub tp_feature_req_word: u32,
+}
+
+/// The versions of the ring buffers.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L251>.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[expect(non_camel_case_types)]
+pub enum TpacketVersion {
+    TPACKET_V1 = 0,
+    TPACKET_V2 = 1,
+    TPACKET_V3 = 2,
+}
+
+impl TpacketVersion {
+    /// Returns the length of the frame header, including the link-layer socket address.
+    pub fn hdrlen(&self) -> usize {
+        match self {
+            // Both `struct tpacket_hdr` and `struct tpacket2_hdr` are 32 bytes long.
+            Self::TPACKET_V1 | Self::TPACKET_V2 => 32,
+            Self::TPACKET_V3 => size_of::<CTpacket3Hdr>(),
+        }
+    }
+}
+
+/// A `TPACKET_V3` receive ring.
+pub(super) struct RxRing {
+    vmo: Vmo<Rights>,
+    block_size: usize,
+    block_nr: usize,
+    /// The offset of the first frame in each block.
+    first_frame_offset: usize,
+    /// The offset of the network header in each frame.
+    net_offset: usize,
+
+    /// The index of the block that is being filled.
+    cur_block: usize,
+    /// The offset of the next frame in the current block.
+    next_frame_offset: usize,
+    /// The offset of the last frame in the current block.
+    last_frame_offset: Option<usize>,
+    num_pkts: u32,
+    seq_num: u64,
+    ts_first: Duration,
+    ts_last: Duration,
+    /// Whether the current block is still owned by the user space.
+    is_frozen: bool,
+    /// The number of times that the ring is frozen.
+    freeze_count: u32,
+
+    retire_timer: Arc<Timer>,
+}
+
+impl RxRing {
+    /// Creates a receive ring.
+    ///
+    /// The retire timer should call [`Self::retire_on_timeout`] when it expires. It will be
+    /// started by this method.
+    pub(super) fn new(
+        req: &CTpacketReq3,
+        kind: PacketSocketKind,
+        reserve: usize,
+        retire_timer: Arc<Timer>,
+    ) -> Result<Self> {
+        let block_size = req.tp_block_size as usize;
+        let block_nr = req.tp_block_nr as usize;
+        let frame_size = req.tp_frame_size as usize;
+        let min_frame_size = TPACKET3_HDRLEN + reserve;
+        let first_frame_offset = BLK_HDR_LEN + (req.tp_sizeof_priv as usize).align_up(8);
+
+        if block_size == 0 || block_size > i32::MAX as usize || block_size % PAGE_SIZE != 0 {
+            return_errno_with_message!(Errno::EINVAL, "the block size is invalid");
+        }
+        if block_size < first_frame_offset + min_frame_size {
+            return_errno_with_message!(Errno::EINVAL, "the block size is too small");
+        }
+        if frame_size < min_frame_size || frame_size % TPACKET_ALIGNMENT != 0 {
+            return_errno_with_message!(Errno::EINVAL, "the frame size is invalid");
+        }
+        let frames_per_block = block_size / frame_size;
+        if frames_per_block
+            .checked_mul(block_nr)
+            .is_none_or(|frame_nr| frame_nr != req.tp_frame_nr as usize)
+        {
+            return_errno_with_message!(Errno::EINVAL, "the number of frames is inconsistent");
+        }
+        let ring_size = block_size
+            .checked_mul(block_nr)
+            .ok_or_else(|| Error::with_message(Errno::EINVAL, "the ring size is too large"))?;
+
+        let vmo = VmoOptions::<Rights>::new(ring_size).alloc()?;
+        // Commit all the pages in advance, since frames are written in the softirq context.
+        for page_idx in 0..ring_size / PAGE_SIZE {
+            vmo.commit_on(page_idx, CommitFlags::empty())?;
+        }
+
+        // See `tpacket_rcv` in Linux for how the offsets are calculated.
+        let net_offset = match kind {
+            PacketSocketKind::Raw => tpacket_align(TPACKET3_HDRLEN + 16) + reserve,
+            PacketSocketKind::Dgram => tpacket_align(TPACKET3_HDRLEN) + 16 + reserve,
+        };
+
+        let retire_tov = match req.tp_retire_blk_tov {
+            0 => DEFAULT_RETIRE_TOV,
+            msecs => Duration::from_millis(msecs as u64),
+        };
+        retire_timer.set_interval(retire_tov);
+        retire_timer.set_timeout(Timeout::After(retire_tov));
+
+        let mut ring = Self {
+            vmo,
+            block_size,
+            block_nr,
+            first_frame_offset,
+            net_offset,
+            cur_block: 0,
+            next_frame_offset: first_frame_offset,
+            last_frame_offset: None,
+            num_pkts: 0,
+            seq_num: 1,
+            ts_first: Duration::ZERO,
+            ts_last: Duration::ZERO,
+            is_frozen: false,
+            freeze_count: 0,
+            retire_timer,
+        };
+        ring.open_block();
+
+        Ok(ring)
+    }
+
+    /// Returns the VMO that backs the ring.
+    pub(super) fn vmo(&self) -> &Vmo<Rights> {
+        &self.vmo
+    }
+
+    /// Returns the size of the ring.
+    pub(super) fn size(&self) -> usize {
+        self.block_size * self.block_nr
+    }
+
+    /// Returns the number of times that the ring is frozen and resets it.
+    pub(super) fn take_freeze_count(&mut self) -> u32 {
+        core::mem::take(&mut self.freeze_count)
+    }
+
+    /// Returns whether a block is ready to be consumed by the user space.
+    pub(super) fn has_user_block(&self) -> bool {
+        let prev_block = (self.cur_block + self.block_nr - 1) % self.block_nr;
+        self.block_status(prev_block) != TP_STATUS_KERNEL
+    }
+
+    /// Puts a frame into the ring.
+    ///
+    /// `data` starts from the link-layer header for `SOCK_RAW` sockets and from the network header
+    /// for `SOCK_DGRAM` sockets. Only the first `snaplen` bytes will be stored.
+    ///
+    /// This method returns `false` if the frame is dropped because the ring is full.
+    pub(super) fn put(
+        &mut self,
+        data: &[u8],
+        snaplen: usize,
+        mac_len: usize,
+        addr: &LinkLayerSocketAddr,
+    ) -> bool {
+        if self.is_frozen && !self.try_thaw() {
+            return false;
+        }
+
+        let mac_offset = self.net_offset - mac_len;
+        // Leave some room so that a frame always fits in an empty block.
+        let max_frame_len = self.block_size - self.first_frame_offset - V3_ALIGNMENT;
+        let snaplen = snaplen.min(max_frame_len.saturating_sub(mac_offset));
+        let frame_len = (mac_offset + snaplen).align_up(V3_ALIGNMENT);
+
+        if self.next_frame_offset + frame_len >= self.block_size {
+            self.close_block(0);
+            if !self.try_open_next_block() {
+                return false;
+            }
+        }
+
+        let now = RealTimeClock::get().read_time();
+        let frame_offset = self.block_offset(self.cur_block) + self.next_frame_offset;
+
+        let hdr = CTpacket3Hdr {
+            tp_next_offset: frame_len as u32,
+            tp_sec: now.as_secs() as u32,
+            tp_nsec: now.subsec_nanos(),
+            tp_snaplen: snaplen as u32,
+            tp_len: data.len() as u32,
+            tp_status: TP_STATUS_USER,
+            tp_mac: mac_offset as u16,
+            tp_net: self.net_offset as u16,
+            ..CTpacket3Hdr::new_zeroed()
+        };
+        let sll = CSocketAddrLinkLayer::from(*addr);
+
+        let res = self
+            .vmo
+            .write_bytes(frame_offset + mac_offset, &data[..snaplen])
+            .and_then(|_| {
+                self.vmo.write_val(
+                    frame_offset + tpacket_align(size_of::<CTpacket3Hdr>()),
+                    &sll,
+                )
+            })
+            .and_then(|_| self.vmo.write_val(frame_offset, &hdr));
+        if res.is_err() {
+            return false;
+        }
+
+        self.last_frame_offset = Some(self.next_frame_offset);
+        self.next_frame_offset += frame_len;
+        self.num_pkts += 1;
+        self.ts_last = now;
+
+        true
+    }
+
+    /// Retires the current block because the retire timer expires.
+    ///
+    /// This method returns `true` if a block is handed over to the user space.
+    pub(super) fn retire_on_timeout(&mut self) -> bool {
+        if self.is_frozen {
+            // If the user space has returned the block, the ring is thawed. Otherwise, the user
+            // space is still lagging behind and we have nothing to do.
+            self.try_thaw();
+            return false;
+        }
+
+        if self.num_pkts == 0 {
+            return false;
+        }
+
+        self.close_block(TP_STATUS_BLK_TMO);
+        self.try_open_next_block();
+
+        true
+    }
+
+    fn try_thaw(&mut self) -> bool {
+        if self.block_status(self.cur_block) != TP_STATUS_KERNEL {
+            return false;
+        }
+
+        self.is_frozen = false;
+        self.open_block();
+        true
+    }
+
+    fn try_open_next_block(&mut self) -> bool {
+        self.cur_block = (self.cur_block + 1) % self.block_nr;
+
+        if self.block_status(self.cur_block) != TP_STATUS_KERNEL {
+            self.is_frozen = true;
+            self.freeze_count += 1;
+            return false;
+        }
+
+        self.open_block();
+        true
+    }
+
+    fn open_block(&mut self) {
+        self.next_frame_offset = self.first_frame_offset;
+        self.last_frame_offset = None;
+        self.num_pkts = 0;
+        self.ts_first = RealTimeClock::get().read_time();
+        self.ts_last = self.ts_first;
+    }
+
+    fn close_block(&mut self, status: u32) {
+        let block_offset = self.block_offset(self.cur_block);
+
+        if let Some(last_frame_offset) = self.last_frame_offset {
+            let _ = self.vmo.write_val(block_offset + last_frame_offset, &0u32);
+        }
+
+        let desc = CTpacketBlockDesc {
+            version: TpacketVersion::TPACKET_V3 as u32,
+            offset_to_priv: BLK_HDR_LEN as u32,
+            block_status: TP_STATUS_USER | status,
+            num_pkts: self.num_pkts,
+            offset_to_first_pkt: self.first_frame_offset as u32,
+            blk_len: self.next_frame_offset as u32,
+            seq_num: self.seq_num,
+            ts_first_pkt: CTpacketBdTs::from(self.ts_first),
+            ts_last_pkt: CTpacketBdTs::from(self.ts_last),
+        };
+        let _ = self.vmo.write_val(block_offset, &desc);
+
+        self.seq_num += 1;
+    }
+
+    fn block_status(&self, block: usize) -> u32 {
+        let status_offset =
+            self.block_offset(block) + core::mem::offset_of!(CTpacketBlockDesc, block_status);
+        self.vmo.read_val(status_offset).unwrap_or(TP_STATUS_USER)
+    }
+
+    fn block_offset(&self, block: usize) -> usize {
+        block * self.block_size
+    }
+}
+
+impl Drop for RxRing {
+    fn drop(&mut self) {
+        self.retire_timer.cancel();
+    }
+}
+
+/// The descriptor at the beginning of each block.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L193>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CTpacketBlockDesc {
+    version: u32,
+    offset_to_priv: u32,
+    block_status: u32,
+    num_pkts: u32,
+    offset_to_first_pkt: u32,
+    blk_len: u32,
+    seq_num: u64,
+    ts_first_pkt: CTpacketBdTs,
+    ts_last_pkt: CTpacketBdTs,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CTpacketBdTs {
+    ts_sec: u32,
+    ts_nsec: u32,
+}
+
+impl From<Duration> for CTpacketBdTs {
+    fn from(value: Duration) -> Self {
+        Self {
+            ts_sec: value.as_secs() as u32,
+            ts_nsec: value.subsec_nanos(),
+        }
+    }
+}
+
+/// The header at the beginning of each frame.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L166>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CTpacket3Hdr {
+    tp_next_offset: u32,
+    tp_sec: u32,
+    tp_nsec: u32,
+    tp_snaplen: u32,
+    tp_len: u32,
+    tp_status: u32,
+    tp_mac: u16,
+    tp_net: u16,
+    tp_rxhash: u32,
+    tp_vlan_tci: u32,
+    tp_vlan_tpid: u16,
+    tp_padding: u16,
+    tp_padding2: [u8; 8],
+}
+
+const fn tpacket_align(len: usize) -> usize {
+    (len + TPACKET_ALIGNMENT - 1) & !(TPACKET_ALIGNMENT - 1)
+}
+
+const TPACKET_ALIGNMENT: usize = 16;
+const V3_ALIGNMENT: usize = 8;
+const SOCKADDR_LL_LEN: usize = size_of::<CSocketAddrLinkLayer>();
+const TPACKET3_HDRLEN: usize = tpacket_align(size_of::<CTpacket3Hdr>()) + SOCKADDR_LL_LEN;
+const BLK_HDR_LEN: usize = size_of::<CTpacketBlockDesc>().next_multiple_of(V3_ALIGNMENT);
+
+const TP_STATUS_KERNEL: u32 = 0;
+const TP_STATUS_USER: u32 = 1 << 0;
+const TP_STATUS_BLK_TMO: u32 = 1 << 5;
+
+/// The default timeout to retire a block.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/packet/internal.h#L59>.
+const DEFAULT_RETIRE_TOV: Duration = Duration::from_millis(8);
@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use aster_bigtcp::{
+    iface::{PacketTap, PacketType},
+    wire::EthernetAddress,
+};
+use aster_rights::Rights;
+use aster_softirq::BottomHalfDisabled;
+
+use super::{
+    addr::LinkLayerSocketAddr,
+    ring::{CTpacketReq3, RxRing, TpacketVersion},
+    PacketSocketKind, ETHER_ADDR_LEN, ETH_P_ALL,
+};
+use crate::{
+    events::IoEvents,
+    net::socket::util::{FilterMeta, SocketFilter},
+    prelude::*,
+    process::signal::Pollee,
+    time::{clocks::MonotonicClock, Timer},
+    util::MultiWrite,
+    vm::vmo::Vmo,
+};
+
+/// The receiving side of a packet socket.
+///
+/// The tap is registered with the ifaces that the packet socket is bound to. It filters the
+/// captured frames and stores them either in the receive queue or in the receive ring.
+pub struct PacketSocketTap {
+    kind: PacketSocketKind,
+    inner: SpinLock<TapInner, BottomHalfDisabled>,
+    pollee: Pollee,
+}
+
+struct TapInner {
+    /// The link-layer protocol in host byte order.
+    protocol: u16,
+    filter: Option<SocketFilter>,
+    recv_queue: VecDeque<CapturedFrame>,
+    recv_queue_len: usize,
+    version: TpacketVersion,
+    reserve: u32,
+    rx_ring: Option<RxRing>,
+    stats: PacketStats,
+}
+
+struct CapturedFrame {
+    data: Box<[u8]>,
+    orig_len: usize,
+    addr: LinkLayerSocketAddr,
+}
+
+/// The statistics reported by `PACKET_STATISTICS`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PacketStats {
+    /// The number of frames received, including the dropped ones.
+    pub packets: u32,
+    /// The number of frames dropped because of insufficient space.
+    pub drops: u32,
+    /// The number of times that the receive ring is frozen.
+    ///
+    /// This is only reported for `TPACKET_V3` sockets.
+    pub freeze_q_cnt: Option<u32>,
+}
+
+impl PacketSocketTap {
+    pub(super) fn new(kind: PacketSocketKind, protocol: u16) -> Self {
+        let inner = TapInner {
+            protocol,
+            filter: None,
+            recv_queue: VecDeque::new(),
+            recv_queue_len: 0,
+            version: TpacketVersion::TPACKET_V1,
+            reserve: 0,
+            rx_ring: None,
+            stats: PacketStats::default(),
+        };
+
+        Self {
+            kind,
+            inner: SpinLock::new(inner),
+            pollee: Pollee::new(),
+        }
+    }
+
+    pub(super) fn pollee(&self) -> &Pollee {
+        &self.pollee
+    }
+
+    pub(super) fn kind(&self) -> PacketSocketKind {
+        self.kind
+    }
+
+    pub(super) fn protocol(&self) -> u16 {
+        self.inner.lock().protocol
+    }
+
+    pub(super) fn set_protocol(&self, protocol: u16) {
+        self.inner.lock().protocol = protocol;
+    }
+
+    /// Sets the socket filter and returns the old one.
+    pub(super) fn set_filter(&self, filter: Option<SocketFilter>) -> Option<SocketFilter> {
+        core::mem::replace(&mut self.inner.lock().filter, filter)
+    }
+
+    pub(super) fn version(&self) -> TpacketVersion {
+        self.inner.lock().version
+    }
+
+    pub(super) fn set_version(&self, version: TpacketVersion) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if inner.rx_ring.is_some() {
+            return_errno_with_message!(Errno::EBUSY, "the receive ring has been set up");
+        }
+        inner.version = version;
+        Ok(())
+    }
+
+    pub(super) fn reserve(&self) -> u32 {
+        self.inner.lock().reserve
+    }
+
+    pub(super) fn set_reserve(&self, reserve: u32) -> Result<()> {
+        let mut inner = self.inner.lock();
+        if inner.rx_ring.is_some() {
+            return_errno_with_message!(Errno::EBUSY, "the receive ring has been set up");
+        }
+        inner.reserve = reserve;
+        Ok(())
+    }
+
+    /// Sets up or tears down the receive ring.
+    pub(super) fn set_rx_ring(self: &Arc<Self>, req: &CTpacketReq3) -> Result<()> {
+        if req.tp_block_nr == 0 {
+            if req.tp_frame_nr != 0 {
+                return_errno_with_message!(Errno::EINVAL, "the number of frames is inconsistent");
+            }
+            self.teardown_rx_ring();
+            return Ok(());
+        }
+
+        let (version, reserve) = {
+            let inner = self.inner.lock();
+            if inner.rx_ring.is_some() {
+                return_errno_with_message!(Errno::EBUSY, "the receive ring has been set up");
+            }
+            (inner.version, inner.reserve)
+        };
+        if version != TpacketVersion::TPACKET_V3 {
+            // TODO: Support `TPACKET_V1` and `TPACKET_V2` rings.
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "only TPACKET_V3 receive rings are supported"
+            );
+        }
+
+        let retire_timer = {
+            let tap = Arc::downgrade(self);
+            MonotonicClock::timer_manager().create_timer(move || {
+                if let Some(tap) = tap.upgrade() {
+                    tap.retire_on_timeout();
+                }
+            })
+        };
+        let rx_ring = RxRing::new(req, self.kind, reserve as usize, retire_timer)?;
+
+        let mut inner = self.inner.lock();
+        if inner.rx_ring.is_some() || inner.version != version || inner.reserve != reserve {
+            return_errno_with_message!(Errno::EBUSY, "the receive ring has been set up");
+        }
+        inner.rx_ring = Some(rx_ring);
+        inner.recv_queue.clear();
+        inner.recv_queue_len = 0;
+
+        Ok(())
+    }
+
+    /// Tears down the receive ring, if any.
+    pub(super) fn teardown_rx_ring(&self) {
+        // Drop the ring outside the lock.
+        let rx_ring = self.inner.lock().rx_ring.take();
+        drop(rx_ring);
+    }
+
+    /// Returns the VMO that backs the receive ring to map it into the user space.
+    pub(super) fn rx_ring_vmo(&self, offset: usize, len: usize) -> Result<Vmo<Rights>> {
+        let inner = self.inner.lock();
+
+        let Some(rx_ring) = inner.rx_ring.as_ref() else {
+            return_errno_with_message!(Errno::EINVAL, "the receive ring is not set up");
+        };
+        if offset != 0 || len != rx_ring.size() {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "the mapping does not cover the exact receive ring"
+            );
+        }
+
+        rx_ring.vmo().dup()
+    }
+
+    /// Returns the statistics and resets them.
+    pub(super) fn take_stats(&self) -> PacketStats {
+        let mut inner = self.inner.lock();
+
+        let mut stats = core::mem::take(&mut inner.stats);
+        stats.packets += stats.drops;
+        if inner.version == TpacketVersion::TPACKET_V3 {
+            let freeze_q_cnt = inner
+                .rx_ring
+                .as_mut()
+                .map_or(0, |rx_ring| rx_ring.take_freeze_count());
+            stats.freeze_q_cnt = Some(freeze_q_cnt);
+        }
+
+        stats
+    }
+
+    pub(super) fn try_recv(
+        &self,
+        writer: &mut dyn MultiWrite,
+    ) -> Result<(usize, usize, LinkLayerSocketAddr)> {
+        let mut inner = self.inner.lock();
+
+        let Some(frame) = inner.recv_queue.pop_front() else {
+            return_errno_with_message!(Errno::EAGAIN, "the receive queue is empty");
+        };
+        inner.recv_queue_len -= frame.data.len();
+        drop(inner);
+
+        self.pollee.invalidate();
+
+        // FIXME: The frame is lost if the user buffer is invalid.
+        let read_len = writer.write(&mut VmReader::from(&*frame.data))?;
+
+        Ok((read_len, frame.orig_len, frame.addr))
+    }
+
+    pub(super) fn check_io_events(&self) -> IoEvents {
+        let inner = self.inner.lock();
+
+        let is_readable = match inner.rx_ring.as_ref() {
+            Some(rx_ring) => rx_ring.has_user_block(),
+            None => !inner.recv_queue.is_empty(),
+        };
+
+        if is_readable {
+            IoEvents::IN
+        } else {
+            IoEvents::empty()
+        }
+    }
+
+    fn retire_on_timeout(&self) {
+        let mut inner = self.inner.lock();
+
+        let has_new_block = inner
+            .rx_ring
+            .as_mut()
+            .is_some_and(|rx_ring| rx_ring.retire_on_timeout());
+        drop(inner);
+
+        if has_new_block {
+            self.pollee.notify(IoEvents::IN);
+        }
+    }
+}
+
+impl PacketTap for PacketSocketTap {
+    fn capture(&self, iface_index: u32, frame: &[u8], pkt_type: PacketType) {
+        if frame.len() < ETHER_HEADER_LEN {
+            return;
+        }
+        let ethertype = u16::from_be_bytes([frame[12], frame[13]]);
+        let src_addr = EthernetAddress::from_bytes(&frame[ETHER_ADDR_LEN..ETHER_ADDR_LEN * 2]);
+
+        let mut inner = self.inner.lock();
+
+        // Outgoing frames are only delivered to sockets that capture all protocols.
+        let is_wanted = match inner.protocol {
+            0 => false,
+            ETH_P_ALL => true,
+            protocol => protocol == ethertype && pkt_type != PacketType::Outgoing,
+        };
+        if !is_wanted {
+            return;
+        }
+
+        let (data, mac_len) = match self.kind {
+            PacketSocketKind::Raw => (frame, ETHER_HEADER_LEN),
+            PacketSocketKind::Dgram => (&frame[ETHER_HEADER_LEN..], 0),
+        };
+
+        let snaplen = match inner.filter.as_ref() {
+            Some(filter) => {
+                let meta = FilterMeta {
+                    protocol: ethertype,
+                    pkttype: pkt_type as u8,
+                    ifindex: iface_index,
+                };
+                match filter.run(data, &meta) {
+                    0 => return,
+                    snaplen => (snaplen as usize).min(data.len()),
+                }
+            }
+            None => data.len(),
+        };
+
+        let addr =
+            LinkLayerSocketAddr::new_ether(ethertype, iface_index, pkt_type as u8, &src_addr);
+
+        let is_delivered = if let Some(rx_ring) = inner.rx_ring.as_mut() {
+            rx_ring.put(data, snaplen, mac_len, &addr)
+        } else if inner.recv_queue_len + snaplen <= RECV_QUEUE_CAPACITY {
+            inner.recv_queue.push_back(CapturedFrame {
+                data: data[..snaplen].into(),
+                orig_len: data.len(),
+                addr,
+            });
+            inner.recv_queue_len += snaplen;
+            true
+        } else {
+            false
+        };
+
+        if !is_delivered {
+            inner.stats.drops += 1;
+            return;
+        }
+        inner.stats.packets += 1;
+
+        // Frames in the receive ring become visible only after the block is retired.
+        let should_notify = inner
+            .rx_ring
+            .as_ref()
+            .is_none_or(|rx_ring| rx_ring.has_user_block());
+        drop(inner);
+
+        if should_notify {
+            self.pollee.notify(IoEvents::IN);
+        }
+    }
+}
+
+const ETHER_HEADER_LEN: usize = 14;
+
+/// The capacity of the receive queue.
+///
+/// This is the default value of `net.core.rmem_default` in Linux.
+const RECV_QUEUE_CAPACITY: usize = 212992;
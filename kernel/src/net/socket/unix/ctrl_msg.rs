@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use crate::{
+    fs::file_handle::FileLike,
+    net::socket::util::ControlMessage,
+    prelude::*,
+    process::{credentials::capabilities::CapSet, posix_thread::AsPosixThread, Gid, Pid, Uid},
+};
+
+/// The credentials of a Unix socket peer or of the sender of a message.
+///
+/// This corresponds to `struct ucred` in Linux, which is used by `SO_PEERCRED` and
+/// `SCM_CREDENTIALS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, CopyGetters)]
+#[get_copy = "pub"]
+pub struct UnixCredentials {
+    pid: Pid,
+    uid: Uid,
+    gid: Gid,
+}
+
+impl UnixCredentials {
+    /// Creates new credentials.
+    pub const fn new(pid: Pid, uid: Uid, gid: Gid) -> Self {
+        Self { pid, uid, gid }
+    }
+
+    /// Returns the credentials that are reported when the peer is unknown.
+    pub(super) const fn new_unknown() -> Self {
+        Self::new(0, Uid::new(u32::MAX), Gid::new(u32::MAX))
+    }
+
+    /// Returns the credentials of the current process that are reported as the peer
+    /// credentials (`SO_PEERCRED`).
+    ///
+    /// The effective user and group IDs are used.
+    pub(super) fn new_peer() -> Self {
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        let credentials = posix_thread.credentials();
+
+        Self::new(
+            posix_thread.process().pid(),
+            credentials.euid(),
+            credentials.egid(),
+        )
+    }
+
+    /// Returns the credentials of the current process that are sent by default
+    /// (`SCM_CREDENTIALS`).
+    ///
+    /// The real user and group IDs are used.
+    fn new_sender() -> Self {
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        let credentials = posix_thread.credentials();
+
+        Self::new(
+            posix_thread.process().pid(),
+            credentials.ruid(),
+            credentials.rgid(),
+        )
+    }
+
+    /// Checks whether the current process is allowed to send messages with the credentials.
+    ///
+    /// Without privileges, the process can only specify its own process ID, one of its real,
+    /// effective, or saved user IDs, and one of its real, effective, or saved group IDs.
+    fn check_sender(&self) -> Result<()> {
+        let current_thread = current_thread!();
+        let posix_thread = current_thread.as_posix_thread().unwrap();
+        let credentials = posix_thread.credentials();
+        let capset = credentials.effective_capset();
+
+        let is_pid_valid =
+            self.pid == posix_thread.process().pid() || capset.contains(CapSet::SYS_ADMIN);
+        let is_uid_valid = [credentials.ruid(), credentials.euid(), credentials.suid()]
+            .contains(&self.uid)
+            || capset.contains(CapSet::SETUID);
+        let is_gid_valid = [credentials.rgid(), credentials.egid(), credentials.sgid()]
+            .contains(&self.gid)
+            || capset.contains(CapSet::SETGID);
+
+        if !is_pid_valid || !is_uid_valid || !is_gid_valid {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "the credentials cannot be sent by the current process"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The auxiliary data sent along with the data on a Unix socket.
+pub(super) struct AuxiliaryData {
+    files: Vec<Arc<dyn FileLike>>,
+    cred: UnixCredentials,
+}
+
+impl AuxiliaryData {
+    /// Builds the auxiliary data from the control messages to send.
+    ///
+    /// If no credentials are specified, the credentials of the current process are used.
+    pub(super) fn from_control(control_messages: Vec<ControlMessage>) -> Result<Self> {
+        let mut files = Vec::new();
+        let mut cred = None;
+
+        for control_message in control_messages {
+            match control_message {
+                ControlMessage::Files(mut new_files) => files.append(&mut new_files),
+                ControlMessage::Credentials(new_cred) => {
+                    new_cred.check_sender()?;
+                    cred = Some(new_cred);
+                }
+            }
+        }
+
+        if files.len() > MAX_FILES {
+            return_errno_with_message!(Errno::EINVAL, "too many files are sent in one message");
+        }
+
+        Ok(Self {
+            files,
+            cred: cred.unwrap_or_else(UnixCredentials::new_sender),
+        })
+    }
+
+    /// Converts the received auxiliary data to control messages.
+    ///
+    /// The credentials are reported only if `SO_PASSCRED` is enabled on the receiving socket.
+    pub(super) fn into_control(self, is_pass_cred: bool) -> Vec<ControlMessage> {
+        let mut control_messages = Vec::new();
+
+        if is_pass_cred {
+            control_messages.push(ControlMessage::Credentials(self.cred));
+        }
+        if !self.files.is_empty() {
+            control_messages.push(ControlMessage::Files(self.files));
+        }
+
+        control_messages
+    }
+
+    /// Takes the files out and returns them with a copy of the credentials.
+    pub(super) fn take(&mut self) -> Self {
+        Self {
+            files: core::mem::take(&mut self.files),
+            cred: self.cred,
+        }
+    }
+
+    /// Moves the files of `other` to `self`.
+    pub(super) fn append_files_from(&mut self, other: &mut Self) {
+        self.files.append(&mut other.files);
+    }
+
+    /// Returns whether there are files.
+    pub(super) fn has_files(&self) -> bool {
+        !self.files.is_empty()
+    }
+
+    /// Returns the credentials.
+    pub(super) fn cred(&self) -> &UnixCredentials {
+        &self.cred
+    }
+}
+
+/// The maximum number of files that can be sent in one message (`SCM_MAX_FD` in Linux).
+const MAX_FILES: usize = 253;
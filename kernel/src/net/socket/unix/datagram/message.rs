@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use ostd::sync::WaitQueue;
+
+use crate::{
+    events::IoEvents,
+    net::socket::unix::{addr::UnixSocketAddrBound, ctrl_msg::AuxiliaryData},
+    prelude::*,
+    process::signal::Pollee,
+    util::{MultiRead, MultiWrite},
+};
+
+/// A message sent on a Unix datagram socket.
+pub(super) struct Message {
+    bytes: Vec<u8>,
+    src_addr: Option<UnixSocketAddrBound>,
+    aux_data: AuxiliaryData,
+}
+
+impl Message {
+    /// Reads a message from `reader`.
+    pub(super) fn new(
+        reader: &mut dyn MultiRead,
+        src_addr: Option<UnixSocketAddrBound>,
+        aux_data: AuxiliaryData,
+    ) -> Result<Self> {
+        let mut bytes = vec![0u8; reader.sum_lens()];
+        let read_len = reader.read(&mut VmWriter::from(bytes.as_mut_slice()))?;
+        bytes.truncate(read_len);
+
+        Ok(Self {
+            bytes,
+            src_addr,
+            aux_data,
+        })
+    }
+
+    /// Returns the length of the message.
+    pub(super) fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Writes the message to `writer`.
+    ///
+    /// If `writer` is too small, the message is truncated. This method returns the number of
+    /// bytes written.
+    pub(super) fn write_to(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+        writer.write(&mut VmReader::from(self.bytes.as_slice()))
+    }
+
+    /// Consumes the message and returns the source address and the auxiliary data.
+    pub(super) fn into_parts(self) -> (Option<UnixSocketAddrBound>, AuxiliaryData) {
+        (self.src_addr, self.aux_data)
+    }
+}
+
+/// The receive queue of a Unix datagram socket.
+///
+/// FIXME: If a socket is sent over itself and is never received, the files form a reference cycle
+/// and will never be dropped. See also the notes on the auxiliary data of Unix stream sockets.
+pub(super) struct MessageQueue {
+    inner: Mutex<QueueInner>,
+    pollee: Pollee,
+    wait_queue: WaitQueue,
+}
+
+struct QueueInner {
+    messages: VecDeque<Message>,
+    total_len: usize,
+    is_shutdown: bool,
+    is_closed: bool,
+}
+
+impl MessageQueue {
+    pub(super) fn new(pollee: Pollee) -> Self {
+        Self {
+            inner: Mutex::new(QueueInner {
+                messages: VecDeque::new(),
+                total_len: 0,
+                is_shutdown: false,
+                is_closed: false,
+            }),
+            pollee,
+            wait_queue: WaitQueue::new(),
+        }
+    }
+
+    /// Pushes a message to the queue, blocking if the queue is full.
+    ///
+    /// If `is_limited` is true, the number of messages in the queue is limited in addition to the
+    /// total length of the messages. Linux lifts this limit if the sender is the peer of the
+    /// receiver.
+    pub(super) fn push(
+        &self,
+        message: Message,
+        is_limited: bool,
+        is_nonblocking: bool,
+    ) -> Result<()> {
+        let mut message = Some(message);
+
+        if is_nonblocking {
+            return self.try_push(&mut message, is_limited);
+        }
+
+        self.wait_queue
+            .pause_until(|| match self.try_push(&mut message, is_limited) {
+                Err(err) if err.error() == Errno::EAGAIN => None,
+                result => Some(result),
+            })?
+    }
+
+    /// Tries to push a message to the queue.
+    ///
+    /// The message is taken out of `message` only if it is pushed successfully.
+    fn try_push(&self, message: &mut Option<Message>, is_limited: bool) -> Result<()> {
+        let mut inner = self.inner.lock();
+
+        if inner.is_closed {
+            return_errno_with_message!(Errno::ECONNREFUSED, "the receiving socket is closed");
+        }
+        if inner.is_shutdown {
+            return_errno_with_message!(
+                Errno::EPIPE,
+                "the receiving socket is shut down for reading"
+            );
+        }
+
+        let len = message.as_ref().unwrap().len();
+
+        // Linux allows a maximum of `max_dgram_qlen + 1` messages in the queue. Although this
+        // seems to be mostly an implementation detail, we follow the exact Linux behavior to
+        // ensure that our regression tests pass with the Linux kernel.
+        if is_limited && inner.messages.len() > MAX_QUEUE_LEN {
+            return_errno_with_message!(Errno::EAGAIN, "the receive queue is full");
+        }
+        if !inner.messages.is_empty() && inner.total_len + len > DEFAULT_BUF_SIZE {
+            return_errno_with_message!(Errno::EAGAIN, "the receive buffer is full");
+        }
+
+        inner.messages.push_back(message.take().unwrap());
+        inner.total_len += len;
+        drop(inner);
+
+        self.pollee.notify(IoEvents::IN);
+
+        Ok(())
+    }
+
+    /// Tries to pop a message from the queue.
+    ///
+    /// - Returns `Ok(Some(_))` with the popped message if successful.
+    /// - Returns `Ok(None)` if the queue is shut down and there is no message left.
+    /// - Returns `Err(EAGAIN)` if the queue is empty.
+    pub(super) fn try_pop(&self) -> Result<Option<Message>> {
+        let mut inner = self.inner.lock();
+
+        let Some(message) = inner.messages.pop_front() else {
+            if inner.is_shutdown {
+                return Ok(None);
+            }
+            return_errno_with_message!(Errno::EAGAIN, "the receive queue is empty");
+        };
+        inner.total_len -= message.len();
+        drop(inner);
+
+        self.pollee.invalidate();
+        self.wait_queue.wake_all();
+
+        Ok(Some(message))
+    }
+
+    /// Shuts down the queue for reading.
+    pub(super) fn shutdown(&self) {
+        self.inner.lock().is_shutdown = true;
+
+        self.pollee.notify(IoEvents::IN | IoEvents::RDHUP);
+        self.wait_queue.wake_all();
+    }
+
+    /// Closes the queue and drops all the messages in it.
+    ///
+    /// This should be called when the receiving socket is closed.
+    pub(super) fn close(&self) {
+        let messages = {
+            let mut inner = self.inner.lock();
+            inner.is_closed = true;
+            inner.total_len = 0;
+            core::mem::take(&mut inner.messages)
+        };
+        // The messages may contain files, so drop them without holding the lock.
+        drop(messages);
+
+        self.wait_queue.wake_all();
+    }
+
+    /// Returns whether the queue is shut down for reading.
+    pub(super) fn is_shutdown(&self) -> bool {
+        self.inner.lock().is_shutdown
+    }
+
+    pub(super) fn check_io_events(&self) -> IoEvents {
+        let inner = self.inner.lock();
+
+        let mut events = IoEvents::empty();
+        if inner.is_shutdown {
+            events |= IoEvents::IN | IoEvents::RDHUP;
+        }
+        if !inner.messages.is_empty() {
+            events |= IoEvents::IN;
+        }
+        events
+    }
+}
+
+/// The maximum number of messages in the queue (`net.unix.max_dgram_qlen` in Linux).
+const MAX_QUEUE_LEN: usize = 10;
+
+/// The size of the receive buffer (`SK_RMEM_MAX` in Linux).
+///
+/// This is also the maximum length of a single message.
+pub(super) const DEFAULT_BUF_SIZE: usize = 212992;
@@ -0,0 +1,407 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use super::message::{Message, MessageQueue, DEFAULT_BUF_SIZE};
+use crate::{
+    events::IoEvents,
+    match_sock_option_mut, match_sock_option_ref,
+    net::socket::{
+        options::{PassCred, PeerCred, SocketOption},
+        private::SocketPrivate,
+        unix::{
+            addr::{UnixSocketAddrBound, UnixSocketAddrKey},
+            ctrl_msg::AuxiliaryData,
+            table::{register_socket, ReportInfo, UnixSocketInfo, UnixSocketState},
+            UnixCredentials, UnixSocketAddr,
+        },
+        util::{MessageHeader, SendRecvFlags, SockShutdownCmd, SocketAddr},
+        Socket,
+    },
+    prelude::*,
+    process::signal::{PollHandle, Pollable, Pollee},
+    util::{net::SockType, MultiRead, MultiWrite},
+};
+
+pub struct UnixDatagramSocket {
+    weak_self: Weak<Self>,
+    addr: Mutex<Option<UnixSocketAddrBound>>,
+    peer: SpinLock<Option<Weak<UnixDatagramSocket>>>,
+    peer_cred: UnixCredentials,
+    queue: Arc<MessageQueue>,
+    pollee: Pollee,
+    is_write_shutdown: AtomicBool,
+    is_nonblocking: AtomicBool,
+    is_pass_cred: AtomicBool,
+}
+
+impl UnixDatagramSocket {
+    pub fn new(is_nonblocking: bool) -> Arc<Self> {
+        Self::new_with_peer_cred(is_nonblocking, UnixCredentials::new_unknown())
+    }
+
+    pub fn new_pair(is_nonblocking: bool) -> (Arc<Self>, Arc<Self>) {
+        let cred = UnixCredentials::new_peer();
+
+        let socket_a = Self::new_with_peer_cred(is_nonblocking, cred);
+        let socket_b = Self::new_with_peer_cred(is_nonblocking, cred);
+
+        *socket_a.peer.lock() = Some(Arc::downgrade(&socket_b));
+        *socket_b.peer.lock() = Some(Arc::downgrade(&socket_a));
+
+        (socket_a, socket_b)
+    }
+
+    fn new_with_peer_cred(is_nonblocking: bool, peer_cred: UnixCredentials) -> Arc<Self> {
+        let pollee = Pollee::new();
+
+        let socket = Arc::new_cyclic(|weak_self| Self {
+            weak_self: weak_self.clone(),
+            addr: Mutex::new(None),
+            peer: SpinLock::new(None),
+            peer_cred,
+            queue: Arc::new(MessageQueue::new(pollee.clone())),
+            pollee,
+            is_write_shutdown: AtomicBool::new(false),
+            is_nonblocking: AtomicBool::new(is_nonblocking),
+            is_pass_cred: AtomicBool::new(false),
+        });
+        register_socket(Arc::downgrade(&socket) as _);
+        socket
+    }
+
+    fn bind_addr(&self, addr_to_bind: UnixSocketAddr) -> Result<()> {
+        let mut addr = self.addr.lock();
+
+        if addr.is_some() {
+            return addr_to_bind.bind_unnamed();
+        }
+
+        let bound_addr = addr_to_bind.bind()?;
+        DATAGRAM_TABLE.add_socket(bound_addr.to_key(), self.weak_self.clone());
+        *addr = Some(bound_addr);
+
+        Ok(())
+    }
+
+    /// Binds the socket to an ephemeral address if it is not bound and credentials are passed.
+    ///
+    /// Linux does this so that the receiver can always identify the sender of the credentials.
+    fn autobind_for_cred(&self, target: &UnixDatagramSocket) -> Result<()> {
+        if self.is_pass_cred() || target.is_pass_cred() {
+            self.bind_addr(UnixSocketAddr::Unnamed)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the connected peer.
+    ///
+    /// If the peer has been closed, the socket is disconnected and this method fails with
+    /// `ECONNREFUSED`.
+    fn connected_peer(&self) -> Result<Arc<UnixDatagramSocket>> {
+        let mut peer = self.peer.lock();
+
+        let Some(weak_peer) = peer.as_ref() else {
+            return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected");
+        };
+        let Some(peer_socket) = weak_peer.upgrade() else {
+            *peer = None;
+            return_errno_with_message!(Errno::ECONNREFUSED, "the peer socket is closed");
+        };
+
+        Ok(peer_socket)
+    }
+
+    /// Returns whether `other` is the connected peer of the socket.
+    fn is_peer(&self, other: &UnixDatagramSocket) -> bool {
+        self.peer
+            .lock()
+            .as_ref()
+            .is_some_and(|peer| core::ptr::eq(peer.as_ptr(), other))
+    }
+
+    /// Checks whether the socket can send messages to `target`.
+    ///
+    /// If `target` is connected, it only accepts messages from its peer.
+    fn check_may_send(&self, target: &UnixDatagramSocket) -> Result<()> {
+        let is_allowed = match target.peer.lock().as_ref() {
+            Some(peer) => core::ptr::eq(peer.as_ptr(), self),
+            None => true,
+        };
+
+        if !is_allowed {
+            return_errno_with_message!(
+                Errno::EPERM,
+                "the target socket is connected to another socket"
+            );
+        }
+
+        Ok(())
+    }
+
+    fn is_pass_cred(&self) -> bool {
+        self.is_pass_cred.load(Ordering::Relaxed)
+    }
+
+    fn check_io_events(&self) -> IoEvents {
+        let mut events = self.queue.check_io_events();
+
+        if events.contains(IoEvents::RDHUP) && self.is_write_shutdown.load(Ordering::Relaxed) {
+            events |= IoEvents::HUP;
+        }
+
+        // TODO: Linux reports `IoEvents::OUT` only if the receive queue of the connected peer is
+        // not full.
+        events | IoEvents::OUT
+    }
+}
+
+impl Pollable for UnixDatagramSocket {
+    fn poll(&self, mask: IoEvents, poller: Option<&mut PollHandle>) -> IoEvents {
+        self.pollee
+            .poll_with(mask, poller, || self.check_io_events())
+    }
+}
+
+impl SocketPrivate for UnixDatagramSocket {
+    fn is_nonblocking(&self) -> bool {
+        self.is_nonblocking.load(Ordering::Relaxed)
+    }
+
+    fn set_nonblocking(&self, nonblocking: bool) {
+        self.is_nonblocking.store(nonblocking, Ordering::Relaxed);
+    }
+}
+
+impl Socket for UnixDatagramSocket {
+    fn bind(&self, socket_addr: SocketAddr) -> Result<()> {
+        let addr = UnixSocketAddr::try_from(socket_addr)?;
+
+        self.bind_addr(addr)
+    }
+
+    fn connect(&self, socket_addr: SocketAddr) -> Result<()> {
+        let remote_addr = UnixSocketAddr::try_from(socket_addr)?.connect()?;
+        let target = get_socket(&remote_addr)?;
+
+        self.check_may_send(&target)?;
+        self.autobind_for_cred(&target)?;
+
+        *self.peer.lock() = Some(Arc::downgrade(&target));
+
+        Ok(())
+    }
+
+    fn shutdown(&self, cmd: SockShutdownCmd) -> Result<()> {
+        if cmd.shut_write() {
+            self.is_write_shutdown.store(true, Ordering::Relaxed);
+        }
+
+        if cmd.shut_read() {
+            self.queue.shutdown();
+        }
+
+        if self.is_write_shutdown.load(Ordering::Relaxed) && self.queue.is_shutdown() {
+            self.pollee.notify(IoEvents::HUP);
+        }
+
+        Ok(())
+    }
+
+    fn addr(&self) -> Result<SocketAddr> {
+        let addr = self.addr.lock().clone();
+
+        Ok(addr.into())
+    }
+
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        let peer = self.peer.lock().as_ref().and_then(Weak::upgrade);
+        let Some(peer) = peer else {
+            return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected");
+        };
+
+        let peer_addr = peer.addr.lock().clone();
+
+        Ok(peer_addr.into())
+    }
+
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_pass_cred: PassCred => {
+                socket_pass_cred.set(self.is_pass_cred());
+            },
+            socket_peer_cred: PeerCred => {
+                socket_peer_cred.set(self.peer_cred);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            socket_pass_cred: PassCred => {
+                let pass_cred = socket_pass_cred.get().unwrap();
+                self.is_pass_cred.store(*pass_cred, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn sendmsg(
+        &self,
+        reader: &mut dyn MultiRead,
+        message_header: MessageHeader,
+        flags: SendRecvFlags,
+    ) -> Result<usize> {
+        // TODO: Deal with flags
+        if !flags.is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let MessageHeader {
+            addr,
+            control_messages,
+            ..
+        } = message_header;
+
+        if self.is_write_shutdown.load(Ordering::Relaxed) {
+            return_errno_with_message!(Errno::EPIPE, "the socket is shut down for writing");
+        }
+        if reader.sum_lens() > DEFAULT_BUF_SIZE {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        let target = match addr {
+            Some(addr) => {
+                let remote_addr = UnixSocketAddr::try_from(addr)?.connect()?;
+                get_socket(&remote_addr)?
+            }
+            None => self.connected_peer()?,
+        };
+
+        self.check_may_send(&target)?;
+        self.autobind_for_cred(&target)?;
+
+        let aux_data = AuxiliaryData::from_control(control_messages)?;
+        let message = Message::new(reader, self.addr.lock().clone(), aux_data)?;
+        let message_len = message.len();
+
+        // Only the queue is kept while waiting, so that the target socket can be closed.
+        let is_limited = !target.is_peer(self);
+        let queue = target.queue.clone();
+        drop(target);
+
+        queue.push(message, is_limited, self.is_nonblocking())?;
+
+        Ok(message_len)
+    }
+
+    fn recvmsg(
+        &self,
+        writer: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, MessageHeader)> {
+        // TODO: Deal with other flags. Only MSG_TRUNC is handled here.
+        if !flags.sub(SendRecvFlags::MSG_TRUNC).is_all_supported() {
+            warn!("unsupported flags: {:?}", flags);
+        }
+
+        let Some(message) = self.block_on(IoEvents::IN, || self.queue.try_pop())? else {
+            return Ok((0, MessageHeader::new(None, Vec::new())));
+        };
+
+        let message_len = message.len();
+        let read_len = message.write_to(writer)?;
+
+        let msg_flags = if read_len < message_len {
+            SendRecvFlags::MSG_TRUNC
+        } else {
+            SendRecvFlags::empty()
+        };
+        let received_len = if flags.contains(SendRecvFlags::MSG_TRUNC) {
+            message_len
+        } else {
+            read_len
+        };
+
+        // Like Linux, no source address is reported if the sender is not bound.
+        let (src_addr, aux_data) = message.into_parts();
+        let control_messages = aux_data.into_control(self.is_pass_cred());
+        let message_header = MessageHeader::new(src_addr.map(SocketAddr::from), control_messages)
+            .with_flags(msg_flags);
+
+        Ok((received_len, message_header))
+    }
+}
+
+impl ReportInfo for UnixDatagramSocket {
+    fn info(&self) -> UnixSocketInfo {
+        let is_connected = self
+            .peer
+            .lock()
+            .as_ref()
+            .is_some_and(|peer| peer.strong_count() > 0);
+        let state = if is_connected {
+            UnixSocketState::Connected
+        } else {
+            UnixSocketState::Unconnected
+        };
+
+        UnixSocketInfo {
+            type_: SockType::SOCK_DGRAM,
+            state,
+            addr: self.addr.lock().clone().into(),
+        }
+    }
+}
+
+impl Drop for UnixDatagramSocket {
+    fn drop(&mut self) {
+        if let Some(addr) = self.addr.get_mut() {
+            DATAGRAM_TABLE.remove_socket(&addr.to_key());
+        }
+
+        self.queue.close();
+    }
+}
+
+static DATAGRAM_TABLE: DatagramTable = DatagramTable::new();
+
+/// A table of the bound Unix datagram sockets, indexed by their addresses.
+struct DatagramTable {
+    sockets: RwLock<BTreeMap<UnixSocketAddrKey, Weak<UnixDatagramSocket>>>,
+}
+
+impl DatagramTable {
+    const fn new() -> Self {
+        Self {
+            sockets: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    fn add_socket(&self, addr_key: UnixSocketAddrKey, socket: Weak<UnixDatagramSocket>) {
+        self.sockets.write().insert(addr_key, socket);
+    }
+
+    fn get_socket(&self, addr_key: &UnixSocketAddrKey) -> Option<Arc<UnixDatagramSocket>> {
+        self.sockets.read().get(addr_key).and_then(Weak::upgrade)
+    }
+
+    fn remove_socket(&self, addr_key: &UnixSocketAddrKey) {
+        self.sockets.write().remove(addr_key);
+    }
+}
+
+fn get_socket(addr_key: &UnixSocketAddrKey) -> Result<Arc<UnixDatagramSocket>> {
+    DATAGRAM_TABLE.get_socket(addr_key).ok_or_else(|| {
+        Error::with_message(
+            Errno::ECONNREFUSED,
+            "no datagram socket is bound to the remote address",
+        )
+    })
+}
@@ -1,8 +1,14 @@
 // SPDX-License-Identifier: MPL-2.0
 
 mod addr;
+mod ctrl_msg;
+mod datagram;
 mod ns;
 mod stream;
+mod table;
 
 pub use addr::UnixSocketAddr;
+pub use ctrl_msg::UnixCredentials;
+pub use datagram::UnixDatagramSocket;
 pub use stream::UnixStreamSocket;
+pub use table::{unix_socket_infos, UnixSocketInfo, UnixSocketState};
@@ -2,13 +2,17 @@
 
 use core::ops::Deref;
 
-use ostd::sync::PreemptDisabled;
+use ostd::{mm::Infallible, sync::PreemptDisabled};
 
 use crate::{
     events::IoEvents,
     fs::utils::{Channel, Consumer, Producer},
     net::socket::{
-        unix::{addr::UnixSocketAddrBound, UnixSocketAddr},
+        unix::{
+            addr::UnixSocketAddrBound,
+            ctrl_msg::{AuxiliaryData, UnixCredentials},
+            UnixSocketAddr,
+        },
         util::SockShutdownCmd,
     },
     prelude::*,
@@ -20,12 +24,17 @@ pub(super) struct Connected {
     addr: AddrView,
     reader: Consumer<u8>,
     writer: Producer<u8>,
+    reader_aux: Arc<Mutex<AuxiliaryQueue>>,
+    writer_aux: Arc<Mutex<AuxiliaryQueue>>,
+    peer_cred: UnixCredentials,
 }
 
 impl Connected {
     pub(super) fn new_pair(
         addr: Option<UnixSocketAddrBound>,
         peer_addr: Option<UnixSocketAddrBound>,
+        cred: UnixCredentials,
+        peer_cred: UnixCredentials,
         reader_pollee: Option<Pollee>,
         writer_pollee: Option<Pollee>,
     ) -> (Connected, Connected) {
@@ -34,17 +43,26 @@ impl Connected {
         let (writer_this, reader_peer) =
             Channel::with_capacity_and_pollees(DEFAULT_BUF_SIZE, writer_pollee, None).split();
 
+        let aux_peer_to_this = Arc::new(Mutex::new(AuxiliaryQueue::new()));
+        let aux_this_to_peer = Arc::new(Mutex::new(AuxiliaryQueue::new()));
+
         let (addr_this, addr_peer) = AddrView::new_pair(addr, peer_addr);
 
         let this = Connected {
             addr: addr_this,
             reader: reader_this,
             writer: writer_this,
+            reader_aux: aux_peer_to_this.clone(),
+            writer_aux: aux_this_to_peer.clone(),
+            peer_cred,
         };
         let peer = Connected {
             addr: addr_peer,
             reader: reader_peer,
             writer: writer_peer,
+            reader_aux: aux_this_to_peer,
+            writer_aux: aux_peer_to_this,
+            peer_cred: cred,
         };
 
         (this, peer)
@@ -58,6 +76,10 @@ impl Connected {
         self.addr.peer_addr()
     }
 
+    pub(super) fn peer_cred(&self) -> UnixCredentials {
+        self.peer_cred
+    }
+
     pub(super) fn bind(&self, addr_to_bind: UnixSocketAddr) -> Result<()> {
         let mut addr = self.addr.addr();
 
@@ -71,18 +93,44 @@ impl Connected {
         Ok(())
     }
 
-    pub(super) fn try_read(&self, writer: &mut dyn MultiWrite) -> Result<usize> {
+    /// Tries to read data and the auxiliary data sent along with it.
+    ///
+    /// The read operation stops at the boundaries where the credentials of the data change if
+    /// `is_pass_cred` is true. It also stops after the data that carry files.
+    pub(super) fn try_read(
+        &self,
+        writer: &mut dyn MultiWrite,
+        is_pass_cred: bool,
+    ) -> Result<(usize, Option<AuxiliaryData>)> {
         if writer.is_empty() {
             if self.reader.is_empty() {
                 return_errno_with_message!(Errno::EAGAIN, "the channel is empty");
             }
-            return Ok(0);
+            return Ok((0, None));
         }
 
-        self.reader.try_read(writer)
+        // The lock must be held during reading so that the auxiliary data always match the data
+        // in the channel.
+        let mut reader_aux = self.reader_aux.lock();
+
+        let max_len = reader_aux.max_read_len(is_pass_cred);
+        let read_len = self
+            .reader
+            .try_read(&mut LimitedWriter::new(writer, max_len))?;
+        let aux_data = reader_aux.consume(read_len);
+
+        Ok((read_len, aux_data))
     }
 
-    pub(super) fn try_write(&self, reader: &mut dyn MultiRead) -> Result<usize> {
+    /// Tries to write data and the auxiliary data.
+    ///
+    /// The files in `aux_data` are taken out if any data are written, so that they will only be
+    /// sent once.
+    pub(super) fn try_write(
+        &self,
+        reader: &mut dyn MultiRead,
+        aux_data: &mut AuxiliaryData,
+    ) -> Result<usize> {
         if reader.is_empty() {
             if self.writer.is_shutdown() {
                 return_errno_with_message!(Errno::EPIPE, "the channel is shut down");
@@ -90,7 +138,95 @@ impl Connected {
             return Ok(0);
         }
 
-        self.writer.try_write(reader)
+        // The lock must be held during writing so that the auxiliary data always match the data
+        // in the channel.
+        let mut writer_aux = self.writer_aux.lock();
+
+        let written_len = self.writer.try_write(reader)?;
+        writer_aux.push(written_len, aux_data);
+
+        Ok(written_len)
+    }
+
+    /// Tries to read a message and the auxiliary data sent along with it.
+    ///
+    /// This method is used by `SOCK_SEQPACKET` sockets. At most one message is read. If `writer`
+    /// is too small, the rest of the message is discarded.
+    ///
+    /// On success, this method returns the number of bytes read, the length of the message, and
+    /// the auxiliary data.
+    pub(super) fn try_read_message(
+        &self,
+        writer: &mut dyn MultiWrite,
+    ) -> Result<(usize, usize, Option<AuxiliaryData>)> {
+        // This must be recorded before the actual operation to avoid race conditions.
+        let is_shutdown = self.reader.is_shutdown();
+
+        // The lock must be held during reading so that the auxiliary data always match the data
+        // in the channel.
+        let mut reader_aux = self.reader_aux.lock();
+
+        let Some(msg_len) = reader_aux.front_len() else {
+            if is_shutdown {
+                return Ok((0, 0, None));
+            }
+            return_errno_with_message!(Errno::EAGAIN, "the channel is empty");
+        };
+
+        let read_len = if writer.is_empty() {
+            0
+        } else {
+            self.reader
+                .try_read(&mut LimitedWriter::new(writer, msg_len))?
+        };
+        if read_len < msg_len {
+            let discarded_len = self
+                .reader
+                .try_read(&mut DiscardWriter::new(msg_len - read_len))?;
+            debug_assert_eq!(read_len + discarded_len, msg_len);
+        }
+        let aux_data = reader_aux.consume(msg_len);
+
+        Ok((read_len, msg_len, aux_data))
+    }
+
+    /// Tries to write a message and the auxiliary data.
+    ///
+    /// This method is used by `SOCK_SEQPACKET` sockets. The message is either written as a whole
+    /// or not written at all.
+    pub(super) fn try_write_message(
+        &self,
+        reader: &mut dyn MultiRead,
+        aux_data: &mut AuxiliaryData,
+    ) -> Result<usize> {
+        let msg_len = reader.sum_lens();
+        if msg_len > DEFAULT_BUF_SIZE {
+            return_errno_with_message!(Errno::EMSGSIZE, "the message is too large");
+        }
+
+        if self.writer.is_shutdown() {
+            return_errno_with_message!(Errno::EPIPE, "the channel is shut down");
+        }
+        // TODO: Linux delivers zero-length messages on `SOCK_SEQPACKET` sockets, but they cannot
+        // be represented in the channel yet.
+        if msg_len == 0 {
+            return Ok(0);
+        }
+
+        // The lock must be held during writing so that the auxiliary data always match the data
+        // in the channel. Since all writers hold the lock, the free space cannot shrink after the
+        // check below.
+        let mut writer_aux = self.writer_aux.lock();
+
+        if self.writer.free_len() < msg_len {
+            return_errno_with_message!(Errno::EAGAIN, "the channel is full");
+        }
+
+        let written_len = self.writer.try_write(reader)?;
+        debug_assert_eq!(written_len, msg_len);
+        writer_aux.push_message(written_len, aux_data);
+
+        Ok(written_len)
     }
 
     pub(super) fn shutdown(&self, cmd: SockShutdownCmd) {
@@ -170,4 +306,176 @@ impl AddrView {
     }
 }
 
+/// A queue of the auxiliary data that are sent along with the data in a channel.
+///
+/// Each record covers a number of consecutive bytes in the channel, and all the records together
+/// cover all the bytes in the channel.
+///
+/// FIXME: If a socket is sent over itself (or over its peer) and is never received, the files
+/// form a reference cycle and will never be dropped. Linux has a garbage collector to deal with
+/// this, but we do not support it yet.
+struct AuxiliaryQueue {
+    records: VecDeque<AuxiliaryRecord>,
+}
+
+struct AuxiliaryRecord {
+    len: usize,
+    aux_data: AuxiliaryData,
+}
+
+impl AuxiliaryQueue {
+    fn new() -> Self {
+        Self {
+            records: VecDeque::new(),
+        }
+    }
+
+    /// Records that `len` bytes are written with the auxiliary data.
+    fn push(&mut self, len: usize, aux_data: &mut AuxiliaryData) {
+        let aux_data = aux_data.take();
+
+        if !aux_data.has_files() {
+            if let Some(last) = self.records.back_mut() {
+                if !last.aux_data.has_files() && last.aux_data.cred() == aux_data.cred() {
+                    last.len += len;
+                    return;
+                }
+            }
+        }
+
+        self.records.push_back(AuxiliaryRecord { len, aux_data });
+    }
+
+    /// Records that a message of `len` bytes is written with the auxiliary data.
+    ///
+    /// Unlike [`Self::push`], the record is never merged with the previous one, so it marks the
+    /// message boundary.
+    fn push_message(&mut self, len: usize, aux_data: &mut AuxiliaryData) {
+        let aux_data = aux_data.take();
+        self.records.push_back(AuxiliaryRecord { len, aux_data });
+    }
+
+    /// Returns the length of the first record, if any.
+    fn front_len(&self) -> Option<usize> {
+        self.records.front().map(|record| record.len)
+    }
+
+    /// Returns the maximum number of bytes that can be read at once.
+    fn max_read_len(&self, is_pass_cred: bool) -> usize {
+        let mut records = self.records.iter();
+
+        let Some(first) = records.next() else {
+            return usize::MAX;
+        };
+        if first.aux_data.has_files() {
+            return first.len;
+        }
+
+        let mut max_len = first.len;
+        for record in records {
+            if is_pass_cred && record.aux_data.cred() != first.aux_data.cred() {
+                break;
+            }
+
+            max_len += record.len;
+            if record.aux_data.has_files() {
+                break;
+            }
+        }
+
+        max_len
+    }
+
+    /// Records that `len` bytes are read and returns the auxiliary data for them.
+    ///
+    /// The files are taken out once any of the data carrying them are read. The credentials are
+    /// the credentials of the first byte.
+    fn consume(&mut self, mut len: usize) -> Option<AuxiliaryData> {
+        if len == 0 {
+            return None;
+        }
+
+        let mut aux_data = self.records.front_mut().unwrap().aux_data.take();
+
+        while len > 0 {
+            let record = self.records.front_mut().unwrap();
+            aux_data.append_files_from(&mut record.aux_data);
+
+            if len < record.len {
+                record.len -= len;
+                break;
+            }
+
+            len -= record.len;
+            self.records.pop_front();
+        }
+
+        Some(aux_data)
+    }
+}
+
+/// A writer that accepts at most a limited number of bytes.
+struct LimitedWriter<'a> {
+    inner: &'a mut dyn MultiWrite,
+    limit: usize,
+}
+
+impl<'a> LimitedWriter<'a> {
+    fn new(inner: &'a mut dyn MultiWrite, limit: usize) -> Self {
+        Self { inner, limit }
+    }
+}
+
+impl MultiWrite for LimitedWriter<'_> {
+    fn write(&mut self, reader: &mut VmReader<'_, Infallible>) -> Result<usize> {
+        reader.limit(self.limit);
+
+        let written_len = self.inner.write(reader)?;
+        self.limit -= written_len;
+
+        Ok(written_len)
+    }
+
+    fn sum_lens(&self) -> usize {
+        self.inner.sum_lens().min(self.limit)
+    }
+
+    fn skip_some(&mut self, nbytes: usize) {
+        let nbytes = nbytes.min(self.limit);
+
+        self.inner.skip_some(nbytes);
+        self.limit -= nbytes;
+    }
+}
+
+/// A writer that discards at most a limited number of bytes.
+struct DiscardWriter {
+    limit: usize,
+}
+
+impl DiscardWriter {
+    fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+}
+
+impl MultiWrite for DiscardWriter {
+    fn write(&mut self, reader: &mut VmReader<'_, Infallible>) -> Result<usize> {
+        let discarded_len = reader.remain().min(self.limit);
+
+        reader.skip(discarded_len);
+        self.limit -= discarded_len;
+
+        Ok(discarded_len)
+    }
+
+    fn sum_lens(&self) -> usize {
+        self.limit
+    }
+
+    fn skip_some(&mut self, nbytes: usize) {
+        self.limit -= nbytes.min(self.limit);
+    }
+}
+
 const DEFAULT_BUF_SIZE: usize = 65536;
@@ -12,35 +12,57 @@ use super::{
 use crate::{
     events::IoEvents,
     fs::file_handle::FileLike,
+    match_sock_option_mut, match_sock_option_ref,
     net::socket::{
+        options::{PassCred, PeerCred, SocketOption},
         private::SocketPrivate,
-        unix::UnixSocketAddr,
+        unix::{
+            ctrl_msg::AuxiliaryData,
+            table::{register_socket, ReportInfo, UnixSocketInfo, UnixSocketState},
+            UnixCredentials, UnixSocketAddr,
+        },
         util::{MessageHeader, SendRecvFlags, SockShutdownCmd, SocketAddr},
         Socket,
     },
     prelude::*,
     process::signal::{PollHandle, Pollable},
-    util::{MultiRead, MultiWrite},
+    util::{net::SockType, MultiRead, MultiWrite},
 };
 
 pub struct UnixStreamSocket {
     state: RwMutex<Takeable<State>>,
     is_nonblocking: AtomicBool,
+    is_pass_cred: AtomicBool,
+    /// Whether the socket is a `SOCK_SEQPACKET` socket, which preserves message boundaries.
+    is_seqpacket: bool,
 }
 
 impl UnixStreamSocket {
-    pub(super) fn new_init(init: Init, is_nonblocking: bool) -> Arc<Self> {
-        Arc::new(Self {
+    pub(super) fn new_init(init: Init, is_nonblocking: bool, is_seqpacket: bool) -> Arc<Self> {
+        let socket = Arc::new(Self {
             state: RwMutex::new(Takeable::new(State::Init(init))),
             is_nonblocking: AtomicBool::new(is_nonblocking),
-        })
+            is_pass_cred: AtomicBool::new(false),
+            is_seqpacket,
+        });
+        register_socket(Arc::downgrade(&socket) as _);
+        socket
     }
 
-    pub(super) fn new_connected(connected: Connected, is_nonblocking: bool) -> Arc<Self> {
-        Arc::new(Self {
+    pub(super) fn new_connected(
+        connected: Connected,
+        is_nonblocking: bool,
+        is_pass_cred: bool,
+        is_seqpacket: bool,
+    ) -> Arc<Self> {
+        let socket = Arc::new(Self {
             state: RwMutex::new(Takeable::new(State::Connected(connected))),
             is_nonblocking: AtomicBool::new(is_nonblocking),
-        })
+            is_pass_cred: AtomicBool::new(is_pass_cred),
+            is_seqpacket,
+        });
+        register_socket(Arc::downgrade(&socket) as _);
+        socket
     }
 }
 
@@ -51,30 +73,70 @@ enum State {
 }
 
 impl UnixStreamSocket {
-    pub fn new(is_nonblocking: bool) -> Arc<Self> {
-        Self::new_init(Init::new(), is_nonblocking)
+    pub fn new(is_nonblocking: bool, is_seqpacket: bool) -> Arc<Self> {
+        Self::new_init(Init::new(), is_nonblocking, is_seqpacket)
     }
 
-    pub fn new_pair(is_nonblocking: bool) -> (Arc<Self>, Arc<Self>) {
-        let (conn_a, conn_b) = Connected::new_pair(None, None, None, None);
+    pub fn new_pair(is_nonblocking: bool, is_seqpacket: bool) -> (Arc<Self>, Arc<Self>) {
+        let cred = UnixCredentials::new_peer();
+        let (conn_a, conn_b) = Connected::new_pair(None, None, cred, cred, None, None);
         (
-            Self::new_connected(conn_a, is_nonblocking),
-            Self::new_connected(conn_b, is_nonblocking),
+            Self::new_connected(conn_a, is_nonblocking, false, is_seqpacket),
+            Self::new_connected(conn_b, is_nonblocking, false, is_seqpacket),
         )
     }
 
-    fn try_send(&self, buf: &mut dyn MultiRead, _flags: SendRecvFlags) -> Result<usize> {
+    fn try_send(
+        &self,
+        buf: &mut dyn MultiRead,
+        aux_data: &mut AuxiliaryData,
+        _flags: SendRecvFlags,
+    ) -> Result<usize> {
         match self.state.read().as_ref() {
-            State::Connected(connected) => connected.try_write(buf),
+            State::Connected(connected) if self.is_seqpacket => {
+                connected.try_write_message(buf, aux_data)
+            }
+            State::Connected(connected) => connected.try_write(buf, aux_data),
             State::Init(_) | State::Listen(_) => {
                 return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected")
             }
         }
     }
 
-    fn try_recv(&self, buf: &mut dyn MultiWrite, _flags: SendRecvFlags) -> Result<usize> {
+    /// Tries to receive data.
+    ///
+    /// On success, this method returns the number of bytes received (or the message length if
+    /// `MSG_TRUNC` is specified for `SOCK_SEQPACKET` sockets), the auxiliary data, and the flags
+    /// that describe the received message.
+    fn try_recv(
+        &self,
+        buf: &mut dyn MultiWrite,
+        flags: SendRecvFlags,
+    ) -> Result<(usize, Option<AuxiliaryData>, SendRecvFlags)> {
         match self.state.read().as_ref() {
-            State::Connected(connected) => connected.try_read(buf),
+            State::Connected(connected) if self.is_seqpacket => {
+                let (read_len, msg_len, aux_data) = connected.try_read_message(buf)?;
+
+                let msg_flags = if read_len < msg_len {
+                    SendRecvFlags::MSG_TRUNC
+                } else {
+                    SendRecvFlags::empty()
+                };
+                let recv_len = if flags.contains(SendRecvFlags::MSG_TRUNC) {
+                    msg_len
+                } else {
+                    read_len
+                };
+
+                Ok((recv_len, aux_data, msg_flags))
+            }
+            State::Connected(connected) => {
+                let (read_len, aux_data) = connected.try_read(buf, self.is_pass_cred())?;
+                Ok((read_len, aux_data, SendRecvFlags::empty()))
+            }
+            State::Init(_) | State::Listen(_) if self.is_seqpacket => {
+                return_errno_with_message!(Errno::ENOTCONN, "the socket is not connected")
+            }
             State::Init(_) | State::Listen(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not connected")
             }
@@ -116,9 +178,13 @@ impl UnixStreamSocket {
         })
     }
 
+    fn is_pass_cred(&self) -> bool {
+        self.is_pass_cred.load(Ordering::Relaxed)
+    }
+
     fn try_accept(&self) -> Result<(Arc<dyn FileLike>, SocketAddr)> {
         match self.state.read().as_ref() {
-            State::Listen(listen) => listen.try_accept() as _,
+            State::Listen(listen) => listen.try_accept(self.is_pass_cred()) as _,
             State::Init(_) | State::Connected(_) => {
                 return_errno_with_message!(Errno::EINVAL, "the socket is not listening")
             }
@@ -126,6 +192,28 @@ impl UnixStreamSocket {
     }
 }
 
+impl ReportInfo for UnixStreamSocket {
+    fn info(&self) -> UnixSocketInfo {
+        let type_ = if self.is_seqpacket {
+            SockType::SOCK_SEQPACKET
+        } else {
+            SockType::SOCK_STREAM
+        };
+
+        let (state, addr) = match self.state.read().as_ref() {
+            State::Init(init) => (UnixSocketState::Unconnected, init.addr().cloned()),
+            State::Listen(listen) => (UnixSocketState::Listening, Some(listen.addr().clone())),
+            State::Connected(connected) => (UnixSocketState::Connected, connected.addr()),
+        };
+
+        UnixSocketInfo {
+            type_,
+            state,
+            addr: addr.into(),
+        }
+    }
+}
+
 impl Pollable for UnixStreamSocket {
     fn poll(&self, mask: IoEvents, poller: Option<&mut PollHandle>) -> IoEvents {
         let inner = self.state.read();
@@ -165,6 +253,13 @@ impl Socket for UnixStreamSocket {
         let remote_addr = UnixSocketAddr::try_from(socket_addr)?.connect()?;
         let backlog = get_backlog(&remote_addr)?;
 
+        if backlog.is_seqpacket() != self.is_seqpacket {
+            return_errno_with_message!(
+                Errno::EPROTOTYPE,
+                "the remote socket is of a different socket type"
+            );
+        }
+
         if self.is_nonblocking() {
             self.try_connect(&backlog)
         } else {
@@ -200,7 +295,7 @@ impl Socket for UnixStreamSocket {
                 }
             };
 
-            let listener = match init.listen(backlog) {
+            let listener = match init.listen(backlog, self.is_seqpacket) {
                 Ok(listener) => listener,
                 Err((err, init)) => {
                     return (State::Init(init), Err(err));
@@ -246,27 +341,63 @@ impl Socket for UnixStreamSocket {
         Ok(peer_addr.into())
     }
 
+    fn get_option(&self, option: &mut dyn SocketOption) -> Result<()> {
+        match_sock_option_mut!(option, {
+            socket_pass_cred: PassCred => {
+                socket_pass_cred.set(self.is_pass_cred());
+            },
+            socket_peer_cred: PeerCred => {
+                let peer_cred = match self.state.read().as_ref() {
+                    State::Init(_) => UnixCredentials::new_unknown(),
+                    State::Listen(listen) => listen.cred(),
+                    State::Connected(connected) => connected.peer_cred(),
+                };
+                socket_peer_cred.set(peer_cred);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to get is unknown")
+        });
+
+        Ok(())
+    }
+
+    fn set_option(&self, option: &dyn SocketOption) -> Result<()> {
+        match_sock_option_ref!(option, {
+            socket_pass_cred: PassCred => {
+                let pass_cred = socket_pass_cred.get().unwrap();
+                self.is_pass_cred.store(*pass_cred, Ordering::Relaxed);
+            },
+            _ => return_errno_with_message!(Errno::ENOPROTOOPT, "the socket option to be set is unknown")
+        });
+
+        Ok(())
+    }
+
     fn sendmsg(
         &self,
         reader: &mut dyn MultiRead,
         message_header: MessageHeader,
         flags: SendRecvFlags,
     ) -> Result<usize> {
-        // TODO: Deal with flags
-        if !flags.is_all_supported() {
+        // TODO: Deal with flags. Every message sent on `SOCK_SEQPACKET` sockets is a record, so
+        // `MSG_EOR` has no additional effect.
+        let unhandled_flags = if self.is_seqpacket {
+            flags - SendRecvFlags::MSG_EOR
+        } else {
+            flags
+        };
+        if !unhandled_flags.is_all_supported() {
             warn!("unsupported flags: {:?}", flags);
         }
 
         let MessageHeader {
-            control_message, ..
+            control_messages, ..
         } = message_header;
 
-        if control_message.is_some() {
-            // TODO: Support sending control message
-            warn!("sending control message is not supported");
-        }
+        let mut aux_data = AuxiliaryData::from_control(control_messages)?;
 
-        self.block_on(IoEvents::OUT, || self.try_send(reader, flags))
+        self.block_on(IoEvents::OUT, || {
+            self.try_send(reader, &mut aux_data, flags)
+        })
     }
 
     fn recvmsg(
@@ -274,16 +405,23 @@ impl Socket for UnixStreamSocket {
         writer: &mut dyn MultiWrite,
         flags: SendRecvFlags,
     ) -> Result<(usize, MessageHeader)> {
-        // TODO: Deal with flags
-        if !flags.is_all_supported() {
+        // TODO: Deal with flags. `MSG_TRUNC` is only handled for `SOCK_SEQPACKET` sockets.
+        let unhandled_flags = if self.is_seqpacket {
+            flags - SendRecvFlags::MSG_TRUNC
+        } else {
+            flags
+        };
+        if !unhandled_flags.is_all_supported() {
             warn!("unsupported flags: {:?}", flags);
         }
 
-        let received_bytes = self.block_on(IoEvents::IN, || self.try_recv(writer, flags))?;
-
-        // TODO: Receive control message
+        let (received_bytes, aux_data, msg_flags) =
+            self.block_on(IoEvents::IN, || self.try_recv(writer, flags))?;
 
-        let message_header = MessageHeader::new(None, None);
+        let control_messages = aux_data
+            .map(|aux_data| aux_data.into_control(self.is_pass_cred()))
+            .unwrap_or_default();
+        let message_header = MessageHeader::new(None, control_messages).with_flags(msg_flags);
 
         Ok((received_bytes, message_header))
     }
@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! A table of all UNIX domain sockets.
+//!
+//! Unlike the namespaces, which only contain the bound sockets, the table contains every UNIX
+//! domain socket in the system. It is only used to report the sockets to the user space (e.g., via
+//! `/proc/net/unix`).
+
+use super::UnixSocketAddr;
+use crate::{prelude::*, util::net::SockType};
+
+/// A snapshot of a UNIX domain socket.
+#[derive(Debug, Clone)]
+pub struct UnixSocketInfo {
+    pub type_: SockType,
+    pub state: UnixSocketState,
+    /// The bound address, which is [`UnixSocketAddr::Unnamed`] if the socket is not bound.
+    pub addr: UnixSocketAddr,
+}
+
+/// The state of a UNIX domain socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnixSocketState {
+    Unconnected,
+    Listening,
+    Connected,
+}
+
+/// A UNIX domain socket that can be reported.
+pub(super) trait ReportInfo: Send + Sync {
+    fn info(&self) -> UnixSocketInfo;
+}
+
+/// Adds a new socket to the table.
+///
+/// The socket is removed from the table automatically after it is dropped.
+pub(super) fn register_socket(socket: Weak<dyn ReportInfo>) {
+    SOCKET_TABLE.insert(socket);
+}
+
+/// Returns the snapshots of all the UNIX domain sockets.
+pub fn unix_socket_infos() -> Vec<UnixSocketInfo> {
+    let sockets = SOCKET_TABLE.alive_sockets();
+
+    // The table lock must be released before querying the sockets, which acquire their own locks.
+    sockets.iter().map(|socket| socket.info()).collect()
+}
+
+static SOCKET_TABLE: SocketTable = SocketTable::new();
+
+/// The minimum number of entries before the dropped sockets are purged from the table.
+const MIN_PURGE_THRESHOLD: usize = 64;
+
+struct SocketTable {
+    inner: SpinLock<SocketTableInner>,
+}
+
+struct SocketTableInner {
+    sockets: Vec<Weak<dyn ReportInfo>>,
+    /// The number of entries at which the dropped sockets will be purged.
+    ///
+    /// The threshold is doubled with respect to the number of live sockets after each purge, so
+    /// that the cost of purging is amortized over insertions.
+    purge_threshold: usize,
+}
+
+impl SocketTable {
+    const fn new() -> Self {
+        Self {
+            inner: SpinLock::new(SocketTableInner {
+                sockets: Vec::new(),
+                purge_threshold: MIN_PURGE_THRESHOLD,
+            }),
+        }
+    }
+
+    fn insert(&self, socket: Weak<dyn ReportInfo>) {
+        let mut inner = self.inner.lock();
+
+        if inner.sockets.len() >= inner.purge_threshold {
+            inner.sockets.retain(|socket| socket.strong_count() > 0);
+            inner.purge_threshold = (inner.sockets.len() * 2).max(MIN_PURGE_THRESHOLD);
+        }
+
+        inner.sockets.push(socket);
+    }
+
+    fn alive_sockets(&self) -> Vec<Arc<dyn ReportInfo>> {
+        let inner = self.inner.lock();
+
+        inner.sockets.iter().filter_map(Weak::upgrade).collect()
+    }
+}
@@ -0,0 +1,264 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Legacy `ioctl` commands that query and configure network interfaces.
+//!
+//! These commands can be issued on any socket. New applications should use netlink route sockets
+//! instead, but tools like BusyBox `ifconfig` still rely on these commands.
+
+use aster_bigtcp::{
+    iface::InterfaceFlags,
+    wire::{Ipv4Address, Ipv4Cidr},
+};
+
+use crate::{
+    current_userspace,
+    fs::utils::IoctlCmd,
+    net::{
+        iface::{all_ifaces, find_iface, Iface},
+        socket::netlink::{set_iface_addr, set_link_flags, set_link_mtu},
+    },
+    prelude::*,
+    util::net::{CSocketAddrFamily, CSocketAddrInet},
+};
+
+/// Handles an interface `ioctl` command.
+///
+/// The caller must make sure that `cmd` is one of the `SIOC*IF*` commands.
+pub fn handle_iface_ioctl(cmd: IoctlCmd, arg: Vaddr) -> Result<i32> {
+    if matches!(cmd, IoctlCmd::SIOCGIFCONF) {
+        get_iface_conf(arg)?;
+        return Ok(0);
+    }
+
+    let user_space = current_userspace!();
+    let mut ifreq = user_space.read_val::<CIfReq>(arg)?;
+
+    if matches!(cmd, IoctlCmd::SIOCGIFNAME) {
+        let index = ifreq.data::<i32>();
+        let iface = find_iface(|iface| iface.index() as i32 == index)
+            .ok_or_else(|| Error::with_message(Errno::ENODEV, "the interface does not exist"))?;
+        ifreq.set_name(iface.name());
+        user_space.write_val(arg, &ifreq)?;
+        return Ok(0);
+    }
+
+    let iface = &ifreq.iface()?;
+
+    match cmd {
+        IoctlCmd::SIOCGIFINDEX => ifreq.set_data(&(iface.index() as i32)),
+        IoctlCmd::SIOCGIFFLAGS => ifreq.set_data(&(iface.flags().bits() as u16)),
+        IoctlCmd::SIOCGIFMTU => ifreq.set_data(&(iface.mtu() as i32)),
+        IoctlCmd::SIOCGIFADDR => {
+            let addr = iface.ipv4_addr().ok_or_else(no_addr_error)?;
+            ifreq.set_data(&CSocketAddrInet::from((addr, 0)));
+        }
+        IoctlCmd::SIOCGIFNETMASK => {
+            let prefix_len = iface.prefix_len().ok_or_else(no_addr_error)?;
+            ifreq.set_data(&CSocketAddrInet::from((netmask(prefix_len), 0)));
+        }
+        IoctlCmd::SIOCGIFHWADDR => {
+            let mut hw_addr = CSocketAddr {
+                sa_family: iface.type_() as u16,
+                sa_data: [0; 14],
+            };
+            if let Some(ether_addr) = iface.hw_addr() {
+                hw_addr.sa_data[..ether_addr.0.len()].copy_from_slice(&ether_addr.0);
+            }
+            ifreq.set_data(&hw_addr);
+        }
+        IoctlCmd::SIOCSIFFLAGS => {
+            let flags = InterfaceFlags::from_bits_truncate(ifreq.data::<u16>() as u32);
+            set_link_flags(iface, flags)?;
+            return Ok(0);
+        }
+        IoctlCmd::SIOCSIFMTU => {
+            let mtu = ifreq.data::<i32>();
+            if mtu < 0 {
+                return_errno_with_message!(Errno::EINVAL, "the MTU is negative");
+            }
+            set_link_mtu(iface, mtu as usize)?;
+            return Ok(0);
+        }
+        IoctlCmd::SIOCSIFADDR => {
+            set_addr(iface, ifreq.inet_addr()?)?;
+            return Ok(0);
+        }
+        IoctlCmd::SIOCSIFNETMASK => {
+            set_netmask(iface, ifreq.inet_addr()?)?;
+            return Ok(0);
+        }
+        IoctlCmd::SIOCSIFHWADDR => {
+            return_errno_with_message!(
+                Errno::EOPNOTSUPP,
+                "changing the hardware address is not supported"
+            );
+        }
+        _ => unreachable!("the command is not an interface command: {:?}", cmd),
+    }
+
+    user_space.write_val(arg, &ifreq)?;
+    Ok(0)
+}
+
+/// Handles `SIOCGIFCONF`, which lists the IPv4 addresses of all interfaces.
+fn get_iface_conf(arg: Vaddr) -> Result<()> {
+    let user_space = current_userspace!();
+    let mut ifconf = user_space.read_val::<CIfConf>(arg)?;
+
+    let ifreq_len = size_of::<CIfReq>();
+    // Collect the ifaces first, because accessing the user space may sleep.
+    let ifaces_with_addr: Vec<_> = all_ifaces()
+        .iter()
+        .filter_map(|iface| {
+            let addr = iface.ipv4_addr()?;
+            Some((iface.clone(), addr))
+        })
+        .collect();
+
+    // If the buffer is null, only the required length is reported.
+    if ifconf.ifc_buf == 0 {
+        ifconf.ifc_len = (ifaces_with_addr.len() * ifreq_len) as i32;
+        user_space.write_val(arg, &ifconf)?;
+        return Ok(());
+    }
+
+    if ifconf.ifc_len < 0 {
+        return_errno_with_message!(Errno::EINVAL, "the buffer length is negative");
+    }
+    let buf_len = ifconf.ifc_len as usize;
+
+    let mut written_len = 0;
+    for (iface, addr) in ifaces_with_addr {
+        if written_len + ifreq_len > buf_len {
+            break;
+        }
+
+        let mut ifreq = CIfReq::new_zeroed();
+        ifreq.set_name(iface.name());
+        ifreq.set_data(&CSocketAddrInet::from((addr, 0)));
+        user_space.write_val(ifconf.ifc_buf as Vaddr + written_len, &ifreq)?;
+
+        written_len += ifreq_len;
+    }
+
+    ifconf.ifc_len = written_len as i32;
+    user_space.write_val(arg, &ifconf)?;
+
+    Ok(())
+}
+
+/// Handles `SIOCSIFADDR`.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/net/ipv4/devinet.c#L1181>.
+fn set_addr(iface: &Arc<Iface>, addr: Ipv4Address) -> Result<()> {
+    // A zero address removes the address of the interface.
+    if addr.is_unspecified() {
+        return set_iface_addr(iface, None);
+    }
+
+    // Like Linux, the prefix length is deduced from the class of the address.
+    let first_octet = addr.octets()[0];
+    let prefix_len = match first_octet.leading_ones() {
+        0 => 8,
+        1 => 16,
+        2 => 24,
+        3 => return_errno_with_message!(Errno::EINVAL, "the address is a multicast address"),
+        _ => 32,
+    };
+
+    set_iface_addr(iface, Some(Ipv4Cidr::new(addr, prefix_len)))
+}
+
+/// Handles `SIOCSIFNETMASK`.
+fn set_netmask(iface: &Arc<Iface>, mask: Ipv4Address) -> Result<()> {
+    let addr = iface.ipv4_addr().ok_or_else(no_addr_error)?;
+
+    let prefix_len = u32::from_be_bytes(mask.octets()).leading_ones() as u8;
+    if netmask(prefix_len) != mask {
+        return_errno_with_message!(Errno::EINVAL, "the network mask is not contiguous");
+    }
+
+    set_iface_addr(iface, Some(Ipv4Cidr::new(addr, prefix_len)))
+}
+
+fn netmask(prefix_len: u8) -> Ipv4Address {
+    let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+    Ipv4Address::from(mask.to_be_bytes())
+}
+
+fn no_addr_error() -> Error {
+    Error::with_message(Errno::EADDRNOTAVAIL, "the interface has no IPv4 address")
+}
+
+/// The size of an interface name, including the trailing null byte.
+const IFNAMSIZ: usize = 16;
+
+/// The request for the interface `ioctl` commands.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if.h#L234>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CIfReq {
+    ifr_name: [u8; IFNAMSIZ],
+    /// The union of the request-specific data, of which `struct ifmap` is the largest member.
+    ifr_data: [u8; 24],
+}
+
+impl CIfReq {
+    fn iface(&self) -> Result<Arc<Iface>> {
+        let name_len = self
+            .ifr_name
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(IFNAMSIZ - 1);
+        let name = &self.ifr_name[..name_len];
+
+        find_iface(|iface| iface.name().as_bytes() == name)
+            .ok_or_else(|| Error::with_message(Errno::ENODEV, "the interface does not exist"))
+    }
+
+    fn set_name(&mut self, name: &str) {
+        let name_len = name.len().min(IFNAMSIZ - 1);
+        self.ifr_name = [0; IFNAMSIZ];
+        self.ifr_name[..name_len].copy_from_slice(&name.as_bytes()[..name_len]);
+    }
+
+    fn data<T: Pod>(&self) -> T {
+        T::from_bytes(&self.ifr_data[..size_of::<T>()])
+    }
+
+    fn set_data<T: Pod>(&mut self, data: &T) {
+        self.ifr_data = [0; 24];
+        self.ifr_data[..size_of::<T>()].copy_from_slice(data.as_bytes());
+    }
+
+    fn inet_addr(&self) -> Result<Ipv4Address> {
+        if self.data::<u16>() != CSocketAddrFamily::AF_INET as u16 {
+            return_errno_with_message!(Errno::EINVAL, "the address family is not AF_INET");
+        }
+
+        let (addr, _) = self.data::<CSocketAddrInet>().into();
+        Ok(addr)
+    }
+}
+
+/// The generic socket address, used for hardware addresses.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/linux/socket.h#L35>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CSocketAddr {
+    sa_family: u16,
+    sa_data: [u8; 14],
+}
+
+/// The request for `SIOCGIFCONF`.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if.h#L285>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CIfConf {
+    ifc_len: i32,
+    _pad: u32,
+    ifc_buf: u64,
+}
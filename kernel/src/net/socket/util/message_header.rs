@@ -1,32 +1,63 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use super::SocketAddr;
-use crate::prelude::*;
+use super::{SendRecvFlags, SocketAddr};
+use crate::{fs::file_handle::FileLike, net::socket::unix::UnixCredentials, prelude::*};
 
 /// Message header used for sendmsg/recvmsg.
 #[derive(Debug)]
 pub struct MessageHeader {
     pub(in crate::net) addr: Option<SocketAddr>,
-    pub(in crate::net) control_message: Option<ControlMessage>,
+    pub(in crate::net) control_messages: Vec<ControlMessage>,
+    pub(in crate::net) flags: SendRecvFlags,
 }
 
 impl MessageHeader {
     /// Creates a new `MessageHeader`.
-    pub const fn new(addr: Option<SocketAddr>, control_message: Option<ControlMessage>) -> Self {
+    pub const fn new(addr: Option<SocketAddr>, control_messages: Vec<ControlMessage>) -> Self {
         Self {
             addr,
-            control_message,
+            control_messages,
+            flags: SendRecvFlags::empty(),
         }
     }
 
+    /// Sets the flags that describe the received message (e.g., `MSG_TRUNC`).
+    pub(in crate::net) fn with_flags(mut self, flags: SendRecvFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
     /// Returns the socket address.
     pub fn addr(&self) -> Option<&SocketAddr> {
         self.addr.as_ref()
     }
+
+    /// Returns the flags that describe the received message.
+    pub fn flags(&self) -> SendRecvFlags {
+        self.flags
+    }
+
+    /// Consumes the header and returns the control messages.
+    pub fn into_control_messages(self) -> Vec<ControlMessage> {
+        self.control_messages
+    }
 }
 
 /// Control message carried by MessageHeader.
 ///
-/// TODO: Implement the struct. The struct is empty now.
-#[derive(Debug)]
-pub struct ControlMessage;
+/// Currently, only the socket-level control messages used by Unix sockets are supported.
+pub enum ControlMessage {
+    /// The open files passed to the peer (`SCM_RIGHTS`).
+    Files(Vec<Arc<dyn FileLike>>),
+    /// The credentials of the sender (`SCM_CREDENTIALS`).
+    Credentials(UnixCredentials),
+}
+
+impl Debug for ControlMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Files(files) => f.debug_tuple("Files").field(&files.len()).finish(),
+            Self::Credentials(cred) => f.debug_tuple("Credentials").field(cred).finish(),
+        }
+    }
+}
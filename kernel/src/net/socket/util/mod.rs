@@ -1,15 +1,19 @@
 // SPDX-License-Identifier: MPL-2.0
 
 pub(super) mod datagram_common;
+mod iface_ioctl;
 mod linger_option;
 mod message_header;
 pub(super) mod options;
 mod send_recv_flags;
 mod shutdown_cmd;
 mod socket_addr;
+mod socket_filter;
 
+pub(super) use iface_ioctl::handle_iface_ioctl;
 pub use linger_option::LingerOption;
-pub use message_header::MessageHeader;
+pub use message_header::{ControlMessage, MessageHeader};
 pub use send_recv_flags::SendRecvFlags;
 pub use shutdown_cmd::SockShutdownCmd;
 pub use socket_addr::SocketAddr;
+pub use socket_filter::{CSockFilter, FilterMeta, SocketFilter};
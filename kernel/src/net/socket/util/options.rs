@@ -1,7 +1,8 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use aster_bigtcp::socket::{
-    NeedIfacePoll, TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN, UDP_RECV_PAYLOAD_LEN, UDP_SEND_PAYLOAD_LEN,
+    NeedIfacePoll, RAW_RECV_BUF_LEN, RAW_SEND_BUF_LEN, TCP_RECV_BUF_LEN, TCP_SEND_BUF_LEN,
+    UDP_RECV_PAYLOAD_LEN, UDP_SEND_PAYLOAD_LEN,
 };
 
 use super::LingerOption;
@@ -50,6 +51,18 @@ impl SocketOptionSet {
         }
     }
 
+    /// Return the default socket level options for raw IP socket.
+    pub fn new_raw() -> Self {
+        Self {
+            reuse_addr: false,
+            reuse_port: false,
+            send_buf: RAW_SEND_BUF_LEN as u32,
+            recv_buf: RAW_RECV_BUF_LEN as u32,
+            linger: LingerOption::default(),
+            keep_alive: false,
+        }
+    }
+
     /// Gets socket-level options.
     ///
     /// Note that the socket error has to be handled separately, because it is automatically
@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Classic BPF socket filters.
+//!
+//! A socket filter is a classic BPF program attached to a socket with `SO_ATTACH_FILTER`. The
+//! program runs on each incoming packet and returns the number of bytes to keep, where zero means
+//! that the packet should be dropped.
+//!
+//! Reference: <https://www.kernel.org/doc/html/v6.13/networking/filter.html>.
+
+use crate::prelude::*;
+
+/// A classic BPF instruction.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/filter.h#L24>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CSockFilter {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32,
+}
+
+/// The metadata of a packet that can be loaded with ancillary data offsets.
+#[derive(Debug, Clone, Copy)]
+pub struct FilterMeta {
+    /// The link-layer protocol in host byte order.
+    pub protocol: u16,
+    /// The packet type (e.g., `PACKET_HOST`).
+    pub pkttype: u8,
+    /// The index of the interface that the packet passes through.
+    pub ifindex: u32,
+}
+
+/// A validated classic BPF program.
+#[derive(Debug, Clone)]
+pub struct SocketFilter {
+    insns: Box<[CSockFilter]>,
+}
+
+impl SocketFilter {
+    /// Creates a socket filter from its instructions.
+    ///
+    /// This method fails with [`EINVAL`] if the program is not valid. A valid program is not empty,
+    /// ends with a return instruction, and never jumps out of itself.
+    ///
+    /// [`EINVAL`]: crate::error::Errno::EINVAL
+    pub fn new(insns: Vec<CSockFilter>) -> Result<Self> {
+        if insns.is_empty() || insns.len() > BPF_MAXINSNS {
+            return_errno_with_message!(Errno::EINVAL, "the filter length is invalid");
+        }
+
+        for (pc, insn) in insns.iter().enumerate() {
+            check_insn(insn, insns.len() - pc - 1)?;
+        }
+
+        if insns.last().unwrap().code & 0x07 != BPF_RET {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "the filter does not end with a return instruction"
+            );
+        }
+
+        Ok(Self {
+            insns: insns.into_boxed_slice(),
+        })
+    }
+
+    /// Runs the filter on a packet.
+    ///
+    /// This method returns the number of bytes to keep. If the return value is zero, the packet
+    /// should be dropped.
+    pub fn run(&self, packet: &[u8], meta: &FilterMeta) -> u32 {
+        let mut a: u32 = 0;
+        let mut x: u32 = 0;
+        let mut mem = [0u32; BPF_MEMWORDS];
+        let mut pc = 0;
+
+        loop {
+            // The validation ensures that we never run out of the program.
+            let insn = &self.insns[pc];
+            pc += 1;
+
+            let k = insn.k;
+            let src = if insn.code & BPF_X != 0 { x } else { k };
+
+            match insn.code & 0x07 {
+                BPF_LD | BPF_LDX => {
+                    let value = match insn.code & 0xe0 {
+                        BPF_IMM => k,
+                        BPF_MEM => mem[k as usize],
+                        BPF_LEN => packet.len() as u32,
+                        BPF_ABS => match load(packet, meta, insn.code, k) {
+                            Some(value) => value,
+                            None => return 0,
+                        },
+                        BPF_IND => match load(packet, meta, insn.code, x.wrapping_add(k)) {
+                            Some(value) => value,
+                            None => return 0,
+                        },
+                        // BPF_MSH: Load the IP header length.
+                        _ => match packet.get(k as usize) {
+                            Some(byte) => ((*byte & 0x0f) as u32) << 2,
+                            None => return 0,
+                        },
+                    };
+                    if insn.code & 0x07 == BPF_LD {
+                        a = value;
+                    } else {
+                        x = value;
+                    }
+                }
+                BPF_ST => mem[k as usize] = a,
+                BPF_STX => mem[k as usize] = x,
+                BPF_ALU => {
+                    a = match insn.code & 0xf0 {
+                        BPF_ADD => a.wrapping_add(src),
+                        BPF_SUB => a.wrapping_sub(src),
+                        BPF_MUL => a.wrapping_mul(src),
+                        BPF_DIV => match a.checked_div(src) {
+                            Some(value) => value,
+                            None => return 0,
+                        },
+                        BPF_MOD => match a.checked_rem(src) {
+                            Some(value) => value,
+                            None => return 0,
+                        },
+                        BPF_OR => a | src,
+                        BPF_AND => a & src,
+                        BPF_XOR => a ^ src,
+                        BPF_LSH => a.checked_shl(src).unwrap_or(0),
+                        BPF_RSH => a.checked_shr(src).unwrap_or(0),
+                        // BPF_NEG
+                        _ => a.wrapping_neg(),
+                    };
+                }
+                BPF_JMP => {
+                    let taken = match insn.code & 0xf0 {
+                        BPF_JA => {
+                            pc += k as usize;
+                            continue;
+                        }
+                        BPF_JEQ => a == src,
+                        BPF_JGT => a > src,
+                        BPF_JGE => a >= src,
+                        // BPF_JSET
+                        _ => a & src != 0,
+                    };
+                    let offset = if taken { insn.jt } else { insn.jf };
+                    pc += offset as usize;
+                }
+                BPF_RET => {
+                    return match insn.code & 0x18 {
+                        BPF_RET_K => k,
+                        BPF_RET_X => x,
+                        // BPF_RET_A
+                        _ => a,
+                    };
+                }
+                // BPF_MISC
+                _ => {
+                    if insn.code & 0xf8 == BPF_TXA {
+                        a = x;
+                    } else {
+                        x = a;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Checks whether an instruction is valid, given the number of instructions after it.
+fn check_insn(insn: &CSockFilter, remaining: usize) -> Result<()> {
+    let code = insn.code;
+    let k = insn.k;
+
+    let is_valid = match code & 0x07 {
+        BPF_LD | BPF_LDX => {
+            let size = code & 0x18;
+            let mode = code & 0xe0;
+            let is_ld = code & 0x07 == BPF_LD;
+            match mode {
+                BPF_IMM | BPF_LEN => size == BPF_W,
+                BPF_MEM => size == BPF_W && (k as usize) < BPF_MEMWORDS,
+                BPF_ABS | BPF_IND => is_ld && size != 0x18,
+                BPF_MSH => !is_ld && size == BPF_B,
+                _ => false,
+            }
+        }
+        BPF_ST | BPF_STX => code & 0xf8 == 0 && (k as usize) < BPF_MEMWORDS,
+        BPF_ALU => {
+            let op = code & 0xf0;
+            let is_const = code & BPF_X == 0;
+            match op {
+                BPF_DIV | BPF_MOD => !is_const || k != 0,
+                BPF_LSH | BPF_RSH => !is_const || k < 32,
+                BPF_ADD | BPF_SUB | BPF_MUL | BPF_OR | BPF_AND | BPF_XOR => true,
+                BPF_NEG => code & BPF_X == 0,
+                _ => false,
+            }
+        }
+        BPF_JMP => match code & 0xf0 {
+            BPF_JA => code & BPF_X == 0 && (k as usize) < remaining,
+            BPF_JEQ | BPF_JGT | BPF_JGE | BPF_JSET => {
+                (insn.jt as usize) < remaining && (insn.jf as usize) < remaining
+            }
+            _ => false,
+        },
+        BPF_RET => code & 0xe0 == 0 && code & 0x18 != 0x18,
+        // BPF_MISC
+        _ => matches!(code & 0xf8, BPF_TAX | BPF_TXA),
+    };
+
+    if !is_valid {
+        return_errno_with_message!(Errno::EINVAL, "the filter contains an invalid instruction");
+    }
+
+    Ok(())
+}
+
+/// Loads a word, a half word, or a byte from the packet or its metadata.
+///
+/// This method returns `None` if the offset is out of bounds or unsupported.
+fn load(packet: &[u8], meta: &FilterMeta, code: u16, offset: u32) -> Option<u32> {
+    if offset >= SKF_AD_OFF {
+        return match offset - SKF_AD_OFF {
+            SKF_AD_PROTOCOL => Some(meta.protocol as u32),
+            SKF_AD_PKTTYPE => Some(meta.pkttype as u32),
+            SKF_AD_IFINDEX => Some(meta.ifindex),
+            _ => None,
+        };
+    }
+
+    let offset = offset as usize;
+    match code & 0x18 {
+        BPF_W => {
+            let bytes = packet.get(offset..offset.checked_add(4)?)?;
+            Some(u32::from_be_bytes(bytes.try_into().unwrap()))
+        }
+        BPF_H => {
+            let bytes = packet.get(offset..offset.checked_add(2)?)?;
+            Some(u16::from_be_bytes(bytes.try_into().unwrap()) as u32)
+        }
+        // BPF_B
+        _ => packet.get(offset).map(|byte| *byte as u32),
+    }
+}
+
+const BPF_MAXINSNS: usize = 4096;
+const BPF_MEMWORDS: usize = 16;
+
+// Instruction classes
+const BPF_LD: u16 = 0x00;
+const BPF_LDX: u16 = 0x01;
+const BPF_ST: u16 = 0x02;
+const BPF_STX: u16 = 0x03;
+const BPF_ALU: u16 = 0x04;
+const BPF_JMP: u16 = 0x05;
+const BPF_RET: u16 = 0x06;
+
+// Load sizes
+const BPF_W: u16 = 0x00;
+const BPF_H: u16 = 0x08;
+const BPF_B: u16 = 0x10;
+
+// Load modes
+const BPF_IMM: u16 = 0x00;
+const BPF_ABS: u16 = 0x20;
+const BPF_IND: u16 = 0x40;
+const BPF_MEM: u16 = 0x60;
+const BPF_LEN: u16 = 0x80;
+const BPF_MSH: u16 = 0xa0;
+
+// ALU operations
+const BPF_ADD: u16 = 0x00;
+const BPF_SUB: u16 = 0x10;
+const BPF_MUL: u16 = 0x20;
+const BPF_DIV: u16 = 0x30;
+const BPF_OR: u16 = 0x40;
+const BPF_AND: u16 = 0x50;
+const BPF_LSH: u16 = 0x60;
+const BPF_RSH: u16 = 0x70;
+const BPF_NEG: u16 = 0x80;
+const BPF_MOD: u16 = 0x90;
+const BPF_XOR: u16 = 0xa0;
+
+// Jump operations
+const BPF_JA: u16 = 0x00;
+const BPF_JEQ: u16 = 0x10;
+const BPF_JGT: u16 = 0x20;
+const BPF_JGE: u16 = 0x30;
+const BPF_JSET: u16 = 0x40;
+
+// Operand sources
+const BPF_X: u16 = 0x08;
+
+// Return values
+const BPF_RET_K: u16 = 0x00;
+const BPF_RET_X: u16 = 0x08;
+
+// Miscellaneous operations
+const BPF_TAX: u16 = 0x00;
+const BPF_TXA: u16 = 0x80;
+
+// Ancillary data offsets
+const SKF_AD_OFF: u32 = -0x1000i32 as u32;
+const SKF_AD_PROTOCOL: u32 = 0;
+const SKF_AD_PKTTYPE: u32 = 4;
+const SKF_AD_IFINDEX: u32 = 8;
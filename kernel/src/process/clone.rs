@@ -379,6 +379,9 @@ fn clone_child_process(
         child.set_exit_signal(sig);
     };
 
+    // The child process inherits the dumpable flag of the parent process.
+    child.set_dumpable(process.dumpable());
+
     // Sets parent process and group for child process.
     set_parent_and_group(process, &child);
 
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::capabilities::CapSet;
+use crate::{
+    prelude::*,
+    process::{
+        posix_thread::{AsPosixThread, PosixThread},
+        Dumpable, Process,
+    },
+};
+
+/// The credentials of the accessing thread that are checked against the target process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtraceCreds {
+    /// The file system user ID and group ID are checked.
+    ///
+    /// This is used when the access goes through the file system (e.g., `/proc/[pid]/mem`).
+    Fs,
+    /// The real user ID and group ID are checked.
+    ///
+    /// This is used when the access goes through system calls (e.g., `process_vm_readv`).
+    Real,
+}
+
+/// Checks whether the `tracer` thread may access the memory and the other sensitive states of
+/// the `target` process, as if the `tracer` were to trace the `target` using `ptrace`.
+///
+/// The access is granted if
+///  - the `tracer` belongs to the `target` process, or
+///  - the user IDs and group IDs of the `tracer` selected by `creds` match the real, effective
+///    and saved-set user IDs and group IDs of the `target`, and the `target` is dumpable, or
+///  - the `tracer` has the `CAP_SYS_PTRACE` capability.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/kernel/ptrace.c#L276>
+pub fn check_ptrace_access(
+    tracer: &PosixThread,
+    target: &Process,
+    creds: PtraceCreds,
+) -> Result<()> {
+    if core::ptr::eq(tracer.process().as_ref(), target) {
+        return Ok(());
+    }
+
+    let tracer_credentials = tracer.credentials();
+    let (tracer_uid, tracer_gid) = match creds {
+        PtraceCreds::Fs => (tracer_credentials.fsuid(), tracer_credentials.fsgid()),
+        PtraceCreds::Real => (tracer_credentials.ruid(), tracer_credentials.rgid()),
+    };
+    let has_cap = tracer_credentials
+        .effective_capset()
+        .contains(CapSet::SYS_PTRACE);
+
+    let target_thread = target.main_thread();
+    let target_credentials = target_thread.as_posix_thread().unwrap().credentials();
+    let is_same_user = tracer_uid == target_credentials.ruid()
+        && tracer_uid == target_credentials.euid()
+        && tracer_uid == target_credentials.suid()
+        && tracer_gid == target_credentials.rgid()
+        && tracer_gid == target_credentials.egid()
+        && tracer_gid == target_credentials.sgid();
+
+    if !is_same_user && !has_cap {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "the credentials of the target process do not match"
+        );
+    }
+
+    if target.dumpable() != Dumpable::User && !has_cap {
+        return_errno_with_message!(Errno::EPERM, "the target process is not dumpable");
+    }
+
+    Ok(())
+}
@@ -28,8 +28,7 @@ pub fn kill(pid: Pid, signal: Option<UserSignal>, ctx: &Context) -> Result<()> {
 
         if !ctx.posix_thread.has_signal_blocked(signal.num()) {
             // Killing the current thread does not raise any permission issues.
-            ctx.posix_thread.enqueue_signal(Box::new(signal));
-            return Ok(());
+            return ctx.posix_thread.enqueue_user_signal(signal);
         }
 
         return kill_process(ctx.process, Some(signal), ctx);
@@ -95,7 +94,7 @@ pub fn tgkill(tid: Tid, tgid: Pid, signal: Option<UserSignal>, ctx: &Context) ->
     if let Some(signal) = signal {
         // We've checked the permission issues above.
         // FIXME: We should take some lock while checking the permission to avoid race conditions.
-        posix_thread.enqueue_signal(Box::new(signal));
+        posix_thread.enqueue_user_signal(signal)?;
     }
 
     Ok(())
@@ -164,9 +163,7 @@ fn kill_process(process: &Process, signal: Option<UserSignal>, ctx: &Context) ->
         return Ok(());
     }
 
-    permitted_thread.enqueue_signal_locked(Box::new(signal), sig_dispositions);
-
-    Ok(())
+    permitted_thread.enqueue_user_signal_locked(signal, sig_dispositions)
 }
 
 fn current_thread_sender_ids(signum: Option<&SigNum>, ctx: &Context) -> SignalSenderIds {
@@ -11,11 +11,14 @@ use super::{
         sig_disposition::SigDispositions,
         sig_mask::{AtomicSigMask, SigMask, SigSet},
         sig_num::SigNum,
-        sig_queues::SigQueues,
-        signals::Signal,
+        sig_queues::{SigPendingCharge, SigQueues},
+        signals::{
+            user::{UserSignal, UserSignalKind},
+            Signal,
+        },
         SigEvents, SigEventsFilter,
     },
-    Credentials, Process,
+    Credentials, Dumpable, Process, ResourceType,
 };
 use crate::{
     events::Observer,
@@ -239,10 +242,57 @@ impl PosixThread {
         signal: Box<dyn Signal>,
         _sig_dispositions: MutexGuard<SigDispositions>,
     ) {
-        self.sig_queues.enqueue(signal);
+        let charge = SigPendingCharge::new(self.credentials().ruid());
+        self.sig_queues.enqueue(signal, charge);
         self.wake_signalled_waker();
     }
 
+    /// Enqueues a thread-directed signal sent by a user process.
+    ///
+    /// This method does not perform permission checks, but it fails with [`Errno::EAGAIN`] if the
+    /// signal should be limited by `RLIMIT_SIGPENDING` and the limit is reached.
+    pub(in crate::process) fn enqueue_user_signal(&self, signal: UserSignal) -> Result<()> {
+        let process = self.process();
+        let sig_dispositions = process.sig_dispositions().lock();
+
+        let signum = signal.num();
+        if sig_dispositions.get(signum).will_ignore(signum) {
+            return Ok(());
+        }
+
+        self.enqueue_user_signal_locked(signal, sig_dispositions)
+    }
+
+    /// Enqueues a thread-directed signal sent by a user process with locked dispositions.
+    ///
+    /// See [`Self::enqueue_signal_locked`] and [`Self::enqueue_user_signal`] for details.
+    pub(in crate::process) fn enqueue_user_signal_locked(
+        &self,
+        signal: UserSignal,
+        _sig_dispositions: MutexGuard<SigDispositions>,
+    ) -> Result<()> {
+        let ruid = self.credentials().ruid();
+
+        // Like Linux, the signals sent by `kill` are never lost due to `RLIMIT_SIGPENDING`, while
+        // the signals sent by `tgkill` and `sigqueue` fail if the limit is reached.
+        let charge = match signal.kind() {
+            UserSignalKind::Kill => SigPendingCharge::new(ruid),
+            UserSignalKind::Tkill | UserSignalKind::Sigqueue(_) => {
+                let limit = self
+                    .process()
+                    .resource_limits()
+                    .get_rlimit(ResourceType::RLIMIT_SIGPENDING)
+                    .get_cur();
+                SigPendingCharge::try_new(ruid, limit)?
+            }
+        };
+
+        self.sig_queues.enqueue(Box::new(signal), charge);
+        self.wake_signalled_waker();
+
+        Ok(())
+    }
+
     /// Returns a reference to the profiling clock of the current thread.
     pub fn prof_clock(&self) -> &Arc<ProfClock> {
         &self.prof_clock
@@ -270,6 +320,12 @@ impl PosixThread {
         self.prof_timer_manager.process_expired_timers();
     }
 
+    /// Checks the `TimerCallback`s that are managed by the `virtual_timer_manager`.
+    /// If any have timed out, call the corresponding callback functions.
+    pub fn process_expired_virtual_timers(&self) {
+        self.virtual_timer_manager.process_expired_timers();
+    }
+
     pub fn dequeue_signal(&self, mask: &SigMask) -> Option<Box<dyn Signal>> {
         self.sig_queues.dequeue(mask)
     }
@@ -303,6 +359,33 @@ impl PosixThread {
         ));
         self.credentials.dup().restrict()
     }
+
+    /// Updates the credentials of the current thread with `f`.
+    ///
+    /// If the effective or file system user IDs or group IDs are changed, the process
+    /// becomes not dumpable, so that it cannot be accessed by the processes with the
+    /// original credentials.
+    pub fn update_credentials<T>(
+        &self,
+        f: impl FnOnce(&Credentials<WriteOp>) -> Result<T>,
+    ) -> Result<T> {
+        let dump_ids = |credentials: &Credentials<ReadOp>| {
+            (
+                credentials.euid(),
+                credentials.fsuid(),
+                credentials.egid(),
+                credentials.fsgid(),
+            )
+        };
+
+        let old_ids = dump_ids(&self.credentials());
+        let res = f(&self.credentials_mut());
+        if dump_ids(&self.credentials()) != old_ids {
+            self.process().set_dumpable(Dumpable::Disable);
+        }
+
+        res
+    }
 }
 
 static POSIX_TID_ALLOCATOR: AtomicU32 = AtomicU32::new(1);
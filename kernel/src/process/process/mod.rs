@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU8, Ordering};
 
 use self::timer_manager::PosixTimerManager;
 use super::{
@@ -38,6 +38,7 @@ use ostd::{sync::WaitQueue, task::Task};
 pub use process_group::ProcessGroup;
 pub use session::Session;
 pub use terminal::Terminal;
+pub use timer_manager::PosixTimer;
 
 /// Process id.
 pub type Pid = u32;
@@ -52,6 +53,31 @@ pub type Sid = u32;
 
 pub type ExitCode = u32;
 
+/// Whether a process can be dumped and accessed by other processes with the same credentials.
+///
+/// See `PR_SET_DUMPABLE` in <https://man7.org/linux/man-pages/man2/prctl.2.html>.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
+pub enum Dumpable {
+    /// The process is not dumpable. Only privileged processes can access it.
+    Disable = 0,
+    /// The process is dumpable as the user of the process.
+    User = 1,
+    /// The process is dumpable as root.
+    Root = 2,
+}
+
+define_atomic_version_of_integer_like_type!(Dumpable, try_from = true, {
+    #[derive(Debug)]
+    struct AtomicDumpable(AtomicU8);
+});
+
+impl From<Dumpable> for u8 {
+    fn from(value: Dumpable) -> Self {
+        value as _
+    }
+}
+
 pub(super) fn init() {
     timer_manager::init();
 }
@@ -109,8 +135,13 @@ pub struct Process {
     /// The signal that should be sent to the parent when this process exits.
     exit_signal: AtomicSigNum,
 
+    /// Whether the process is dumpable.
+    dumpable: AtomicDumpable,
+
     /// A profiling clock measures the user CPU time and kernel CPU time of the current process.
     prof_clock: Arc<ProfClock>,
+    /// A profiling clock accumulating the CPU time of the terminated and waited-for children.
+    children_prof_clock: Arc<ProfClock>,
 
     /// A manager that manages timer resources and utilities of the process.
     timer_manager: PosixTimerManager,
@@ -214,10 +245,12 @@ impl Process {
             sig_dispositions,
             parent_death_signal: AtomicSigNum::new_empty(),
             exit_signal: AtomicSigNum::new_empty(),
+            dumpable: AtomicDumpable::new(Dumpable::User),
             resource_limits,
             nice: AtomicNice::new(nice),
             timer_manager: PosixTimerManager::new(&prof_clock, process_ref),
             prof_clock,
+            children_prof_clock: ProfClock::new(),
         })
     }
 
@@ -245,6 +278,11 @@ impl Process {
         &self.prof_clock
     }
 
+    /// Gets the profiling clock of the terminated and waited-for children of the process.
+    pub fn children_prof_clock(&self) -> &Arc<ProfClock> {
+        &self.children_prof_clock
+    }
+
     /// Gets the timer resources and utilities of the process.
     pub fn timer_manager(&self) -> &PosixTimerManager {
         &self.timer_manager
@@ -610,7 +648,7 @@ impl Process {
     /// chooses an arbitrary thread to which to deliver the signal.
     //
     // TODO: Restrict this method with the access control tool.
-    pub fn enqueue_signal(&self, signal: impl Signal + Clone + 'static) {
+    pub fn enqueue_signal(&self, signal: impl Signal + 'static) {
         if self.status.is_zombie() {
             return;
         }
@@ -666,6 +704,21 @@ impl Process {
         self.exit_signal.as_sig_num()
     }
 
+    // ******************* Dumpable ********************
+
+    /// Returns whether the process is dumpable.
+    ///
+    /// A process that is not dumpable can only be accessed (e.g., via
+    /// `process_vm_readv` or `/proc/[pid]/mem`) by privileged processes.
+    pub fn dumpable(&self) -> Dumpable {
+        self.dumpable.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the process is dumpable.
+    pub fn set_dumpable(&self, dumpable: Dumpable) {
+        self.dumpable.store(dumpable, Ordering::Relaxed);
+    }
+
     // ******************* Status ********************
 
     /// Returns a reference to the process status.
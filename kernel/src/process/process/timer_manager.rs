@@ -1,24 +1,27 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{
-    boxed::Box,
-    sync::{Arc, Weak},
-    vec::Vec,
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
 };
-use core::time::Duration;
 
 use id_alloc::IdAlloc;
 use ostd::{
     arch::{timer::TIMER_FREQ, trap::is_kernel_interrupted},
-    sync::Mutex,
     timer,
 };
 
 use super::Process;
 use crate::{
+    prelude::*,
     process::{
         posix_thread::AsPosixThread,
-        signal::{constants::SIGALRM, signals::kernel::KernelSignal},
+        signal::{
+            constants::{SIGALRM, SIGKILL, SIGXCPU},
+            sig_num::SigNum,
+            signals::{kernel::KernelSignal, timer::TimerOverrun},
+        },
+        ResourceType,
     },
     thread::{
         work_queue::{submit_work_item, work_item::WorkItem},
@@ -71,12 +74,15 @@ fn update_cpu_time() {
             .virtual_timer()
             .timer_manager()
             .process_expired_timers();
+        posix_thread.process_expired_virtual_timers();
     }
     timer_manager
         .prof_timer()
         .timer_manager()
         .process_expired_timers();
     posix_thread.process_expired_timers();
+
+    timer_manager.check_cpu_rlimit(&process);
 }
 
 /// Registers a function to update the CPU clock in processes and
@@ -97,13 +103,50 @@ pub struct PosixTimerManager {
     id_allocator: Mutex<IdAlloc>,
     /// A container managing all POSIX timers created by `timer_create()` syscall
     /// within the process context.
-    posix_timers: Mutex<Vec<Option<Arc<Timer>>>>,
+    posix_timers: Mutex<Vec<Option<Arc<PosixTimer>>>>,
+    /// The CPU time in seconds before which no more `SIGXCPU` will be sent.
+    ///
+    /// Like Linux, `SIGXCPU` is sent once per second after the soft limit of `RLIMIT_CPU` is
+    /// reached.
+    next_xcpu_secs: AtomicU64,
+    /// A callback to send `SIGXCPU` to the process.
+    xcpu_callback: Box<dyn Fn() + Send + Sync>,
+    /// A callback to send `SIGKILL` to the process.
+    kill_callback: Box<dyn Fn() + Send + Sync>,
+}
+
+/// A POSIX timer, i.e., a timer created by `timer_create()` syscall.
+pub struct PosixTimer {
+    timer: Arc<Timer>,
+    overrun: Arc<TimerOverrun>,
+}
+
+impl PosixTimer {
+    /// Creates a POSIX timer.
+    ///
+    /// The `overrun` should be shared with the signals sent by the `timer`.
+    pub fn new(timer: Arc<Timer>, overrun: Arc<TimerOverrun>) -> Self {
+        Self { timer, overrun }
+    }
+
+    /// Returns the underlying timer.
+    pub fn timer(&self) -> &Arc<Timer> {
+        &self.timer
+    }
+
+    /// Returns the overrun count of the last delivered signal.
+    pub fn overrun(&self) -> i32 {
+        self.overrun.last_count()
+    }
 }
 
-fn create_process_timer_callback(process_ref: &Weak<Process>) -> impl Fn() + Clone {
+fn create_process_signal_callback(
+    process_ref: &Weak<Process>,
+    signum: SigNum,
+) -> impl Fn() + Clone {
     let current_process = process_ref.clone();
     let sent_signal = move || {
-        let signal = KernelSignal::new(SIGALRM);
+        let signal = KernelSignal::new(signum);
         if let Some(process) = current_process.upgrade() {
             process.enqueue_signal(signal);
         }
@@ -124,7 +167,7 @@ impl PosixTimerManager {
     pub(super) fn new(prof_clock: &Arc<ProfClock>, process_ref: &Weak<Process>) -> Self {
         const MAX_NUM_OF_POSIX_TIMERS: usize = 10000;
 
-        let callback = create_process_timer_callback(process_ref);
+        let callback = create_process_signal_callback(process_ref, SIGALRM);
 
         let alarm_timer = RealTimeClock::timer_manager().create_timer(callback.clone());
 
@@ -138,6 +181,9 @@ impl PosixTimerManager {
             prof_timer,
             id_allocator: Mutex::new(IdAlloc::with_capacity(MAX_NUM_OF_POSIX_TIMERS)),
             posix_timers: Mutex::new(Vec::new()),
+            next_xcpu_secs: AtomicU64::new(0),
+            xcpu_callback: Box::new(create_process_signal_callback(process_ref, SIGXCPU)),
+            kill_callback: Box::new(create_process_signal_callback(process_ref, SIGKILL)),
         }
     }
 
@@ -172,23 +218,38 @@ impl PosixTimerManager {
         self.virtual_timer.timer_manager().create_timer(func)
     }
 
-    /// Adds a POSIX timer to the managed `posix_timers`, and allocate a timer ID for this timer.
-    /// Return the timer ID.
-    pub fn add_posix_timer(&self, posix_timer: Arc<Timer>) -> usize {
+    /// Allocates a timer ID and adds the POSIX timer created by `new_timer` with the ID.
+    ///
+    /// Returns the timer ID.
+    pub fn add_posix_timer<F>(&self, new_timer: F) -> Result<usize>
+    where
+        F: FnOnce(usize) -> Result<PosixTimer>,
+    {
         let mut timers = self.posix_timers.lock();
         // Holding the lock of `posix_timers` is required to operate the `id_allocator`.
-        let timer_id = self.id_allocator.lock().alloc().unwrap();
+        let Some(timer_id) = self.id_allocator.lock().alloc() else {
+            return_errno_with_message!(Errno::EAGAIN, "too many POSIX timers");
+        };
+
+        let posix_timer = match new_timer(timer_id) {
+            Ok(posix_timer) => posix_timer,
+            Err(err) => {
+                self.id_allocator.lock().free(timer_id);
+                return Err(err);
+            }
+        };
+
         if timers.len() < timer_id + 1 {
             timers.resize(timer_id + 1, None);
         }
         // The ID allocated is not used by any other timers so this index in `timers`
         // must be `None`.
-        timers[timer_id] = Some(posix_timer);
-        timer_id
+        timers[timer_id] = Some(Arc::new(posix_timer));
+        Ok(timer_id)
     }
 
     /// Finds a POSIX timer by the input `timer_id`.
-    pub fn find_posix_timer(&self, timer_id: usize) -> Option<Arc<Timer>> {
+    pub fn find_posix_timer(&self, timer_id: usize) -> Option<Arc<PosixTimer>> {
         let timers = self.posix_timers.lock();
         if timer_id >= timers.len() {
             return None;
@@ -198,7 +259,7 @@ impl PosixTimerManager {
     }
 
     /// Removes the POSIX timer with the ID `timer_id`.
-    pub fn remove_posix_timer(&self, timer_id: usize) -> Option<Arc<Timer>> {
+    pub fn remove_posix_timer(&self, timer_id: usize) -> Option<Arc<PosixTimer>> {
         let mut timers = self.posix_timers.lock();
         if timer_id >= timers.len() {
             return None;
@@ -211,4 +272,27 @@ impl PosixTimerManager {
         }
         timer
     }
+
+    /// Enforces `RLIMIT_CPU` on the process.
+    ///
+    /// Like Linux, `SIGXCPU` is sent when the CPU time reaches the soft limit and then once per
+    /// second, and `SIGKILL` is sent when the CPU time reaches the hard limit.
+    fn check_cpu_rlimit(&self, process: &Process) {
+        let rlimit = process
+            .resource_limits()
+            .get_rlimit(ResourceType::RLIMIT_CPU);
+        let (soft_limit, hard_limit) = (rlimit.get_cur(), rlimit.get_max());
+        if soft_limit == u64::MAX && hard_limit == u64::MAX {
+            return;
+        }
+
+        let cpu_secs = process.prof_clock().read_time().as_secs();
+        if cpu_secs >= hard_limit {
+            (self.kill_callback)();
+        } else if cpu_secs >= soft_limit && cpu_secs >= self.next_xcpu_secs.load(Ordering::Relaxed)
+        {
+            self.next_xcpu_secs.store(cpu_secs + 1, Ordering::Relaxed);
+            (self.xcpu_callback)();
+        }
+    }
 }
@@ -7,7 +7,10 @@ use aster_rights::Full;
 
 use crate::{
     prelude::*,
-    vm::{perms::VmPerms, vmar::Vmar},
+    vm::{
+        perms::VmPerms,
+        vmar::{vm_mapping::SpecialMapping, Vmar},
+    },
 };
 
 /// The base address of user heap
@@ -42,6 +45,7 @@ impl Heap {
                 .new_map(PAGE_SIZE, perms)
                 .unwrap()
                 .offset(self.base)
+                .special(SpecialMapping::Heap)
         };
         vmar_map_options.build()?;
 
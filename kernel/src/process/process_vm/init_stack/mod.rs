@@ -32,7 +32,7 @@ use crate::{
     util::random::getrandom,
     vm::{
         perms::VmPerms,
-        vmar::Vmar,
+        vmar::{vm_mapping::SpecialMapping, Vmar},
         vmo::{Vmo, VmoOptions, VmoRightsOp},
     },
 };
@@ -179,6 +179,7 @@ impl InitStack {
                 .new_map(self.max_size, perms)?
                 .offset(map_addr)
                 .vmo(vmo.dup().to_dyn())
+                .special(SpecialMapping::Stack)
         };
         vmar_map_options.build()?;
 
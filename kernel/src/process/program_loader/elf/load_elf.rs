@@ -25,11 +25,11 @@ use crate::{
         process_vm::{AuxKey, AuxVec, ProcessVm},
         TermStatus,
     },
-    vdso::{vdso_vmo, VDSO_VMO_SIZE},
+    vdso::{vdso_vmo, VDSO_TEXT_OFFSET, VDSO_VMO_SIZE},
     vm::{
         perms::VmPerms,
         util::duplicate_frame,
-        vmar::Vmar,
+        vmar::{vm_mapping::SpecialMapping, Vmar},
         vmo::{CommitFlags, VmoRightsOp},
     },
 };
@@ -301,7 +301,7 @@ fn map_segment_vmo(
     if segment_size != 0 {
         let mut vm_map_options = root_vmar
             .new_map(segment_size, perms)?
-            .vmo(segment_vmo.dup()?)
+            .dentry(elf_file.clone())
             .vmo_offset(segment_offset)
             .vmo_limit(segment_offset + segment_size)
             .can_overwrite(true);
@@ -438,18 +438,29 @@ fn map_vdso_to_vm(process_vm: &ProcessVm) -> Option<Vaddr> {
     let options = root_vmar
         .new_map(VDSO_VMO_SIZE, VmPerms::empty())
         .unwrap()
-        .vmo(vdso_vmo.dup().unwrap());
+        .vmo(vdso_vmo.dup().unwrap())
+        .special(SpecialMapping::Vvar);
 
     let vdso_data_base = options.build().unwrap();
-    let vdso_text_base = vdso_data_base + 0x4000;
+    let vdso_text_base = vdso_data_base + VDSO_TEXT_OFFSET;
 
     let data_perms = VmPerms::READ | VmPerms::WRITE;
     let text_perms = VmPerms::READ | VmPerms::EXEC;
     root_vmar
         .protect(data_perms, vdso_data_base..vdso_data_base + PAGE_SIZE)
         .unwrap();
+
+    // The VDSO text is mapped separately so that it can be told apart from
+    // the VDSO data.
     root_vmar
-        .protect(text_perms, vdso_text_base..vdso_text_base + PAGE_SIZE)
+        .new_map(VDSO_VMO_SIZE - VDSO_TEXT_OFFSET, text_perms)
+        .unwrap()
+        .vmo(vdso_vmo.dup().unwrap())
+        .vmo_offset(VDSO_TEXT_OFFSET)
+        .offset(vdso_text_base)
+        .can_overwrite(true)
+        .special(SpecialMapping::Vdso)
+        .build()
         .unwrap();
     Some(vdso_text_base)
 }
@@ -16,8 +16,10 @@ use crate::prelude::*;
 const RLIM_INFINITY: u64 = u64::MAX;
 const INIT_RLIMIT_NPROC: u64 = 0;
 const INIT_RLIMIT_NICE: u64 = 0;
-const INIT_RLIMIT_SIGPENDING: u64 = 0;
 const INIT_RLIMIT_RTPRIO: u64 = 0;
+// Linux overrides the generic value (zero) with half of the maximum number of threads in
+// `fork_init`. We use a fixed value, which is what Linux computes for a machine with 2 GiB memory.
+const INIT_RLIMIT_SIGPENDING: u64 = 8192;
 // https://github.com/torvalds/linux/blob/fac04efc5c793dccbd07e2d59af9f90b7fc0dca4/include/uapi/linux/fs.h#L37
 const INIT_RLIMIT_NOFILE_CUR: u64 = 1024;
 const INIT_RLIMIT_NOFILE_MAX: u64 = 4096;
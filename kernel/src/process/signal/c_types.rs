@@ -57,6 +57,38 @@ impl siginfo_t {
     pub fn si_addr(&self) -> Vaddr {
         read_union_field!(self, Self, siginfo_fields.sigfault.addr)
     }
+
+    pub fn set_si_pid(&mut self, si_pid: Pid) {
+        self.siginfo_fields.common.first.piduid.pid = si_pid;
+    }
+
+    pub fn si_pid(&self) -> Pid {
+        read_union_field!(self, Self, siginfo_fields.common.first.piduid.pid)
+    }
+
+    pub fn set_si_uid(&mut self, si_uid: Uid) {
+        self.siginfo_fields.common.first.piduid.uid = si_uid;
+    }
+
+    pub fn si_uid(&self) -> Uid {
+        read_union_field!(self, Self, siginfo_fields.common.first.piduid.uid)
+    }
+
+    pub fn set_si_timerid(&mut self, si_timerid: i32) {
+        self.siginfo_fields.common.first.timer.timerid = si_timerid;
+    }
+
+    pub fn set_si_overrun(&mut self, si_overrun: i32) {
+        self.siginfo_fields.common.first.timer.overrun = si_overrun;
+    }
+
+    pub fn set_si_value(&mut self, si_value: sigval_t) {
+        self.siginfo_fields.common.second.value = si_value;
+    }
+
+    pub fn si_value(&self) -> sigval_t {
+        read_union_field!(self, Self, siginfo_fields.common.second.value)
+    }
 }
 
 #[derive(Clone, Copy, Pod)]
@@ -118,6 +150,12 @@ pub union sigval_t {
 }
 
 impl sigval_t {
+    pub fn new_int(sigval_int: i32) -> Self {
+        let mut value = Self::new_zeroed();
+        value.sigval_int = sigval_int;
+        value
+    }
+
     pub fn read_int(&self) -> i32 {
         read_union_field!(self, Self, sigval_int)
     }
@@ -127,6 +165,14 @@ impl sigval_t {
     }
 }
 
+impl Debug for sigval_t {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("sigval_t")
+            .field("sigval_ptr", &self.read_ptr())
+            .finish()
+    }
+}
+
 #[derive(Clone, Copy, Pod)]
 #[repr(C)]
 union siginfo_sigchild_t {
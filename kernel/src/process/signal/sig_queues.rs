@@ -12,6 +12,7 @@ use super::{
 use crate::{
     events::{Observer, Subject},
     prelude::*,
+    process::Uid,
 };
 
 pub struct SigQueues {
@@ -35,11 +36,19 @@ impl SigQueues {
         self.count.load(Ordering::Relaxed) == 0
     }
 
-    pub fn enqueue(&self, signal: Box<dyn Signal>) {
+    /// Enqueues a signal.
+    ///
+    /// The signal stays charged to the user with `charge` until it is dequeued. If the signal is
+    /// discarded because it is a standard signal that is already pending, the charge is released
+    /// immediately.
+    pub fn enqueue(&self, signal: Box<dyn Signal>, charge: SigPendingCharge) {
         let signum = signal.num();
 
         let mut queues = self.queues.lock();
-        if queues.enqueue(signal) {
+        if queues.enqueue(QueuedSignal {
+            signal,
+            _charge: charge,
+        }) {
             self.count.fetch_add(1, Ordering::Relaxed);
             // Avoid holding lock when notifying observers
             drop(queues);
@@ -58,7 +67,7 @@ impl SigQueues {
         if signal.is_some() {
             self.count.fetch_sub(1, Ordering::Relaxed);
         }
-        signal
+        signal.map(|queued| queued.signal)
     }
 
     /// Returns the pending signals
@@ -94,9 +103,69 @@ impl Default for SigQueues {
     }
 }
 
+/// The number of pending signals of each user.
+///
+/// Like Linux, a pending signal is charged to the real user ID of the receiving thread, and the
+/// number is limited by `RLIMIT_SIGPENDING` of the receiving process.
+///
+/// Only the users with pending signals have entries here. An entry is removed once the last
+/// pending signal of the user is released, so the map does not grow with every user ID that has
+/// ever received a signal.
+static USER_SIGPENDING: SpinLock<BTreeMap<Uid, u64>> = SpinLock::new(BTreeMap::new());
+
+/// A pending signal charged to a user.
+///
+/// The charge is released when this object is dropped.
+pub struct SigPendingCharge {
+    uid: Uid,
+}
+
+impl SigPendingCharge {
+    /// Charges a pending signal to the user, ignoring `RLIMIT_SIGPENDING`.
+    ///
+    /// This is used for the signals that must not be lost, e.g., the signals sent by the kernel.
+    pub fn new(uid: Uid) -> Self {
+        *USER_SIGPENDING.lock().entry(uid).or_default() += 1;
+        Self { uid }
+    }
+
+    /// Charges a pending signal to the user if the user has fewer than `limit` pending signals.
+    pub fn try_new(uid: Uid, limit: u64) -> Result<Self> {
+        let mut user_sigpending = USER_SIGPENDING.lock();
+
+        let count = user_sigpending.get(&uid).copied().unwrap_or(0);
+        if count >= limit {
+            return_errno_with_message!(
+                Errno::EAGAIN,
+                "the pending signals reach `RLIMIT_SIGPENDING`"
+            );
+        }
+        user_sigpending.insert(uid, count + 1);
+
+        Ok(Self { uid })
+    }
+}
+
+impl Drop for SigPendingCharge {
+    fn drop(&mut self) {
+        let mut user_sigpending = USER_SIGPENDING.lock();
+
+        let count = user_sigpending.get_mut(&self.uid).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            user_sigpending.remove(&self.uid);
+        }
+    }
+}
+
+struct QueuedSignal {
+    signal: Box<dyn Signal>,
+    _charge: SigPendingCharge,
+}
+
 struct Queues {
-    std_queues: Vec<Option<Box<dyn Signal>>>,
-    rt_queues: Vec<VecDeque<Box<dyn Signal>>>,
+    std_queues: Vec<Option<QueuedSignal>>,
+    rt_queues: Vec<VecDeque<QueuedSignal>>,
 }
 
 impl Queues {
@@ -109,8 +178,8 @@ impl Queues {
         }
     }
 
-    fn enqueue(&mut self, signal: Box<dyn Signal>) -> bool {
-        let signum = signal.num();
+    fn enqueue(&mut self, signal: QueuedSignal) -> bool {
+        let signum = signal.signal.num();
         if signum.is_std() {
             // Standard signals
             //
@@ -140,7 +209,7 @@ impl Queues {
         true
     }
 
-    fn dequeue(&mut self, blocked: &SigMask) -> Option<Box<dyn Signal>> {
+    fn dequeue(&mut self, blocked: &SigMask) -> Option<QueuedSignal> {
         // Deliver standard signals.
         //
         // According to signal(7):
@@ -203,17 +272,21 @@ impl Queues {
         self.std_queues.iter().any(|signal| {
             signal
                 .as_ref()
-                .is_some_and(|signal| !blocked.contains(signal.num()))
-        }) || self.rt_queues.iter().any(|rt_queue| !rt_queue.is_empty())
+                .is_some_and(|queued| !blocked.contains(queued.signal.num()))
+        }) || self.rt_queues.iter().any(|rt_queue| {
+            rt_queue
+                .front()
+                .is_some_and(|queued| !blocked.contains(queued.signal.num()))
+        })
     }
 
-    fn get_std_queue_mut(&mut self, signum: SigNum) -> &mut Option<Box<dyn Signal>> {
+    fn get_std_queue_mut(&mut self, signum: SigNum) -> &mut Option<QueuedSignal> {
         debug_assert!(signum.is_std());
         let idx = (signum.as_u8() - MIN_STD_SIG_NUM) as usize;
         &mut self.std_queues[idx]
     }
 
-    fn get_rt_queue_mut(&mut self, signum: SigNum) -> &mut VecDeque<Box<dyn Signal>> {
+    fn get_rt_queue_mut(&mut self, signum: SigNum) -> &mut VecDeque<QueuedSignal> {
         debug_assert!(signum.is_real_time());
         let idx = (signum.as_u8() - MIN_RT_SIG_NUM) as usize;
         &mut self.rt_queues[idx]
@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+use super::Signal;
+use crate::{
+    prelude::*,
+    process::signal::{
+        c_types::{siginfo_t, sigval_t},
+        constants::SI_TIMER,
+        sig_num::SigNum,
+    },
+};
+
+/// A signal sent by a POSIX timer (i.e., a timer created by `timer_create`) on expiration.
+#[derive(Debug)]
+pub struct TimerSignal {
+    num: SigNum,
+    timer_id: i32,
+    value: sigval_t,
+    overrun: Arc<TimerOverrun>,
+}
+
+impl TimerSignal {
+    /// Creates a signal of the POSIX timer.
+    ///
+    /// The caller should have marked the signal of the timer as pending with
+    /// [`TimerOverrun::try_mark_pending`]. The signal will be marked as not pending when it is
+    /// dropped (i.e., when it is delivered or discarded).
+    pub fn new(num: SigNum, timer_id: i32, value: sigval_t, overrun: Arc<TimerOverrun>) -> Self {
+        Self {
+            num,
+            timer_id,
+            value,
+            overrun,
+        }
+    }
+}
+
+impl Signal for TimerSignal {
+    fn num(&self) -> SigNum {
+        self.num
+    }
+
+    fn to_info(&self) -> siginfo_t {
+        let mut info = siginfo_t::new(self.num, SI_TIMER);
+        info.set_si_timerid(self.timer_id);
+        info.set_si_overrun(self.overrun.pending_count());
+        info.set_si_value(self.value);
+        info
+    }
+}
+
+impl Drop for TimerSignal {
+    fn drop(&mut self) {
+        self.overrun.clear_pending();
+    }
+}
+
+/// The overrun count of a POSIX timer.
+///
+/// Like Linux, a POSIX timer has at most one pending signal. If the timer expires while its signal
+/// is still pending, no new signal is sent but the overrun count is incremented.
+#[derive(Debug, Default)]
+pub struct TimerOverrun {
+    is_pending: AtomicBool,
+    /// The number of the expirations while the signal is pending.
+    pending_count: AtomicI32,
+    /// The overrun count of the last delivered signal.
+    last_count: AtomicI32,
+}
+
+impl TimerOverrun {
+    /// Marks the signal of the timer as pending.
+    ///
+    /// If the signal is already pending, this method increments the overrun count and returns
+    /// `false`. Otherwise, the caller should send a new [`TimerSignal`].
+    pub fn try_mark_pending(&self) -> bool {
+        if !self.is_pending.swap(true, Ordering::AcqRel) {
+            return true;
+        }
+
+        // The overrun count saturates at `DELAYTIMER_MAX` (i.e., `i32::MAX`).
+        let _ = self
+            .pending_count
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                count.checked_add(1)
+            });
+        false
+    }
+
+    /// Returns the overrun count of the last delivered signal.
+    ///
+    /// This is the value returned by `timer_getoverrun`.
+    pub fn last_count(&self) -> i32 {
+        self.last_count.load(Ordering::Relaxed)
+    }
+
+    fn pending_count(&self) -> i32 {
+        self.pending_count.load(Ordering::Relaxed)
+    }
+
+    fn clear_pending(&self) {
+        let count = self.pending_count.swap(0, Ordering::Relaxed);
+        self.last_count.store(count, Ordering::Relaxed);
+        self.is_pending.store(false, Ordering::Release);
+    }
+}
@@ -91,7 +91,7 @@ pub fn do_wait(
 
                 if let Some(status) = wait_zombie(&unwaited_children) {
                     if !wait_options.contains(WaitOptions::WNOWAIT) {
-                        reap_zombie_child(status.pid(), &mut children_lock);
+                        reap_zombie_child(ctx.process, status.pid(), &mut children_lock);
                     }
                     return Some(Ok(Some(status)));
                 }
@@ -136,6 +136,10 @@ impl WaitStatus {
         self.process().prof_clock()
     }
 
+    pub fn children_prof_clock(&self) -> &Arc<ProfClock> {
+        self.process().children_prof_clock()
+    }
+
     fn process(&self) -> &Arc<Process> {
         match self {
             WaitStatus::Zombie(process)
@@ -177,10 +181,26 @@ fn wait_stopped_or_continued(
 }
 
 /// Free zombie child with pid, returns the exit code of child process.
-fn reap_zombie_child(pid: Pid, children_lock: &mut BTreeMap<Pid, Arc<Process>>) -> ExitCode {
+fn reap_zombie_child(
+    parent: &Process,
+    pid: Pid,
+    children_lock: &mut BTreeMap<Pid, Arc<Process>>,
+) -> ExitCode {
     let child_process = children_lock.remove(&pid).unwrap();
     assert!(child_process.status().is_zombie());
 
+    // Account the CPU time of the child, including the time of its own waited-for children, to
+    // the parent.
+    let child_clock = child_process.prof_clock();
+    let child_children_clock = child_process.children_prof_clock();
+    let parent_children_clock = parent.children_prof_clock();
+    parent_children_clock.user_clock().add_time(
+        child_clock.user_clock().read_time() + child_children_clock.user_clock().read_time(),
+    );
+    parent_children_clock.kernel_clock().add_time(
+        child_clock.kernel_clock().read_time() + child_children_clock.kernel_clock().read_time(),
+    );
+
     for task in child_process.tasks().lock().as_slice() {
         thread_table::remove_thread(task.as_posix_thread().unwrap().tid());
     }
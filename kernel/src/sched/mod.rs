@@ -6,6 +6,8 @@ mod stats;
 
 pub use self::{
     nice::{AtomicNice, Nice},
-    sched_class::{init, RealTimePolicy, RealTimePriority, SchedAttr, SchedPolicy},
-    stats::{loadavg, nr_queued_and_running},
+    sched_class::{
+        init, init_on_ap, DeadlineParams, RealTimePolicy, RealTimePriority, SchedAttr, SchedPolicy,
+    },
+    stats::{cpu_sched_stats, loadavg, nr_queued_and_running},
 };
@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Load balancing between the per-CPU run queues.
+//!
+//! A thread is placed on a CPU when it is spawned or woken up (see
+//! [`ClassScheduler::select_cpu`]). After that, threads that are waiting in
+//! the FAIR run queue of a busy CPU can be pulled by other CPUs:
+//!
+//! - periodically, from the timer interrupt of every CPU (see
+//!   [`ClassScheduler::balance_tick`]). Since the tick of an idle CPU is
+//!   stopped, a busy CPU with waiting threads kicks an idle CPU that the
+//!   threads are allowed to run on, at most once in a balancing interval of
+//!   the idle CPUs;
+//! - when a CPU is about to run out of threads to run, which is known as
+//!   the newly-idle balancing.
+//!
+//! Only the FAIR class is balanced. A thread is only migrated to a CPU in its
+//! affinity mask, and cache-hot threads are skipped unless the balancing has
+//! failed repeatedly.
+//!
+//! A thread in the DEADLINE class is bound to the CPU where its bandwidth is
+//! reserved. If it is found on another CPU (e.g., because its bandwidth was
+//! reserved while it was running elsewhere), it is pushed to the reserved CPU
+//! when it stops running (see [`ClassScheduler::push_deadline`]).
+//!
+//! All the remote run queues are accessed with `try_lock`, so the balancing
+//! can be performed while holding the local run queue lock without worrying
+//! about lock ordering.
+
+use core::sync::atomic::Ordering;
+
+use ostd::{
+    arch::read_tsc as sched_clock,
+    cpu::{CpuId, CpuSet, PinCurrentCpu},
+    smp::inter_processor_call,
+    task::{
+        scheduler::{info::CommonSchedInfo, EnqueueFlags},
+        Task,
+    },
+    timer::Jiffies,
+    trap::irq::disable_local,
+};
+
+use super::{ClassScheduler, PerCpuClassRqSet, SchedClassRq, SchedEntity, SchedPolicyKind};
+use crate::{
+    sched::stats::CpuIdleType,
+    thread::{AsThread, Thread},
+};
+
+/// The interval of periodic balancing on an idle CPU, in jiffies.
+const IDLE_BALANCE_INTERVAL: u64 = 4;
+
+/// The interval of periodic balancing on a busy CPU, in jiffies.
+const BUSY_BALANCE_INTERVAL: u64 = 32;
+
+/// The number of failed balancing attempts after which cache-hot threads
+/// can also be migrated.
+const CACHE_NICE_TRIES: u32 = 1;
+
+impl ClassScheduler {
+    /// Selects the CPU that a spawned or woken-up thread should run on.
+    pub(super) fn select_cpu(&self, thread: &Thread, flags: EnqueueFlags) -> CpuId {
+        let guard = disable_local();
+        let affinity = thread.atomic_cpu_affinity().load(Ordering::Relaxed);
+
+        if let Some(deadline_cpu) = thread.sched_attr().deadline_cpu() {
+            return deadline_cpu;
+        }
+
+        if let Some(last_cpu) = thread.sched_attr().last_cpu() {
+            return self.select_idle_sibling(thread, last_cpu, &affinity, guard.current_cpu());
+        }
+        debug_assert!(flags == EnqueueFlags::Spawn);
+
+        let selected = self.select_least_loaded(&affinity, guard.current_cpu());
+        self.last_chosen_cpu.set_anyway(selected);
+        selected
+    }
+
+    /// Selects the CPU for a woken-up thread that last ran on `prev`.
+    ///
+    /// The previous CPU is preferred if it is idle or if the thread is still
+    /// cache-hot there. Otherwise, an idle CPU is searched starting from the
+    /// waker's CPU.
+    fn select_idle_sibling(
+        &self,
+        thread: &Thread,
+        prev: CpuId,
+        affinity: &CpuSet,
+        waker: CpuId,
+    ) -> CpuId {
+        let prev_allowed = affinity.contains(prev);
+        if prev_allowed {
+            if self.rqs[prev.as_usize()].lock().is_idle() {
+                return prev;
+            }
+            if thread.sched_attr().is_cache_hot(sched_clock()) {
+                return prev;
+            }
+        }
+
+        let candidates = Some(waker)
+            .filter(|cpu| affinity.contains(*cpu))
+            .into_iter()
+            .chain(affinity.iter().filter(|cpu| *cpu != waker));
+        for candidate in candidates {
+            if candidate != prev && self.rqs[candidate.as_usize()].lock().is_idle() {
+                return candidate;
+            }
+        }
+
+        if prev_allowed {
+            prev
+        } else {
+            self.select_least_loaded(affinity, waker)
+        }
+    }
+
+    fn select_least_loaded(&self, affinity: &CpuSet, fallback: CpuId) -> CpuId {
+        let mut selected = fallback;
+        let mut minimum_load = u32::MAX;
+        let last_chosen = match self.last_chosen_cpu.get() {
+            Some(cpu) => cpu.as_usize() as isize,
+            None => -1,
+        };
+        // Simulate a round-robin selection starting from the last chosen CPU.
+        //
+        // It still checks every CPU to find the one with the minimum load, but
+        // avoids keeping selecting the same CPU when there are multiple equally
+        // idle CPUs.
+        let affinity_iter = affinity
+            .iter()
+            .filter(|&cpu| cpu.as_usize() as isize > last_chosen)
+            .chain(
+                affinity
+                    .iter()
+                    .filter(|&cpu| cpu.as_usize() as isize <= last_chosen),
+            );
+        for candidate in affinity_iter {
+            let rq = self.rqs[candidate.as_usize()].lock();
+            let (load, _) = rq.nr_queued_and_running();
+            if load < minimum_load {
+                minimum_load = load;
+                selected = candidate;
+            }
+        }
+        selected
+    }
+
+    /// Performs the periodic load balancing for the current CPU.
+    ///
+    /// This is called in the timer interrupt handler of every CPU.
+    pub(super) fn balance_tick(&self) {
+        let guard = disable_local();
+        let this_cpu = guard.current_cpu();
+        let Some(mut this_rq) = self.rqs[this_cpu.as_usize()].try_lock() else {
+            return;
+        };
+
+        let now = Jiffies::elapsed().as_u64();
+        if now < this_rq.next_balance {
+            return;
+        }
+
+        let (idle, interval) = if this_rq.is_idle() {
+            (CpuIdleType::Idle, IDLE_BALANCE_INTERVAL)
+        } else {
+            (CpuIdleType::NotIdle, BUSY_BALANCE_INTERVAL)
+        };
+        this_rq.next_balance = now + interval;
+
+        self.balance(&mut this_rq, idle);
+
+        if idle == CpuIdleType::NotIdle && !this_rq.fair.is_empty() {
+            let allowed_cpus = this_rq.fair.allowed_cpus();
+            drop(this_rq);
+            self.kick_idle_cpu(now, &allowed_cpus);
+        }
+    }
+
+    /// Pushes a thread in the DEADLINE class that is leaving the CPU
+    /// `this_cpu` to the CPU that the thread is bound to.
+    ///
+    /// Returns the thread if it should be put back to `this_cpu`, which is the
+    /// case if the thread is already on the right CPU or if the run queue of
+    /// the right CPU is contended. In the latter case, the thread will be
+    /// pushed the next time it stops running.
+    pub(super) fn push_deadline(
+        &self,
+        this_cpu: CpuId,
+        entity: SchedEntity,
+    ) -> Option<SchedEntity> {
+        let Some(cpu) = entity
+            .1
+            .sched_attr()
+            .deadline_cpu()
+            .filter(|cpu| *cpu != this_cpu)
+        else {
+            return Some(entity);
+        };
+        let Some(mut rq) = self.rqs[cpu.as_usize()].try_lock() else {
+            return Some(entity);
+        };
+
+        entity.1.sched_attr().set_last_cpu(cpu);
+        entity.0.cpu().set_anyway(cpu);
+        rq.enqueue_entity(entity, None);
+        drop(rq);
+
+        // This is rare, so the CPU is simply kicked to reschedule.
+        inter_processor_call(&CpuSet::from(cpu), || {});
+        None
+    }
+
+    /// Wakes up an idle CPU in `allowed_cpus` so that it can pull the waiting
+    /// threads.
+    ///
+    /// The woken-up CPU restarts its tick, which performs the periodic
+    /// balancing immediately if the balancing is overdue. The kicks are
+    /// rate-limited globally, so at most one idle CPU is kicked in every
+    /// [`IDLE_BALANCE_INTERVAL`].
+    fn kick_idle_cpu(&self, now: u64, allowed_cpus: &CpuSet) {
+        let next_kick = self.next_idle_kick.load(Ordering::Relaxed);
+        if now < next_kick {
+            return;
+        }
+
+        let Some(idle_cpu) = allowed_cpus.iter().find(|cpu| {
+            self.rqs[cpu.as_usize()]
+                .try_lock()
+                .is_some_and(|rq| rq.is_idle())
+        }) else {
+            return;
+        };
+
+        // Another CPU may be kicking an idle CPU at the same time.
+        if self
+            .next_idle_kick
+            .compare_exchange(
+                next_kick,
+                now + IDLE_BALANCE_INTERVAL,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        inter_processor_call(&CpuSet::from(idle_cpu), || {});
+    }
+
+    /// Pulls threads from the busiest CPU to `this_rq`.
+    ///
+    /// Returns the number of threads that have been pulled.
+    pub(super) fn balance(&self, this_rq: &mut PerCpuClassRqSet, idle: CpuIdleType) -> u32 {
+        let this_cpu = this_rq.cpu;
+        let this_load = this_rq.load();
+        this_rq.stats.lb[idle as usize].lb_count += 1;
+
+        // Find the busiest CPU that has FAIR threads waiting to run.
+        let mut busiest: Option<(CpuId, u32)> = None;
+        for (index, rq) in self.rqs.iter().enumerate() {
+            if index == this_cpu.as_usize() {
+                continue;
+            }
+            let Some(rq) = rq.try_lock() else {
+                continue;
+            };
+            let load = rq.load();
+            if rq.fair.is_empty() || load < this_load + 2 {
+                continue;
+            }
+            if busiest.is_none_or(|(_, busiest_load)| load > busiest_load) {
+                busiest = Some((rq.cpu, load));
+            }
+        }
+
+        let Some((busiest_cpu, busiest_load)) = busiest else {
+            let stats = &mut this_rq.stats.lb[idle as usize];
+            stats.lb_nobusyq += 1;
+            stats.lb_balanced += 1;
+            this_rq.nr_balance_failed = 0;
+            return 0;
+        };
+
+        let imbalance = (busiest_load - this_load) / 2;
+        this_rq.stats.lb[idle as usize].lb_imbalance += u64::from(imbalance);
+
+        let mut nr_pulled = 0;
+        let mut nr_hot_pulled = 0;
+        if let Some(mut busiest_rq) = self.rqs[busiest_cpu.as_usize()].try_lock() {
+            let allow_hot = this_rq.nr_balance_failed > CACHE_NICE_TRIES;
+            let now = sched_clock();
+
+            let tasks = busiest_rq
+                .fair
+                .detach_migratable(imbalance as usize, |task| {
+                    can_migrate(task, this_cpu, allow_hot, now)
+                });
+            for task in tasks {
+                let thread = task.as_thread().unwrap();
+                if thread.sched_attr().is_cache_hot(now) {
+                    nr_hot_pulled += 1;
+                }
+                thread.sched_attr().set_last_cpu(this_cpu);
+                task.cpu().set_anyway(this_cpu);
+                this_rq.fair.attach_migrated(task);
+
+                nr_pulled += 1;
+            }
+        }
+
+        let stats = &mut this_rq.stats.lb[idle as usize];
+        if nr_pulled == 0 {
+            stats.lb_failed += 1;
+            this_rq.nr_balance_failed += 1;
+        } else {
+            stats.lb_gained += u64::from(nr_pulled);
+            stats.lb_hot_gained += nr_hot_pulled;
+            this_rq.nr_balance_failed = 0;
+        }
+
+        nr_pulled
+    }
+}
+
+/// Checks whether a waiting task can be migrated to `dst_cpu`.
+fn can_migrate(task: &Task, dst_cpu: CpuId, allow_hot: bool, now: u64) -> bool {
+    let Some(thread) = task.as_thread() else {
+        return false;
+    };
+
+    if thread.sched_attr().policy_kind() != SchedPolicyKind::Fair {
+        return false;
+    }
+
+    if !thread
+        .atomic_cpu_affinity()
+        .contains(dst_cpu, Ordering::Relaxed)
+    {
+        return false;
+    }
+
+    allow_hot || !thread.sched_attr().is_cache_hot(now)
+}
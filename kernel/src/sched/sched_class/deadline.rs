@@ -0,0 +1,514 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::{boxed::Box, collections::BinaryHeap};
+use core::{
+    cmp::{self, Reverse},
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering::Relaxed},
+    time::Duration,
+};
+
+use ostd::{
+    arch::read_tsc as sched_clock,
+    cpu::{num_cpus, CpuId, CpuSet, PinCurrentCpu},
+    task::{
+        scheduler::{EnqueueFlags, UpdateFlags},
+        AtomicCpuId, Task,
+    },
+    timer,
+    trap::irq::disable_local,
+};
+use spin::Once;
+
+use super::{
+    time::{clocks_to_ns, ns_to_clocks},
+    CurrentRuntime, SchedAttr, SchedClassRq,
+};
+use crate::{prelude::*, thread::AsThread};
+
+/// The minimum runtime, measured in nanoseconds.
+const MIN_RUNTIME_NS: u64 = 1 << 10;
+/// The minimum period, measured in nanoseconds.
+const MIN_PERIOD_NS: u64 = 100_000;
+/// The maximum period, measured in nanoseconds.
+const MAX_PERIOD_NS: u64 = (1 << 22) * 1_000;
+
+/// The number of fractional bits of bandwidths.
+const BW_SHIFT: u32 = 20;
+/// The maximum total bandwidth on a single CPU, i.e., 95%.
+const MAX_BW_PER_CPU: u64 = (95 << BW_SHIFT) / 100;
+
+/// The total bandwidth of the threads in the DEADLINE class reserved on each CPU.
+///
+/// The load balancer does not migrate the threads in the DEADLINE class.
+/// Instead, each thread is bound to the CPU where its bandwidth is reserved,
+/// so the bandwidth is admitted per CPU.
+static RESERVED_BW: Once<Box<[AtomicU64]>> = Once::new();
+
+fn reserved_bw(cpu: CpuId) -> &'static AtomicU64 {
+    let reserved_bw =
+        RESERVED_BW.call_once(|| (0..num_cpus()).map(|_| AtomicU64::new(0)).collect());
+    &reserved_bw[cpu.as_usize()]
+}
+
+/// The parameters of the DEADLINE scheduling policy, measured in nanoseconds.
+///
+/// A thread with these parameters is guaranteed to receive `runtime` of CPU
+/// time in every `period`, and the CPU time is received before the relative
+/// `deadline` in the period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeadlineParams {
+    runtime: u64,
+    deadline: u64,
+    period: u64,
+    overrun_signal: bool,
+}
+
+impl DeadlineParams {
+    /// Creates new DEADLINE parameters.
+    ///
+    /// If `period` is zero, `deadline` is used as the period. If
+    /// `overrun_signal` is true, the thread will receive a `SIGXCPU` when it
+    /// overruns its runtime.
+    pub fn new(
+        runtime: u64,
+        deadline: u64,
+        period: u64,
+        overrun_signal: bool,
+    ) -> core::result::Result<Self, &'static str> {
+        let period = if period == 0 { deadline } else { period };
+
+        if deadline == 0 {
+            return Err("the deadline is zero");
+        }
+        if runtime < MIN_RUNTIME_NS {
+            return Err("the runtime is too small");
+        }
+        if !(runtime <= deadline && deadline <= period) {
+            return Err("the runtime, deadline and period are not in order");
+        }
+        if !(MIN_PERIOD_NS..=MAX_PERIOD_NS).contains(&period) {
+            return Err("the period is out of range");
+        }
+
+        Ok(Self {
+            runtime,
+            deadline,
+            period,
+            overrun_signal,
+        })
+    }
+
+    pub fn runtime(&self) -> u64 {
+        self.runtime
+    }
+
+    pub fn deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    pub fn overrun_signal(&self) -> bool {
+        self.overrun_signal
+    }
+
+    /// Returns the bandwidth, i.e., `runtime / period`, as a fixed-point number.
+    fn bandwidth(&self) -> u64 {
+        ((u128::from(self.runtime) << BW_SHIFT) / u128::from(self.period)) as u64
+    }
+}
+
+/// The scheduling attribute for the DEADLINE scheduling class.
+///
+/// The DEADLINE class schedules threads with EDF (Earliest Deadline First).
+/// The CPU time of each thread is reserved with CBS (Constant Bandwidth
+/// Server): a thread that has consumed its runtime is throttled until its
+/// current deadline, after which its runtime is replenished and its deadline
+/// is postponed by a period.
+///
+/// All the time values here are measured in [`sched_clock`]s.
+#[derive(Debug)]
+pub struct DeadlineAttr {
+    runtime: AtomicU64,
+    deadline: AtomicU64,
+    period: AtomicU64,
+    overrun_signal: AtomicBool,
+    /// The bandwidth accounted in [`RESERVED_BW`], zero if the policy is not DEADLINE.
+    bandwidth: AtomicU64,
+    /// The CPU where the bandwidth is reserved, `None` if the policy is not DEADLINE.
+    reserved_cpu: AtomicCpuId,
+
+    /// The runtime left in the current period.
+    remaining: AtomicI64,
+    /// The absolute deadline of the current period.
+    abs_deadline: AtomicU64,
+    /// Whether a runtime overrun is waiting to be reported with `SIGXCPU`.
+    overrun_pending: AtomicBool,
+}
+
+impl DeadlineAttr {
+    pub fn new(params: Option<DeadlineParams>, affinity: &CpuSet) -> Self {
+        let this = Self {
+            runtime: AtomicU64::new(0),
+            deadline: AtomicU64::new(0),
+            period: AtomicU64::new(0),
+            overrun_signal: AtomicBool::new(false),
+            bandwidth: AtomicU64::new(0),
+            reserved_cpu: AtomicCpuId::default(),
+            remaining: AtomicI64::new(0),
+            abs_deadline: AtomicU64::new(0),
+            overrun_pending: AtomicBool::new(false),
+        };
+        if let Some(params) = params {
+            // Threads created by the kernel are trusted, so no admission control is applied.
+            let cpu = affinity
+                .iter()
+                .min_by_key(|cpu| reserved_bw(*cpu).load(Relaxed))
+                .unwrap_or(CpuId::bsp());
+            reserved_bw(cpu).fetch_add(params.bandwidth(), Relaxed);
+            this.bandwidth.store(params.bandwidth(), Relaxed);
+            this.reserved_cpu.set_anyway(cpu);
+            this.set_params(&params);
+        }
+        this
+    }
+
+    /// Updates the DEADLINE parameters, or clears them if `params` is `None`.
+    ///
+    /// The bandwidth is reserved on a CPU in `affinity`, preferably the CPU
+    /// where it is already reserved, and then `preferred_cpu`. The update is
+    /// rejected with `EBUSY` if no such CPU can admit the bandwidth.
+    pub fn update(
+        &self,
+        params: Option<&DeadlineParams>,
+        affinity: &CpuSet,
+        preferred_cpu: Option<CpuId>,
+    ) -> Result<()> {
+        let new_bw = params.map_or(0, DeadlineParams::bandwidth);
+        let old_bw = self.bandwidth.load(Relaxed);
+        let old_cpu = self.reserved_cpu.get();
+
+        let Some(params) = params else {
+            if let Some(old_cpu) = old_cpu {
+                reserved_bw(old_cpu).fetch_sub(old_bw, Relaxed);
+            }
+            self.bandwidth.store(0, Relaxed);
+            self.reserved_cpu.set_to_none();
+            return Ok(());
+        };
+
+        let mut candidates = [old_cpu, preferred_cpu]
+            .into_iter()
+            .flatten()
+            .chain(affinity.iter())
+            .filter(|cpu| affinity.contains(*cpu));
+        let new_cpu = candidates
+            .find(|cpu| {
+                let released_bw = if Some(*cpu) == old_cpu { old_bw } else { 0 };
+                reserved_bw(*cpu)
+                    .fetch_update(Relaxed, Relaxed, |total| {
+                        let total = total - released_bw + new_bw;
+                        (total <= MAX_BW_PER_CPU).then_some(total)
+                    })
+                    .is_ok()
+            })
+            .ok_or_else(|| Error::with_message(Errno::EBUSY, "the bandwidth is not available"))?;
+
+        if let Some(old_cpu) = old_cpu.filter(|old_cpu| *old_cpu != new_cpu) {
+            reserved_bw(old_cpu).fetch_sub(old_bw, Relaxed);
+        }
+        self.bandwidth.store(new_bw, Relaxed);
+        self.reserved_cpu.set_anyway(new_cpu);
+
+        self.set_params(params);
+        Ok(())
+    }
+
+    /// Returns the CPU where the bandwidth is reserved, which the thread is
+    /// bound to.
+    pub(super) fn reserved_cpu(&self) -> Option<CpuId> {
+        self.reserved_cpu.get()
+    }
+
+    fn set_params(&self, params: &DeadlineParams) {
+        self.runtime.store(ns_to_clocks(params.runtime), Relaxed);
+        self.deadline.store(ns_to_clocks(params.deadline), Relaxed);
+        self.period.store(ns_to_clocks(params.period), Relaxed);
+        self.overrun_signal.store(params.overrun_signal, Relaxed);
+        // Start a new period at the next enqueue.
+        self.remaining.store(0, Relaxed);
+        self.abs_deadline.store(0, Relaxed);
+    }
+
+    /// Takes the pending runtime overrun, which should be reported to the
+    /// thread with `SIGXCPU`.
+    pub fn take_overrun(&self) -> bool {
+        self.overrun_pending.swap(false, Relaxed)
+    }
+
+    /// Checks whether there is a pending runtime overrun.
+    pub fn has_pending_overrun(&self) -> bool {
+        self.overrun_pending.load(Relaxed)
+    }
+
+    pub(super) fn abs_deadline(&self) -> u64 {
+        self.abs_deadline.load(Relaxed)
+    }
+
+    fn is_throttled(&self) -> bool {
+        self.remaining.load(Relaxed) <= 0
+    }
+
+    /// Starts a new period if the current one cannot be used by a woken-up
+    /// thread without exceeding its bandwidth (the CBS wakeup rule).
+    fn update_on_wakeup(&self, now: u64) {
+        let abs_deadline = self.abs_deadline.load(Relaxed);
+        let remaining = self.remaining.load(Relaxed);
+
+        let overflow = abs_deadline > now && remaining > 0 && {
+            // remaining / (abs_deadline - now) > runtime / period
+            let lhs = remaining as u128 * u128::from(self.period.load(Relaxed));
+            let rhs = u128::from(abs_deadline - now) * u128::from(self.runtime.load(Relaxed));
+            lhs > rhs
+        };
+
+        if abs_deadline <= now || overflow {
+            self.abs_deadline
+                .store(now + self.deadline.load(Relaxed), Relaxed);
+            self.remaining
+                .store(self.runtime.load(Relaxed) as i64, Relaxed);
+        }
+    }
+
+    /// Replenishes the runtime of a throttled thread when its current
+    /// deadline is reached.
+    ///
+    /// Returns whether the thread is no longer throttled.
+    fn replenish(&self, now: u64) -> bool {
+        let mut abs_deadline = self.abs_deadline.load(Relaxed);
+        if now < abs_deadline {
+            return false;
+        }
+
+        let runtime = self.runtime.load(Relaxed) as i64;
+        let period = self.period.load(Relaxed);
+        let mut remaining = self.remaining.load(Relaxed);
+        while remaining <= 0 {
+            abs_deadline += period;
+            remaining += runtime;
+        }
+
+        // Start over if the thread lags too far behind.
+        if abs_deadline <= now {
+            abs_deadline = now + self.deadline.load(Relaxed);
+            remaining = runtime;
+        }
+
+        self.abs_deadline.store(abs_deadline, Relaxed);
+        self.remaining.store(remaining, Relaxed);
+        true
+    }
+}
+
+impl Drop for DeadlineAttr {
+    fn drop(&mut self) {
+        if let Some(cpu) = self.reserved_cpu.get() {
+            reserved_bw(cpu).fetch_sub(self.bandwidth.load(Relaxed), Relaxed);
+        }
+    }
+}
+
+/// The wrapper for threads in the DEADLINE run queue, keyed by the absolute deadline.
+struct DeadlineQueueItem(Arc<Task>, u64);
+
+impl core::fmt::Debug for DeadlineQueueItem {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self.1)
+    }
+}
+
+impl PartialEq for DeadlineQueueItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.1.eq(&other.1)
+    }
+}
+
+impl Eq for DeadlineQueueItem {}
+
+impl PartialOrd for DeadlineQueueItem {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DeadlineQueueItem {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.1.cmp(&other.1)
+    }
+}
+
+/// The per-cpu run queue for the DEADLINE scheduling class.
+///
+/// See [`DeadlineAttr`] for the scheduling algorithm.
+///
+/// The throttled threads are replenished when the CPU updates its current
+/// thread or picks the next thread. To replenish them in time even if the
+/// tick of the CPU is stopped, a timer event is requested at the earliest
+/// replenishment time when a thread is throttled (see
+/// [`Self::arm_replenish_timer`]).
+#[derive(Debug)]
+pub(super) struct DeadlineClassRq {
+    cpu: CpuId,
+    /// The ready-to-run threads, ordered by their absolute deadlines.
+    entities: BinaryHeap<Reverse<DeadlineQueueItem>>,
+    /// The threads that have run out of their runtime in the current period.
+    throttled: Vec<Arc<Task>>,
+}
+
+impl DeadlineClassRq {
+    pub fn new(cpu: CpuId) -> Self {
+        Self {
+            cpu,
+            entities: BinaryHeap::new(),
+            throttled: Vec::new(),
+        }
+    }
+
+    /// Moves the throttled threads whose runtime can be replenished back to
+    /// the ready-to-run threads.
+    pub fn unthrottle(&mut self, now: u64) {
+        let mut index = 0;
+        while index < self.throttled.len() {
+            let attr = &self.throttled[index]
+                .as_thread()
+                .unwrap()
+                .sched_attr()
+                .deadline;
+            if attr.replenish(now) {
+                let entity = self.throttled.swap_remove(index);
+                self.push_ready(entity);
+            } else {
+                index += 1;
+            }
+        }
+
+        self.arm_replenish_timer(now);
+    }
+
+    /// Requests a timer event at the earliest replenishment time of the
+    /// throttled threads.
+    ///
+    /// The event wakes up the CPU if it is idle, and the idle CPU replenishes
+    /// the threads when it reschedules. A busy CPU still replenishes the
+    /// threads in its next tick.
+    ///
+    /// Timer events can only be requested on the local CPU, so nothing is
+    /// done if this run queue belongs to another CPU. This is fine because
+    /// the event has been requested by the owning CPU when it throttled the
+    /// thread (see [`Self::update_current`]), and a thread enqueued to an idle
+    /// CPU always makes the CPU reschedule.
+    fn arm_replenish_timer(&self, now: u64) {
+        let Some(replenish_time) = self
+            .throttled
+            .iter()
+            .map(|entity| {
+                entity
+                    .as_thread()
+                    .unwrap()
+                    .sched_attr()
+                    .deadline
+                    .abs_deadline()
+            })
+            .min()
+        else {
+            return;
+        };
+
+        arm_timer_on(self.cpu, replenish_time, now);
+    }
+
+    fn push_ready(&mut self, entity: Arc<Task>) {
+        let abs_deadline = entity
+            .as_thread()
+            .unwrap()
+            .sched_attr()
+            .deadline
+            .abs_deadline();
+        self.entities
+            .push(Reverse(DeadlineQueueItem(entity, abs_deadline)));
+    }
+}
+
+impl SchedClassRq for DeadlineClassRq {
+    fn enqueue(&mut self, entity: Arc<Task>, flags: Option<EnqueueFlags>) {
+        let attr = &entity.as_thread().unwrap().sched_attr().deadline;
+        let now = sched_clock();
+
+        if flags.is_some() {
+            attr.update_on_wakeup(now);
+        }
+
+        if attr.is_throttled() && !attr.replenish(now) {
+            self.throttled.push(entity);
+            self.arm_replenish_timer(now);
+        } else {
+            self.push_ready(entity);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    fn pick_next(&mut self) -> Option<Arc<Task>> {
+        self.unthrottle(sched_clock());
+
+        let Reverse(DeadlineQueueItem(entity, _)) = self.entities.pop()?;
+        Some(entity)
+    }
+
+    fn update_current(
+        &mut self,
+        rt: &CurrentRuntime,
+        attr: &SchedAttr,
+        flags: UpdateFlags,
+    ) -> bool {
+        let attr = &attr.deadline;
+
+        if flags == UpdateFlags::Yield {
+            // A yielding thread gives up the rest of its runtime in the current period.
+            attr.remaining.store(0, Relaxed);
+            return true;
+        }
+
+        let remaining = attr.remaining.fetch_sub(rt.delta as i64, Relaxed) - rt.delta as i64;
+        if remaining <= 0 {
+            if attr.overrun_signal.load(Relaxed) {
+                attr.overrun_pending.store(true, Relaxed);
+            }
+            // The thread may sleep before it is put back into the run queue,
+            // so the timer event is requested here.
+            arm_timer_on(self.cpu, attr.abs_deadline(), sched_clock());
+            return true;
+        }
+
+        self.entities
+            .peek()
+            .is_some_and(|Reverse(earliest)| earliest.1 < attr.abs_deadline())
+    }
+}
+
+/// Requests a timer event at `time` if the current CPU is `cpu`.
+fn arm_timer_on(cpu: CpuId, time: u64, now: u64) {
+    let irq_guard = disable_local();
+    if irq_guard.current_cpu() == cpu {
+        let timeout_ns = clocks_to_ns(time.saturating_sub(now));
+        timer::set_next_event(Duration::from_nanos(timeout_ns));
+    }
+}
@@ -1,13 +1,14 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use alloc::{collections::BinaryHeap, sync::Arc};
+use alloc::{collections::BinaryHeap, sync::Arc, vec::Vec};
 use core::{
     cmp::{self, Reverse},
+    mem,
     sync::atomic::{AtomicU64, Ordering::Relaxed},
 };
 
 use ostd::{
-    cpu::{num_cpus, CpuId},
+    cpu::{num_cpus, CpuId, CpuSet},
     task::{
         scheduler::{EnqueueFlags, UpdateFlags},
         Task,
@@ -217,6 +218,80 @@ impl FairClassRq {
     fn time_slice(&self, cur_weight: u64) -> u64 {
         self.period() * cur_weight / (self.total_weight + cur_weight)
     }
+
+    /// Detaches at most `max_count` ready-to-run threads so that they can be
+    /// migrated to another CPU.
+    ///
+    /// Among the threads accepted by `can_migrate`, the ones with the largest
+    /// vruntimes are chosen, since they are the last ones to run here and are
+    /// likely to have the coldest caches. The run queue is scanned and rebuilt
+    /// only once, regardless of the number of detached threads.
+    ///
+    /// The vruntimes of the detached threads are made relative to the
+    /// `min_vruntime` of this run queue. [`Self::attach_migrated`] must be used
+    /// to put them into the destination run queue.
+    pub fn detach_migratable(
+        &mut self,
+        max_count: usize,
+        mut can_migrate: impl FnMut(&Arc<Task>) -> bool,
+    ) -> Vec<Arc<Task>> {
+        if max_count == 0 || self.entities.is_empty() {
+            return Vec::new();
+        }
+
+        let (mut chosen, mut kept): (Vec<_>, Vec<_>) = mem::take(&mut self.entities)
+            .into_vec()
+            .into_iter()
+            .partition(|Reverse(item)| can_migrate(&item.0));
+        if chosen.len() > max_count {
+            // Move the `max_count` items with the largest vruntimes to the end.
+            let num_kept = chosen.len() - max_count;
+            chosen.select_nth_unstable_by_key(num_kept, |Reverse(item)| item.key());
+            kept.extend(chosen.drain(..num_kept));
+        }
+
+        self.entities = BinaryHeap::from(kept);
+
+        chosen
+            .into_iter()
+            .map(|Reverse(FairQueueItem(entity, vruntime))| {
+                let fair_attr = &entity.as_thread().unwrap().sched_attr().fair;
+                self.total_weight -= fair_attr.weight.load(Relaxed);
+                fair_attr
+                    .vruntime
+                    .store(vruntime.saturating_sub(self.min_vruntime), Relaxed);
+                entity
+            })
+            .collect()
+    }
+
+    /// Returns the CPUs that at least one of the ready-to-run threads is
+    /// allowed to run on.
+    pub fn allowed_cpus(&self) -> CpuSet {
+        let mut cpus = CpuSet::new_empty();
+        for Reverse(FairQueueItem(entity, _)) in self.entities.iter() {
+            let affinity = entity
+                .as_thread()
+                .unwrap()
+                .atomic_cpu_affinity()
+                .load(Relaxed);
+            for cpu in affinity.iter() {
+                cpus.add(cpu);
+            }
+        }
+        cpus
+    }
+
+    /// Attaches a thread detached by [`Self::detach_migratable`] from another
+    /// CPU, preserving its vruntime lag against `min_vruntime`.
+    pub fn attach_migrated(&mut self, entity: Arc<Task>) {
+        let fair_attr = &entity.as_thread().unwrap().sched_attr().fair;
+        let vruntime = fair_attr.vruntime.load(Relaxed) + self.min_vruntime;
+        fair_attr.vruntime.store(vruntime, Relaxed);
+
+        self.total_weight += fair_attr.weight.load(Relaxed);
+        self.entities.push(Reverse(FairQueueItem(entity, vruntime)));
+    }
 }
 
 impl SchedClassRq for FairClassRq {
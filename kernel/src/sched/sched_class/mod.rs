@@ -2,12 +2,14 @@
 
 #![warn(unused)]
 
-use alloc::{boxed::Box, sync::Arc};
-use core::{fmt, sync::atomic::Ordering};
+use core::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use ostd::{
     arch::read_tsc as sched_clock,
-    cpu::{all_cpus, CpuId, PinCurrentCpu},
+    cpu::{all_cpus, CpuId, CpuSet, PinCurrentCpu},
     sync::SpinLock,
     task::{
         scheduler::{
@@ -16,18 +18,25 @@ use ostd::{
         },
         AtomicCpuId, Task,
     },
+    timer,
     trap::irq::disable_local,
 };
+use spin::Once;
 
 use super::{
     nice::Nice,
-    stats::{set_stats_from_scheduler, SchedulerStats},
+    stats::{set_stats_from_scheduler, CpuIdleType, CpuSchedStats, SchedulerStats},
+};
+use crate::{
+    prelude::*,
+    thread::{AsThread, Thread},
 };
-use crate::thread::{AsThread, Thread};
 
+mod balance;
 mod policy;
 mod time;
 
+mod deadline;
 mod fair;
 mod idle;
 mod real_time;
@@ -35,14 +44,18 @@ mod stop;
 
 use self::policy::{SchedPolicyKind, SchedPolicyState};
 pub use self::{
+    deadline::DeadlineParams,
     policy::SchedPolicy,
     real_time::{RealTimePolicy, RealTimePriority},
 };
 
 type SchedEntity = (Arc<Task>, Arc<Thread>);
 
+static CLASS_SCHEDULER: Once<&'static ClassScheduler> = Once::new();
+
 pub fn init() {
-    let scheduler = Box::leak(Box::new(ClassScheduler::new()));
+    let scheduler: &'static ClassScheduler = Box::leak(Box::new(ClassScheduler::new()));
+    CLASS_SCHEDULER.call_once(|| scheduler);
 
     // Inject the scheduler into the ostd for actual scheduling work.
     inject_scheduler(scheduler);
@@ -51,6 +64,22 @@ pub fn init() {
     // We set this after injecting the scheduler into ostd,
     // so that the loadavg statistics are updated after the scheduler is used.
     set_stats_from_scheduler(scheduler);
+
+    register_balance_callback();
+}
+
+/// Initializes the scheduler on an application processor.
+///
+/// This function should be called on every AP after [`init`] is called on the BSP.
+pub fn init_on_ap() {
+    register_balance_callback();
+}
+
+/// Registers the periodic load balancing into the timer interrupt of the current CPU.
+fn register_balance_callback() {
+    timer::register_callback(|| {
+        CLASS_SCHEDULER.get().unwrap().balance_tick();
+    });
 }
 
 /// Represents the middle layer between scheduling classes and generic scheduler
@@ -59,17 +88,29 @@ pub fn init() {
 pub struct ClassScheduler {
     rqs: Box<[SpinLock<PerCpuClassRqSet>]>,
     last_chosen_cpu: AtomicCpuId,
+    /// The jiffies before which no more idle CPUs should be kicked to balance.
+    ///
+    /// This is shared by all CPUs, so that the idle CPUs are not flooded with
+    /// kicks when many CPUs are busy.
+    next_idle_kick: AtomicU64,
 }
 
 /// Represents the run queue for each CPU core. It stores a list of run queues for
 /// scheduling classes in its corresponding CPU core. The current task of this CPU
 /// core is also stored in this structure.
 struct PerCpuClassRqSet {
+    cpu: CpuId,
     stop: stop::StopClassRq,
+    deadline: deadline::DeadlineClassRq,
     real_time: real_time::RealTimeClassRq,
     fair: fair::FairClassRq,
     idle: idle::IdleClassRq,
     current: Option<(SchedEntity, CurrentRuntime)>,
+    stats: CpuSchedStats,
+    /// The time for the next periodic load balancing, in jiffies.
+    next_balance: u64,
+    /// The number of consecutive failed load balancing attempts.
+    nr_balance_failed: u32,
 }
 
 /// Stores the runtime information of the current task.
@@ -131,16 +172,29 @@ trait SchedClassRq: Send + fmt::Debug {
 pub struct SchedAttr {
     policy: SchedPolicyState,
     last_cpu: AtomicCpuId,
+    /// The time at which the thread stopped running, in [`sched_clock`]s.
+    last_ran: AtomicU64,
+    deadline: deadline::DeadlineAttr,
     real_time: real_time::RealTimeAttr,
     fair: fair::FairAttr,
 }
 
 impl SchedAttr {
     /// Constructs a new `SchedAttr` with the given scheduling policy.
-    pub fn new(policy: SchedPolicy) -> Self {
+    ///
+    /// `affinity` is the CPU affinity of the thread.
+    pub fn new(policy: SchedPolicy, affinity: &CpuSet) -> Self {
         Self {
             policy: SchedPolicyState::new(policy),
             last_cpu: AtomicCpuId::default(),
+            last_ran: AtomicU64::new(0),
+            deadline: deadline::DeadlineAttr::new(
+                match policy {
+                    SchedPolicy::Deadline(params) => Some(params),
+                    _ => None,
+                },
+                affinity,
+            ),
             real_time: {
                 let (prio, policy) = match policy {
                     SchedPolicy::RealTime { rt_prio, rt_policy } => (rt_prio.get(), rt_policy),
@@ -168,20 +222,69 @@ impl SchedAttr {
     ///
     /// Specifically for real-time policies, if the new policy doesn't
     /// specify a base slice factor for RR, the old one will be kept.
-    pub fn set_policy(&self, policy: SchedPolicy) {
-        self.policy.set(policy, |policy| match policy {
-            SchedPolicy::RealTime { rt_prio, rt_policy } => {
-                self.real_time.update(rt_prio.get(), rt_policy);
+    ///
+    /// For the deadline policy, this method fails with `EBUSY` if the
+    /// requested bandwidth cannot be admitted on any CPU in `affinity`, which
+    /// is the CPU affinity of the thread.
+    pub fn set_policy(&self, policy: SchedPolicy, affinity: &CpuSet) -> Result<()> {
+        self.policy.set(policy, |policy| {
+            let params = match &policy {
+                SchedPolicy::Deadline(params) => Some(params),
+                _ => None,
+            };
+            self.deadline.update(params, affinity, self.last_cpu())?;
+
+            match policy {
+                SchedPolicy::RealTime { rt_prio, rt_policy } => {
+                    self.real_time.update(rt_prio.get(), rt_policy);
+                }
+                SchedPolicy::Fair(nice) => self.fair.update(nice),
+                _ => {}
             }
-            SchedPolicy::Fair(nice) => self.fair.update(nice),
-            _ => {}
-        });
+            Ok(())
+        })
+    }
+
+    /// Takes the pending runtime overrun of the deadline policy.
+    ///
+    /// If this method returns `true`, the thread should be notified with
+    /// `SIGXCPU`, as requested by `SCHED_FLAG_DL_OVERRUN`.
+    pub fn take_deadline_overrun(&self) -> bool {
+        self.deadline.take_overrun()
+    }
+
+    /// Checks whether there is a pending runtime overrun of the deadline policy.
+    pub fn has_pending_deadline_overrun(&self) -> bool {
+        self.deadline.has_pending_overrun()
     }
 
     pub fn update_policy<T>(&self, f: impl FnOnce(&mut SchedPolicy) -> T) -> T {
         self.policy.update(f)
     }
 
+    /// Checks whether the CPU affinity of the thread can be changed to `affinity`.
+    ///
+    /// A thread of the deadline policy is bound to the CPU where its bandwidth
+    /// is reserved, so the CPU must be kept in the affinity. Otherwise, this
+    /// method fails with `EBUSY`.
+    pub fn check_affinity(&self, affinity: &CpuSet) -> Result<()> {
+        match self.deadline_cpu() {
+            Some(cpu) if !affinity.contains(cpu) => Err(Error::with_message(
+                Errno::EBUSY,
+                "the bandwidth of the deadline policy is reserved on another CPU",
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the CPU that a thread of the deadline policy is bound to.
+    fn deadline_cpu(&self) -> Option<CpuId> {
+        if self.policy_kind() != SchedPolicyKind::Deadline {
+            return None;
+        }
+        self.deadline.reserved_cpu()
+    }
+
     fn last_cpu(&self) -> Option<CpuId> {
         self.last_cpu.get()
     }
@@ -189,6 +292,30 @@ impl SchedAttr {
     fn set_last_cpu(&self, cpu_id: CpuId) {
         self.last_cpu.set_anyway(cpu_id);
     }
+
+    /// Checks whether a thread with this attribute should preempt a thread
+    /// with the `other` attribute.
+    ///
+    /// The deadline policy must be checked after the thread is enqueued, so
+    /// that its absolute deadline is up-to-date.
+    fn preempts(&self, other: &SchedAttr) -> bool {
+        match (self.policy_kind(), other.policy_kind()) {
+            (SchedPolicyKind::Deadline, SchedPolicyKind::Deadline) => {
+                self.deadline.abs_deadline() < other.deadline.abs_deadline()
+            }
+            _ => self.policy() < other.policy(),
+        }
+    }
+
+    fn set_last_ran(&self, now: u64) {
+        self.last_ran.store(now, Ordering::Relaxed);
+    }
+
+    /// Checks whether the thread ran recently enough that its working set is
+    /// likely to be still in the cache of its last CPU.
+    fn is_cache_hot(&self, now: u64) -> bool {
+        now.saturating_sub(self.last_ran.load(Ordering::Relaxed)) < time::migration_cost_clocks()
+    }
 }
 
 impl Scheduler for ClassScheduler {
@@ -206,6 +333,7 @@ impl Scheduler for ClassScheduler {
             }
         };
 
+        let is_local = disable_local().current_cpu() == cpu;
         let mut rq = self.rqs[cpu.as_usize()].disable_irq().lock();
 
         // Note: call set_if_is_none again to prevent a race condition.
@@ -213,17 +341,31 @@ impl Scheduler for ClassScheduler {
             return None;
         }
 
+        if flags == EnqueueFlags::Wake {
+            rq.stats.ttwu_count += 1;
+            if is_local {
+                rq.stats.ttwu_local += 1;
+            }
+            if thread
+                .sched_attr()
+                .last_cpu()
+                .is_some_and(|last| last != cpu)
+            {
+                rq.stats.ttwu_move_affine += 1;
+            }
+        }
+
+        thread.sched_attr().set_last_cpu(cpu);
+        rq.enqueue_entity((task, thread.clone()), Some(flags));
+
         // Preempt if the new task has a higher priority.
         let should_preempt = rq
             .current
             .as_ref()
             .is_none_or(|((_, rq_current_thread), _)| {
-                thread.sched_attr().policy() < rq_current_thread.sched_attr().policy()
+                thread.sched_attr().preempts(rq_current_thread.sched_attr())
             });
 
-        thread.sched_attr().set_last_cpu(cpu);
-        rq.enqueue_entity((task, thread), Some(flags));
-
         should_preempt.then_some(cpu)
     }
 
@@ -243,62 +385,30 @@ impl ClassScheduler {
     pub fn new() -> Self {
         let class_rq = |cpu| {
             SpinLock::new(PerCpuClassRqSet {
+                cpu,
                 stop: stop::StopClassRq::new(),
+                deadline: deadline::DeadlineClassRq::new(cpu),
                 real_time: real_time::RealTimeClassRq::new(cpu),
                 fair: fair::FairClassRq::new(cpu),
                 idle: idle::IdleClassRq::new(),
                 current: None,
+                stats: CpuSchedStats::default(),
+                next_balance: 0,
+                nr_balance_failed: 0,
             })
         };
         ClassScheduler {
             rqs: all_cpus().map(class_rq).collect(),
             last_chosen_cpu: AtomicCpuId::default(),
+            next_idle_kick: AtomicU64::new(0),
         }
     }
-
-    // TODO: Implement a better algorithm and replace the current naive implementation.
-    fn select_cpu(&self, thread: &Thread, flags: EnqueueFlags) -> CpuId {
-        if let Some(last_cpu) = thread.sched_attr().last_cpu() {
-            return last_cpu;
-        }
-        debug_assert!(flags == EnqueueFlags::Spawn);
-        let guard = disable_local();
-        let affinity = thread.atomic_cpu_affinity().load(Ordering::Relaxed);
-        let mut selected = guard.current_cpu();
-        let mut minimum_load = u32::MAX;
-        let last_chosen = match self.last_chosen_cpu.get() {
-            Some(cpu) => cpu.as_usize() as isize,
-            None => -1,
-        };
-        // Simulate a round-robin selection starting from the last chosen CPU.
-        //
-        // It still checks every CPU to find the one with the minimum load, but
-        // avoids keeping selecting the same CPU when there are multiple equally
-        // idle CPUs.
-        let affinity_iter = affinity
-            .iter()
-            .filter(|&cpu| cpu.as_usize() as isize > last_chosen)
-            .chain(
-                affinity
-                    .iter()
-                    .filter(|&cpu| cpu.as_usize() as isize <= last_chosen),
-            );
-        for candidate in affinity_iter {
-            let rq = self.rqs[candidate.as_usize()].lock();
-            let (load, _) = rq.nr_queued_and_running();
-            if load < minimum_load {
-                minimum_load = load;
-                selected = candidate;
-            }
-        }
-        self.last_chosen_cpu.set_anyway(selected);
-        selected
-    }
 }
 
 impl PerCpuClassRqSet {
     fn pick_next_entity(&mut self) -> Option<SchedEntity> {
         (self.stop.pick_next())
+            .or_else(|| self.deadline.pick_next())
             .or_else(|| self.real_time.pick_next())
             .or_else(|| self.fair.pick_next())
             .or_else(|| self.idle.pick_next())
@@ -311,6 +421,7 @@ impl PerCpuClassRqSet {
     fn enqueue_entity(&mut self, (task, thread): SchedEntity, flags: Option<EnqueueFlags>) {
         match thread.sched_attr().policy_kind() {
             SchedPolicyKind::Stop => self.stop.enqueue(task, flags),
+            SchedPolicyKind::Deadline => self.deadline.enqueue(task, flags),
             SchedPolicyKind::RealTime => self.real_time.enqueue(task, flags),
             SchedPolicyKind::Fair => self.fair.enqueue(task, flags),
             SchedPolicyKind::Idle => self.idle.enqueue(task, flags),
@@ -318,10 +429,31 @@ impl PerCpuClassRqSet {
     }
 
     fn nr_queued_and_running(&self) -> (u32, u32) {
-        let queued = self.stop.len() + self.real_time.len() + self.fair.len() + self.idle.len();
+        let queued = self.stop.len()
+            + self.deadline.len()
+            + self.real_time.len()
+            + self.fair.len()
+            + self.idle.len();
         let running = usize::from(self.current.is_some());
         (queued as u32, running as u32)
     }
+
+    /// Returns the number of non-idle threads in the run queues, plus the current
+    /// thread if it is not an idle one.
+    ///
+    /// This is the load used for load balancing.
+    fn load(&self) -> u32 {
+        let queued = self.stop.len() + self.deadline.len() + self.real_time.len() + self.fair.len();
+        let running = self.current.as_ref().is_some_and(|((_, thread), _)| {
+            thread.sched_attr().policy_kind() != SchedPolicyKind::Idle
+        });
+        (queued + usize::from(running)) as u32
+    }
+
+    /// Checks whether the CPU has nothing to run other than idle threads.
+    fn is_idle(&self) -> bool {
+        self.load() == 0
+    }
 }
 
 impl LocalRunQueue for PerCpuClassRqSet {
@@ -330,39 +462,77 @@ impl LocalRunQueue for PerCpuClassRqSet {
     }
 
     fn pick_next_current(&mut self) -> Option<&Arc<Task>> {
+        self.stats.sched_count += 1;
+
+        // Try pulling threads from other CPUs if this CPU is going to be idle.
+        let is_current_idle = self.current.as_ref().is_some_and(|((_, thread), _)| {
+            thread.sched_attr().policy_kind() == SchedPolicyKind::Idle
+        });
+        if !is_current_idle
+            && self.stop.is_empty()
+            && self.deadline.is_empty()
+            && self.real_time.is_empty()
+            && self.fair.is_empty()
+        {
+            if let Some(scheduler) = CLASS_SCHEDULER.get() {
+                scheduler.balance(self, CpuIdleType::NewlyIdle);
+            }
+        }
+
         self.pick_next_entity().and_then(|next| {
+            if next.1.sched_attr().policy_kind() == SchedPolicyKind::Idle {
+                self.stats.sched_goidle += 1;
+            }
+
             // We guarantee that a task can appear at once in a `PerCpuClassRqSet`. So, the `next` cannot be the same
             // as the current task here.
             if let Some((old, _)) = self.current.replace((next, CurrentRuntime::new())) {
-                self.enqueue_entity(old, None);
+                old.1.sched_attr().set_last_ran(sched_clock());
+                let old = match CLASS_SCHEDULER.get() {
+                    Some(scheduler) => scheduler.push_deadline(self.cpu, old),
+                    None => Some(old),
+                };
+                if let Some(old) = old {
+                    self.enqueue_entity(old, None);
+                }
             }
             self.current.as_ref().map(|((task, _), _)| task)
         })
     }
 
     fn update_current(&mut self, flags: UpdateFlags) -> bool {
+        if flags == UpdateFlags::Yield {
+            self.stats.yld_count += 1;
+        }
+
+        // Throttled deadline threads may become runnable as time goes by.
+        self.deadline.unthrottle(sched_clock());
+
         if let Some(((_, cur), rt)) = &mut self.current {
             rt.update();
             let attr = &cur.sched_attr();
 
             let (current_expired, lookahead) = match attr.policy_kind() {
                 SchedPolicyKind::Stop => (self.stop.update_current(rt, attr, flags), 0),
-                SchedPolicyKind::RealTime => (self.real_time.update_current(rt, attr, flags), 1),
-                SchedPolicyKind::Fair => (self.fair.update_current(rt, attr, flags), 2),
-                SchedPolicyKind::Idle => (self.idle.update_current(rt, attr, flags), 3),
+                SchedPolicyKind::Deadline => (self.deadline.update_current(rt, attr, flags), 1),
+                SchedPolicyKind::RealTime => (self.real_time.update_current(rt, attr, flags), 2),
+                SchedPolicyKind::Fair => (self.fair.update_current(rt, attr, flags), 3),
+                SchedPolicyKind::Idle => (self.idle.update_current(rt, attr, flags), 4),
             };
 
             current_expired
                 || (lookahead >= 1 && !self.stop.is_empty())
-                || (lookahead >= 2 && !self.real_time.is_empty())
-                || (lookahead >= 3 && !self.fair.is_empty())
+                || (lookahead >= 2 && !self.deadline.is_empty())
+                || (lookahead >= 3 && !self.real_time.is_empty())
+                || (lookahead >= 4 && !self.fair.is_empty())
         } else {
             true
         }
     }
 
     fn dequeue_current(&mut self) -> Option<Arc<Task>> {
-        self.current.take().map(|((cur_task, _), _)| {
+        self.current.take().map(|((cur_task, cur_thread), _)| {
+            cur_thread.sched_attr().set_last_ran(sched_clock());
             cur_task.schedule_info().cpu.set_to_none();
             cur_task
         })
@@ -376,6 +546,10 @@ impl SchedulerStats for ClassScheduler {
             (queued + q, running + r)
         })
     }
+
+    fn cpu_sched_stats(&self, cpu: CpuId) -> CpuSchedStats {
+        self.rqs[cpu.as_usize()].disable_irq().lock().stats
+    }
 }
 
 impl Default for ClassScheduler {
@@ -6,8 +6,11 @@ use atomic_integer_wrapper::define_atomic_version_of_integer_like_type;
 use int_to_c_enum::TryFromInt;
 use ostd::sync::SpinLock;
 
-pub use super::real_time::{RealTimePolicy, RealTimePriority};
-use crate::sched::nice::Nice;
+pub use super::{
+    deadline::DeadlineParams,
+    real_time::{RealTimePolicy, RealTimePriority},
+};
+use crate::{prelude::*, sched::nice::Nice};
 
 /// The User-chosen scheduling policy.
 ///
@@ -15,6 +18,7 @@ use crate::sched::nice::Nice;
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SchedPolicy {
     Stop,
+    Deadline(DeadlineParams),
     RealTime {
         rt_prio: RealTimePriority,
         rt_policy: RealTimePolicy,
@@ -27,15 +31,17 @@ pub enum SchedPolicy {
 #[repr(u8)]
 pub(super) enum SchedPolicyKind {
     Stop = 0,
-    RealTime = 1,
-    Fair = 2,
-    Idle = 3,
+    Deadline = 1,
+    RealTime = 2,
+    Fair = 3,
+    Idle = 4,
 }
 
 impl SchedPolicy {
     pub(super) fn kind(&self) -> SchedPolicyKind {
         match self {
             SchedPolicy::Stop => SchedPolicyKind::Stop,
+            SchedPolicy::Deadline(_) => SchedPolicyKind::Deadline,
             SchedPolicy::RealTime { .. } => SchedPolicyKind::RealTime,
             SchedPolicy::Fair(_) => SchedPolicyKind::Fair,
             SchedPolicy::Idle => SchedPolicyKind::Idle,
@@ -76,7 +82,11 @@ impl SchedPolicyState {
         *self.policy.disable_irq().lock()
     }
 
-    pub fn set(&self, mut policy: SchedPolicy, update: impl FnOnce(SchedPolicy)) {
+    pub fn set(
+        &self,
+        mut policy: SchedPolicy,
+        update: impl FnOnce(SchedPolicy) -> Result<()>,
+    ) -> Result<()> {
         let mut this = self.policy.disable_irq().lock();
 
         // Keep the old base slice factor if the new policy doesn't specify one.
@@ -97,9 +107,10 @@ impl SchedPolicyState {
             *base_slice_factor = slot.or(*base_slice_factor);
         }
 
-        update(policy);
+        update(policy)?;
         self.kind.store(policy.kind(), Relaxed);
         *this = policy;
+        Ok(())
     }
 
     pub fn update<T>(&self, update: impl FnOnce(&mut SchedPolicy) -> T) -> T {
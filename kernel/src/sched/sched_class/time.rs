@@ -34,11 +34,19 @@ pub const BASE_SLICE_NS: u64 = 750_000;
 /// The minimum scheduling period, measured in nanoseconds.
 pub const MIN_PERIOD_NS: u64 = 6_000_000;
 
-fn consts() -> (u64, u64) {
-    static CONSTS: Once<(u64, u64)> = Once::new();
+/// The time after which a thread that has stopped running is no longer
+/// considered cache-hot, measured in nanoseconds.
+pub const MIGRATION_COST_NS: u64 = 500_000;
+
+fn consts() -> (u64, u64, u64) {
+    static CONSTS: Once<(u64, u64, u64)> = Once::new();
     *CONSTS.call_once(|| {
         let (a, b) = tsc_factors();
-        (BASE_SLICE_NS * b / a, MIN_PERIOD_NS * b / a)
+        (
+            BASE_SLICE_NS * b / a,
+            MIN_PERIOD_NS * b / a,
+            MIGRATION_COST_NS * b / a,
+        )
     })
 }
 
@@ -51,3 +59,20 @@ pub fn base_slice_clocks() -> u64 {
 pub fn min_period_clocks() -> u64 {
     consts().1
 }
+
+/// Returns the cache-hot threshold for migrating threads, measured in TSC clock units.
+pub fn migration_cost_clocks() -> u64 {
+    consts().2
+}
+
+/// Converts a duration in nanoseconds to TSC clock units.
+pub fn ns_to_clocks(ns: u64) -> u64 {
+    let (a, b) = tsc_factors();
+    (u128::from(ns) * u128::from(b) / u128::from(a)) as u64
+}
+
+/// Converts a duration in TSC clock units to nanoseconds, rounding up.
+pub fn clocks_to_ns(clocks: u64) -> u64 {
+    let (a, b) = tsc_factors();
+    (u128::from(clocks) * u128::from(a)).div_ceil(u128::from(b)) as u64
+}
@@ -3,4 +3,7 @@
 pub mod loadavg;
 mod scheduler_stats;
 
-pub use scheduler_stats::{nr_queued_and_running, set_stats_from_scheduler, SchedulerStats};
+pub use scheduler_stats::{
+    cpu_sched_stats, nr_queued_and_running, set_stats_from_scheduler, CpuIdleType, CpuSchedStats,
+    SchedulerStats,
+};
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use ostd::timer;
+use ostd::{cpu::CpuId, timer};
 use spin::Once;
 
 use super::loadavg;
@@ -29,9 +29,77 @@ pub trait SchedulerStats: Sync + Send {
     /// We decided to return a tuple instead of having two separate functions to
     /// avoid the overhead of disabling the preemption twice to inspect the scheduler.
     fn nr_queued_and_running(&self) -> (u32, u32);
+
+    /// Returns the scheduling statistics of the given CPU.
+    fn cpu_sched_stats(&self, cpu: CpuId) -> CpuSchedStats;
+}
+
+/// The type of CPU idleness under which a load balancing is attempted.
+///
+/// The variants follow the order of Linux's `enum cpu_idle_type`, which is
+/// also the order of the load-balancing fields in `/proc/schedstat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuIdleType {
+    /// A periodic balancing on an idle CPU.
+    Idle = 0,
+    /// A periodic balancing on a busy CPU.
+    NotIdle = 1,
+    /// A balancing on a CPU that is about to become idle.
+    NewlyIdle = 2,
+}
+
+impl CpuIdleType {
+    /// The number of CPU idle types.
+    pub const COUNT: usize = 3;
+}
+
+/// The load-balancing statistics of a CPU under a certain [`CpuIdleType`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoadBalanceStats {
+    /// The number of load-balancing attempts.
+    pub lb_count: u64,
+    /// The number of attempts that found the CPU already balanced.
+    pub lb_balanced: u64,
+    /// The number of attempts that failed to migrate any thread.
+    pub lb_failed: u64,
+    /// The sum of the imbalances found.
+    pub lb_imbalance: u64,
+    /// The number of threads migrated to this CPU.
+    pub lb_gained: u64,
+    /// The number of migrated threads that were cache-hot.
+    pub lb_hot_gained: u64,
+    /// The number of attempts that found no busier CPU.
+    pub lb_nobusyq: u64,
+}
+
+/// The scheduling statistics of a CPU.
+///
+/// Reference: <https://docs.kernel.org/scheduler/sched-stats.html>
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CpuSchedStats {
+    /// The number of times that threads yield the CPU.
+    pub yld_count: u64,
+    /// The number of times that the scheduler is invoked.
+    pub sched_count: u64,
+    /// The number of times that the CPU switches to the idle thread.
+    pub sched_goidle: u64,
+    /// The number of wakeups that enqueue threads to this CPU.
+    pub ttwu_count: u64,
+    /// The number of wakeups issued by this CPU that enqueue threads to this CPU.
+    pub ttwu_local: u64,
+    /// The load-balancing statistics, indexed by [`CpuIdleType`].
+    pub lb: [LoadBalanceStats; CpuIdleType::COUNT],
+    /// The number of wakeups that move threads to an idle CPU other than the
+    /// CPU on which they last ran.
+    pub ttwu_move_affine: u64,
 }
 
 /// Get the amount of tasks in the runqueues and the amount of running tasks.
 pub fn nr_queued_and_running() -> (u32, u32) {
     SCHEDULER_STATS.get().unwrap().nr_queued_and_running()
 }
+
+/// Get the scheduling statistics of the given CPU.
+pub fn cpu_sched_stats(cpu: CpuId) -> CpuSchedStats {
+    SCHEDULER_STATS.get().unwrap().cpu_sched_stats(cpu)
+}
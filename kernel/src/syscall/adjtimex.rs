@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{clock_gettime::ClockId, clock_settime::check_sys_time, SyscallReturn};
+use crate::{
+    prelude::*,
+    time::{
+        clockid_t,
+        ntp::{do_adjtimex, timex_t},
+    },
+};
+
+pub fn sys_adjtimex(timex_addr: Vaddr, ctx: &Context) -> Result<SyscallReturn> {
+    sys_clock_adjtime(ClockId::CLOCK_REALTIME as _, timex_addr, ctx)
+}
+
+pub fn sys_clock_adjtime(
+    clockid: clockid_t,
+    timex_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let mut timex = ctx.user_space().read_val::<timex_t>(timex_addr)?;
+    debug!("clockid = {:?}, timex = {:?}", clockid, timex);
+
+    match ClockId::try_from(clockid) {
+        Ok(ClockId::CLOCK_REALTIME) => (),
+        Ok(_) => return_errno_with_message!(Errno::EOPNOTSUPP, "the clock cannot be adjusted"),
+        Err(_) => return_errno_with_message!(Errno::EINVAL, "invalid clock ID"),
+    }
+
+    if !timex.is_read_only() {
+        check_sys_time(ctx)?;
+    }
+
+    let state = do_adjtimex(&mut timex)?;
+    ctx.user_space().write_val(timex_addr, &timex)?;
+
+    Ok(SyscallReturn::Return(state as _))
+}
@@ -3,6 +3,7 @@
 use crate::syscall::{
     accept::{sys_accept, sys_accept4},
     access::{sys_faccessat, sys_faccessat2},
+    adjtimex::{sys_adjtimex, sys_clock_adjtime},
     bind::sys_bind,
     brk::sys_brk,
     capget::sys_capget,
@@ -12,6 +13,7 @@ use crate::syscall::{
     chown::{sys_fchown, sys_fchownat},
     chroot::sys_chroot,
     clock_gettime::sys_clock_gettime,
+    clock_settime::sys_clock_settime,
     clone::{sys_clone, sys_clone3},
     close::{sys_close, sys_close_range},
     connect::sys_connect,
@@ -57,6 +59,7 @@ use crate::syscall::{
     madvise::sys_madvise,
     mkdir::sys_mkdirat,
     mknod::sys_mknodat,
+    mlock::{sys_mlock, sys_mlock2, sys_mlockall, sys_munlock, sys_munlockall},
     mmap::sys_mmap,
     mount::sys_mount,
     mprotect::sys_mprotect,
@@ -70,6 +73,7 @@ use crate::syscall::{
     pread64::sys_pread64,
     preadv::{sys_preadv, sys_preadv2, sys_readv},
     prlimit64::{sys_getrlimit, sys_prlimit64, sys_setrlimit},
+    process_vm::{sys_process_vm_readv, sys_process_vm_writev},
     pselect6::sys_pselect6,
     pwrite64::sys_pwrite64,
     pwritev::{sys_pwritev, sys_pwritev2, sys_writev},
@@ -81,7 +85,10 @@ use crate::syscall::{
     rt_sigaction::sys_rt_sigaction,
     rt_sigpending::sys_rt_sigpending,
     rt_sigprocmask::sys_rt_sigprocmask,
+    rt_sigqueueinfo::sys_rt_sigqueueinfo,
     rt_sigsuspend::sys_rt_sigsuspend,
+    rt_sigtimedwait::sys_rt_sigtimedwait,
+    rt_tgsigqueueinfo::sys_rt_tgsigqueueinfo,
     sched_affinity::{sys_sched_getaffinity, sys_sched_setaffinity},
     sched_get_priority_max::sys_sched_get_priority_max,
     sched_get_priority_min::sys_sched_get_priority_min,
@@ -113,6 +120,7 @@ use crate::syscall::{
     setreuid::sys_setreuid,
     setsid::sys_setsid,
     setsockopt::sys_setsockopt,
+    settimeofday::sys_settimeofday,
     setuid::sys_setuid,
     shutdown::sys_shutdown,
     sigaltstack::sys_sigaltstack,
@@ -124,9 +132,10 @@ use crate::syscall::{
     statx::sys_statx,
     symlink::sys_symlinkat,
     sync::sys_sync,
+    syslog::sys_syslog,
     tgkill::sys_tgkill,
     timer_create::{sys_timer_create, sys_timer_delete},
-    timer_settime::{sys_timer_gettime, sys_timer_settime},
+    timer_settime::{sys_timer_getoverrun, sys_timer_gettime, sys_timer_settime},
     timerfd_create::sys_timerfd_create,
     timerfd_gettime::sys_timerfd_gettime,
     timerfd_settime::sys_timerfd_settime,
@@ -208,7 +217,11 @@ impl_syscall_nums_and_dispatch_fn! {
     SYS_GETITIMER = 102          => sys_getitimer(args[..2]);
     SYS_SETITIMER = 103          => sys_setitimer(args[..3]);
     SYS_TIMER_CREATE = 107       => sys_timer_create(args[..3]);
+    SYS_TIMER_GETTIME = 108      => sys_timer_gettime(args[..2]);
+    SYS_TIMER_GETOVERRUN = 109   => sys_timer_getoverrun(args[..1]);
+    SYS_TIMER_SETTIME = 110      => sys_timer_settime(args[..4]);
     SYS_TIMER_DELETE = 111       => sys_timer_delete(args[..1]);
+    SYS_SYSLOG = 116             => sys_syslog(args[..3]);
     SYS_SCHED_SETPARAM = 118     => sys_sched_setparam(args[..2]);
     SYS_SCHED_SETSCHEDULER = 119 => sys_sched_setscheduler(args[..3]);
     SYS_SCHED_GETSCHEDULER = 120 => sys_sched_getscheduler(args[..1]);
@@ -225,6 +238,8 @@ impl_syscall_nums_and_dispatch_fn! {
     SYS_RT_SIGACTION = 134       => sys_rt_sigaction(args[..4]);
     SYS_RT_SIGPROCMASK = 135     => sys_rt_sigprocmask(args[..4]);
     SYS_RT_SIGPENDING = 136      => sys_rt_sigpending(args[..2]);
+    SYS_RT_SIGTIMEDWAIT = 137    => sys_rt_sigtimedwait(args[..4]);
+    SYS_RT_SIGQUEUEINFO = 138    => sys_rt_sigqueueinfo(args[..3]);
     SYS_SET_PRIORITY = 140       => sys_set_priority(args[..3]);
     SYS_GET_PRIORITY = 141       => sys_get_priority(args[..2]);
     SYS_SETREGID = 143           => sys_setregid(args[..2]);
@@ -251,6 +266,8 @@ impl_syscall_nums_and_dispatch_fn! {
     SYS_PRCTL = 167              => sys_prctl(args[..5]);
     SYS_GETCPU = 168             => sys_getcpu(args[..3]);
     SYS_GETTIMEOFDAY = 169       => sys_gettimeofday(args[..1]);
+    SYS_SETTIMEOFDAY = 170       => sys_settimeofday(args[..2]);
+    SYS_ADJTIMEX = 171           => sys_adjtimex(args[..1]);
     SYS_GETPID = 172             => sys_getpid(args[..0]);
     SYS_GETPPID = 173            => sys_getppid(args[..0]);
     SYS_GETUID = 174             => sys_getuid(args[..0]);
@@ -284,21 +301,31 @@ impl_syscall_nums_and_dispatch_fn! {
     SYS_MMAP = 222               => sys_mmap(args[..6]);
     SYS_MPROTECT = 226           => sys_mprotect(args[..3]);
     SYS_MSYNC = 227              => sys_msync(args[..3]);
+    SYS_MLOCK = 228              => sys_mlock(args[..2]);
+    SYS_MUNLOCK = 229            => sys_munlock(args[..2]);
+    SYS_MLOCKALL = 230           => sys_mlockall(args[..1]);
+    SYS_MUNLOCKALL = 231         => sys_munlockall(args[..0]);
     SYS_MADVISE = 233            => sys_madvise(args[..3]);
+    SYS_RT_TGSIGQUEUEINFO = 240  => sys_rt_tgsigqueueinfo(args[..4]);
     SYS_ACCEPT4 = 242            => sys_accept4(args[..4]);
     SYS_WAIT4 = 260              => sys_wait4(args[..4]);
     SYS_PRLIMIT64 = 261          => sys_prlimit64(args[..4]);
+    SYS_PROCESS_VM_READV = 270   => sys_process_vm_readv(args[..6]);
+    SYS_PROCESS_VM_WRITEV = 271  => sys_process_vm_writev(args[..6]);
     SYS_SCHED_SETATTR = 274      => sys_sched_setattr(args[..3]);
     SYS_SCHED_GETATTR = 275      => sys_sched_getattr(args[..4]);
     SYS_GETRANDOM = 278          => sys_getrandom(args[..3]);
     SYS_EXECVEAT = 281           => sys_execveat(args[..5], &mut user_ctx);
+    SYS_MLOCK2 = 284             => sys_mlock2(args[..3]);
     SYS_PREADV2 = 286            => sys_preadv2(args[..5]);
     SYS_PWRITEV2 = 287           => sys_pwritev2(args[..5]);
     SYS_STATX = 291              => sys_statx(args[..5]);
     SYS_CLOCK_GETTIME = 403      => sys_clock_gettime(args[..2]);
+    SYS_CLOCK_SETTIME = 404      => sys_clock_settime(args[..2]);
+    SYS_CLOCK_ADJTIME = 405      => sys_clock_adjtime(args[..2]);
     SYS_CLOCK_NANOSLEEP = 407    => sys_clock_nanosleep(args[..4]);
-    SYS_TIMER_GETTIME = 408      => sys_timer_gettime(args[..2]);
-    SYS_TIMER_SETTIME = 409      => sys_timer_settime(args[..4]);
+    SYS_TIMER_GETTIME64 = 408    => sys_timer_gettime(args[..2]);
+    SYS_TIMER_SETTIME64 = 409    => sys_timer_settime(args[..4]);
     SYS_TIMERFD_GETTIME = 410    => sys_timerfd_gettime(args[..2]);
     SYS_TIMERFD_SETTIME = 411    => sys_timerfd_settime(args[..4]);
     SYS_UTIMENSAT = 412          => sys_utimensat(args[..4]);
@@ -9,8 +9,9 @@ use crate::{
     prelude::*,
     process::{
         posix_thread::{thread_table, AsPosixThread},
-        process_table,
+        process_table, Pid, Process,
     },
+    thread::{Thread, Tid},
     time::{
         clockid_t,
         clocks::{
@@ -128,28 +129,64 @@ pub fn read_clock(clockid: clockid_t, ctx: &Context) -> Result<Duration> {
         let dynamic_clockid_info = DynamicClockIdInfo::try_from(clockid)?;
         match dynamic_clockid_info {
             DynamicClockIdInfo::Pid(pid, clock_type) => {
-                let process = process_table::get_process(pid)
-                    .ok_or_else(|| crate::Error::with_message(Errno::EINVAL, "invalid clock ID"))?;
+                let process = find_clock_process(pid)?;
                 match clock_type {
-                    DynamicClockType::Profiling => Ok(process.prof_clock().read_time()),
+                    DynamicClockType::Profiling | DynamicClockType::Scheduling => {
+                        Ok(process.prof_clock().read_time())
+                    }
                     DynamicClockType::Virtual => Ok(process.prof_clock().user_clock().read_time()),
-                    // TODO: support scheduling clock and fd clock.
-                    _ => unimplemented!(),
+                    DynamicClockType::FD => unreachable!(),
                 }
             }
             DynamicClockIdInfo::Tid(tid, clock_type) => {
-                let thread = thread_table::get_thread(tid)
-                    .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid clock ID"))?;
+                let thread = find_clock_thread(tid, ctx)?;
                 let posix_thread = thread.as_posix_thread().unwrap();
                 match clock_type {
-                    DynamicClockType::Profiling => Ok(posix_thread.prof_clock().read_time()),
+                    DynamicClockType::Profiling | DynamicClockType::Scheduling => {
+                        Ok(posix_thread.prof_clock().read_time())
+                    }
                     DynamicClockType::Virtual => {
                         Ok(posix_thread.prof_clock().user_clock().read_time())
                     }
-                    _ => unimplemented!(),
+                    DynamicClockType::FD => unreachable!(),
                 }
             }
-            DynamicClockIdInfo::Fd(_) => unimplemented!(),
+            DynamicClockIdInfo::Fd(_) => {
+                return_errno_with_message!(Errno::EINVAL, "FD clocks are not supported")
+            }
         }
     }
 }
+
+/// Finds the process that a process CPU clock refers to.
+///
+/// Like Linux, PID zero refers to the current process.
+pub(super) fn find_clock_process(pid: Pid) -> Result<Arc<Process>> {
+    if pid == 0 {
+        return Ok(current!());
+    }
+
+    process_table::get_process(pid)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid clock ID"))
+}
+
+/// Finds the thread that a thread CPU clock refers to.
+///
+/// Like Linux, TID zero refers to the current thread, and the thread must be in the current
+/// process.
+pub(super) fn find_clock_thread(tid: Tid, ctx: &Context) -> Result<Arc<Thread>> {
+    if tid == 0 {
+        return Ok(current_thread!());
+    }
+
+    let thread = thread_table::get_thread(tid)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid clock ID"))?;
+    if thread.as_posix_thread().unwrap().process().pid() != ctx.process.pid() {
+        return_errno_with_message!(
+            Errno::EINVAL,
+            "the thread of the clock is not in the current process"
+        );
+    }
+
+    Ok(thread)
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{clock_gettime::ClockId, SyscallReturn};
+use crate::{
+    prelude::*,
+    process::credentials::capabilities::CapSet,
+    time::{clockid_t, clocks::RealTimeClock, timespec_t},
+};
+
+pub fn sys_clock_settime(
+    clockid: clockid_t,
+    timespec_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let timespec = ctx.user_space().read_val::<timespec_t>(timespec_addr)?;
+    debug!("clockid = {:?}, timespec = {:?}", clockid, timespec);
+
+    let time = Duration::try_from(timespec)?;
+
+    // Only `CLOCK_REALTIME` can be set.
+    if !matches!(ClockId::try_from(clockid), Ok(ClockId::CLOCK_REALTIME)) {
+        return_errno_with_message!(Errno::EINVAL, "the clock cannot be set");
+    }
+
+    check_sys_time(ctx)?;
+
+    RealTimeClock::get().set_time(time)?;
+
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Checks whether the current thread is allowed to change the system clocks.
+pub(super) fn check_sys_time(ctx: &Context) -> Result<()> {
+    if !ctx
+        .posix_thread
+        .credentials()
+        .effective_capset()
+        .contains(CapSet::SYS_TIME)
+    {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "changing the system clocks requires `CAP_SYS_TIME`"
+        );
+    }
+
+    Ok(())
+}
@@ -15,8 +15,8 @@ use crate::{
     },
     prelude::*,
     process::{
-        check_executable_file, posix_thread::ThreadName, renew_vm_and_map, Credentials, Process,
-        ProgramToLoad, MAX_ARGV_NUMBER, MAX_ARG_LEN, MAX_ENVP_NUMBER, MAX_ENV_LEN,
+        check_executable_file, posix_thread::ThreadName, renew_vm_and_map, Credentials, Dumpable,
+        Process, ProgramToLoad, MAX_ARGV_NUMBER, MAX_ARG_LEN, MAX_ENVP_NUMBER, MAX_ENV_LEN,
     },
 };
 
@@ -144,6 +144,15 @@ fn do_execve(
     set_gid_from_elf(process, &credentials, &elf_file)?;
     credentials.set_keep_capabilities(false);
 
+    // A process that runs a set-user-ID or set-group-ID program is not dumpable, so that the
+    // privileged program cannot be accessed by unprivileged processes.
+    let credentials = posix_thread.credentials();
+    if credentials.euid() != credentials.ruid() || credentials.egid() != credentials.rgid() {
+        process.set_dumpable(Dumpable::Disable);
+    } else {
+        process.set_dumpable(Dumpable::User);
+    }
+
     // set executable path
     process.set_executable_path(new_executable_path);
     // set signal disposition to default
@@ -32,6 +32,7 @@ pub fn sys_getsockopt(
     let mut raw_option = new_raw_socket_option(level, optname)?;
     debug!("raw option: {:?}", raw_option);
 
+    raw_option.read_input_from_user(optval, optlen)?;
     socket.get_option(raw_option.as_sock_option_mut())?;
 
     let write_len = raw_option.write_to_user(optval, optlen)?;
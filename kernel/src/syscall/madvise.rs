@@ -42,14 +42,35 @@ pub fn sys_madvise(
                 .read_bytes(start, &mut VmWriter::from(buffer.as_mut_slice()))?;
         }
         MadviseBehavior::MADV_DONTNEED => {
+            check_discardable(start, end, ctx)?;
             warn!("MADV_DONTNEED isn't implemented, do nothing for now.");
         }
-        MadviseBehavior::MADV_FREE => madv_free(start, end, ctx)?,
+        MadviseBehavior::MADV_FREE => {
+            check_discardable(start, end, ctx)?;
+            madv_free(start, end, ctx)?
+        }
         _ => todo!(),
     }
     Ok(SyscallReturn::Return(0))
 }
 
+/// Checks whether the pages in the range can be discarded.
+///
+/// The locked pages must stay resident in memory, so they cannot be discarded.
+fn check_discardable(start: Vaddr, end: Vaddr, ctx: &Context) -> Result<()> {
+    let user_space = ctx.user_space();
+    let root_vmar = user_space.root_vmar();
+    if root_vmar
+        .query(start..end)
+        .iter()
+        .any(|vm_mapping| vm_mapping.mlock().is_some())
+    {
+        return_errno_with_message!(Errno::EINVAL, "the locked pages cannot be discarded");
+    }
+
+    Ok(())
+}
+
 fn madv_free(start: Vaddr, end: Vaddr, ctx: &Context) -> Result<()> {
     let user_space = ctx.user_space();
     let root_vmar = user_space.root_vmar();
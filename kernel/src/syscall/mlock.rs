@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::ops::Range;
+
+use align_ext::AlignExt;
+
+use super::SyscallReturn;
+use crate::{
+    prelude::*,
+    process::{credentials::capabilities::CapSet, ResourceType},
+    vm::vmar::vm_mapping::MlockMode,
+};
+
+pub fn sys_mlock(addr: Vaddr, len: usize, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("addr = 0x{:x}, len = 0x{:x}", addr, len);
+
+    do_mlock(addr, len, MlockMode::Populate, ctx)
+}
+
+pub fn sys_mlock2(addr: Vaddr, len: usize, flags: u32, ctx: &Context) -> Result<SyscallReturn> {
+    let flags = MlockFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid mlock flags"))?;
+    debug!(
+        "addr = 0x{:x}, len = 0x{:x}, flags = {:?}",
+        addr, len, flags
+    );
+
+    let mlock = if flags.contains(MlockFlags::MLOCK_ONFAULT) {
+        MlockMode::OnFault
+    } else {
+        MlockMode::Populate
+    };
+    do_mlock(addr, len, mlock, ctx)
+}
+
+pub fn sys_munlock(addr: Vaddr, len: usize, ctx: &Context) -> Result<SyscallReturn> {
+    debug!("addr = 0x{:x}, len = 0x{:x}", addr, len);
+
+    let range = to_page_range(addr, len)?;
+    if range.is_empty() {
+        return Ok(SyscallReturn::Return(0));
+    }
+
+    ctx.user_space().root_vmar().munlock(range)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_mlockall(flags: u32, ctx: &Context) -> Result<SyscallReturn> {
+    let flags = MlockAllFlags::from_bits(flags)
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid mlockall flags"))?;
+    debug!("flags = {:?}", flags);
+
+    if !flags.intersects(MlockAllFlags::MCL_CURRENT | MlockAllFlags::MCL_FUTURE) {
+        return_errno_with_message!(
+            Errno::EINVAL,
+            "either MCL_CURRENT or MCL_FUTURE should be specified"
+        );
+    }
+    check_mlock_permitted(ctx)?;
+
+    let mlock = if flags.contains(MlockAllFlags::MCL_ONFAULT) {
+        MlockMode::OnFault
+    } else {
+        MlockMode::Populate
+    };
+    ctx.user_space().root_vmar().mlock_all(
+        flags.contains(MlockAllFlags::MCL_CURRENT).then_some(mlock),
+        flags.contains(MlockAllFlags::MCL_FUTURE).then_some(mlock),
+    )?;
+    Ok(SyscallReturn::Return(0))
+}
+
+pub fn sys_munlockall(ctx: &Context) -> Result<SyscallReturn> {
+    ctx.user_space().root_vmar().munlock_all()?;
+    Ok(SyscallReturn::Return(0))
+}
+
+fn do_mlock(addr: Vaddr, len: usize, mlock: MlockMode, ctx: &Context) -> Result<SyscallReturn> {
+    check_mlock_permitted(ctx)?;
+
+    let range = to_page_range(addr, len)?;
+    if range.is_empty() {
+        return Ok(SyscallReturn::Return(0));
+    }
+
+    ctx.user_space().root_vmar().mlock(mlock, range)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Checks whether the current process is permitted to lock memory.
+///
+/// Locking memory is permitted if `RLIMIT_MEMLOCK` is not zero or if the
+/// current thread has the `CAP_IPC_LOCK` capability.
+pub(super) fn check_mlock_permitted(ctx: &Context) -> Result<()> {
+    let rlimit_memlock = ctx
+        .process
+        .resource_limits()
+        .get_rlimit(ResourceType::RLIMIT_MEMLOCK)
+        .get_cur();
+    if rlimit_memlock != 0 {
+        return Ok(());
+    }
+
+    let has_ipc_lock = ctx
+        .posix_thread
+        .credentials()
+        .effective_capset()
+        .contains(CapSet::IPC_LOCK);
+    if !has_ipc_lock {
+        return_errno_with_message!(Errno::EPERM, "locking memory is not permitted");
+    }
+
+    Ok(())
+}
+
+/// Returns the range of the pages that contain `addr..addr + len`.
+fn to_page_range(addr: Vaddr, len: usize) -> Result<Range<Vaddr>> {
+    let start = addr.align_down(PAGE_SIZE);
+    let end = addr
+        .checked_add(len)
+        .and_then(|end| end.checked_next_multiple_of(PAGE_SIZE))
+        .ok_or_else(|| Error::with_message(Errno::EINVAL, "integer overflow when (addr + len)"))?;
+
+    Ok(start..end)
+}
+
+bitflags! {
+    struct MlockFlags: u32 {
+        const MLOCK_ONFAULT = 0x01;
+    }
+}
+
+bitflags! {
+    struct MlockAllFlags: u32 {
+        const MCL_CURRENT = 0x01;
+        const MCL_FUTURE  = 0x02;
+        const MCL_ONFAULT = 0x04;
+    }
+}
@@ -5,14 +5,18 @@
 use align_ext::AlignExt;
 use aster_rights::Rights;
 
-use super::SyscallReturn;
+use super::{mlock::check_mlock_permitted, SyscallReturn};
 use crate::{
     fs::{
         file_handle::FileLike,
         file_table::{get_file_fast, FileDesc},
     },
     prelude::*,
-    vm::{perms::VmPerms, vmar::is_userspace_vaddr, vmo::VmoOptions},
+    vm::{
+        perms::VmPerms,
+        vmar::{is_userspace_vaddr, vm_mapping::MlockMode},
+        vmo::VmoOptions,
+    },
 };
 
 pub fn sys_mmap(
@@ -103,6 +107,11 @@ fn do_sys_mmap(
             options = options.is_shared(true);
         }
 
+        if flags.contains(MMapFlags::MAP_LOCKED) {
+            check_mlock_permitted(ctx)?;
+            options = options.mlock(MlockMode::Populate);
+        }
+
         if option.flags.contains(MMapFlags::MAP_ANONYMOUS) {
             if offset != 0 {
                 return_errno_with_message!(
@@ -122,28 +131,36 @@ fn do_sys_mmap(
         } else {
             let mut file_table = ctx.thread_local.borrow_file_table_mut();
             let file = get_file_fast!(&mut file_table, fd);
-            let inode_handle = file.as_inode_or_err()?;
 
-            let access_mode = inode_handle.access_mode();
-            if vm_perms.contains(VmPerms::READ) && !access_mode.is_readable() {
-                return_errno!(Errno::EACCES);
-            }
-            if option.typ() == MMapType::Shared
-                && vm_perms.contains(VmPerms::WRITE)
-                && !access_mode.is_writable()
-            {
-                return_errno!(Errno::EACCES);
+            if let Some(socket) = file.as_socket() {
+                // The pages of a socket (e.g., the receive ring of a packet socket) are shared
+                // between the kernel and the user space, even for private mappings.
+                let vmo = socket.mmap_vmo(offset, len)?;
+                options = options.vmo(vmo).vmo_offset(offset).is_shared(true);
+            } else {
+                let inode_handle = file.as_inode_or_err()?;
+
+                let access_mode = inode_handle.access_mode();
+                if vm_perms.contains(VmPerms::READ) && !access_mode.is_readable() {
+                    return_errno!(Errno::EACCES);
+                }
+                if option.typ() == MMapType::Shared
+                    && vm_perms.contains(VmPerms::WRITE)
+                    && !access_mode.is_writable()
+                {
+                    return_errno!(Errno::EACCES);
+                }
+
+                let dentry = inode_handle.dentry();
+                if dentry.inode().page_cache().is_none() {
+                    return_errno_with_message!(Errno::EBADF, "File does not have page cache");
+                }
+
+                options = options
+                    .dentry(dentry.clone())
+                    .vmo_offset(offset)
+                    .handle_page_faults_around();
             }
-
-            let inode = inode_handle.dentry().inode();
-            if inode.page_cache().is_none() {
-                return_errno_with_message!(Errno::EBADF, "File does not have page cache");
-            }
-
-            options = options
-                .inode(inode.clone())
-                .vmo_offset(offset)
-                .handle_page_faults_around();
         }
 
         options
@@ -10,6 +10,7 @@ use crate::{context::Context, cpu::LinuxAbi, prelude::*};
 
 mod accept;
 mod access;
+mod adjtimex;
 mod alarm;
 mod arch;
 mod arch_prctl;
@@ -22,6 +23,7 @@ mod chmod;
 mod chown;
 mod chroot;
 mod clock_gettime;
+mod clock_settime;
 mod clone;
 mod close;
 mod connect;
@@ -71,6 +73,7 @@ mod lseek;
 mod madvise;
 mod mkdir;
 mod mknod;
+mod mlock;
 mod mmap;
 mod mount;
 mod mprotect;
@@ -87,6 +90,7 @@ mod prctl;
 mod pread64;
 mod preadv;
 mod prlimit64;
+mod process_vm;
 mod pselect6;
 mod pwrite64;
 mod pwritev;
@@ -100,8 +104,11 @@ mod rmdir;
 mod rt_sigaction;
 mod rt_sigpending;
 mod rt_sigprocmask;
+mod rt_sigqueueinfo;
 mod rt_sigreturn;
 mod rt_sigsuspend;
+mod rt_sigtimedwait;
+mod rt_tgsigqueueinfo;
 mod sched_affinity;
 mod sched_get_priority_max;
 mod sched_get_priority_min;
@@ -134,6 +141,7 @@ mod setresuid;
 mod setreuid;
 mod setsid;
 mod setsockopt;
+mod settimeofday;
 mod setuid;
 mod setxattr;
 mod shutdown;
@@ -147,6 +155,7 @@ mod statx;
 mod symlink;
 mod sync;
 mod sysinfo;
+mod syslog;
 mod tgkill;
 mod time;
 mod timer_create;
@@ -3,7 +3,7 @@
 use super::SyscallReturn;
 use crate::{
     prelude::*,
-    process::{posix_thread::MAX_THREAD_NAME_LEN, signal::sig_num::SigNum},
+    process::{posix_thread::MAX_THREAD_NAME_LEN, signal::sig_num::SigNum, Dumpable},
 };
 
 pub fn sys_prctl(
@@ -31,15 +31,14 @@ pub fn sys_prctl(
             ctx.user_space().write_val(write_to_addr, &write_val)?;
         }
         PrctlCmd::PR_GET_DUMPABLE => {
-            // TODO: when coredump is supported, return the actual value
-            return Ok(SyscallReturn::Return(Dumpable::Disable as _));
+            return Ok(SyscallReturn::Return(ctx.process.dumpable() as _));
         }
         PrctlCmd::PR_SET_DUMPABLE(dumpable) => {
             if dumpable != Dumpable::Disable && dumpable != Dumpable::User {
                 return_errno!(Errno::EINVAL)
             }
 
-            // TODO: implement coredump
+            ctx.process.set_dumpable(dumpable);
         }
         PrctlCmd::PR_GET_KEEPCAPS => {
             let keep_cap = {
@@ -130,14 +129,6 @@ pub enum PrctlCmd {
     PR_GET_CHILD_SUBREAPER(Vaddr),
 }
 
-#[repr(u64)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromInt)]
-pub enum Dumpable {
-    Disable = 0, /* No setuid dumping */
-    User = 1,    /* Dump as user of process */
-    Root = 2,    /* Dump as root */
-}
-
 impl PrctlCmd {
     fn from_args(option: i32, arg2: u64, _arg3: u64, _arg4: u64, _arg5: u64) -> Result<PrctlCmd> {
         match option {
@@ -147,7 +138,11 @@ impl PrctlCmd {
             }
             PR_GET_PDEATHSIG => Ok(PrctlCmd::PR_GET_PDEATHSIG(arg2 as _)),
             PR_GET_DUMPABLE => Ok(PrctlCmd::PR_GET_DUMPABLE),
-            PR_SET_DUMPABLE => Ok(PrctlCmd::PR_SET_DUMPABLE(Dumpable::try_from(arg2)?)),
+            PR_SET_DUMPABLE => {
+                let dumpable = u8::try_from(arg2)
+                    .map_err(|_| Error::with_message(Errno::EINVAL, "invalid dumpable value"))?;
+                Ok(PrctlCmd::PR_SET_DUMPABLE(Dumpable::try_from(dumpable)?))
+            }
             PR_SET_NAME => Ok(PrctlCmd::PR_SET_NAME(arg2 as _)),
             PR_GET_NAME => Ok(PrctlCmd::PR_GET_NAME(arg2 as _)),
             PR_GET_TIMERSLACK => todo!(),
@@ -0,0 +1,168 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::ops::Range;
+
+use super::SyscallReturn;
+use crate::{
+    prelude::*,
+    process::{
+        credentials::{check_ptrace_access, PtraceCreds},
+        posix_thread::{thread_table, AsPosixThread},
+        Pid, Process,
+    },
+    util::{copy_remote_io_vecs, MultiRead, MultiWrite, VmReaderArray, VmWriterArray},
+};
+
+pub fn sys_process_vm_readv(
+    pid: Pid,
+    local_io_vec_ptr: Vaddr,
+    local_io_vec_count: usize,
+    remote_io_vec_ptr: Vaddr,
+    remote_io_vec_count: usize,
+    flags: u64,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "pid = {}, local_io_vec_ptr = 0x{:x}, local_io_vec_count = {}, remote_io_vec_ptr = 0x{:x}, remote_io_vec_count = {}, flags = {}",
+        pid, local_io_vec_ptr, local_io_vec_count, remote_io_vec_ptr, remote_io_vec_count, flags
+    );
+
+    check_args(local_io_vec_count, remote_io_vec_count, flags)?;
+
+    let user_space = ctx.user_space();
+    let mut local_writers =
+        VmWriterArray::from_user_io_vecs(&user_space, local_io_vec_ptr, local_io_vec_count)?;
+    let remote_io_vecs = copy_remote_io_vecs(&user_space, remote_io_vec_ptr, remote_io_vec_count)?;
+
+    let process = get_target_process(pid, ctx)?;
+    let vmar_guard = process.lock_root_vmar();
+    let Some(vmar) = vmar_guard.as_ref() else {
+        return_errno_with_message!(Errno::ESRCH, "the target process has exited");
+    };
+
+    let mut buffer = vec![0u8; PAGE_SIZE];
+    let mut copied_len = 0;
+    let res = copy_remote(&remote_io_vecs, &mut copied_len, |remote_addr, len| {
+        let len = len.min(local_writers.sum_lens());
+        let read_len = vmar.read_remote(
+            remote_addr,
+            &mut VmWriter::from(&mut buffer[..len]).to_fallible(),
+        )?;
+        let written_len = local_writers.write(&mut VmReader::from(&buffer[..read_len]))?;
+        Ok((written_len, len))
+    });
+
+    finish_copy(res, copied_len)
+}
+
+pub fn sys_process_vm_writev(
+    pid: Pid,
+    local_io_vec_ptr: Vaddr,
+    local_io_vec_count: usize,
+    remote_io_vec_ptr: Vaddr,
+    remote_io_vec_count: usize,
+    flags: u64,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "pid = {}, local_io_vec_ptr = 0x{:x}, local_io_vec_count = {}, remote_io_vec_ptr = 0x{:x}, remote_io_vec_count = {}, flags = {}",
+        pid, local_io_vec_ptr, local_io_vec_count, remote_io_vec_ptr, remote_io_vec_count, flags
+    );
+
+    check_args(local_io_vec_count, remote_io_vec_count, flags)?;
+
+    let user_space = ctx.user_space();
+    let mut local_readers =
+        VmReaderArray::from_user_io_vecs(&user_space, local_io_vec_ptr, local_io_vec_count)?;
+    let remote_io_vecs = copy_remote_io_vecs(&user_space, remote_io_vec_ptr, remote_io_vec_count)?;
+
+    let process = get_target_process(pid, ctx)?;
+    let vmar_guard = process.lock_root_vmar();
+    let Some(vmar) = vmar_guard.as_ref() else {
+        return_errno_with_message!(Errno::ESRCH, "the target process has exited");
+    };
+
+    let mut buffer = vec![0u8; PAGE_SIZE];
+    let mut copied_len = 0;
+    let res = copy_remote(&remote_io_vecs, &mut copied_len, |remote_addr, len| {
+        let len = len.min(local_readers.sum_lens());
+        let read_len = local_readers.read(&mut VmWriter::from(&mut buffer[..len]))?;
+        let written_len = vmar.write_remote(
+            remote_addr,
+            &mut VmReader::from(&buffer[..read_len]).to_fallible(),
+        )?;
+        Ok((written_len, len))
+    });
+
+    finish_copy(res, copied_len)
+}
+
+/// The maximum number of IO vectors.
+const IOV_MAX: usize = 1024;
+
+fn check_args(local_io_vec_count: usize, remote_io_vec_count: usize, flags: u64) -> Result<()> {
+    if flags != 0 {
+        return_errno_with_message!(Errno::EINVAL, "the flags must be zero");
+    }
+    if local_io_vec_count > IOV_MAX || remote_io_vec_count > IOV_MAX {
+        return_errno_with_message!(Errno::EINVAL, "the number of IO vectors is too large");
+    }
+    Ok(())
+}
+
+fn get_target_process(pid: Pid, ctx: &Context) -> Result<Arc<Process>> {
+    let process = thread_table::get_thread(pid)
+        .and_then(|thread| {
+            thread
+                .as_posix_thread()
+                .map(|posix_thread| posix_thread.process())
+        })
+        .ok_or_else(|| Error::with_message(Errno::ESRCH, "the target process does not exist"))?;
+
+    check_ptrace_access(ctx.posix_thread, &process, PtraceCreds::Real)?;
+
+    Ok(process)
+}
+
+/// Copies the data between the remote IO vectors and the local buffers.
+///
+/// The data are copied in chunks of at most one page. For each chunk, `copy_chunk` is called
+/// with the remote address and the maximum length, and it should return the number of bytes
+/// that are copied and the number of bytes that are expected to be copied. The copying stops
+/// when the local buffers are exhausted or the remote memory is inaccessible.
+fn copy_remote<F>(
+    remote_io_vecs: &[Range<Vaddr>],
+    copied_len: &mut usize,
+    mut copy_chunk: F,
+) -> Result<()>
+where
+    F: FnMut(Vaddr, usize) -> Result<(usize, usize)>,
+{
+    for remote_io_vec in remote_io_vecs.iter() {
+        let mut remote_addr = remote_io_vec.start;
+        while remote_addr < remote_io_vec.end {
+            let max_len = (remote_io_vec.end - remote_addr).min(PAGE_SIZE);
+            let (len, expected_len) = copy_chunk(remote_addr, max_len)?;
+            *copied_len += len;
+            remote_addr += len;
+
+            if expected_len == 0 {
+                // The local buffers are exhausted.
+                return Ok(());
+            }
+            if len < expected_len {
+                return_errno_with_message!(Errno::EFAULT, "the remote memory is inaccessible");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the number of bytes that are copied, or the error if nothing is copied.
+fn finish_copy(res: Result<()>, copied_len: usize) -> Result<SyscallReturn> {
+    match res {
+        Err(err) if copied_len == 0 => Err(err),
+        _ => Ok(SyscallReturn::Return(copied_len as _)),
+    }
+}
@@ -36,10 +36,14 @@ pub fn sys_recvfrom(
                 _ => err,
             })?;
 
-    if let Some(socket_addr) = message_header.addr()
-        && src_addr != 0
-    {
-        write_socket_addr_to_user(socket_addr, src_addr, addrlen_ptr)?;
+    if src_addr != 0 {
+        if let Some(socket_addr) = message_header.addr() {
+            write_socket_addr_to_user(socket_addr, src_addr, addrlen_ptr)?;
+        } else {
+            // Like Linux, report an empty address if the source address is unknown (e.g., if the
+            // sender is an unbound Unix socket).
+            ctx.user_space().write_val(addrlen_ptr, &0i32)?;
+        }
     }
 
     Ok(SyscallReturn::Return(recv_size as _))
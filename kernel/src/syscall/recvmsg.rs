@@ -1,11 +1,16 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::mem::offset_of;
+
 use super::SyscallReturn;
 use crate::{
-    fs::file_table::{get_file_fast, FileDesc},
-    net::socket::util::SendRecvFlags,
+    fs::{
+        file_handle::FileLike,
+        file_table::{get_file_fast, FdFlags, FileDesc},
+    },
+    net::socket::util::{ControlMessage, SendRecvFlags},
     prelude::*,
-    util::net::CUserMsgHdr,
+    util::net::{CControlHeader, CSocketControlType, CSocketOptionLevel, CUserCred, CUserMsgHdr},
 };
 
 pub fn sys_recvmsg(
@@ -22,11 +27,11 @@ pub fn sys_recvmsg(
         sockfd, c_user_msghdr, flags
     );
 
-    let mut file_table = ctx.thread_local.borrow_file_table_mut();
-    let file = get_file_fast!(&mut file_table, sockfd);
-    let socket = file.as_socket_or_err()?;
-
     let (total_bytes, message_header) = {
+        let mut file_table = ctx.thread_local.borrow_file_table_mut();
+        let file = get_file_fast!(&mut file_table, sockfd);
+        let socket = file.as_socket_or_err()?;
+
         let user_space = ctx.user_space();
         let mut io_vec_writer = c_user_msghdr.copy_writer_array_from_user(&user_space)?;
         socket
@@ -42,9 +47,157 @@ pub fn sys_recvmsg(
         c_user_msghdr.write_socket_addr_to_user(addr)?;
     }
 
-    if c_user_msghdr.msg_control != 0 {
-        warn!("receiving control message is not supported");
-    }
+    let msg_flags = message_header.flags();
+    let (control_len, control_flags) = write_control_messages(
+        &c_user_msghdr,
+        message_header.into_control_messages(),
+        flags,
+        ctx,
+    )?;
+    let msg_flags = msg_flags | control_flags;
+
+    let user_space = ctx.user_space();
+    user_space.write_val(
+        user_msghdr_ptr + offset_of!(CUserMsgHdr, msg_controllen),
+        &control_len,
+    )?;
+    user_space.write_val(
+        user_msghdr_ptr + offset_of!(CUserMsgHdr, msg_flags),
+        &msg_flags.bits(),
+    )?;
 
     Ok(SyscallReturn::Return(total_bytes as _))
 }
+
+/// Writes the control messages to the user space.
+///
+/// This method returns the length of the written control messages and the message flags. If the
+/// user buffer is too small, the control messages are truncated and `MSG_CTRUNC` is set in the
+/// message flags. The files that cannot be received due to the truncation are dropped.
+///
+/// If writing any control message fails, all the file descriptors installed by this method are
+/// closed before the error is returned.
+fn write_control_messages(
+    c_user_msghdr: &CUserMsgHdr,
+    control_messages: Vec<ControlMessage>,
+    flags: SendRecvFlags,
+    ctx: &Context,
+) -> Result<(usize, SendRecvFlags)> {
+    const HEADER_LEN: usize = size_of::<CControlHeader>();
+
+    let max_len = if c_user_msghdr.msg_control == 0 {
+        0
+    } else {
+        c_user_msghdr.msg_controllen
+    };
+    let mut written_len = 0;
+    let mut msg_flags = SendRecvFlags::empty();
+    let mut installed_fds = Vec::new();
+
+    for control_message in control_messages {
+        let avail_len = max_len - written_len;
+        if avail_len < HEADER_LEN {
+            msg_flags |= SendRecvFlags::MSG_CTRUNC;
+            continue;
+        }
+
+        let (control_type, payload) = match control_message {
+            ControlMessage::Credentials(cred) => {
+                let c_cred = CUserCred::from(cred);
+                (
+                    CSocketControlType::SCM_CREDENTIALS,
+                    c_cred.as_bytes().to_vec(),
+                )
+            }
+            ControlMessage::Files(mut files) => {
+                let max_files = (avail_len - HEADER_LEN) / size_of::<FileDesc>();
+                if files.len() > max_files {
+                    msg_flags |= SendRecvFlags::MSG_CTRUNC;
+                    files.truncate(max_files);
+                }
+                if files.is_empty() {
+                    continue;
+                }
+
+                let fds = install_files(files, flags, ctx);
+                let payload = fds
+                    .iter()
+                    .flat_map(|fd| fd.as_bytes())
+                    .copied()
+                    .collect::<Vec<_>>();
+                installed_fds.extend(fds);
+                (CSocketControlType::SCM_RIGHTS, payload)
+            }
+        };
+
+        let payload_len = payload.len().min(avail_len - HEADER_LEN);
+        if payload_len < payload.len() {
+            msg_flags |= SendRecvFlags::MSG_CTRUNC;
+        }
+
+        let header = CControlHeader {
+            cmsg_len: CControlHeader::len_for_payload(payload_len),
+            cmsg_level: CSocketOptionLevel::SOL_SOCKET as i32,
+            cmsg_type: control_type as i32,
+        };
+        let res = write_control_message(
+            c_user_msghdr.msg_control + written_len,
+            &header,
+            &payload[..payload_len],
+            ctx,
+        );
+        if let Err(err) = res {
+            close_files(&installed_fds, ctx);
+            return Err(err);
+        }
+
+        written_len += CControlHeader::space_for_payload(payload_len).min(avail_len);
+    }
+
+    Ok((written_len, msg_flags))
+}
+
+fn write_control_message(
+    addr: Vaddr,
+    header: &CControlHeader,
+    payload: &[u8],
+    ctx: &Context,
+) -> Result<()> {
+    let user_space = ctx.user_space();
+    user_space.write_val(addr, header)?;
+    user_space.write_bytes(
+        addr + size_of::<CControlHeader>(),
+        &mut VmReader::from(payload),
+    )?;
+    Ok(())
+}
+
+/// Installs the received files into the file table of the current process.
+fn install_files(
+    files: Vec<Arc<dyn FileLike>>,
+    flags: SendRecvFlags,
+    ctx: &Context,
+) -> Vec<FileDesc> {
+    let fd_flags = if flags.contains(SendRecvFlags::MSG_CMSG_CLOEXEC) {
+        FdFlags::CLOEXEC
+    } else {
+        FdFlags::empty()
+    };
+
+    let mut file_table = ctx.thread_local.borrow_file_table_mut();
+    let mut file_table_locked = file_table.unwrap().write();
+
+    files
+        .into_iter()
+        .map(|file| file_table_locked.insert(file, fd_flags))
+        .collect()
+}
+
+fn close_files(fds: &[FileDesc], ctx: &Context) {
+    let mut file_table = ctx.thread_local.borrow_file_table_mut();
+    let mut file_table_locked = file_table.unwrap().write();
+
+    for fd in fds {
+        file_table_locked.close_file(*fd);
+    }
+}
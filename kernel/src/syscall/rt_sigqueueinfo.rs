@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::SyscallReturn;
+use crate::{
+    prelude::*,
+    process::{
+        kill,
+        signal::{
+            c_types::siginfo_t,
+            constants::SI_TKILL,
+            sig_num::SigNum,
+            signals::user::{UserSignal, UserSignalKind},
+        },
+        Pid,
+    },
+};
+
+/// Queues a signal and its data (i.e., `si_value`) to a process.
+pub fn sys_rt_sigqueueinfo(
+    pid: Pid,
+    sig_num: u8,
+    info_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "pid = {}, sig_num = {}, info_addr = 0x{:x}",
+        pid, sig_num, info_addr
+    );
+
+    let signal = read_sigqueue_signal(sig_num, info_addr, pid == ctx.process.pid(), ctx)?;
+    kill(pid, signal, ctx)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Reads the signal information provided by the user and creates the signal to queue.
+///
+/// Like Linux, the user cannot pretend to be the kernel, `kill`, or `tgkill` (i.e., use a
+/// non-negative `si_code` or `SI_TKILL`) unless the signal is sent to itself.
+///
+/// Only `si_value` is taken from the signal information. The signal is always delivered with
+/// `SI_QUEUE` and the IDs of the current process.
+pub(super) fn read_sigqueue_signal(
+    sig_num: u8,
+    info_addr: Vaddr,
+    is_to_self: bool,
+    ctx: &Context,
+) -> Result<Option<UserSignal>> {
+    let sig_num = if sig_num == 0 {
+        None
+    } else {
+        Some(SigNum::try_from(sig_num)?)
+    };
+
+    let info: siginfo_t = ctx.user_space().read_val(info_addr)?;
+    if (info.si_code >= 0 || info.si_code == SI_TKILL) && !is_to_self {
+        return_errno_with_message!(
+            Errno::EPERM,
+            "the signal code cannot be used to send signals to others"
+        );
+    }
+
+    let signal = sig_num.map(|sig_num| {
+        let pid = ctx.process.pid();
+        let uid = ctx.posix_thread.credentials().ruid();
+        UserSignal::new(sig_num, UserSignalKind::Sigqueue(info.si_value()), pid, uid)
+    });
+    Ok(signal)
+}
@@ -0,0 +1,88 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use ostd::sync::Waiter;
+
+use super::SyscallReturn;
+use crate::{
+    prelude::*,
+    process::signal::{
+        constants::{SIGKILL, SIGSTOP},
+        sig_mask::SigSet,
+        signals::Signal,
+        with_sigmask_changed,
+    },
+    time::timespec_t,
+};
+
+pub fn sys_rt_sigtimedwait(
+    set_addr: Vaddr,
+    info_addr: Vaddr,
+    timeout_addr: Vaddr,
+    sigset_size: usize,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "set_addr = 0x{:x}, info_addr = 0x{:x}, timeout_addr = 0x{:x}, sigset_size = {}",
+        set_addr, info_addr, timeout_addr, sigset_size
+    );
+
+    if sigset_size != size_of::<SigSet>() {
+        return_errno_with_message!(Errno::EINVAL, "invalid sigset size");
+    }
+
+    let user_space = ctx.user_space();
+
+    let set = {
+        let mut set: SigSet = user_space.read_val(set_addr)?;
+        // SIGKILL and SIGSTOP cannot be waited for.
+        set -= SIGKILL;
+        set -= SIGSTOP;
+        set
+    };
+
+    let timeout = if timeout_addr != 0 {
+        let time_spec = user_space.read_val::<timespec_t>(timeout_addr)?;
+        Some(Duration::try_from(time_spec)?)
+    } else {
+        None
+    };
+
+    let signal = do_sigtimedwait(set, timeout.as_ref(), ctx)?;
+
+    if info_addr != 0 {
+        user_space.write_val(info_addr, &signal.to_info())?;
+    }
+
+    Ok(SyscallReturn::Return(signal.num().as_u8() as _))
+}
+
+/// Waits for one of the signals in `set` and dequeues it.
+fn do_sigtimedwait(
+    set: SigSet,
+    timeout: Option<&Duration>,
+    ctx: &Context,
+) -> Result<Box<dyn Signal>> {
+    // The signals that are not in the set stay in the queues.
+    let others = !set;
+
+    // Fast path: One of the signals is already pending.
+    if let Some(signal) = ctx.posix_thread.dequeue_signal(&others) {
+        return Ok(signal);
+    }
+
+    // Like Linux, the signals in the set are unblocked while waiting. So process-directed signals
+    // in the set can be delivered to the current thread, even if they were blocked. Other unblocked
+    // signals interrupt the wait.
+    let waiter = Waiter::new_pair().0;
+    with_sigmask_changed(
+        ctx,
+        |old_mask| old_mask - set,
+        || waiter.pause_until_or_timeout(|| ctx.posix_thread.dequeue_signal(&others), timeout),
+    )
+    .map_err(|err| match err.error() {
+        Errno::ETIME => Error::new(Errno::EAGAIN),
+        _ => err,
+    })
+}
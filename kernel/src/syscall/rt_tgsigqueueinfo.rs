@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::{rt_sigqueueinfo::read_sigqueue_signal, SyscallReturn};
+use crate::{
+    prelude::*,
+    process::{tgkill, Pid},
+    thread::Tid,
+};
+
+/// Queues a signal and its data (i.e., `si_value`) to a thread.
+pub fn sys_rt_tgsigqueueinfo(
+    tgid: Pid,
+    tid: Tid,
+    sig_num: u8,
+    info_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    debug!(
+        "tgid = {}, tid = {}, sig_num = {}, info_addr = 0x{:x}",
+        tgid, tid, sig_num, info_addr
+    );
+
+    let signal = read_sigqueue_signal(sig_num, info_addr, tid == ctx.posix_thread.tid(), ctx)?;
+    tgkill(tid, tgid, signal, ctx)?;
+    Ok(SyscallReturn::Return(0))
+}
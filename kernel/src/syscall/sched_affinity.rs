@@ -5,7 +5,11 @@ use core::{cmp, mem, sync::atomic::Ordering};
 use ostd::cpu::{num_cpus, CpuId, CpuSet};
 
 use super::SyscallReturn;
-use crate::{prelude::*, process::posix_thread::thread_table, thread::Tid};
+use crate::{
+    prelude::*,
+    process::posix_thread::thread_table,
+    thread::{Thread, Tid},
+};
 
 pub fn sys_sched_getaffinity(
     tid: Tid,
@@ -28,8 +32,8 @@ pub fn sys_sched_getaffinity(
 
 // TODO: The manual page of `sched_setaffinity` says that if the thread is not
 // running on the CPU specified in the affinity mask, it would be migrated to
-// one of the CPUs specified in the mask. We currently do not support this
-// feature as the scheduler is not ready for migration yet.
+// one of the CPUs specified in the mask. Currently, the thread is only moved
+// to an allowed CPU the next time it is woken up.
 pub fn sys_sched_setaffinity(
     tid: Tid,
     cpuset_size: usize,
@@ -38,17 +42,18 @@ pub fn sys_sched_setaffinity(
 ) -> Result<SyscallReturn> {
     let user_cpu_set = read_cpu_set_from(ctx.user_space(), cpuset_size, cpu_set_ptr)?;
 
-    match tid {
-        0 => ctx
-            .thread
+    let set_affinity = |thread: &Thread| -> Result<()> {
+        thread.sched_attr().check_affinity(&user_cpu_set)?;
+        thread
             .atomic_cpu_affinity()
-            .store(&user_cpu_set, Ordering::Relaxed),
+            .store(&user_cpu_set, Ordering::Relaxed);
+        Ok(())
+    };
+
+    match tid {
+        0 => set_affinity(ctx.thread)?,
         _ => match thread_table::get_thread(tid) {
-            Some(thread) => {
-                thread
-                    .atomic_cpu_affinity()
-                    .store(&user_cpu_set, Ordering::Relaxed);
-            }
+            Some(thread) => set_affinity(thread.as_ref())?,
             None => return Err(Error::with_message(Errno::ESRCH, "thread does not exist")),
         },
     }
@@ -9,8 +9,8 @@ use super::{
 use crate::{
     prelude::*,
     process::posix_thread::thread_table,
-    sched::{Nice, RealTimePolicy, SchedAttr, SchedPolicy},
-    thread::Tid,
+    sched::{DeadlineParams, Nice, RealTimePolicy, SchedAttr, SchedPolicy},
+    thread::{Thread, Tid},
 };
 
 pub(super) const SCHED_NORMAL: u32 = 0;
@@ -19,9 +19,12 @@ pub(super) const SCHED_RR: u32 = 2;
 // pub(super) const SCHED_BATCH: u32 = 3; // not supported (never).
 // SCHED_ISO: reserved but not implemented yet on Linux.
 pub(super) const SCHED_IDLE: u32 = 5;
-// pub(super) const SCHED_DEADLINE: u32 = 6; // not supported yet.
+pub(super) const SCHED_DEADLINE: u32 = 6;
 // pub(super) const SCHED_EXT: u32 = 7; // not supported (never).
 
+/// Sends `SIGXCPU` when a `SCHED_DEADLINE` thread overruns its runtime.
+pub(super) const SCHED_FLAG_DL_OVERRUN: u64 = 0x04;
+
 #[derive(Default, Debug, Pod, Clone, Copy)]
 #[repr(C)]
 pub(super) struct LinuxSchedAttr {
@@ -60,6 +63,19 @@ impl TryFrom<SchedPolicy> for LinuxSchedAttr {
                 ..Default::default()
             },
 
+            SchedPolicy::Deadline(params) => LinuxSchedAttr {
+                sched_policy: SCHED_DEADLINE,
+                sched_flags: if params.overrun_signal() {
+                    SCHED_FLAG_DL_OVERRUN
+                } else {
+                    0
+                },
+                sched_runtime: params.runtime(),
+                sched_deadline: params.deadline(),
+                sched_period: params.period(),
+                ..Default::default()
+            },
+
             SchedPolicy::RealTime { rt_prio, rt_policy } => LinuxSchedAttr {
                 sched_policy: match rt_policy {
                     RealTimePolicy::Fifo => SCHED_FIFO,
@@ -114,6 +130,16 @@ impl TryFrom<LinuxSchedAttr> for SchedPolicy {
 
             SCHED_IDLE => SchedPolicy::Idle,
 
+            SCHED_DEADLINE => SchedPolicy::Deadline(
+                DeadlineParams::new(
+                    value.sched_runtime,
+                    value.sched_deadline,
+                    value.sched_period,
+                    value.sched_flags & SCHED_FLAG_DL_OVERRUN != 0,
+                )
+                .map_err(|msg| Error::with_message(Errno::EINVAL, msg))?,
+            ),
+
             _ => {
                 return Err(Error::with_message(
                     Errno::EINVAL,
@@ -181,13 +207,21 @@ pub(super) fn access_sched_attr_with<T>(
     tid: Tid,
     ctx: &Context,
     f: impl FnOnce(&SchedAttr) -> Result<T>,
+) -> Result<T> {
+    access_thread_with(tid, ctx, |thread| f(thread.sched_attr()))
+}
+
+pub(super) fn access_thread_with<T>(
+    tid: Tid,
+    ctx: &Context,
+    f: impl FnOnce(&Thread) -> Result<T>,
 ) -> Result<T> {
     match tid {
-        0 => f(ctx.thread.sched_attr()),
+        0 => f(ctx.thread),
         _ if tid > (i32::MAX as u32) => Err(Error::with_message(Errno::EINVAL, "invalid tid")),
         _ => f(thread_table::get_thread(tid)
             .ok_or_else(|| Error::with_message(Errno::ESRCH, "thread does not exist"))?
-            .sched_attr()),
+            .as_ref()),
     }
 }
 
@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::sync::atomic::Ordering;
+
 use super::{
-    sched_getattr::{access_sched_attr_with, read_linux_sched_attr_from_user},
+    sched_getattr::{access_thread_with, read_linux_sched_attr_from_user},
     SyscallReturn,
 };
 use crate::{prelude::*, sched::SchedPolicy, thread::Tid};
@@ -19,9 +21,9 @@ pub fn sys_sched_setattr(
 
     let attr = read_linux_sched_attr_from_user(addr, ctx).map_err(|_| Error::new(Errno::EINVAL))?;
     let policy = SchedPolicy::try_from(attr)?;
-    access_sched_attr_with(tid, ctx, |attr| {
-        attr.set_policy(policy);
-        Ok(())
+    access_thread_with(tid, ctx, |thread| {
+        let affinity = thread.atomic_cpu_affinity().load(Ordering::Relaxed);
+        thread.sched_attr().set_policy(policy, &affinity)
     })?;
 
     Ok(SyscallReturn::Return(0))
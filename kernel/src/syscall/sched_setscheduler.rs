@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::sync::atomic::Ordering;
+
 use super::{
-    sched_getattr::{access_sched_attr_with, LinuxSchedAttr},
+    sched_getattr::{access_thread_with, LinuxSchedAttr},
     SyscallReturn,
 };
 use crate::{prelude::*, thread::Tid};
@@ -24,9 +26,9 @@ pub fn sys_sched_setscheduler(
     };
 
     let policy = attr.try_into()?;
-    access_sched_attr_with(tid, ctx, |attr| {
-        attr.set_policy(policy);
-        Ok(())
+    access_thread_with(tid, ctx, |thread| {
+        let affinity = thread.atomic_cpu_affinity().load(Ordering::Relaxed);
+        thread.sched_attr().set_policy(policy, &affinity)
     })?;
 
     Ok(SyscallReturn::Return(0))
@@ -2,10 +2,10 @@
 
 use super::SyscallReturn;
 use crate::{
-    fs::file_table::{get_file_fast, FileDesc},
-    net::socket::util::{MessageHeader, SendRecvFlags},
+    fs::file_table::{get_file_fast, FileDesc, WithFileTable},
+    net::socket::util::{ControlMessage, MessageHeader, SendRecvFlags},
     prelude::*,
-    util::net::CUserMsgHdr,
+    util::net::{CControlHeader, CSocketControlType, CSocketOptionLevel, CUserCred, CUserMsgHdr},
 };
 
 pub fn sys_sendmsg(
@@ -23,25 +23,19 @@ pub fn sys_sendmsg(
     );
 
     let mut file_table = ctx.thread_local.borrow_file_table_mut();
-    let file = get_file_fast!(&mut file_table, sockfd);
-    let socket = file.as_socket_or_err()?;
 
     let user_space = ctx.user_space();
     let (mut io_vec_reader, message_header) = {
         let addr = c_user_msghdr.read_socket_addr_from_user()?;
         let io_vec_reader = c_user_msghdr.copy_reader_array_from_user(&user_space)?;
+        let control_messages = read_control_messages(&c_user_msghdr, &mut file_table, ctx)?;
 
-        let control_message = {
-            if c_user_msghdr.msg_control != 0 {
-                // TODO: support sending control message
-                warn!("control message is not supported now");
-            }
-            None
-        };
-
-        (io_vec_reader, MessageHeader::new(addr, control_message))
+        (io_vec_reader, MessageHeader::new(addr, control_messages))
     };
 
+    let file = get_file_fast!(&mut file_table, sockfd);
+    let socket = file.as_socket_or_err()?;
+
     let total_bytes = socket
         .sendmsg(&mut io_vec_reader, message_header, flags)
         .map_err(|err| match err.error() {
@@ -52,3 +46,74 @@ pub fn sys_sendmsg(
 
     Ok(SyscallReturn::Return(total_bytes as _))
 }
+
+/// Reads and parses the control messages from the user space.
+fn read_control_messages(
+    c_user_msghdr: &CUserMsgHdr,
+    file_table: &mut impl WithFileTable,
+    ctx: &Context,
+) -> Result<Vec<ControlMessage>> {
+    // The maximum length of the control messages (`sysctl_optmem_max` in Linux).
+    const MAX_CONTROL_LEN: usize = 20480;
+
+    let control_len = c_user_msghdr.msg_controllen;
+    if control_len == 0 {
+        return Ok(Vec::new());
+    }
+    if control_len > MAX_CONTROL_LEN {
+        return_errno_with_message!(Errno::ENOBUFS, "the control messages are too long");
+    }
+
+    let mut buffer = vec![0u8; control_len];
+    ctx.user_space().read_bytes(
+        c_user_msghdr.msg_control,
+        &mut VmWriter::from(buffer.as_mut_slice()),
+    )?;
+
+    let mut control_messages = Vec::new();
+
+    let mut remaining = buffer.as_slice();
+    while remaining.len() >= size_of::<CControlHeader>() {
+        let header = CControlHeader::from_bytes(remaining);
+        if header.cmsg_len < size_of::<CControlHeader>() || header.cmsg_len > remaining.len() {
+            return_errno_with_message!(Errno::EINVAL, "the control message length is invalid");
+        }
+        let payload = &remaining[size_of::<CControlHeader>()..header.cmsg_len];
+        remaining = &remaining[header.space().min(remaining.len())..];
+
+        // Control messages at other levels are protocol-specific and are not supported.
+        if !matches!(
+            CSocketOptionLevel::try_from(header.cmsg_level),
+            Ok(CSocketOptionLevel::SOL_SOCKET)
+        ) {
+            warn!("unsupported control message level: {}", header.cmsg_level);
+            continue;
+        }
+
+        let control_type = CSocketControlType::try_from(header.cmsg_type)
+            .map_err(|_| Error::with_message(Errno::EINVAL, "unsupported control message type"))?;
+        let control_message = match control_type {
+            CSocketControlType::SCM_RIGHTS => {
+                let files = file_table.read_with(|file_table| {
+                    payload
+                        .chunks_exact(size_of::<FileDesc>())
+                        .map(|bytes| {
+                            let fd = FileDesc::from_bytes(bytes);
+                            file_table.get_file(fd).cloned()
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })?;
+                ControlMessage::Files(files)
+            }
+            CSocketControlType::SCM_CREDENTIALS => {
+                if payload.len() != size_of::<CUserCred>() {
+                    return_errno_with_message!(Errno::EINVAL, "the credentials are invalid");
+                }
+                ControlMessage::Credentials(CUserCred::from_bytes(payload).into())
+            }
+        };
+        control_messages.push(control_message);
+    }
+
+    Ok(control_messages)
+}
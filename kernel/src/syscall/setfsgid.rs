@@ -12,10 +12,9 @@ pub fn sys_setfsgid(gid: i32, ctx: &Context) -> Result<SyscallReturn> {
         Some(Gid::new(gid as u32))
     };
 
-    let old_fsgid = {
-        let credentials = ctx.posix_thread.credentials_mut();
-        credentials.set_fsgid(fsgid)?
-    };
+    let old_fsgid = ctx
+        .posix_thread
+        .update_credentials(|credentials| credentials.set_fsgid(fsgid))?;
 
     Ok(SyscallReturn::Return(
         <Gid as Into<u32>>::into(old_fsgid) as _
@@ -12,10 +12,9 @@ pub fn sys_setfsuid(uid: i32, ctx: &Context) -> Result<SyscallReturn> {
         Some(Uid::new(uid as u32))
     };
 
-    let old_fsuid = {
-        let credentials = ctx.posix_thread.credentials_mut();
-        credentials.set_fsuid(fsuid)?
-    };
+    let old_fsuid = ctx
+        .posix_thread
+        .update_credentials(|credentials| credentials.set_fsuid(fsuid))?;
 
     Ok(SyscallReturn::Return(
         <Uid as Into<u32>>::into(old_fsuid) as _
@@ -12,8 +12,10 @@ pub fn sys_setgid(gid: i32, ctx: &Context) -> Result<SyscallReturn> {
 
     let gid = Gid::new(gid as u32);
 
-    let credentials = ctx.posix_thread.credentials_mut();
-    credentials.set_gid(gid);
+    ctx.posix_thread.update_credentials(|credentials| {
+        credentials.set_gid(gid);
+        Ok(())
+    })?;
 
     Ok(SyscallReturn::Return(0))
 }
@@ -18,8 +18,8 @@ pub fn sys_setregid(rgid: i32, egid: i32, ctx: &Context) -> Result<SyscallReturn
         None
     };
 
-    let credentials = ctx.posix_thread.credentials_mut();
-    credentials.set_regid(rgid, egid)?;
+    ctx.posix_thread
+        .update_credentials(|credentials| credentials.set_regid(rgid, egid))?;
 
     Ok(SyscallReturn::Return(0))
 }
@@ -24,8 +24,8 @@ pub fn sys_setresgid(rgid: i32, egid: i32, sgid: i32, ctx: &Context) -> Result<S
 
     debug!("rgid = {:?}, egid = {:?}, sgid = {:?}", rgid, egid, sgid);
 
-    let credentials = ctx.posix_thread.credentials_mut();
-    credentials.set_resgid(rgid, egid, sgid)?;
+    ctx.posix_thread
+        .update_credentials(|credentials| credentials.set_resgid(rgid, egid, sgid))?;
 
     Ok(SyscallReturn::Return(0))
 }
@@ -24,9 +24,8 @@ pub fn sys_setresuid(ruid: i32, euid: i32, suid: i32, ctx: &Context) -> Result<S
 
     debug!("ruid = {:?}, euid = {:?}, suid = {:?}", ruid, euid, suid);
 
-    let credentials = ctx.posix_thread.credentials_mut();
-
-    credentials.set_resuid(ruid, euid, suid)?;
+    ctx.posix_thread
+        .update_credentials(|credentials| credentials.set_resuid(ruid, euid, suid))?;
 
     Ok(SyscallReturn::Return(0))
 }
@@ -18,8 +18,8 @@ pub fn sys_setreuid(ruid: i32, euid: i32, ctx: &Context) -> Result<SyscallReturn
         None
     };
 
-    let credentials = ctx.posix_thread.credentials_mut();
-    credentials.set_reuid(ruid, euid)?;
+    ctx.posix_thread
+        .update_credentials(|credentials| credentials.set_reuid(ruid, euid))?;
 
     Ok(SyscallReturn::Return(0))
 }
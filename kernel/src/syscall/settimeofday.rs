@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use core::time::Duration;
+
+use super::{clock_settime::check_sys_time, SyscallReturn};
+use crate::{
+    prelude::*,
+    time::{clocks::RealTimeClock, timeval_t},
+};
+
+/// The `timezone` structure used by `settimeofday`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Pod)]
+struct timezone_t {
+    minuteswest: i32,
+    dsttime: i32,
+}
+
+pub fn sys_settimeofday(
+    timeval_addr: Vaddr,
+    timezone_addr: Vaddr,
+    ctx: &Context,
+) -> Result<SyscallReturn> {
+    let time = if timeval_addr != 0 {
+        let timeval = ctx.user_space().read_val::<timeval_t>(timeval_addr)?;
+        Some(Duration::try_from(timeval)?)
+    } else {
+        None
+    };
+
+    let timezone = if timezone_addr != 0 {
+        let timezone = ctx.user_space().read_val::<timezone_t>(timezone_addr)?;
+        if !(-15 * 60..=15 * 60).contains(&timezone.minuteswest) {
+            return_errno_with_message!(Errno::EINVAL, "the timezone is out of range");
+        }
+        Some(timezone)
+    } else {
+        None
+    };
+
+    debug!("time = {:?}, timezone = {:?}", time, timezone);
+
+    check_sys_time(ctx)?;
+
+    // The use of the timezone structure is obsolete (see `sys_gettimeofday`), so the timezone
+    // is validated but not recorded.
+    if let Some(time) = time {
+        RealTimeClock::get().set_time(time)?;
+    }
+
+    Ok(SyscallReturn::Return(0))
+}
@@ -12,8 +12,10 @@ pub fn sys_setuid(uid: i32, ctx: &Context) -> Result<SyscallReturn> {
 
     let uid = Uid::new(uid as u32);
 
-    let credentials = ctx.posix_thread.credentials_mut();
-    credentials.set_uid(uid);
+    ctx.posix_thread.update_credentials(|credentials| {
+        credentials.set_uid(uid);
+        Ok(())
+    })?;
 
     Ok(SyscallReturn::Return(0))
 }
@@ -4,11 +4,12 @@ use super::SyscallReturn;
 use crate::{
     fs::{file_handle::FileLike, file_table::FdFlags},
     net::socket::{
-        ip::{DatagramSocket, StreamSocket},
+        ip::{DatagramSocket, RawSocket, StreamSocket},
         netlink::{
             is_valid_protocol, NetlinkRouteSocket, NetlinkUeventSocket, StandardNetlinkProtocol,
         },
-        unix::UnixStreamSocket,
+        packet::{PacketSocket, PacketSocketKind},
+        unix::{UnixDatagramSocket, UnixStreamSocket},
         vsock::VsockStreamSocket,
     },
     prelude::*,
@@ -25,9 +26,14 @@ pub fn sys_socket(domain: i32, type_: i32, protocol: i32, ctx: &Context) -> Resu
     );
     let is_nonblocking = sock_flags.contains(SockFlags::SOCK_NONBLOCK);
     let file_like = match (domain, sock_type) {
-        // FIXME: SOCK_SEQPACKET is added to run fcntl_test, not supported yet.
-        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM | SockType::SOCK_SEQPACKET) => {
-            UnixStreamSocket::new(is_nonblocking) as Arc<dyn FileLike>
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM) => {
+            UnixStreamSocket::new(is_nonblocking, false) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_SEQPACKET) => {
+            UnixStreamSocket::new(is_nonblocking, true) as Arc<dyn FileLike>
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_DGRAM) => {
+            UnixDatagramSocket::new(is_nonblocking) as Arc<dyn FileLike>
         }
         (CSocketAddrFamily::AF_INET, SockType::SOCK_STREAM) => {
             let protocol = Protocol::try_from(protocol)?;
@@ -46,9 +52,21 @@ pub fn sys_socket(domain: i32, type_: i32, protocol: i32, ctx: &Context) -> Resu
                 Protocol::IPPROTO_IP | Protocol::IPPROTO_UDP => {
                     DatagramSocket::new(is_nonblocking) as Arc<dyn FileLike>
                 }
+                Protocol::IPPROTO_ICMP => RawSocket::new_ping(is_nonblocking)? as Arc<dyn FileLike>,
                 _ => return_errno_with_message!(Errno::EAFNOSUPPORT, "unsupported protocol"),
             }
         }
+        (CSocketAddrFamily::AF_INET, SockType::SOCK_RAW) => {
+            debug!("protocol = {}", protocol);
+            // Raw sockets can be created for any IP protocol except `IPPROTO_IP`.
+            let protocol = match u8::try_from(protocol) {
+                Ok(0) | Err(_) => {
+                    return_errno_with_message!(Errno::EPROTONOSUPPORT, "unsupported protocol")
+                }
+                Ok(protocol) => protocol,
+            };
+            RawSocket::new_raw(is_nonblocking, protocol)? as Arc<dyn FileLike>
+        }
         (CSocketAddrFamily::AF_NETLINK, SockType::SOCK_RAW | SockType::SOCK_DGRAM) => {
             let netlink_family = StandardNetlinkProtocol::try_from(protocol as u32);
             debug!("netlink family = {:?}", netlink_family);
@@ -76,6 +94,16 @@ pub fn sys_socket(domain: i32, type_: i32, protocol: i32, ctx: &Context) -> Resu
                 }
             }
         }
+        (CSocketAddrFamily::AF_PACKET, SockType::SOCK_RAW | SockType::SOCK_DGRAM) => {
+            // The protocol is in network byte order.
+            let protocol = u16::from_be(protocol as u16);
+            debug!("protocol = {:#x}", protocol);
+            let kind = match sock_type {
+                SockType::SOCK_RAW => PacketSocketKind::Raw,
+                _ => PacketSocketKind::Dgram,
+            };
+            PacketSocket::new(is_nonblocking, kind, protocol)? as Arc<dyn FileLike>
+        }
         (CSocketAddrFamily::AF_VSOCK, SockType::SOCK_STREAM) => {
             Arc::new(VsockStreamSocket::new(is_nonblocking)?) as Arc<dyn FileLike>
         }
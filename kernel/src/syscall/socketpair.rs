@@ -2,8 +2,11 @@
 
 use super::SyscallReturn;
 use crate::{
-    fs::file_table::{FdFlags, FileDesc},
-    net::socket::unix::UnixStreamSocket,
+    fs::{
+        file_handle::FileLike,
+        file_table::{FdFlags, FileDesc},
+    },
+    net::socket::unix::{UnixDatagramSocket, UnixStreamSocket},
     prelude::*,
     util::net::{CSocketAddrFamily, Protocol, SockFlags, SockType, SOCK_TYPE_MASK},
 };
@@ -28,7 +31,16 @@ pub fn sys_socketpair(
     let nonblocking = sock_flags.contains(SockFlags::SOCK_NONBLOCK);
     let (socket_a, socket_b) = match (domain, sock_type) {
         (CSocketAddrFamily::AF_UNIX, SockType::SOCK_STREAM) => {
-            UnixStreamSocket::new_pair(nonblocking)
+            let (socket_a, socket_b) = UnixStreamSocket::new_pair(nonblocking, false);
+            (socket_a as Arc<dyn FileLike>, socket_b as Arc<dyn FileLike>)
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_SEQPACKET) => {
+            let (socket_a, socket_b) = UnixStreamSocket::new_pair(nonblocking, true);
+            (socket_a as Arc<dyn FileLike>, socket_b as Arc<dyn FileLike>)
+        }
+        (CSocketAddrFamily::AF_UNIX, SockType::SOCK_DGRAM) => {
+            let (socket_a, socket_b) = UnixDatagramSocket::new_pair(nonblocking);
+            (socket_a as Arc<dyn FileLike>, socket_b as Arc<dyn FileLike>)
         }
         _ => return_errno_with_message!(
             Errno::EAFNOSUPPORT,
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use alloc::format;
+
+use aster_logger::{
+    console_log_levels,
+    log_buffer::{self, LogRecord, MAX_TEXT_LEN, NR_RECORDS},
+};
+use int_to_c_enum::TryFromInt;
+
+use super::SyscallReturn;
+use crate::{
+    device::kmsg::{clear_seq, record_wait_queue, set_clear_seq},
+    prelude::*,
+    process::credentials::capabilities::CapSet,
+};
+
+pub fn sys_syslog(action: i32, buf: Vaddr, len: i32, ctx: &Context) -> Result<SyscallReturn> {
+    let action = SyslogAction::try_from(action)?;
+    debug!("action = {:?}, buf = 0x{:x}, len = {}", action, buf, len);
+
+    check_syslog_permissions(action, ctx)?;
+
+    let res = match action {
+        SyslogAction::SYSLOG_ACTION_CLOSE | SyslogAction::SYSLOG_ACTION_OPEN => 0,
+        SyslogAction::SYSLOG_ACTION_READ => {
+            let len = check_user_buffer(buf, len)?;
+            if len == 0 {
+                return Ok(SyscallReturn::Return(0));
+            }
+
+            let text = record_wait_queue().pause_until(|| read_unread_records(len))?;
+            ctx.user_space()
+                .write_bytes(buf, &mut VmReader::from(text.as_bytes()))?;
+            text.len()
+        }
+        SyslogAction::SYSLOG_ACTION_READ_ALL | SyslogAction::SYSLOG_ACTION_READ_CLEAR => {
+            let len = check_user_buffer(buf, len)?;
+
+            let (text, end_seq) = read_last_records(len);
+            ctx.user_space()
+                .write_bytes(buf, &mut VmReader::from(text.as_bytes()))?;
+
+            if action == SyslogAction::SYSLOG_ACTION_READ_CLEAR {
+                set_clear_seq(end_seq);
+            }
+            text.len()
+        }
+        SyslogAction::SYSLOG_ACTION_CLEAR => {
+            set_clear_seq(log_buffer::next_seq());
+            0
+        }
+        SyslogAction::SYSLOG_ACTION_CONSOLE_OFF => {
+            let levels = console_log_levels();
+            let mut saved_level = SAVED_CONSOLE_LOGLEVEL.lock();
+            if saved_level.is_none() {
+                *saved_level = Some(levels.console_loglevel());
+            }
+            levels.set_console_loglevel(levels.minimum_console_loglevel());
+            0
+        }
+        SyslogAction::SYSLOG_ACTION_CONSOLE_ON => {
+            if let Some(level) = SAVED_CONSOLE_LOGLEVEL.lock().take() {
+                console_log_levels().set_console_loglevel(level);
+            }
+            0
+        }
+        SyslogAction::SYSLOG_ACTION_CONSOLE_LEVEL => {
+            if !(1..=8).contains(&len) {
+                return_errno_with_message!(Errno::EINVAL, "the console log level is invalid");
+            }
+
+            let levels = console_log_levels();
+            let level = (len as u8).max(levels.minimum_console_loglevel());
+            levels.set_console_loglevel(level);
+            // Like Linux, setting the level implicitly turns on the console.
+            *SAVED_CONSOLE_LOGLEVEL.lock() = None;
+            0
+        }
+        SyslogAction::SYSLOG_ACTION_SIZE_UNREAD => {
+            let mut seq = SYSLOG_SEQ.lock();
+            *seq = (*seq).max(log_buffer::first_seq());
+            (*seq..log_buffer::next_seq())
+                .filter_map(log_buffer::read_record)
+                .map(|record| format_record(&record).len())
+                .sum()
+        }
+        SyslogAction::SYSLOG_ACTION_SIZE_BUFFER => NR_RECORDS * MAX_TEXT_LEN,
+    };
+
+    Ok(SyscallReturn::Return(res as _))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, TryFromInt)]
+#[repr(i32)]
+#[expect(non_camel_case_types)]
+enum SyslogAction {
+    SYSLOG_ACTION_CLOSE = 0,
+    SYSLOG_ACTION_OPEN = 1,
+    SYSLOG_ACTION_READ = 2,
+    SYSLOG_ACTION_READ_ALL = 3,
+    SYSLOG_ACTION_READ_CLEAR = 4,
+    SYSLOG_ACTION_CLEAR = 5,
+    SYSLOG_ACTION_CONSOLE_OFF = 6,
+    SYSLOG_ACTION_CONSOLE_ON = 7,
+    SYSLOG_ACTION_CONSOLE_LEVEL = 8,
+    SYSLOG_ACTION_SIZE_UNREAD = 9,
+    SYSLOG_ACTION_SIZE_BUFFER = 10,
+}
+
+/// The sequence number of the next record to read by `SYSLOG_ACTION_READ`.
+static SYSLOG_SEQ: Mutex<u64> = Mutex::new(0);
+
+/// The console log level saved by `SYSLOG_ACTION_CONSOLE_OFF`.
+static SAVED_CONSOLE_LOGLEVEL: Mutex<Option<u8>> = Mutex::new(None);
+
+/// Checks whether the current thread is allowed to perform the action.
+///
+/// Like Linux with `kernel.dmesg_restrict` unset, everyone can read all the records and the size
+/// of the log buffer. Other actions require `CAP_SYSLOG` (or `CAP_SYS_ADMIN` for compatibility).
+fn check_syslog_permissions(action: SyslogAction, ctx: &Context) -> Result<()> {
+    if matches!(
+        action,
+        SyslogAction::SYSLOG_ACTION_READ_ALL | SyslogAction::SYSLOG_ACTION_SIZE_BUFFER
+    ) {
+        return Ok(());
+    }
+
+    let capset = ctx.posix_thread.credentials().effective_capset();
+    if !capset.contains(CapSet::SYSLOG) && !capset.contains(CapSet::SYS_ADMIN) {
+        return_errno_with_message!(Errno::EPERM, "the action requires `CAP_SYSLOG`");
+    }
+
+    Ok(())
+}
+
+fn check_user_buffer(buf: Vaddr, len: i32) -> Result<usize> {
+    if buf == 0 {
+        return_errno_with_message!(Errno::EINVAL, "the buffer is null");
+    }
+    if len < 0 {
+        return_errno_with_message!(Errno::EINVAL, "the buffer length is negative");
+    }
+
+    Ok(len as usize)
+}
+
+/// Reads and consumes the unread records that fit in `len` bytes.
+///
+/// If the first unread record is longer than `len` bytes, it is truncated. This method returns
+/// `None` if there are no unread records.
+fn read_unread_records(len: usize) -> Option<String> {
+    let mut seq = SYSLOG_SEQ.lock();
+    *seq = (*seq).max(log_buffer::first_seq());
+
+    let mut text = String::new();
+    while *seq < log_buffer::next_seq() {
+        // The record may be overwritten after `first_seq` is read above.
+        let Some(record) = log_buffer::read_record(*seq) else {
+            *seq = (*seq + 1).max(log_buffer::first_seq());
+            continue;
+        };
+
+        let record_text = format_record(&record);
+        if text.len() + record_text.len() > len {
+            if text.is_empty() {
+                text.push_str(truncate(&record_text, len));
+                *seq += 1;
+            }
+            break;
+        }
+
+        text.push_str(&record_text);
+        *seq += 1;
+    }
+
+    (!text.is_empty()).then_some(text)
+}
+
+/// Reads the most recent records after the last clear that fit in `len` bytes.
+///
+/// This method returns the records and the sequence number after the last record.
+fn read_last_records(len: usize) -> (String, u64) {
+    let start_seq = clear_seq().max(log_buffer::first_seq());
+    let end_seq = log_buffer::next_seq();
+
+    let mut records = Vec::new();
+    let mut total_len = 0;
+    for seq in (start_seq..end_seq).rev() {
+        let Some(record) = log_buffer::read_record(seq) else {
+            // The record is still being appended by another CPU. Skip it, since the older records
+            // may still be available.
+            if seq >= log_buffer::first_seq() {
+                continue;
+            }
+            // The record has been overwritten. The remaining records are older, so they have been
+            // overwritten as well.
+            break;
+        };
+
+        let record_text = format_record(&record);
+        if total_len + record_text.len() > len {
+            break;
+        }
+        total_len += record_text.len();
+        records.push(record_text);
+    }
+
+    let text = records.into_iter().rev().collect();
+    (text, end_seq)
+}
+
+/// Formats a record in the format of `syslog`, e.g., `<6>[    1.234567] text\n`.
+fn format_record(record: &LogRecord) -> String {
+    let timestamp = record.timestamp();
+    format!(
+        "<{}>[{:5}.{:06}] {}\n",
+        record.priority(),
+        timestamp.as_secs(),
+        timestamp.subsec_micros(),
+        record.text()
+    )
+}
+
+fn truncate(text: &str, len: usize) -> &str {
+    let len = (0..=len).rev().find(|&i| text.is_char_boundary(i)).unwrap();
+    &text[..len]
+}
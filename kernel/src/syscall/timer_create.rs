@@ -1,23 +1,23 @@
 // SPDX-License-Identifier: MPL-2.0
 
 use super::{
-    clock_gettime::{DynamicClockIdInfo, DynamicClockType},
+    clock_gettime::{find_clock_process, find_clock_thread, DynamicClockIdInfo, DynamicClockType},
     SyscallReturn,
 };
 use crate::{
     prelude::*,
     process::{
         posix_thread::{thread_table, AsPosixThread},
-        process_table,
         signal::{
-            c_types::{sigevent_t, SigNotify},
+            c_types::{sigevent_t, sigval_t, SigNotify},
             constants::SIGALRM,
             sig_num::SigNum,
-            signals::kernel::KernelSignal,
+            signals::timer::{TimerOverrun, TimerSignal},
         },
+        PosixTimer,
     },
     syscall::ClockId,
-    thread::work_queue::{submit_work_item, work_item::WorkItem},
+    thread::work_queue::{submit_work_item, work_item::WorkItem, WorkPriority},
     time::{
         clockid_t,
         clocks::{BootTimeClock, MonotonicClock, RealTimeClock},
@@ -38,86 +38,132 @@ pub fn sys_timer_create(
         );
     }
 
-    let current_process = current!();
-    let sent_signal: Box<dyn Fn() + Send + Sync + 'static> = {
-        // If `sigevent_addr` is NULL, use the default method (like `sys_alarm`) to send signal.
-        if sigevent_addr == 0 {
-            let process = current_process.clone();
-            let signal = KernelSignal::new(SIGALRM);
-            Box::new(move || {
-                process.enqueue_signal(signal);
-            })
-        // Determine the timeout action through `sigevent`.
+    let sig_event = if sigevent_addr == 0 {
+        None
+    } else {
+        Some(ctx.user_space().read_val::<sigevent_t>(sigevent_addr)?)
+    };
+
+    let timer_id = ctx.process.timer_manager().add_posix_timer(|timer_id| {
+        let overrun = Arc::new(TimerOverrun::default());
+        let notify = create_notify_func(sig_event.as_ref(), timer_id as i32, &overrun, ctx)?;
+
+        let timer = if let Some(notify) = notify {
+            let work_item = WorkItem::new(notify);
+            let overrun = overrun.clone();
+            create_timer(
+                clockid,
+                move || {
+                    // Like Linux, the timer has at most one pending signal. The other expirations
+                    // are counted as overruns.
+                    if overrun.try_mark_pending() {
+                        submit_work_item(work_item.clone(), WorkPriority::High);
+                    }
+                },
+                ctx,
+            )?
         } else {
-            let sig_event = ctx.user_space().read_val::<sigevent_t>(sigevent_addr)?;
-            let sigev_notify = SigNotify::try_from(sig_event.sigev_notify)?;
-            let signo = sig_event.sigev_signo;
-            match sigev_notify {
-                // Do nothing when the timer is expired.
-                SigNotify::SIGEV_NONE => Box::new(|| {}),
-                // Send a signal to the current process when the timer is expired.
-                SigNotify::SIGEV_SIGNAL => {
-                    let process = current_process.clone();
-                    let signal = KernelSignal::new(SigNum::try_from(signo as u8)?);
-                    Box::new(move || {
-                        process.enqueue_signal(signal);
-                    })
-                }
-                // Spawn a posix thread to run the `sigev_function`, which is stored in
-                // `sig_event.sigev_un._sigev_thread`.
-                //
-                // TODO: enable this instructions. Currently the system does not provide an API to spawn
-                // a posix thread to run a specified function.
-                SigNotify::SIGEV_THREAD => {
-                    unimplemented!()
-                }
-                // Send a signal to the specified thread when the timer is expired.
-                SigNotify::SIGEV_THREAD_ID => {
-                    let tid = sig_event.sigev_un.read_tid() as u32;
-                    let thread = thread_table::get_thread(tid).ok_or_else(|| {
-                        Error::with_message(Errno::EINVAL, "target thread does not exist")
-                    })?;
+            create_timer(clockid, || {}, ctx)?
+        };
+
+        Ok(PosixTimer::new(timer, overrun))
+    })?;
+
+    ctx.user_space().write_val(timer_id_addr, &timer_id)?;
+    Ok(SyscallReturn::Return(0))
+}
+
+/// Creates the function that sends the signal of a POSIX timer as `sig_event` specifies.
+///
+/// This function returns `None` if no signal should be sent.
+fn create_notify_func(
+    sig_event: Option<&sigevent_t>,
+    timer_id: i32,
+    overrun: &Arc<TimerOverrun>,
+    ctx: &Context,
+) -> Result<Option<Box<dyn Fn() + Send + Sync>>> {
+    // If `sig_event` is NULL, send `SIGALRM` to the current process with the timer ID as the
+    // value.
+    let Some(sig_event) = sig_event else {
+        let overrun = overrun.clone();
+        let signal = move || {
+            TimerSignal::new(
+                SIGALRM,
+                timer_id,
+                sigval_t::new_int(timer_id),
+                overrun.clone(),
+            )
+        };
+        return Ok(Some(create_process_notify_func(signal, ctx)));
+    };
+
+    let new_signal = |overrun: &Arc<TimerOverrun>| -> Result<_> {
+        let signum = SigNum::try_from(sig_event.sigev_signo as u8)?;
+        let value = sig_event.sigev_value;
+        let overrun = overrun.clone();
+        Ok(move || TimerSignal::new(signum, timer_id, value, overrun.clone()))
+    };
+
+    let sigev_notify = SigNotify::try_from(sig_event.sigev_notify)?;
+    let notify_func = match sigev_notify {
+        // Do nothing when the timer is expired.
+        SigNotify::SIGEV_NONE => return Ok(None),
+        // Send a signal to the current process when the timer is expired.
+        SigNotify::SIGEV_SIGNAL => create_process_notify_func(new_signal(overrun)?, ctx),
+        // Run the `sigev_function` in a new thread. This is implemented in the C library with
+        // `SIGEV_THREAD_ID`, so the kernel does not accept it.
+        SigNotify::SIGEV_THREAD => {
+            return_errno_with_message!(Errno::EINVAL, "`SIGEV_THREAD` is not a kernel interface")
+        }
+        // Send a signal to the specified thread when the timer is expired.
+        SigNotify::SIGEV_THREAD_ID => {
+            let tid = sig_event.sigev_un.read_tid() as u32;
+            let thread = thread_table::get_thread(tid).ok_or_else(|| {
+                Error::with_message(Errno::EINVAL, "target thread does not exist")
+            })?;
+            if thread.as_posix_thread().unwrap().process().pid() != ctx.process.pid() {
+                return_errno_with_message!(
+                    Errno::EINVAL,
+                    "target thread should belong to current process"
+                );
+            }
+
+            let new_signal = new_signal(overrun)?;
+            Box::new(move || {
+                // The signal is discarded (and the timer can send new signals) if the thread has
+                // exited.
+                let signal = new_signal();
+                if !thread.is_exited() {
                     let posix_thread = thread.as_posix_thread().unwrap();
-                    if posix_thread.process().pid() != current_process.pid() {
-                        return_errno_with_message!(
-                            Errno::EINVAL,
-                            "target thread should belong to current process"
-                        );
-                    }
-                    let signal = KernelSignal::new(SigNum::try_from(signo as u8)?);
-                    Box::new(move || {
-                        if let Some(thread) = thread.as_posix_thread() {
-                            thread.enqueue_signal(Box::new(signal));
-                        }
-                    })
+                    posix_thread.enqueue_signal(Box::new(signal));
                 }
-            }
+            })
         }
     };
 
-    let work_func = sent_signal;
-    let work_item = WorkItem::new(work_func);
-    let func = move || {
-        submit_work_item(
-            work_item.clone(),
-            crate::thread::work_queue::WorkPriority::High,
-        );
-    };
-
-    let timer = create_timer(clockid, func, ctx)?;
+    Ok(Some(notify_func))
+}
 
-    let timer_id = current_process.timer_manager().add_posix_timer(timer);
-    ctx.user_space().write_val(timer_id_addr, &timer_id)?;
-    Ok(SyscallReturn::Return(0))
+fn create_process_notify_func<F>(new_signal: F, ctx: &Context) -> Box<dyn Fn() + Send + Sync>
+where
+    F: Fn() -> TimerSignal + Send + Sync + 'static,
+{
+    let process = ctx.posix_thread.weak_process();
+    Box::new(move || {
+        let signal = new_signal();
+        if let Some(process) = process.upgrade() {
+            process.enqueue_signal(signal);
+        }
+    })
 }
 
 pub fn sys_timer_delete(timer_id: usize, _ctx: &Context) -> Result<SyscallReturn> {
     let current_process = current!();
-    let Some(timer) = current_process.timer_manager().remove_posix_timer(timer_id) else {
+    let Some(posix_timer) = current_process.timer_manager().remove_posix_timer(timer_id) else {
         return_errno_with_message!(Errno::EINVAL, "invalid timer ID");
     };
 
-    timer.cancel();
+    posix_timer.timer().cancel();
     Ok(SyscallReturn::Return(0))
 }
 
@@ -143,27 +189,30 @@ where
         let dynamic_clockid_info = DynamicClockIdInfo::try_from(clockid)?;
         match dynamic_clockid_info {
             DynamicClockIdInfo::Pid(pid, clock_type) => {
-                let process = process_table::get_process(pid)
-                    .ok_or_else(|| crate::Error::with_message(Errno::EINVAL, "invalid clock id"))?;
+                let process = find_clock_process(pid)?;
                 let process_timer_manager = process.timer_manager();
                 match clock_type {
-                    DynamicClockType::Profiling => process_timer_manager.create_prof_timer(func),
+                    DynamicClockType::Profiling | DynamicClockType::Scheduling => {
+                        process_timer_manager.create_prof_timer(func)
+                    }
                     DynamicClockType::Virtual => process_timer_manager.create_virtual_timer(func),
-                    // TODO: support scheduling clock and fd clock.
-                    _ => unimplemented!(),
+                    DynamicClockType::FD => unreachable!(),
                 }
             }
             DynamicClockIdInfo::Tid(tid, clock_type) => {
-                let thread = thread_table::get_thread(tid)
-                    .ok_or_else(|| Error::with_message(Errno::EINVAL, "invalid clock id"))?;
+                let thread = find_clock_thread(tid, ctx)?;
                 let posix_thread = thread.as_posix_thread().unwrap();
                 match clock_type {
-                    DynamicClockType::Profiling => posix_thread.create_prof_timer(func),
+                    DynamicClockType::Profiling | DynamicClockType::Scheduling => {
+                        posix_thread.create_prof_timer(func)
+                    }
                     DynamicClockType::Virtual => posix_thread.create_virtual_timer(func),
-                    _ => unimplemented!(),
+                    DynamicClockType::FD => unreachable!(),
                 }
             }
-            DynamicClockIdInfo::Fd(_) => unimplemented!(),
+            DynamicClockIdInfo::Fd(_) => {
+                return_errno_with_message!(Errno::EINVAL, "FD clocks are not supported")
+            }
         }
     };
     Ok(timer)
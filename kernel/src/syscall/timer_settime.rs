@@ -24,9 +24,10 @@ pub fn sys_timer_settime(
     let interval = Duration::try_from(new_itimerspec.it_interval)?;
     let expire_time = Duration::try_from(new_itimerspec.it_value)?;
 
-    let Some(timer) = ctx.process.timer_manager().find_posix_timer(timer_id) else {
+    let Some(posix_timer) = ctx.process.timer_manager().find_posix_timer(timer_id) else {
         return_errno_with_message!(Errno::EINVAL, "invalid timer ID");
     };
+    let timer = posix_timer.timer();
 
     if old_itimerspec_addr > 0 {
         let old_interval = timespec_t::from(timer.interval());
@@ -62,9 +63,10 @@ pub fn sys_timer_gettime(
     if itimerspec_addr == 0 {
         return_errno_with_message!(Errno::EINVAL, "invalid pointer to return value");
     }
-    let Some(timer) = ctx.process.timer_manager().find_posix_timer(timer_id) else {
+    let Some(posix_timer) = ctx.process.timer_manager().find_posix_timer(timer_id) else {
         return_errno_with_message!(Errno::EINVAL, "invalid timer ID");
     };
+    let timer = posix_timer.timer();
 
     let interval = timespec_t::from(timer.interval());
     let remain = timespec_t::from(timer.remain());
@@ -76,3 +78,11 @@ pub fn sys_timer_gettime(
 
     Ok(SyscallReturn::Return(0))
 }
+
+pub fn sys_timer_getoverrun(timer_id: usize, ctx: &Context) -> Result<SyscallReturn> {
+    let Some(posix_timer) = ctx.process.timer_manager().find_posix_timer(timer_id) else {
+        return_errno_with_message!(Errno::EINVAL, "invalid timer ID");
+    };
+
+    Ok(SyscallReturn::Return(posix_timer.overrun() as _))
+}
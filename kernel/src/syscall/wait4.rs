@@ -37,11 +37,11 @@ pub fn sys_wait4(
     }
 
     if rusage_addr != 0 {
-        let rusage = rusage_t {
-            ru_utime: wait_status.prof_clock().user_clock().read_time().into(),
-            ru_stime: wait_status.prof_clock().kernel_clock().read_time().into(),
-            ..Default::default()
-        };
+        // Like Linux, the resource usage includes that of the waited-for children of the child.
+        let rusage = rusage_t::from_prof_clocks(&[
+            wait_status.prof_clock(),
+            wait_status.children_prof_clock(),
+        ]);
 
         ctx.user_space().write_val(rusage_addr, &rusage)?;
     }
@@ -68,8 +68,8 @@ impl Thread {
             task,
             data: Box::new(data),
             is_exited: AtomicBool::new(false),
+            sched_attr: SchedAttr::new(sched_policy, &cpu_affinity),
             cpu_affinity: AtomicCpuSet::new(cpu_affinity),
-            sched_attr: SchedAttr::new(sched_policy),
         }
     }
 
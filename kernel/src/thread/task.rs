@@ -14,7 +14,7 @@ use crate::{
     prelude::*,
     process::{
         posix_thread::{AsPosixThread, AsThreadLocal, ThreadLocal},
-        signal::handle_pending_signal,
+        signal::{constants::SIGXCPU, handle_pending_signal, signals::kernel::KernelSignal},
     },
     syscall::handle_syscall,
     thread::{exception::handle_exception, AsThread},
@@ -63,7 +63,10 @@ pub fn create_new_user_task(
                 .unwrap();
         }
 
-        let has_kernel_event_fn = || current_posix_thread.has_pending();
+        let has_kernel_event_fn = || {
+            current_posix_thread.has_pending()
+                || current_thread.sched_attr().has_pending_deadline_overrun()
+        };
 
         let ctx = Context {
             process: current_process.as_ref(),
@@ -94,6 +97,12 @@ pub fn create_new_user_task(
                 break;
             }
 
+            // Report the runtime overrun of `SCHED_DEADLINE`, which cannot be
+            // done by the scheduler itself since it holds the run queue lock.
+            if current_thread.sched_attr().take_deadline_overrun() {
+                current_posix_thread.enqueue_signal(Box::new(KernelSignal::new(SIGXCPU)));
+            }
+
             // Handle signals
             handle_pending_signal(user_ctx, &ctx, syscall_number);
 
@@ -3,13 +3,20 @@
 use alloc::sync::Arc;
 use core::time::Duration;
 
-use aster_time::read_monotonic_time;
+use aster_time::{read_monotonic_raw_time, read_monotonic_time};
 use ostd::{cpu::PinCurrentCpu, cpu_local, sync::SpinLock, task::disable_preempt, timer::Jiffies};
 use paste::paste;
 use spin::Once;
 
-use crate::time::{
-    self, system_time::START_TIME_AS_DURATION, timer::TimerManager, Clock, SystemTime,
+use crate::{
+    prelude::*,
+    time::{
+        self,
+        system_time::{realtime_offset, set_realtime_offset},
+        timer::TimerManager,
+        Clock,
+    },
+    vdso,
 };
 
 /// The Clock that reads the jiffies, and turn the counter into `Duration`.
@@ -36,6 +43,28 @@ impl RealTimeClock {
             .get()
             .unwrap()
     }
+
+    /// Sets the time of this clock.
+    ///
+    /// The time cannot be earlier than the time elapsed since boot, i.e., the time of the
+    /// [`MonotonicClock`]. Otherwise, this method will fail with `EINVAL`.
+    pub fn set_time(&self, time: Duration) -> Result<()> {
+        let Some(offset) = time.checked_sub(read_monotonic_time()) else {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "the real time cannot be earlier than the boot time"
+            );
+        };
+
+        set_realtime_offset(offset);
+        vdso::update_vdso_clock();
+
+        // The timers of this clock may have expired because the time jumped forward. Request an
+        // immediate timer event to check them.
+        ostd::timer::set_next_event(Duration::ZERO);
+
+        Ok(())
+    }
 }
 
 /// `MonotonicClock` represents a clock that measures time in a way that is
@@ -62,10 +91,11 @@ impl MonotonicClock {
 
 /// `RealTimeCoarseClock` is a coarse-grained version of a real-time clock.
 ///
-/// This clock will maintain a record to `RealTimeClock`. This record
+/// This clock will maintain a record to `MonotonicClock`. This record
 /// will be updated during each system timer interruption. Reading this clock
-/// will directly reads the value of the record instead of calculating the time
-/// based on the clocksource. Hence it is faster but less accurate.
+/// will directly reads the value of the record and adds the offset of the real time
+/// instead of calculating the time based on the clocksource. Hence it is faster but
+/// less accurate.
 ///
 /// Usually it will not be used to create a timer.
 pub struct RealTimeCoarseClock {
@@ -73,7 +103,7 @@ pub struct RealTimeCoarseClock {
 }
 
 impl RealTimeCoarseClock {
-    /// A reference to the current value of this clock.
+    /// A reference to the current value of the monotonic time recorded by this clock.
     fn current_ref() -> &'static Once<SpinLock<Duration>> {
         static CURRENT: Once<SpinLock<Duration>> = Once::new();
 
@@ -103,10 +133,7 @@ impl MonotonicCoarseClock {
 }
 
 /// `MonotonicRawClock` provides raw monotonic time that is not influenced by
-/// NTP corrections.
-///
-/// Note: Currently we have not implement NTP corrections so we treat this clock
-/// as the [`MonotonicClock`].
+/// NTP corrections (i.e., the frequency adjustments made by `adjtimex`).
 pub struct MonotonicRawClock {
     _private: (),
 }
@@ -151,9 +178,7 @@ impl Clock for JiffiesClock {
 
 impl Clock for RealTimeClock {
     fn read_time(&self) -> Duration {
-        SystemTime::now()
-            .duration_since(&SystemTime::UNIX_EPOCH)
-            .unwrap()
+        realtime_offset() + read_monotonic_time()
     }
 }
 
@@ -165,19 +190,23 @@ impl Clock for MonotonicClock {
 
 impl Clock for RealTimeCoarseClock {
     fn read_time(&self) -> Duration {
-        *Self::current_ref().get().unwrap().disable_irq().lock()
+        *Self::current_ref().get().unwrap().disable_irq().lock() + realtime_offset()
     }
 }
 
 impl Clock for MonotonicCoarseClock {
     fn read_time(&self) -> Duration {
-        RealTimeCoarseClock::get().read_time() - *START_TIME_AS_DURATION.get().unwrap()
+        *RealTimeCoarseClock::current_ref()
+            .get()
+            .unwrap()
+            .disable_irq()
+            .lock()
     }
 }
 
 impl Clock for MonotonicRawClock {
     fn read_time(&self) -> Duration {
-        read_monotonic_time()
+        read_monotonic_raw_time()
     }
 }
 
@@ -225,7 +254,7 @@ macro_rules! define_timer_managers {
         fn _init_system_wide_timer_managers() {
             $(
                 let clock = paste! {[<$clock_id _INSTANCE>].get().unwrap().clone()};
-                let timer_manager = TimerManager::new(clock);
+                let timer_manager = TimerManager::new_high_res(clock);
                 for cpu in ostd::cpu::all_cpus() {
                     paste! {
                         [<$clock_id _MANAGER>].get_on_cpu(cpu).call_once(|| timer_manager.clone());
@@ -270,7 +299,7 @@ pub static JIFFIES_TIMER_MANAGER: Once<Arc<TimerManager>> = Once::new();
 
 fn init_jiffies_clock_manager() {
     let jiffies_clock = JiffiesClock { _private: () };
-    let jiffies_timer_manager = TimerManager::new(Arc::new(jiffies_clock));
+    let jiffies_timer_manager = TimerManager::new_high_res(Arc::new(jiffies_clock));
     JIFFIES_TIMER_MANAGER.call_once(|| jiffies_timer_manager);
 
     let callback = || {
@@ -283,14 +312,14 @@ fn init_jiffies_clock_manager() {
 }
 
 fn update_coarse_clock() {
-    let real_time = RealTimeClock::get().read_time();
+    let monotonic_time = read_monotonic_time();
     let current = RealTimeCoarseClock::current_ref().get().unwrap();
-    *current.disable_irq().lock() = real_time;
+    *current.disable_irq().lock() = monotonic_time;
 }
 
 fn init_coarse_clock() {
-    let real_time = RealTimeClock::get().read_time();
-    RealTimeCoarseClock::current_ref().call_once(|| SpinLock::new(real_time));
+    let monotonic_time = read_monotonic_time();
+    RealTimeCoarseClock::current_ref().call_once(|| SpinLock::new(monotonic_time));
     time::softirq::register_callback(update_coarse_clock);
 }
 
@@ -311,13 +340,13 @@ pub fn init_for_ktest() {
     for cpu in ostd::cpu::all_cpus() {
         CLOCK_REALTIME_MANAGER.get_on_cpu(cpu).call_once(|| {
             let clock = RealTimeClock { _private: () };
-            TimerManager::new(Arc::new(clock))
+            TimerManager::new_high_res(Arc::new(clock))
         });
     }
     CLOCK_REALTIME_COARSE_INSTANCE.call_once(|| Arc::new(RealTimeCoarseClock { _private: () }));
     RealTimeCoarseClock::current_ref().call_once(|| SpinLock::new(Duration::from_secs(0)));
     JIFFIES_TIMER_MANAGER.call_once(|| {
         let clock = JiffiesClock { _private: () };
-        TimerManager::new(Arc::new(clock))
+        TimerManager::new_high_res(Arc::new(clock))
     });
 }
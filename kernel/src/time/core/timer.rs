@@ -11,7 +11,7 @@ use core::{
     time::Duration,
 };
 
-use ostd::sync::SpinLock;
+use ostd::{sync::SpinLock, timer};
 
 use super::Clock;
 
@@ -149,14 +149,32 @@ fn interval_timer_callback(timer: &Weak<Timer>) {
 pub struct TimerManager {
     clock: Arc<dyn Clock>,
     timer_callbacks: SpinLock<BinaryHeap<Arc<TimerCallback>>>,
+    is_high_res: bool,
 }
 
 impl TimerManager {
     /// Create a `TimerManager` instance from a clock.
+    ///
+    /// The expiries of the timers are checked only when [`Self::process_expired_timers`] is
+    /// called by the owner of the `TimerManager` (e.g., in every tick).
     pub fn new(clock: Arc<dyn Clock>) -> Arc<Self> {
+        Self::new_with_resolution(clock, false)
+    }
+
+    /// Creates a high-resolution `TimerManager` instance from a clock.
+    ///
+    /// The `TimerManager` requests a timer event (see [`timer::set_next_event`]) at the expired
+    /// time of its earliest timer, so its timers can expire between ticks and even if the tick
+    /// is stopped. The clock should advance at the same rate as the monotonic time.
+    pub fn new_high_res(clock: Arc<dyn Clock>) -> Arc<Self> {
+        Self::new_with_resolution(clock, true)
+    }
+
+    fn new_with_resolution(clock: Arc<dyn Clock>, is_high_res: bool) -> Arc<Self> {
         Arc::new(Self {
             clock,
             timer_callbacks: SpinLock::new(BinaryHeap::new()),
+            is_high_res,
         })
     }
 
@@ -177,10 +195,27 @@ impl TimerManager {
     }
 
     fn insert(&self, timer_callback: Arc<TimerCallback>) {
-        self.timer_callbacks
-            .disable_irq()
-            .lock()
-            .push(timer_callback);
+        let mut timeout_list = self.timer_callbacks.disable_irq().lock();
+        let expired_time = timer_callback.expired_time;
+        timeout_list.push(timer_callback);
+
+        // Only the earliest timer needs a new timer event.
+        if timeout_list
+            .peek()
+            .is_some_and(|earliest| earliest.expired_time == expired_time)
+        {
+            self.request_timer_event(expired_time);
+        }
+    }
+
+    /// Requests a timer event at `expired_time` if this is a high-resolution `TimerManager`.
+    fn request_timer_event(&self, expired_time: Duration) {
+        if !self.is_high_res {
+            return;
+        }
+
+        let now = self.clock.read_time();
+        timer::set_next_event(expired_time.saturating_sub(now));
     }
 
     /// Check the managed timers, and if any have timed out,
@@ -201,6 +236,7 @@ impl TimerManager {
                 } else if t.expired_time <= current_time {
                     callbacks.push(timeout_list.pop().unwrap());
                 } else {
+                    self.request_timer_event(t.expired_time);
                     break;
                 }
             }
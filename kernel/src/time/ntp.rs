@@ -0,0 +1,406 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! Clock adjustments for NTP daemons.
+//!
+//! This module implements the kernel part of `adjtimex`. The NTP state is kept in a
+//! Linux-compatible form, but the adjustments are made in a simpler way:
+//! - The frequency adjustment (including the adjustment of the tick length) is applied to the
+//!   coeff of the clocksource directly.
+//! - The offset adjustment slews the clock at the maximum rate (500 ppm) until the offset is
+//!   compensated. This applies to both the `adjtime`-compatible mode and the PLL mode, since
+//!   the PLL and FLL are not implemented.
+//!
+//! Reference: <https://elixir.bootlin.com/linux/v6.13/source/kernel/time/ntp.c>.
+
+use core::time::Duration;
+
+use spin::Once;
+
+use super::{
+    clocks::{MonotonicClock, RealTimeClock},
+    timer::Timeout,
+    timeval_t, Clock, Timer, NSEC_PER_SEC, NSEC_PER_USEC, USEC_PER_SEC,
+};
+use crate::{prelude::*, vdso};
+
+/// The `timex` structure used by `adjtimex`.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/timex.h#L56>.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Pod)]
+pub struct timex_t {
+    pub modes: u32,
+    _pad0: u32,
+    pub offset: i64,
+    pub freq: i64,
+    pub maxerror: i64,
+    pub esterror: i64,
+    pub status: i32,
+    _pad1: u32,
+    pub constant: i64,
+    pub precision: i64,
+    pub tolerance: i64,
+    pub time: timeval_t,
+    pub tick: i64,
+    pub ppsfreq: i64,
+    pub jitter: i64,
+    pub shift: i32,
+    _pad2: u32,
+    pub stabil: i64,
+    pub jitcnt: i64,
+    pub calcnt: i64,
+    pub errcnt: i64,
+    pub stbcnt: i64,
+    pub tai: i32,
+    _reserved: [i32; 11],
+}
+
+impl timex_t {
+    /// Returns whether the `timex` only queries the NTP state without changing it.
+    pub fn is_read_only(&self) -> bool {
+        self.modes == 0 || self.modes == AdjtimexModes::OFFSET_SS_READ.bits()
+    }
+}
+
+bitflags! {
+    /// The modes of `adjtimex`.
+    struct AdjtimexModes: u32 {
+        const OFFSET    = 0x0001;
+        const FREQUENCY = 0x0002;
+        const MAXERROR  = 0x0004;
+        const ESTERROR  = 0x0008;
+        const STATUS    = 0x0010;
+        const TIMECONST = 0x0020;
+        const TAI       = 0x0080;
+        const SETOFFSET = 0x0100;
+        const MICRO     = 0x1000;
+        const NANO      = 0x2000;
+        const TICK      = 0x4000;
+        /// The `adjtime`-compatible mode.
+        const ADJTIME   = 0x8000;
+
+        const OFFSET_SINGLESHOT = Self::OFFSET.bits | Self::ADJTIME.bits;
+        /// In the `adjtime`-compatible mode, the `NANO` bit means read-only.
+        const OFFSET_SS_READ = Self::OFFSET_SINGLESHOT.bits | Self::NANO.bits;
+    }
+}
+
+bitflags! {
+    /// The clock status bits of the NTP state.
+    struct ClockStatus: i32 {
+        const PLL       = 0x0001;
+        const PPSFREQ   = 0x0002;
+        const PPSTIME   = 0x0004;
+        const FLL       = 0x0008;
+        const INS       = 0x0010;
+        const DEL       = 0x0020;
+        const UNSYNC    = 0x0040;
+        const FREQHOLD  = 0x0080;
+        const PPSSIGNAL = 0x0100;
+        const PPSJITTER = 0x0200;
+        const PPSWANDER = 0x0400;
+        const PPSERROR  = 0x0800;
+        const CLOCKERR  = 0x1000;
+        const NANO      = 0x2000;
+        const MODE      = 0x4000;
+        const CLK       = 0x8000;
+
+        /// The bits that cannot be changed by `ADJ_STATUS`.
+        const RONLY = Self::PPSSIGNAL.bits
+            | Self::PPSJITTER.bits
+            | Self::PPSWANDER.bits
+            | Self::PPSERROR.bits
+            | Self::CLOCKERR.bits
+            | Self::NANO.bits
+            | Self::MODE.bits
+            | Self::CLK.bits;
+    }
+}
+
+/// The clock is synchronized.
+const TIME_OK: i32 = 0;
+/// The clock is not synchronized.
+const TIME_ERROR: i32 = 5;
+
+/// The shift of the scaled frequency, which is in the unit of ppm with 16-bit fractions.
+const SHIFT_USEC: u32 = 16;
+/// The maximum frequency adjustment, in the unit of scaled ppm.
+const MAXFREQ_SCALED: i64 = 500 << SHIFT_USEC;
+/// The rate at which the offset is slewed, in the unit of ppb.
+const SLEW_RATE_PPB: i64 = 500_000;
+/// The maximum offset that can be adjusted in the PLL mode, in nanoseconds.
+const MAXPHASE: i64 = 500_000_000;
+/// The maximum offset that can be adjusted in the `adjtime`-compatible mode, in microseconds.
+const MAX_ADJTIME: i64 = i32::MAX as i64;
+/// The maximum time constant of the PLL.
+const MAXTC: i64 = 10;
+/// The maximum TAI offset, in seconds.
+const MAX_TAI_OFFSET: i64 = 100_000;
+/// The maximum error, in microseconds.
+const NTP_PHASE_LIMIT: i64 = 16_000_000;
+/// The nominal length of a tick, in microseconds.
+///
+/// The tick length visible to the user space is based on `USER_HZ` (i.e., 100), which is not
+/// related to the real frequency of the timer interrupts.
+const NOMINAL_TICK_USEC: i64 = USEC_PER_SEC / 100;
+
+/// Adjusts the system clocks and returns the clock state.
+///
+/// The `timex` is updated with the NTP state after the adjustments. The caller must have
+/// checked the permission if the `timex` is not [read only](timex_t::is_read_only).
+pub fn do_adjtimex(timex: &mut timex_t) -> Result<i32> {
+    let modes = AdjtimexModes::from_bits_truncate(timex.modes);
+    validate_timex(timex, modes)?;
+
+    if modes.contains(AdjtimexModes::SETOFFSET) {
+        inject_offset(&timex.time, modes.contains(AdjtimexModes::NANO))?;
+    }
+
+    let mut state = NTP_STATE.disable_irq().lock();
+
+    if modes.contains(AdjtimexModes::ADJTIME) {
+        let remaining = state.remaining_offset() / NSEC_PER_USEC;
+        if !modes.contains(AdjtimexModes::NANO) {
+            let offset = timex.offset.clamp(-MAX_ADJTIME, MAX_ADJTIME);
+            state.start_slew(offset * NSEC_PER_USEC);
+            state.apply_frequency();
+        }
+        timex.offset = remaining;
+    } else {
+        if !modes.is_empty() {
+            state.process_modes(timex, modes);
+        }
+        let remaining = state.remaining_offset();
+        timex.offset = if state.status.contains(ClockStatus::NANO) {
+            remaining
+        } else {
+            remaining / NSEC_PER_USEC
+        };
+    }
+
+    let now = RealTimeClock::get().read_time();
+    timex.freq = state.freq;
+    timex.maxerror = state.maxerror;
+    timex.esterror = state.esterror;
+    timex.status = state.status.bits();
+    timex.constant = state.constant;
+    timex.precision = 1;
+    timex.tolerance = MAXFREQ_SCALED;
+    timex.tick = state.tick;
+    timex.tai = state.tai;
+    timex.time = timeval_t {
+        sec: now.as_secs() as i64,
+        usec: if state.status.contains(ClockStatus::NANO) {
+            now.subsec_nanos() as i64
+        } else {
+            now.subsec_micros() as i64
+        },
+    };
+
+    if state
+        .status
+        .intersects(ClockStatus::UNSYNC | ClockStatus::CLOCKERR)
+    {
+        Ok(TIME_ERROR)
+    } else {
+        Ok(TIME_OK)
+    }
+}
+
+fn validate_timex(timex: &timex_t, modes: AdjtimexModes) -> Result<()> {
+    if modes.contains(AdjtimexModes::ADJTIME) {
+        if modes != AdjtimexModes::OFFSET_SINGLESHOT && modes != AdjtimexModes::OFFSET_SS_READ {
+            return_errno_with_message!(
+                Errno::EINVAL,
+                "the adjtime-compatible mode cannot be mixed with other modes"
+            );
+        }
+        return Ok(());
+    }
+
+    if modes.contains(AdjtimexModes::TICK)
+        && !(NOMINAL_TICK_USEC * 9 / 10..=NOMINAL_TICK_USEC * 11 / 10).contains(&timex.tick)
+    {
+        return_errno_with_message!(Errno::EINVAL, "the tick length is out of range");
+    }
+
+    if modes.contains(AdjtimexModes::SETOFFSET) {
+        let max_subsec = if modes.contains(AdjtimexModes::NANO) {
+            NSEC_PER_SEC
+        } else {
+            USEC_PER_SEC
+        };
+        if !(0..max_subsec).contains(&timex.time.usec) {
+            return_errno_with_message!(Errno::EINVAL, "the time offset is not normalized");
+        }
+    }
+
+    Ok(())
+}
+
+/// Steps the real time by the offset in `time`.
+fn inject_offset(time: &timeval_t, is_nano: bool) -> Result<()> {
+    let subsec_nanos = if is_nano {
+        time.usec
+    } else {
+        time.usec * NSEC_PER_USEC
+    };
+    let delta_nanos = (time.sec as i128) * (NSEC_PER_SEC as i128) + subsec_nanos as i128;
+
+    let realtime_clock = RealTimeClock::get();
+    let now_nanos = realtime_clock.read_time().as_nanos() as i128;
+    let Ok(new_nanos) = u64::try_from(now_nanos + delta_nanos) else {
+        return_errno_with_message!(Errno::EINVAL, "the time offset is out of range");
+    };
+
+    realtime_clock.set_time(Duration::from_nanos(new_nanos))
+}
+
+static NTP_STATE: SpinLock<NtpState> = SpinLock::new(NtpState::new());
+
+/// The timer that ends the ongoing slew.
+static SLEW_TIMER: Once<Arc<Timer>> = Once::new();
+
+struct NtpState {
+    status: ClockStatus,
+    /// The frequency adjustment, in the unit of scaled ppm.
+    freq: i64,
+    /// The maximum error, in microseconds.
+    maxerror: i64,
+    /// The estimated error, in microseconds.
+    esterror: i64,
+    /// The time constant of the PLL.
+    constant: i64,
+    /// The tick length, in microseconds.
+    tick: i64,
+    /// The offset between the TAI and the UTC, in seconds.
+    tai: i32,
+    slew: Option<Slew>,
+}
+
+/// An ongoing slew that compensates an offset.
+#[derive(Debug, Clone, Copy)]
+struct Slew {
+    /// The rate of the slew, which is either [`SLEW_RATE_PPB`] or its negation.
+    ppb: i64,
+    /// The monotonic time when the slew ends.
+    end: Duration,
+}
+
+impl NtpState {
+    const fn new() -> Self {
+        Self {
+            status: ClockStatus::UNSYNC,
+            freq: 0,
+            maxerror: NTP_PHASE_LIMIT,
+            esterror: NTP_PHASE_LIMIT,
+            constant: 2,
+            tick: NOMINAL_TICK_USEC,
+            tai: 0,
+            slew: None,
+        }
+    }
+
+    fn process_modes(&mut self, timex: &timex_t, modes: AdjtimexModes) {
+        if modes.contains(AdjtimexModes::STATUS) {
+            let new_status = ClockStatus::from_bits_truncate(timex.status);
+            self.status = (self.status & ClockStatus::RONLY) | (new_status - ClockStatus::RONLY);
+        }
+        if modes.contains(AdjtimexModes::NANO) {
+            self.status |= ClockStatus::NANO;
+        }
+        if modes.contains(AdjtimexModes::MICRO) {
+            self.status -= ClockStatus::NANO;
+        }
+        if modes.contains(AdjtimexModes::FREQUENCY) {
+            self.freq = timex.freq.clamp(-MAXFREQ_SCALED, MAXFREQ_SCALED);
+        }
+        if modes.contains(AdjtimexModes::MAXERROR) {
+            self.maxerror = timex.maxerror.clamp(0, NTP_PHASE_LIMIT);
+        }
+        if modes.contains(AdjtimexModes::ESTERROR) {
+            self.esterror = timex.esterror.clamp(0, NTP_PHASE_LIMIT);
+        }
+        if modes.contains(AdjtimexModes::TIMECONST) {
+            let constant = if self.status.contains(ClockStatus::NANO) {
+                timex.constant
+            } else {
+                timex.constant.saturating_add(4)
+            };
+            self.constant = constant.clamp(0, MAXTC);
+        }
+        if modes.contains(AdjtimexModes::TAI) && (0..=MAX_TAI_OFFSET).contains(&timex.constant) {
+            self.tai = timex.constant as i32;
+        }
+        // The offset is only used in the PLL mode.
+        if modes.contains(AdjtimexModes::OFFSET) && self.status.contains(ClockStatus::PLL) {
+            let offset = if self.status.contains(ClockStatus::NANO) {
+                timex.offset
+            } else {
+                timex.offset.saturating_mul(NSEC_PER_USEC)
+            };
+            self.start_slew(offset.clamp(-MAXPHASE, MAXPHASE));
+        }
+        if modes.contains(AdjtimexModes::TICK) {
+            self.tick = timex.tick;
+        }
+
+        if modes.intersects(AdjtimexModes::TICK | AdjtimexModes::FREQUENCY | AdjtimexModes::OFFSET)
+        {
+            self.apply_frequency();
+        }
+    }
+
+    /// Starts to slew the clock to compensate the offset, in nanoseconds.
+    ///
+    /// The ongoing slew, if any, is replaced.
+    fn start_slew(&mut self, offset: i64) {
+        let timer = SLEW_TIMER.call_once(|| MonotonicClock::timer_manager().create_timer(end_slew));
+
+        if offset == 0 {
+            timer.cancel();
+            self.slew = None;
+            return;
+        }
+
+        let duration =
+            Duration::from_nanos(offset.unsigned_abs() * (NSEC_PER_SEC / SLEW_RATE_PPB) as u64);
+        self.slew = Some(Slew {
+            ppb: SLEW_RATE_PPB * offset.signum(),
+            end: MonotonicClock::get().read_time() + duration,
+        });
+        timer.set_timeout(Timeout::After(duration));
+    }
+
+    /// Returns the offset that has not been compensated by the ongoing slew, in nanoseconds.
+    fn remaining_offset(&self) -> i64 {
+        let Some(slew) = self.slew else {
+            return 0;
+        };
+
+        let now = MonotonicClock::get().read_time();
+        let remaining_time = slew.end.saturating_sub(now).as_nanos() as i64;
+        remaining_time / (NSEC_PER_SEC / slew.ppb)
+    }
+
+    /// Applies the frequency adjustments to the clocksource.
+    fn apply_frequency(&self) {
+        let freq_ppb = (self.freq * 1000) >> SHIFT_USEC;
+        let tick_ppb = (self.tick - NOMINAL_TICK_USEC) * (NSEC_PER_SEC / NOMINAL_TICK_USEC);
+        let slew_ppb = self.slew.map_or(0, |slew| slew.ppb);
+
+        aster_time::default_clocksource().set_freq_adjustment(freq_ppb + tick_ppb + slew_ppb);
+        vdso::update_vdso_clock();
+    }
+}
+
+fn end_slew() {
+    let mut state = NTP_STATE.disable_irq().lock();
+
+    // The timer may be triggered for a slew that has been replaced by a newer one.
+    let now = MonotonicClock::get().read_time();
+    if state.slew.is_some_and(|slew| slew.end <= now) {
+        state.slew = None;
+        state.apply_frequency();
+    }
+}
@@ -14,6 +14,10 @@ pub(super) fn init() {
     timer::register_callback(|| {
         SoftIrqLine::get(TIMER_SOFTIRQ_ID).raise();
     });
+    // The high-resolution timer managers request timer events for their earliest timers.
+    timer::set_event_callback(|| {
+        SoftIrqLine::get(TIMER_SOFTIRQ_ID).raise();
+    });
 }
 
 /// Registers a function that will be executed during timer softirq.
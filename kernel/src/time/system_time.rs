@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: MPL-2.0
 
-use core::time::Duration;
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
 
 use aster_time::{read_monotonic_time, read_start_time};
 use spin::Once;
@@ -13,15 +16,30 @@ use crate::prelude::*;
 pub struct SystemTime(PrimitiveDateTime);
 
 pub static START_TIME: Once<SystemTime> = Once::new();
-pub(super) static START_TIME_AS_DURATION: Once<Duration> = Once::new();
+
+/// The real time at which the monotonic time is zero, in nanoseconds since the Unix epoch.
+///
+/// The real time is the sum of the monotonic time and this offset. The offset is initialized
+/// with the start time and changes when the real time is set (e.g., via `clock_settime`).
+static REALTIME_OFFSET_NANOS: AtomicU64 = AtomicU64::new(0);
 
 pub(super) fn init() {
     let start_time = convert_system_time(read_start_time()).unwrap();
-    START_TIME_AS_DURATION
-        .call_once(|| start_time.duration_since(&SystemTime::UNIX_EPOCH).unwrap());
+    let start_time_as_duration = start_time.duration_since(&SystemTime::UNIX_EPOCH).unwrap();
+    REALTIME_OFFSET_NANOS.store(start_time_as_duration.as_nanos() as u64, Ordering::Relaxed);
     START_TIME.call_once(|| start_time);
 }
 
+/// Returns the offset of the real time relative to the monotonic time.
+pub fn realtime_offset() -> Duration {
+    Duration::from_nanos(REALTIME_OFFSET_NANOS.load(Ordering::Relaxed))
+}
+
+/// Sets the offset of the real time relative to the monotonic time.
+pub(super) fn set_realtime_offset(offset: Duration) {
+    REALTIME_OFFSET_NANOS.store(offset.as_nanos() as u64, Ordering::Relaxed);
+}
+
 impl SystemTime {
     /// The unix epoch, which represents 1970-01-01 00:00:00
     pub const UNIX_EPOCH: SystemTime = SystemTime::unix_epoch();
@@ -41,10 +59,8 @@ impl SystemTime {
     /// Returns the current system time
     pub fn now() -> Self {
         // The get real time result should always be valid
-        START_TIME
-            .get()
-            .unwrap()
-            .checked_add(read_monotonic_time())
+        SystemTime::UNIX_EPOCH
+            .checked_add(realtime_offset() + read_monotonic_time())
             .unwrap()
     }
 
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 
+use core::ops::Range;
+
 use ostd::mm::{Infallible, VmSpace};
 
 use crate::prelude::*;
@@ -83,6 +85,23 @@ fn copy_iovs_and_convert<'a, T: 'a>(
     Ok(v.into_boxed_slice())
 }
 
+/// Copies IO vectors from the user space without checking the buffers that they point to.
+///
+/// This is useful when the buffers belong to another address space, e.g., the remote IO vectors
+/// of `process_vm_readv`. The empty IO vectors are skipped.
+pub fn copy_remote_io_vecs(
+    user_space: &CurrentUserSpace,
+    start_addr: Vaddr,
+    count: usize,
+) -> Result<Box<[Range<Vaddr>]>> {
+    copy_iovs_and_convert(user_space, start_addr, count, |iov, _| {
+        let end = iov.base.checked_add(iov.len).ok_or_else(|| {
+            Error::with_message(Errno::EFAULT, "the IO vector exceeds the address space")
+        })?;
+        Ok(iov.base..end)
+    })
+}
+
 /// A collection of [`VmReader`]s.
 ///
 /// Such readers are built from user-provided buffer, so it's always fallible.
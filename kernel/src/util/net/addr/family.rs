@@ -4,7 +4,10 @@ use core::cmp::min;
 
 use ostd::task::Task;
 
-use super::{ip::CSocketAddrInet, netlink::CSocketAddrNetlink, unix, vsock::CSocketAddrVm};
+use super::{
+    ip::CSocketAddrInet, netlink::CSocketAddrNetlink, packet::CSocketAddrLinkLayer, unix,
+    vsock::CSocketAddrVm,
+};
 use crate::{current_userspace, net::socket::util::SocketAddr, prelude::*};
 
 /// Address family.
@@ -176,6 +179,13 @@ pub fn read_socket_addr_from_user(addr: Vaddr, addr_len: usize) -> Result<Socket
             let addr = CSocketAddrVm::from_bytes(storage.as_bytes());
             SocketAddr::Vsock(addr.into())
         }
+        Ok(CSocketAddrFamily::AF_PACKET) => {
+            if addr_len < size_of::<CSocketAddrLinkLayer>() {
+                return_errno_with_message!(Errno::EINVAL, "the socket address length is too small");
+            }
+            let addr = CSocketAddrLinkLayer::from_bytes(storage.as_bytes());
+            SocketAddr::LinkLayer(addr.try_into()?)
+        }
         _ => {
             return_errno_with_message!(
                 Errno::EAFNOSUPPORT,
@@ -262,6 +272,15 @@ pub fn write_socket_addr_with_max_len(
         SocketAddr::Vsock(addr) => {
             write_c_socket_address_util::<CSocketAddrVm, _>(*addr, dest, max_len as usize)?
         }
+        SocketAddr::LinkLayer(addr) => {
+            // The length covers only the used part of the link-layer address.
+            let c_addr = CSocketAddrLinkLayer::from(*addr);
+            let actual_len = CSocketAddrLinkLayer::ADDR_OFFSET + addr.halen as usize;
+            let written_len = min(actual_len, max_len as _);
+            current_userspace!()
+                .write_bytes(dest, &mut VmReader::from(&c_addr.as_bytes()[..written_len]))?;
+            actual_len
+        }
     };
 
     Ok(actual_len as i32)
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::family::CSocketAddrFamily;
+use crate::{net::socket::packet::LinkLayerSocketAddr, prelude::*};
+
+/// Link-layer socket address.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L14>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CSocketAddrLinkLayer {
+    /// Address family (AF_PACKET).
+    sll_family: u16,
+    /// Link-layer protocol in network byte order.
+    sll_protocol: u16,
+    /// Interface index.
+    sll_ifindex: i32,
+    /// ARP hardware type.
+    sll_hatype: u16,
+    /// Packet type.
+    sll_pkttype: u8,
+    /// Length of the link-layer address.
+    sll_halen: u8,
+    /// Link-layer address.
+    sll_addr: [u8; 8],
+}
+
+impl CSocketAddrLinkLayer {
+    /// The offset of the `sll_addr` field.
+    pub(super) const ADDR_OFFSET: usize = core::mem::offset_of!(Self, sll_addr);
+}
+
+impl From<LinkLayerSocketAddr> for CSocketAddrLinkLayer {
+    fn from(value: LinkLayerSocketAddr) -> Self {
+        Self {
+            sll_family: CSocketAddrFamily::AF_PACKET as u16,
+            sll_protocol: value.protocol.to_be(),
+            sll_ifindex: value.ifindex as i32,
+            sll_hatype: value.hatype,
+            sll_pkttype: value.pkttype,
+            sll_halen: value.halen,
+            sll_addr: value.addr,
+        }
+    }
+}
+
+impl TryFrom<CSocketAddrLinkLayer> for LinkLayerSocketAddr {
+    type Error = Error;
+
+    fn try_from(value: CSocketAddrLinkLayer) -> Result<Self> {
+        debug_assert_eq!(value.sll_family, CSocketAddrFamily::AF_PACKET as u16);
+
+        if value.sll_ifindex < 0 {
+            return_errno_with_message!(Errno::ENODEV, "the interface index is negative");
+        }
+
+        Ok(Self {
+            protocol: u16::from_be(value.sll_protocol),
+            ifindex: value.sll_ifindex as u32,
+            hatype: value.sll_hatype,
+            pkttype: value.sll_pkttype,
+            halen: value.sll_halen.min(8),
+            addr: value.sll_addr,
+        })
+    }
+}
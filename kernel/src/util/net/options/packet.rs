@@ -0,0 +1,97 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::RawSocketOption;
+use crate::{
+    current_userspace, impl_raw_sock_option_get_only, impl_raw_sock_option_set_only,
+    impl_raw_socket_option,
+    net::socket::packet::options::{
+        AddMembership, DropMembership, HdrLen, Reserve, RxRing, Statistics, Version,
+    },
+    prelude::*,
+    util::net::options::SocketOption,
+};
+
+/// Socket options for packet sockets.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/if_packet.h#L41>.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[expect(non_camel_case_types)]
+#[expect(clippy::upper_case_acronyms)]
+enum CPacketOptionName {
+    ADD_MEMBERSHIP = 1,
+    DROP_MEMBERSHIP = 2,
+    RECV_OUTPUT = 3,
+    RX_RING = 5,
+    STATISTICS = 6,
+    COPY_THRESH = 7,
+    AUXDATA = 8,
+    ORIGDEV = 9,
+    VERSION = 10,
+    HDRLEN = 11,
+    RESERVE = 12,
+    TX_RING = 13,
+    LOSS = 14,
+    VNET_HDR = 15,
+    TX_TIMESTAMP = 16,
+    TIMESTAMP = 17,
+    FANOUT = 18,
+    TX_HAS_OFF = 19,
+    QDISC_BYPASS = 20,
+    ROLLOVER_STATS = 21,
+    FANOUT_DATA = 22,
+    IGNORE_OUTGOING = 23,
+}
+
+pub fn new_packet_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
+    let name = CPacketOptionName::try_from(name).map_err(|_| Errno::ENOPROTOOPT)?;
+    match name {
+        CPacketOptionName::ADD_MEMBERSHIP => Ok(Box::new(AddMembership::new())),
+        CPacketOptionName::DROP_MEMBERSHIP => Ok(Box::new(DropMembership::new())),
+        CPacketOptionName::RX_RING => Ok(Box::new(RxRing::new())),
+        CPacketOptionName::STATISTICS => Ok(Box::new(Statistics::new())),
+        CPacketOptionName::VERSION => Ok(Box::new(Version::new())),
+        CPacketOptionName::HDRLEN => Ok(Box::new(HdrLen::new())),
+        CPacketOptionName::RESERVE => Ok(Box::new(Reserve::new())),
+        _ => return_errno_with_message!(Errno::ENOPROTOOPT, "unsupported packet-level option"),
+    }
+}
+
+impl_raw_sock_option_set_only!(AddMembership);
+impl_raw_sock_option_set_only!(DropMembership);
+impl_raw_sock_option_set_only!(RxRing);
+impl_raw_sock_option_get_only!(Statistics);
+impl_raw_socket_option!(Version);
+impl_raw_socket_option!(Reserve);
+
+// `PACKET_HDRLEN` takes the version as its input and returns the header length of that version.
+impl RawSocketOption for HdrLen {
+    fn read_from_user(&mut self, _addr: Vaddr, _max_len: u32) -> Result<()> {
+        return_errno_with_message!(Errno::ENOPROTOOPT, "the option is getter-only");
+    }
+
+    fn read_input_from_user(&mut self, addr: Vaddr, max_len: u32) -> Result<()> {
+        if (max_len as usize) < core::mem::size_of::<u32>() {
+            return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+        }
+
+        let version = current_userspace!().read_val::<u32>(addr)?;
+        self.set(version);
+        Ok(())
+    }
+
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        use super::utils::WriteToUser;
+
+        let output = self.get().unwrap();
+        output.write_to_user(addr, max_len)
+    }
+
+    fn as_sock_option_mut(&mut self) -> &mut dyn SocketOption {
+        self
+    }
+
+    fn as_sock_option(&self) -> &dyn SocketOption {
+        self
+    }
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MPL-2.0
+
+use super::RawSocketOption;
+use crate::{
+    impl_raw_socket_option, net::socket::ip::raw_options::IcmpFilter, prelude::*,
+    util::net::options::SocketOption,
+};
+
+/// Socket options for raw IP sockets.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/icmp.h#L118>.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt)]
+#[expect(non_camel_case_types)]
+pub enum CRawOptionName {
+    ICMP_FILTER = 1,
+}
+
+pub fn new_raw_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
+    let name = CRawOptionName::try_from(name).map_err(|_| Errno::ENOPROTOOPT)?;
+    match name {
+        CRawOptionName::ICMP_FILTER => Ok(Box::new(IcmpFilter::new())),
+    }
+}
+
+impl_raw_socket_option!(IcmpFilter);
@@ -2,9 +2,10 @@
 
 use super::RawSocketOption;
 use crate::{
-    impl_raw_sock_option_get_only, impl_raw_socket_option,
+    impl_raw_sock_option_get_only, impl_raw_sock_option_set_only, impl_raw_socket_option,
     net::socket::options::{
-        Error, KeepAlive, Linger, RecvBuf, ReuseAddr, ReusePort, SendBuf, SocketOption,
+        AttachFilter, DetachFilter, Error, KeepAlive, Linger, PassCred, PeerCred, RecvBuf,
+        ReuseAddr, ReusePort, SendBuf, SocketOption,
     },
     prelude::*,
 };
@@ -34,6 +35,10 @@ enum CSocketOptionName {
     LINGER = 13,
     BSDCOMPAT = 14,
     REUSEPORT = 15,
+    PASSCRED = 16,
+    PEERCRED = 17,
+    ATTACH_FILTER = 26,
+    DETACH_FILTER = 27,
     RCVTIMEO_NEW = 66,
     SNDTIMEO_NEW = 67,
 }
@@ -48,6 +53,10 @@ pub fn new_socket_option(name: i32) -> Result<Box<dyn RawSocketOption>> {
         CSocketOptionName::REUSEPORT => Ok(Box::new(ReusePort::new())),
         CSocketOptionName::LINGER => Ok(Box::new(Linger::new())),
         CSocketOptionName::KEEPALIVE => Ok(Box::new(KeepAlive::new())),
+        CSocketOptionName::PASSCRED => Ok(Box::new(PassCred::new())),
+        CSocketOptionName::PEERCRED => Ok(Box::new(PeerCred::new())),
+        CSocketOptionName::ATTACH_FILTER => Ok(Box::new(AttachFilter::new())),
+        CSocketOptionName::DETACH_FILTER => Ok(Box::new(DetachFilter::new())),
         _ => return_errno_with_message!(Errno::ENOPROTOOPT, "unsupported socket-level option"),
     }
 }
@@ -59,3 +68,7 @@ impl_raw_sock_option_get_only!(Error);
 impl_raw_socket_option!(ReusePort);
 impl_raw_socket_option!(Linger);
 impl_raw_socket_option!(KeepAlive);
+impl_raw_socket_option!(PassCred);
+impl_raw_sock_option_get_only!(PeerCred);
+impl_raw_sock_option_set_only!(AttachFilter);
+impl_raw_sock_option_set_only!(DetachFilter);
@@ -5,10 +5,16 @@ use core::{num::NonZeroU8, time::Duration};
 use crate::{
     current_userspace,
     net::socket::{
-        ip::{options::IpTtl, stream_options::CongestionControl},
-        util::LingerOption,
+        ip::{
+            options::IpTtl,
+            stream_options::{CTcpInfo, CongestionControl},
+        },
+        packet::{CPacketMreq, CTpacketReq3, PacketStats},
+        unix::UnixCredentials,
+        util::{CSockFilter, LingerOption, SocketFilter},
     },
     prelude::*,
+    util::net::CUserCred,
 };
 
 /// Create an object by reading its C counterpart from the user space.
@@ -65,6 +71,29 @@ macro_rules! impl_read_write_for_32bit_type {
 impl_read_write_for_32bit_type!(i32);
 impl_read_write_for_32bit_type!(u32);
 
+/// This macro is used to implement `ReadFromUser` for C structs that are read as is.
+macro_rules! impl_read_for_pod_type {
+    ($pod_ty: ty) => {
+        impl ReadFromUser for $pod_ty {
+            fn read_from_user(addr: Vaddr, max_len: u32) -> Result<Self> {
+                if (max_len as usize) < core::mem::size_of::<$pod_ty>() {
+                    return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+                }
+                crate::current_userspace!().read_val::<$pod_ty>(addr)
+            }
+        }
+    };
+}
+
+impl_read_for_pod_type!(CPacketMreq);
+impl_read_for_pod_type!(CTpacketReq3);
+
+impl ReadFromUser for () {
+    fn read_from_user(_addr: Vaddr, _max_len: u32) -> Result<Self> {
+        Ok(())
+    }
+}
+
 impl ReadFromUser for bool {
     fn read_from_user(addr: Vaddr, max_len: u32) -> Result<Self> {
         let val = i32::read_from_user(addr, max_len)?;
@@ -162,6 +191,59 @@ impl WriteToUser for LingerOption {
     }
 }
 
+impl WriteToUser for UnixCredentials {
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        let write_len = core::mem::size_of::<CUserCred>().min(max_len as usize);
+
+        let cred = CUserCred::from(*self);
+        current_userspace!()
+            .write_bytes(addr, &mut VmReader::from(&cred.as_bytes()[..write_len]))?;
+
+        Ok(write_len)
+    }
+}
+
+impl WriteToUser for PacketStats {
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        let mut bytes = [0u8; 12];
+        bytes[0..4].copy_from_slice(self.packets.as_bytes());
+        bytes[4..8].copy_from_slice(self.drops.as_bytes());
+
+        // `struct tpacket_stats_v3` has an extra field compared to `struct tpacket_stats`.
+        let stats_len = if let Some(freeze_q_cnt) = self.freeze_q_cnt {
+            bytes[8..12].copy_from_slice(freeze_q_cnt.as_bytes());
+            12
+        } else {
+            8
+        };
+        let write_len = stats_len.min(max_len as usize);
+
+        current_userspace!().write_bytes(addr, &mut VmReader::from(&bytes[..write_len]))?;
+
+        Ok(write_len)
+    }
+}
+
+impl ReadFromUser for SocketFilter {
+    fn read_from_user(addr: Vaddr, max_len: u32) -> Result<Self> {
+        if (max_len as usize) < core::mem::size_of::<CSockFprog>() {
+            return_errno_with_message!(Errno::EINVAL, "max_len is too short");
+        }
+
+        let user_space = current_userspace!();
+
+        let fprog = user_space.read_val::<CSockFprog>(addr)?;
+
+        let mut insns = Vec::with_capacity(fprog.len as usize);
+        for i in 0..fprog.len as usize {
+            let insn_addr = fprog.filter as usize + i * core::mem::size_of::<CSockFilter>();
+            insns.push(user_space.read_val::<CSockFilter>(insn_addr)?);
+        }
+
+        SocketFilter::new(insns)
+    }
+}
+
 const TCP_CONGESTION_NAME_MAX: u32 = 16;
 
 impl ReadFromUser for CongestionControl {
@@ -179,9 +261,12 @@ impl ReadFromUser for CongestionControl {
         #[expect(clippy::useless_asref)]
         current_userspace!().read_bytes(addr, &mut VmWriter::from(dst.as_mut()))?;
 
-        let name = core::str::from_utf8(dst)
+        // The name is a C string, so anything after the first NUL byte is ignored.
+        let name_len = dst.iter().position(|byte| *byte == 0).unwrap_or(dst.len());
+        let name = core::str::from_utf8(&dst[..name_len])
             .map_err(|_| Error::with_message(Errno::ENOENT, "non-UTF8 congestion name"))?;
-        CongestionControl::new(name)
+        CongestionControl::from_name(name)
+            .ok_or_else(|| Error::with_message(Errno::ENOENT, "unsupported congestion name"))
     }
 }
 
@@ -201,6 +286,29 @@ impl WriteToUser for CongestionControl {
     }
 }
 
+impl WriteToUser for CTcpInfo {
+    fn write_to_user(&self, addr: Vaddr, max_len: u32) -> Result<usize> {
+        // Like Linux, truncate the structure if the user buffer is too small.
+        let write_len = size_of::<CTcpInfo>().min(max_len as usize);
+
+        current_userspace!()
+            .write_bytes(addr, &mut VmReader::from(&self.as_bytes()[..write_len]))?;
+
+        Ok(write_len)
+    }
+}
+
+/// A classic BPF program.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/include/uapi/linux/filter.h#L31>.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+struct CSockFprog {
+    len: u16,
+    _pad: [u8; 6],
+    filter: u64,
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod)]
 struct CLinger {
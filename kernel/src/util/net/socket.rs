@@ -2,8 +2,9 @@
 
 use super::read_socket_addr_from_user;
 use crate::{
-    net::socket::util::SocketAddr,
+    net::socket::{unix::UnixCredentials, util::SocketAddr},
     prelude::*,
+    process::{Gid, Pid, Uid},
     util::{net::write_socket_addr_with_max_len, VmReaderArray, VmWriterArray},
 };
 
@@ -88,9 +89,9 @@ pub struct CUserMsgHdr {
     /// Ancillary data
     pub msg_control: Vaddr,
     /// Ancillary data buffer length
-    pub msg_controllen: u32,
+    pub msg_controllen: usize,
     /// Flags on received message
-    pub msg_flags: u32,
+    pub msg_flags: i32,
 }
 
 impl CUserMsgHdr {
@@ -126,3 +127,84 @@ impl CUserMsgHdr {
         VmWriterArray::from_user_io_vecs(user_space, self.msg_iov, self.msg_iovlen as usize)
     }
 }
+
+/// The header of a control message (ancillary data).
+///
+/// The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/socket.h#L95.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CControlHeader {
+    /// Data byte count, including the header
+    pub cmsg_len: usize,
+    /// Originating protocol
+    pub cmsg_level: i32,
+    /// Protocol-specific type
+    pub cmsg_type: i32,
+}
+
+impl CControlHeader {
+    /// The alignment of control messages (`CMSG_ALIGN` in Linux).
+    const ALIGN: usize = size_of::<usize>();
+
+    /// Returns the value of `cmsg_len` for a payload of `payload_len` bytes (`CMSG_LEN` in
+    /// Linux).
+    pub const fn len_for_payload(payload_len: usize) -> usize {
+        size_of::<Self>() + payload_len
+    }
+
+    /// Returns the space occupied by a control message with a payload of `payload_len` bytes
+    /// (`CMSG_SPACE` in Linux).
+    pub const fn space_for_payload(payload_len: usize) -> usize {
+        Self::len_for_payload(payload_len).next_multiple_of(Self::ALIGN)
+    }
+
+    /// Returns the space occupied by the control message, including the padding.
+    pub const fn space(&self) -> usize {
+        self.cmsg_len.next_multiple_of(Self::ALIGN)
+    }
+
+    /// Returns the length of the payload.
+    pub const fn payload_len(&self) -> usize {
+        self.cmsg_len - size_of::<Self>()
+    }
+}
+
+/// Types of socket-level control messages.
+///
+/// The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/socket.h#L168.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, TryFromInt, PartialEq, Eq)]
+#[expect(non_camel_case_types)]
+pub enum CSocketControlType {
+    /// Access rights (an array of file descriptors)
+    SCM_RIGHTS = 1,
+    /// Process credentials (`struct ucred`)
+    SCM_CREDENTIALS = 2,
+}
+
+/// Process credentials.
+///
+/// The definition is from https://elixir.bootlin.com/linux/v6.0.9/source/include/linux/socket.h#L174.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod)]
+pub struct CUserCred {
+    pub pid: Pid,
+    pub uid: Uid,
+    pub gid: Gid,
+}
+
+impl From<UnixCredentials> for CUserCred {
+    fn from(value: UnixCredentials) -> Self {
+        Self {
+            pid: value.pid(),
+            uid: value.uid(),
+            gid: value.gid(),
+        }
+    }
+}
+
+impl From<CUserCred> for UnixCredentials {
+    fn from(value: CUserCred) -> Self {
+        UnixCredentials::new(value.pid, value.uid, value.gid)
+    }
+}
@@ -11,14 +11,14 @@
 //! necessary time-related information, and a Virtual Memory Object (VMO) that encapsulates both the data and the
 //! VDSO routines. The VMO is intended to be mapped into the address space of every user space process for efficient access.
 //!
-//! The module is initialized with `init`, which sets up the `START_SECS_COUNT` and prepares the VDSO instance for
-//! use. It also hooks up the VDSO data update routine to the time management subsystem for periodic updates.
+//! The module is initialized with `init`, which prepares the VDSO instance for use. It also hooks up the VDSO data
+//! update routine to the time management subsystem for periodic updates.
 
 use alloc::{boxed::Box, sync::Arc};
 use core::{mem::ManuallyDrop, time::Duration};
 
 use aster_rights::Rights;
-use aster_time::{read_monotonic_time, Instant};
+use aster_time::{read_monotonic_time, Instant, VdsoClock};
 use aster_util::coeff::Coeff;
 use ostd::{
     mm::{UFrame, VmIo, PAGE_SIZE},
@@ -30,15 +30,13 @@ use spin::Once;
 use crate::{
     fs::fs_resolver::{FsPath, FsResolver, AT_FDCWD},
     syscall::ClockId,
-    time::{clocks::MonotonicClock, timer::Timeout, SystemTime, START_TIME},
+    time::{clocks::MonotonicClock, realtime_offset, timer::Timeout},
     vm::vmo::{Vmo, VmoOptions},
 };
 
 const CLOCK_TAI: usize = 11;
 const VDSO_BASES: usize = CLOCK_TAI + 1;
-const DEFAULT_CLOCK_MODE: VdsoClockMode = VdsoClockMode::Tsc;
 
-static START_SECS_COUNT: Once<u64> = Once::new();
 static VDSO: Once<Arc<Vdso>> = Once::new();
 
 #[derive(Debug, Copy, Clone)]
@@ -50,6 +48,16 @@ enum VdsoClockMode {
     Timens = i32::MAX as isize,
 }
 
+impl From<&VdsoClock> for VdsoClockMode {
+    fn from(vdso_clock: &VdsoClock) -> Self {
+        match vdso_clock {
+            VdsoClock::Tsc => VdsoClockMode::Tsc,
+            VdsoClock::Pvclock(_) => VdsoClockMode::Pvclock,
+            VdsoClock::None => VdsoClockMode::None,
+        }
+    }
+}
+
 /// Instant used in `VdsoData`.
 ///
 /// Each `VdsoInstant` will store a instant information for a specified `ClockId`.
@@ -134,15 +142,13 @@ impl VdsoData {
     }
 
     /// Init VDSO data based on the default clocksource.
-    fn init(&mut self) {
-        let clocksource = aster_time::default_clocksource();
-        let coeff = clocksource.coeff();
-        self.set_clock_mode(DEFAULT_CLOCK_MODE);
-        self.set_coeff(coeff);
-
-        let (last_instant, last_cycles) = clocksource.last_record();
-        self.update_high_res_instant(last_instant, last_cycles);
-        self.update_coarse_res_instant(last_instant);
+    fn init(&mut self, clock_mode: VdsoClockMode) {
+        let last_record = aster_time::default_clocksource().last_record();
+        self.set_clock_mode(clock_mode);
+        self.set_coeff(&last_record.coeff());
+
+        self.update_high_res_instant(last_record.instant(), last_record.cycles());
+        self.update_coarse_res_instant(last_record.instant());
     }
 
     fn set_clock_mode(&mut self, mode: VdsoClockMode) {
@@ -162,15 +168,15 @@ impl VdsoData {
     fn update_high_res_instant(&mut self, instant: Instant, instant_cycles: u64) {
         self.last_cycles = instant_cycles;
         for clock_id in HIGH_RES_CLOCK_IDS {
-            let secs = if clock_id == ClockId::CLOCK_REALTIME {
-                instant.secs() + START_SECS_COUNT.get().unwrap()
+            let instant = if clock_id == ClockId::CLOCK_REALTIME {
+                instant + realtime_offset()
             } else {
-                instant.secs()
+                instant
             };
 
             self.update_clock_instant(
                 clock_id as usize,
-                secs,
+                instant.secs(),
                 (instant.nanos() as u64) << self.shift as u64,
             );
         }
@@ -178,12 +184,12 @@ impl VdsoData {
 
     fn update_coarse_res_instant(&mut self, instant: Instant) {
         for clock_id in COARSE_RES_CLOCK_IDS {
-            let secs = if clock_id == ClockId::CLOCK_REALTIME_COARSE {
-                instant.secs() + START_SECS_COUNT.get().unwrap()
+            let instant = if clock_id == ClockId::CLOCK_REALTIME_COARSE {
+                instant + realtime_offset()
             } else {
-                instant.secs()
+                instant
             };
-            self.update_clock_instant(clock_id as usize, secs, instant.nanos() as u64);
+            self.update_clock_instant(clock_id as usize, instant.secs(), instant.nanos() as u64);
         }
     }
 }
@@ -210,11 +216,18 @@ static SEQ_LOCK: SpinLock<()> = SpinLock::new(());
 /// The size of the VDSO VMO.
 pub const VDSO_VMO_SIZE: usize = 5 * PAGE_SIZE;
 
+/// The offset of the VDSO library text in the VDSO VMO.
+pub const VDSO_TEXT_OFFSET: usize = 0x4000;
+
+/// The offset of the page that contains the `pvclock_vcpu_time_info` structure in the VDSO VMO.
+const VDSO_PVCLOCK_OFFSET: usize = 0x1000;
+
 impl Vdso {
     /// Construct a new `Vdso`, including an initialized `VdsoData` and a VMO of the VDSO.
     fn new() -> Self {
+        let vdso_clock = aster_time::vdso_clock();
         let mut vdso_data = VdsoData::empty();
-        vdso_data.init();
+        vdso_data.init(VdsoClockMode::from(&vdso_clock));
 
         let (vdso_vmo, data_frame) = {
             let vmo_options = VmoOptions::<Rights>::new(VDSO_VMO_SIZE);
@@ -231,7 +244,14 @@ impl Vdso {
             let mut vdso_text = Box::new([0u8; PAGE_SIZE]);
             vdso_lib_vmo.read_bytes(0, &mut *vdso_text).unwrap();
             // Write VDSO library to VDSO VMO.
-            vdso_vmo.write_bytes(0x4000, &*vdso_text).unwrap();
+            vdso_vmo.write_bytes(VDSO_TEXT_OFFSET, &*vdso_text).unwrap();
+
+            // Map the `pvclock_vcpu_time_info` structure, which is updated by the hypervisor.
+            if let VdsoClock::Pvclock(pvclock_frame) = vdso_clock {
+                vdso_vmo
+                    .replace(pvclock_frame.into(), VDSO_PVCLOCK_OFFSET / PAGE_SIZE)
+                    .unwrap();
+            }
 
             let data_frame = vdso_vmo.try_commit_page(0).unwrap();
             (vdso_vmo, data_frame)
@@ -244,7 +264,7 @@ impl Vdso {
     }
 
     fn update_high_res_instant(&self, instant: Instant, instant_cycles: u64) {
-        let seq_lock = SEQ_LOCK.lock();
+        let seq_lock = SEQ_LOCK.disable_irq().lock();
         self.data
             .lock()
             .update_high_res_instant(instant, instant_cycles);
@@ -261,7 +281,7 @@ impl Vdso {
     }
 
     fn update_coarse_res_instant(&self, instant: Instant) {
-        let seq_lock = SEQ_LOCK.lock();
+        let seq_lock = SEQ_LOCK.disable_irq().lock();
         self.data.lock().update_coarse_res_instant(instant);
 
         // Update begins.
@@ -274,6 +294,40 @@ impl Vdso {
         self.data_frame.write_val(0x80, &0).unwrap();
     }
 
+    /// Updates the whole clock information, including the coeff of the clocksource and all
+    /// the instants.
+    ///
+    /// This should be called after the clock is set or adjusted, so that user space never
+    /// combines the new coeff with an old instant, or vice versa.
+    fn update_clock(&self) {
+        let seq_lock = SEQ_LOCK.disable_irq().lock();
+
+        // The coeff must be taken from the same record as the instant and the cycles. Otherwise,
+        // a concurrent frequency adjustment may pair the new coeff with the old instant.
+        let last_record = aster_time::default_clocksource().last_record();
+        let coeff = last_record.coeff();
+        let (last_instant, last_cycles) = (last_record.instant(), last_record.cycles());
+        let coarse_instant = Instant::from(read_monotonic_time());
+        {
+            let mut data = self.data.lock();
+            data.set_coeff(&coeff);
+            data.update_high_res_instant(last_instant, last_cycles);
+            data.update_coarse_res_instant(coarse_instant);
+        }
+
+        // Update begins.
+        self.data_frame.write_val(0x80, &1).unwrap();
+        self.data_frame.write_val(0x88, &last_cycles).unwrap();
+        self.data_frame.write_val(0x98, &coeff.mult()).unwrap();
+        self.data_frame.write_val(0x9C, &coeff.shift()).unwrap();
+        for clock_id in HIGH_RES_CLOCK_IDS.iter().chain(COARSE_RES_CLOCK_IDS.iter()) {
+            self.update_data_frame_instant(*clock_id);
+        }
+
+        // Update finishes.
+        self.data_frame.write_val(0x80, &0).unwrap();
+    }
+
     /// Update the requisite fields of the VDSO data in the `data_frame`.
     fn update_data_frame_instant(&self, clockid: ClockId) {
         let clock_index = clockid as usize;
@@ -302,14 +356,12 @@ fn update_vdso_coarse_res_instant() {
     VDSO.get().unwrap().update_coarse_res_instant(instant);
 }
 
-/// Init `START_SECS_COUNT`, which is used to record the seconds passed since 1970-01-01 00:00:00.
-fn init_start_secs_count() {
-    let time_duration = START_TIME
-        .get()
-        .unwrap()
-        .duration_since(&SystemTime::UNIX_EPOCH)
-        .unwrap();
-    START_SECS_COUNT.call_once(|| time_duration.as_secs());
+/// Updates the whole clock information in Vdso after the clock is set or adjusted.
+pub(crate) fn update_vdso_clock() {
+    // The clock can be set before Vdso is initialized.
+    if let Some(vdso) = VDSO.get() {
+        vdso.update_clock();
+    }
 }
 
 fn init_vdso() {
@@ -319,7 +371,6 @@ fn init_vdso() {
 
 /// Init this module.
 pub(super) fn init() {
-    init_start_secs_count();
     init_vdso();
     aster_time::VDSO_DATA_HIGH_RES_UPDATE_FN.call_once(|| Arc::new(update_vdso_high_res_instant));
 
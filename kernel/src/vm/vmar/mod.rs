@@ -14,7 +14,8 @@ use aster_rights::Rights;
 use ostd::{
     cpu::CpuId,
     mm::{
-        tlb::TlbFlushOp, vm_space::CursorMut, PageFlags, PageProperty, VmSpace, MAX_USERSPACE_VADDR,
+        tlb::TlbFlushOp, vm_space::CursorMut, PageFlags, PageProperty, UFrame, UntypedMem, VmSpace,
+        MAX_USERSPACE_VADDR,
     },
     sync::RwMutexReadGuard,
     task::disable_preempt,
@@ -22,12 +23,14 @@ use ostd::{
 
 use self::{
     interval_set::{Interval, IntervalSet},
-    vm_mapping::{MappedVmo, VmMapping},
+    vm_mapping::{MappedVmo, MlockMode, SpecialMapping, VmMapping},
 };
 use crate::{
-    fs::utils::Inode,
+    fs::path::Dentry,
     prelude::*,
-    process::{Process, ResourceType},
+    process::{
+        credentials::capabilities::CapSet, posix_thread::AsPosixThread, Process, ResourceType,
+    },
     thread::exception::PageFaultInfo,
     util::per_cpu_counter::PerCpuCounter,
     vm::{
@@ -129,6 +132,44 @@ impl<R> Vmar<R> {
     ) -> Result<Vaddr> {
         self.0.remap(old_addr, old_size, new_addr, new_size)
     }
+
+    /// Locks the mappings in the specified range in memory.
+    ///
+    /// The range's start and end addresses must be page-aligned. Also, the
+    /// range must be completely mapped.
+    ///
+    /// If `mlock` is [`MlockMode::Populate`], all the pages in the range are
+    /// faulted in before this method returns.
+    pub fn mlock(&self, mlock: MlockMode, range: Range<usize>) -> Result<()> {
+        self.0.set_mlock(Some(mlock), range)
+    }
+
+    /// Unlocks the mappings in the specified range.
+    ///
+    /// The range's start and end addresses must be page-aligned. Also, the
+    /// range must be completely mapped.
+    pub fn munlock(&self, range: Range<usize>) -> Result<()> {
+        self.0.set_mlock(None, range)
+    }
+
+    /// Locks all the mappings in memory.
+    ///
+    /// If `mlock_current` is `Some`, all the existing mappings are locked
+    /// with it. If `mlock_future` is `Some`, all the mappings created later
+    /// are locked with it.
+    pub fn mlock_all(
+        &self,
+        mlock_current: Option<MlockMode>,
+        mlock_future: Option<MlockMode>,
+    ) -> Result<()> {
+        self.0
+            .set_mlock_all(mlock_current, mlock_current.is_some(), mlock_future)
+    }
+
+    /// Unlocks all the mappings, including the ones created later.
+    pub fn munlock_all(&self) -> Result<()> {
+        self.0.set_mlock_all(None, true, None)
+    }
 }
 
 pub(super) struct Vmar_ {
@@ -149,6 +190,11 @@ struct VmarInner {
     vm_mappings: IntervalSet<Vaddr, VmMapping>,
     /// The total mapped memory in bytes.
     total_vm: usize,
+    /// The total locked memory in bytes.
+    locked_vm: usize,
+    /// How the new mappings are locked in memory, as set by `mlockall` with
+    /// `MCL_FUTURE`.
+    mlock_future: Option<MlockMode>,
 }
 
 impl VmarInner {
@@ -156,6 +202,8 @@ impl VmarInner {
         Self {
             vm_mappings: IntervalSet::new(),
             total_vm: 0,
+            locked_vm: 0,
+            mlock_future: None,
         }
     }
 
@@ -183,6 +231,40 @@ impl VmarInner {
         Ok(())
     }
 
+    /// Returns `Ok` if the calling process may expand its locked
+    /// memory by the passed size.
+    ///
+    /// The limit does not apply to processes with `CAP_IPC_LOCK`.
+    fn check_extra_locked_size_fits_rlimit(&self, expand_size: usize) -> Result<()> {
+        let Some(process) = Process::current() else {
+            return Ok(());
+        };
+
+        let has_ipc_lock = current_thread!()
+            .as_posix_thread()
+            .unwrap()
+            .credentials()
+            .effective_capset()
+            .contains(CapSet::IPC_LOCK);
+        if has_ipc_lock {
+            return Ok(());
+        }
+
+        let rlimit_memlock = process
+            .resource_limits()
+            .get_rlimit(ResourceType::RLIMIT_MEMLOCK)
+            .get_cur();
+
+        let new_locked_vm = self
+            .locked_vm
+            .checked_add(expand_size)
+            .ok_or(Errno::EAGAIN)?;
+        if new_locked_vm > rlimit_memlock as usize {
+            return_errno_with_message!(Errno::EAGAIN, "locked memory limit overflow");
+        }
+        Ok(())
+    }
+
     /// Checks whether `addr..addr + size` is covered by a single `VmMapping`,
     /// and returns the address of the single `VmMapping` if successful.
     fn check_lies_in_single_mapping(&self, addr: Vaddr, size: usize) -> Result<Vaddr> {
@@ -205,6 +287,9 @@ impl VmarInner {
     /// Make sure the insertion doesn't exceed address space limit.
     fn insert(&mut self, vm_mapping: VmMapping) {
         self.total_vm += vm_mapping.map_size();
+        if vm_mapping.mlock().is_some() {
+            self.locked_vm += vm_mapping.map_size();
+        }
         self.vm_mappings.insert(vm_mapping);
     }
 
@@ -212,6 +297,9 @@ impl VmarInner {
     fn remove(&mut self, key: &Vaddr) -> Option<VmMapping> {
         let vm_mapping = self.vm_mappings.remove(key)?;
         self.total_vm -= vm_mapping.map_size();
+        if vm_mapping.mlock().is_some() {
+            self.locked_vm -= vm_mapping.map_size();
+        }
         Some(vm_mapping)
     }
 
@@ -232,6 +320,21 @@ impl VmarInner {
         sum_overlap_size
     }
 
+    /// Calculates the total amount of overlap between unlocked `VmMapping`s
+    /// and the provided range.
+    fn count_unlocked_overlap_size(&self, range: Range<Vaddr>) -> usize {
+        let mut sum_overlap_size = 0;
+        for vm_mapping in self.vm_mappings.find(&range) {
+            if vm_mapping.mlock().is_some() {
+                continue;
+            }
+            let vm_mapping_range = vm_mapping.range();
+            let intersected_range = get_intersected_range(&range, &vm_mapping_range);
+            sum_overlap_size += intersected_range.end - intersected_range.start;
+        }
+        sum_overlap_size
+    }
+
     /// Allocates a free region for mapping with a specific offset and size.
     ///
     /// If the provided range is already occupied, return an error.
@@ -376,6 +479,9 @@ impl VmarInner {
         debug_assert_eq!(last_mapping.map_end(), old_map_end);
 
         self.check_extra_size_fits_rlimit(new_map_end - old_map_end)?;
+        if last_mapping.mlock().is_some() {
+            self.check_extra_locked_size_fits_rlimit(new_map_end - old_map_end)?;
+        }
         let last_mapping = self.remove(&last_mapping_addr).unwrap();
         let last_mapping = last_mapping.enlarge(new_map_end - old_map_end);
         self.insert(last_mapping);
@@ -477,6 +583,150 @@ impl Vmar_ {
         Ok(())
     }
 
+    /// Locks or unlocks the mappings in the range in memory.
+    ///
+    /// The range must be completely mapped. If the mappings are locked with
+    /// [`MlockMode::Populate`], their pages are faulted in.
+    fn set_mlock(&self, mlock: Option<MlockMode>, range: Range<usize>) -> Result<()> {
+        assert!(range.start % PAGE_SIZE == 0);
+        assert!(range.end % PAGE_SIZE == 0);
+
+        {
+            let mut inner = self.inner.write();
+
+            if inner.count_overlap_size(range.clone()) != range.len() {
+                return_errno_with_message!(Errno::ENOMEM, "the range is not completely mapped");
+            }
+            if mlock.is_some() {
+                let expand_size = inner.count_unlocked_overlap_size(range.clone());
+                inner
+                    .check_extra_locked_size_fits_rlimit(expand_size)
+                    .map_err(|_| {
+                        Error::with_message(Errno::ENOMEM, "locked memory limit overflow")
+                    })?;
+            }
+
+            let mut mlock_mappings = Vec::new();
+            for vm_mapping in inner.vm_mappings.find(&range) {
+                if vm_mapping.mlock() != mlock {
+                    mlock_mappings.push(vm_mapping.map_to_addr());
+                }
+            }
+
+            for vm_mapping_addr in mlock_mappings {
+                let vm_mapping = inner.remove(&vm_mapping_addr).unwrap();
+                let vm_mapping_range = vm_mapping.range();
+                let intersected_range = get_intersected_range(&range, &vm_mapping_range);
+
+                let (left, taken, right) = vm_mapping.split_range(&intersected_range);
+                inner.insert(taken.set_mlock(mlock));
+                if let Some(left) = left {
+                    inner.insert(left);
+                }
+                if let Some(right) = right {
+                    inner.insert(right);
+                }
+            }
+        }
+
+        if mlock == Some(MlockMode::Populate) {
+            self.populate(range);
+        }
+
+        Ok(())
+    }
+
+    /// Locks or unlocks all the mappings in memory.
+    ///
+    /// If `lock_current` is true, the existing mappings are locked with
+    /// `mlock`, or unlocked if `mlock` is `None`. The new mappings will be
+    /// locked with `mlock_future`.
+    fn set_mlock_all(
+        &self,
+        mlock: Option<MlockMode>,
+        lock_current: bool,
+        mlock_future: Option<MlockMode>,
+    ) -> Result<()> {
+        {
+            let mut inner = self.inner.write();
+
+            if lock_current && mlock.is_some() {
+                let expand_size = inner.total_vm - inner.locked_vm;
+                inner
+                    .check_extra_locked_size_fits_rlimit(expand_size)
+                    .map_err(|_| {
+                        Error::with_message(Errno::ENOMEM, "locked memory limit overflow")
+                    })?;
+            }
+
+            inner.mlock_future = mlock_future;
+            if !lock_current {
+                return Ok(());
+            }
+
+            let mut mlock_mappings = Vec::new();
+            for vm_mapping in inner.vm_mappings.iter() {
+                if vm_mapping.mlock() != mlock {
+                    mlock_mappings.push(vm_mapping.map_to_addr());
+                }
+            }
+
+            for vm_mapping_addr in mlock_mappings {
+                let vm_mapping = inner.remove(&vm_mapping_addr).unwrap();
+                inner.insert(vm_mapping.set_mlock(mlock));
+            }
+        }
+
+        if mlock == Some(MlockMode::Populate) {
+            self.populate(self.base..self.base + self.size);
+        }
+
+        Ok(())
+    }
+
+    /// Faults in the pages of the mappings in the range.
+    ///
+    /// Errors are ignored, since some pages may not be accessible (e.g., the
+    /// pages beyond the end of the mapped file).
+    fn populate(&self, range: Range<usize>) {
+        let mut addr = range.start;
+        while addr < range.end {
+            let (required_perms, end) = {
+                let inner = self.inner.read();
+                let Some(vm_mapping) = inner.vm_mappings.find(&(addr..range.end)).next() else {
+                    break;
+                };
+                addr = addr.max(vm_mapping.map_to_addr());
+
+                let perms = vm_mapping.perms();
+                let required_perms = if perms.is_empty() {
+                    // The pages of inaccessible mappings cannot be faulted in.
+                    None
+                } else if perms.contains(VmPerms::WRITE) && !vm_mapping.is_shared() {
+                    // Private writable pages are faulted in for writing so
+                    // that they will not be copied on write later.
+                    Some(VmPerms::WRITE)
+                } else {
+                    Some(perms & VmPerms::READ)
+                };
+
+                (required_perms, vm_mapping.map_end().min(range.end))
+            };
+
+            if let Some(required_perms) = required_perms {
+                for page_addr in (addr..end).step_by(PAGE_SIZE) {
+                    let page_fault_info = PageFaultInfo {
+                        address: page_addr,
+                        required_perms,
+                    };
+                    let _ = self.handle_page_fault(&page_fault_info);
+                }
+            }
+
+            addr = end;
+        }
+    }
+
     /// Handles user space page fault, if the page fault is successfully handled, return Ok(()).
     pub fn handle_page_fault(&self, page_fault_info: &PageFaultInfo) -> Result<()> {
         let address = page_fault_info.address;
@@ -496,10 +746,106 @@ impl Vmar_ {
         return_errno_with_message!(Errno::EACCES, "page fault addr is not in current vmar");
     }
 
+    /// Reads the memory at `vaddr` of the VMAR into `writer`, on behalf of another address space.
+    pub fn read_remote(&self, vaddr: Vaddr, writer: &mut VmWriter) -> Result<usize> {
+        self.access_remote(
+            vaddr,
+            writer.avail(),
+            VmPerms::READ,
+            |frame, offset, len| {
+                let mut frame_reader = frame.reader();
+                frame_reader.skip(offset).limit(len);
+                Ok(writer.write_fallible(&mut frame_reader)?)
+            },
+        )
+    }
+
+    /// Writes the memory at `vaddr` of the VMAR from `reader`, on behalf of another address space.
+    pub fn write_remote(&self, vaddr: Vaddr, reader: &mut VmReader) -> Result<usize> {
+        self.access_remote(
+            vaddr,
+            reader.remain(),
+            VmPerms::WRITE,
+            |frame, offset, len| {
+                let mut frame_writer = frame.writer();
+                frame_writer.skip(offset).limit(len);
+                Ok(reader.read_fallible(&mut frame_writer)?)
+            },
+        )
+    }
+
+    /// Accesses at most `len` bytes of the memory at `vaddr` page by page.
+    ///
+    /// Each page is faulted in with `required_perms` before `access` is called with the frame,
+    /// the offset in the frame, and the number of bytes to access. The access stops at the first
+    /// page that cannot be faulted in.
+    fn access_remote<F>(
+        &self,
+        vaddr: Vaddr,
+        len: usize,
+        required_perms: VmPerms,
+        mut access: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(&UFrame, usize, usize) -> Result<usize>,
+    {
+        let mut accessed_len = 0;
+        while accessed_len < len {
+            let Some(addr) = vaddr.checked_add(accessed_len) else {
+                break;
+            };
+            let page_addr = addr.align_down(PAGE_SIZE);
+            let Ok(frame) = self.fault_in_page(page_addr, required_perms) else {
+                break;
+            };
+
+            let offset = addr - page_addr;
+            let access_len = (PAGE_SIZE - offset).min(len - accessed_len);
+            let copied_len = access(&frame, offset, access_len)?;
+            accessed_len += copied_len;
+            if copied_len < access_len {
+                break;
+            }
+        }
+
+        Ok(accessed_len)
+    }
+
+    /// Faults in the page at `page_addr` with `required_perms` and returns the mapped frame.
+    fn fault_in_page(&self, page_addr: Vaddr, required_perms: VmPerms) -> Result<UFrame> {
+        let page_fault_info = PageFaultInfo {
+            address: page_addr,
+            required_perms,
+        };
+
+        // The page may be unmapped or write-protected again by the owner of the VMAR after it is
+        // faulted in, so we retry once. Further retries are not expected to help.
+        const MAX_ATTEMPTS: usize = 2;
+
+        for _ in 0..MAX_ATTEMPTS {
+            self.handle_page_fault(&page_fault_info)?;
+
+            let preempt_guard = disable_preempt();
+            let mut cursor = self
+                .vm_space
+                .cursor(&preempt_guard, &(page_addr..page_addr + PAGE_SIZE))?;
+            if let (_, Some((frame, prop))) = cursor.query()? {
+                if VmPerms::from(prop.flags).contains(required_perms) {
+                    return Ok(frame);
+                }
+            }
+        }
+
+        return_errno_with_message!(
+            Errno::EFAULT,
+            "the page is not mapped with the required permissions"
+        );
+    }
+
     /// Clears all content of the root VMAR.
     fn clear_root_vmar(&self) -> Result<()> {
         let mut inner = self.inner.write();
-        inner.vm_mappings.clear();
+        *inner = VmarInner::new();
 
         // Keep `inner` locked to avoid race conditions.
         let preempt_guard = disable_preempt();
@@ -541,6 +887,20 @@ impl Vmar_ {
             inner.check_lies_in_single_mapping(map_addr, old_size)?;
         }
         inner.resize_mapping(&self.vm_space, map_addr, old_size, new_size, &mut rss_delta)?;
+
+        // The enlarged part of a locked mapping should also be faulted in.
+        if new_size > old_size {
+            let new_map_end = map_addr + new_size;
+            let mlock = inner
+                .vm_mappings
+                .find_one(&(new_map_end - 1))
+                .and_then(|vm_mapping| vm_mapping.mlock());
+            drop(inner);
+            if mlock == Some(MlockMode::Populate) {
+                self.populate(map_addr + old_size..new_map_end);
+            }
+        }
+
         Ok(())
     }
 
@@ -557,6 +917,10 @@ impl Vmar_ {
 
         let mut inner = self.inner.write();
         let old_mapping_addr = inner.check_lies_in_single_mapping(old_addr, old_size)?;
+        let old_mlock = inner.vm_mappings.find_one(&old_addr).unwrap().mlock();
+        if old_mlock.is_some() && new_size > old_size {
+            inner.check_extra_locked_size_fits_rlimit(new_size - old_size)?;
+        }
 
         let mut old_range = old_addr..old_addr + old_size;
         let mut old_size = old_size;
@@ -639,9 +1003,21 @@ impl Vmar_ {
         cursor.flusher().dispatch_tlb_flush();
         cursor.flusher().sync_tlb_flush();
 
+        drop(cursor);
+        drop(preempt_guard);
+        drop(inner);
+        if old_mlock == Some(MlockMode::Populate) && new_size > old_size {
+            self.populate(new_range.start + old_size..new_range.end);
+        }
+
         Ok(new_range.start)
     }
 
+    /// Returns the total size of the locked mappings in bytes.
+    pub fn locked_size(&self) -> usize {
+        self.inner.read().locked_vm
+    }
+
     /// Returns the attached `VmSpace`.
     fn vm_space(&self) -> &Arc<VmSpace> {
         &self.vm_space
@@ -764,6 +1140,35 @@ impl<R> Vmar<R> {
     pub fn get_rss_counter(&self, rss_type: RssType) -> usize {
         self.0.get_rss_counter(rss_type)
     }
+
+    /// Returns the total size of the locked mappings in bytes.
+    pub fn locked_size(&self) -> usize {
+        self.0.locked_size()
+    }
+
+    /// Reads the memory at `vaddr` of the VMAR into `writer`, on behalf of another address space
+    /// (e.g., for `process_vm_readv` or `/proc/[pid]/mem`).
+    ///
+    /// Unlike reading via [`VmSpace::reader`], the pages are faulted in as needed. The reading
+    /// stops at the first page that is not readable, so the returned number of bytes can be less
+    /// than the available space of `writer`.
+    ///
+    /// FIXME: This function should require access control
+    pub fn read_remote(&self, vaddr: Vaddr, writer: &mut VmWriter) -> Result<usize> {
+        self.0.read_remote(vaddr, writer)
+    }
+
+    /// Writes the memory at `vaddr` of the VMAR from `reader`, on behalf of another address space
+    /// (e.g., for `process_vm_writev` or `/proc/[pid]/mem`).
+    ///
+    /// Unlike writing via [`VmSpace::writer`], the pages are faulted in (and copied on write) as
+    /// needed. The writing stops at the first page that is not writable, so the returned number
+    /// of bytes can be less than the remaining bytes of `reader`.
+    ///
+    /// FIXME: This function should require access control
+    pub fn write_remote(&self, vaddr: Vaddr, reader: &mut VmReader) -> Result<usize> {
+        self.0.write_remote(vaddr, reader)
+    }
 }
 
 /// Options for creating a new mapping. The mapping is not allowed to overlap
@@ -772,7 +1177,8 @@ impl<R> Vmar<R> {
 pub struct VmarMapOptions<'a, R1, R2> {
     parent: &'a Vmar<R1>,
     vmo: Option<Vmo<R2>>,
-    inode: Option<Arc<dyn Inode>>,
+    dentry: Option<Dentry>,
+    special: Option<SpecialMapping>,
     perms: VmPerms,
     vmo_offset: usize,
     vmo_limit: usize,
@@ -784,6 +1190,8 @@ pub struct VmarMapOptions<'a, R1, R2> {
     is_shared: bool,
     // Whether the mapping needs to handle surrounding pages when handling page fault.
     handle_page_faults_around: bool,
+    // How the mapping is locked in memory, e.g., when mapped with `MAP_LOCKED`.
+    mlock: Option<MlockMode>,
 }
 
 impl<'a, R1, R2> VmarMapOptions<'a, R1, R2> {
@@ -797,7 +1205,8 @@ impl<'a, R1, R2> VmarMapOptions<'a, R1, R2> {
         Self {
             parent,
             vmo: None,
-            inode: None,
+            dentry: None,
+            special: None,
             perms,
             vmo_offset: 0,
             vmo_limit: usize::MAX,
@@ -807,6 +1216,7 @@ impl<'a, R1, R2> VmarMapOptions<'a, R1, R2> {
             can_overwrite: false,
             is_shared: false,
             handle_page_faults_around: false,
+            mlock: None,
         }
     }
 
@@ -824,15 +1234,15 @@ impl<'a, R1, R2> VmarMapOptions<'a, R1, R2> {
     ///  2. Mappings are not allowed to overlap by default. As a result,
     ///     oversized mappings can reserve space for future expansions.
     ///
-    /// The [`Vmo`] of a mapping will be implicitly set if [`Self::inode`] is
+    /// The [`Vmo`] of a mapping will be implicitly set if [`Self::dentry`] is
     /// set.
     ///
     /// # Panics
     ///
-    /// This function panics if an [`Inode`] is already provided.
+    /// This function panics if a [`Dentry`] is already provided.
     pub fn vmo(mut self, vmo: Vmo<R2>) -> Self {
-        if self.inode.is_some() {
-            panic!("Cannot set `vmo` when `inode` is already set");
+        if self.dentry.is_some() {
+            panic!("Cannot set `vmo` when `dentry` is already set");
         }
         self.vmo = Some(vmo);
 
@@ -906,33 +1316,51 @@ impl<'a, R1, R2> VmarMapOptions<'a, R1, R2> {
         self.handle_page_faults_around = true;
         self
     }
+
+    /// Marks the mapping as a special mapping created by the kernel.
+    ///
+    /// The kind of the special mapping is reported in `/proc/[pid]/maps`.
+    pub fn special(mut self, special: SpecialMapping) -> Self {
+        self.special = Some(special);
+        self
+    }
+
+    /// Locks the mapping in memory.
+    ///
+    /// If not set, the mapping is locked only if all the future mappings of
+    /// the VMAR are required to be locked.
+    pub fn mlock(mut self, mlock: MlockMode) -> Self {
+        self.mlock = Some(mlock);
+        self
+    }
 }
 
 impl<R1> VmarMapOptions<'_, R1, Rights> {
-    /// Binds an [`Inode`] to the mapping.
+    /// Binds a file, which is represented by its [`Dentry`], to the mapping.
     ///
-    /// This is used for file-backed mappings. The provided file inode will be
-    /// mapped. See [`Self::vmo`] for details on the map size.
+    /// This is used for file-backed mappings. The inode of the provided file
+    /// will be mapped. See [`Self::vmo`] for details on the map size.
     ///
-    /// If an [`Inode`] is provided, the [`Self::vmo`] must not be provided
-    /// again. The actually mapped [`Vmo`] will be the [`Inode`]'s page cache.
+    /// If a [`Dentry`] is provided, the [`Self::vmo`] must not be provided
+    /// again. The actually mapped [`Vmo`] will be the inode's page cache.
     ///
     /// # Panics
     ///
     /// This function panics if:
-    ///  - a [`Vmo`] or [`Inode`] is already provided;
-    ///  - the provided [`Inode`] does not have a page cache.
-    pub fn inode(mut self, inode: Arc<dyn Inode>) -> Self {
+    ///  - a [`Vmo`] or [`Dentry`] is already provided;
+    ///  - the inode of the provided [`Dentry`] does not have a page cache.
+    pub fn dentry(mut self, dentry: Dentry) -> Self {
         if self.vmo.is_some() {
-            panic!("Cannot set `inode` when `vmo` is already set");
+            panic!("Cannot set `dentry` when `vmo` is already set");
         }
         self.vmo = Some(
-            inode
+            dentry
+                .inode()
                 .page_cache()
                 .expect("Map an inode without page cache")
                 .to_dyn(),
         );
-        self.inode = Some(inode);
+        self.dentry = Some(dentry);
 
         self
     }
@@ -952,7 +1380,8 @@ where
         let Self {
             parent,
             vmo,
-            inode,
+            dentry,
+            special,
             perms,
             vmo_offset,
             vmo_limit,
@@ -962,10 +1391,16 @@ where
             can_overwrite,
             is_shared,
             handle_page_faults_around,
+            mlock,
         } = self;
 
         let mut inner = parent.0.inner.write();
 
+        let mlock = mlock.or(inner.mlock_future);
+        if mlock.is_some() {
+            inner.check_extra_locked_size_fits_rlimit(map_size)?;
+        }
+
         inner.check_extra_size_fits_rlimit(map_size).or_else(|e| {
             if can_overwrite {
                 let offset = offset.ok_or(Error::with_message(
@@ -1010,14 +1445,21 @@ where
             NonZeroUsize::new(map_size).unwrap(),
             map_to_addr,
             vmo,
-            inode,
+            dentry,
+            special,
             is_shared,
             handle_page_faults_around,
             perms,
-        );
+        )
+        .set_mlock(mlock);
 
         // Add the mapping to the VMAR.
         inner.insert(vm_mapping);
+        drop(inner);
+
+        if mlock == Some(MlockMode::Populate) {
+            parent.0.populate(map_to_addr..map_to_addr + map_size);
+        }
 
         Ok(map_to_addr)
     }
@@ -9,14 +9,15 @@ use core::{
 use align_ext::AlignExt;
 use ostd::{
     mm::{
-        tlb::TlbFlushOp, CachePolicy, FrameAllocOptions, PageFlags, PageProperty, UFrame, VmSpace,
+        tlb::TlbFlushOp, CachePolicy, FrameAllocOptions, Paddr, PageFlags, PageProperty, UFrame,
+        VmSpace,
     },
     task::disable_preempt,
 };
 
 use super::{interval_set::Interval, RssDelta, RssType};
 use crate::{
-    fs::utils::Inode,
+    fs::{path::Dentry, utils::Inode},
     prelude::*,
     thread::exception::PageFaultInfo,
     vm::{
@@ -57,11 +58,13 @@ pub struct VmMapping {
     /// The start of the virtual address maps to the start of the range
     /// specified in [`MappedVmo`].
     vmo: Option<MappedVmo>,
-    /// The inode of the file that backs the mapping.
+    /// The dentry of the file that backs the mapping.
     ///
-    /// If the inode is `Some`, it means that the mapping is file-backed.
-    /// And the `vmo` field must be the page cache of the inode.
-    inode: Option<Arc<dyn Inode>>,
+    /// If the dentry is `Some`, it means that the mapping is file-backed.
+    /// And the `vmo` field must be the page cache of the dentry's inode.
+    dentry: Option<Dentry>,
+    /// The kind of the mapping if it is a special one created by the kernel.
+    special: Option<SpecialMapping>,
     /// Whether the mapping is shared.
     ///
     /// The updates to a shared mapping are visible among processes, or carried
@@ -74,6 +77,10 @@ pub struct VmMapping {
     ///
     /// All pages within the same `VmMapping` have the same permissions.
     perms: VmPerms,
+    /// How the pages of the mapping are locked in memory.
+    ///
+    /// If this field is `None`, the mapping is not locked.
+    mlock: Option<MlockMode>,
 }
 
 impl Interval<Vaddr> for VmMapping {
@@ -89,7 +96,8 @@ impl VmMapping {
         map_size: NonZeroUsize,
         map_to_addr: Vaddr,
         vmo: Option<MappedVmo>,
-        inode: Option<Arc<dyn Inode>>,
+        dentry: Option<Dentry>,
+        special: Option<SpecialMapping>,
         is_shared: bool,
         handle_page_faults_around: bool,
         perms: VmPerms,
@@ -98,17 +106,21 @@ impl VmMapping {
             map_size,
             map_to_addr,
             vmo,
-            inode,
+            dentry,
+            special,
             is_shared,
             handle_page_faults_around,
             perms,
+            mlock: None,
         }
     }
 
     pub(super) fn new_fork(&self) -> Result<VmMapping> {
         Ok(VmMapping {
             vmo: self.vmo.as_ref().map(|vmo| vmo.dup()).transpose()?,
-            inode: self.inode.clone(),
+            dentry: self.dentry.clone(),
+            // Memory locks are not inherited by the child process.
+            mlock: None,
             ..*self
         })
     }
@@ -116,6 +128,7 @@ impl VmMapping {
     pub(super) fn clone_for_remap_at(&self, va: Vaddr) -> Result<VmMapping> {
         let mut vm_mapping = self.new_fork()?;
         vm_mapping.map_to_addr = va;
+        vm_mapping.mlock = self.mlock;
         Ok(vm_mapping)
     }
 
@@ -139,9 +152,38 @@ impl VmMapping {
         self.perms
     }
 
+    /// Returns how the pages of the mapping are locked in memory.
+    ///
+    /// Returns `None` if the mapping is not locked.
+    pub fn mlock(&self) -> Option<MlockMode> {
+        self.mlock
+    }
+
     /// Returns the inode of the file that backs the mapping.
     pub fn inode(&self) -> Option<&Arc<dyn Inode>> {
-        self.inode.as_ref()
+        self.dentry.as_ref().map(|dentry| dentry.inode())
+    }
+
+    /// Returns the dentry of the file that backs the mapping.
+    pub fn dentry(&self) -> Option<&Dentry> {
+        self.dentry.as_ref()
+    }
+
+    /// Returns the kind of the mapping if it is a special mapping.
+    pub fn special(&self) -> Option<SpecialMapping> {
+        self.special
+    }
+
+    /// Returns whether the mapping is shared.
+    pub fn is_shared(&self) -> bool {
+        self.is_shared
+    }
+
+    /// Returns the offset in the VMO where the mapping starts.
+    ///
+    /// Returns `None` if the mapping is not VMO-backed.
+    pub fn vmo_offset(&self) -> Option<usize> {
+        self.vmo.as_ref().map(|vmo| vmo.range.start)
     }
 
     /// Returns the mapping's RSS type.
@@ -416,14 +458,14 @@ impl VmMapping {
             map_to_addr: self.map_to_addr,
             map_size: NonZeroUsize::new(left_size).unwrap(),
             vmo: l_vmo,
-            inode: self.inode.clone(),
+            dentry: self.dentry.clone(),
             ..self
         };
         let right = Self {
             map_to_addr: at,
             map_size: NonZeroUsize::new(right_size).unwrap(),
             vmo: r_vmo,
-            inode: self.inode,
+            dentry: self.dentry,
             ..self
         };
 
@@ -505,6 +547,134 @@ impl VmMapping {
 
         Self { perms, ..self }
     }
+
+    /// Changes how the pages of the mapping are locked in memory.
+    ///
+    /// The pages are not faulted in by this method. The caller should do it
+    /// if needed.
+    pub(super) fn set_mlock(self, mlock: Option<MlockMode>) -> Self {
+        Self { mlock, ..self }
+    }
+}
+
+/****************************** Page queries *********************************/
+
+impl VmMapping {
+    /// Calls `f` on each mapped page within `range` of the mapping.
+    ///
+    /// The range must be page-aligned and within the mapping. The callback is
+    /// called with preemption disabled, so it must not sleep.
+    pub fn for_each_mapped_page<F>(&self, vm_space: &VmSpace, range: Range<Vaddr>, mut f: F)
+    where
+        F: FnMut(Vaddr, MappedPageInfo),
+    {
+        debug_assert!(self.map_to_addr <= range.start && range.end <= self.map_end());
+        debug_assert!(range.start % PAGE_SIZE == 0 && range.end % PAGE_SIZE == 0);
+
+        let preempt_guard = disable_preempt();
+        let Ok(mut cursor) = vm_space.cursor(&preempt_guard, &range) else {
+            return;
+        };
+
+        while cursor.virt_addr() < range.end {
+            let Some(mapped_va) = cursor.find_next(range.end - cursor.virt_addr()) else {
+                break;
+            };
+            let (va, Some((frame, prop))) = cursor.query().unwrap() else {
+                panic!("Found mapped page but query failed");
+            };
+            debug_assert_eq!(mapped_va, va.start);
+
+            f(va.start, self.mapped_page_info(&frame, prop.flags));
+
+            if va.end >= range.end {
+                break;
+            }
+            cursor.jump(va.end).unwrap();
+        }
+    }
+
+    fn mapped_page_info(&self, frame: &UFrame, flags: PageFlags) -> MappedPageInfo {
+        // Pages of the VMO are mapped as read-only in private mappings, so a
+        // writable page in a private mapping must be a copy made on write.
+        let is_vmo_page = self.vmo.is_some() && (self.is_shared || !flags.contains(PageFlags::W));
+
+        // The reference count of a frame consists of the mappings, the frame
+        // handle that we are holding, and the one held by the VMO if the frame
+        // is in the VMO.
+        let map_count = frame
+            .reference_count()
+            .saturating_sub(1 + is_vmo_page as u64)
+            .max(1) as usize;
+
+        let is_anon = !is_vmo_page
+            || (!self.is_shared
+                && self.dentry.is_none()
+                && !matches!(
+                    self.special,
+                    Some(SpecialMapping::Vvar | SpecialMapping::Vdso)
+                ));
+
+        MappedPageInfo {
+            paddr: frame.start_paddr(),
+            flags,
+            map_count,
+            is_anon,
+        }
+    }
+}
+
+/// The information of a page mapped by a [`VmMapping`].
+#[derive(Debug, Clone, Copy)]
+pub struct MappedPageInfo {
+    /// The physical address of the page.
+    pub paddr: Paddr,
+    /// The flags of the page table entry.
+    pub flags: PageFlags,
+    /// The number of mappings that map the page.
+    ///
+    /// This is an estimate derived from the reference count of the frame.
+    pub map_count: usize,
+    /// Whether the page is an anonymous page, i.e., a page that belongs
+    /// neither to a file nor to shared memory.
+    pub is_anon: bool,
+}
+
+/// The ways in which the pages of a [`VmMapping`] are locked in memory.
+///
+/// The locked pages stay resident in memory: they are never reclaimed or
+/// swapped out until they are unlocked or unmapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MlockMode {
+    /// All the pages are faulted in when they are locked.
+    Populate,
+    /// The pages are locked when they are faulted in on demand.
+    OnFault,
+}
+
+/// The kinds of the special mappings that are created by the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecialMapping {
+    /// The program break area.
+    Heap,
+    /// The stack of the main thread.
+    Stack,
+    /// The VDSO data.
+    Vvar,
+    /// The VDSO text.
+    Vdso,
+}
+
+impl SpecialMapping {
+    /// Returns the name of the mapping as shown in `/proc/[pid]/maps`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Heap => "[heap]",
+            Self::Stack => "[stack]",
+            Self::Vvar => "[vvar]",
+            Self::Vdso => "[vdso]",
+        }
+    }
 }
 
 /// A wrapper that represents a mapped [`Vmo`] and provide required functionalities
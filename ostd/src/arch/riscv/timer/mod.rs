@@ -9,9 +9,8 @@ use core::{
 
 use crate::{
     arch::{self, boot::DEVICE_TREE},
-    cpu::{CpuId, IsaExtensions, PinCurrentCpu},
-    timer::INTERRUPT_CALLBACKS,
-    trap,
+    cpu::IsaExtensions,
+    timer::tick,
 };
 
 /// The timer frequency (Hz). Here we choose 1000Hz since 1000Hz is easier for
@@ -23,7 +22,6 @@ use crate::{
 pub const TIMER_FREQ: u64 = 1000;
 
 static TIMEBASE_FREQ: AtomicU64 = AtomicU64::new(0);
-static TIMER_INTERVAL: AtomicU64 = AtomicU64::new(0);
 
 /// Initializes the timer module.
 ///
@@ -43,10 +41,6 @@ pub(super) unsafe fn init() {
             .timebase_frequency() as u64,
         Ordering::Relaxed,
     );
-    TIMER_INTERVAL.store(
-        TIMEBASE_FREQ.load(Ordering::Relaxed) / TIMER_FREQ,
-        Ordering::Relaxed,
-    );
 
     if is_sstc_enabled() {
         // SAFETY: Mutating the static variable `SET_NEXT_TIMER_FN` is safe here
@@ -56,7 +50,8 @@ pub(super) unsafe fn init() {
             SET_NEXT_TIMER_FN = set_next_timer_sstc;
         }
     }
-    set_next_timer();
+    tick::init();
+    tick::start();
     // SAFETY: Accessing the `sie` CSR to enable the timer interrupt is safe
     // here because this function is only called during timer initialization,
     // and we ensure that only the timer interrupt bit is set without affecting
@@ -67,42 +62,38 @@ pub(super) unsafe fn init() {
 }
 
 pub(super) fn handle_timer_interrupt() {
-    let irq_guard = trap::irq::disable_local();
-    if irq_guard.current_cpu() == CpuId::bsp() {
-        crate::timer::jiffies::ELAPSED.fetch_add(1, Ordering::Relaxed);
-    }
-
-    let callbacks_guard = INTERRUPT_CALLBACKS.get_with(&irq_guard);
-    for callback in callbacks_guard.borrow().iter() {
-        (callback)();
-    }
-    drop(callbacks_guard);
+    tick::handle_timer_interrupt();
+}
 
-    set_next_timer();
+/// Returns whether the timer can be programmed to fire at an arbitrary time.
+///
+/// The timer of RISC-V is always programmed with the time to fire.
+pub(crate) fn is_oneshot_supported() -> bool {
+    true
 }
 
-fn set_next_timer() {
+/// Programs the timer of the current CPU to fire at the time value `deadline`.
+pub(crate) fn set_next_deadline(deadline: u64) {
     // SAFETY: Calling the `SET_NEXT_TIMER_FN` function pointer is safe here
     // because we ensure that it is set to a valid function during the timer
     // initialization, and we never modify it after that.
     unsafe {
-        SET_NEXT_TIMER_FN();
+        SET_NEXT_TIMER_FN(deadline);
     }
 }
 
-static mut SET_NEXT_TIMER_FN: fn() = set_next_timer_sbi;
+static mut SET_NEXT_TIMER_FN: fn(u64) = set_next_timer_sbi;
 
-fn set_next_timer_sbi() {
-    sbi_rt::set_timer(TIMER_INTERVAL.load(Ordering::Relaxed));
+fn set_next_timer_sbi(deadline: u64) {
+    sbi_rt::set_timer(deadline);
 }
 
-fn set_next_timer_sstc() {
+fn set_next_timer_sstc(deadline: u64) {
     // SAFETY: Setting the next timer using the `stimecmp` CSR is safe here
-    // because we are using the `stimecmp` CSR to set the next timer interrupt
-    // only when we're handling a timer interrupt, which is a standard operation
-    // specified by RISC-V SSTC extension.
+    // because it only affects when the next timer interrupt occurs, which is
+    // a standard operation specified by RISC-V SSTC extension.
     unsafe {
-        asm!("csrrw {}, stimecmp, {}", out(reg) _, in(reg) get_next_when());
+        asm!("csrrw {}, stimecmp, {}", out(reg) _, in(reg) deadline);
     }
 }
 
@@ -110,12 +101,6 @@ fn is_sstc_enabled() -> bool {
     arch::cpu::has_extensions(IsaExtensions::SSTC)
 }
 
-fn get_next_when() -> u64 {
-    let current = riscv::register::time::read64();
-    let interval = TIMER_INTERVAL.load(Ordering::Relaxed);
-    current + interval
-}
-
 pub(crate) fn get_timebase_freq() -> u64 {
     TIMEBASE_FREQ.load(Ordering::Relaxed)
 }
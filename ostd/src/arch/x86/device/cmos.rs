@@ -10,7 +10,7 @@
 #![expect(unused_variables)]
 
 use acpi::fadt::Fadt;
-use x86_64::instructions::port::{ReadOnlyAccess, WriteOnlyAccess};
+use x86_64::instructions::port::{ReadWriteAccess, WriteOnlyAccess};
 
 use crate::{
     arch::kernel::acpi::get_acpi_tables,
@@ -21,7 +21,7 @@ sensitive_io_port!(unsafe {
     /// CMOS address I/O port
     pub static CMOS_ADDRESS: IoPort<u8, WriteOnlyAccess> = IoPort::new(0x70);
     /// CMOS data I/O port
-    pub static CMOS_DATA: IoPort<u8, ReadOnlyAccess> = IoPort::new(0x71);
+    pub static CMOS_DATA: IoPort<u8, ReadWriteAccess> = IoPort::new(0x71);
 });
 
 /// Gets the century register location. This function is used in RTC(Real Time Clock) module initialization.
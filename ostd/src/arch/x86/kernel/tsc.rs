@@ -19,8 +19,9 @@ use crate::{
 pub(in crate::arch) static TSC_FREQ: AtomicU64 = AtomicU64::new(0);
 
 pub fn init_tsc_freq() {
-    let tsc_freq =
-        determine_tsc_freq_via_cpuid().map_or_else(determine_tsc_freq_via_pit, |freq| freq);
+    let tsc_freq = determine_tsc_freq_via_cpuid()
+        .or_else(crate::arch::kvmclock::tsc_freq)
+        .unwrap_or_else(determine_tsc_freq_via_pit);
     TSC_FREQ.store(tsc_freq, Ordering::Relaxed);
     info!("TSC frequency:{:?} Hz", tsc_freq);
 }
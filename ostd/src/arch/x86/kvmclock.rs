@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The KVM paravirtual clock (kvmclock).
+//!
+//! With kvmclock, the hypervisor shares a `pvclock_vcpu_time_info` structure with each vCPU.
+//! The structure contains the parameters that convert the TSC of the vCPU to the number of
+//! nanoseconds elapsed since an arbitrary point in the past. The hypervisor updates the
+//! parameters whenever they change (e.g., after the vCPU is migrated to another physical CPU),
+//! so the clock does not need to be calibrated in the guest.
+//!
+//! Reference: <https://docs.kernel.org/virt/kvm/x86/msr.html>.
+
+use core::{
+    ptr::{addr_of, read_volatile},
+    sync::atomic::{fence, AtomicU64, Ordering},
+};
+
+use log::{info, warn};
+use spin::Once;
+use x86::{cpuid::cpuid, msr::wrmsr};
+
+use super::read_tsc;
+use crate::{
+    cpu::{num_cpus, CpuId, PinCurrentCpu},
+    mm::{paddr_to_vaddr, Frame, FrameAllocOptions, Segment, PAGE_SIZE},
+    task::disable_preempt,
+};
+
+const KVM_CPUID_SIGNATURE: u32 = 0x4000_0000;
+const KVM_CPUID_FEATURES: u32 = 0x4000_0001;
+/// The signature in EBX, ECX, and EDX of the [`KVM_CPUID_SIGNATURE`] leaf, i.e., "KVMKVMKVM\0\0\0".
+const KVM_SIGNATURE: [u32; 3] = [0x4b4d_564b, 0x564b_4d56, 0x0000_004d];
+
+const KVM_FEATURE_CLOCKSOURCE: u32 = 1 << 0;
+const KVM_FEATURE_CLOCKSOURCE2: u32 = 1 << 3;
+const KVM_FEATURE_CLOCKSOURCE_STABLE_BIT: u32 = 1 << 24;
+
+const MSR_KVM_SYSTEM_TIME: u32 = 0x12;
+const MSR_KVM_SYSTEM_TIME_NEW: u32 = 0x4b56_4d01;
+
+/// The flag indicating that the clocks of all the vCPUs are synchronized.
+const PVCLOCK_TSC_STABLE_BIT: u8 = 1 << 0;
+
+/// The size reserved for the `pvclock_vcpu_time_info` of each CPU.
+///
+/// Each structure is placed in its own cache line, like Linux does.
+const PVTI_SIZE: usize = 64;
+
+/// The `pvclock_vcpu_time_info` structure.
+///
+/// Reference: <https://elixir.bootlin.com/linux/v6.13/source/arch/x86/include/asm/pvclock-abi.h#L25>.
+#[repr(C)]
+struct PvclockVcpuTimeInfo {
+    version: u32,
+    _pad0: u32,
+    tsc_timestamp: u64,
+    system_time: u64,
+    tsc_to_system_mul: u32,
+    tsc_shift: i8,
+    flags: u8,
+    _pad: [u8; 2],
+}
+
+struct KvmClock {
+    /// The memory area that contains the `pvclock_vcpu_time_info` structures.
+    ///
+    /// The structure of the CPU `i` is at the offset `i * PVTI_SIZE`.
+    pvti_area: Segment<()>,
+    /// The MSR to register the `pvclock_vcpu_time_info` structure.
+    system_time_msr: u32,
+    /// Whether the hypervisor reports that the clocks of all the vCPUs are synchronized.
+    is_stable_bit_supported: bool,
+}
+
+static KVMCLOCK: Once<KvmClock> = Once::new();
+
+/// The maximum value that has been read, which keeps the clock monotonic across CPUs if the
+/// clocks of the vCPUs are not synchronized.
+static LAST_VALUE: AtomicU64 = AtomicU64::new(0);
+
+/// Initializes kvmclock on the bootstrapping processor if it is supported.
+///
+/// This should be called before the application processors are booted.
+pub(super) fn init_on_bsp() {
+    let Some(features) = kvm_features() else {
+        return;
+    };
+
+    let system_time_msr = if features & KVM_FEATURE_CLOCKSOURCE2 != 0 {
+        MSR_KVM_SYSTEM_TIME_NEW
+    } else if features & KVM_FEATURE_CLOCKSOURCE != 0 {
+        MSR_KVM_SYSTEM_TIME
+    } else {
+        return;
+    };
+
+    let nframes = (num_cpus() * PVTI_SIZE).div_ceil(PAGE_SIZE);
+    let pvti_area = match FrameAllocOptions::new().alloc_segment(nframes) {
+        Ok(pvti_area) => pvti_area,
+        Err(err) => {
+            warn!("Failed to allocate memory for kvmclock: {:?}", err);
+            return;
+        }
+    };
+
+    let kvmclock = KVMCLOCK.call_once(|| KvmClock {
+        pvti_area,
+        system_time_msr,
+        is_stable_bit_supported: features & KVM_FEATURE_CLOCKSOURCE_STABLE_BIT != 0,
+    });
+    kvmclock.register(CpuId::bsp());
+
+    info!(
+        "kvmclock enabled, stable: {}",
+        kvmclock.is_stable_bit_supported
+    );
+}
+
+/// Initializes kvmclock on the current application processor if it is supported.
+pub(super) fn init_on_ap() {
+    if let Some(kvmclock) = KVMCLOCK.get() {
+        kvmclock.register(CpuId::current_racy());
+    }
+}
+
+/// Returns the features in the KVM CPUID leaf, or `None` if we are not running on KVM.
+fn kvm_features() -> Option<u32> {
+    // The hypervisor CPUID leaves are valid only if the hypervisor bit is set.
+    const HYPERVISOR_BIT: u32 = 1 << 31;
+    if cpuid!(1).ecx & HYPERVISOR_BIT == 0 {
+        return None;
+    }
+
+    let signature = cpuid!(KVM_CPUID_SIGNATURE);
+    if [signature.ebx, signature.ecx, signature.edx] != KVM_SIGNATURE {
+        return None;
+    }
+    // Old hypervisors may report zero as the maximum leaf.
+    if signature.eax != 0 && signature.eax < KVM_CPUID_FEATURES {
+        return None;
+    }
+
+    Some(cpuid!(KVM_CPUID_FEATURES).eax)
+}
+
+impl KvmClock {
+    fn register(&self, cpu: CpuId) {
+        let paddr = self.pvti_area.start_paddr() + cpu.as_usize() * PVTI_SIZE;
+        // SAFETY: The MSR is supported as reported by the CPUID leaf. The memory is owned by
+        // `self.pvti_area`, which lives forever and is not accessed as other objects.
+        unsafe { wrmsr(self.system_time_msr, paddr as u64 | 1) };
+    }
+
+    /// Reads the `pvclock_vcpu_time_info` structure of the CPU, together with the TSC.
+    fn read_pvti(&self, cpu: CpuId) -> PvtiSnapshot {
+        let paddr = self.pvti_area.start_paddr() + cpu.as_usize() * PVTI_SIZE;
+        let pvti = paddr_to_vaddr(paddr) as *const PvclockVcpuTimeInfo;
+
+        // SAFETY (for the reads below): The pointer points to a valid `pvclock_vcpu_time_info`
+        // structure in `self.pvti_area`, which is only written by the hypervisor. The fields are
+        // read with volatile accesses, and the version protocol detects concurrent updates.
+        loop {
+            // SAFETY: See above.
+            let version = unsafe { read_volatile(addr_of!((*pvti).version)) };
+            fence(Ordering::Acquire);
+
+            // SAFETY: See above.
+            let mut snapshot = unsafe {
+                PvtiSnapshot {
+                    tsc_timestamp: read_volatile(addr_of!((*pvti).tsc_timestamp)),
+                    system_time: read_volatile(addr_of!((*pvti).system_time)),
+                    tsc_to_system_mul: read_volatile(addr_of!((*pvti).tsc_to_system_mul)),
+                    tsc_shift: read_volatile(addr_of!((*pvti).tsc_shift)),
+                    flags: read_volatile(addr_of!((*pvti).flags)),
+                    tsc: 0,
+                }
+            };
+            // SAFETY: `lfence` only orders the instructions, so that the TSC is not read before
+            // the parameters.
+            unsafe { core::arch::asm!("lfence", options(nostack, preserves_flags)) };
+            snapshot.tsc = read_tsc();
+
+            fence(Ordering::Acquire);
+            // SAFETY: See above.
+            let new_version = unsafe { read_volatile(addr_of!((*pvti).version)) };
+            // An odd version means that the hypervisor is updating the structure.
+            if version % 2 == 0 && version == new_version {
+                return snapshot;
+            }
+
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// A consistent snapshot of a `pvclock_vcpu_time_info` structure and the TSC.
+struct PvtiSnapshot {
+    tsc_timestamp: u64,
+    system_time: u64,
+    tsc_to_system_mul: u32,
+    tsc_shift: i8,
+    flags: u8,
+    tsc: u64,
+}
+
+impl PvtiSnapshot {
+    /// Returns the time in nanoseconds at the TSC.
+    fn nanos(&self) -> u64 {
+        let mut delta = self.tsc.wrapping_sub(self.tsc_timestamp);
+        if self.tsc_shift >= 0 {
+            delta <<= self.tsc_shift;
+        } else {
+            delta >>= -self.tsc_shift;
+        }
+
+        self.system_time
+            .wrapping_add(((delta as u128 * self.tsc_to_system_mul as u128) >> 32) as u64)
+    }
+
+    /// Returns the frequency of the TSC in Hz.
+    fn tsc_freq(&self) -> u64 {
+        // This is the inverse of the conversion in `Self::nanos`.
+        let mut freq = (1_000_000_000u128 << 32) / self.tsc_to_system_mul as u128;
+        if self.tsc_shift >= 0 {
+            freq >>= self.tsc_shift;
+        } else {
+            freq <<= -self.tsc_shift;
+        }
+
+        freq as u64
+    }
+
+    fn is_stable(&self) -> bool {
+        self.flags & PVCLOCK_TSC_STABLE_BIT != 0
+    }
+}
+
+/// Returns whether kvmclock is available.
+pub fn is_available() -> bool {
+    KVMCLOCK.get().is_some()
+}
+
+/// Returns whether the clocks of all the vCPUs are synchronized.
+///
+/// If they are, kvmclock can be read by user space using the `pvclock_vcpu_time_info`
+/// structure in [`vdso_frame`] without switching to the kernel.
+pub fn is_stable() -> bool {
+    let Some(kvmclock) = KVMCLOCK.get() else {
+        return false;
+    };
+    if !kvmclock.is_stable_bit_supported {
+        return false;
+    }
+
+    kvmclock.read_pvti(CpuId::bsp()).is_stable()
+}
+
+/// Returns the frequency of the TSC in Hz reported by the hypervisor, or `None` if kvmclock is
+/// not available.
+pub(super) fn tsc_freq() -> Option<u64> {
+    let kvmclock = KVMCLOCK.get()?;
+    let snapshot = kvmclock.read_pvti(CpuId::bsp());
+    (snapshot.tsc_to_system_mul != 0).then(|| snapshot.tsc_freq())
+}
+
+/// Returns the frame that contains the `pvclock_vcpu_time_info` structure of the bootstrapping
+/// processor at its beginning.
+///
+/// This is the layout that the vDSO of Linux expects for its `pvclock_page`.
+pub fn vdso_frame() -> Option<Frame<()>> {
+    KVMCLOCK
+        .get()
+        .map(|kvmclock| kvmclock.pvti_area.clone().next().unwrap())
+}
+
+/// Reads kvmclock, which returns the number of nanoseconds since an arbitrary point in the past.
+///
+/// # Panics
+///
+/// This function will panic if kvmclock is not available.
+pub fn read() -> u64 {
+    let kvmclock = KVMCLOCK.get().expect("kvmclock is not available");
+
+    let preempt_guard = disable_preempt();
+    let snapshot = kvmclock.read_pvti(preempt_guard.current_cpu());
+    drop(preempt_guard);
+
+    let nanos = snapshot.nanos();
+    if kvmclock.is_stable_bit_supported && snapshot.is_stable() {
+        return nanos;
+    }
+
+    // The clocks of the vCPUs may be slightly different, so a later read on another CPU may
+    // return an earlier time. Never go backwards.
+    let last = LAST_VALUE.fetch_max(nanos, Ordering::Relaxed);
+    last.max(nanos)
+}
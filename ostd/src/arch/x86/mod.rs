@@ -10,6 +10,7 @@ pub(crate) mod io;
 pub(crate) mod iommu;
 pub(crate) mod irq;
 pub mod kernel;
+pub mod kvmclock;
 pub(crate) mod mm;
 pub(crate) mod pci;
 pub mod qemu;
@@ -69,6 +70,12 @@ pub(crate) unsafe fn late_init_on_bsp() {
     kernel::apic::init(&io_mem_builder).expect("APIC doesn't exist");
     kernel::irq::init(&io_mem_builder);
 
+    // kvmclock is not usable in TDX guests, since the hypervisor cannot access private memory.
+    if_tdx_enabled!({
+    } else {
+        kvmclock::init_on_bsp();
+    });
+
     kernel::tsc::init_tsc_freq();
     timer::init_bsp();
 
@@ -98,6 +105,7 @@ pub(crate) unsafe fn late_init_on_bsp() {
 /// And it should be called after the BSP's call to [`init_on_bsp`].
 pub(crate) unsafe fn init_on_ap() {
     timer::init_ap();
+    kvmclock::init_on_ap();
 }
 
 pub(crate) fn interrupts_ack(irq_number: usize) {
@@ -1,5 +1,12 @@
 // SPDX-License-Identifier: MPL-2.0
 
+//! The APIC timer.
+//!
+//! The timer is programmed in the one-shot manner. If the TSC-deadline mode is supported, the
+//! timer fires when the TSC reaches the programmed deadline. Otherwise, the timer counts down
+//! from an initial count, which is converted from the deadline with the frequency calibrated by
+//! the PIT.
+
 use core::sync::atomic::{AtomicU64, Ordering};
 
 use log::info;
@@ -7,7 +14,8 @@ use log::info;
 use super::TIMER_FREQ;
 use crate::{
     arch::{
-        kernel::apic::{self, Apic, DivideConfig},
+        kernel::apic::{self, DivideConfig},
+        read_tsc,
         timer::pit::OperatingMode,
         tsc_freq,
     },
@@ -15,14 +23,19 @@ use crate::{
     trap::{irq::IrqLine, TrapFrame},
 };
 
-/// Initializes APIC with TSC-deadline mode or periodic mode.
+/// The LVT timer mode bit that enables the TSC-deadline mode.
+///
+/// If neither this bit nor the periodic mode bit is set, the timer is in the one-shot mode.
+const LVT_TIMER_TSC_DEADLINE: u64 = 1 << 18;
+
+/// Initializes APIC with TSC-deadline mode or one-shot mode.
 ///
 /// Return the corresponding [`IrqLine`] for the system timer.
 pub(super) fn init_bsp() -> IrqLine {
     if is_tsc_deadline_mode_supported() {
         init_deadline_mode_config();
     } else {
-        init_periodic_mode_config();
+        init_oneshot_mode_config();
     }
 
     let timer_irq = IrqLine::alloc().unwrap();
@@ -37,17 +50,27 @@ pub(super) fn init_ap(timer_irq: &IrqLine) {
     init_timer(timer_irq);
 }
 
-/// A callback that needs to be called on timer interrupt.
-pub(super) fn timer_callback() {
+/// Programs the APIC timer of the current CPU to fire at the TSC value `deadline`.
+pub(super) fn set_next_deadline(deadline: u64) {
     use x86::msr::{wrmsr, IA32_TSC_DEADLINE};
 
     match CONFIG.get().expect("ACPI timer config is not initialized") {
-        Config::DeadlineMode { tsc_interval } => {
-            let tsc_value = crate::arch::read_tsc();
-            let next_tsc_value = tsc_interval + tsc_value;
-            unsafe { wrmsr(IA32_TSC_DEADLINE, next_tsc_value) };
+        Config::DeadlineMode => {
+            // Writing zero disarms the timer, so the earliest deadline is one.
+            // SAFETY: Writing the deadline only affects when the next timer interrupt occurs.
+            unsafe { wrmsr(IA32_TSC_DEADLINE, deadline.max(1)) };
+        }
+        Config::OneshotMode { init_count } => {
+            let tsc_interval = tsc_freq() / TIMER_FREQ;
+            let tsc_delta = deadline.saturating_sub(read_tsc());
+            let count = (tsc_delta as u128 * *init_count as u128 / tsc_interval as u128)
+                .clamp(1, u32::MAX as u128);
+
+            let preempt_guard = disable_preempt();
+            let apic = apic::get_or_init(&preempt_guard as _);
+            // Writing the initial count restarts the countdown.
+            apic.set_timer_init_count(count as u64);
         }
-        Config::PeriodicMode { .. } => {}
     }
 }
 
@@ -65,45 +88,34 @@ fn init_timer(timer_irq: &IrqLine) {
     let apic = apic::get_or_init(&preempt_guard as _);
 
     match CONFIG.get().expect("ACPI timer config is not initialized") {
-        Config::DeadlineMode { .. } => {
-            init_deadline_mode(apic, timer_irq);
+        Config::DeadlineMode => {
+            apic.set_lvt_timer(timer_irq.num() as u64 | LVT_TIMER_TSC_DEADLINE);
         }
-        Config::PeriodicMode { init_count } => {
-            init_periodic_mode(apic, timer_irq, *init_count);
+        Config::OneshotMode { .. } => {
+            apic.set_timer_div_config(DivideConfig::Divide64);
+            apic.set_lvt_timer(timer_irq.num() as u64);
         }
     }
 }
 
-fn init_deadline_mode(apic: &dyn Apic, timer_irq: &IrqLine) {
-    // Enable TSC deadline mode
-    apic.set_lvt_timer(timer_irq.num() as u64 | (1 << 18));
-
-    timer_callback();
-}
-
-fn init_periodic_mode(apic: &dyn Apic, timer_irq: &IrqLine, init_count: u64) {
-    // Enable periodic mode
-    apic.set_timer_init_count(init_count);
-    apic.set_lvt_timer(timer_irq.num() as u64 | (1 << 17));
-    apic.set_timer_div_config(DivideConfig::Divide64);
-}
-
 static CONFIG: spin::Once<Config> = spin::Once::new();
 
 enum Config {
-    DeadlineMode { tsc_interval: u64 },
-    PeriodicMode { init_count: u64 },
+    DeadlineMode,
+    /// The one-shot mode, where `init_count` is the count of the APIC timer in a tick.
+    OneshotMode {
+        init_count: u64,
+    },
 }
 
 fn init_deadline_mode_config() {
     info!("[Timer]: Enable APIC TSC deadline mode");
 
-    let tsc_interval = tsc_freq() / TIMER_FREQ;
-    CONFIG.call_once(|| Config::DeadlineMode { tsc_interval });
+    CONFIG.call_once(|| Config::DeadlineMode);
 }
 
-fn init_periodic_mode_config() {
-    info!("[Timer]: Enable APIC periodic mode");
+fn init_oneshot_mode_config() {
+    info!("[Timer]: Enable APIC one-shot mode");
 
     // Allocate IRQ
     let mut irq = IrqLine::alloc().unwrap();
@@ -165,7 +177,7 @@ fn init_periodic_mode_config() {
             "APIC timer: first {:#x}, current {:#x}, init {:#x}",
             apic_first_count, apic_current_count, apic_init_count,
         );
-        CONFIG.call_once(|| Config::PeriodicMode {
+        CONFIG.call_once(|| Config::OneshotMode {
             init_count: apic_init_count,
         });
     }
@@ -6,15 +6,12 @@ mod apic;
 mod hpet;
 pub(crate) mod pit;
 
-use core::sync::atomic::Ordering;
-
 use spin::Once;
 
 use crate::{
     arch::kernel,
-    cpu::{CpuId, PinCurrentCpu},
-    timer::INTERRUPT_CALLBACKS,
-    trap::{self, irq::IrqLine, TrapFrame},
+    timer::tick,
+    trap::{irq::IrqLine, TrapFrame},
 };
 
 /// The timer frequency (Hz).
@@ -35,6 +32,8 @@ static TIMER_IRQ: Once<IrqLine> = Once::new();
 
 /// Initializes the timer state and enable timer interrupts on BSP.
 pub(super) fn init_bsp() {
+    tick::init();
+
     let mut timer_irq = if kernel::apic::exists() {
         apic::init_bsp()
     } else {
@@ -51,26 +50,33 @@ pub(super) fn init_bsp() {
 
     timer_irq.on_active(timer_callback);
     TIMER_IRQ.call_once(|| timer_irq);
+
+    tick::start();
 }
 
 /// Enables timer interrupt on this AP.
 pub(super) fn init_ap() {
     if kernel::apic::exists() {
         apic::init_ap(TIMER_IRQ.get().unwrap());
+        tick::start();
     }
 }
 
-fn timer_callback(_: &TrapFrame) {
-    let irq_guard = trap::irq::disable_local();
-    if irq_guard.current_cpu() == CpuId::bsp() {
-        crate::timer::jiffies::ELAPSED.fetch_add(1, Ordering::SeqCst);
-    }
+/// Returns whether the timer can be programmed to fire at an arbitrary time.
+///
+/// This is true if the APIC timer is used. The PIT is only used in the periodic mode, and the
+/// HPET is not used as a per-CPU timer.
+pub(crate) fn is_oneshot_supported() -> bool {
+    kernel::apic::exists()
+}
 
-    let callbacks_guard = INTERRUPT_CALLBACKS.get_with(&irq_guard);
-    for callback in callbacks_guard.borrow().iter() {
-        (callback)();
-    }
-    drop(callbacks_guard);
+/// Programs the timer of the current CPU to fire at the TSC value `deadline`.
+///
+/// This should be called only if [`is_oneshot_supported`] returns true.
+pub(crate) fn set_next_deadline(deadline: u64) {
+    apic::set_next_deadline(deadline);
+}
 
-    apic::timer_callback();
+fn timer_callback(_: &TrapFrame) {
+    tick::handle_timer_interrupt();
 }
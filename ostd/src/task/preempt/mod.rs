@@ -7,6 +7,10 @@ pub use self::guard::{disable_preempt, DisabledPreemptGuard};
 
 /// Halts the CPU until interrupts if no preemption is required.
 ///
+/// The tick of the CPU is stopped while the CPU is halted, so the CPU will not
+/// be woken up in every tick. The timer still wakes up the CPU for the
+/// requested timer events (see [`crate::timer::set_next_event`]).
+///
 /// This function will return if:
 ///  - preemption is required when calling this function,
 ///  - preemption is required during halting the CPU, or
@@ -28,9 +32,11 @@ pub fn halt_cpu() {
     if cpu_local::need_preempt() {
         drop(irq_guard);
     } else {
+        crate::timer::tick::stop_idle_tick(&irq_guard);
         core::mem::forget(irq_guard);
         // IRQs were previously enabled (checked by `might_sleep`). So we can re-enable them now.
         crate::arch::irq::enable_local_and_halt();
+        crate::timer::tick::restart_idle_tick();
     }
 
     super::scheduler::might_preempt();
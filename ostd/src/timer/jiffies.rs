@@ -5,16 +5,24 @@ use core::{
     time::Duration,
 };
 
-use crate::arch::timer::TIMER_FREQ;
+use super::tick;
+use crate::arch::{read_tsc, timer::TIMER_FREQ};
 
 /// Jiffies is a term used to denote the units of time measurement by the kernel.
 ///
 /// A jiffy represents one tick of the system timer interrupt,
 /// whose frequency is equal to [`TIMER_FREQ`] Hz.
+///
+/// The jiffies are derived from the time elapsed since the timer is initialized, instead of
+/// being counted in the timer interrupts. So they keep increasing even if the ticks are
+/// stopped on idle CPUs.
 #[derive(Copy, Clone, Debug)]
 pub struct Jiffies(u64);
 
-pub(crate) static ELAPSED: AtomicU64 = AtomicU64::new(0);
+/// The largest number of jiffies that has been observed.
+///
+/// This keeps the jiffies monotonic if the TSCs of the CPUs are slightly out of sync.
+static ELAPSED: AtomicU64 = AtomicU64::new(0);
 
 impl Jiffies {
     /// Creates a new instance.
@@ -24,7 +32,7 @@ impl Jiffies {
 
     /// Returns the elapsed time since the system boots up.
     pub fn elapsed() -> Self {
-        Self::new(ELAPSED.load(Ordering::Relaxed))
+        Self::new(update_elapsed(read_tsc()))
     }
 
     /// Gets the number of jiffies.
@@ -43,3 +51,14 @@ impl From<Jiffies> for Duration {
         value.as_duration()
     }
 }
+
+/// Updates the elapsed jiffies at the TSC value `now` and returns them.
+pub(super) fn update_elapsed(now: u64) -> u64 {
+    let Some(jiffies) = tick::tsc_to_jiffies(now) else {
+        // The timer has not been initialized yet.
+        return ELAPSED.load(Ordering::Relaxed);
+    };
+
+    let last = ELAPSED.fetch_max(jiffies, Ordering::Relaxed);
+    last.max(jiffies)
+}
@@ -3,11 +3,13 @@
 //! The timer support.
 
 pub(crate) mod jiffies;
+pub(crate) mod tick;
 
 use alloc::{boxed::Box, vec::Vec};
 use core::cell::RefCell;
 
 pub use jiffies::Jiffies;
+pub use tick::{nr_interrupts, set_event_callback, set_next_event};
 
 use crate::{cpu_local, trap};
 
@@ -18,6 +20,9 @@ cpu_local! {
 }
 
 /// Register a function that will be executed during the system timer interruption.
+///
+/// The function is executed in every tick of the current CPU. Note that the tick of an idle CPU
+/// is slowed down to once a second, so the function may not be executed in every jiffy.
 pub fn register_callback<F>(func: F)
 where
     F: Fn() + Sync + Send + 'static,
@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: MPL-2.0
+
+//! The management of the timer interrupts on each CPU.
+//!
+//! Each CPU has a tick at [`TIMER_FREQ`] Hz, which drives the callbacks registered by
+//! [`register_callback`]. In addition, a CPU can request a timer event with [`set_next_event`].
+//!
+//! If the timer hardware can be programmed in the one-shot manner, the ticks and the events are
+//! multiplexed on the same timer: the timer is programmed to fire at the next tick or at the next
+//! event, whichever comes first. This allows
+//!  - firing the events at a resolution finer than a tick, and
+//!  - stopping the tick on idle CPUs, so that they are not woken up in every tick.
+//!
+//! Otherwise, the timer fires periodically, and the events are checked in each tick.
+//!
+//! [`register_callback`]: super::register_callback
+
+use alloc::boxed::Box;
+use core::{
+    cell::RefCell,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use spin::Once;
+
+use super::{jiffies, INTERRUPT_CALLBACKS};
+use crate::{
+    arch::{
+        read_tsc,
+        timer::{self, TIMER_FREQ},
+        tsc_freq,
+    },
+    cpu::{CpuId, PinCurrentCpu},
+    cpu_local,
+    trap::{self, irq::DisabledLocalIrqGuard},
+};
+
+/// The maximum number of ticks that an idle CPU can skip.
+///
+/// Some periodic work (e.g., updating the clock source) is still done in the ticks, so the tick
+/// of an idle CPU is slowed down to once a second instead of being stopped forever.
+const MAX_IDLE_TICKS: u64 = TIMER_FREQ;
+
+/// The TSC value when the timer is initialized.
+static START_TSC: AtomicU64 = AtomicU64::new(0);
+/// The number of TSC cycles in a tick.
+static TICK_CYCLES: AtomicU64 = AtomicU64::new(0);
+
+type EventCallback = Box<dyn Fn() + Sync + Send>;
+
+static EVENT_CALLBACK: Once<EventCallback> = Once::new();
+
+/// The timer state of a CPU.
+///
+/// All the times are represented as TSC values.
+struct TickState {
+    /// The time of the next tick.
+    next_tick: u64,
+    /// The time of the next event, or `u64::MAX` if there are no events.
+    next_event: u64,
+    /// Whether the tick is stopped because the CPU is idle.
+    is_tick_stopped: bool,
+}
+
+cpu_local! {
+    static TICK_STATE: RefCell<TickState> = RefCell::new(TickState {
+        next_tick: 0,
+        next_event: u64::MAX,
+        is_tick_stopped: false,
+    });
+    /// The number of timer interrupts handled on a CPU.
+    static NR_INTERRUPTS: AtomicU64 = AtomicU64::new(0);
+}
+
+impl TickState {
+    /// Returns the time at which the next tick should be handled.
+    fn tick_deadline(&self) -> u64 {
+        if self.is_tick_stopped {
+            let idle_cycles = (MAX_IDLE_TICKS - 1) * TICK_CYCLES.load(Ordering::Relaxed);
+            self.next_tick.saturating_add(idle_cycles)
+        } else {
+            self.next_tick
+        }
+    }
+
+    /// Moves the next tick to the first tick boundary after `now`.
+    fn advance_tick(&mut self, now: u64) {
+        let tick_cycles = TICK_CYCLES.load(Ordering::Relaxed);
+        let missed_ticks = now.saturating_sub(self.next_tick) / tick_cycles + 1;
+        self.next_tick += missed_ticks * tick_cycles;
+    }
+
+    /// Programs the timer to fire at the next tick or the next event, whichever comes first.
+    fn program(&self) {
+        if !timer::is_oneshot_supported() {
+            return;
+        }
+
+        timer::set_next_deadline(self.tick_deadline().min(self.next_event));
+    }
+}
+
+/// Initializes the tick parameters.
+///
+/// This should be called on the bootstrapping processor after the TSC frequency is known and
+/// before [`start`] is called on any CPU.
+pub(crate) fn init() {
+    START_TSC.store(read_tsc(), Ordering::Relaxed);
+    TICK_CYCLES.store(tsc_freq() / TIMER_FREQ, Ordering::Relaxed);
+}
+
+/// Starts the tick on the current CPU.
+///
+/// This should be called after the timer of the current CPU is initialized.
+pub(crate) fn start() {
+    let irq_guard = trap::irq::disable_local();
+    let mut state = TICK_STATE.get_with(&irq_guard).borrow_mut();
+
+    state.next_tick = read_tsc() + TICK_CYCLES.load(Ordering::Relaxed);
+    state.program();
+}
+
+/// Converts the TSC value to the jiffies since the timer is initialized.
+///
+/// This method returns `None` if the timer has not been initialized.
+pub(super) fn tsc_to_jiffies(tsc: u64) -> Option<u64> {
+    let tick_cycles = TICK_CYCLES.load(Ordering::Relaxed);
+    if tick_cycles == 0 {
+        return None;
+    }
+
+    Some(tsc.saturating_sub(START_TSC.load(Ordering::Relaxed)) / tick_cycles)
+}
+
+fn duration_to_cycles(duration: Duration) -> u64 {
+    let cycles = duration.as_nanos() * tsc_freq() as u128 / 1_000_000_000;
+    u64::try_from(cycles).unwrap_or(u64::MAX)
+}
+
+/// Handles the timer interrupt on the current CPU.
+///
+/// This should be called by the timer interrupt handler of the architecture.
+pub(crate) fn handle_timer_interrupt() {
+    let irq_guard = trap::irq::disable_local();
+    let now = read_tsc();
+    jiffies::update_elapsed(now);
+
+    NR_INTERRUPTS
+        .get_on_cpu(irq_guard.current_cpu())
+        .fetch_add(1, Ordering::Relaxed);
+
+    let (is_tick_due, is_event_due) = {
+        let mut state = TICK_STATE.get_with(&irq_guard).borrow_mut();
+
+        // Without one-shot programming, each interrupt is a tick.
+        let is_tick_due = !timer::is_oneshot_supported() || now >= state.tick_deadline();
+        if is_tick_due {
+            state.advance_tick(now);
+        }
+
+        let is_event_due = now >= state.next_event;
+        if is_event_due {
+            state.next_event = u64::MAX;
+        }
+
+        (is_tick_due, is_event_due)
+    };
+
+    if is_tick_due {
+        let callbacks_guard = INTERRUPT_CALLBACKS.get_with(&irq_guard);
+        for callback in callbacks_guard.borrow().iter() {
+            (callback)();
+        }
+    }
+
+    if is_event_due {
+        if let Some(callback) = EVENT_CALLBACK.get() {
+            (callback)();
+        }
+    }
+
+    TICK_STATE.get_with(&irq_guard).borrow().program();
+}
+
+/// Returns the number of timer interrupts that have been handled on `cpu`.
+///
+/// Since the tick of an idle CPU is stopped, an idle CPU handles far fewer timer interrupts than
+/// a busy CPU.
+pub fn nr_interrupts(cpu: CpuId) -> u64 {
+    NR_INTERRUPTS.get_on_cpu(cpu).load(Ordering::Relaxed)
+}
+
+/// Requests a timer event on the current CPU after `timeout`.
+///
+/// When the event expires, the callback set by [`set_event_callback`] will be executed in the
+/// timer interrupt of the current CPU. If the timer hardware supports one-shot programming, the
+/// event will be fired without waiting for the next tick, even if the tick is stopped.
+/// Otherwise, it will be fired at the first tick after `timeout`.
+///
+/// Each CPU keeps only the earliest event. So requesting an event has no effect if an earlier
+/// event has already been requested on the current CPU.
+pub fn set_next_event(timeout: Duration) {
+    let irq_guard = trap::irq::disable_local();
+    let deadline = read_tsc().saturating_add(duration_to_cycles(timeout));
+
+    let mut state = TICK_STATE.get_with(&irq_guard).borrow_mut();
+    if deadline >= state.next_event {
+        return;
+    }
+
+    state.next_event = deadline;
+    state.program();
+}
+
+/// Sets the function that will be executed when an event requested by [`set_next_event`]
+/// expires.
+///
+/// The function is shared by all CPUs. It is executed in the timer interrupt of the CPU that
+/// has requested the event.
+///
+/// # Panics
+///
+/// This function panics if the function has already been set.
+pub fn set_event_callback<F>(func: F)
+where
+    F: Fn() + Sync + Send + 'static,
+{
+    assert!(!EVENT_CALLBACK.is_completed());
+    EVENT_CALLBACK.call_once(|| Box::new(func));
+}
+
+/// Stops the tick on the current CPU, which is going to be idle.
+///
+/// The tick will be restarted by [`restart_idle_tick`] when the CPU is no longer idle.
+pub(crate) fn stop_idle_tick(irq_guard: &DisabledLocalIrqGuard) {
+    if !timer::is_oneshot_supported() {
+        return;
+    }
+
+    let mut state = TICK_STATE.get_with(irq_guard).borrow_mut();
+    state.is_tick_stopped = true;
+    state.program();
+}
+
+/// Restarts the tick on the current CPU after it wakes up from the idle state.
+///
+/// If ticks have been skipped, the timer will fire immediately to handle a tick.
+pub(crate) fn restart_idle_tick() {
+    let irq_guard = trap::irq::disable_local();
+
+    let mut state = TICK_STATE.get_with(&irq_guard).borrow_mut();
+    if !state.is_tick_stopped {
+        return;
+    }
+    state.is_tick_stopped = false;
+    state.program();
+}
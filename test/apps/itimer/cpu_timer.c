@@ -0,0 +1,178 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <pthread.h>
+#include <signal.h>
+#include <string.h>
+#include <sys/resource.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+static sigset_t timer_sigs;
+static siginfo_t info;
+
+static const struct timespec zero_timeout = { 0 };
+
+static long cputime_ms(clockid_t clock)
+{
+	struct timespec ts;
+
+	if (clock_gettime(clock, &ts) < 0)
+		return -1;
+	return ts.tv_sec * 1000 + ts.tv_nsec / 1000000;
+}
+
+static void burn_cpu(clockid_t clock, long ms)
+{
+	long start = cputime_ms(clock);
+
+	while (cputime_ms(clock) - start < ms)
+		;
+}
+
+FN_SETUP(block)
+{
+	CHECK(sigemptyset(&timer_sigs));
+	CHECK(sigaddset(&timer_sigs, SIGALRM));
+	CHECK(sigaddset(&timer_sigs, SIGRTMIN));
+	CHECK(sigprocmask(SIG_BLOCK, &timer_sigs, NULL));
+}
+END_SETUP()
+
+FN_TEST(cpu_clock_ids)
+{
+	clockid_t clock;
+	struct timespec ts;
+	pid_t pid;
+	int status;
+
+	TEST_SUCC(clock_getcpuclockid(0, &clock));
+	TEST_SUCC(clock_gettime(clock, &ts));
+	TEST_SUCC(clock_getcpuclockid(getpid(), &clock));
+	TEST_SUCC(clock_gettime(clock, &ts));
+	TEST_SUCC(pthread_getcpuclockid(pthread_self(), &clock));
+	TEST_SUCC(clock_gettime(clock, &ts));
+
+	pid = CHECK(fork());
+	if (pid == 0) {
+		pause();
+		_exit(0);
+	}
+
+	// The CPU clock of another process is readable until it is reaped.
+	TEST_SUCC(clock_getcpuclockid(pid, &clock));
+	TEST_SUCC(clock_gettime(clock, &ts));
+
+	TEST_SUCC(kill(pid, SIGKILL));
+	TEST_RES(waitpid(pid, &status, 0),
+		 _ret == pid && WIFSIGNALED(status) &&
+			 WTERMSIG(status) == SIGKILL);
+	TEST_ERRNO(clock_gettime(clock, &ts), EINVAL);
+}
+END_TEST()
+
+FN_TEST(process_cputime_timer)
+{
+	struct sigevent sev;
+	struct itimerspec its;
+	timer_t timer;
+
+	memset(&sev, 0, sizeof(sev));
+	sev.sigev_notify = SIGEV_THREAD_ID;
+	sev.sigev_signo = SIGRTMIN;
+	sev.sigev_value.sival_int = 42;
+	sev._sigev_un._tid = gettid();
+	TEST_SUCC(timer_create(CLOCK_PROCESS_CPUTIME_ID, &sev, &timer));
+
+	memset(&its, 0, sizeof(its));
+	its.it_value.tv_nsec = 20 * 1000 * 1000;
+	its.it_interval.tv_nsec = 20 * 1000 * 1000;
+	TEST_SUCC(timer_settime(timer, 0, &its, NULL));
+
+	// The signal is blocked, so the expirations after the first one are
+	// counted as overruns.
+	burn_cpu(CLOCK_PROCESS_CPUTIME_ID, 200);
+
+	TEST_RES(sigtimedwait(&timer_sigs, &info, &zero_timeout),
+		 _ret == SIGRTMIN && info.si_code == SI_TIMER &&
+			 info.si_value.sival_int == 42 && info.si_overrun > 0);
+	TEST_RES(timer_getoverrun(timer), _ret == info.si_overrun);
+
+	TEST_SUCC(timer_delete(timer));
+	TEST_ERRNO(timer_getoverrun(timer), EINVAL);
+
+	// Discard the signal that may be sent after the dequeue above.
+	sigtimedwait(&timer_sigs, &info, &zero_timeout);
+}
+END_TEST()
+
+FN_TEST(thread_cputime_timer)
+{
+	struct itimerspec its;
+	timer_t timer;
+
+	TEST_SUCC(timer_create(CLOCK_THREAD_CPUTIME_ID, NULL, &timer));
+
+	memset(&its, 0, sizeof(its));
+	its.it_value.tv_nsec = 50 * 1000 * 1000;
+	TEST_SUCC(timer_settime(timer, 0, &its, NULL));
+
+	burn_cpu(CLOCK_THREAD_CPUTIME_ID, 100);
+
+	TEST_RES(sigtimedwait(&timer_sigs, &info, &zero_timeout),
+		 _ret == SIGALRM && info.si_code == SI_TIMER &&
+			 info.si_overrun == 0);
+	TEST_RES(timer_getoverrun(timer), _ret == 0);
+
+	TEST_SUCC(timer_delete(timer));
+}
+END_TEST()
+
+static int xcpu_pipe[2];
+
+static void handle_xcpu(int sig)
+{
+	char byte = 0;
+
+	(void)sig;
+	(void)!write(xcpu_pipe[1], &byte, 1);
+}
+
+FN_TEST(rlimit_cpu)
+{
+	struct rusage usage;
+	pid_t pid;
+	int status;
+	char byte;
+
+	TEST_SUCC(pipe(xcpu_pipe));
+
+	pid = CHECK(fork());
+	if (pid == 0) {
+		struct rlimit limit = { .rlim_cur = 1, .rlim_max = 2 };
+
+		signal(SIGXCPU, handle_xcpu);
+		CHECK(setrlimit(RLIMIT_CPU, &limit));
+		burn_cpu(CLOCK_PROCESS_CPUTIME_ID, 10 * 1000);
+		_exit(1);
+	}
+	TEST_SUCC(close(xcpu_pipe[1]));
+
+	// `SIGXCPU` is sent at the soft limit, and `SIGKILL` at the hard limit.
+	TEST_RES(wait4(pid, &status, 0, &usage),
+		 _ret == pid && WIFSIGNALED(status) &&
+			 WTERMSIG(status) == SIGKILL &&
+			 usage.ru_utime.tv_sec + usage.ru_stime.tv_sec >= 1);
+	TEST_RES(read(xcpu_pipe[0], &byte, 1), _ret == 1);
+	TEST_SUCC(close(xcpu_pipe[0]));
+
+	// The CPU time of the reaped child is accounted to the parent.
+	TEST_RES(getrusage(RUSAGE_CHILDREN, &usage),
+		 usage.ru_utime.tv_sec + usage.ru_stime.tv_sec >= 1);
+}
+END_TEST()
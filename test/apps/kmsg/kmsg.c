@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/klog.h>
+#include <unistd.h>
+
+#define SYSLOG_ACTION_READ_ALL 3
+#define SYSLOG_ACTION_CLEAR 5
+#define SYSLOG_ACTION_CONSOLE_LEVEL 8
+#define SYSLOG_ACTION_SIZE_BUFFER 10
+
+static int kmsg_fd;
+static char record[2048];
+
+static int write_str(int fd, const char *str)
+{
+	return write(fd, str, strlen(str));
+}
+
+static int parse_prio(const char *rec)
+{
+	int prio;
+	unsigned long long seq, usecs;
+
+	if (sscanf(rec, "%d,%llu,%llu,", &prio, &seq, &usecs) != 3)
+		return -1;
+	return prio;
+}
+
+static const char *record_text(const char *rec)
+{
+	const char *text = strchr(rec, ';');
+
+	return text ? text + 1 : "";
+}
+
+FN_SETUP(open)
+{
+	kmsg_fd = CHECK(open("/dev/kmsg", O_RDWR | O_NONBLOCK));
+}
+END_SETUP()
+
+FN_TEST(write_and_read)
+{
+	const char *msg = "<5>kmsg test: hello\n";
+
+	TEST_RES(lseek(kmsg_fd, 0, SEEK_END), _ret == 0);
+	TEST_ERRNO(read(kmsg_fd, record, sizeof(record)), EAGAIN);
+
+	TEST_RES(write_str(kmsg_fd, msg), _ret == strlen(msg));
+	TEST_RES(read(kmsg_fd, record, sizeof(record)),
+		 _ret > 0 && record[_ret - 1] == '\n' &&
+			 parse_prio(record) == 5 &&
+			 strncmp(record_text(record), "kmsg test: hello\n",
+				 _ret - (record_text(record) - record)) == 0);
+	TEST_ERRNO(read(kmsg_fd, record, sizeof(record)), EAGAIN);
+
+	// Records are never split across reads.
+	TEST_RES(write_str(kmsg_fd, msg), _ret == strlen(msg));
+	TEST_ERRNO(read(kmsg_fd, record, 8), EINVAL);
+	TEST_RES(read(kmsg_fd, record, sizeof(record)),
+		 _ret > 0 && parse_prio(record) == 5);
+}
+END_TEST()
+
+FN_TEST(default_priority_and_escape)
+{
+	TEST_RES(lseek(kmsg_fd, 0, SEEK_END), _ret == 0);
+
+	// Without a prefix, the record has `LOG_USER` and the default level.
+	TEST_RES(write_str(kmsg_fd, "a\\b\x01\n"), _ret == 5);
+	TEST_RES(read(kmsg_fd, record, sizeof(record)),
+		 _ret > 0 && parse_prio(record) == ((1 << 3) | 4) &&
+			 strncmp(record_text(record), "a\\x5cb\\x01\n", 11) ==
+				 0);
+}
+END_TEST()
+
+FN_TEST(seek)
+{
+	TEST_ERRNO(lseek(kmsg_fd, 1, SEEK_SET), ESPIPE);
+	TEST_ERRNO(lseek(kmsg_fd, 0, SEEK_CUR), EINVAL);
+	TEST_RES(lseek(kmsg_fd, 0, SEEK_SET), _ret == 0);
+	TEST_RES(read(kmsg_fd, record, sizeof(record)), _ret > 0);
+
+	// `SEEK_DATA` seeks to the first record after the last clear.
+	TEST_SUCC(klogctl(SYSLOG_ACTION_CLEAR, NULL, 0));
+	TEST_RES(write_str(kmsg_fd, "kmsg test: after clear"), _ret == 22);
+	TEST_RES(lseek(kmsg_fd, 0, SEEK_DATA), _ret == 0);
+	TEST_RES(read(kmsg_fd, record, sizeof(record)),
+		 _ret > 0 && strncmp(record_text(record),
+				     "kmsg test: after clear\n", 23) == 0);
+}
+END_TEST()
+
+FN_TEST(syslog)
+{
+	static char buf[256 * 1024 + 1];
+
+	TEST_ERRNO(klogctl(-1, NULL, 0), EINVAL);
+	TEST_ERRNO(klogctl(SYSLOG_ACTION_READ_ALL, NULL, 10), EINVAL);
+	TEST_RES(klogctl(SYSLOG_ACTION_SIZE_BUFFER, NULL, 0), _ret > 0);
+
+	TEST_RES(write_str(kmsg_fd, "<3>kmsg test: syslog"), _ret == 20);
+	TEST_RES(klogctl(SYSLOG_ACTION_READ_ALL, buf, sizeof(buf) - 1),
+		 _ret > 0 && (buf[_ret] = '\0',
+			      strstr(buf, "<3>[") != NULL &&
+				      strstr(buf, "] kmsg test: syslog\n") !=
+					      NULL));
+
+	TEST_SUCC(klogctl(SYSLOG_ACTION_CLEAR, NULL, 0));
+	TEST_RES(klogctl(SYSLOG_ACTION_READ_ALL, buf, sizeof(buf) - 1),
+		 (buf[_ret] = '\0', strstr(buf, "kmsg test") == NULL));
+}
+END_TEST()
+
+static int read_printk(int levels[4])
+{
+	char buf[64];
+	int fd, len;
+
+	fd = open("/proc/sys/kernel/printk", O_RDONLY);
+	if (fd < 0)
+		return -1;
+	len = read(fd, buf, sizeof(buf) - 1);
+	close(fd);
+	if (len < 0)
+		return -1;
+	buf[len] = '\0';
+
+	if (sscanf(buf, "%d %d %d %d", &levels[0], &levels[1], &levels[2],
+		   &levels[3]) != 4)
+		return -1;
+	return 0;
+}
+
+static int write_printk(const char *str)
+{
+	int fd, len;
+
+	fd = open("/proc/sys/kernel/printk", O_WRONLY);
+	if (fd < 0)
+		return -1;
+	len = write_str(fd, str);
+	close(fd);
+	return len;
+}
+
+FN_TEST(printk)
+{
+	int orig[4], levels[4];
+	char buf[64];
+
+	TEST_SUCC(read_printk(orig));
+
+	TEST_RES(write_printk("3\n"), _ret == 2);
+	TEST_RES(read_printk(levels),
+		 levels[0] == 3 && levels[1] == orig[1] &&
+			 levels[2] == orig[2] && levels[3] == orig[3]);
+
+	TEST_ERRNO(write_printk("a b"), EINVAL);
+	TEST_ERRNO(write_printk("1 2 3 4 5"), EINVAL);
+
+	TEST_SUCC(klogctl(SYSLOG_ACTION_CONSOLE_LEVEL, NULL, 6));
+	TEST_RES(read_printk(levels), levels[0] == 6);
+	TEST_ERRNO(klogctl(SYSLOG_ACTION_CONSOLE_LEVEL, NULL, 9), EINVAL);
+
+	snprintf(buf, sizeof(buf), "%d %d %d %d\n", orig[0], orig[1], orig[2],
+		 orig[3]);
+	TEST_RES(write_printk(buf), _ret == strlen(buf));
+	TEST_RES(read_printk(levels), levels[0] == orig[0]);
+}
+END_TEST()
@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define PAGE_SIZE 4096
+#define NUM_PAGES 16
+#define TOTAL_SIZE (PAGE_SIZE * NUM_PAGES)
+
+// Returns the value of the field in `/proc/self/status` in kB, or -1 on errors.
+static long get_status_kb(const char *field)
+{
+	FILE *f = fopen("/proc/self/status", "r");
+	if (f == NULL)
+		return -1;
+
+	char line[256];
+	long kb = -1;
+	while (fgets(line, sizeof(line), f)) {
+		if (strncmp(line, field, strlen(field)) == 0) {
+			sscanf(line + strlen(field), "%ld", &kb);
+			break;
+		}
+	}
+
+	fclose(f);
+	return kb;
+}
+
+#define VM_LCK_KB() get_status_kb("VmLck:")
+#define RSS_ANON_KB() get_status_kb("RssAnon:")
+
+// Maps `TOTAL_SIZE` bytes of anonymous memory, or returns NULL on errors.
+static char *mmap_anon(int flags)
+{
+	void *addr = mmap(NULL, TOTAL_SIZE, PROT_READ | PROT_WRITE,
+			  MAP_PRIVATE | MAP_ANONYMOUS | flags, -1, 0);
+	return addr == MAP_FAILED ? NULL : addr;
+}
+
+// The buffer is followed by an unmapped page.
+static char *buf;
+
+FN_SETUP(mmap_buf)
+{
+	buf = mmap(NULL, TOTAL_SIZE + PAGE_SIZE, PROT_READ | PROT_WRITE,
+		   MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	CHECK(buf == MAP_FAILED ? -1 : 0);
+	CHECK(munmap(buf + TOTAL_SIZE, PAGE_SIZE));
+}
+END_SETUP()
+
+FN_TEST(mlock)
+{
+	long rss = TEST_SUCC(RSS_ANON_KB());
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+
+	// The unaligned range is extended to the whole pages.
+	TEST_SUCC(mlock(buf + 1, TOTAL_SIZE - PAGE_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == TOTAL_SIZE / 1024);
+
+	// The pages are faulted in.
+	TEST_RES(RSS_ANON_KB(), _ret >= rss + TOTAL_SIZE / 1024);
+
+	// Locking the locked pages again does not change anything.
+	TEST_SUCC(mlock(buf, TOTAL_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == TOTAL_SIZE / 1024);
+
+	TEST_SUCC(munlock(buf + PAGE_SIZE, PAGE_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == (TOTAL_SIZE - PAGE_SIZE) / 1024);
+
+	TEST_SUCC(munlock(buf, TOTAL_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(mlock_onfault)
+{
+	char *addr = mmap_anon(0);
+	TEST_RES(addr == NULL ? -1 : 0, _ret == 0);
+	long rss = TEST_SUCC(RSS_ANON_KB());
+
+	TEST_SUCC(mlock2(addr, TOTAL_SIZE, MLOCK_ONFAULT));
+	TEST_RES(VM_LCK_KB(), _ret == TOTAL_SIZE / 1024);
+
+	// The pages are not faulted in.
+	TEST_RES(RSS_ANON_KB(), _ret < rss + TOTAL_SIZE / 1024);
+
+	TEST_SUCC(munmap(addr, TOTAL_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(mlock_invalid)
+{
+	// The range is not completely mapped.
+	TEST_ERRNO(mlock(buf, TOTAL_SIZE + PAGE_SIZE), ENOMEM);
+	TEST_ERRNO(munlock(buf, TOTAL_SIZE + PAGE_SIZE), ENOMEM);
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+
+	TEST_ERRNO(mlock2(buf, PAGE_SIZE, 2), EINVAL);
+
+	TEST_ERRNO(mlockall(0), EINVAL);
+	TEST_ERRNO(mlockall(MCL_ONFAULT), EINVAL);
+	TEST_ERRNO(mlockall(8), EINVAL);
+}
+END_TEST()
+
+FN_TEST(madvise_locked)
+{
+	TEST_SUCC(mlock(buf, PAGE_SIZE));
+	TEST_ERRNO(madvise(buf, TOTAL_SIZE, MADV_DONTNEED), EINVAL);
+	TEST_SUCC(munlock(buf, PAGE_SIZE));
+}
+END_TEST()
+
+FN_TEST(map_locked)
+{
+	long rss = TEST_SUCC(RSS_ANON_KB());
+
+	char *addr = mmap_anon(MAP_LOCKED);
+	TEST_RES(addr == NULL ? -1 : 0, _ret == 0);
+	TEST_RES(VM_LCK_KB(), _ret == TOTAL_SIZE / 1024);
+	TEST_RES(RSS_ANON_KB(), _ret >= rss + TOTAL_SIZE / 1024);
+
+	// The unmapped part is no longer locked.
+	TEST_SUCC(munmap(addr, PAGE_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == (TOTAL_SIZE - PAGE_SIZE) / 1024);
+
+	TEST_SUCC(munmap(addr + PAGE_SIZE, TOTAL_SIZE - PAGE_SIZE));
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+}
+END_TEST()
+
+FN_TEST(mlockall_future)
+{
+	TEST_SUCC(mlockall(MCL_FUTURE));
+
+	// The existing mappings are not locked.
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+
+	// The new mappings are locked.
+	char *addr = mmap_anon(0);
+	TEST_RES(addr == NULL ? -1 : 0, _ret == 0);
+	TEST_RES(VM_LCK_KB(), _ret == TOTAL_SIZE / 1024);
+
+	TEST_SUCC(munlockall());
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+
+	// The new mappings are no longer locked.
+	char *addr2 = mmap_anon(0);
+	TEST_RES(addr2 == NULL ? -1 : 0, _ret == 0);
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+
+	TEST_SUCC(munmap(addr, TOTAL_SIZE));
+	TEST_SUCC(munmap(addr2, TOTAL_SIZE));
+}
+END_TEST()
+
+FN_TEST(mlockall_current)
+{
+	TEST_SUCC(mlockall(MCL_CURRENT));
+	TEST_RES(VM_LCK_KB(), _ret >= TOTAL_SIZE / 1024);
+
+	TEST_SUCC(munlockall());
+	TEST_RES(VM_LCK_KB(), _ret == 0);
+}
+END_TEST()
+
+// Returns the locked memory size of a child process after forking, or a
+// negative value on errors.
+static int fork_and_get_locked_kb(void)
+{
+	pid_t pid = fork();
+	if (pid < 0)
+		return -1;
+
+	if (pid == 0)
+		_exit(VM_LCK_KB() == 0 ? 0 : 1);
+
+	int status;
+	if (waitpid(pid, &status, 0) != pid || !WIFEXITED(status))
+		return -1;
+	return WEXITSTATUS(status);
+}
+
+FN_TEST(mlock_fork)
+{
+	TEST_SUCC(mlock(buf, TOTAL_SIZE));
+
+	// The memory locks are not inherited by the child process.
+	TEST_RES(fork_and_get_locked_kb(), _ret == 0);
+
+	TEST_SUCC(munlock(buf, TOTAL_SIZE));
+}
+END_TEST()
+
+static int rlimit_memlock_unprivileged(void)
+{
+	struct rlimit rlimit = { .rlim_cur = 2 * PAGE_SIZE,
+				 .rlim_max = 2 * PAGE_SIZE };
+	if (setrlimit(RLIMIT_MEMLOCK, &rlimit) < 0)
+		return 11;
+
+	// The locked memory cannot exceed the limit.
+	if (mlock(buf, 2 * PAGE_SIZE) < 0)
+		return 12;
+	if (mlock(buf + 2 * PAGE_SIZE, PAGE_SIZE) != -1 || errno != ENOMEM)
+		return 13;
+	if (mmap(NULL, PAGE_SIZE, PROT_READ,
+		 MAP_PRIVATE | MAP_ANONYMOUS | MAP_LOCKED, -1,
+		 0) != MAP_FAILED ||
+	    errno != EAGAIN)
+		return 14;
+	if (mlockall(MCL_CURRENT) != -1 || errno != ENOMEM)
+		return 15;
+
+	// Unlocking some pages makes room for other pages.
+	if (munlock(buf, PAGE_SIZE) < 0)
+		return 16;
+	if (mlock(buf + 2 * PAGE_SIZE, PAGE_SIZE) < 0)
+		return 17;
+
+	// No memory can be locked with a zero limit.
+	rlimit.rlim_cur = 0;
+	if (setrlimit(RLIMIT_MEMLOCK, &rlimit) < 0)
+		return 18;
+	if (mlock(buf, PAGE_SIZE) != -1 || errno != EPERM)
+		return 19;
+
+	return 0;
+}
+
+FN_TEST(rlimit_memlock)
+{
+	// Without `CAP_IPC_LOCK`, the locked memory is limited by `RLIMIT_MEMLOCK`.
+	TEST_RES(run_unprivileged(rlimit_memlock_unprivileged), _ret == 0);
+}
+END_TEST()
+
+FN_SETUP(munmap_buf)
+{
+	CHECK(munmap(buf, TOTAL_SIZE));
+}
+END_SETUP()
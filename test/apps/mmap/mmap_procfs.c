@@ -0,0 +1,247 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <fcntl.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#define PAGE_SIZE 4096
+#define NUM_PAGES 4
+#define TOTAL_SIZE (PAGE_SIZE * NUM_PAGES)
+
+#define PM_PRESENT (1ULL << 63)
+
+static char buf[65536];
+
+static ssize_t read_proc_file(const char *path)
+{
+	int fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+
+	ssize_t len = 0;
+	for (;;) {
+		ssize_t n = read(fd, buf + len, sizeof(buf) - 1 - len);
+		if (n < 0) {
+			close(fd);
+			return -1;
+		}
+		if (n == 0)
+			break;
+		len += n;
+	}
+	buf[len] = '\0';
+
+	close(fd);
+	return len;
+}
+
+// Finds the line in `/proc/self/maps` or `/proc/self/smaps` that starts with
+// the address range of the mapping at `addr`.
+static char *find_mapping(void *addr, size_t size)
+{
+	char prefix[64];
+	snprintf(prefix, sizeof(prefix), "%08lx-%08lx ", (unsigned long)addr,
+		 (unsigned long)addr + size);
+
+	char *line = buf;
+	while (line != NULL && *line != '\0') {
+		if (strncmp(line, prefix, strlen(prefix)) == 0)
+			return line;
+		line = strchr(line, '\n');
+		if (line != NULL)
+			line++;
+	}
+	return NULL;
+}
+
+static int maps_has_mapping(void *addr, size_t size, const char *perms,
+			    const char *name)
+{
+	if (read_proc_file("/proc/self/maps") < 0)
+		return -1;
+
+	char *line = find_mapping(addr, size);
+	if (line == NULL)
+		return 0;
+
+	char *end = strchr(line, '\n');
+	*end = '\0';
+
+	if (strncmp(strchr(line, ' ') + 1, perms, strlen(perms)) != 0)
+		return 0;
+	if (name != NULL) {
+		char *pathname = strrchr(line, ' ') + 1;
+		return strcmp(pathname, name) == 0;
+	}
+	return 1;
+}
+
+static int maps_has_name(const char *name)
+{
+	if (read_proc_file("/proc/self/maps") < 0)
+		return -1;
+
+	char *line = strtok(buf, "\n");
+	while (line != NULL) {
+		char *pathname = strrchr(line, ' ') + 1;
+		if (strcmp(pathname, name) == 0)
+			return 1;
+		line = strtok(NULL, "\n");
+	}
+	return 0;
+}
+
+static long smaps_field_kb(const char *path, void *addr, size_t size,
+			   const char *field)
+{
+	if (read_proc_file(path) < 0)
+		return -1;
+
+	char *line = addr != NULL ? find_mapping(addr, size) : buf;
+	if (line == NULL)
+		return -1;
+
+	char *value = strstr(line, field);
+	if (value == NULL)
+		return -1;
+
+	long kb = -1;
+	sscanf(value + strlen(field), "%ld", &kb);
+	return kb;
+}
+
+static int rollup_has_header(void)
+{
+	if (read_proc_file("/proc/self/smaps_rollup") < 0)
+		return -1;
+
+	char *end = strchr(buf, '\n');
+	if (end == NULL)
+		return 0;
+	*end = '\0';
+
+	return strstr(buf, "[rollup]") != NULL;
+}
+
+static uint64_t pagemap_entry(int fd, void *addr)
+{
+	uint64_t entry;
+	off_t offset = (uintptr_t)addr / PAGE_SIZE * sizeof(entry);
+
+	if (pread(fd, &entry, sizeof(entry), offset) != sizeof(entry))
+		return -1;
+	return entry;
+}
+
+static void *mem;
+
+FN_SETUP(mmap)
+{
+	// Surround the mapping with inaccessible pages so that it will not be
+	// merged with other mappings.
+	char *guard = mmap(NULL, TOTAL_SIZE + 2 * PAGE_SIZE, PROT_NONE,
+			   MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	CHECK(guard == MAP_FAILED ? -1 : 0);
+
+	mem = mmap(guard + PAGE_SIZE, TOTAL_SIZE, PROT_READ | PROT_WRITE,
+		   MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED, -1, 0);
+	CHECK(mem == MAP_FAILED ? -1 : 0);
+
+	// Only touch the first half of the pages.
+	for (int i = 0; i < NUM_PAGES / 2; ++i)
+		((volatile char *)mem)[i * PAGE_SIZE] = 1;
+
+	// Make sure that the heap is not empty.
+	CHECK(sbrk(PAGE_SIZE) == (void *)-1 ? -1 : 0);
+}
+END_SETUP()
+
+FN_TEST(maps)
+{
+	TEST_RES(maps_has_mapping(mem, TOTAL_SIZE, "rw-p", NULL), _ret == 1);
+
+	TEST_RES(maps_has_name("[heap]"), _ret == 1);
+	TEST_RES(maps_has_name("[stack]"), _ret == 1);
+	TEST_RES(maps_has_name("[vdso]"), _ret == 1);
+}
+END_TEST()
+
+FN_TEST(maps_file)
+{
+	const char *filename = "/tmp/maps_test_file";
+	int fd = TEST_SUCC(open(filename, O_CREAT | O_RDWR, 0600));
+	TEST_SUCC(ftruncate(fd, PAGE_SIZE));
+
+	void *file_mem = mmap(NULL, PAGE_SIZE, PROT_READ, MAP_SHARED, fd, 0);
+	TEST_SUCC(file_mem == MAP_FAILED ? -1 : 0);
+	TEST_RES(maps_has_mapping(file_mem, PAGE_SIZE, "r--s", filename),
+		 _ret == 1);
+
+	TEST_SUCC(munmap(file_mem, PAGE_SIZE));
+	TEST_RES(maps_has_mapping(file_mem, PAGE_SIZE, "r--s", filename),
+		 _ret == 0);
+
+	TEST_SUCC(close(fd));
+	TEST_SUCC(unlink(filename));
+}
+END_TEST()
+
+FN_TEST(smaps)
+{
+	TEST_RES(smaps_field_kb("/proc/self/smaps", mem, TOTAL_SIZE, "Size:"),
+		 _ret == TOTAL_SIZE / 1024);
+	TEST_RES(smaps_field_kb("/proc/self/smaps", mem, TOTAL_SIZE, "Rss:"),
+		 _ret == TOTAL_SIZE / 2 / 1024);
+	TEST_RES(smaps_field_kb("/proc/self/smaps", mem, TOTAL_SIZE, "Pss:"),
+		 _ret == TOTAL_SIZE / 2 / 1024);
+	TEST_RES(smaps_field_kb("/proc/self/smaps", mem, TOTAL_SIZE,
+				"Private_Dirty:"),
+		 _ret == TOTAL_SIZE / 2 / 1024);
+	TEST_RES(smaps_field_kb("/proc/self/smaps", mem, TOTAL_SIZE,
+				"Anonymous:"),
+		 _ret == TOTAL_SIZE / 2 / 1024);
+	TEST_RES(smaps_field_kb("/proc/self/smaps", mem, TOTAL_SIZE, "Swap:"),
+		 _ret == 0);
+}
+END_TEST()
+
+FN_TEST(smaps_rollup)
+{
+	TEST_RES(rollup_has_header(), _ret == 1);
+	TEST_RES(smaps_field_kb("/proc/self/smaps_rollup", NULL, 0, "Rss:"),
+		 _ret >= TOTAL_SIZE / 2 / 1024);
+	TEST_RES(smaps_field_kb("/proc/self/smaps_rollup", NULL, 0,
+				"Anonymous:"),
+		 _ret >= TOTAL_SIZE / 2 / 1024);
+}
+END_TEST()
+
+FN_TEST(pagemap)
+{
+	int fd = TEST_SUCC(open("/proc/self/pagemap", O_RDONLY));
+
+	TEST_RES(pagemap_entry(fd, mem), _ret & PM_PRESENT);
+	TEST_RES(pagemap_entry(fd, (char *)mem + (NUM_PAGES - 1) * PAGE_SIZE),
+		 _ret == 0);
+
+	uint64_t entry;
+	TEST_ERRNO(pread(fd, &entry, sizeof(entry) - 1, 0), EINVAL);
+	TEST_ERRNO(pread(fd, &entry, sizeof(entry), 1), EINVAL);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_SETUP(munmap)
+{
+	CHECK(munmap((char *)mem - PAGE_SIZE, TOTAL_SIZE + 2 * PAGE_SIZE));
+}
+END_SETUP()
@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <linux/sockios.h>
+#include <net/ethernet.h>
+#include <net/if.h>
+#include <net/if_arp.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+#include <sys/ioctl.h>
+#include <sys/socket.h>
+#include <poll.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+static int sk_ioctl;
+
+static struct ifreq lo_req = { .ifr_name = "lo" };
+static struct ifreq eth_req = { .ifr_name = "eth0" };
+
+static int get_inet_addr(struct ifreq *ifr, const char *expected)
+{
+	struct sockaddr_in *addr = (struct sockaddr_in *)&ifr->ifr_addr;
+
+	return addr->sin_family == AF_INET &&
+	       strcmp(inet_ntoa(addr->sin_addr), expected) == 0;
+}
+
+static void set_inet_addr(struct ifreq *ifr, const char *addr_str)
+{
+	struct sockaddr_in *addr = (struct sockaddr_in *)&ifr->ifr_addr;
+
+	memset(addr, 0, sizeof(*addr));
+	addr->sin_family = AF_INET;
+	inet_aton(addr_str, &addr->sin_addr);
+}
+
+FN_SETUP(socket)
+{
+	sk_ioctl = CHECK(socket(AF_INET, SOCK_DGRAM, 0));
+}
+END_SETUP()
+
+FN_TEST(get_index_and_name)
+{
+	struct ifreq ifr = { .ifr_name = "invalid" };
+
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFINDEX, &lo_req),
+		 lo_req.ifr_ifindex == 1);
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCGIFINDEX, &ifr), ENODEV);
+
+	memset(&ifr, 0, sizeof(ifr));
+	ifr.ifr_ifindex = 1;
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFNAME, &ifr),
+		 strcmp(ifr.ifr_name, "lo") == 0);
+
+	ifr.ifr_ifindex = 0x7fff;
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCGIFNAME, &ifr), ENODEV);
+}
+END_TEST()
+
+FN_TEST(get_conf)
+{
+	struct ifreq reqs[8];
+	struct ifconf ifc = { .ifc_len = 0, .ifc_req = NULL };
+	int len;
+
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFCONF, &ifc),
+		 ifc.ifc_len >= (int)sizeof(struct ifreq) &&
+			 ifc.ifc_len / sizeof(struct ifreq) *
+					 sizeof(struct ifreq) ==
+				 ifc.ifc_len);
+	len = ifc.ifc_len;
+
+	ifc.ifc_len = sizeof(reqs);
+	ifc.ifc_req = reqs;
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFCONF, &ifc),
+		 ifc.ifc_len == len && strcmp(reqs[0].ifr_name, "lo") == 0 &&
+			 get_inet_addr(&reqs[0], "127.0.0.1"));
+
+	// Entries that do not fit in the buffer are skipped.
+	ifc.ifc_len = sizeof(struct ifreq) + 1;
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFCONF, &ifc),
+		 ifc.ifc_len == sizeof(struct ifreq));
+}
+END_TEST()
+
+FN_TEST(get_flags_and_mtu)
+{
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFFLAGS, &lo_req),
+		 (lo_req.ifr_flags & (IFF_UP | IFF_LOOPBACK | IFF_RUNNING)) ==
+			 (IFF_UP | IFF_LOOPBACK | IFF_RUNNING));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFFLAGS, &eth_req),
+		 (eth_req.ifr_flags & (IFF_UP | IFF_LOOPBACK)) == IFF_UP);
+
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFMTU, &eth_req),
+		 eth_req.ifr_mtu == 1500);
+}
+END_TEST()
+
+FN_TEST(get_addr)
+{
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFADDR, &lo_req),
+		 get_inet_addr(&lo_req, "127.0.0.1"));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFNETMASK, &lo_req),
+		 get_inet_addr(&lo_req, "255.0.0.0"));
+
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFADDR, &eth_req),
+		 get_inet_addr(&eth_req, "10.0.2.15"));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFNETMASK, &eth_req),
+		 get_inet_addr(&eth_req, "255.255.255.0"));
+}
+END_TEST()
+
+FN_TEST(get_hwaddr)
+{
+	static const char zero_addr[ETH_ALEN];
+
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFHWADDR, &lo_req),
+		 lo_req.ifr_hwaddr.sa_family == ARPHRD_LOOPBACK &&
+			 memcmp(lo_req.ifr_hwaddr.sa_data, zero_addr,
+				ETH_ALEN) == 0);
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFHWADDR, &eth_req),
+		 eth_req.ifr_hwaddr.sa_family == ARPHRD_ETHER &&
+			 memcmp(eth_req.ifr_hwaddr.sa_data, zero_addr,
+				ETH_ALEN) != 0);
+
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCSIFHWADDR, &eth_req), EOPNOTSUPP);
+}
+END_TEST()
+
+FN_TEST(set_flags)
+{
+	struct ifreq ifr = lo_req;
+
+	ifr.ifr_flags = 0;
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFFLAGS, &ifr));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFFLAGS, &ifr),
+		 (ifr.ifr_flags & (IFF_UP | IFF_LOOPBACK | IFF_RUNNING)) ==
+			 IFF_LOOPBACK);
+
+	ifr.ifr_flags |= IFF_UP;
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFFLAGS, &ifr));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFFLAGS, &ifr),
+		 (ifr.ifr_flags & (IFF_UP | IFF_LOOPBACK | IFF_RUNNING)) ==
+			 (IFF_UP | IFF_LOOPBACK | IFF_RUNNING));
+}
+END_TEST()
+
+FN_TEST(set_mtu)
+{
+	struct ifreq ifr = eth_req;
+
+	ifr.ifr_mtu = 1400;
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFMTU, &ifr));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFMTU, &ifr), ifr.ifr_mtu == 1400);
+
+	ifr.ifr_mtu = 10;
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCSIFMTU, &ifr), EINVAL);
+
+	ifr.ifr_mtu = 1500;
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFMTU, &ifr));
+}
+END_TEST()
+
+FN_TEST(set_addr)
+{
+	struct ifreq ifr = lo_req;
+
+	set_inet_addr(&ifr, "127.0.0.2");
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFADDR, &ifr));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFADDR, &ifr),
+		 get_inet_addr(&ifr, "127.0.0.2"));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFNETMASK, &ifr),
+		 get_inet_addr(&ifr, "255.0.0.0"));
+
+	set_inet_addr(&ifr, "255.255.0.0");
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFNETMASK, &ifr));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFNETMASK, &ifr),
+		 get_inet_addr(&ifr, "255.255.0.0"));
+
+	set_inet_addr(&ifr, "255.0.255.0");
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCSIFNETMASK, &ifr), EINVAL);
+
+	set_inet_addr(&ifr, "224.0.0.1");
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCSIFADDR, &ifr), EINVAL);
+
+	// The class A address also restores the prefix length.
+	set_inet_addr(&ifr, "127.0.0.1");
+	TEST_SUCC(ioctl(sk_ioctl, SIOCSIFADDR, &ifr));
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFNETMASK, &ifr),
+		 get_inet_addr(&ifr, "255.0.0.0"));
+}
+END_TEST()
+
+FN_TEST(udp_queues)
+{
+	struct sockaddr_in addr = { .sin_family = AF_INET };
+	socklen_t addrlen = sizeof(addr);
+	struct pollfd pfd = { .events = POLLIN };
+	int sk, val;
+
+	sk = TEST_SUCC(socket(AF_INET, SOCK_DGRAM, 0));
+
+	TEST_RES(ioctl(sk, FIONREAD, &val), val == 0);
+
+	addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(getsockname(sk, (struct sockaddr *)&addr, &addrlen));
+
+	TEST_RES(sendto(sk, "hello", 5, 0, (struct sockaddr *)&addr,
+			sizeof(addr)),
+		 _ret == 5);
+	TEST_RES(sendto(sk, "hello world", 11, 0, (struct sockaddr *)&addr,
+			sizeof(addr)),
+		 _ret == 11);
+
+	pfd.fd = sk;
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1);
+
+	// `SIOCINQ` reports the length of the next datagram.
+	TEST_RES(ioctl(sk, SIOCINQ, &val), val == 5);
+	TEST_RES(ioctl(sk, SIOCOUTQ, &val), val == 0);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(tcp_queues)
+{
+	struct sockaddr_in addr = { .sin_family = AF_INET };
+	socklen_t addrlen = sizeof(addr);
+	struct pollfd pfd = { .events = POLLIN };
+	int sk_listen, sk_connect, sk_accept, val;
+
+	sk_listen = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+	sk_connect = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+
+	TEST_RES(ioctl(sk_connect, SIOCINQ, &val), val == 0);
+
+	addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+	TEST_SUCC(bind(sk_listen, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(getsockname(sk_listen, (struct sockaddr *)&addr, &addrlen));
+	TEST_SUCC(listen(sk_listen, 1));
+
+	TEST_ERRNO(ioctl(sk_listen, SIOCINQ, &val), EINVAL);
+	TEST_ERRNO(ioctl(sk_listen, SIOCOUTQ, &val), EINVAL);
+
+	TEST_SUCC(connect(sk_connect, (struct sockaddr *)&addr, sizeof(addr)));
+	sk_accept = TEST_SUCC(accept(sk_listen, NULL, NULL));
+
+	TEST_RES(write(sk_connect, "hello world", 11), _ret == 11);
+
+	pfd.fd = sk_accept;
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1);
+
+	TEST_RES(ioctl(sk_accept, SIOCINQ, &val), val == 11);
+	TEST_RES(ioctl(sk_accept, FIONREAD, &val), val == 11);
+
+	TEST_SUCC(close(sk_accept));
+	TEST_SUCC(close(sk_connect));
+	TEST_SUCC(close(sk_listen));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_ioctl));
+}
+END_SETUP()
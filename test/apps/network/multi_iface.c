@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MPL-2.0
+
+// This test requires the VM to have two NICs, which are named `eth0` and
+// `eth1`. Only the first one is configured with an IPv4 address. It is run
+// by `run_netconf_test.sh`, whose VM has the secondary NIC attached.
+
+#include <linux/sockios.h>
+#include <net/if.h>
+#include <net/if_arp.h>
+#include <sys/ioctl.h>
+#include <sys/socket.h>
+#include <fcntl.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+static int sk_ioctl;
+
+static struct ifreq eth0_req = { .ifr_name = "eth0" };
+static struct ifreq eth1_req = { .ifr_name = "eth1" };
+
+static char buf[65536];
+
+static int read_file(const char *path)
+{
+	int fd, len, ret;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+
+	len = 0;
+	while ((ret = read(fd, buf + len, sizeof(buf) - 1 - len)) > 0)
+		len += ret;
+	buf[len] = '\0';
+	close(fd);
+
+	return ret < 0 ? -1 : len;
+}
+
+FN_SETUP(socket)
+{
+	sk_ioctl = CHECK(socket(AF_INET, SOCK_DGRAM, 0));
+}
+END_SETUP()
+
+FN_TEST(index)
+{
+	int eth0_index = TEST_RES(if_nametoindex("eth0"), _ret > 0);
+	int eth1_index = TEST_RES(if_nametoindex("eth1"), _ret > 0);
+	TEST_RES(eth0_index != eth1_index, _ret);
+
+	TEST_ERRNO(if_nametoindex("eth2"), ENODEV);
+}
+END_TEST()
+
+FN_TEST(flags)
+{
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFFLAGS, &eth0_req),
+		 (eth0_req.ifr_flags & (IFF_UP | IFF_LOOPBACK)) == IFF_UP);
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFFLAGS, &eth1_req),
+		 (eth1_req.ifr_flags & (IFF_UP | IFF_LOOPBACK)) == IFF_UP);
+}
+END_TEST()
+
+FN_TEST(hwaddr)
+{
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFHWADDR, &eth0_req),
+		 eth0_req.ifr_hwaddr.sa_family == ARPHRD_ETHER);
+	TEST_RES(ioctl(sk_ioctl, SIOCGIFHWADDR, &eth1_req),
+		 eth1_req.ifr_hwaddr.sa_family == ARPHRD_ETHER);
+
+	// Each NIC has its own MAC address.
+	TEST_RES(memcmp(eth0_req.ifr_hwaddr.sa_data,
+			eth1_req.ifr_hwaddr.sa_data, 6),
+		 _ret != 0);
+}
+END_TEST()
+
+FN_TEST(addr)
+{
+	TEST_SUCC(ioctl(sk_ioctl, SIOCGIFADDR, &eth0_req));
+	TEST_ERRNO(ioctl(sk_ioctl, SIOCGIFADDR, &eth1_req), EADDRNOTAVAIL);
+}
+END_TEST()
+
+FN_TEST(proc_net_dev)
+{
+	TEST_RES(read_file("/proc/net/dev"),
+		 strstr(buf, "\n  eth0: ") != NULL &&
+			 strstr(buf, "\n  eth1: ") != NULL);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_ioctl));
+}
+END_SETUP()
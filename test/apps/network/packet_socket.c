@@ -0,0 +1,319 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <linux/filter.h>
+#include <linux/if_packet.h>
+#include <net/ethernet.h>
+#include <net/if.h>
+#include <net/if_arp.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+#include <sys/mman.h>
+#include <sys/socket.h>
+#include <poll.h>
+#include <stddef.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+#define RING_BLOCK_SIZE 4096
+#define RING_BLOCK_NR 4
+#define RING_FRAME_SIZE 2048
+
+// The protocol for local experiments.
+#define ETH_P_LOCAL 0x88B5
+
+static int sk_raw;
+static int sk_dgram;
+static int sk_udp;
+static int ifindex;
+
+static struct sockaddr_in gw_addr = { .sin_family = AF_INET };
+
+static char buf[2048];
+
+FN_SETUP(sockets)
+{
+	ifindex = CHECK(if_nametoindex("eth0"));
+	gw_addr.sin_port = htons(9);
+	CHECK(inet_aton("10.0.2.2", &gw_addr.sin_addr));
+
+	sk_raw = CHECK(socket(AF_PACKET, SOCK_RAW, htons(ETH_P_ALL)));
+	sk_dgram = CHECK(socket(AF_PACKET, SOCK_DGRAM | SOCK_NONBLOCK,
+				htons(ETH_P_ALL)));
+	sk_udp = CHECK(socket(AF_INET, SOCK_DGRAM, 0));
+}
+END_SETUP()
+
+static int send_udp(void)
+{
+	return sendto(sk_udp, "packet", 6, 0, (struct sockaddr *)&gw_addr,
+		      sizeof(gw_addr));
+}
+
+static int drain(int sk)
+{
+	while (recv(sk, buf, sizeof(buf), MSG_DONTWAIT) >= 0)
+		;
+	if (errno != EAGAIN)
+		return -1;
+
+	errno = 0;
+	return 0;
+}
+
+FN_TEST(bind_and_getsockname)
+{
+	struct sockaddr_ll addr = { .sll_family = AF_PACKET,
+				    .sll_protocol = htons(ETH_P_ALL) };
+	socklen_t addrlen = sizeof(addr);
+
+	addr.sll_ifindex = 0x7fff;
+	TEST_ERRNO(bind(sk_raw, (struct sockaddr *)&addr, sizeof(addr)),
+		   ENODEV);
+
+	addr.sll_ifindex = ifindex;
+	TEST_SUCC(bind(sk_raw, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(bind(sk_dgram, (struct sockaddr *)&addr, sizeof(addr)));
+
+	memset(&addr, 0, sizeof(addr));
+	TEST_RES(getsockname(sk_raw, (struct sockaddr *)&addr, &addrlen),
+		 addrlen == offsetof(struct sockaddr_ll, sll_addr) + ETH_ALEN &&
+			 addr.sll_family == AF_PACKET &&
+			 addr.sll_protocol == htons(ETH_P_ALL) &&
+			 addr.sll_ifindex == ifindex &&
+			 addr.sll_hatype == ARPHRD_ETHER &&
+			 addr.sll_halen == ETH_ALEN);
+}
+END_TEST()
+
+FN_TEST(capture_outgoing)
+{
+	struct ether_header *eth = (struct ether_header *)buf;
+	struct sockaddr_ll addr;
+	socklen_t addrlen = sizeof(addr);
+	int len;
+
+	TEST_SUCC(drain(sk_raw));
+	TEST_SUCC(drain(sk_dgram));
+
+	TEST_RES(send_udp(), _ret == 6);
+
+	// The first outgoing frame is either an ARP request or the UDP packet.
+	len = TEST_RES(recvfrom(sk_raw, buf, sizeof(buf), 0,
+				(struct sockaddr *)&addr, &addrlen),
+		       _ret > (long)sizeof(*eth) &&
+			       addrlen == sizeof(addr) &&
+			       addr.sll_pkttype == PACKET_OUTGOING &&
+			       addr.sll_ifindex == ifindex);
+	TEST_RES(ntohs(eth->ether_type),
+		 _ret == ETHERTYPE_IP || _ret == ETHERTYPE_ARP);
+
+	// The datagram socket receives the same frame without the link-layer header.
+	TEST_RES(recv(sk_dgram, buf, sizeof(buf), 0),
+		 _ret == len - (long)sizeof(*eth));
+}
+END_TEST()
+
+FN_TEST(socket_filter)
+{
+	struct sock_filter drop_all[] = {
+		BPF_STMT(BPF_RET | BPF_K, 0),
+	};
+	struct sock_filter bad_jump[] = {
+		BPF_JUMP(BPF_JMP | BPF_JEQ | BPF_K, 0, 1, 1),
+		BPF_STMT(BPF_RET | BPF_K, 0),
+	};
+	struct sock_fprog prog = { .len = 1, .filter = drop_all };
+	int val = 0;
+
+	TEST_SUCC(setsockopt(sk_dgram, SOL_SOCKET, SO_ATTACH_FILTER, &prog,
+			     sizeof(prog)));
+
+	TEST_SUCC(drain(sk_dgram));
+	TEST_RES(send_udp(), _ret == 6);
+	TEST_RES(recv(sk_raw, buf, sizeof(buf), 0), _ret > 0);
+	TEST_ERRNO(recv(sk_dgram, buf, sizeof(buf), 0), EAGAIN);
+
+	TEST_SUCC(setsockopt(sk_dgram, SOL_SOCKET, SO_DETACH_FILTER, &val,
+			     sizeof(val)));
+	TEST_ERRNO(setsockopt(sk_dgram, SOL_SOCKET, SO_DETACH_FILTER, &val,
+			      sizeof(val)),
+		   ENOENT);
+
+	prog.len = 0;
+	TEST_ERRNO(setsockopt(sk_dgram, SOL_SOCKET, SO_ATTACH_FILTER, &prog,
+			      sizeof(prog)),
+		   EINVAL);
+
+	prog.len = 2;
+	prog.filter = bad_jump;
+	TEST_ERRNO(setsockopt(sk_dgram, SOL_SOCKET, SO_ATTACH_FILTER, &prog,
+			      sizeof(prog)),
+		   EINVAL);
+}
+END_TEST()
+
+// Returns the length of the first captured frame with the `ETH_P_LOCAL` protocol, or -1 if
+// there is no such frame.
+static int recv_local_frame(int sk)
+{
+	struct sockaddr_ll addr;
+	socklen_t addrlen;
+	int len;
+
+	for (;;) {
+		addrlen = sizeof(addr);
+		len = recvfrom(sk, buf, sizeof(buf), MSG_DONTWAIT,
+			       (struct sockaddr *)&addr, &addrlen);
+		if (len < 0)
+			return -1;
+		if (addr.sll_protocol == htons(ETH_P_LOCAL))
+			return len;
+	}
+}
+
+FN_TEST(send_raw)
+{
+	char frame[64] = { 0 };
+	struct ether_header *eth = (struct ether_header *)frame;
+	struct sockaddr_ll addr;
+	socklen_t addrlen = sizeof(addr);
+
+	// Send a broadcast frame with the local experimental protocol.
+	TEST_SUCC(getsockname(sk_raw, (struct sockaddr *)&addr, &addrlen));
+	memset(eth->ether_dhost, 0xff, ETH_ALEN);
+	memcpy(eth->ether_shost, addr.sll_addr, ETH_ALEN);
+	eth->ether_type = htons(ETH_P_LOCAL);
+
+	TEST_SUCC(drain(sk_dgram));
+	TEST_RES(send(sk_raw, frame, sizeof(frame), 0), _ret == sizeof(frame));
+	TEST_RES(recv_local_frame(sk_dgram),
+		 _ret == sizeof(frame) - sizeof(*eth));
+
+	// The destination address selects the iface.
+	TEST_RES(sendto(sk_raw, frame, sizeof(frame), 0,
+			(struct sockaddr *)&addr, sizeof(addr)),
+		 _ret == sizeof(frame));
+	TEST_RES(recv_local_frame(sk_dgram),
+		 _ret == sizeof(frame) - sizeof(*eth));
+
+	addr.sll_ifindex = 0x7fff;
+	TEST_ERRNO(sendto(sk_raw, frame, sizeof(frame), 0,
+			  (struct sockaddr *)&addr, sizeof(addr)),
+		   ENXIO);
+
+	// The frame must contain the link-layer header.
+	TEST_ERRNO(send(sk_raw, frame, sizeof(*eth) - 1, 0), EINVAL);
+}
+END_TEST()
+
+FN_TEST(membership)
+{
+	struct packet_mreq mreq = { .mr_ifindex = ifindex,
+				    .mr_type = PACKET_MR_PROMISC };
+
+	TEST_SUCC(setsockopt(sk_raw, SOL_PACKET, PACKET_ADD_MEMBERSHIP, &mreq,
+			     sizeof(mreq)));
+	TEST_SUCC(setsockopt(sk_raw, SOL_PACKET, PACKET_DROP_MEMBERSHIP, &mreq,
+			     sizeof(mreq)));
+	// Dropping a membership that does not exist is not an error.
+	TEST_SUCC(setsockopt(sk_raw, SOL_PACKET, PACKET_DROP_MEMBERSHIP, &mreq,
+			     sizeof(mreq)));
+
+	mreq.mr_ifindex = 0x7fff;
+	TEST_ERRNO(setsockopt(sk_raw, SOL_PACKET, PACKET_ADD_MEMBERSHIP, &mreq,
+			      sizeof(mreq)),
+		   ENODEV);
+}
+END_TEST()
+
+FN_TEST(version_and_hdrlen)
+{
+	int val;
+	socklen_t len = sizeof(val);
+
+	TEST_RES(getsockopt(sk_raw, SOL_PACKET, PACKET_VERSION, &val, &len),
+		 len == sizeof(val) && val == TPACKET_V1);
+
+	val = TPACKET_V3;
+	TEST_RES(getsockopt(sk_raw, SOL_PACKET, PACKET_HDRLEN, &val, &len),
+		 len == sizeof(val) && val == sizeof(struct tpacket3_hdr));
+
+	val = 3;
+	TEST_ERRNO(getsockopt(sk_raw, SOL_PACKET, PACKET_HDRLEN, &val, &len),
+		   EINVAL);
+	TEST_ERRNO(setsockopt(sk_raw, SOL_PACKET, PACKET_VERSION, &val,
+			      sizeof(val)),
+		   EINVAL);
+}
+END_TEST()
+
+FN_TEST(rx_ring)
+{
+	struct tpacket_req3 req = {
+		.tp_block_size = RING_BLOCK_SIZE,
+		.tp_block_nr = RING_BLOCK_NR,
+		.tp_frame_size = RING_FRAME_SIZE,
+		.tp_frame_nr = RING_BLOCK_SIZE / RING_FRAME_SIZE * RING_BLOCK_NR,
+		.tp_retire_blk_tov = 10,
+	};
+	size_t ring_size = RING_BLOCK_SIZE * RING_BLOCK_NR;
+	struct tpacket_block_desc *desc;
+	struct tpacket3_hdr *hdr;
+	struct tpacket_stats_v3 stats;
+	socklen_t len = sizeof(stats);
+	struct pollfd pfd = { .fd = sk_raw, .events = POLLIN };
+	int val = TPACKET_V3;
+	char *ring;
+
+	TEST_SUCC(setsockopt(sk_raw, SOL_PACKET, PACKET_VERSION, &val,
+			     sizeof(val)));
+	TEST_SUCC(setsockopt(sk_raw, SOL_PACKET, PACKET_RX_RING, &req,
+			     sizeof(req)));
+	TEST_ERRNO(setsockopt(sk_raw, SOL_PACKET, PACKET_VERSION, &val,
+			      sizeof(val)),
+		   EBUSY);
+	TEST_RES(getsockopt(sk_raw, SOL_PACKET, PACKET_STATISTICS, &stats,
+			    &len),
+		 len == sizeof(stats));
+
+	ring = (char *)CHECK_WITH((long)mmap(NULL, ring_size,
+					     PROT_READ | PROT_WRITE, MAP_SHARED,
+					     sk_raw, 0),
+				  _ret != (long)MAP_FAILED);
+
+	TEST_RES(send_udp(), _ret == 6);
+
+	// The block becomes visible after it is retired by the timer.
+	TEST_RES(poll(&pfd, 1, 1000), _ret == 1 && (pfd.revents & POLLIN));
+
+	desc = (struct tpacket_block_desc *)ring;
+	TEST_RES(desc->hdr.bh1.block_status,
+		 (_ret & TP_STATUS_USER) && desc->hdr.bh1.num_pkts >= 1);
+
+	hdr = (struct tpacket3_hdr *)(ring + desc->hdr.bh1.offset_to_first_pkt);
+	TEST_RES(hdr->tp_snaplen,
+		 _ret == hdr->tp_len && _ret > sizeof(struct ether_header));
+
+	// Return the block to the kernel.
+	desc->hdr.bh1.block_status = TP_STATUS_KERNEL;
+
+	TEST_SUCC(munmap(ring, ring_size));
+
+	memset(&req, 0, sizeof(req));
+	TEST_SUCC(setsockopt(sk_raw, SOL_PACKET, PACKET_RX_RING, &req,
+			     sizeof(req)));
+	TEST_ERRNO((long)mmap(NULL, ring_size, PROT_READ, MAP_SHARED, sk_raw,
+			      0),
+		   EINVAL);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_raw));
+	CHECK(close(sk_dgram));
+	CHECK(close(sk_udp));
+}
+END_SETUP()
@@ -0,0 +1,214 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <netinet/in.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <fcntl.h>
+#include <stddef.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+static char buf[65536];
+
+static int starts_with(const char *str, const char *prefix)
+{
+	return strncmp(str, prefix, strlen(prefix)) == 0;
+}
+
+static int read_file(const char *path)
+{
+	int fd, len, ret;
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+
+	len = 0;
+	while ((ret = read(fd, buf + len, sizeof(buf) - 1 - len)) > 0)
+		len += ret;
+	buf[len] = '\0';
+	close(fd);
+
+	return ret < 0 ? -1 : len;
+}
+
+// Finds the line in `/proc/net/{tcp,udp}` whose local address is
+// `127.0.0.1:port` and returns its state.
+static int find_inet_state(const char *path, int port)
+{
+	char local[32];
+	char *line;
+	unsigned int state;
+
+	if (read_file(path) < 0)
+		return -1;
+
+	// The address is printed as a native integer, so this only works on
+	// little-endian machines.
+	snprintf(local, sizeof(local), ": 0100007F:%04X ", port);
+	line = strstr(buf, local);
+	if (line == NULL)
+		return -1;
+	if (sscanf(line + strlen(local), "%*s %x", &state) != 1)
+		return -1;
+
+	return state;
+}
+
+static int sk_listen;
+static int sk_connect;
+static int sk_accept;
+static int sk_udp;
+static int sk_unix;
+static int tcp_port;
+static int udp_port;
+
+#define UNIX_NAME "proc_net_test"
+
+FN_SETUP(sockets)
+{
+	struct sockaddr_in addr = { .sin_family = AF_INET };
+	socklen_t addrlen = sizeof(addr);
+	struct sockaddr_un unix_addr = { .sun_family = AF_UNIX };
+
+	addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+
+	sk_listen = CHECK(socket(AF_INET, SOCK_STREAM, 0));
+	CHECK(bind(sk_listen, (struct sockaddr *)&addr, sizeof(addr)));
+	CHECK(getsockname(sk_listen, (struct sockaddr *)&addr, &addrlen));
+	CHECK(listen(sk_listen, 3));
+	tcp_port = ntohs(addr.sin_port);
+
+	sk_connect = CHECK(socket(AF_INET, SOCK_STREAM, 0));
+	CHECK(connect(sk_connect, (struct sockaddr *)&addr, sizeof(addr)));
+	sk_accept = CHECK(accept(sk_listen, NULL, NULL));
+
+	addr.sin_port = 0;
+	sk_udp = CHECK(socket(AF_INET, SOCK_DGRAM, 0));
+	CHECK(bind(sk_udp, (struct sockaddr *)&addr, sizeof(addr)));
+	CHECK(getsockname(sk_udp, (struct sockaddr *)&addr, &addrlen));
+	udp_port = ntohs(addr.sin_port);
+
+	memcpy(unix_addr.sun_path + 1, UNIX_NAME, strlen(UNIX_NAME));
+	sk_unix = CHECK(socket(AF_UNIX, SOCK_STREAM, 0));
+	CHECK(bind(sk_unix, (struct sockaddr *)&unix_addr,
+		   offsetof(struct sockaddr_un, sun_path) + 1 +
+			   strlen(UNIX_NAME)));
+	CHECK(listen(sk_unix, 1));
+}
+END_SETUP()
+
+FN_TEST(tcp)
+{
+	TEST_RES(read_file("/proc/net/tcp"),
+		 starts_with(buf, "  sl  local_address rem_address   st"));
+
+	// TCP_LISTEN
+	TEST_RES(find_inet_state("/proc/net/tcp", tcp_port), _ret == 0x0A);
+}
+END_TEST()
+
+FN_TEST(udp)
+{
+	TEST_RES(read_file("/proc/net/udp"),
+		 starts_with(buf, "   sl  local_address rem_address   st"));
+
+	// TCP_CLOSE
+	TEST_RES(find_inet_state("/proc/net/udp", udp_port), _ret == 0x07);
+}
+END_TEST()
+
+FN_TEST(unix)
+{
+	TEST_RES(read_file("/proc/net/unix"),
+		 starts_with(buf, "Num       RefCount Protocol Flags    Type St"));
+
+	// __SO_ACCEPTCON, SOCK_STREAM, SS_UNCONNECTED
+	TEST_RES(read_file("/proc/net/unix"),
+		 strstr(buf, " 00010000 0001 01 ") != NULL &&
+			 strstr(buf, " @" UNIX_NAME "\n") != NULL);
+}
+END_TEST()
+
+FN_TEST(dev)
+{
+	TEST_RES(read_file("/proc/net/dev"),
+		 starts_with(buf, "Inter-|   Receive") &&
+			 strstr(buf, "    lo: ") != NULL);
+}
+END_TEST()
+
+FN_TEST(snmp)
+{
+	TEST_RES(read_file("/proc/net/snmp"),
+		 starts_with(buf, "Ip: Forwarding DefaultTTL InReceives") &&
+			 strstr(buf, "\nTcp: RtoAlgorithm RtoMin RtoMax") !=
+				 NULL &&
+			 strstr(buf, "\nUdp: InDatagrams NoPorts") != NULL);
+	TEST_RES(read_file("/proc/net/netstat"),
+		 starts_with(buf, "TcpExt: ") &&
+			 strstr(buf, "\nIpExt: ") != NULL);
+}
+END_TEST()
+
+FN_TEST(route)
+{
+	TEST_RES(read_file("/proc/net/route"),
+		 starts_with(buf, "Iface\tDestination\tGateway \tFlags"));
+	TEST_SUCC(read_file("/proc/net/if_inet6"));
+}
+END_TEST()
+
+// Returns whether the kernel command line contains `arg`.
+static int has_kernel_arg(const char *arg)
+{
+	const char *pos;
+	size_t len = strlen(arg);
+
+	if (read_file("/proc/cmdline") < 0)
+		return -1;
+
+	for (pos = buf; (pos = strstr(pos, arg)) != NULL; pos += len) {
+		if ((pos == buf || pos[-1] == ' ') &&
+		    (pos[len] == ' ' || pos[len] == '\n' || pos[len] == '\0'))
+			return 1;
+	}
+	return 0;
+}
+
+FN_TEST(pnp)
+{
+	int is_dhcp = TEST_RES(has_kernel_arg("ip=dhcp"), _ret >= 0);
+
+	// With `ip=dhcp`, the lease must be obtained from the DHCP server of the
+	// QEMU user network, which is at 10.0.2.2. Its DNS server is at 10.0.2.3.
+	if (is_dhcp)
+		TEST_RES(read_file("/proc/net/pnp"),
+			 starts_with(buf, "#PROTO: DHCP\n") &&
+				 strstr(buf, "\nnameserver 10.0.2.3\n") != NULL &&
+				 strstr(buf, "\nbootserver 10.0.2.2\n") != NULL);
+	else
+		TEST_RES(read_file("/proc/net/pnp"),
+			 strcmp(buf, "#MANUAL\n") == 0);
+}
+END_TEST()
+
+FN_TEST(self_net)
+{
+	TEST_RES(read_file("/proc/self/net/dev"),
+		 starts_with(buf, "Inter-|   Receive"));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_unix));
+	CHECK(close(sk_udp));
+	CHECK(close(sk_accept));
+	CHECK(close(sk_connect));
+	CHECK(close(sk_listen));
+}
+END_SETUP()
@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <netinet/in.h>
+#include <netinet/ip.h>
+#include <linux/icmp.h>
+#include <arpa/inet.h>
+#include <sys/socket.h>
+#include <fcntl.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+#define PING_GROUP_RANGE "/proc/sys/net/ipv4/ping_group_range"
+
+static int sk_raw_icmp;
+static int sk_raw_hdrincl;
+
+static struct sockaddr_in lo_addr = { .sin_family = AF_INET };
+
+static char buf[1024];
+
+static unsigned short icmp_checksum(const void *data, size_t len)
+{
+	const unsigned short *ptr = data;
+	unsigned int sum = 0;
+
+	for (; len > 1; len -= 2)
+		sum += *ptr++;
+	if (len == 1)
+		sum += *(const unsigned char *)ptr;
+
+	sum = (sum >> 16) + (sum & 0xffff);
+	sum += sum >> 16;
+
+	return ~sum;
+}
+
+static size_t make_echo_request(void *data, unsigned short id,
+				unsigned short seq)
+{
+	struct icmphdr *icmp = data;
+
+	memset(icmp, 0, sizeof(*icmp));
+	icmp->type = ICMP_ECHO;
+	icmp->un.echo.id = htons(id);
+	icmp->un.echo.sequence = htons(seq);
+	memcpy(icmp + 1, "ping", 4);
+	icmp->checksum = icmp_checksum(icmp, sizeof(*icmp) + 4);
+
+	return sizeof(*icmp) + 4;
+}
+
+static int write_ping_group_range(const char *range)
+{
+	int fd, ret;
+
+	fd = open(PING_GROUP_RANGE, O_WRONLY);
+	if (fd < 0)
+		return -1;
+
+	ret = write(fd, range, strlen(range));
+	close(fd);
+
+	return ret;
+}
+
+static int read_ping_group_range(char *range, size_t len)
+{
+	int fd, ret;
+
+	fd = open(PING_GROUP_RANGE, O_RDONLY);
+	if (fd < 0)
+		return -1;
+
+	ret = read(fd, range, len - 1);
+	close(fd);
+	if (ret >= 0)
+		range[ret] = '\0';
+
+	return ret;
+}
+
+FN_SETUP(sockets)
+{
+	lo_addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+
+	sk_raw_icmp = CHECK(socket(AF_INET, SOCK_RAW, IPPROTO_ICMP));
+	sk_raw_hdrincl = CHECK(socket(AF_INET, SOCK_RAW, IPPROTO_RAW));
+}
+END_SETUP()
+
+FN_TEST(raw_invalid_protocol)
+{
+	TEST_ERRNO(socket(AF_INET, SOCK_RAW, 0), EPROTONOSUPPORT);
+}
+END_TEST()
+
+FN_TEST(raw_hdrincl)
+{
+	int val;
+	socklen_t len = sizeof(val);
+
+	TEST_RES(getsockopt(sk_raw_icmp, IPPROTO_IP, IP_HDRINCL, &val, &len),
+		 len == sizeof(val) && val == 0);
+	TEST_RES(getsockopt(sk_raw_hdrincl, IPPROTO_IP, IP_HDRINCL, &val,
+			    &len),
+		 len == sizeof(val) && val == 1);
+}
+END_TEST()
+
+FN_TEST(raw_echo)
+{
+	struct iphdr *ip = (struct iphdr *)buf;
+	struct icmphdr *icmp;
+	size_t len;
+
+	len = make_echo_request(buf, 0x1234, 1);
+	TEST_RES(sendto(sk_raw_icmp, buf, len, 0, (struct sockaddr *)&lo_addr,
+			sizeof(lo_addr)),
+		 _ret == len);
+
+	// The raw socket sees both the echo request and the echo reply.
+	TEST_RES(recv(sk_raw_icmp, buf, sizeof(buf), 0),
+		 _ret == sizeof(*ip) + len && ip->protocol == IPPROTO_ICMP &&
+			 ip->saddr == htonl(INADDR_LOOPBACK));
+	icmp = (struct icmphdr *)(buf + ip->ihl * 4);
+	TEST_RES(icmp->type, _ret == ICMP_ECHO);
+
+	TEST_RES(recv(sk_raw_icmp, buf, sizeof(buf), 0),
+		 _ret == sizeof(*ip) + len && ip->protocol == IPPROTO_ICMP);
+	icmp = (struct icmphdr *)(buf + ip->ihl * 4);
+	TEST_RES(icmp->type, _ret == ICMP_ECHOREPLY &&
+				     icmp->un.echo.id == htons(0x1234) &&
+				     icmp->un.echo.sequence == htons(1));
+}
+END_TEST()
+
+FN_TEST(raw_icmp_filter)
+{
+	struct icmp_filter filter = { .data = 1 << ICMP_ECHO };
+	struct icmp_filter got;
+	socklen_t len = sizeof(got);
+	struct iphdr *ip = (struct iphdr *)buf;
+	struct icmphdr *icmp;
+
+	TEST_ERRNO(setsockopt(sk_raw_hdrincl, SOL_RAW, ICMP_FILTER, &filter,
+			      sizeof(filter)),
+		   EOPNOTSUPP);
+
+	TEST_SUCC(setsockopt(sk_raw_icmp, SOL_RAW, ICMP_FILTER, &filter,
+			     sizeof(filter)));
+	TEST_RES(getsockopt(sk_raw_icmp, SOL_RAW, ICMP_FILTER, &got, &len),
+		 len == sizeof(got) && got.data == filter.data);
+
+	len = make_echo_request(buf, 0x1234, 2);
+	TEST_RES(sendto(sk_raw_icmp, buf, len, 0, (struct sockaddr *)&lo_addr,
+			sizeof(lo_addr)),
+		 _ret == len);
+
+	// The echo request is filtered out.
+	TEST_RES(recv(sk_raw_icmp, buf, sizeof(buf), 0),
+		 _ret == sizeof(*ip) + len);
+	icmp = (struct icmphdr *)(buf + ip->ihl * 4);
+	TEST_RES(icmp->type, _ret == ICMP_ECHOREPLY &&
+				     icmp->un.echo.sequence == htons(2));
+}
+END_TEST()
+
+FN_TEST(raw_send_hdrincl)
+{
+	struct iphdr *ip = (struct iphdr *)buf;
+	struct icmphdr *icmp;
+	size_t len;
+
+	memset(ip, 0, sizeof(*ip));
+	ip->version = 4;
+	ip->ihl = sizeof(*ip) / 4;
+	ip->ttl = 64;
+	ip->protocol = IPPROTO_ICMP;
+	ip->daddr = htonl(INADDR_LOOPBACK);
+	len = sizeof(*ip) + make_echo_request(ip + 1, 0x4321, 3);
+
+	TEST_RES(sendto(sk_raw_hdrincl, buf, len, 0,
+			(struct sockaddr *)&lo_addr, sizeof(lo_addr)),
+		 _ret == len);
+
+	TEST_RES(recv(sk_raw_icmp, buf, sizeof(buf), 0), _ret == len);
+	icmp = (struct icmphdr *)(buf + ip->ihl * 4);
+	TEST_RES(icmp->type, _ret == ICMP_ECHOREPLY &&
+				     icmp->un.echo.id == htons(0x4321) &&
+				     icmp->un.echo.sequence == htons(3));
+
+	TEST_ERRNO(sendto(sk_raw_hdrincl, buf, sizeof(*ip) - 1, 0,
+			  (struct sockaddr *)&lo_addr, sizeof(lo_addr)),
+		   EINVAL);
+}
+END_TEST()
+
+FN_TEST(ping_group_range)
+{
+	char range[64];
+
+	TEST_RES(read_ping_group_range(range, sizeof(range)),
+		 strcmp(range, "1\t0\n") == 0);
+	TEST_ERRNO(socket(AF_INET, SOCK_DGRAM, IPPROTO_ICMP), EACCES);
+
+	TEST_ERRNO(write_ping_group_range("-1 0"), EINVAL);
+	TEST_ERRNO(write_ping_group_range("0 4294967295"), EINVAL);
+
+	TEST_SUCC(write_ping_group_range("0 2147483647"));
+	TEST_RES(read_ping_group_range(range, sizeof(range)),
+		 strcmp(range, "0\t2147483647\n") == 0);
+}
+END_TEST()
+
+FN_TEST(ping_echo)
+{
+	struct sockaddr_in addr;
+	socklen_t addrlen = sizeof(addr);
+	struct icmphdr *icmp = (struct icmphdr *)buf;
+	size_t len;
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_INET, SOCK_DGRAM, IPPROTO_ICMP));
+
+	len = make_echo_request(buf, 0, 4);
+	icmp->type = ICMP_ECHOREPLY;
+	TEST_ERRNO(sendto(sk, buf, len, 0, (struct sockaddr *)&lo_addr,
+			  sizeof(lo_addr)),
+		   EINVAL);
+
+	len = make_echo_request(buf, 0, 5);
+	TEST_RES(sendto(sk, buf, len, 0, (struct sockaddr *)&lo_addr,
+			sizeof(lo_addr)),
+		 _ret == len);
+	TEST_RES(getsockname(sk, (struct sockaddr *)&addr, &addrlen),
+		 addrlen == sizeof(addr) && addr.sin_port != 0);
+
+	// The ping socket receives the ICMP message without the IP header.
+	TEST_RES(recv(sk, buf, sizeof(buf), 0),
+		 _ret == len && icmp->type == ICMP_ECHOREPLY &&
+			 icmp->un.echo.id == addr.sin_port &&
+			 icmp->un.echo.sequence == htons(5));
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(write_ping_group_range("1 0"));
+
+	CHECK(close(sk_raw_icmp));
+	CHECK(close(sk_raw_hdrincl));
+}
+END_SETUP()
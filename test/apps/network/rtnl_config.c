@@ -0,0 +1,269 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <linux/rtnetlink.h>
+#include <net/if.h>
+#include <arpa/inet.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+struct rtnl_req {
+	struct nlmsghdr hdr;
+	union {
+		struct rtmsg rtm;
+		struct ifaddrmsg ifa;
+		struct ifinfomsg ifi;
+	};
+	char attrs[64];
+};
+
+static int sk_rtnl;
+static int sk_monitor;
+static int lo_index;
+static unsigned int seq;
+
+static char resp_buf[4096];
+
+#define TEST_NET "192.0.2.0"
+#define TEST_ADDR "192.0.2.1"
+
+FN_SETUP(sockets)
+{
+	struct sockaddr_nl addr = { .nl_family = AF_NETLINK,
+				    .nl_groups = RTMGRP_IPV4_ROUTE };
+
+	sk_rtnl = CHECK(
+		socket(PF_NETLINK, SOCK_DGRAM | SOCK_NONBLOCK, NETLINK_ROUTE));
+
+	sk_monitor = CHECK(
+		socket(PF_NETLINK, SOCK_DGRAM | SOCK_NONBLOCK, NETLINK_ROUTE));
+	CHECK(bind(sk_monitor, (struct sockaddr *)&addr, sizeof(addr)));
+
+	lo_index = CHECK_WITH(if_nametoindex("lo"), _ret > 0);
+}
+END_SETUP()
+
+static void init_req(struct rtnl_req *req, int type, int flags, size_t len)
+{
+	memset(req, 0, sizeof(*req));
+	req->hdr.nlmsg_len = NLMSG_LENGTH(len);
+	req->hdr.nlmsg_type = type;
+	req->hdr.nlmsg_flags = NLM_F_REQUEST | flags;
+	req->hdr.nlmsg_seq = ++seq;
+}
+
+static void add_attr(struct rtnl_req *req, int type, const void *data,
+		     size_t len)
+{
+	struct rtattr *rta =
+		(struct rtattr *)((char *)req + NLMSG_ALIGN(req->hdr.nlmsg_len));
+
+	rta->rta_type = type;
+	rta->rta_len = RTA_LENGTH(len);
+	memcpy(RTA_DATA(rta), data, len);
+	req->hdr.nlmsg_len = NLMSG_ALIGN(req->hdr.nlmsg_len) + rta->rta_len;
+}
+
+static void add_addr_attr(struct rtnl_req *req, int type, const char *addr)
+{
+	struct in_addr in;
+
+	inet_pton(AF_INET, addr, &in);
+	add_attr(req, type, &in, sizeof(in));
+}
+
+static void init_route_req(struct rtnl_req *req, int type, int flags,
+			   const char *dst, int dst_len)
+{
+	init_req(req, type, flags, sizeof(struct rtmsg));
+	req->rtm.rtm_family = AF_INET;
+	req->rtm.rtm_dst_len = dst_len;
+	req->rtm.rtm_table = RT_TABLE_MAIN;
+	req->rtm.rtm_protocol = RTPROT_STATIC;
+	req->rtm.rtm_scope = RT_SCOPE_LINK;
+	req->rtm.rtm_type = RTN_UNICAST;
+	add_addr_attr(req, RTA_DST, dst);
+}
+
+// Sends the request and receives the response.
+//
+// If the response is an error message, this function fails with the reported
+// error. Otherwise, this function returns the type of the response message.
+static int send_req(struct rtnl_req *req)
+{
+	struct nlmsghdr *resp = (struct nlmsghdr *)resp_buf;
+	struct nlmsgerr *err;
+
+	if (send(sk_rtnl, req, req->hdr.nlmsg_len, 0) < 0)
+		return -1;
+	if (recv(sk_rtnl, resp_buf, sizeof(resp_buf), 0) < 0)
+		return -1;
+
+	if (resp->nlmsg_seq != req->hdr.nlmsg_seq) {
+		errno = EPROTO;
+		return -1;
+	}
+	if (resp->nlmsg_type != NLMSG_ERROR)
+		return resp->nlmsg_type;
+
+	err = NLMSG_DATA(resp);
+	if (err->error != 0) {
+		errno = -err->error;
+		return -1;
+	}
+	return NLMSG_ERROR;
+}
+
+static struct rtattr *find_route_attr(int type)
+{
+	struct nlmsghdr *resp = (struct nlmsghdr *)resp_buf;
+	struct rtmsg *rtm = NLMSG_DATA(resp);
+	struct rtattr *rta = RTM_RTA(rtm);
+	int len = RTM_PAYLOAD(resp);
+
+	for (; RTA_OK(rta, len); rta = RTA_NEXT(rta, len))
+		if (rta->rta_type == type)
+			return rta;
+	return NULL;
+}
+
+static int resp_oif(void)
+{
+	struct rtattr *rta = find_route_attr(RTA_OIF);
+
+	return rta ? *(int *)RTA_DATA(rta) : -1;
+}
+
+static int resp_rtm_type(void)
+{
+	struct nlmsghdr *resp = (struct nlmsghdr *)resp_buf;
+
+	return ((struct rtmsg *)NLMSG_DATA(resp))->rtm_type;
+}
+
+FN_TEST(new_route)
+{
+	struct rtnl_req req;
+	struct nlmsghdr *notif = (struct nlmsghdr *)resp_buf;
+
+	init_route_req(&req, RTM_NEWROUTE, NLM_F_CREATE | NLM_F_ACK, TEST_NET,
+		       24);
+	add_attr(&req, RTA_OIF, &lo_index, sizeof(lo_index));
+	TEST_RES(send_req(&req), _ret == NLMSG_ERROR);
+
+	// The change should be notified to the listeners.
+	TEST_RES(recv(sk_monitor, resp_buf, sizeof(resp_buf), 0),
+		 notif->nlmsg_type == RTM_NEWROUTE && resp_oif() == lo_index);
+
+	init_route_req(&req, RTM_NEWROUTE,
+		       NLM_F_CREATE | NLM_F_EXCL | NLM_F_ACK, TEST_NET, 24);
+	add_attr(&req, RTA_OIF, &lo_index, sizeof(lo_index));
+	TEST_ERRNO(send_req(&req), EEXIST);
+	TEST_ERRNO(recv(sk_monitor, resp_buf, sizeof(resp_buf), 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(new_route_invalid)
+{
+	struct rtnl_req req;
+
+	// The prefix has host bits set
+	init_route_req(&req, RTM_NEWROUTE, NLM_F_CREATE | NLM_F_ACK, TEST_ADDR,
+		       24);
+	add_attr(&req, RTA_OIF, &lo_index, sizeof(lo_index));
+	TEST_ERRNO(send_req(&req), EINVAL);
+
+	// The prefix length is too long
+	init_route_req(&req, RTM_NEWROUTE, NLM_F_CREATE | NLM_F_ACK, TEST_NET,
+		       33);
+	add_attr(&req, RTA_OIF, &lo_index, sizeof(lo_index));
+	TEST_ERRNO(send_req(&req), EINVAL);
+
+	// The output interface does not exist
+	init_route_req(&req, RTM_NEWROUTE, NLM_F_CREATE | NLM_F_ACK, TEST_NET,
+		       24);
+	add_attr(&req, RTA_OIF, &(int){ 0x7fff }, sizeof(int));
+	TEST_ERRNO(send_req(&req), ENODEV);
+}
+END_TEST()
+
+FN_TEST(get_route)
+{
+	struct rtnl_req req;
+
+	init_req(&req, RTM_GETROUTE, 0, sizeof(struct rtmsg));
+	req.rtm.rtm_family = AF_INET;
+	req.rtm.rtm_dst_len = 32;
+	add_addr_attr(&req, RTA_DST, TEST_ADDR);
+	TEST_RES(send_req(&req), _ret == RTM_NEWROUTE &&
+					 resp_rtm_type() == RTN_UNICAST &&
+					 resp_oif() == lo_index);
+
+	init_req(&req, RTM_GETROUTE, 0, sizeof(struct rtmsg));
+	req.rtm.rtm_family = AF_INET;
+	req.rtm.rtm_dst_len = 32;
+	add_addr_attr(&req, RTA_DST, "127.0.0.1");
+	TEST_RES(send_req(&req), _ret == RTM_NEWROUTE &&
+					 resp_rtm_type() == RTN_LOCAL &&
+					 resp_oif() == lo_index);
+}
+END_TEST()
+
+FN_TEST(del_route)
+{
+	struct rtnl_req req;
+	struct nlmsghdr *notif = (struct nlmsghdr *)resp_buf;
+
+	init_route_req(&req, RTM_DELROUTE, NLM_F_ACK, TEST_NET, 24);
+	TEST_RES(send_req(&req), _ret == NLMSG_ERROR);
+
+	TEST_RES(recv(sk_monitor, resp_buf, sizeof(resp_buf), 0),
+		 notif->nlmsg_type == RTM_DELROUTE && resp_oif() == lo_index);
+
+	init_route_req(&req, RTM_DELROUTE, NLM_F_ACK, TEST_NET, 24);
+	TEST_ERRNO(send_req(&req), ESRCH);
+	TEST_ERRNO(recv(sk_monitor, resp_buf, sizeof(resp_buf), 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(addr_invalid)
+{
+	struct rtnl_req req;
+
+	// The interface does not exist
+	init_req(&req, RTM_NEWADDR, NLM_F_CREATE | NLM_F_ACK,
+		 sizeof(struct ifaddrmsg));
+	req.ifa.ifa_family = AF_INET;
+	req.ifa.ifa_prefixlen = 24;
+	req.ifa.ifa_index = 0x7fff;
+	add_addr_attr(&req, IFA_LOCAL, TEST_ADDR);
+	TEST_ERRNO(send_req(&req), ENODEV);
+
+	// The address does not exist
+	init_req(&req, RTM_DELADDR, NLM_F_ACK, sizeof(struct ifaddrmsg));
+	req.ifa.ifa_family = AF_INET;
+	req.ifa.ifa_prefixlen = 8;
+	req.ifa.ifa_index = lo_index;
+	add_addr_attr(&req, IFA_LOCAL, "127.0.0.9");
+	TEST_ERRNO(send_req(&req), EADDRNOTAVAIL);
+}
+END_TEST()
+
+FN_TEST(link_invalid)
+{
+	struct rtnl_req req;
+
+	// The interface does not exist
+	init_req(&req, RTM_NEWLINK, NLM_F_ACK, sizeof(struct ifinfomsg));
+	req.ifi.ifi_family = AF_UNSPEC;
+	req.ifi.ifi_index = 0x7fff;
+	TEST_ERRNO(send_req(&req), ENODEV);
+
+	// Updating the interface without changing anything should succeed
+	init_req(&req, RTM_NEWLINK, NLM_F_ACK, sizeof(struct ifinfomsg));
+	req.ifi.ifi_family = AF_UNSPEC;
+	req.ifi.ifi_index = lo_index;
+	TEST_RES(send_req(&req), _ret == NLMSG_ERROR);
+}
+END_TEST()
@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#include <netinet/in.h>
+#include <netinet/tcp.h>
+#include <sys/socket.h>
+#include <fcntl.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+#define TCP_CA_NAME_MAX 16
+
+#define SYSCTL_CONGESTION "/proc/sys/net/ipv4/tcp_congestion_control"
+#define SYSCTL_AVAILABLE "/proc/sys/net/ipv4/tcp_available_congestion_control"
+
+static int get_congestion(int sk, char *name)
+{
+	socklen_t len = TCP_CA_NAME_MAX;
+
+	memset(name, 0, TCP_CA_NAME_MAX);
+	return getsockopt(sk, IPPROTO_TCP, TCP_CONGESTION, name, &len);
+}
+
+static int set_congestion(int sk, const char *name)
+{
+	return setsockopt(sk, IPPROTO_TCP, TCP_CONGESTION, name, strlen(name));
+}
+
+static int read_sysctl(const char *path, char *buf, size_t len)
+{
+	int fd, ret;
+
+	memset(buf, 0, len);
+
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return -1;
+	ret = read(fd, buf, len - 1);
+	close(fd);
+
+	return ret;
+}
+
+static int write_sysctl(const char *path, const char *buf)
+{
+	int fd, ret;
+
+	fd = open(path, O_WRONLY);
+	if (fd < 0)
+		return -1;
+	ret = write(fd, buf, strlen(buf));
+	close(fd);
+
+	return ret;
+}
+
+FN_TEST(sysctl)
+{
+	char buf[64];
+
+	TEST_RES(read_sysctl(SYSCTL_AVAILABLE, buf, sizeof(buf)),
+		 strstr(buf, "reno") != NULL && strstr(buf, "cubic") != NULL);
+	TEST_RES(read_sysctl(SYSCTL_CONGESTION, buf, sizeof(buf)),
+		 strcmp(buf, "cubic\n") == 0);
+
+	TEST_ERRNO(write_sysctl(SYSCTL_CONGESTION, "invalid\n"), ENOENT);
+	TEST_RES(read_sysctl(SYSCTL_CONGESTION, buf, sizeof(buf)),
+		 strcmp(buf, "cubic\n") == 0);
+}
+END_TEST()
+
+FN_TEST(default_congestion)
+{
+	char name[TCP_CA_NAME_MAX];
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+	TEST_RES(get_congestion(sk, name), strcmp(name, "cubic") == 0);
+	TEST_SUCC(close(sk));
+
+	// The default algorithm only affects new sockets.
+	TEST_RES(write_sysctl(SYSCTL_CONGESTION, "reno\n"), _ret == 5);
+	sk = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+	TEST_RES(get_congestion(sk, name), strcmp(name, "reno") == 0);
+	TEST_RES(write_sysctl(SYSCTL_CONGESTION, "cubic\n"), _ret == 6);
+	TEST_RES(get_congestion(sk, name), strcmp(name, "reno") == 0);
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(set_congestion)
+{
+	char name[TCP_CA_NAME_MAX];
+	int sk;
+
+	sk = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+
+	TEST_SUCC(set_congestion(sk, "reno"));
+	TEST_RES(get_congestion(sk, name), strcmp(name, "reno") == 0);
+
+	// The trailing NUL byte is allowed.
+	TEST_SUCC(setsockopt(sk, IPPROTO_TCP, TCP_CONGESTION, "cubic", 6));
+	TEST_RES(get_congestion(sk, name), strcmp(name, "cubic") == 0);
+
+	TEST_ERRNO(set_congestion(sk, "invalid"), ENOENT);
+	TEST_RES(get_congestion(sk, name), strcmp(name, "cubic") == 0);
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(tcp_info)
+{
+	struct sockaddr_in addr = { .sin_family = AF_INET };
+	socklen_t addrlen = sizeof(addr);
+	struct tcp_info info;
+	socklen_t len;
+	char name[TCP_CA_NAME_MAX];
+	char buf[4096] = { 0 };
+	int sk_listen, sk_connect, sk_accept;
+	int i;
+
+	sk_listen = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+	sk_connect = TEST_SUCC(socket(AF_INET, SOCK_STREAM, 0));
+
+	len = sizeof(info);
+	TEST_RES(getsockopt(sk_connect, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == sizeof(info) && info.tcpi_state == TCP_CLOSE);
+
+	addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+	TEST_SUCC(bind(sk_listen, (struct sockaddr *)&addr, sizeof(addr)));
+	TEST_SUCC(getsockname(sk_listen, (struct sockaddr *)&addr, &addrlen));
+	TEST_SUCC(set_congestion(sk_listen, "reno"));
+	TEST_SUCC(listen(sk_listen, 1));
+
+	len = sizeof(info);
+	TEST_RES(getsockopt(sk_listen, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == sizeof(info) && info.tcpi_state == TCP_LISTEN);
+
+	TEST_SUCC(connect(sk_connect, (struct sockaddr *)&addr, sizeof(addr)));
+	sk_accept = TEST_SUCC(accept(sk_listen, NULL, NULL));
+
+	// Accepted sockets inherit the algorithm from the listening socket.
+	TEST_RES(get_congestion(sk_accept, name), strcmp(name, "reno") == 0);
+
+	for (i = 0; i < 16; ++i) {
+		TEST_RES(write(sk_connect, buf, sizeof(buf)),
+			 _ret == sizeof(buf));
+		TEST_RES(read(sk_accept, buf, sizeof(buf)), _ret > 0);
+	}
+
+	len = sizeof(info);
+	TEST_RES(getsockopt(sk_connect, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == sizeof(info) && info.tcpi_state == TCP_ESTABLISHED &&
+			 info.tcpi_snd_cwnd >= 10 &&
+			 info.tcpi_snd_ssthresh > 0 && info.tcpi_rto > 0 &&
+			 info.tcpi_snd_mss > 0);
+
+	// A short buffer receives a truncated structure.
+	len = 8;
+	TEST_RES(getsockopt(sk_connect, IPPROTO_TCP, TCP_INFO, &info, &len),
+		 len == 8 && info.tcpi_state == TCP_ESTABLISHED);
+
+	// The algorithm can be changed for established connections.
+	TEST_SUCC(set_congestion(sk_connect, "bbr"));
+	TEST_RES(get_congestion(sk_connect, name), strcmp(name, "bbr") == 0);
+	TEST_RES(write(sk_connect, buf, sizeof(buf)), _ret == sizeof(buf));
+	TEST_RES(read(sk_accept, buf, sizeof(buf)), _ret > 0);
+
+	TEST_SUCC(close(sk_accept));
+	TEST_SUCC(close(sk_connect));
+	TEST_SUCC(close(sk_listen));
+}
+END_TEST()
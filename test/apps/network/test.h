@@ -29,12 +29,17 @@
  * finishes, a summary sentence is printed describing the number of test
  * failures. The program will exit with a non-zero code if and only if there is
  * at least one test failure.
+ *
+ *  - To test the behavior of unprivileged processes, run_unprivileged() can be
+ * used to run a function in a child process that has dropped its privileges.
  */
 
 #include <errno.h>
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
 
 /** Starts the definition of a setup function. */
 #define FN_SETUP(name)                                                        \
@@ -158,6 +163,37 @@ static int __total_failures;
  */
 #define TEST_RES(func, cond) TEST(func, 0, cond)
 
+/**
+ * Runs a function in an unprivileged child process and returns its exit status.
+ *
+ * The child process switches to the user and group 65534 (i.e., nobody), which
+ * drops all of its capabilities, and then exits with the return value of the
+ * function. The exit status is 10 if the privileges cannot be dropped, so the
+ * function should use other non-zero values to report failures.
+ *
+ * This function returns -1 if the child process cannot be created or does not
+ * exit normally.
+ */
+static inline int run_unprivileged(int (*func)(void))
+{
+	pid_t pid;
+	int status;
+
+	pid = fork();
+	if (pid < 0)
+		return -1;
+
+	if (pid == 0) {
+		if (setgid(65534) < 0 || setuid(65534) < 0)
+			_exit(10);
+		_exit(func());
+	}
+
+	if (waitpid(pid, &status, 0) != pid || !WIFEXITED(status))
+		return -1;
+	return WEXITSTATUS(status);
+}
+
 int main(void)
 {
 	return __total_failures ? 1 : 0;
@@ -0,0 +1,352 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <stddef.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+#define PATH_ADDR "/tmp/unix_dgram_sock"
+#define ABSTRACT_NAME "unix_dgram_abstract"
+
+static struct sockaddr_un path_addr = { .sun_family = AF_UNIX,
+					.sun_path = PATH_ADDR };
+#define PATH_ADDRLEN \
+	(offsetof(struct sockaddr_un, sun_path) + sizeof(PATH_ADDR))
+
+static struct sockaddr_un abstract_addr = { .sun_family = AF_UNIX,
+					    .sun_path = "\0" ABSTRACT_NAME };
+#define ABSTRACT_ADDRLEN \
+	(offsetof(struct sockaddr_un, sun_path) + sizeof(ABSTRACT_NAME))
+
+static int sk_path;
+static int sk_abstract;
+static int sk_unbound;
+
+FN_SETUP(general)
+{
+	unlink(PATH_ADDR);
+
+	sk_path = CHECK(socket(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+	CHECK(bind(sk_path, (struct sockaddr *)&path_addr, PATH_ADDRLEN));
+
+	sk_abstract = CHECK(socket(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+	CHECK(bind(sk_abstract, (struct sockaddr *)&abstract_addr,
+		   ABSTRACT_ADDRLEN));
+
+	sk_unbound = CHECK(socket(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+}
+END_SETUP()
+
+static char buf[16];
+static struct sockaddr_un recv_addr;
+static socklen_t recv_addrlen;
+
+static ssize_t recv_from(int sk, size_t len, int flags)
+{
+	memset(buf, 0, sizeof(buf));
+	memset(&recv_addr, 0, sizeof(recv_addr));
+	recv_addrlen = sizeof(recv_addr);
+
+	return recvfrom(sk, buf, len, flags, (struct sockaddr *)&recv_addr,
+			&recv_addrlen);
+}
+
+static int recv_flags;
+
+static ssize_t recv_msg(int sk, size_t len, int flags)
+{
+	struct iovec iov = { .iov_base = buf, .iov_len = len };
+	struct msghdr msg = { .msg_iov = &iov, .msg_iovlen = 1 };
+	ssize_t ret;
+
+	memset(buf, 0, sizeof(buf));
+	ret = recvmsg(sk, &msg, flags);
+	recv_flags = msg.msg_flags;
+
+	return ret;
+}
+
+FN_TEST(addr)
+{
+	struct sockaddr_un addr;
+	socklen_t addrlen;
+
+	addrlen = sizeof(addr);
+	TEST_RES(getsockname(sk_path, (struct sockaddr *)&addr, &addrlen),
+		 addrlen == PATH_ADDRLEN &&
+			 strcmp(addr.sun_path, PATH_ADDR) == 0);
+
+	addrlen = sizeof(addr);
+	TEST_RES(getsockname(sk_unbound, (struct sockaddr *)&addr, &addrlen),
+		 addrlen == sizeof(sa_family_t));
+
+	addrlen = sizeof(addr);
+	TEST_ERRNO(getpeername(sk_path, (struct sockaddr *)&addr, &addrlen),
+		   ENOTCONN);
+
+	// The address is already in use.
+	int sk = TEST_SUCC(socket(PF_UNIX, SOCK_DGRAM, 0));
+	TEST_ERRNO(bind(sk, (struct sockaddr *)&path_addr, PATH_ADDRLEN),
+		   EADDRINUSE);
+	TEST_ERRNO(bind(sk, (struct sockaddr *)&abstract_addr,
+			ABSTRACT_ADDRLEN),
+		   EADDRINUSE);
+	TEST_SUCC(close(sk));
+
+	// The socket is already bound.
+	TEST_ERRNO(bind(sk_path, (struct sockaddr *)&abstract_addr,
+			ABSTRACT_ADDRLEN),
+		   EINVAL);
+}
+END_TEST()
+
+FN_TEST(sendto_unconnected)
+{
+	TEST_ERRNO(recv_from(sk_path, sizeof(buf), 0), EAGAIN);
+
+	// From an unbound socket
+	TEST_RES(sendto(sk_unbound, "abc", 3, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 3);
+	TEST_RES(recv_from(sk_path, sizeof(buf), 0),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0 && recv_addrlen == 0);
+
+	// From a socket bound to a path
+	TEST_RES(sendto(sk_path, "de", 2, 0, (struct sockaddr *)&abstract_addr,
+			ABSTRACT_ADDRLEN),
+		 _ret == 2);
+	TEST_RES(recv_from(sk_abstract, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0 &&
+			 recv_addrlen == PATH_ADDRLEN &&
+			 strcmp(recv_addr.sun_path, PATH_ADDR) == 0);
+
+	// From a socket bound to an abstract name
+	TEST_RES(sendto(sk_abstract, "f", 1, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 1);
+	TEST_RES(recv_from(sk_path, sizeof(buf), 0),
+		 _ret == 1 && buf[0] == 'f' &&
+			 recv_addrlen == ABSTRACT_ADDRLEN &&
+			 memcmp(recv_addr.sun_path, abstract_addr.sun_path,
+				sizeof(ABSTRACT_NAME)) == 0);
+
+	TEST_ERRNO(send(sk_unbound, "g", 1, 0), ENOTCONN);
+}
+END_TEST()
+
+FN_TEST(message_boundaries)
+{
+	TEST_RES(sendto(sk_unbound, "abc", 3, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 3);
+	TEST_RES(sendto(sk_unbound, "de", 2, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 2);
+	TEST_RES(sendto(sk_unbound, "", 0, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 0);
+	TEST_RES(sendto(sk_unbound, "fghi", 4, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 4);
+	TEST_RES(sendto(sk_unbound, "jklm", 4, 0, (struct sockaddr *)&path_addr,
+			PATH_ADDRLEN),
+		 _ret == 4);
+
+	TEST_RES(recv_msg(sk_path, sizeof(buf), 0),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0 && recv_flags == 0);
+	TEST_RES(recv_msg(sk_path, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0 && recv_flags == 0);
+	TEST_RES(recv_msg(sk_path, sizeof(buf), 0),
+		 _ret == 0 && recv_flags == 0);
+
+	// The rest of the message is discarded if the buffer is too small.
+	TEST_RES(recv_msg(sk_path, 2, 0),
+		 _ret == 2 && memcmp(buf, "fg\0", 3) == 0 &&
+			 recv_flags == MSG_TRUNC);
+	TEST_RES(recv_msg(sk_path, 2, MSG_TRUNC),
+		 _ret == 4 && memcmp(buf, "jk\0", 3) == 0 &&
+			 recv_flags == MSG_TRUNC);
+
+	TEST_ERRNO(recv_msg(sk_path, sizeof(buf), 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(connect)
+{
+	struct sockaddr_un addr;
+	socklen_t addrlen;
+
+	int sk = TEST_SUCC(socket(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+
+	TEST_SUCC(connect(sk, (struct sockaddr *)&path_addr, PATH_ADDRLEN));
+	addrlen = sizeof(addr);
+	TEST_RES(getpeername(sk, (struct sockaddr *)&addr, &addrlen),
+		 addrlen == PATH_ADDRLEN &&
+			 strcmp(addr.sun_path, PATH_ADDR) == 0);
+
+	TEST_RES(send(sk, "abc", 3, 0), _ret == 3);
+	TEST_RES(recv_from(sk_path, sizeof(buf), 0),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0);
+
+	// Messages can still be sent to other addresses.
+	TEST_RES(sendto(sk, "de", 2, 0, (struct sockaddr *)&abstract_addr,
+			ABSTRACT_ADDRLEN),
+		 _ret == 2);
+	TEST_RES(recv_from(sk_abstract, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0);
+
+	// The socket can be connected again.
+	TEST_SUCC(connect(sk, (struct sockaddr *)&abstract_addr,
+			  ABSTRACT_ADDRLEN));
+	TEST_RES(write(sk, "f", 1), _ret == 1);
+	TEST_RES(read(sk_abstract, buf, sizeof(buf)), _ret == 1 && buf[0] == 'f');
+
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_TEST(connected_receiver)
+{
+	struct sockaddr_un addr = { .sun_family = AF_UNIX,
+				    .sun_path = "\0unix_dgram_receiver" };
+	socklen_t addrlen = offsetof(struct sockaddr_un, sun_path) + 20;
+
+	int sk = TEST_SUCC(socket(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0));
+	TEST_SUCC(bind(sk, (struct sockaddr *)&addr, addrlen));
+	TEST_SUCC(connect(sk, (struct sockaddr *)&path_addr, PATH_ADDRLEN));
+
+	// A connected socket only accepts messages from its peer.
+	TEST_ERRNO(sendto(sk_unbound, "a", 1, 0, (struct sockaddr *)&addr,
+			  addrlen),
+		   EPERM);
+	TEST_ERRNO(sendto(sk_abstract, "a", 1, 0, (struct sockaddr *)&addr,
+			  addrlen),
+		   EPERM);
+	TEST_RES(sendto(sk_path, "b", 1, 0, (struct sockaddr *)&addr, addrlen),
+		 _ret == 1);
+	TEST_RES(recv_from(sk, sizeof(buf), 0),
+		 _ret == 1 && buf[0] == 'b' && recv_addrlen == PATH_ADDRLEN);
+
+	// Other sockets cannot connect to it either.
+	int sk2 = TEST_SUCC(socket(PF_UNIX, SOCK_DGRAM, 0));
+	TEST_ERRNO(connect(sk2, (struct sockaddr *)&addr, addrlen), EPERM);
+	TEST_SUCC(close(sk2));
+
+	TEST_SUCC(close(sk));
+
+	// The address is released after the socket is closed.
+	TEST_ERRNO(sendto(sk_path, "c", 1, 0, (struct sockaddr *)&addr,
+			  addrlen),
+		   ECONNREFUSED);
+}
+END_TEST()
+
+FN_TEST(invalid_target)
+{
+	struct sockaddr_un addr = { .sun_family = AF_UNIX,
+				    .sun_path = "\0unix_dgram_none" };
+	socklen_t addrlen = offsetof(struct sockaddr_un, sun_path) + 16;
+
+	TEST_ERRNO(sendto(sk_unbound, "a", 1, 0, (struct sockaddr *)&addr,
+			  addrlen),
+		   ECONNREFUSED);
+	TEST_ERRNO(connect(sk_unbound, (struct sockaddr *)&addr, addrlen),
+		   ECONNREFUSED);
+
+	strcpy(addr.sun_path, "/tmp/unix_dgram_none");
+	addrlen = sizeof(addr);
+	TEST_ERRNO(sendto(sk_unbound, "a", 1, 0, (struct sockaddr *)&addr,
+			  addrlen),
+		   ENOENT);
+
+	// The message is too large.
+	static char large_buf[300000];
+	TEST_ERRNO(sendto(sk_unbound, large_buf, sizeof(large_buf), 0,
+			  (struct sockaddr *)&path_addr, PATH_ADDRLEN),
+		   EMSGSIZE);
+}
+END_TEST()
+
+FN_TEST(queue_full)
+{
+	int i;
+
+	// Linux allows a maximum of `max_dgram_qlen + 1` (i.e., 11) messages.
+	for (i = 0; i < 11; ++i)
+		TEST_RES(sendto(sk_unbound, "a", 1, 0,
+				(struct sockaddr *)&path_addr, PATH_ADDRLEN),
+			 _ret == 1);
+	TEST_ERRNO(sendto(sk_unbound, "a", 1, 0, (struct sockaddr *)&path_addr,
+			  PATH_ADDRLEN),
+		   EAGAIN);
+
+	for (i = 0; i < 11; ++i)
+		TEST_RES(recv_from(sk_path, sizeof(buf), 0), _ret == 1);
+	TEST_ERRNO(recv_from(sk_path, sizeof(buf), 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(socketpair)
+{
+	int sk[2];
+
+	TEST_SUCC(socketpair(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0, sk));
+
+	TEST_RES(send(sk[0], "abc", 3, 0), _ret == 3);
+	TEST_RES(send(sk[0], "de", 2, 0), _ret == 2);
+	TEST_RES(send(sk[1], "f", 1, 0), _ret == 1);
+
+	TEST_RES(recv_from(sk[1], sizeof(buf), 0),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0 && recv_addrlen == 0);
+	TEST_RES(recv_from(sk[1], sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0);
+	TEST_RES(recv_from(sk[0], sizeof(buf), 0), _ret == 1 && buf[0] == 'f');
+
+	// The peer does not limit the number of messages.
+	for (int i = 0; i < 20; ++i)
+		TEST_RES(send(sk[0], "a", 1, 0), _ret == 1);
+
+	// The socket is disconnected after the peer is closed.
+	TEST_SUCC(close(sk[1]));
+	TEST_ERRNO(send(sk[0], "a", 1, 0), ECONNREFUSED);
+	TEST_ERRNO(send(sk[0], "a", 1, 0), ENOTCONN);
+
+	TEST_SUCC(close(sk[0]));
+}
+END_TEST()
+
+FN_TEST(shutdown)
+{
+	int sk[2];
+
+	TEST_SUCC(socketpair(PF_UNIX, SOCK_DGRAM | SOCK_NONBLOCK, 0, sk));
+
+	TEST_SUCC(shutdown(sk[0], SHUT_WR));
+	TEST_ERRNO(send(sk[0], "a", 1, MSG_NOSIGNAL), EPIPE);
+	TEST_RES(send(sk[1], "b", 1, 0), _ret == 1);
+
+	TEST_SUCC(shutdown(sk[0], SHUT_RD));
+	TEST_ERRNO(send(sk[1], "c", 1, MSG_NOSIGNAL), EPIPE);
+
+	// The remaining messages can still be received.
+	TEST_RES(recv_from(sk[0], sizeof(buf), 0), _ret == 1 && buf[0] == 'b');
+
+	TEST_SUCC(close(sk[0]));
+	TEST_SUCC(close(sk[1]));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_path));
+	CHECK(close(sk_abstract));
+	CHECK(close(sk_unbound));
+
+	CHECK(unlink(PATH_ADDR));
+}
+END_SETUP()
@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <sys/wait.h>
+#include <fcntl.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+#define MAX_FDS 4
+
+static int sk_pair[2];
+static int pipe_fds[2];
+
+FN_SETUP(general)
+{
+	CHECK(socketpair(PF_UNIX, SOCK_STREAM, 0, sk_pair));
+	CHECK(pipe(pipe_fds));
+}
+END_SETUP()
+
+static union {
+	char buf[CMSG_SPACE(sizeof(int) * MAX_FDS) +
+		 CMSG_SPACE(sizeof(struct ucred))];
+	struct cmsghdr align;
+} control;
+
+static char data[16];
+static struct msghdr msg;
+static struct iovec iov;
+
+static void init_msg(const char *buf, size_t len, size_t control_len)
+{
+	memset(&msg, 0, sizeof(msg));
+	memset(&control, 0, sizeof(control));
+
+	iov.iov_base = (void *)buf;
+	iov.iov_len = len;
+	msg.msg_iov = &iov;
+	msg.msg_iovlen = 1;
+
+	if (control_len != 0) {
+		msg.msg_control = control.buf;
+		msg.msg_controllen = control_len;
+	}
+}
+
+static ssize_t send_fds(int sk, const char *buf, const int *fds, int nfds)
+{
+	init_msg(buf, strlen(buf), CMSG_SPACE(sizeof(int) * nfds));
+
+	struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+	cmsg->cmsg_level = SOL_SOCKET;
+	cmsg->cmsg_type = SCM_RIGHTS;
+	cmsg->cmsg_len = CMSG_LEN(sizeof(int) * nfds);
+	memcpy(CMSG_DATA(cmsg), fds, sizeof(int) * nfds);
+
+	return sendmsg(sk, &msg, 0);
+}
+
+static ssize_t send_cred(int sk, const char *buf, pid_t pid, uid_t uid,
+			 gid_t gid)
+{
+	init_msg(buf, strlen(buf), CMSG_SPACE(sizeof(struct ucred)));
+
+	struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+	struct ucred cred = { .pid = pid, .uid = uid, .gid = gid };
+	cmsg->cmsg_level = SOL_SOCKET;
+	cmsg->cmsg_type = SCM_CREDENTIALS;
+	cmsg->cmsg_len = CMSG_LEN(sizeof(struct ucred));
+	memcpy(CMSG_DATA(cmsg), &cred, sizeof(cred));
+
+	return sendmsg(sk, &msg, 0);
+}
+
+static ssize_t recv_msg(int sk, size_t len, size_t control_len, int flags)
+{
+	memset(data, 0, sizeof(data));
+	init_msg(data, len, control_len);
+
+	return recvmsg(sk, &msg, flags);
+}
+
+// Returns the number of received file descriptors and stores them in `fds`.
+static int get_fds(int *fds)
+{
+	struct cmsghdr *cmsg;
+
+	for (cmsg = CMSG_FIRSTHDR(&msg); cmsg != NULL;
+	     cmsg = CMSG_NXTHDR(&msg, cmsg)) {
+		if (cmsg->cmsg_level != SOL_SOCKET ||
+		    cmsg->cmsg_type != SCM_RIGHTS)
+			continue;
+
+		int nfds = (cmsg->cmsg_len - CMSG_LEN(0)) / sizeof(int);
+		memcpy(fds, CMSG_DATA(cmsg), sizeof(int) * nfds);
+		return nfds;
+	}
+
+	return 0;
+}
+
+// Returns whether the received credentials match the arguments.
+static int check_cred(pid_t pid, uid_t uid, gid_t gid)
+{
+	struct cmsghdr *cmsg;
+
+	for (cmsg = CMSG_FIRSTHDR(&msg); cmsg != NULL;
+	     cmsg = CMSG_NXTHDR(&msg, cmsg)) {
+		if (cmsg->cmsg_level != SOL_SOCKET ||
+		    cmsg->cmsg_type != SCM_CREDENTIALS)
+			continue;
+
+		struct ucred cred;
+		memcpy(&cred, CMSG_DATA(cmsg), sizeof(cred));
+		return cmsg->cmsg_len == CMSG_LEN(sizeof(cred)) &&
+		       cred.pid == pid && cred.uid == uid && cred.gid == gid;
+	}
+
+	return 0;
+}
+
+// Returns whether the pipe can be accessed via the received file descriptor.
+static int check_pipe(int fd)
+{
+	char c = 0;
+
+	if (write(pipe_fds[1], "x", 1) != 1)
+		return 0;
+	if (read(fd, &c, 1) != 1)
+		return 0;
+
+	return c == 'x';
+}
+
+FN_TEST(scm_rights)
+{
+	int fds[MAX_FDS];
+
+	TEST_RES(send_fds(sk_pair[0], "a", &pipe_fds[0], 1), _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'a' && msg.msg_flags == 0 &&
+			 msg.msg_controllen == CMSG_SPACE(sizeof(int)) &&
+			 get_fds(fds) == 1 && fds[0] != pipe_fds[0]);
+
+	TEST_RES(check_pipe(fds[0]), _ret == 1);
+	TEST_RES(fcntl(fds[0], F_GETFD), _ret == 0);
+	TEST_SUCC(close(fds[0]));
+
+	// The received file descriptors can be closed on exec.
+	TEST_RES(send_fds(sk_pair[0], "b", &pipe_fds[0], 1), _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control),
+			  MSG_CMSG_CLOEXEC),
+		 _ret == 1 && data[0] == 'b' && get_fds(fds) == 1);
+	TEST_RES(fcntl(fds[0], F_GETFD), _ret == FD_CLOEXEC);
+	TEST_SUCC(close(fds[0]));
+}
+END_TEST()
+
+FN_TEST(scm_rights_ctrunc)
+{
+	int fds[MAX_FDS];
+	int sent_fds[2] = { pipe_fds[0], pipe_fds[1] };
+
+	// There is only space for one file descriptor.
+	TEST_RES(send_fds(sk_pair[0], "a", sent_fds, 2), _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), CMSG_LEN(sizeof(int)), 0),
+		 _ret == 1 && msg.msg_flags == MSG_CTRUNC &&
+			 msg.msg_controllen == CMSG_LEN(sizeof(int)) &&
+			 get_fds(fds) == 1);
+	TEST_RES(check_pipe(fds[0]), _ret == 1);
+	TEST_SUCC(close(fds[0]));
+
+	// There is no space for any file descriptors.
+	TEST_RES(send_fds(sk_pair[0], "b", sent_fds, 2), _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), 0, 0),
+		 _ret == 1 && data[0] == 'b' && msg.msg_flags == MSG_CTRUNC &&
+			 msg.msg_controllen == 0);
+}
+END_TEST()
+
+FN_TEST(scm_rights_boundaries)
+{
+	int fds[MAX_FDS];
+
+	// The data with files can be merged with the data before it.
+	TEST_RES(send(sk_pair[0], "ab", 2, 0), _ret == 2);
+	TEST_RES(send_fds(sk_pair[0], "c", &pipe_fds[0], 1), _ret == 1);
+	TEST_RES(send(sk_pair[0], "d", 1, 0), _ret == 1);
+
+	// But it cannot be merged with the data after it.
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 3 && memcmp(data, "abc", 3) == 0 && get_fds(fds) == 1);
+	TEST_SUCC(close(fds[0]));
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'd' && msg.msg_controllen == 0);
+
+	// The files are received with the first byte of the data.
+	TEST_RES(send_fds(sk_pair[0], "ef", &pipe_fds[0], 1), _ret == 2);
+	TEST_RES(recv_msg(sk_pair[1], 1, sizeof(control), 0),
+		 _ret == 1 && data[0] == 'e' && get_fds(fds) == 1);
+	TEST_SUCC(close(fds[0]));
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'f' && msg.msg_controllen == 0);
+}
+END_TEST()
+
+FN_TEST(scm_rights_invalid)
+{
+	int bad_fd = 1000;
+
+	TEST_ERRNO(send_fds(sk_pair[0], "a", &bad_fd, 1), EBADF);
+
+	// The control message is longer than the buffer.
+	init_msg("a", 1, CMSG_SPACE(sizeof(int)));
+	CMSG_FIRSTHDR(&msg)->cmsg_level = SOL_SOCKET;
+	CMSG_FIRSTHDR(&msg)->cmsg_type = SCM_RIGHTS;
+	CMSG_FIRSTHDR(&msg)->cmsg_len = CMSG_SPACE(sizeof(int)) + 1;
+	TEST_ERRNO(sendmsg(sk_pair[0], &msg, 0), EINVAL);
+
+	// The control message type is unknown.
+	init_msg("a", 1, CMSG_SPACE(sizeof(int)));
+	CMSG_FIRSTHDR(&msg)->cmsg_level = SOL_SOCKET;
+	CMSG_FIRSTHDR(&msg)->cmsg_type = 100;
+	CMSG_FIRSTHDR(&msg)->cmsg_len = CMSG_LEN(sizeof(int));
+	TEST_ERRNO(sendmsg(sk_pair[0], &msg, 0), EINVAL);
+
+	// The credentials have a wrong length.
+	init_msg("a", 1, CMSG_SPACE(sizeof(int)));
+	CMSG_FIRSTHDR(&msg)->cmsg_level = SOL_SOCKET;
+	CMSG_FIRSTHDR(&msg)->cmsg_type = SCM_CREDENTIALS;
+	CMSG_FIRSTHDR(&msg)->cmsg_len = CMSG_LEN(sizeof(int));
+	TEST_ERRNO(sendmsg(sk_pair[0], &msg, 0), EINVAL);
+}
+END_TEST()
+
+FN_TEST(scm_credentials)
+{
+	int passcred;
+	socklen_t optlen = sizeof(passcred);
+
+	TEST_RES(getsockopt(sk_pair[1], SOL_SOCKET, SO_PASSCRED, &passcred,
+			    &optlen),
+		 passcred == 0 && optlen == sizeof(passcred));
+
+	// The credentials are not received without `SO_PASSCRED`.
+	TEST_RES(send_cred(sk_pair[0], "a", getpid(), getuid(), getgid()),
+		 _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && msg.msg_controllen == 0);
+
+	passcred = 1;
+	TEST_SUCC(setsockopt(sk_pair[1], SOL_SOCKET, SO_PASSCRED, &passcred,
+			     sizeof(passcred)));
+	TEST_RES(getsockopt(sk_pair[1], SOL_SOCKET, SO_PASSCRED, &passcred,
+			    &optlen),
+		 passcred == 1);
+
+	// The credentials are received even if they are not sent explicitly.
+	TEST_RES(send(sk_pair[0], "b", 1, 0), _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'b' &&
+			 check_cred(getpid(), getuid(), getgid()));
+
+	// The data with different credentials cannot be merged.
+	TEST_RES(send_cred(sk_pair[0], "c", getpid(), 1, 1), _ret == 1);
+	TEST_RES(send(sk_pair[0], "d", 1, 0), _ret == 1);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'c' && check_cred(getpid(), 1, 1));
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'd' &&
+			 check_cred(getpid(), getuid(), getgid()));
+
+	passcred = 0;
+	TEST_SUCC(setsockopt(sk_pair[1], SOL_SOCKET, SO_PASSCRED, &passcred,
+			     sizeof(passcred)));
+}
+END_TEST()
+
+static int scm_credentials_unprivileged(void)
+{
+	// The credentials of other users cannot be sent.
+	if (send_cred(sk_pair[0], "a", getpid(), 0, 65534) != -1 ||
+	    errno != EPERM)
+		return 11;
+	if (send_cred(sk_pair[0], "a", getpid(), 65534, 0) != -1 ||
+	    errno != EPERM)
+		return 12;
+	if (send_cred(sk_pair[0], "a", getppid(), 65534, 65534) != -1 ||
+	    errno != EPERM)
+		return 13;
+
+	if (send_cred(sk_pair[0], "a", getpid(), 65534, 65534) != 1)
+		return 14;
+
+	return 0;
+}
+
+FN_TEST(scm_credentials_unprivileged)
+{
+	TEST_RES(run_unprivileged(scm_credentials_unprivileged), _ret == 0);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(data), sizeof(control), 0),
+		 _ret == 1 && data[0] == 'a');
+}
+END_TEST()
+
+FN_TEST(peer_cred)
+{
+	struct ucred cred;
+	socklen_t optlen = sizeof(cred);
+	int sk;
+
+	TEST_RES(getsockopt(sk_pair[0], SOL_SOCKET, SO_PEERCRED, &cred,
+			    &optlen),
+		 optlen == sizeof(cred) && cred.pid == getpid() &&
+			 cred.uid == geteuid() && cred.gid == getegid());
+
+	// The peer of an unconnected socket is unknown.
+	sk = TEST_SUCC(socket(PF_UNIX, SOCK_STREAM, 0));
+	TEST_RES(getsockopt(sk, SOL_SOCKET, SO_PEERCRED, &cred, &optlen),
+		 cred.pid == 0 && cred.uid == (uid_t)-1 &&
+			 cred.gid == (gid_t)-1);
+
+	// The option cannot be set.
+	TEST_ERRNO(setsockopt(sk, SOL_SOCKET, SO_PEERCRED, &cred, optlen),
+		   ENOPROTOOPT);
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_pair[0]));
+	CHECK(close(sk_pair[1]));
+	CHECK(close(pipe_fds[0]));
+	CHECK(close(pipe_fds[1]));
+}
+END_SETUP()
@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <stddef.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test.h"
+
+#define PATH_ADDR "/tmp/unix_seqpacket_sock"
+
+static struct sockaddr_un path_addr = { .sun_family = AF_UNIX,
+					.sun_path = PATH_ADDR };
+#define PATH_ADDRLEN \
+	(offsetof(struct sockaddr_un, sun_path) + sizeof(PATH_ADDR))
+
+static int sk_pair[2];
+static int sk_listen;
+
+FN_SETUP(general)
+{
+	unlink(PATH_ADDR);
+
+	CHECK(socketpair(PF_UNIX, SOCK_SEQPACKET | SOCK_NONBLOCK, 0, sk_pair));
+
+	sk_listen = CHECK(socket(PF_UNIX, SOCK_SEQPACKET | SOCK_NONBLOCK, 0));
+	CHECK(bind(sk_listen, (struct sockaddr *)&path_addr, PATH_ADDRLEN));
+	CHECK(listen(sk_listen, 2));
+}
+END_SETUP()
+
+static char buf[16];
+static int recv_flags;
+
+static ssize_t recv_msg(int sk, size_t len, int flags)
+{
+	struct iovec iov = { .iov_base = buf, .iov_len = len };
+	struct msghdr msg = { .msg_iov = &iov, .msg_iovlen = 1 };
+	ssize_t ret;
+
+	memset(buf, 0, sizeof(buf));
+	ret = recvmsg(sk, &msg, flags);
+	recv_flags = msg.msg_flags;
+
+	return ret;
+}
+
+FN_TEST(message_boundaries)
+{
+	TEST_RES(send(sk_pair[0], "abc", 3, 0), _ret == 3);
+	TEST_RES(send(sk_pair[0], "de", 2, MSG_EOR), _ret == 2);
+	TEST_RES(send(sk_pair[0], "fghi", 4, 0), _ret == 4);
+	TEST_RES(send(sk_pair[0], "jklm", 4, 0), _ret == 4);
+
+	TEST_RES(recv_msg(sk_pair[1], sizeof(buf), 0),
+		 _ret == 3 && memcmp(buf, "abc", 3) == 0 && recv_flags == 0);
+	TEST_RES(recv_msg(sk_pair[1], sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "de", 2) == 0 && recv_flags == 0);
+
+	// The rest of the message is discarded if the buffer is too small.
+	TEST_RES(recv_msg(sk_pair[1], 2, 0),
+		 _ret == 2 && memcmp(buf, "fg\0", 3) == 0 &&
+			 recv_flags == MSG_TRUNC);
+	TEST_RES(recv_msg(sk_pair[1], 2, MSG_TRUNC),
+		 _ret == 4 && memcmp(buf, "jk\0", 3) == 0 &&
+			 recv_flags == MSG_TRUNC);
+
+	TEST_ERRNO(recv_msg(sk_pair[1], sizeof(buf), 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(message_too_large)
+{
+	static char large_buf[300000];
+
+	TEST_ERRNO(send(sk_pair[0], large_buf, sizeof(large_buf), 0),
+		   EMSGSIZE);
+	TEST_ERRNO(recv_msg(sk_pair[1], sizeof(buf), 0), EAGAIN);
+}
+END_TEST()
+
+FN_TEST(connect_accept)
+{
+	int sk_client, sk_accepted;
+
+	sk_client = TEST_SUCC(socket(PF_UNIX, SOCK_SEQPACKET, 0));
+	TEST_SUCC(connect(sk_client, (struct sockaddr *)&path_addr,
+			  PATH_ADDRLEN));
+	sk_accepted = TEST_SUCC(accept(sk_listen, NULL, NULL));
+
+	TEST_RES(send(sk_client, "ab", 2, 0), _ret == 2);
+	TEST_RES(send(sk_client, "cd", 2, 0), _ret == 2);
+	TEST_RES(recv_msg(sk_accepted, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "ab", 2) == 0);
+	TEST_RES(recv_msg(sk_accepted, sizeof(buf), 0),
+		 _ret == 2 && memcmp(buf, "cd", 2) == 0);
+
+	// The connection is closed after the peer is closed.
+	TEST_SUCC(close(sk_client));
+	TEST_RES(recv_msg(sk_accepted, sizeof(buf), 0), _ret == 0);
+
+	TEST_SUCC(close(sk_accepted));
+}
+END_TEST()
+
+FN_TEST(wrong_type)
+{
+	int sk;
+
+	// Stream sockets cannot connect to seqpacket sockets.
+	sk = TEST_SUCC(socket(PF_UNIX, SOCK_STREAM, 0));
+	TEST_ERRNO(connect(sk, (struct sockaddr *)&path_addr, PATH_ADDRLEN),
+		   EPROTOTYPE);
+	TEST_SUCC(close(sk));
+
+	// Unconnected seqpacket sockets cannot send or receive messages.
+	sk = TEST_SUCC(socket(PF_UNIX, SOCK_SEQPACKET, 0));
+	TEST_ERRNO(send(sk, "a", 1, 0), ENOTCONN);
+	TEST_ERRNO(recv_msg(sk, sizeof(buf), 0), ENOTCONN);
+	TEST_SUCC(close(sk));
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	CHECK(close(sk_pair[0]));
+	CHECK(close(sk_pair[1]));
+	CHECK(close(sk_listen));
+
+	CHECK(unlink(PATH_ADDR));
+}
+END_SETUP()
@@ -0,0 +1,279 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <fcntl.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/prctl.h>
+#include <sys/uio.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#define PAGE_SIZE 4096
+
+// The buffer is followed by an unmapped page.
+static char *remote_buf;
+static char local_buf[PAGE_SIZE];
+static pid_t child;
+static char mem_path[64];
+
+FN_SETUP(remote_buf)
+{
+	remote_buf = mmap(NULL, 2 * PAGE_SIZE, PROT_READ | PROT_WRITE,
+			  MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+	CHECK(remote_buf == MAP_FAILED ? -1 : 0);
+	CHECK(munmap(remote_buf + PAGE_SIZE, PAGE_SIZE));
+
+	memset(remote_buf, 'a', PAGE_SIZE);
+}
+END_SETUP()
+
+FN_SETUP(fork_child)
+{
+	child = CHECK(fork());
+	if (child == 0) {
+		for (;;)
+			pause();
+	}
+
+	// The memory of the parent is changed after forking, so the memory read
+	// from the child must be the copy made at forking.
+	memset(remote_buf, 'b', PAGE_SIZE);
+
+	snprintf(mem_path, sizeof(mem_path), "/proc/%d/mem", child);
+}
+END_SETUP()
+
+static ssize_t read_remote(void *addr, size_t len)
+{
+	struct iovec local = { .iov_base = local_buf, .iov_len = len };
+	struct iovec remote = { .iov_base = addr, .iov_len = len };
+
+	memset(local_buf, 0, sizeof(local_buf));
+	return process_vm_readv(child, &local, 1, &remote, 1, 0);
+}
+
+static ssize_t write_remote(void *addr, size_t len, char c)
+{
+	struct iovec local = { .iov_base = local_buf, .iov_len = len };
+	struct iovec remote = { .iov_base = addr, .iov_len = len };
+
+	memset(local_buf, c, sizeof(local_buf));
+	return process_vm_writev(child, &local, 1, &remote, 1, 0);
+}
+
+static int local_buf_is(char c, size_t len)
+{
+	for (size_t i = 0; i < len; ++i)
+		if (local_buf[i] != c)
+			return 0;
+	return 1;
+}
+
+FN_TEST(process_vm_readv)
+{
+	TEST_RES(read_remote(remote_buf, PAGE_SIZE),
+		 _ret == PAGE_SIZE && local_buf_is('a', PAGE_SIZE));
+	TEST_RES(read_remote(remote_buf + 100, 200),
+		 _ret == 200 && local_buf_is('a', 200));
+}
+END_TEST()
+
+FN_TEST(process_vm_readv_iovecs)
+{
+	char first[10], second[20];
+	struct iovec local[2] = {
+		{ .iov_base = first, .iov_len = sizeof(first) },
+		{ .iov_base = second, .iov_len = sizeof(second) },
+	};
+	struct iovec remote[2] = {
+		{ .iov_base = remote_buf, .iov_len = 5 },
+		{ .iov_base = remote_buf + 100, .iov_len = 100 },
+	};
+
+	TEST_RES(process_vm_readv(child, local, 2, remote, 2, 0),
+		 _ret == 30 && first[0] == 'a' && second[19] == 'a');
+}
+END_TEST()
+
+FN_TEST(process_vm_readv_fault)
+{
+	// The remote memory is partially accessible.
+	TEST_RES(read_remote(remote_buf + PAGE_SIZE / 2, PAGE_SIZE),
+		 _ret == PAGE_SIZE / 2);
+
+	// The remote memory is inaccessible.
+	TEST_ERRNO(read_remote(remote_buf + PAGE_SIZE, 1), EFAULT);
+
+	// The local memory is inaccessible.
+	struct iovec local = { .iov_base = remote_buf + PAGE_SIZE,
+			       .iov_len = 1 };
+	struct iovec remote = { .iov_base = remote_buf, .iov_len = 1 };
+	TEST_ERRNO(process_vm_readv(child, &local, 1, &remote, 1, 0), EFAULT);
+}
+END_TEST()
+
+FN_TEST(process_vm_writev)
+{
+	TEST_RES(write_remote(remote_buf + 10, 20, 'c'), _ret == 20);
+	TEST_RES(read_remote(remote_buf, 40),
+		 _ret == 40 && local_buf[9] == 'a' && local_buf[10] == 'c' &&
+			 local_buf[29] == 'c' && local_buf[30] == 'a');
+
+	// The memory of the current process is not changed.
+	TEST_RES(remote_buf[10], _ret == 'b');
+
+	TEST_ERRNO(write_remote(remote_buf + PAGE_SIZE, 1, 'c'), EFAULT);
+}
+END_TEST()
+
+FN_TEST(process_vm_invalid_args)
+{
+	struct iovec local = { .iov_base = local_buf, .iov_len = 1 };
+	struct iovec remote = { .iov_base = remote_buf, .iov_len = 1 };
+
+	TEST_ERRNO(process_vm_readv(child, &local, 1, &remote, 1, 1), EINVAL);
+	TEST_ERRNO(process_vm_readv(child, &local, 1025, &remote, 1, 0),
+		   EINVAL);
+	TEST_ERRNO(process_vm_readv(0x3fffffff, &local, 1, &remote, 1, 0),
+		   ESRCH);
+}
+END_TEST()
+
+FN_TEST(proc_pid_mem)
+{
+	int fd = TEST_SUCC(open(mem_path, O_RDWR));
+
+	TEST_RES(pread(fd, local_buf, 10, (off_t)(remote_buf + 10)),
+		 _ret == 10 && local_buf_is('c', 10));
+
+	memset(local_buf, 'd', 10);
+	TEST_RES(pwrite(fd, local_buf, 10, (off_t)remote_buf), _ret == 10);
+	TEST_RES(read_remote(remote_buf, 20),
+		 _ret == 20 && local_buf_is('d', 10) && local_buf[10] == 'c');
+
+	TEST_ERRNO(pread(fd, local_buf, 1, (off_t)(remote_buf + PAGE_SIZE)),
+		   EIO);
+	TEST_ERRNO(pwrite(fd, local_buf, 1, (off_t)(remote_buf + PAGE_SIZE)),
+		   EIO);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(proc_self_mem)
+{
+	int fd = TEST_SUCC(open("/proc/self/mem", O_RDWR));
+
+	TEST_RES(pread(fd, local_buf, 10, (off_t)remote_buf),
+		 _ret == 10 && local_buf_is('b', 10));
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+FN_TEST(dumpable)
+{
+	TEST_RES(prctl(PR_GET_DUMPABLE), _ret == 1);
+	TEST_ERRNO(prctl(PR_SET_DUMPABLE, 2), EINVAL);
+	TEST_ERRNO(prctl(PR_SET_DUMPABLE, 3), EINVAL);
+
+	TEST_SUCC(prctl(PR_SET_DUMPABLE, 0));
+	TEST_RES(prctl(PR_GET_DUMPABLE), _ret == 0);
+	TEST_SUCC(prctl(PR_SET_DUMPABLE, 1));
+	TEST_RES(prctl(PR_GET_DUMPABLE), _ret == 1);
+}
+END_TEST()
+
+// Returns zero if a process with the same credentials can read the memory of
+// the current process, one if the access is denied, and negative values on
+// errors.
+static int check_access_from_child(void)
+{
+	pid_t parent = getpid();
+	pid_t pid = fork();
+	if (pid < 0)
+		return -1;
+
+	if (pid == 0) {
+		struct iovec local = { .iov_base = local_buf, .iov_len = 1 };
+		struct iovec remote = { .iov_base = remote_buf, .iov_len = 1 };
+
+		if (process_vm_readv(parent, &local, 1, &remote, 1, 0) == 1)
+			_exit(0);
+		_exit(errno == EPERM ? 1 : 2);
+	}
+
+	int status;
+	if (waitpid(pid, &status, 0) != pid || !WIFEXITED(status))
+		return -1;
+	return WEXITSTATUS(status) <= 1 ? WEXITSTATUS(status) : -1;
+}
+
+// Returns zero if `/proc/[pid]/<name>` of the parent process cannot be read
+// because of the access check, and non-zero values otherwise.
+static int check_proc_denied(const char *name)
+{
+	char path[64], buf[8];
+	int fd;
+	ssize_t len;
+
+	snprintf(path, sizeof(path), "/proc/%d/%s", getppid(), name);
+	fd = open(path, O_RDONLY);
+	if (fd < 0)
+		return errno != EACCES;
+
+	len = read(fd, buf, sizeof(buf));
+	close(fd);
+	return len != -1 || errno != EACCES;
+}
+
+static int ptrace_access_unprivileged(void)
+{
+	// Changing the credentials makes the process not dumpable.
+	if (prctl(PR_GET_DUMPABLE) != 0)
+		return 15;
+	if (prctl(PR_SET_DUMPABLE, 1) < 0)
+		return 16;
+
+	// The root process cannot be accessed.
+	struct iovec local = { .iov_base = local_buf, .iov_len = 1 };
+	struct iovec remote = { .iov_base = remote_buf, .iov_len = 1 };
+	if (process_vm_readv(getppid(), &local, 1, &remote, 1, 0) != -1 ||
+	    errno != EPERM)
+		return 11;
+	if (check_proc_denied("maps") || check_proc_denied("smaps") ||
+	    check_proc_denied("smaps_rollup") || check_proc_denied("pagemap"))
+		return 17;
+
+	// The process with the same credentials can be accessed.
+	if (check_access_from_child() != 0)
+		return 12;
+
+	// The process that is not dumpable cannot be accessed.
+	if (prctl(PR_SET_DUMPABLE, 0) < 0)
+		return 13;
+	if (check_access_from_child() != 1)
+		return 14;
+
+	return 0;
+}
+
+FN_TEST(ptrace_access)
+{
+	TEST_RES(run_unprivileged(ptrace_access_unprivileged), _ret == 0);
+}
+END_TEST()
+
+FN_SETUP(kill_child)
+{
+	CHECK(kill(child, SIGKILL));
+	CHECK_WITH(waitpid(child, NULL, 0), _ret == child);
+	CHECK(munmap(remote_buf, PAGE_SIZE));
+}
+END_SETUP()
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <pthread.h>
+#include <pty.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <termios.h>
+#include <time.h>
+#include <unistd.h>
+
+#ifndef N_NULL
+#define N_NULL 27
+#endif
+
+static int master, slave;
+static struct termios default_termios;
+
+FN_SETUP(openpty)
+{
+	CHECK(openpty(&master, &slave, NULL, NULL, NULL));
+	CHECK(tcgetattr(slave, &default_termios));
+}
+END_SETUP()
+
+static int set_termios(tcflag_t iflag, tcflag_t oflag, tcflag_t lflag,
+		       cc_t vmin, cc_t vtime)
+{
+	struct termios term = default_termios;
+
+	term.c_iflag = iflag;
+	term.c_oflag = oflag;
+	term.c_lflag = lflag;
+	term.c_cc[VMIN] = vmin;
+	term.c_cc[VTIME] = vtime;
+
+	return tcsetattr(slave, TCSAFLUSH, &term);
+}
+
+static int reset_termios(void)
+{
+	if (tcsetattr(slave, TCSANOW, &default_termios) < 0)
+		return -1;
+	return tcflush(slave, TCIOFLUSH);
+}
+
+static int read_exact(int fd, const char *expected)
+{
+	char buf[64];
+	ssize_t len = read(fd, buf, sizeof(buf));
+
+	if (len != (ssize_t)strlen(expected) || memcmp(buf, expected, len) != 0)
+		return -1;
+	return 0;
+}
+
+static long long elapsed_ms(struct timespec *start)
+{
+	struct timespec now;
+
+	clock_gettime(CLOCK_MONOTONIC, &now);
+	return (now.tv_sec - start->tv_sec) * 1000LL +
+	       (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+FN_TEST(output_processing)
+{
+	// ONLCR
+	TEST_SUCC(set_termios(0, OPOST | ONLCR, 0, 1, 0));
+	TEST_RES(write(slave, "a\nb", 3), _ret == 3);
+	TEST_SUCC(read_exact(master, "a\r\nb"));
+
+	// Tabs are expanded to the next multiple of 8 columns
+	TEST_SUCC(set_termios(0, OPOST | ONLCR | XTABS, 0, 1, 0));
+	TEST_RES(write(slave, "\nab\t", 4), _ret == 4);
+	TEST_SUCC(read_exact(master, "\r\nab      "));
+
+	// No processing without OPOST
+	TEST_SUCC(set_termios(0, ONLCR, 0, 1, 0));
+	TEST_RES(write(slave, "a\nb", 3), _ret == 3);
+	TEST_SUCC(read_exact(master, "a\nb"));
+
+	TEST_SUCC(reset_termios());
+}
+END_TEST()
+
+FN_TEST(canonical_editing)
+{
+	TEST_SUCC(set_termios(ICRNL | IUTF8, 0, ICANON | IEXTEN, 1, 0));
+
+	// VERASE removes a whole UTF-8 character
+	TEST_RES(write(master, "x\xc3\xa9\x7f\n", 5), _ret == 5);
+	TEST_SUCC(read_exact(slave, "x\n"));
+
+	// VWERASE removes the last word
+	TEST_RES(write(master, "foo bar\027baz\r", 12), _ret == 12);
+	TEST_SUCC(read_exact(slave, "foo baz\n"));
+
+	// VLNEXT makes the next character literal
+	TEST_RES(write(master, "\x16\x7f\n", 3), _ret == 3);
+	TEST_SUCC(read_exact(slave, "\x7f\n"));
+
+	// VEOF ends a line without a terminator, or signals the end of file
+	TEST_RES(write(master, "ab\x04\x04", 4), _ret == 4);
+	TEST_SUCC(read_exact(slave, "ab"));
+	TEST_RES(read(slave, (char[8]){}, 8), _ret == 0);
+
+	TEST_SUCC(reset_termios());
+}
+END_TEST()
+
+FN_TEST(noncanonical_timeout)
+{
+	struct timespec start;
+	char buf[8];
+
+	// VMIN = 0, VTIME > 0: Wait for at most 0.1s
+	TEST_SUCC(set_termios(0, 0, 0, 0, 1));
+	clock_gettime(CLOCK_MONOTONIC, &start);
+	TEST_RES(read(slave, buf, sizeof(buf)), _ret == 0);
+	TEST_RES(elapsed_ms(&start), _ret >= 90);
+
+	TEST_RES(write(master, "a", 1), _ret == 1);
+	TEST_RES(read(slave, buf, sizeof(buf)), _ret == 1 && buf[0] == 'a');
+
+	// VMIN > 0, VTIME > 0: Return fewer than VMIN bytes after the inter-byte timeout
+	TEST_SUCC(set_termios(0, 0, 0, 4, 1));
+	TEST_RES(write(master, "bc", 2), _ret == 2);
+	clock_gettime(CLOCK_MONOTONIC, &start);
+	TEST_RES(read(slave, buf, sizeof(buf)),
+		 _ret == 2 && buf[0] == 'b' && buf[1] == 'c');
+	TEST_RES(elapsed_ms(&start), _ret >= 90);
+
+	// VMIN = 0, VTIME = 0: Never block
+	TEST_SUCC(set_termios(0, 0, 0, 0, 0));
+	TEST_RES(read(slave, buf, sizeof(buf)), _ret == 0);
+
+	TEST_SUCC(reset_termios());
+}
+END_TEST()
+
+static void *write_slave(void *arg)
+{
+	(void)arg;
+
+	if (write(slave, "z", 1) != 1)
+		return (void *)1;
+	return NULL;
+}
+
+static int check_stopped_write(int (*restart)(void))
+{
+	pthread_t thread;
+	void *res;
+	int len;
+
+	if (pthread_create(&thread, NULL, write_slave, NULL) != 0)
+		return -1;
+
+	// The write should be blocked
+	usleep(100 * 1000);
+	if (ioctl(master, FIONREAD, &len) < 0 || len != 0)
+		return -1;
+
+	if (restart() < 0)
+		return -1;
+
+	if (pthread_join(thread, &res) != 0 || res != NULL)
+		return -1;
+
+	return read_exact(master, "z");
+}
+
+static int restart_by_vstart(void)
+{
+	return write(master, "\x11", 1) == 1 ? 0 : -1;
+}
+
+static int restart_by_tcoon(void)
+{
+	return tcflow(slave, TCOON);
+}
+
+FN_TEST(flow_control)
+{
+	TEST_SUCC(set_termios(IXON, 0, 0, 1, 0));
+
+	// VSTOP and VSTART
+	TEST_RES(write(master, "\x13", 1), _ret == 1);
+	TEST_SUCC(check_stopped_write(restart_by_vstart));
+
+	// TCOOFF and TCOON
+	TEST_SUCC(tcflow(slave, TCOOFF));
+	TEST_SUCC(check_stopped_write(restart_by_tcoon));
+
+	// VSTART and VSTOP are not passed to the reader
+	TEST_RES(write(master, "\x13\x11y", 3), _ret == 3);
+	TEST_SUCC(read_exact(slave, "y"));
+
+	TEST_SUCC(reset_termios());
+}
+END_TEST()
+
+FN_TEST(flush_and_queue)
+{
+	int len;
+
+	TEST_SUCC(set_termios(0, 0, 0, 1, 0));
+
+	TEST_RES(write(master, "abc", 3), _ret == 3);
+	TEST_RES(ioctl(slave, FIONREAD, &len), len == 3);
+	TEST_SUCC(tcflush(slave, TCIFLUSH));
+	TEST_RES(ioctl(slave, FIONREAD, &len), len == 0);
+
+	TEST_SUCC(tcflush(slave, TCOFLUSH));
+	TEST_ERRNO(tcflush(slave, 100), EINVAL);
+
+	// The PTY sends the output immediately
+	TEST_SUCC(tcdrain(slave));
+	TEST_RES(ioctl(slave, TIOCOUTQ, &len), len == 0);
+
+	TEST_SUCC(reset_termios());
+}
+END_TEST()
+
+FN_TEST(insert_input)
+{
+	TEST_SUCC(set_termios(0, 0, 0, 1, 0));
+
+	// Requires `CAP_SYS_ADMIN` because the PTY is not our controlling terminal
+	TEST_SUCC(ioctl(slave, TIOCSTI, "q"));
+	TEST_SUCC(read_exact(slave, "q"));
+
+	TEST_SUCC(reset_termios());
+}
+END_TEST()
+
+FN_TEST(line_discipline)
+{
+	int ldisc;
+
+	TEST_RES(ioctl(slave, TIOCGETD, &ldisc), ldisc == N_TTY);
+
+	ldisc = 100;
+	TEST_ERRNO(ioctl(slave, TIOCSETD, &ldisc), EINVAL);
+
+	ldisc = N_NULL;
+	TEST_SUCC(ioctl(slave, TIOCSETD, &ldisc));
+	TEST_RES(ioctl(slave, TIOCGETD, &ldisc), ldisc == N_NULL);
+
+	TEST_RES(write(master, "a\n", 2), _ret == 2);
+	TEST_ERRNO(read(slave, (char[8]){}, 8), EOPNOTSUPP);
+	TEST_ERRNO(write(slave, "a", 1), EOPNOTSUPP);
+
+	ldisc = N_TTY;
+	TEST_SUCC(ioctl(slave, TIOCSETD, &ldisc));
+	TEST_RES(ioctl(slave, TIOCGETD, &ldisc), ldisc == N_TTY);
+}
+END_TEST()
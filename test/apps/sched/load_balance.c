@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sched.h>
+#include <signal.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#define MAX_CHILDREN 64
+#define SPIN_SECS 2
+
+static long nr_cpus;
+static pid_t pids[MAX_CHILDREN];
+static int pipe_fds[2];
+
+static long long read_monotonic_ns(void)
+{
+	struct timespec ts;
+
+	clock_gettime(CLOCK_MONOTONIC, &ts);
+	return ts.tv_sec * 1000000000LL + ts.tv_nsec;
+}
+
+// Spins with all the CPUs allowed, then reports the CPU that it runs on.
+static void child_main(void)
+{
+	cpu_set_t set;
+	long long deadline;
+	char cpu;
+	int i;
+
+	CPU_ZERO(&set);
+	for (i = 0; i < nr_cpus; i++)
+		CPU_SET(i, &set);
+	if (sched_setaffinity(0, sizeof(set), &set) < 0)
+		_exit(EXIT_FAILURE);
+
+	// The current CPU is still allowed, so only the load balancing can move
+	// the busy children to other CPUs.
+	deadline = read_monotonic_ns() + SPIN_SECS * 1000000000LL;
+	while (read_monotonic_ns() < deadline)
+		;
+
+	cpu = sched_getcpu();
+	if (write(pipe_fds[1], &cpu, 1) != 1)
+		_exit(EXIT_FAILURE);
+
+	pause();
+	_exit(EXIT_SUCCESS);
+}
+
+FN_SETUP(spawn)
+{
+	cpu_set_t set;
+	int i;
+
+	nr_cpus = CHECK(sysconf(_SC_NPROCESSORS_ONLN));
+	if (nr_cpus > MAX_CHILDREN)
+		nr_cpus = MAX_CHILDREN;
+	CHECK(pipe(pipe_fds));
+
+	// All the children start on CPU 0.
+	CPU_ZERO(&set);
+	CPU_SET(0, &set);
+	CHECK(sched_setaffinity(0, sizeof(set), &set));
+
+	for (i = 0; i < nr_cpus; i++) {
+		pids[i] = CHECK(fork());
+		if (pids[i] == 0)
+			child_main();
+	}
+}
+END_SETUP()
+
+// Returns the number of distinct CPUs that the children run on, or -1 on
+// errors.
+static int count_used_cpus(void)
+{
+	int used[MAX_CHILDREN] = { 0 };
+	int i, nr_used = 0;
+	char cpu;
+
+	for (i = 0; i < nr_cpus; i++) {
+		if (read(pipe_fds[0], &cpu, 1) != 1)
+			return -1;
+		if (cpu < 0 || cpu >= MAX_CHILDREN)
+			return -1;
+		if (!used[(int)cpu]) {
+			used[(int)cpu] = 1;
+			nr_used++;
+		}
+	}
+
+	return nr_used;
+}
+
+FN_TEST(spread)
+{
+	// With one busy child per CPU, the children should spread out. The
+	// check is loose so that it is not affected by other activities.
+	TEST_RES(count_used_cpus(), nr_cpus == 1 || _ret >= 2);
+}
+END_TEST()
+
+FN_SETUP(cleanup)
+{
+	int i;
+
+	for (i = 0; i < nr_cpus; i++) {
+		CHECK(kill(pids[i], SIGKILL));
+		CHECK(waitpid(pids[i], NULL, 0));
+	}
+	CHECK(close(pipe_fds[0]));
+	CHECK(close(pipe_fds[1]));
+}
+END_SETUP()
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sched.h>
+#include <signal.h>
+#include <stdint.h>
+#include <sys/syscall.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#ifndef SCHED_DEADLINE
+#define SCHED_DEADLINE 6
+#endif
+
+#define SCHED_FLAG_DL_OVERRUN 0x04
+
+struct sched_attr {
+	uint32_t size;
+	uint32_t sched_policy;
+	uint64_t sched_flags;
+	int32_t sched_nice;
+	uint32_t sched_priority;
+	uint64_t sched_runtime;
+	uint64_t sched_deadline;
+	uint64_t sched_period;
+	uint32_t sched_util_min;
+	uint32_t sched_util_max;
+};
+
+static int sched_setattr(pid_t pid, const struct sched_attr *attr)
+{
+	return syscall(SYS_sched_setattr, pid, attr, 0);
+}
+
+static int sched_getattr(pid_t pid, struct sched_attr *attr)
+{
+	return syscall(SYS_sched_getattr, pid, attr, sizeof(*attr), 0);
+}
+
+static int set_deadline(uint64_t runtime, uint64_t deadline, uint64_t period)
+{
+	struct sched_attr attr = {
+		.size = sizeof(attr),
+		.sched_policy = SCHED_DEADLINE,
+		.sched_flags = SCHED_FLAG_DL_OVERRUN,
+		.sched_runtime = runtime,
+		.sched_deadline = deadline,
+		.sched_period = period,
+	};
+
+	return sched_setattr(0, &attr);
+}
+
+FN_TEST(invalid_params)
+{
+	// The deadline is zero.
+	TEST_ERRNO(set_deadline(1000000, 0, 0), EINVAL);
+	// The runtime is too small.
+	TEST_ERRNO(set_deadline(100, 1000000, 1000000), EINVAL);
+	// The runtime exceeds the deadline.
+	TEST_ERRNO(set_deadline(2000000, 1000000, 3000000), EINVAL);
+	// The deadline exceeds the period.
+	TEST_ERRNO(set_deadline(1000000, 3000000, 2000000), EINVAL);
+	// The period is too small.
+	TEST_ERRNO(set_deadline(10000, 50000, 50000), EINVAL);
+}
+END_TEST()
+
+FN_TEST(set_and_get)
+{
+	struct sched_attr attr;
+
+	TEST_SUCC(set_deadline(10000000, 30000000, 0));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_DEADLINE);
+	TEST_RES(sched_getattr(0, &attr),
+		 attr.sched_policy == SCHED_DEADLINE &&
+			 attr.sched_flags == SCHED_FLAG_DL_OVERRUN &&
+			 attr.sched_runtime == 10000000 &&
+			 attr.sched_deadline == 30000000 &&
+			 attr.sched_period == 30000000);
+
+	// Yielding gives up the remaining runtime in the current period.
+	TEST_SUCC(sched_yield());
+
+	attr = (struct sched_attr){
+		.size = sizeof(attr),
+		.sched_policy = SCHED_OTHER,
+	};
+	TEST_SUCC(sched_setattr(0, &attr));
+	TEST_RES(sched_getscheduler(0), _ret == SCHED_OTHER);
+}
+END_TEST()
+
+FN_TEST(admission_control)
+{
+	long nr_cpus = sysconf(_SC_NPROCESSORS_ONLN);
+	pid_t pids[nr_cpus];
+	int i;
+
+	// Each thread reserves 90% of a CPU, so only `nr_cpus` threads fit.
+	for (i = 0; i < nr_cpus; i++) {
+		pids[i] = CHECK(fork());
+		if (pids[i] == 0) {
+			pause();
+			exit(EXIT_SUCCESS);
+		}
+	}
+
+	for (i = 0; i < nr_cpus; i++) {
+		struct sched_attr attr = {
+			.size = sizeof(attr),
+			.sched_policy = SCHED_DEADLINE,
+			.sched_runtime = 9000000,
+			.sched_deadline = 10000000,
+			.sched_period = 10000000,
+		};
+		TEST_SUCC(sched_setattr(pids[i], &attr));
+	}
+	TEST_ERRNO(set_deadline(9000000, 10000000, 10000000), EBUSY);
+
+	for (i = 0; i < nr_cpus; i++) {
+		CHECK(kill(pids[i], SIGKILL));
+		CHECK(waitpid(pids[i], NULL, 0));
+	}
+}
+END_TEST()
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+static FILE *schedstat;
+
+FN_SETUP(open_schedstat)
+{
+	int fd = CHECK(open("/proc/schedstat", O_RDONLY));
+
+	schedstat = fdopen(fd, "r");
+	CHECK_WITH(schedstat != NULL, _ret);
+}
+END_SETUP()
+
+static int count_fields(const char *line)
+{
+	int nr_fields = 0, offset = 0, consumed;
+	unsigned long long field;
+
+	while (sscanf(line + offset, "%llu%n", &field, &consumed) == 1) {
+		offset += consumed;
+		nr_fields++;
+	}
+
+	return nr_fields;
+}
+
+static int read_version(void)
+{
+	int version;
+
+	if (fscanf(schedstat, "version %d\n", &version) != 1)
+		return -1;
+	return version;
+}
+
+static int read_timestamp(void)
+{
+	unsigned long long timestamp;
+
+	return fscanf(schedstat, "timestamp %llu\n", &timestamp);
+}
+
+FN_TEST(schedstat_format)
+{
+	char line[1024];
+	int cpu, offset;
+	int nr_cpus = 0, nr_domains = 0;
+
+	TEST_RES(read_version(), _ret == 15);
+	TEST_RES(read_timestamp(), _ret == 1);
+
+	while (fgets(line, sizeof(line), schedstat) != NULL) {
+		offset = 0;
+		if (sscanf(line, "cpu%d%n", &cpu, &offset) == 1) {
+			TEST_RES(count_fields(line + offset),
+				 _ret == 9 && cpu == nr_cpus);
+			nr_cpus++;
+		} else if (sscanf(line, "domain0 %*s%n", &offset) == 0 &&
+			   offset > 0) {
+			TEST_RES(count_fields(line + offset), _ret == 36);
+			nr_domains++;
+		}
+	}
+
+	TEST_RES(sysconf(_SC_NPROCESSORS_CONF),
+		 _ret == nr_cpus && _ret == nr_domains);
+}
+END_TEST()
+
+FN_SETUP(close_schedstat)
+{
+	CHECK(fclose(schedstat));
+}
+END_SETUP()
@@ -33,9 +33,18 @@ sleep 0.2
 ./tcp_poll
 ./udp_err
 ./unix_err
+./unix_scm
+./unix_dgram
+./unix_seqpacket
+./raw_ping
+./packet_socket
+./iface_ioctl
+./tcp_congestion
+./proc_net
 
 ./netlink_route
 ./rtnl_err
+./rtnl_config
 ./uevent_err
 
 echo "All network test passed"
@@ -27,22 +27,38 @@ hello_pie/hello
 hello_world/hello_world
 itimer/setitimer
 itimer/timer_create
+itimer/cpu_timer
+kmsg/kmsg
 mmap/mmap_and_fork
 mmap/mmap_and_mremap
+mmap/mmap_mlock
+mmap/mmap_procfs
 mmap/mmap_shared_filebacked
 mmap/mmap_readahead
 mmap/mmap_vmrss
 process/group_session
 process/job_control
+process/process_vm
 process/wait4
 pthread/pthread_test
 pty/open_pty
 pty/pty_blocking
+pty/pty_termios
 sched/sched_attr
+sched/sched_deadline
+sched/schedstat
+serial/serial_loopback
 shm/posix_shm
 signal_c/parent_death_signal
 signal_c/signal_test
 signal_c/signal_test2
+signal_c/sigqueue
+time/adjtimex
+time/clock_settime
+time/clocksource
+time/hrtimer
+time/rtc
+vt/vt_ioctl
 "
 
 for testcase in ${tests}
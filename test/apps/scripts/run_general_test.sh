@@ -9,6 +9,8 @@ cd ${SCRIPT_DIR}
 
 ./shell_cmd.sh
 ./test_epoll_pwait.sh
+# This test is only meaningful with SMP.
+./sched/load_balance
 
 # TODO: Support the following tests with SMP
 if [ -z $BLOCK_UNSUPPORTED_SMP_TESTS ]; then
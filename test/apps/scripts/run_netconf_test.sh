@@ -0,0 +1,17 @@
+#!/bin/sh
+
+# SPDX-License-Identifier: MPL-2.0
+
+# The network tests that require a special configuration of the VM. They are run with
+# `make run AUTO_TEST=netconf`, which attaches a secondary NIC to the VM and enables the DHCP
+# client with `ip=dhcp` if the QEMU user network is used.
+
+set -e
+
+NETTEST_DIR=/test/network
+cd ${NETTEST_DIR}
+
+echo "Start netconf test......"
+./multi_iface
+./proc_net
+echo "All netconf tests passed."
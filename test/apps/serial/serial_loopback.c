@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <fcntl.h>
+#include <pthread.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <sys/stat.h>
+#include <sys/sysmacros.h>
+#include <termios.h>
+#include <time.h>
+#include <unistd.h>
+
+#ifndef TIOCM_LOOP
+#define TIOCM_LOOP 0x8000
+#endif
+
+// The second UART (i.e., COM2) is put in the loopback mode, so the characters sent by it are
+// received by itself, and its modem status lines follow its modem control lines.
+
+static int fd;
+
+FN_SETUP(open)
+{
+	struct termios term;
+	int lines = TIOCM_LOOP;
+
+	fd = CHECK(open("/dev/ttyS1", O_RDWR | O_NOCTTY));
+
+	CHECK(tcgetattr(fd, &term));
+	cfmakeraw(&term);
+	term.c_cc[VMIN] = 1;
+	term.c_cc[VTIME] = 0;
+	CHECK(tcsetattr(fd, TCSANOW, &term));
+
+	CHECK(ioctl(fd, TIOCMBIS, &lines));
+}
+END_SETUP()
+
+static int read_exact(const char *expected)
+{
+	char buf[64];
+	size_t len = strlen(expected);
+	size_t total = 0;
+
+	while (total < len) {
+		ssize_t ret = read(fd, buf + total, len - total);
+		if (ret <= 0)
+			return -1;
+		total += ret;
+	}
+
+	return memcmp(buf, expected, len) == 0 ? 0 : -1;
+}
+
+static long long elapsed_ms(struct timespec *start)
+{
+	struct timespec now;
+
+	clock_gettime(CLOCK_MONOTONIC, &now);
+	return (now.tv_sec - start->tv_sec) * 1000LL +
+	       (now.tv_nsec - start->tv_nsec) / 1000000;
+}
+
+FN_TEST(device_number)
+{
+	struct stat st;
+
+	// `/dev/ttyS1` is the character device (4, 65), as in Linux.
+	TEST_RES(fstat(fd, &st), S_ISCHR(st.st_mode) &&
+				 major(st.st_rdev) == 4 &&
+				 minor(st.st_rdev) == 65);
+}
+END_TEST()
+
+FN_TEST(loopback)
+{
+	TEST_RES(write(fd, "hello", 5), _ret == 5);
+	TEST_SUCC(tcdrain(fd));
+	TEST_SUCC(read_exact("hello"));
+}
+END_TEST()
+
+FN_TEST(line_settings)
+{
+	struct termios term;
+	tcflag_t cflag = CS7 | PARENB | PARODD | CSTOPB;
+
+	TEST_SUCC(tcgetattr(fd, &term));
+	TEST_SUCC(cfsetspeed(&term, B9600));
+	term.c_cflag &= ~(CSIZE | PARENB | PARODD | CSTOPB);
+	term.c_cflag |= cflag;
+	TEST_SUCC(tcsetattr(fd, TCSANOW, &term));
+
+	TEST_RES(write(fd, "abc", 3), _ret == 3);
+	TEST_SUCC(read_exact("abc"));
+
+	TEST_SUCC(tcgetattr(fd, &term));
+	TEST_RES(cfgetospeed(&term), _ret == B9600);
+	TEST_RES(term.c_cflag & (CSIZE | PARENB | PARODD | CSTOPB),
+		 _ret == cflag);
+
+	TEST_SUCC(cfsetspeed(&term, B115200));
+	term.c_cflag &= ~(CSIZE | PARENB | PARODD | CSTOPB);
+	term.c_cflag |= CS8;
+	TEST_SUCC(tcsetattr(fd, TCSANOW, &term));
+
+	TEST_RES(write(fd, "xyz", 3), _ret == 3);
+	TEST_SUCC(read_exact("xyz"));
+}
+END_TEST()
+
+FN_TEST(modem_lines)
+{
+	int lines;
+
+	// In the loopback mode, CTS follows RTS, DSR follows DTR, and CD follows OUT2
+	lines = TIOCM_DTR | TIOCM_RTS | TIOCM_LOOP;
+	TEST_SUCC(ioctl(fd, TIOCMSET, &lines));
+	TEST_RES(ioctl(fd, TIOCMGET, &lines),
+		 (lines & TIOCM_CTS) && (lines & TIOCM_DSR) &&
+			 (lines & TIOCM_CAR) && (lines & TIOCM_LOOP));
+
+	lines = TIOCM_RTS;
+	TEST_SUCC(ioctl(fd, TIOCMBIC, &lines));
+	TEST_RES(ioctl(fd, TIOCMGET, &lines),
+		 !(lines & TIOCM_RTS) && !(lines & TIOCM_CTS) &&
+			 (lines & TIOCM_DSR));
+
+	lines = TIOCM_RTS;
+	TEST_SUCC(ioctl(fd, TIOCMBIS, &lines));
+	TEST_RES(ioctl(fd, TIOCMGET, &lines),
+		 (lines & TIOCM_RTS) && (lines & TIOCM_CTS));
+}
+END_TEST()
+
+static void *clear_rts(void *arg)
+{
+	int lines = TIOCM_RTS;
+
+	(void)arg;
+
+	usleep(100 * 1000);
+	if (ioctl(fd, TIOCMBIC, &lines) < 0)
+		return (void *)1;
+	return NULL;
+}
+
+FN_TEST(wait_modem_change)
+{
+	pthread_t thread;
+	void *res;
+	int lines;
+
+	TEST_RES(pthread_create(&thread, NULL, clear_rts, NULL), _ret == 0);
+	TEST_SUCC(ioctl(fd, TIOCMIWAIT, TIOCM_CTS));
+	TEST_RES(pthread_join(thread, &res), _ret == 0 && res == NULL);
+
+	TEST_RES(ioctl(fd, TIOCMGET, &lines), !(lines & TIOCM_CTS));
+
+	lines = TIOCM_RTS;
+	TEST_SUCC(ioctl(fd, TIOCMBIS, &lines));
+}
+END_TEST()
+
+FN_TEST(send_break)
+{
+	struct timespec start;
+
+	clock_gettime(CLOCK_MONOTONIC, &start);
+	TEST_SUCC(tcsendbreak(fd, 0));
+	TEST_RES(elapsed_ms(&start), _ret >= 240);
+
+	TEST_SUCC(ioctl(fd, TIOCSBRK));
+	TEST_SUCC(ioctl(fd, TIOCCBRK));
+
+	// The output still works after the break
+	TEST_RES(write(fd, "ok", 2), _ret == 2);
+	TEST_SUCC(read_exact("ok"));
+}
+END_TEST()
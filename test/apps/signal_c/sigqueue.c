@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <pthread.h>
+#include <signal.h>
+#include <string.h>
+#include <sys/resource.h>
+#include <sys/syscall.h>
+#include <time.h>
+#include <unistd.h>
+
+static sigset_t rt_sigs;
+static siginfo_t info;
+
+static const struct timespec zero_timeout = { 0 };
+
+FN_SETUP(block)
+{
+	CHECK(sigemptyset(&rt_sigs));
+	CHECK(sigaddset(&rt_sigs, SIGRTMIN));
+	CHECK(sigaddset(&rt_sigs, SIGRTMIN + 1));
+	CHECK(sigprocmask(SIG_BLOCK, &rt_sigs, NULL));
+}
+END_SETUP()
+
+FN_TEST(sigqueue_value)
+{
+	union sigval value = { .sival_int = 42 };
+
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN, value));
+	TEST_RES(sigtimedwait(&rt_sigs, &info, &zero_timeout),
+		 _ret == SIGRTMIN && info.si_signo == SIGRTMIN &&
+			 info.si_code == SI_QUEUE && info.si_pid == getpid() &&
+			 info.si_uid == getuid() &&
+			 info.si_value.sival_int == 42);
+}
+END_TEST()
+
+FN_TEST(sigqueue_order)
+{
+	union sigval value;
+
+	value.sival_int = 1;
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN + 1, value));
+	value.sival_int = 2;
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN, value));
+	value.sival_int = 3;
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN, value));
+
+	// Lower-numbered real-time signals are delivered first, and signals of
+	// the same type are delivered in the order they were sent.
+	TEST_RES(sigwaitinfo(&rt_sigs, &info),
+		 _ret == SIGRTMIN && info.si_value.sival_int == 2);
+	TEST_RES(sigwaitinfo(&rt_sigs, &info),
+		 _ret == SIGRTMIN && info.si_value.sival_int == 3);
+	TEST_RES(sigwaitinfo(&rt_sigs, &info),
+		 _ret == SIGRTMIN + 1 && info.si_value.sival_int == 1);
+}
+END_TEST()
+
+FN_TEST(sigtimedwait_timeout)
+{
+	struct timespec timeout = { .tv_sec = 0, .tv_nsec = 10 * 1000 * 1000 };
+
+	TEST_ERRNO(sigtimedwait(&rt_sigs, &info, &zero_timeout), EAGAIN);
+	TEST_ERRNO(sigtimedwait(&rt_sigs, &info, &timeout), EAGAIN);
+}
+END_TEST()
+
+static void *send_later(void *arg)
+{
+	union sigval value = { .sival_ptr = arg };
+
+	usleep(100 * 1000);
+	sigqueue(getpid(), SIGRTMIN, value);
+
+	return NULL;
+}
+
+FN_TEST(sigwaitinfo_wait)
+{
+	pthread_t thread;
+	static int data;
+
+	TEST_SUCC(pthread_create(&thread, NULL, &send_later, &data));
+	TEST_RES(sigwaitinfo(&rt_sigs, &info),
+		 _ret == SIGRTMIN && info.si_code == SI_QUEUE &&
+			 info.si_value.sival_ptr == &data);
+	TEST_SUCC(pthread_join(thread, NULL));
+}
+END_TEST()
+
+FN_TEST(sigqueueinfo_code)
+{
+	siginfo_t uinfo;
+
+	memset(&uinfo, 0, sizeof(uinfo));
+	uinfo.si_signo = SIGRTMIN;
+	uinfo.si_code = SI_USER;
+
+	// Pretending to be `kill` or `tgkill` is only allowed when sending signals
+	// to oneself.
+	TEST_ERRNO(syscall(SYS_rt_sigqueueinfo, getppid(), SIGRTMIN, &uinfo),
+		   EPERM);
+	uinfo.si_code = SI_TKILL;
+	TEST_ERRNO(syscall(SYS_rt_tgsigqueueinfo, getppid(), getppid(),
+			   SIGRTMIN, &uinfo),
+		   EPERM);
+
+	uinfo.si_code = SI_QUEUE;
+	uinfo.si_value.sival_int = 7;
+	TEST_SUCC(syscall(SYS_rt_tgsigqueueinfo, getpid(), gettid(), SIGRTMIN,
+			  &uinfo));
+	TEST_RES(sigtimedwait(&rt_sigs, &info, &zero_timeout),
+		 _ret == SIGRTMIN && info.si_code == SI_QUEUE &&
+			 info.si_value.sival_int == 7);
+}
+END_TEST()
+
+FN_TEST(rlimit_sigpending)
+{
+	struct rlimit old_limit;
+	struct rlimit limit;
+	union sigval value = { .sival_int = 0 };
+
+	TEST_SUCC(getrlimit(RLIMIT_SIGPENDING, &old_limit));
+	limit.rlim_cur = 2;
+	limit.rlim_max = old_limit.rlim_max;
+	TEST_SUCC(setrlimit(RLIMIT_SIGPENDING, &limit));
+
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN, value));
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN, value));
+	TEST_ERRNO(sigqueue(getpid(), SIGRTMIN, value), EAGAIN);
+
+	// Dequeuing a signal releases its charge.
+	TEST_RES(sigtimedwait(&rt_sigs, &info, &zero_timeout),
+		 _ret == SIGRTMIN);
+	TEST_SUCC(sigqueue(getpid(), SIGRTMIN, value));
+
+	TEST_RES(sigtimedwait(&rt_sigs, &info, &zero_timeout),
+		 _ret == SIGRTMIN);
+	TEST_RES(sigtimedwait(&rt_sigs, &info, &zero_timeout),
+		 _ret == SIGRTMIN);
+
+	TEST_SUCC(setrlimit(RLIMIT_SIGPENDING, &old_limit));
+}
+END_TEST()
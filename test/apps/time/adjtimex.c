@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <linux/capability.h>
+#include <sys/syscall.h>
+#include <sys/time.h>
+#include <sys/timex.h>
+#include <time.h>
+#include <unistd.h>
+
+#define NSEC_PER_SEC 1000000000L
+
+static long long read_clock(clockid_t clockid)
+{
+	struct timespec ts;
+
+	if (clock_gettime(clockid, &ts) < 0)
+		return -1;
+	return (long long)ts.tv_sec * NSEC_PER_SEC + ts.tv_nsec;
+}
+
+FN_TEST(read_state)
+{
+	struct timex tx = { .modes = 0 };
+
+	TEST_RES(adjtimex(&tx), _ret == TIME_OK || _ret == TIME_ERROR);
+	TEST_RES(0, tx.tolerance == 500 << 16);
+	TEST_RES(0, tx.tick == 10000);
+	TEST_RES(0, tx.time.tv_sec > 0);
+}
+END_TEST()
+
+FN_TEST(invalid_args)
+{
+	struct timex tx = { .modes = ADJ_TICK, .tick = 1000 };
+
+	TEST_ERRNO(adjtimex(&tx), EINVAL);
+
+	tx.modes = ADJ_SETOFFSET;
+	tx.time.tv_sec = 0;
+	tx.time.tv_usec = 1000000;
+	TEST_ERRNO(adjtimex(&tx), EINVAL);
+
+	tx.modes = ADJ_OFFSET_SINGLESHOT | ADJ_FREQUENCY;
+	TEST_ERRNO(adjtimex(&tx), EINVAL);
+
+	tx.modes = 0;
+	TEST_ERRNO(clock_adjtime(CLOCK_MONOTONIC, &tx), EOPNOTSUPP);
+	TEST_ERRNO(clock_adjtime(-1234, &tx), EINVAL);
+}
+END_TEST()
+
+FN_TEST(frequency)
+{
+	struct timex tx = { .modes = ADJ_FREQUENCY, .freq = 100 << 16 };
+
+	TEST_SUCC(clock_adjtime(CLOCK_REALTIME, &tx));
+	TEST_RES(0, tx.freq == 100 << 16);
+
+	// The frequency is clamped to 500 ppm.
+	tx.freq = 1000 << 16;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(0, tx.freq == 500 << 16);
+
+	tx.modes = 0;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(0, tx.freq == 500 << 16);
+
+	tx.modes = ADJ_FREQUENCY;
+	tx.freq = 0;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(0, tx.freq == 0);
+}
+END_TEST()
+
+FN_TEST(singleshot_offset)
+{
+	struct timex tx = { .modes = ADJ_OFFSET_SINGLESHOT, .offset = 200000 };
+
+	// Slews the clock by 200 ms, which takes 400 seconds.
+	TEST_SUCC(adjtimex(&tx));
+
+	tx.modes = ADJ_OFFSET_SS_READ;
+	TEST_RES(adjtimex(&tx), tx.offset > 190000 && tx.offset <= 200000);
+
+	// Cancels the slew, and the remaining offset is returned.
+	tx.modes = ADJ_OFFSET_SINGLESHOT;
+	tx.offset = 0;
+	TEST_RES(adjtimex(&tx), tx.offset > 190000 && tx.offset <= 200000);
+
+	tx.modes = ADJ_OFFSET_SS_READ;
+	TEST_RES(adjtimex(&tx), tx.offset == 0);
+}
+END_TEST()
+
+FN_TEST(set_offset)
+{
+	struct timex tx = { .modes = ADJ_SETOFFSET | ADJ_NANO };
+	long long before;
+
+	before = TEST_RES(read_clock(CLOCK_REALTIME), _ret > 0);
+
+	tx.time.tv_sec = 100;
+	tx.time.tv_usec = 0;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(read_clock(CLOCK_REALTIME),
+		 _ret >= before + 100 * NSEC_PER_SEC &&
+			 _ret < before + 101 * NSEC_PER_SEC);
+
+	// A negative offset is represented by a negative second and a positive fraction.
+	tx.time.tv_sec = -101;
+	tx.time.tv_usec = NSEC_PER_SEC / 2;
+	TEST_SUCC(adjtimex(&tx));
+	TEST_RES(read_clock(CLOCK_REALTIME),
+		 _ret >= before - NSEC_PER_SEC / 2 &&
+			 _ret < before + NSEC_PER_SEC / 2);
+
+	tx.modes = ADJ_SETOFFSET | ADJ_NANO;
+	tx.time.tv_sec = 0;
+	tx.time.tv_usec = NSEC_PER_SEC / 2;
+	TEST_SUCC(adjtimex(&tx));
+
+	// Switch back to microseconds.
+	tx.modes = ADJ_MICRO;
+	TEST_RES(adjtimex(&tx), (tx.status & STA_NANO) == 0);
+}
+END_TEST()
+
+FN_TEST(no_permission)
+{
+	struct __user_cap_header_struct hdr = {
+		.version = _LINUX_CAPABILITY_VERSION_3,
+		.pid = 0,
+	};
+	struct __user_cap_data_struct data[2];
+	struct timex tx = { .modes = 0 };
+	struct timespec ts;
+
+	TEST_SUCC(syscall(SYS_capget, &hdr, data));
+	data[0].effective &= ~(1U << CAP_SYS_TIME);
+	TEST_SUCC(syscall(SYS_capset, &hdr, data));
+
+	TEST_SUCC(clock_gettime(CLOCK_REALTIME, &ts));
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME, &ts), EPERM);
+	TEST_ERRNO(settimeofday(NULL, NULL), EPERM);
+
+	// Reading the state is still allowed.
+	TEST_RES(adjtimex(&tx), _ret == TIME_OK || _ret == TIME_ERROR);
+	tx.modes = ADJ_OFFSET_SS_READ;
+	TEST_RES(adjtimex(&tx), _ret == TIME_OK || _ret == TIME_ERROR);
+	tx.modes = ADJ_FREQUENCY;
+	TEST_ERRNO(adjtimex(&tx), EPERM);
+
+	data[0].effective |= 1U << CAP_SYS_TIME;
+	TEST_SUCC(syscall(SYS_capset, &hdr, data));
+}
+END_TEST()
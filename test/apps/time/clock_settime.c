@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sys/time.h>
+#include <time.h>
+
+#define NSEC_PER_SEC 1000000000L
+
+// The time is moved forward by one day, which is restored after the tests.
+#define DELTA_SECS (24 * 60 * 60)
+
+static long long timespec_to_ns(const struct timespec *ts)
+{
+	return (long long)ts->tv_sec * NSEC_PER_SEC + ts->tv_nsec;
+}
+
+static long long read_clock(clockid_t clockid)
+{
+	struct timespec ts;
+
+	if (clock_gettime(clockid, &ts) < 0)
+		return -1;
+	return timespec_to_ns(&ts);
+}
+
+// Checks that `clockid` reads a time in `[expected, expected + 1s)`.
+static int is_near(clockid_t clockid, long long expected)
+{
+	long long now = read_clock(clockid);
+
+	return now >= expected - NSEC_PER_SEC / 10 &&
+	       now < expected + NSEC_PER_SEC;
+}
+
+FN_TEST(invalid_args)
+{
+	struct timespec ts = { .tv_sec = 0, .tv_nsec = 0 };
+
+	TEST_ERRNO(clock_settime(CLOCK_MONOTONIC, &ts), EINVAL);
+	TEST_ERRNO(clock_settime(CLOCK_BOOTTIME, &ts), EINVAL);
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME_COARSE, &ts), EINVAL);
+
+	ts.tv_nsec = NSEC_PER_SEC;
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME, &ts), EINVAL);
+	ts.tv_nsec = -1;
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME, &ts), EINVAL);
+	ts.tv_sec = -1;
+	ts.tv_nsec = 0;
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME, &ts), EINVAL);
+
+	// The real time cannot be earlier than the boot time.
+	ts.tv_sec = 0;
+	TEST_ERRNO(clock_settime(CLOCK_REALTIME, &ts), EINVAL);
+}
+END_TEST()
+
+FN_TEST(set_realtime)
+{
+	struct timespec ts;
+	long long mono_before, target;
+
+	mono_before = TEST_RES(read_clock(CLOCK_MONOTONIC), _ret > 0);
+	target = TEST_RES(read_clock(CLOCK_REALTIME), _ret > 0) +
+		 (long long)DELTA_SECS * NSEC_PER_SEC;
+
+	ts.tv_sec = target / NSEC_PER_SEC;
+	ts.tv_nsec = target % NSEC_PER_SEC;
+	TEST_SUCC(clock_settime(CLOCK_REALTIME, &ts));
+
+	// All the real time clocks, including the vDSO ones, follow the new time.
+	TEST_RES(0, is_near(CLOCK_REALTIME, target));
+	TEST_RES(0, is_near(CLOCK_REALTIME_COARSE, target));
+	TEST_RES(time(NULL), _ret >= ts.tv_sec && _ret <= ts.tv_sec + 1);
+
+	// The monotonic clocks are not affected.
+	TEST_RES(read_clock(CLOCK_MONOTONIC),
+		 _ret >= mono_before && _ret < mono_before + NSEC_PER_SEC);
+
+	target -= (long long)DELTA_SECS * NSEC_PER_SEC;
+	ts.tv_sec = target / NSEC_PER_SEC;
+	ts.tv_nsec = target % NSEC_PER_SEC;
+	TEST_SUCC(clock_settime(CLOCK_REALTIME, &ts));
+	TEST_RES(0, is_near(CLOCK_REALTIME, target));
+}
+END_TEST()
+
+FN_TEST(settimeofday)
+{
+	struct timeval tv;
+	struct timezone tz = { .tz_minuteswest = 0, .tz_dsttime = 0 };
+
+	TEST_SUCC(gettimeofday(&tv, NULL));
+
+	tv.tv_usec = 1000000;
+	TEST_ERRNO(settimeofday(&tv, NULL), EINVAL);
+	tv.tv_usec = 0;
+
+	tz.tz_minuteswest = 24 * 60;
+	TEST_ERRNO(settimeofday(NULL, &tz), EINVAL);
+
+	tv.tv_sec += DELTA_SECS;
+	TEST_SUCC(settimeofday(&tv, NULL));
+	TEST_RES(0, is_near(CLOCK_REALTIME,
+			    (long long)tv.tv_sec * NSEC_PER_SEC));
+
+	tv.tv_sec -= DELTA_SECS;
+	TEST_SUCC(settimeofday(&tv, NULL));
+	TEST_RES(0, is_near(CLOCK_REALTIME,
+			    (long long)tv.tv_sec * NSEC_PER_SEC));
+
+	// Setting nothing is allowed.
+	TEST_SUCC(settimeofday(NULL, NULL));
+}
+END_TEST()
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sched.h>
+#include <sys/auxv.h>
+#include <sys/syscall.h>
+#include <time.h>
+#include <unistd.h>
+
+#define NSEC_PER_SEC 1000000000L
+#define NSEC_PER_MSEC 1000000L
+
+// The vDSO reads the clocksource directly (e.g., the TSC or the kvmclock
+// structure), while the system call reads it in the kernel. They must agree,
+// except for the rounding errors in converting the cycles to nanoseconds.
+#define MAX_ROUNDING_ERROR_NS 1000
+
+static long long ts_to_ns(const struct timespec *ts)
+{
+	return (long long)ts->tv_sec * NSEC_PER_SEC + ts->tv_nsec;
+}
+
+static long long read_vdso(clockid_t clock)
+{
+	struct timespec ts;
+
+	if (clock_gettime(clock, &ts) < 0)
+		return -1;
+	return ts_to_ns(&ts);
+}
+
+static long long read_syscall(clockid_t clock)
+{
+	struct timespec ts;
+
+	if (syscall(SYS_clock_gettime, clock, &ts) < 0)
+		return -1;
+	return ts_to_ns(&ts);
+}
+
+// Returns 0 if the time read by the vDSO and by the system call is monotonic
+// (within the rounding errors) when they are read alternately, or -1
+// otherwise.
+static int check_interleaved(clockid_t clock)
+{
+	long long prev = read_vdso(clock), now;
+	int i;
+
+	for (i = 0; i < 1000; i++) {
+		now = (i % 2 == 0) ? read_syscall(clock) : read_vdso(clock);
+		if (now < prev - MAX_ROUNDING_ERROR_NS)
+			return -1;
+		prev = now;
+	}
+
+	return 0;
+}
+
+FN_TEST(vdso)
+{
+	TEST_RES(getauxval(AT_SYSINFO_EHDR), _ret != 0);
+}
+END_TEST()
+
+FN_TEST(vdso_vs_syscall)
+{
+	TEST_SUCC(check_interleaved(CLOCK_MONOTONIC));
+	TEST_SUCC(check_interleaved(CLOCK_MONOTONIC_RAW));
+}
+END_TEST()
+
+// Returns 0 if the monotonic time is monotonic when the thread migrates across
+// all the CPUs, or -1 otherwise.
+static int check_cross_cpu(void)
+{
+	cpu_set_t old_set, set;
+	long long prev, now;
+	int cpu, i, ret = 0;
+
+	if (sched_getaffinity(0, sizeof(old_set), &old_set) < 0)
+		return -1;
+
+	prev = read_vdso(CLOCK_MONOTONIC);
+	for (i = 0; i < 10 && ret == 0; i++) {
+		for (cpu = 0; cpu < CPU_SETSIZE; cpu++) {
+			if (!CPU_ISSET(cpu, &old_set))
+				continue;
+
+			CPU_ZERO(&set);
+			CPU_SET(cpu, &set);
+			if (sched_setaffinity(0, sizeof(set), &set) < 0) {
+				ret = -1;
+				break;
+			}
+
+			now = read_vdso(CLOCK_MONOTONIC);
+			if (now < prev) {
+				ret = -1;
+				break;
+			}
+			prev = now;
+		}
+	}
+
+	if (sched_setaffinity(0, sizeof(old_set), &old_set) < 0)
+		return -1;
+	return ret;
+}
+
+FN_TEST(cross_cpu)
+{
+	TEST_SUCC(check_cross_cpu());
+}
+END_TEST()
+
+FN_TEST(elapsed)
+{
+	struct timespec ts = { .tv_sec = 0, .tv_nsec = 100 * NSEC_PER_MSEC };
+	long long start, elapsed;
+
+	// The clocksource runs at the right rate, which is checked loosely
+	// against the timer interrupts.
+	start = read_vdso(CLOCK_MONOTONIC);
+	TEST_SUCC(nanosleep(&ts, NULL));
+	elapsed = read_vdso(CLOCK_MONOTONIC) - start;
+	TEST_RES(elapsed, _ret >= 100 * NSEC_PER_MSEC && _ret < NSEC_PER_SEC);
+}
+END_TEST()
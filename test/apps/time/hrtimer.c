@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <sched.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/timerfd.h>
+#include <time.h>
+#include <unistd.h>
+
+#define NSEC_PER_SEC 1000000000L
+#define NSEC_PER_MSEC 1000000L
+
+// The tick is 1 ms. The timers below expire more often than the tick.
+#define SLEEP_NS (100 * 1000L)
+#define INTERVAL_NS (500 * 1000L)
+
+// The upper bound of the shortest sleep. A tick-based timer cannot do better
+// than a tick, but the bound is loose to tolerate the vCPU being preempted by
+// the host. The resolution is checked more precisely in the timerfd test.
+#define MAX_MIN_SLEEP_NS (5 * NSEC_PER_MSEC)
+
+#define BUSY_NS (500 * NSEC_PER_MSEC)
+
+static long long read_monotonic(void)
+{
+	struct timespec ts;
+
+	if (clock_gettime(CLOCK_MONOTONIC, &ts) < 0)
+		return -1;
+	return (long long)ts.tv_sec * NSEC_PER_SEC + ts.tv_nsec;
+}
+
+FN_TEST(nanosleep_resolution)
+{
+	struct timespec ts = { .tv_sec = 0, .tv_nsec = SLEEP_NS };
+	long long start, elapsed, min_elapsed = NSEC_PER_SEC;
+	int i;
+
+	for (i = 0; i < 20; i++) {
+		start = read_monotonic();
+		CHECK(nanosleep(&ts, NULL));
+		elapsed = read_monotonic() - start;
+
+		if (elapsed < min_elapsed)
+			min_elapsed = elapsed;
+	}
+
+	// The sleep must never be shorter than requested.
+	TEST_RES(0, min_elapsed >= SLEEP_NS && min_elapsed < MAX_MIN_SLEEP_NS);
+}
+END_TEST()
+
+FN_TEST(timerfd_resolution)
+{
+	struct itimerspec its = {
+		.it_interval = { .tv_sec = 0, .tv_nsec = INTERVAL_NS },
+		.it_value = { .tv_sec = 0, .tv_nsec = INTERVAL_NS },
+	};
+	unsigned long long expirations, total = 0;
+	long long start;
+	int fd;
+
+	fd = TEST_SUCC(timerfd_create(CLOCK_MONOTONIC, 0));
+	TEST_SUCC(timerfd_settime(fd, 0, &its, NULL));
+
+	start = read_monotonic();
+	while (read_monotonic() - start < 100 * NSEC_PER_MSEC) {
+		CHECK(read(fd, &expirations, sizeof(expirations)));
+		total += expirations;
+	}
+
+	// With a timer rounded to the ticks, there can be at most one expiration
+	// per tick, i.e., about 100 expirations.
+	TEST_RES(0, total > 120);
+
+	TEST_SUCC(close(fd));
+}
+END_TEST()
+
+// Returns the number of local timer interrupts that `cpu` has handled, or -1
+// on errors.
+static long long read_timer_interrupts(int cpu)
+{
+	char line[4096], *token;
+	long long count = -1;
+	FILE *file;
+	int i;
+
+	file = fopen("/proc/interrupts", "r");
+	if (file == NULL)
+		return -1;
+
+	while (fgets(line, sizeof(line), file) != NULL) {
+		token = strtok(line, " \n");
+		if (token == NULL || strcmp(token, "LOC:") != 0)
+			continue;
+
+		for (i = 0; i <= cpu; i++)
+			token = strtok(NULL, " \n");
+		if (token != NULL)
+			count = atoll(token);
+		break;
+	}
+
+	fclose(file);
+	return count;
+}
+
+static int pin_to_cpu(int cpu)
+{
+	cpu_set_t set;
+
+	CPU_ZERO(&set);
+	CPU_SET(cpu, &set);
+	return sched_setaffinity(0, sizeof(set), &set);
+}
+
+FN_TEST(idle_tick)
+{
+	long long busy_start, idle_start, busy, idle, start;
+	int nr_cpus, idle_cpu;
+	cpu_set_t old_set;
+
+	nr_cpus = TEST_SUCC(sysconf(_SC_NPROCESSORS_ONLN));
+	idle_cpu = nr_cpus - 1;
+	TEST_SUCC(sched_getaffinity(0, sizeof(old_set), &old_set));
+
+	// Spin on CPU 0 while the last CPU is (mostly) idle.
+	TEST_SUCC(pin_to_cpu(0));
+	busy_start = TEST_SUCC(read_timer_interrupts(0));
+	idle_start = TEST_SUCC(read_timer_interrupts(idle_cpu));
+
+	start = read_monotonic();
+	while (read_monotonic() - start < BUSY_NS)
+		;
+
+	busy = TEST_SUCC(read_timer_interrupts(0)) - busy_start;
+	idle = TEST_SUCC(read_timer_interrupts(idle_cpu)) - idle_start;
+
+	// The busy CPU takes an interrupt in every tick, i.e., about 500
+	// interrupts. The tick of the idle CPU is stopped, so it should take
+	// far fewer interrupts, even if it is woken up by other activities.
+	TEST_RES(0, busy >= 100 && (nr_cpus == 1 || idle < busy / 2));
+
+	TEST_SUCC(sched_setaffinity(0, sizeof(old_set), &old_set));
+}
+END_TEST()
@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+#include <fcntl.h>
+#include <linux/rtc.h>
+#include <sys/ioctl.h>
+#include <unistd.h>
+
+static int rtc_fd;
+
+FN_SETUP(open)
+{
+	rtc_fd = CHECK(open("/dev/rtc0", O_RDONLY));
+}
+END_SETUP()
+
+FN_TEST(exclusive_open)
+{
+	TEST_ERRNO(open("/dev/rtc0", O_RDONLY), EBUSY);
+}
+END_TEST()
+
+FN_TEST(read_and_set_time)
+{
+	struct rtc_time tm;
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_RD_TIME, &tm));
+	TEST_RES(0, tm.tm_year >= 70 && tm.tm_mon >= 0 && tm.tm_mon < 12 &&
+			    tm.tm_mday >= 1 && tm.tm_mday <= 31 &&
+			    tm.tm_hour < 24 && tm.tm_min < 60 &&
+			    tm.tm_sec < 60);
+
+	// Writing back the current time should succeed.
+	TEST_SUCC(ioctl(rtc_fd, RTC_SET_TIME, &tm));
+
+	tm.tm_mon = 12;
+	TEST_ERRNO(ioctl(rtc_fd, RTC_SET_TIME, &tm), EINVAL);
+	tm.tm_mon = 1;
+	tm.tm_mday = 30;
+	TEST_ERRNO(ioctl(rtc_fd, RTC_SET_TIME, &tm), EINVAL);
+	tm.tm_mday = 1;
+	tm.tm_hour = 24;
+	TEST_ERRNO(ioctl(rtc_fd, RTC_SET_TIME, &tm), EINVAL);
+}
+END_TEST()
+
+FN_TEST(invalid_read)
+{
+	char buf[2];
+
+	TEST_ERRNO(read(rtc_fd, buf, sizeof(buf)), EINVAL);
+}
+END_TEST()
+
+FN_TEST(update_irq)
+{
+	unsigned long data;
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_UIE_ON, 0));
+	TEST_RES(read(rtc_fd, &data, sizeof(data)),
+		 _ret == sizeof(data) && (data & 0xff) == (RTC_IRQF | RTC_UF) &&
+			 (data >> 8) >= 1);
+	TEST_SUCC(ioctl(rtc_fd, RTC_UIE_OFF, 0));
+}
+END_TEST()
+
+FN_TEST(alarm_irq)
+{
+	struct rtc_time tm;
+	struct rtc_wkalrm wkalrm;
+	unsigned int data;
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_RD_TIME, &tm));
+
+	tm.tm_sec += 2;
+	if (tm.tm_sec >= 60) {
+		tm.tm_sec -= 60;
+		if (++tm.tm_min == 60) {
+			tm.tm_min = 0;
+			tm.tm_hour = (tm.tm_hour + 1) % 24;
+		}
+	}
+	TEST_SUCC(ioctl(rtc_fd, RTC_ALM_SET, &tm));
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_WKALM_RD, &wkalrm));
+	TEST_RES(0, !wkalrm.enabled && !wkalrm.pending &&
+			    wkalrm.time.tm_sec == tm.tm_sec &&
+			    wkalrm.time.tm_min == tm.tm_min &&
+			    wkalrm.time.tm_hour == tm.tm_hour);
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_AIE_ON, 0));
+	TEST_RES(read(rtc_fd, &data, sizeof(data)),
+		 _ret == sizeof(data) && (data & RTC_AF) && (data >> 8) >= 1);
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_WKALM_RD, &wkalrm));
+	TEST_RES(0, !wkalrm.enabled && wkalrm.pending);
+}
+END_TEST()
+
+FN_TEST(periodic_irq)
+{
+	unsigned long freq;
+	unsigned long data;
+
+	TEST_ERRNO(ioctl(rtc_fd, RTC_IRQP_SET, 0), EINVAL);
+	TEST_ERRNO(ioctl(rtc_fd, RTC_IRQP_SET, 8193), EINVAL);
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_IRQP_SET, 16));
+	TEST_SUCC(ioctl(rtc_fd, RTC_IRQP_READ, &freq));
+	TEST_RES(0, freq == 16);
+
+	TEST_SUCC(ioctl(rtc_fd, RTC_PIE_ON, 0));
+	TEST_RES(read(rtc_fd, &data, sizeof(data)),
+		 _ret == sizeof(data) && (data & RTC_PF) && (data >> 8) >= 1);
+	TEST_SUCC(ioctl(rtc_fd, RTC_PIE_OFF, 0));
+}
+END_TEST()
+
+FN_SETUP(close)
+{
+	CHECK(close(rtc_fd));
+}
+END_SETUP()
@@ -0,0 +1,130 @@
+// SPDX-License-Identifier: MPL-2.0
+
+#define _GNU_SOURCE
+
+#include "../network/test.h"
+
+#include <fcntl.h>
+#include <linux/kd.h>
+#include <linux/vt.h>
+#include <pthread.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <sys/stat.h>
+#include <sys/sysmacros.h>
+#include <unistd.h>
+
+static int tty1, tty2;
+
+FN_SETUP(open)
+{
+	tty1 = CHECK(open("/dev/tty1", O_RDWR | O_NOCTTY));
+	tty2 = CHECK(open("/dev/tty2", O_RDWR | O_NOCTTY));
+
+	CHECK(ioctl(tty1, VT_ACTIVATE, 1));
+}
+END_SETUP()
+
+static int active_vt(void)
+{
+	struct vt_stat stat;
+
+	if (ioctl(tty1, VT_GETSTATE, &stat) < 0)
+		return -1;
+	return stat.v_active;
+}
+
+FN_TEST(device_number)
+{
+	struct stat st;
+
+	// `/dev/ttyN` is the character device (4, N), as in Linux.
+	TEST_RES(fstat(tty1, &st), S_ISCHR(st.st_mode) &&
+				   major(st.st_rdev) == 4 &&
+				   minor(st.st_rdev) == 1);
+	TEST_RES(fstat(tty2, &st), S_ISCHR(st.st_mode) &&
+				   major(st.st_rdev) == 4 &&
+				   minor(st.st_rdev) == 2);
+}
+END_TEST()
+
+FN_TEST(get_state)
+{
+	struct vt_stat stat;
+
+	TEST_RES(ioctl(tty2, VT_GETSTATE, &stat),
+		 stat.v_active == 1 && (stat.v_state & 0x7e) == 0x7e);
+}
+END_TEST()
+
+FN_TEST(activate)
+{
+	TEST_ERRNO(ioctl(tty1, VT_ACTIVATE, 0), ENXIO);
+	TEST_ERRNO(ioctl(tty1, VT_ACTIVATE, 64), ENXIO);
+
+	// Any virtual terminal can be used to switch to another one
+	TEST_SUCC(ioctl(tty1, VT_ACTIVATE, 2));
+	TEST_RES(active_vt(), _ret == 2);
+	TEST_SUCC(ioctl(tty1, VT_WAITACTIVE, 2));
+
+	TEST_SUCC(ioctl(tty2, VT_ACTIVATE, 1));
+	TEST_RES(active_vt(), _ret == 1);
+}
+END_TEST()
+
+static void *activate_later(void *arg)
+{
+	(void)arg;
+
+	usleep(100 * 1000);
+	if (ioctl(tty1, VT_ACTIVATE, 3) < 0)
+		return (void *)1;
+	return NULL;
+}
+
+FN_TEST(wait_active)
+{
+	pthread_t thread;
+	void *res;
+
+	TEST_ERRNO(ioctl(tty1, VT_WAITACTIVE, 0), ENXIO);
+
+	TEST_RES(pthread_create(&thread, NULL, activate_later, NULL),
+		 _ret == 0);
+	TEST_SUCC(ioctl(tty1, VT_WAITACTIVE, 3));
+	TEST_RES(active_vt(), _ret == 3);
+	TEST_RES(pthread_join(thread, &res), _ret == 0 && res == NULL);
+
+	TEST_SUCC(ioctl(tty1, VT_ACTIVATE, 1));
+}
+END_TEST()
+
+FN_TEST(console_mode)
+{
+	int mode;
+
+	TEST_RES(ioctl(tty2, KDGETMODE, &mode), mode == KD_TEXT);
+
+	TEST_SUCC(ioctl(tty2, KDSETMODE, KD_GRAPHICS));
+	TEST_RES(ioctl(tty2, KDGETMODE, &mode), mode == KD_GRAPHICS);
+	// The mode is per virtual terminal
+	TEST_RES(ioctl(tty1, KDGETMODE, &mode), mode == KD_TEXT);
+
+	TEST_ERRNO(ioctl(tty2, KDSETMODE, 100), EINVAL);
+
+	TEST_SUCC(ioctl(tty2, KDSETMODE, KD_TEXT));
+	TEST_RES(ioctl(tty2, KDGETMODE, &mode), mode == KD_TEXT);
+}
+END_TEST()
+
+FN_TEST(escape_sequences)
+{
+	const char *seq = "\033[2J\033[H\033[1;31mred\033[0m\n"
+			  "\033[5;10r\033[10;1H\n\033[r\033(0lqk\033(B\n";
+	struct winsize size;
+
+	TEST_RES(ioctl(tty1, TIOCGWINSZ, &size),
+		 size.ws_row > 0 && size.ws_col > 0);
+	TEST_RES(write(tty1, seq, strlen(seq)), _ret == strlen(seq));
+}
+END_TEST()
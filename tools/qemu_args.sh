@@ -11,6 +11,7 @@
 #  - NETDEV: "user" or "tap";
 #  - VHOST: "off" or "on";
 #  - VSOCK: "off" or "on";
+#  - SECONDARY_NIC: "off" or "on";
 #  - SMP: number of CPUs;
 #  - MEM: amount of memory, e.g. "8G";
 #  - VNC_PORT: VNC port, default is "42".
@@ -18,6 +19,7 @@
 OVMF=${OVMF:-"on"}
 VHOST=${VHOST:-"off"}
 VSOCK=${VSOCK:-"off"}
+SECONDARY_NIC=${SECONDARY_NIC:-"off"}
 NETDEV=${NETDEV:-"user"}
 
 SSH_RAND_PORT=${SSH_PORT:-$(shuf -i 1024-65535 -n 1)}
@@ -69,6 +71,7 @@ if [ "$1" = "tdx" ]; then
         -device isa-debug-exit,iobase=0xf4,iosize=0x04 \
         -monitor chardev:mux \
         -serial chardev:mux \
+        -serial null \
     "
     echo $QEMU_ARGS
     exit 0
@@ -82,6 +85,7 @@ COMMON_QEMU_ARGS="\
     -nographic \
     -display vnc=0.0.0.0:${VNC_PORT:-42} \
     -serial chardev:mux \
+    -serial null \
     -monitor chardev:mux \
     -chardev stdio,id=mux,mux=on,signal=off,logfile=qemu.log \
     $NETDEV_ARGS \
@@ -146,6 +150,24 @@ if [ "$VSOCK" = "on" ]; then
     fi
 fi
 
+if [ "$SECONDARY_NIC" = "on" ]; then
+    # The secondary NIC is isolated from the host. It tests the support for multiple NICs.
+    SECONDARY_NETDEV_ARGS="-netdev user,id=net02,restrict=on"
+    if [ "$1" = "microvm" ]; then
+        MICROVM_QEMU_ARGS="
+            $MICROVM_QEMU_ARGS \
+            $SECONDARY_NETDEV_ARGS \
+            -device virtio-net-device,netdev=net02 \
+        "
+    else
+        QEMU_ARGS="
+            $QEMU_ARGS \
+            $SECONDARY_NETDEV_ARGS \
+            -device virtio-net-pci,netdev=net02,disable-legacy=on,disable-modern=off$VIRTIO_NET_FEATURES$IOMMU_DEV_EXTRA \
+        "
+    fi
+fi
+
 
 if [ "$1" = "microvm" ]; then
     QEMU_ARGS=$MICROVM_QEMU_ARGS